@@ -0,0 +1,66 @@
+/*
+ * endpointo C API
+ *
+ * Generated from src/ffi.rs with cbindgen (see cbindgen.toml); do not edit.
+ * Regenerate with: cbindgen --config cbindgen.toml --output include/endpointo.h
+ */
+
+#ifndef ENDPOINTO_H
+#define ENDPOINTO_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Crawl `url` and return its endpoints as JSON
+ *
+ * Blocks until the scan finishes. `options_json` may be NULL.
+ *
+ * # Safety
+ *
+ * `url` and `options_json` must each be NULL or point to a NUL-terminated
+ * string. The result must be freed with [`endpointo_free_string`].
+ */
+char *endpointo_scan_url_json(const char *url, const char *options_json);
+
+/*
+ * Parse `len` bytes of in-memory content and return its endpoints as JSON
+ *
+ * `name` stands in for a file name: it is the endpoints' source and its
+ * extension, if any, picks the parser. It and `options_json` may be NULL.
+ *
+ * # Safety
+ *
+ * `data` must point to `len` readable bytes (or be NULL when `len` is 0),
+ * and `name` and `options_json` must each be NULL or point to a
+ * NUL-terminated string. The result must be freed with
+ * [`endpointo_free_string`].
+ */
+char *endpointo_parse_buffer_json(const char *name,
+                                  const uint8_t *data,
+                                  size_t len,
+                                  const char *options_json);
+
+/*
+ * Free a string returned by endpointo; NULL is ignored
+ *
+ * # Safety
+ *
+ * `s` must be NULL or a string returned by this library, not yet freed.
+ */
+void endpointo_free_string(char *s);
+
+/*
+ * The library version, as a static string that must not be freed
+ */
+const char *endpointo_version(void);
+
+#ifdef __cplusplus
+}  /* extern "C" */
+#endif
+
+#endif  /* ENDPOINTO_H */
@@ -0,0 +1,266 @@
+//! The `endpointo` Python module
+//!
+//! Built with maturin (see `pyproject.toml`), this lets Python code run the
+//! scanner in-process:
+//!
+//! ```python
+//! import asyncio, endpointo
+//!
+//! config = endpointo.ScanConfig("https://example.com", profile="aggressive", rate_limit=5)
+//! scanner = endpointo.Scanner(config)
+//! endpoints = asyncio.run(scanner.scan_url("https://example.com"))
+//! ```
+//!
+//! Scans run on a Tokio runtime owned by the module, so the coroutines can be
+//! awaited from any event loop and don't hold the GIL while they run.
+//! Endpoints and results come back as the dicts their JSON output decodes to.
+
+// pyo3 0.22's `#[pymethods]` wrappers convert each `PyErr` into itself
+#![allow(clippy::useless_conversion)]
+
+use crate::config::{PluginSpec, ScanConfig, ScanProfile};
+use crate::error::Error;
+use crate::scanner::Scanner;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use serde::Serialize;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+create_exception!(endpointo, EndpointoError, PyException);
+
+impl From<Error> for PyErr {
+    fn from(e: Error) -> Self {
+        EndpointoError::new_err(e.to_string())
+    }
+}
+
+/// The runtime every scan runs on
+fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("failed to start the Tokio runtime")
+    })
+}
+
+/// Run a future on the module's runtime and wait for it without blocking
+/// the caller's event loop
+async fn spawn<F, T>(future: F) -> PyResult<T>
+where
+    F: Future<Output = crate::error::Result<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    match runtime().spawn(future).await {
+        Ok(result) => result.map_err(PyErr::from),
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(e) => Err(EndpointoError::new_err(e.to_string())),
+    }
+}
+
+/// Convert to the Python value the JSON form decodes to
+fn to_py(py: Python<'_>, value: &impl Serialize) -> PyResult<PyObject> {
+    let json = serde_json::to_string(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let loads = py.import_bound("json")?.getattr("loads")?;
+    Ok(loads.call1((json,))?.unbind())
+}
+
+/// Scan settings; options not given keep the CLI's defaults
+#[pyclass(name = "ScanConfig", module = "endpointo")]
+#[derive(Clone)]
+pub struct PyScanConfig {
+    inner: ScanConfig,
+}
+
+#[pymethods]
+impl PyScanConfig {
+    #[new]
+    #[pyo3(signature = (
+        target_url,
+        *,
+        profile = None,
+        rate_limit = None,
+        timeout = None,
+        max_concurrent = None,
+        user_agent = None,
+        headers = Vec::new(),
+        cookies = None,
+        proxy = None,
+        respect_robots = None,
+        follow_redirects = None,
+        filter = None,
+        plugins = Vec::new(),
+        plugin_timeout = None,
+        max_depth = None,
+        max_pages = None,
+        max_endpoints = None,
+        include_hosts = Vec::new(),
+        exclude_hosts = Vec::new(),
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        target_url: String,
+        profile: Option<&str>,
+        rate_limit: Option<u32>,
+        timeout: Option<u64>,
+        max_concurrent: Option<usize>,
+        user_agent: Option<String>,
+        headers: Vec<String>,
+        cookies: Option<String>,
+        proxy: Option<String>,
+        respect_robots: Option<bool>,
+        follow_redirects: Option<bool>,
+        filter: Option<String>,
+        plugins: Vec<PathBuf>,
+        plugin_timeout: Option<f64>,
+        max_depth: Option<usize>,
+        max_pages: Option<usize>,
+        max_endpoints: Option<usize>,
+        include_hosts: Vec<String>,
+        exclude_hosts: Vec<String>,
+    ) -> PyResult<Self> {
+        let mut config = ScanConfig::new(target_url);
+        if let Some(profile) = profile {
+            let profile: ScanProfile = profile.parse().map_err(PyValueError::new_err)?;
+            config = config.with_profile(profile);
+        }
+        config = config
+            .with_rate_limit(rate_limit)
+            .with_timeout(timeout)
+            .with_max_concurrent(max_concurrent)
+            .with_headers(headers)
+            .with_cookies(cookies)
+            .with_proxy(proxy)
+            .with_plugin_specs(plugins.into_iter().map(PluginSpec::new).collect())
+            .with_plugin_timeout(plugin_timeout.map(Duration::from_secs_f64))
+            .with_max_depth(max_depth)
+            .with_max_pages(max_pages)
+            .with_max_endpoints(max_endpoints)
+            .with_include_hosts(include_hosts)
+            .with_exclude_hosts(exclude_hosts);
+        if let Some(ua) = user_agent {
+            config = config.with_user_agent(ua);
+        }
+        if let Some(respect) = respect_robots {
+            config = config.with_robots(respect);
+        }
+        if let Some(follow) = follow_redirects {
+            config = config.with_redirects(follow);
+        }
+        if let Some(pattern) = filter {
+            config = config.with_filter(pattern);
+        }
+        Ok(Self { inner: config })
+    }
+
+    #[getter]
+    fn target_url(&self) -> &str {
+        &self.inner.target_url
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ScanConfig({:?})", self.inner.target_url)
+    }
+}
+
+/// A scanner built from a `ScanConfig`, with its plugins loaded
+#[pyclass(name = "Scanner", module = "endpointo", frozen)]
+pub struct PyScanner {
+    inner: Arc<Scanner>,
+}
+
+#[pymethods]
+impl PyScanner {
+    #[new]
+    fn new(config: &PyScanConfig) -> PyResult<Self> {
+        let scanner = Scanner::new(config.inner.clone())?;
+        Ok(Self {
+            inner: Arc::new(scanner),
+        })
+    }
+
+    /// Scan the configured target; returns the full result as a dict
+    async fn scan(&self) -> PyResult<PyObject> {
+        let scanner = self.inner.clone();
+        let result = spawn(async move { scanner.scan().await }).await?;
+        Python::with_gil(|py| to_py(py, &result))
+    }
+
+    /// Crawl one URL; returns its endpoints as a list of dicts
+    async fn scan_url(&self, url: String) -> PyResult<PyObject> {
+        let scanner = self.inner.clone();
+        let endpoints = spawn(async move { scanner.scan_url(&url).await }).await?;
+        Python::with_gil(|py| to_py(py, &endpoints))
+    }
+
+    /// Parse one local file; returns its endpoints as a list of dicts
+    async fn parse_file(&self, path: PathBuf) -> PyResult<PyObject> {
+        let scanner = self.inner.clone();
+        let endpoints = spawn(async move { scanner.parse_file(&path).await }).await?;
+        Python::with_gil(|py| to_py(py, &endpoints))
+    }
+
+    /// Parse content that isn't on disk; `name` picks the parser as a file
+    /// name would
+    fn parse_bytes(&self, py: Python<'_>, name: &str, content: &[u8]) -> PyResult<PyObject> {
+        let endpoints = py.allow_threads(|| self.inner.parse_bytes(name, content))?;
+        to_py(py, &endpoints)
+    }
+}
+
+/// The `endpointo` module
+#[pymodule]
+fn endpointo(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add("EndpointoError", m.py().get_type_bound::<EndpointoError>())?;
+    m.add_class::<PyScanConfig>()?;
+    m.add_class::<PyScanner>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyDict;
+
+    #[test]
+    fn test_module_docs_config() {
+        Python::with_gil(|py| {
+            let locals = PyDict::new_bound(py);
+            locals
+                .set_item("endpointo", pyo3::wrap_pymodule!(endpointo)(py))
+                .unwrap();
+
+            // The config from the module docs
+            py.run_bound(
+                r#"config = endpointo.ScanConfig("https://example.com", profile="aggressive", rate_limit=5)"#,
+                None,
+                Some(&locals),
+            )
+            .unwrap();
+            let config: PyScanConfig = locals
+                .get_item("config")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(config.inner.probe);
+            assert!(!config.inner.respect_robots_txt);
+            assert_eq!(config.inner.rate_limit, 5);
+
+            let err = py
+                .run_bound(
+                    r#"endpointo.ScanConfig("https://example.com", profile="deep")"#,
+                    None,
+                    Some(&locals),
+                )
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+        });
+    }
+}
@@ -0,0 +1,277 @@
+//! Tagging and severity classification of endpoints
+//!
+//! Rules match an endpoint's URL by keyword or regex and add a tag with a
+//! severity. Built-in rules cover admin, auth, debug, upload, payment, and
+//! internal endpoints; more can be loaded from a YAML or TOML rules file.
+
+use crate::error::{Error, Result};
+use crate::types::{Endpoint, Severity};
+use regex::Regex;
+use serde::Deserialize;
+use std::path::Path;
+
+/// Tags an endpoint whose URL matches a keyword or a regex
+#[derive(Debug, Clone)]
+pub struct TagRule {
+    /// Tag added to matching endpoints
+    pub tag: String,
+
+    /// Severity of a match
+    pub severity: Severity,
+
+    /// Lowercase words matched against the start of URL words, so `upload`
+    /// matches `/api/fileUploads` but not `/reupload`
+    pub keywords: Vec<String>,
+
+    /// Pattern matched against the whole URL
+    pub regex: Option<Regex>,
+}
+
+impl TagRule {
+    /// Create a rule that matches nothing until given keywords or a regex
+    pub fn new(tag: impl Into<String>, severity: Severity) -> Self {
+        Self {
+            tag: tag.into(),
+            severity,
+            keywords: Vec::new(),
+            regex: None,
+        }
+    }
+
+    /// Set the keywords
+    pub fn with_keywords<S: AsRef<str>>(mut self, keywords: &[S]) -> Self {
+        self.keywords = keywords.iter().map(|k| k.as_ref().to_lowercase()).collect();
+        self
+    }
+
+    /// Set the regex
+    pub fn with_regex(mut self, regex: Regex) -> Self {
+        self.regex = Some(regex);
+        self
+    }
+
+    /// Check whether the rule matches a URL, given the URL's words
+    fn matches(&self, url: &str, words: &[String]) -> bool {
+        self.keywords
+            .iter()
+            .any(|keyword| words.iter().any(|word| word.starts_with(keyword.as_str())))
+            || self.regex.as_ref().is_some_and(|regex| regex.is_match(url))
+    }
+}
+
+/// On-disk rules file layout
+#[derive(Debug, Deserialize)]
+struct RuleFile {
+    rules: Vec<RuleDefinition>,
+}
+
+/// A single rule entry in a rules file
+#[derive(Debug, Deserialize)]
+struct RuleDefinition {
+    tag: String,
+    #[serde(default = "default_rule_severity")]
+    severity: Severity,
+    #[serde(default)]
+    keywords: Vec<String>,
+    #[serde(default)]
+    regex: Option<String>,
+}
+
+fn default_rule_severity() -> Severity {
+    Severity::Medium
+}
+
+/// Load tag rules from a YAML or TOML file
+///
+/// The format is chosen by extension (`.toml` for TOML, anything else is
+/// read as YAML). Each entry needs a `tag` and at least one of `keywords`
+/// and `regex`; `severity` defaults to `medium`.
+pub fn load_rules(path: &Path) -> Result<Vec<TagRule>> {
+    let content = std::fs::read_to_string(path)?;
+
+    let file: RuleFile = match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => toml::from_str(&content)?,
+        _ => serde_yaml::from_str(&content)?,
+    };
+
+    file.rules
+        .into_iter()
+        .map(|def| {
+            if def.keywords.is_empty() && def.regex.is_none() {
+                return Err(Error::ValidationError(format!(
+                    "Rule '{}' needs keywords or a regex",
+                    def.tag
+                )));
+            }
+            let mut rule = TagRule::new(def.tag, def.severity).with_keywords(&def.keywords);
+            if let Some(regex) = def.regex {
+                let regex = Regex::new(&regex).map_err(|e| {
+                    Error::ValidationError(format!("Invalid regex for rule '{}': {}", rule.tag, e))
+                })?;
+                rule = rule.with_regex(regex);
+            }
+            Ok(rule)
+        })
+        .collect()
+}
+
+/// Applies tag rules to endpoints
+pub struct Classifier {
+    rules: Vec<TagRule>,
+}
+
+impl Classifier {
+    /// Create a classifier with the built-in rules
+    pub fn new() -> Self {
+        Self {
+            rules: builtin_rules(),
+        }
+    }
+
+    /// Add rules, run after the existing ones
+    pub fn with_rules(mut self, rules: Vec<TagRule>) -> Self {
+        self.rules.extend(rules);
+        self
+    }
+
+    /// The rules in run order
+    pub fn rules(&self) -> &[TagRule] {
+        &self.rules
+    }
+
+    /// Tag an endpoint with every rule it matches
+    ///
+    /// Its severity becomes the highest of the matching rules' and any
+    /// severity it already had.
+    pub fn classify(&self, endpoint: &mut Endpoint) {
+        let words = url_words(&endpoint.url);
+        for rule in &self.rules {
+            if !rule.matches(&endpoint.url, &words) {
+                continue;
+            }
+            let tags = endpoint.tags.get_or_insert_with(Vec::new);
+            if !tags.contains(&rule.tag) {
+                tags.push(rule.tag.clone());
+            }
+            endpoint.severity = endpoint.severity.max(Some(rule.severity));
+        }
+    }
+
+    /// Tag every endpoint
+    pub fn classify_all(&self, endpoints: &mut [Endpoint]) {
+        for endpoint in endpoints {
+            self.classify(endpoint);
+        }
+    }
+}
+
+impl Default for Classifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The built-in rules
+fn builtin_rules() -> Vec<TagRule> {
+    vec![
+        TagRule::new("admin", Severity::High).with_keywords(&[
+            "admin",
+            "superuser",
+            "backoffice",
+            "manage",
+            "staff",
+            "sudo",
+        ]),
+        TagRule::new("auth", Severity::Medium).with_keywords(&[
+            "auth", "oauth", "login", "logout", "signin", "signup", "register", "password",
+            "session", "token", "sso", "saml", "jwt", "mfa",
+        ]),
+        TagRule::new("debug", Severity::High)
+            .with_keywords(&[
+                "debug", "actuator", "phpinfo", "heapdump", "trace", "console", "profiler",
+            ])
+            .with_regex(Regex::new(r"/\.(?:env|git)(?:/|$)").unwrap()),
+        TagRule::new("upload", Severity::Medium).with_keywords(&[
+            "upload",
+            "attachment",
+            "multipart",
+        ]),
+        TagRule::new("payment", Severity::High).with_keywords(&[
+            "payment",
+            "checkout",
+            "billing",
+            "invoice",
+            "stripe",
+            "refund",
+            "subscription",
+            "wallet",
+        ]),
+        TagRule::new("internal", Severity::Medium)
+            .with_keywords(&["internal", "private", "intranet"]),
+    ]
+}
+
+/// Lowercase words of a URL, split on punctuation and camelCase humps
+fn url_words(url: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut prev_lower = false;
+    for c in url.chars() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower {
+            words.push(std::mem::take(&mut word));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        word.extend(c.to_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Sort endpoints by severity, highest first, keeping the order within each
+/// severity; endpoints no rule matched go last
+pub fn sort_by_severity(endpoints: &mut [Endpoint]) {
+    endpoints.sort_by_key(|e| std::cmp::Reverse(e.severity));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EndpointType;
+
+    #[test]
+    fn test_classify() {
+        let classifier = Classifier::new();
+        let classified = |url: &str| {
+            let mut endpoint = Endpoint::new(url.to_string(), EndpointType::Rest);
+            classifier.classify(&mut endpoint);
+            endpoint
+        };
+
+        let endpoint = classified("/api/admin/users/resetPassword");
+        assert_eq!(endpoint.tags.unwrap(), vec!["admin", "auth"]);
+        assert_eq!(endpoint.severity, Some(Severity::High));
+
+        assert!(classified("/api/fileUploads").has_tag("upload"));
+        assert!(classified("https://example.com/.env").has_tag("debug"));
+        assert!(!classified("/api/reupload").has_tag("upload"));
+        assert_eq!(classified("/api/products").severity, None);
+
+        let mut endpoints = vec![
+            classified("/api/products"),
+            classified("/api/login"),
+            classified("/api/checkout"),
+        ];
+        sort_by_severity(&mut endpoints);
+        let urls: Vec<_> = endpoints.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(urls, vec!["/api/checkout", "/api/login", "/api/products"]);
+    }
+}
@@ -1,5 +1,15 @@
 pub mod cli_mod;
+pub mod completions;
+pub mod dashboard;
+pub mod explorer;
 pub mod interactive;
+pub mod targets;
 
-pub use self::cli_mod::{Cli, Commands, OutputFormat};
+pub use self::cli_mod::{
+    Cli, Commands, ConfigCommand, HttpArgs, LogFormat, OutputFormat, PluginArgs, PluginsCommand,
+};
+pub use self::completions::Shell;
+pub use self::dashboard::Dashboard;
+pub use self::explorer::Explorer;
 pub use self::interactive::InteractiveUi;
+pub use self::targets::load_targets;
@@ -1,4 +1,13 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use crate::cli::completions::Shell;
+use crate::config::ScanProfile;
+use crate::output::diff::DiffFormat;
+use crate::output::fields::Field;
+use crate::output::report::ReportFormat;
+use crate::parser::params::WordKind;
+use crate::passive::PassiveSource;
+use crate::plugins::{Builtin, PluginErrorPolicy, TimeoutPolicy};
+use crate::types::{EndpointType, Severity};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -11,53 +20,338 @@ use std::path::PathBuf;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Configuration file [default: ./endpointo.toml, then ~/.config/endpointo/config.toml]
+    #[arg(long, global = true, value_name = "FILE", env = "ENDPOINTO_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// Don't read a configuration file
+    #[arg(long, global = true)]
+    pub no_config: bool,
+
+    /// Print only results: no banner, progress or status messages
+    #[arg(long, global = true)]
+    pub silent: bool,
+
+    /// No banner, progress display or info logs; summaries and errors still print
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Disable colored output (also disabled by a non-empty NO_COLOR)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// More logs: -v for endpointo's debug logs, -vv for its trace logs and its libraries' debug logs
+    #[arg(
+        short,
+        long,
+        global = true,
+        action = clap::ArgAction::Count,
+        conflicts_with_all = ["quiet", "silent"]
+    )]
+    pub verbose: u8,
+
+    /// Log format on stderr
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "FORMAT",
+        default_value = "text",
+        env = "ENDPOINTO_LOG_FORMAT"
+    )]
+    pub log_format: LogFormat,
+
+    /// Per-module log levels, e.g. endpointo::crawler=trace,reqwest=debug (repeatable); overrides RUST_LOG and -v
+    #[arg(long, global = true, value_name = "DIRECTIVES", env = "ENDPOINTO_LOG")]
+    pub log_filter: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable lines
+    Text,
+    /// One JSON object per event, for log collectors
+    Json,
 }
 
+// Parsed once per run, so the size of the scan variant doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 pub enum Commands {
-    /// Scan a URL and extract endpoints
+    /// Scan one or more URLs and extract endpoints
     #[command(name = "scan")]
     Scan {
-        /// Target URL to scan
-        #[arg(short, long, value_name = "URL")]
-        url: String,
+        /// Target URL to scan (repeatable)
+        #[arg(short, long, value_name = "URL", required_unless_present = "list")]
+        url: Vec<String>,
+
+        /// File with one target per line, or - for stdin
+        #[arg(short, long, value_name = "FILE")]
+        list: Option<PathBuf>,
 
         /// Output file path
         #[arg(short, long, value_name = "FILE")]
         output: Option<PathBuf>,
 
+        /// Write each target's results to its own file in this directory, named after the target
+        #[arg(
+            long,
+            value_name = "DIR",
+            conflicts_with_all = ["output", "shard_size", "watch"]
+        )]
+        output_dir: Option<PathBuf>,
+
         /// Output format
         #[arg(short, long, value_enum, default_value = "json")]
         format: Option<OutputFormat>,
 
-        /// Rate limit (requests per second)
-        #[arg(short, long, value_name = "NUM")]
-        rate_limit: Option<u32>,
+        /// Tera template rendered with the scan result, instead of --format
+        #[arg(long, value_name = "FILE")]
+        template: Option<PathBuf>,
 
-        /// Request timeout in seconds
-        #[arg(short, long, value_name = "SECS", default_value = "30")]
-        timeout: Option<u64>,
+        /// Only output these endpoint fields (url, method, type, source, line, params, tags, severity, confidence, status)
+        #[arg(long, value_name = "FIELDS", value_delimiter = ',')]
+        fields: Vec<Field>,
 
-        /// Number of concurrent threads
-        #[arg(short = 'j', long, value_name = "NUM", default_value = "10")]
-        threads: Option<usize>,
+        /// Write JSON, YAML and XML files as a bare endpoint list, without target, stats and versions
+        #[arg(long)]
+        bare: bool,
+
+        /// Split endpoints into numbered JSON Lines files of this many each, with a manifest
+        #[arg(
+            long,
+            value_name = "NUM",
+            requires = "output",
+            conflicts_with = "template"
+        )]
+        shard_size: Option<usize>,
+
+        /// Preset of scan settings (passive, standard, aggressive); explicit flags override it
+        #[arg(long, value_name = "PROFILE")]
+        profile: Option<ScanProfile>,
+
+        #[command(flatten)]
+        http: HttpArgs,
+
+        /// Crawl even where robots.txt disallows it
+        #[arg(long)]
+        ignore_robots: bool,
 
         /// Filter pattern for endpoints
         #[arg(long, value_name = "PATTERN")]
         filter: Option<String>,
 
-        /// Python plugin to load
-        #[arg(short, long, value_name = "PATH")]
-        plugin: Option<PathBuf>,
+        /// Only crawl and report this host and its subdomains; *.host for subdomains only (repeatable)
+        #[arg(long, value_name = "HOST", value_delimiter = ',')]
+        include_host: Vec<String>,
+
+        /// Never crawl or report this host or its subdomains (repeatable)
+        #[arg(long, value_name = "HOST", value_delimiter = ',')]
+        exclude_host: Vec<String>,
+
+        /// Only crawl and report URLs matching this regex (repeatable)
+        #[arg(long, value_name = "REGEX")]
+        include_regex: Vec<String>,
+
+        /// Never crawl or report URLs matching this regex (repeatable)
+        #[arg(long, value_name = "REGEX")]
+        exclude_regex: Vec<String>,
+
+        #[command(flatten)]
+        plugins: PluginArgs,
+
+        /// Filter or transform endpoints with a Rhai script, e.g. 'endpoint.url.contains("/admin")'
+        #[arg(long, value_name = "SCRIPT")]
+        script: Option<String>,
+
+        /// YAML/TOML file with additional extraction patterns
+        #[arg(long, value_name = "FILE")]
+        patterns: Option<PathBuf>,
+
+        /// Drop low-signal matches (locale codes, npm package paths, ...)
+        #[arg(long)]
+        aggressive_filter: bool,
+
+        /// YAML/TOML file with additional tag rules
+        #[arg(long, value_name = "FILE")]
+        rules: Option<PathBuf>,
+
+        /// Keep only endpoints at or above this severity (info, low, medium, high, critical)
+        #[arg(long, value_name = "LEVEL")]
+        min_severity: Option<Severity>,
+
+        /// Keep only endpoints with one of these tags (comma-separated or repeated)
+        #[arg(long, value_name = "TAG", value_delimiter = ',')]
+        tag: Vec<String>,
+
+        /// Sort endpoints by severity, highest first
+        #[arg(long)]
+        sort_by_severity: bool,
+
+        /// Parse inputs larger than this many bytes in windows
+        #[arg(long, value_name = "BYTES")]
+        max_parse_bytes: Option<usize>,
+
+        /// Window size in bytes for inputs above --max-parse-bytes
+        #[arg(long, value_name = "BYTES")]
+        parse_window: Option<usize>,
+
+        /// Write findings (env vars, internal hosts, private IPs, secrets) to a JSON file
+        #[arg(long, value_name = "FILE")]
+        findings: Option<PathBuf>,
+
+        /// Exit with status 3 when endpoints appear that this earlier results file doesn't have
+        #[arg(long, value_name = "BASELINE")]
+        fail_on_new: Option<PathBuf>,
+
+        /// Exit with status 4 when an endpoint URL matches this regex (repeatable)
+        #[arg(long, value_name = "REGEX")]
+        fail_on_match: Vec<String>,
+
+        /// Exit with status 5 when secrets are found
+        #[arg(long)]
+        fail_on_secrets: bool,
+
+        /// Probe discovered endpoints on the target's host (HEAD, GET if refused)
+        #[arg(long)]
+        probe: bool,
+
+        /// Send OPTIONS to probed endpoints and record their allowed methods
+        #[arg(long, requires = "probe")]
+        probe_options: bool,
+
+        /// Stop fetching and probing after this many seconds per target
+        #[arg(long, value_name = "SECS")]
+        max_duration: Option<u64>,
+
+        /// Keep at most this many endpoints per target
+        #[arg(long, value_name = "NUM")]
+        max_endpoints: Option<usize>,
+
+        /// Follow same-origin links this many hops from each target [default: 0]
+        #[arg(long, value_name = "NUM")]
+        depth: Option<usize>,
+
+        /// Fetch at most this many pages per target when following links [default: 100]
+        #[arg(long, value_name = "NUM")]
+        max_pages: Option<usize>,
+
+        /// Fetch at most this many JavaScript assets in total
+        #[arg(long, value_name = "NUM")]
+        max_assets: Option<usize>,
+
+        /// Remember at most this many visited URLs exactly; older ones are forgotten
+        #[arg(long, value_name = "NUM")]
+        max_visited: Option<usize>,
+
+        /// Keep URLs past --max-visited in a fixed-size bloom filter instead
+        #[arg(long, requires = "max_visited")]
+        visited_bloom: bool,
+
+        /// Re-scan on this interval (e.g. 30m, 6h) and report only new endpoints
+        #[arg(long, value_name = "INTERVAL")]
+        watch: Option<String>,
+
+        /// Save progress to this state file as each target finishes
+        #[arg(long, value_name = "FILE", conflicts_with = "watch")]
+        state: Option<PathBuf>,
+
+        /// Continue the scan saved in this state file, skipping finished targets
+        #[arg(long, value_name = "FILE", conflicts_with = "watch")]
+        resume: Option<PathBuf>,
+
+        /// Full-screen dashboard with queue depth, per-host request rates, a live endpoint feed and errors
+        #[arg(long, conflicts_with = "watch")]
+        tui: bool,
+
+        /// Only show these endpoint types in the dashboard's feed (rest, graphql, websocket, sse, unknown)
+        #[arg(long, value_name = "TYPE", value_delimiter = ',', requires = "tui")]
+        tui_type: Vec<EndpointType>,
+
+        /// After the scan, triage results in an interactive prompt (fuzzy search, filters, export) instead of printing them
+        #[arg(long, conflicts_with = "watch")]
+        explore: bool,
+
+        /// Burp REST API to send discovered URLs to as a scan (e.g. http://127.0.0.1:1337/<key>)
+        #[arg(long, value_name = "URL")]
+        burp_api: Option<String>,
+
+        /// Webhook URL to POST results to
+        #[arg(long, value_name = "URL")]
+        webhook: Option<String>,
+
+        /// Secret to sign webhook requests with (HMAC-SHA256)
+        #[arg(
+            long,
+            value_name = "SECRET",
+            env = "ENDPOINTO_WEBHOOK_SECRET",
+            hide_env_values = true,
+            requires = "webhook"
+        )]
+        webhook_secret: Option<String>,
+
+        /// Send endpoints to the webhook as NDJSON, this many per request
+        #[arg(long, value_name = "NUM", requires = "webhook")]
+        webhook_batch: Option<usize>,
+
+        /// Elasticsearch/OpenSearch URL to bulk-index endpoints into
+        #[arg(long, value_name = "URL")]
+        elasticsearch: Option<String>,
+
+        /// Index to write endpoints to [default: endpointo]
+        #[arg(long, value_name = "NAME", requires = "elasticsearch")]
+        es_index: Option<String>,
+
+        /// Username for Elasticsearch basic auth
+        #[arg(long, value_name = "USER", requires = "elasticsearch")]
+        es_user: Option<String>,
+
+        /// Password for Elasticsearch basic auth
+        #[arg(
+            long,
+            value_name = "PASSWORD",
+            env = "ENDPOINTO_ES_PASSWORD",
+            hide_env_values = true,
+            requires = "es_user"
+        )]
+        es_password: Option<String>,
+
+        /// Elasticsearch API key, encoded or as id:key
+        #[arg(
+            long,
+            value_name = "KEY",
+            env = "ENDPOINTO_ES_API_KEY",
+            hide_env_values = true,
+            requires = "elasticsearch",
+            conflicts_with = "es_user"
+        )]
+        es_api_key: Option<String>,
+
+        /// SQLite project database to record the scan in
+        #[arg(long, value_name = "DB")]
+        project: Option<PathBuf>,
     },
 
-    /// Parse local JavaScript files
+    /// Parse local web assets (JS, TS, HTML, CSS, JSON, sourcemaps, WASM)
     #[command(name = "parse")]
     Parse {
-        /// Input files (glob patterns supported)
-        #[arg(short, long, value_name = "FILES", required = true)]
+        /// Input files, directories (searched recursively), or glob patterns; - reads stdin
+        #[arg(value_name = "INPUT", required_unless_present = "files")]
+        inputs: Vec<PathBuf>,
+
+        /// Input files, directories (searched recursively), or glob patterns
+        #[arg(short, long, value_name = "FILES")]
         files: Vec<PathBuf>,
 
+        /// Extensions to parse when searching directories [default: all parseable]
+        #[arg(long, value_name = "EXT", value_delimiter = ',')]
+        ext: Vec<String>,
+
+        /// Skip files larger than this many bytes
+        #[arg(long, value_name = "BYTES")]
+        max_file_size: Option<u64>,
+
         /// Output file path
         #[arg(short, long, value_name = "FILE")]
         output: Option<PathBuf>,
@@ -66,13 +360,735 @@ pub enum Commands {
         #[arg(short = 'F', long, value_enum, default_value = "json")]
         format: Option<OutputFormat>,
 
+        /// Tera template rendered with the scan result, instead of --format
+        #[arg(long, value_name = "FILE")]
+        template: Option<PathBuf>,
+
+        /// Only output these endpoint fields (url, method, type, source, line, params, tags, severity, confidence, status)
+        #[arg(long, value_name = "FIELDS", value_delimiter = ',')]
+        fields: Vec<Field>,
+
+        /// Write JSON, YAML and XML files as a bare endpoint list, without target, stats and versions
+        #[arg(long)]
+        bare: bool,
+
         /// Filter pattern for endpoints
         #[arg(long, value_name = "PATTERN")]
         filter: Option<String>,
 
-        /// Python plugin to load
-        #[arg(short, long, value_name = "PATH")]
-        plugin: Option<PathBuf>,
+        /// Only report endpoints on this host and its subdomains; *.host for subdomains only (repeatable)
+        #[arg(long, value_name = "HOST", value_delimiter = ',')]
+        include_host: Vec<String>,
+
+        /// Never report endpoints on this host or its subdomains (repeatable)
+        #[arg(long, value_name = "HOST", value_delimiter = ',')]
+        exclude_host: Vec<String>,
+
+        /// Only report endpoints matching this regex (repeatable)
+        #[arg(long, value_name = "REGEX")]
+        include_regex: Vec<String>,
+
+        /// Never report endpoints matching this regex (repeatable)
+        #[arg(long, value_name = "REGEX")]
+        exclude_regex: Vec<String>,
+
+        #[command(flatten)]
+        plugins: PluginArgs,
+
+        /// Filter or transform endpoints with a Rhai script, e.g. 'endpoint.url.contains("/admin")'
+        #[arg(long, value_name = "SCRIPT")]
+        script: Option<String>,
+
+        /// YAML/TOML file with additional extraction patterns
+        #[arg(long, value_name = "FILE")]
+        patterns: Option<PathBuf>,
+
+        /// Drop low-signal matches (locale codes, npm package paths, ...)
+        #[arg(long)]
+        aggressive_filter: bool,
+
+        /// YAML/TOML file with additional tag rules
+        #[arg(long, value_name = "FILE")]
+        rules: Option<PathBuf>,
+
+        /// Keep only endpoints at or above this severity (info, low, medium, high, critical)
+        #[arg(long, value_name = "LEVEL")]
+        min_severity: Option<Severity>,
+
+        /// Keep only endpoints with one of these tags (comma-separated or repeated)
+        #[arg(long, value_name = "TAG", value_delimiter = ',')]
+        tag: Vec<String>,
+
+        /// Sort endpoints by severity, highest first
+        #[arg(long)]
+        sort_by_severity: bool,
+
+        /// Parse inputs larger than this many bytes in windows
+        #[arg(long, value_name = "BYTES")]
+        max_parse_bytes: Option<usize>,
+
+        /// Window size in bytes for inputs above --max-parse-bytes
+        #[arg(long, value_name = "BYTES")]
+        parse_window: Option<usize>,
+
+        /// Write findings (env vars, internal hosts, private IPs, secrets) to a JSON file
+        #[arg(long, value_name = "FILE")]
+        findings: Option<PathBuf>,
+
+        /// Exit with status 3 when endpoints appear that this earlier results file doesn't have
+        #[arg(long, value_name = "BASELINE")]
+        fail_on_new: Option<PathBuf>,
+
+        /// Exit with status 4 when an endpoint URL matches this regex (repeatable)
+        #[arg(long, value_name = "REGEX")]
+        fail_on_match: Vec<String>,
+
+        /// Exit with status 5 when secrets are found
+        #[arg(long)]
+        fail_on_secrets: bool,
+    },
+
+    /// Compare two result files and report new, removed, and changed endpoints
+    #[command(name = "diff")]
+    Diff {
+        /// Results from the earlier scan (JSON, JSON Lines, or YAML)
+        #[arg(value_name = "OLD", required_unless_present = "project")]
+        old: Option<PathBuf>,
+
+        /// Results from the later scan
+        #[arg(value_name = "NEW", required_unless_present = "project")]
+        new: Option<PathBuf>,
+
+        /// Compare the two latest scans recorded in a project database instead
+        #[arg(long, value_name = "DB", conflicts_with_all = ["old", "new"])]
+        project: Option<PathBuf>,
+
+        /// Target whose scans to compare, when the project has several
+        #[arg(long, value_name = "URL", requires = "project")]
+        target: Option<String>,
+
+        /// Write the diff to a file
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Diff format (text, json, markdown) [default: json for files, text otherwise]
+        #[arg(short, long, value_name = "FORMAT")]
+        format: Option<DiffFormat>,
+
+        /// Exit with status 3 when the newer scan has endpoints the older one didn't
+        #[arg(long)]
+        fail_on_new: bool,
+    },
+
+    /// Probe endpoints from earlier results or a URL list and record how they respond
+    #[command(name = "probe")]
+    Probe {
+        /// Results file (JSON, JSON Lines, YAML, or a shard manifest) or a list of URLs, one per line
+        #[arg(value_name = "INPUT")]
+        input: PathBuf,
+
+        /// Base URL for relative endpoints [default: the results' target]
+        #[arg(long, value_name = "URL")]
+        base: Option<String>,
+
+        /// Output file path for the probed results
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "json")]
+        format: Option<OutputFormat>,
+
+        #[command(flatten)]
+        http: HttpArgs,
+
+        /// Also send OPTIONS and record allowed methods
+        #[arg(long)]
+        options: bool,
+
+        /// Follow at most this many redirects per endpoint [default: 5]
+        #[arg(long, value_name = "NUM")]
+        max_redirects: Option<usize>,
+
+        /// Don't check hosts for soft 404s (catch-all responses)
+        #[arg(long)]
+        no_soft_404: bool,
+
+        /// Keep only endpoints that responded, other than with 404, 410 or a soft 404
+        #[arg(long)]
+        live: bool,
+    },
+
+    /// Brute-force paths with wordlists and words mined from earlier results, keeping the ones that exist
+    #[command(name = "fuzz")]
+    Fuzz {
+        /// Target URL; words are tried under its directory
+        #[arg(value_name = "URL")]
+        url: String,
+
+        /// Wordlist file, one word per line (repeatable)
+        #[arg(short, long, value_name = "FILE")]
+        wordlist: Vec<PathBuf>,
+
+        /// Results of an earlier scan: its words are tried under its endpoints' directories, and hits are merged into it
+        #[arg(long, value_name = "RESULTS")]
+        from: Option<PathBuf>,
+
+        /// Don't use the built-in list of common API and admin paths
+        #[arg(long)]
+        no_builtin: bool,
+
+        /// Probe at most this many candidate paths [default: 5000]
+        #[arg(long, value_name = "NUM")]
+        max_requests: Option<usize>,
+
+        /// Output file path
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "json")]
+        format: Option<OutputFormat>,
+
+        #[command(flatten)]
+        http: HttpArgs,
+
+        /// Try paths even where robots.txt disallows them
+        #[arg(long)]
+        ignore_robots: bool,
+
+        /// Only try URLs matching this regex (repeatable)
+        #[arg(long, value_name = "REGEX")]
+        include_regex: Vec<String>,
+
+        /// Never try URLs matching this regex (repeatable)
+        #[arg(long, value_name = "REGEX")]
+        exclude_regex: Vec<String>,
+
+        /// Don't check the target for soft 404s (catch-all responses)
+        #[arg(long)]
+        no_soft_404: bool,
+    },
+
+    /// Check a results file for malformed entries and, optionally, dead URLs
+    #[command(name = "validate")]
+    Validate {
+        /// Results file (JSON, JSON Lines, YAML, or a shard manifest)
+        #[arg(value_name = "INPUT")]
+        input: PathBuf,
+
+        /// Probe up to this many endpoints, spread through the file, and report dead ones
+        #[arg(long, value_name = "NUM")]
+        live: Option<usize>,
+
+        /// Base URL for relative endpoints [default: the results' target]
+        #[arg(long, value_name = "URL", requires = "live")]
+        base: Option<String>,
+
+        #[command(flatten)]
+        http: HttpArgs,
+    },
+
+    /// Convert a results file to another output format without scanning again
+    #[command(name = "export")]
+    Export {
+        /// Results file (JSON, JSON Lines, YAML, or a shard manifest)
+        #[arg(value_name = "INPUT")]
+        input: PathBuf,
+
+        /// Output file path
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "json")]
+        format: Option<OutputFormat>,
+
+        /// Tera template rendered with the result, instead of --format
+        #[arg(long, value_name = "FILE")]
+        template: Option<PathBuf>,
+
+        /// Only output these endpoint fields (url, method, type, source, line, params, tags, severity, confidence, status)
+        #[arg(long, value_name = "FIELDS", value_delimiter = ',')]
+        fields: Vec<Field>,
+
+        /// Write JSON, YAML and XML files as a bare endpoint list, without target, stats and versions
+        #[arg(long)]
+        bare: bool,
+
+        /// Split endpoints into numbered JSON Lines files of this many each, with a manifest
+        #[arg(
+            long,
+            value_name = "NUM",
+            requires = "output",
+            conflicts_with = "template"
+        )]
+        shard_size: Option<usize>,
+
+        /// Base URL for relative endpoints, replacing the results' target
+        #[arg(long, value_name = "URL")]
+        base: Option<String>,
+    },
+
+    /// Merge results files into an OpenAPI 3 document of their REST endpoints
+    #[command(name = "spec")]
+    Spec {
+        /// Results files (JSON, JSON Lines, YAML, or shard manifests)
+        #[arg(value_name = "INPUT", required = true)]
+        inputs: Vec<PathBuf>,
+
+        /// Output file path (JSON for .json, YAML otherwise)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Document title [default: the results' targets]
+        #[arg(long, value_name = "TITLE")]
+        title: Option<String>,
+
+        /// List only this server, leaving out endpoints on other hosts (repeatable)
+        #[arg(long = "server", value_name = "URL")]
+        servers: Vec<String>,
+
+        /// Include only endpoints a probe found live (not 404, 410 or a soft 404)
+        #[arg(long)]
+        verified: bool,
+    },
+
+    /// Build path, parameter and filename wordlists from results files for fuzzers
+    #[command(name = "wordlist")]
+    Wordlist {
+        /// Results files (JSON, JSON Lines, YAML, or shard manifests)
+        #[arg(value_name = "INPUT", required = true)]
+        inputs: Vec<PathBuf>,
+
+        /// Kinds of words to include (paths, params, files) [default: all]
+        #[arg(short, long, value_name = "KINDS", value_delimiter = ',')]
+        kind: Vec<WordKind>,
+
+        /// Write the merged wordlist to this file
+        #[arg(short, long, value_name = "FILE", conflicts_with = "dir")]
+        output: Option<PathBuf>,
+
+        /// Write each kind to its own file in this directory (paths.txt, params.txt, files.txt)
+        #[arg(short, long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+    },
+
+    /// Introspect a GraphQL endpoint, print its schema and list its operations as endpoints
+    #[command(name = "graphql")]
+    Graphql {
+        /// GraphQL endpoint URL
+        #[arg(value_name = "URL")]
+        url: String,
+
+        /// Save the schema as SDL to this file instead of printing it
+        #[arg(long, value_name = "FILE")]
+        schema: Option<PathBuf>,
+
+        /// Output file path for the operations
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "json")]
+        format: Option<OutputFormat>,
+
+        #[command(flatten)]
+        http: HttpArgs,
+    },
+
+    /// Collect a domain's URLs from web archives without touching the target
+    #[command(name = "passive")]
+    Passive {
+        /// Domain to look up, e.g. example.com
+        #[arg(value_name = "DOMAIN")]
+        domain: String,
+
+        /// Sources to query (wayback, commoncrawl, urlscan) [default: all]
+        #[arg(short, long = "source", value_name = "SOURCES", value_delimiter = ',')]
+        sources: Vec<PassiveSource>,
+
+        /// Leave out URLs on subdomains
+        #[arg(long)]
+        no_subdomains: bool,
+
+        /// Maximum URLs to request from each source [default: 10000]
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+
+        /// urlscan.io API key
+        #[arg(
+            long,
+            value_name = "KEY",
+            env = "ENDPOINTO_URLSCAN_KEY",
+            hide_env_values = true
+        )]
+        urlscan_key: Option<String>,
+
+        /// Keep images, fonts, stylesheets and media
+        #[arg(long)]
+        include_static: bool,
+
+        /// Output file path
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "json")]
+        format: Option<OutputFormat>,
+
+        /// Per-source timeout in seconds [default: 60]
+        #[arg(short, long, value_name = "SECS")]
+        timeout: Option<u64>,
+    },
+
+    /// Hand targets out to workers over TCP and merge their results
+    #[command(name = "coordinate")]
+    Coordinate {
+        /// Target URL to scan (repeatable)
+        #[arg(short, long, value_name = "URL", required_unless_present = "list")]
+        url: Vec<String>,
+
+        /// File with one target per line, or - for stdin
+        #[arg(short, long, value_name = "FILE")]
+        list: Option<PathBuf>,
+
+        /// Address to accept workers on
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:7878")]
+        listen: String,
+
+        /// Only hand targets to workers that send this token
+        #[arg(
+            long,
+            value_name = "TOKEN",
+            env = "ENDPOINTO_COORDINATOR_TOKEN",
+            hide_env_values = true
+        )]
+        token: Option<String>,
+
+        /// Output file path
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "json")]
+        format: Option<OutputFormat>,
+
+        /// Write JSON, YAML and XML files as a bare endpoint list, without target, stats and versions
+        #[arg(long)]
+        bare: bool,
+    },
+
+    /// Scan targets handed out by a coordinator until it has no more
+    #[command(name = "worker")]
+    Worker {
+        /// Coordinator address
+        #[arg(long, value_name = "ADDR")]
+        connect: String,
+
+        /// Token the coordinator requires
+        #[arg(
+            long,
+            value_name = "TOKEN",
+            env = "ENDPOINTO_COORDINATOR_TOKEN",
+            hide_env_values = true
+        )]
+        token: Option<String>,
+
+        /// Preset of scan settings (passive, standard, aggressive); explicit flags override it
+        #[arg(long, value_name = "PROFILE")]
+        profile: Option<ScanProfile>,
+
+        #[command(flatten)]
+        http: HttpArgs,
+
+        /// Probe discovered endpoints on the target's host (HEAD, GET if refused)
+        #[arg(long)]
+        probe: bool,
+    },
+
+    /// Run scans requested over an HTTP API
+    #[command(name = "serve")]
+    Serve {
+        /// Address to serve the API on
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+        listen: String,
+
+        /// Require `Authorization: Bearer <TOKEN>` on every request
+        #[arg(
+            long,
+            value_name = "TOKEN",
+            env = "ENDPOINTO_API_TOKEN",
+            hide_env_values = true
+        )]
+        token: Option<String>,
+
+        /// Scans run at once; later ones wait [default: 4]
+        #[arg(long, value_name = "NUM")]
+        max_scans: Option<usize>,
+
+        /// Default preset of scan settings (passive, standard, aggressive); requests may pick another
+        #[arg(long, value_name = "PROFILE")]
+        profile: Option<ScanProfile>,
+
+        #[command(flatten)]
+        http: HttpArgs,
+    },
+
+    /// Show the scans and endpoints recorded in a project database
+    #[command(name = "history")]
+    History {
+        /// Project database written by `scan --project`
+        #[arg(value_name = "DB")]
+        project: PathBuf,
+
+        /// Only show this target
+        #[arg(long, value_name = "URL")]
+        target: Option<String>,
+
+        /// Write the history to a JSON file
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Summarize many scans: trends, new and vanished endpoints, and per-host counts
+    #[command(name = "report")]
+    Report {
+        /// Results files of successive scans (JSON, JSON Lines, YAML, or shard manifests)
+        #[arg(value_name = "INPUT", required_unless_present = "project")]
+        inputs: Vec<PathBuf>,
+
+        /// Report on the scans recorded in a project database instead
+        #[arg(long, value_name = "DB", conflicts_with = "inputs")]
+        project: Option<PathBuf>,
+
+        /// Only report on this target
+        #[arg(long, value_name = "URL", requires = "project")]
+        target: Option<String>,
+
+        /// Count endpoints first seen at or after this date or RFC 3339 time as new [default: those new in the latest scan]
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+
+        /// Write the report to a file
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Report format (html, markdown, json) [default: from the output file's extension, else markdown]
+        #[arg(short, long, value_name = "FORMAT")]
+        format: Option<ReportFormat>,
+    },
+
+    /// Create, show or check configuration files
+    #[command(name = "config")]
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+
+    /// Check connectivity, TLS trust, the proxy, robots.txt, the rate limit and plugins before a long scan
+    #[command(name = "doctor")]
+    Doctor {
+        /// Target URL to check, as it would be scanned
+        #[arg(value_name = "URL")]
+        url: String,
+
+        #[command(flatten)]
+        http: HttpArgs,
+
+        /// Don't check robots.txt, as a scan with --ignore-robots wouldn't
+        #[arg(long)]
+        ignore_robots: bool,
+
+        #[command(flatten)]
+        plugins: PluginArgs,
+
+        /// Requests to send when checking the rate limit; 0 skips the check [default: 10]
+        #[arg(long, value_name = "NUM")]
+        burst: Option<usize>,
+    },
+
+    /// List, test and scaffold plugins
+    #[command(name = "plugins")]
+    Plugins {
+        #[command(subcommand)]
+        action: PluginsCommand,
+    },
+
+    /// Print a shell completion script
+    #[command(name = "completions")]
+    Completions {
+        /// Shell to complete for
+        #[arg(value_enum, value_name = "SHELL")]
+        shell: Shell,
+    },
+
+    /// Print the man page, or write one per command
+    #[command(name = "man")]
+    Man {
+        /// Write endpointo.1 and a page per subcommand, such as
+        /// endpointo-scan.1, into this directory
+        #[arg(short, long = "output-dir", value_name = "DIR")]
+        output_dir: Option<PathBuf>,
+    },
+}
+
+/// HTTP settings, shared by the commands that send requests
+#[derive(Args)]
+pub struct HttpArgs {
+    /// Rate limit (requests per second) [default: 10]
+    #[arg(short, long, value_name = "NUM")]
+    pub rate_limit: Option<u32>,
+
+    /// Request timeout in seconds [default: 30]
+    #[arg(short, long, value_name = "SECS")]
+    pub timeout: Option<u64>,
+
+    /// Number of concurrent requests [default: 10]
+    #[arg(short = 'j', long, value_name = "NUM")]
+    pub threads: Option<usize>,
+
+    /// Extra request header, e.g. "Authorization: Bearer <token>" (repeatable)
+    #[arg(short = 'H', long = "header", value_name = "HEADER")]
+    pub headers: Vec<String>,
+
+    /// Cookies to send, e.g. "session=abc; theme=dark" (repeatable)
+    #[arg(long = "cookie", value_name = "COOKIES")]
+    pub cookies: Vec<String>,
+
+    /// Proxy for every request, e.g. http://127.0.0.1:8080 or socks5://127.0.0.1:1080
+    #[arg(long, value_name = "URL")]
+    pub proxy: Option<String>,
+
+    /// Proxy credentials as user:password, used only when a proxy is set
+    #[arg(
+        long,
+        value_name = "USER:PASS",
+        env = "ENDPOINTO_PROXY_AUTH",
+        hide_env_values = true
+    )]
+    pub proxy_auth: Option<String>,
+}
+
+/// Plugins to load, shared by the commands that run them
+#[derive(Args)]
+pub struct PluginArgs {
+    /// Python plugin, native plugin library or WASM module to load (repeatable; they run in order)
+    #[arg(short, long, value_name = "PATH")]
+    pub plugin: Vec<PathBuf>,
+
+    /// Load every plugin in this directory after the --plugin ones
+    #[arg(long, value_name = "DIR")]
+    pub plugin_dir: Option<PathBuf>,
+
+    /// Skip a plugin by file name, with or without the extension (repeatable)
+    #[arg(long, value_name = "NAME", value_delimiter = ',')]
+    pub disable_plugin: Vec<String>,
+
+    /// Run a built-in plugin before the loaded ones (scope-filter, secret-redactor, static-asset-dropper, tagger; repeatable)
+    #[arg(long, value_name = "NAME", value_delimiter = ',')]
+    pub enable_builtin: Vec<Builtin>,
+
+    /// Give up on a plugin hook call after this many seconds
+    #[arg(long, value_name = "SECS")]
+    pub plugin_timeout: Option<u64>,
+
+    /// What to do when a plugin hook times out (skip, drop, abort) [default: skip]
+    #[arg(long, value_name = "POLICY")]
+    pub on_plugin_timeout: Option<TimeoutPolicy>,
+
+    /// Keep at most this many endpoints a plugin generates from one asset
+    #[arg(long, value_name = "NUM")]
+    pub plugin_max_generated: Option<usize>,
+
+    /// What to do when a plugin hook fails (warn, skip-endpoint, fail) [default: warn]
+    #[arg(long, value_name = "POLICY")]
+    pub plugin_errors: Option<PluginErrorPolicy>,
+
+    /// With --format plugin, hand results only to this plugin
+    #[arg(long, value_name = "NAME")]
+    pub output_plugin: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Write a commented configuration file with every setting at its default
+    #[command(name = "init")]
+    Init {
+        /// File to write
+        #[arg(value_name = "FILE", default_value = "endpointo.toml")]
+        path: PathBuf,
+
+        /// Overwrite the file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Print the effective scan configuration: defaults, then the file, environment and flags
+    #[command(name = "show")]
+    Show {
+        /// Preset of scan settings (passive, standard, aggressive)
+        #[arg(long, value_name = "PROFILE")]
+        profile: Option<ScanProfile>,
+
+        /// Rate limit (requests per second)
+        #[arg(short, long, value_name = "NUM")]
+        rate_limit: Option<u32>,
+
+        /// Request timeout in seconds
+        #[arg(short, long, value_name = "SECS")]
+        timeout: Option<u64>,
+
+        /// Number of concurrent threads
+        #[arg(short = 'j', long, value_name = "NUM")]
+        threads: Option<usize>,
+    },
+
+    /// Check a configuration file for syntax errors, unknown keys and bad values
+    #[command(name = "validate")]
+    Validate {
+        /// File to check [default: the configuration file in use]
+        #[arg(value_name = "FILE")]
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PluginsCommand {
+    /// List the plugins in a directory and the hooks each defines, and the built-in plugins
+    #[command(name = "list")]
+    List {
+        /// Directory to look in
+        #[arg(value_name = "DIR", default_value = "plugins")]
+        dir: PathBuf,
+    },
+
+    /// Run a plugin against sample endpoints and report hook coverage and errors
+    #[command(name = "test")]
+    Test {
+        /// Plugin file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Test against the endpoints in this results file instead of built-in samples
+        #[arg(short, long, value_name = "FILE")]
+        input: Option<PathBuf>,
+    },
+
+    /// Write a working plugin skeleton to start from
+    #[command(name = "new")]
+    New {
+        /// Plugin name; the file is <NAME>.py
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Directory to write the plugin to
+        #[arg(short, long, value_name = "DIR", default_value = "plugins")]
+        dir: PathBuf,
+
+        /// Overwrite the plugin if it exists
+        #[arg(long)]
+        force: bool,
     },
 }
 
@@ -80,10 +1096,53 @@ pub enum Commands {
 pub enum OutputFormat {
     /// JSON format
     Json,
+    /// JSON Lines, one endpoint per line; scans stream results as found
+    Jsonl,
     /// YAML format
     Yaml,
+    /// CSV with a header row (url, method, type, source, line unless --fields is given)
+    Csv,
     /// XML format
     Xml,
     /// HTML report
     Html,
+    /// Parameter and path wordlist, one word per line
+    Wordlist,
+    /// One endpoint URL per line, without colors or headings
+    Plain,
+    /// SARIF 2.1.0 log for code scanning upload
+    Sarif,
+    /// OpenAPI 3 document of the REST endpoints (JSON for .json files, else YAML)
+    Openapi,
+    /// Burp Suite site map XML, for Burp's Import
+    Burp,
+    /// HAR log with an entry per endpoint and probe responses
+    Har,
+    /// A curl command per endpoint
+    Curl,
+    /// An HTTPie command per endpoint
+    Httpie,
+    /// Nuclei target lists per type and tag, plus templates for probed endpoints (-o is a directory)
+    Nuclei,
+    /// Handed to the write_output hook of every loaded plugin that has one (--output-plugin picks one)
+    Plugin,
+}
+
+impl OutputFormat {
+    /// File extension for files in this format (none for nuclei's directories
+    /// or output plugins write)
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Json | OutputFormat::Har => "json",
+            OutputFormat::Jsonl => "jsonl",
+            OutputFormat::Yaml | OutputFormat::Openapi => "yaml",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Xml | OutputFormat::Burp => "xml",
+            OutputFormat::Html => "html",
+            OutputFormat::Wordlist | OutputFormat::Plain => "txt",
+            OutputFormat::Sarif => "sarif",
+            OutputFormat::Curl | OutputFormat::Httpie => "sh",
+            OutputFormat::Nuclei | OutputFormat::Plugin => "",
+        }
+    }
 }
@@ -0,0 +1,112 @@
+//! Shell completion scripts and man pages generated from the CLI definition
+//!
+//! Everything is derived from the clap `Command` by `clap_complete` and
+//! `clap_mangen`, so new subcommands and flags show up in
+//! `endpointo completions` and `endpointo man` without touching this module.
+
+use clap::Command;
+use std::path::Path;
+
+pub use clap_complete::Shell;
+
+/// Completion script for `shell`
+pub fn generate(shell: Shell, mut cmd: Command) -> String {
+    let bin = cmd.get_name().to_string();
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut cmd, bin, &mut script);
+    String::from_utf8_lossy(&script).into_owned()
+}
+
+/// The roff man page of the top-level command, listing the subcommands
+pub fn render_man(cmd: Command) -> String {
+    let mut page = Vec::new();
+    clap_mangen::Man::new(cmd)
+        .render(&mut page)
+        .expect("writing to a Vec can't fail");
+    String::from_utf8_lossy(&page).into_owned()
+}
+
+/// Write a man page per command into `dir`: `endpointo.1`, then
+/// `endpointo-scan.1` and so on for every subcommand
+pub fn write_man_pages(cmd: Command, dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    clap_mangen::generate_to(cmd, dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Cli;
+    use clap::CommandFactory;
+
+    /// Every visible subcommand path with its visible long options
+    fn clap_options() -> Vec<(Vec<String>, Vec<String>)> {
+        fn visit(cmd: &Command, path: Vec<String>, out: &mut Vec<(Vec<String>, Vec<String>)>) {
+            let longs = cmd
+                .get_arguments()
+                .filter(|a| !a.is_hide_set())
+                .filter_map(|a| a.get_long().map(str::to_string))
+                .collect();
+            out.push((path.clone(), longs));
+            for sub in cmd.get_subcommands() {
+                if sub.is_hide_set() || sub.get_name() == "help" {
+                    continue;
+                }
+                let mut sub_path = path.clone();
+                sub_path.push(sub.get_name().to_string());
+                visit(sub, sub_path, out);
+            }
+        }
+        let mut cmd = Cli::command();
+        cmd.build();
+        let mut out = Vec::new();
+        visit(&cmd, Vec::new(), &mut out);
+        out
+    }
+
+    #[test]
+    fn test_scripts_cover_every_flag() {
+        let options = clap_options();
+        assert!(options.len() > 10);
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+            let script = generate(shell, Cli::command());
+            for (path, longs) in &options {
+                for name in path {
+                    assert!(script.contains(name.as_str()), "{:?} lacks {}", shell, name);
+                }
+                for long in longs {
+                    // Fish names long options without their dashes
+                    let flag = match shell {
+                        Shell::Fish => format!("-l {}", long),
+                        _ => format!("--{}", long),
+                    };
+                    assert!(script.contains(&flag), "{:?} lacks {}", shell, flag);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_man_pages_cover_every_flag() {
+        let page = render_man(Cli::command());
+        assert!(page.starts_with(".ie"), "{}", &page[..40]);
+        assert!(page.contains(".TH endpointo 1"));
+        assert!(page.contains("endpointo\\-scan(1)"));
+
+        let dir = tempfile::tempdir().unwrap();
+        write_man_pages(Cli::command(), dir.path()).unwrap();
+        for (path, longs) in clap_options() {
+            let name = ["endpointo".to_string()]
+                .into_iter()
+                .chain(path.iter().cloned())
+                .collect::<Vec<_>>()
+                .join("-");
+            let page = std::fs::read_to_string(dir.path().join(format!("{}.1", name)))
+                .unwrap_or_else(|_| panic!("no man page for {:?}", path));
+            for long in longs {
+                let flag = format!("\\-\\-{}", long.replace('-', "\\-"));
+                assert!(page.contains(&flag), "{}.1 lacks --{}", name, long);
+            }
+        }
+    }
+}
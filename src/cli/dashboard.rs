@@ -0,0 +1,474 @@
+use crate::error::Error;
+use crate::observer::ScanObserver;
+use crate::types::{Endpoint, EndpointType};
+use crate::util::lock;
+use console::{truncate_str, Term};
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::{cursor, execute, terminal};
+use ratatui::style::Stylize;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use ratatui::{Frame, Terminal};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// How often the screen is redrawn
+const FRAME: Duration = Duration::from_millis(150);
+
+/// Requests within this window count toward a host's rate
+const RATE_WINDOW: Duration = Duration::from_secs(5);
+
+/// Endpoints kept for the live feed
+const FEED_LEN: usize = 500;
+
+/// Error messages kept for the error panel
+const ERRORS_SHOWN: usize = 3;
+
+/// Hosts listed in the host panel, busiest first
+const HOSTS_SHOWN: usize = 6;
+
+/// Endpoint types in the order the dashboard counts them
+const TYPES: [EndpointType; 5] = [
+    EndpointType::Rest,
+    EndpointType::GraphQL,
+    EndpointType::WebSocket,
+    EndpointType::Sse,
+    EndpointType::Unknown,
+];
+
+/// Full-screen scan dashboard, an alternative to [`InteractiveUi`]'s bars
+///
+/// Shows the crawl queue, per-host request rates, a live feed of endpoints
+/// (optionally limited to some types) and errors. Register it as an
+/// observer, then call [`Dashboard::show`] to take over the terminal until
+/// the returned [`Screen`] is dropped.
+///
+/// [`InteractiveUi`]: crate::cli::InteractiveUi
+#[derive(Clone)]
+pub struct Dashboard {
+    state: Arc<Mutex<State>>,
+}
+
+/// Counters and recent events behind the dashboard
+struct State {
+    title: String,
+    started: Instant,
+    types: Vec<EndpointType>,
+    /// Assets discovered but not yet fetched or failed
+    pending: HashSet<String>,
+    fetched: usize,
+    bytes: usize,
+    hosts: HashMap<String, HostStats>,
+    by_type: HashMap<EndpointType, usize>,
+    feed: VecDeque<Endpoint>,
+    errors: usize,
+    recent_errors: VecDeque<String>,
+    completed: usize,
+}
+
+#[derive(Default)]
+struct HostStats {
+    requests: usize,
+    errors: usize,
+    recent: VecDeque<Instant>,
+}
+
+impl HostStats {
+    /// Record a request at `now`, forgetting ones outside [`RATE_WINDOW`]
+    fn record(&mut self, now: Instant) {
+        while self
+            .recent
+            .front()
+            .is_some_and(|at| now.duration_since(*at) > RATE_WINDOW)
+        {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(now);
+    }
+
+    /// Requests per second over the [`RATE_WINDOW`] before `now`
+    fn rate(&self, now: Instant) -> f64 {
+        let recent = self
+            .recent
+            .iter()
+            .filter(|at| now.duration_since(**at) <= RATE_WINDOW)
+            .count();
+        recent as f64 / RATE_WINDOW.as_secs_f64()
+    }
+}
+
+impl Dashboard {
+    /// Create a dashboard titled after the scan; an empty `types` shows
+    /// every endpoint type in the feed
+    pub fn new(title: impl Into<String>, types: Vec<EndpointType>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                title: title.into(),
+                started: Instant::now(),
+                types,
+                pending: HashSet::new(),
+                fetched: 0,
+                bytes: 0,
+                hosts: HashMap::new(),
+                by_type: HashMap::new(),
+                feed: VecDeque::new(),
+                errors: 0,
+                recent_errors: VecDeque::new(),
+                completed: 0,
+            })),
+        }
+    }
+
+    /// Whether stderr, where the dashboard is drawn, is a terminal
+    pub fn available() -> bool {
+        Term::stderr().is_term()
+    }
+
+    /// Switch stderr to the alternate screen and redraw until the
+    /// returned guard is dropped
+    pub fn show(&self) -> Screen {
+        let mut screen = Screen::enter();
+        let state = self.state.clone();
+        let stop = screen.stop.clone();
+        // `--no-color` and `NO_COLOR` turn the styles off here too
+        let color = colored::control::SHOULD_COLORIZE.should_colorize();
+        screen.thread = Some(std::thread::spawn(move || {
+            let Ok(mut terminal) = Terminal::new(CrosstermBackend::new(std::io::stderr())) else {
+                return;
+            };
+            while !stop.load(Ordering::Relaxed) {
+                let _ = terminal.draw(|frame| {
+                    let rows = frame.area().height as usize;
+                    let mut lines = lock(&state).lines(Instant::now(), rows);
+                    if !color {
+                        lines = lines.iter().map(|l| Line::from(l.to_string())).collect();
+                    }
+                    draw(frame, lines);
+                });
+                std::thread::sleep(FRAME);
+            }
+        }));
+        screen
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        lock(&self.state)
+    }
+}
+
+/// The dashboard's hold on the terminal; dropping it, on an early return or
+/// a panic too, stops the redraws and restores the screen and cursor
+pub struct Screen {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Screen {
+    /// Switch stderr to the alternate screen with the cursor hidden
+    fn enter() -> Self {
+        let _ = execute!(
+            std::io::stderr(),
+            terminal::EnterAlternateScreen,
+            cursor::Hide
+        );
+        Self {
+            stop: Arc::new(AtomicBool::new(false)),
+            thread: None,
+        }
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        let _ = execute!(
+            std::io::stderr(),
+            cursor::Show,
+            terminal::LeaveAlternateScreen
+        );
+    }
+}
+
+/// Draw `lines` over the whole frame, each cut to its width
+fn draw(frame: &mut Frame, lines: Vec<Line<'static>>) {
+    frame.render_widget(Paragraph::new(lines), frame.area());
+}
+
+/// Host of an asset URL, or the URL itself when it doesn't parse
+fn host_of(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string())
+}
+
+impl State {
+    /// Record a request to `url`'s host, failed or not
+    fn request(&mut self, url: &str, failed: bool) {
+        let host = self.hosts.entry(host_of(url)).or_default();
+        host.requests += 1;
+        host.record(Instant::now());
+        if failed {
+            host.errors += 1;
+        }
+    }
+
+    fn shown(&self, endpoint: &Endpoint) -> bool {
+        self.types.is_empty() || self.types.contains(&endpoint.endpoint_type)
+    }
+
+    /// The screen's lines as of `now`, with as much of the feed as fits in
+    /// `rows`
+    fn lines(&self, now: Instant, rows: usize) -> Vec<Line<'static>> {
+        let elapsed = now.duration_since(self.started).as_secs();
+        let total: usize = self.by_type.values().sum();
+        let mut lines = Vec::new();
+
+        lines.push(Line::from(vec![
+            " endpointo ".bold().black().on_cyan(),
+            " ".into(),
+            self.title.clone().bold(),
+            " ".into(),
+            format!(
+                "[{:02}:{:02}:{:02}]",
+                elapsed / 3600,
+                elapsed / 60 % 60,
+                elapsed % 60
+            )
+            .dim(),
+        ]));
+        lines.push(Line::from(vec![
+            " Queue ".into(),
+            self.pending.len().to_string().bold().light_yellow(),
+            "   Fetched ".into(),
+            self.fetched.to_string().bold(),
+            format!(" ({} KB)   Endpoints ", self.bytes / 1024).into(),
+            total.to_string().bold().light_green(),
+            "   Errors ".into(),
+            match self.errors {
+                0 => "0".into(),
+                n => n.to_string().bold().red(),
+            },
+            format!("   Targets done {}", self.completed).into(),
+        ]));
+        lines.push(Line::default());
+
+        // Hosts, busiest first
+        lines.push(
+            format!(
+                " {:<40} {:>8} {:>9} {:>7}",
+                "HOST", "req/s", "requests", "errors"
+            )
+            .bold()
+            .into(),
+        );
+        let mut hosts: Vec<(&String, &HostStats)> = self.hosts.iter().collect();
+        hosts.sort_by(|a, b| b.1.requests.cmp(&a.1.requests).then(a.0.cmp(b.0)));
+        let listed = hosts.len().min(HOSTS_SHOWN);
+        for (host, stats) in hosts.into_iter().take(HOSTS_SHOWN) {
+            lines.push(Line::from(format!(
+                " {:<40} {:>8.1} {:>9} {:>7}",
+                truncate_str(host, 40, "…"),
+                stats.rate(now),
+                stats.requests,
+                stats.errors
+            )));
+        }
+        if listed == 0 {
+            lines.push(Line::from(vec![
+                " ".into(),
+                "waiting for the first request…".dim(),
+            ]));
+        }
+        lines.push(Line::default());
+
+        // Counts by type, then the feed in the space that's left
+        let mut counts = vec![" ".into(), "ENDPOINTS".bold(), "  ".into()];
+        for (i, kind) in TYPES.iter().enumerate() {
+            if i > 0 {
+                counts.push(" · ".into());
+            }
+            let count = self.by_type.get(kind).copied().unwrap_or(0);
+            let label = format!("{} {}", kind.as_str(), count);
+            counts.push(if self.types.is_empty() || self.types.contains(kind) {
+                label.bold()
+            } else {
+                label.dim()
+            });
+        }
+        lines.push(Line::from(counts));
+
+        let mut footer = Vec::new();
+        if !self.recent_errors.is_empty() {
+            footer.push(Line::default());
+            footer.push(Line::from(vec![" ".into(), "ERRORS".bold().red()]));
+            for error in &self.recent_errors {
+                footer.push(Line::from(vec![
+                    " ".into(),
+                    "✗".red(),
+                    format!(" {}", error).into(),
+                ]));
+            }
+        }
+
+        let room = rows.saturating_sub(lines.len() + footer.len());
+        let feed: Vec<&Endpoint> = self.feed.iter().filter(|e| self.shown(e)).collect();
+        for endpoint in &feed[feed.len().saturating_sub(room)..] {
+            let method = endpoint.method.as_deref().unwrap_or("GET").to_uppercase();
+            let mut line: Vec<Span<'static>> = vec![
+                " ".into(),
+                format!("{:<7}", method).light_blue(),
+                " ".into(),
+                format!("{:<9}", endpoint.endpoint_type.as_str()).dim(),
+                format!(" {} ", endpoint.url).into(),
+            ];
+            if let Some(source) = &endpoint.source {
+                line.push(format!("({})", source).dim());
+            }
+            lines.push(Line::from(line));
+        }
+        lines.extend(footer);
+        lines
+    }
+}
+
+/// Keeps the dashboard's counters and feed up to date
+impl ScanObserver for Dashboard {
+    fn on_asset_discovered(&self, url: &str) {
+        self.state().pending.insert(url.to_string());
+    }
+
+    fn on_asset_fetched(&self, url: &str, bytes: usize) {
+        let mut state = self.state();
+        state.pending.remove(url);
+        state.fetched += 1;
+        state.bytes += bytes;
+        state.request(url, false);
+    }
+
+    fn on_endpoint_found(&self, endpoint: &Endpoint) {
+        let mut state = self.state();
+        *state
+            .by_type
+            .entry(endpoint.endpoint_type.clone())
+            .or_default() += 1;
+        if state.feed.len() == FEED_LEN {
+            state.feed.pop_front();
+        }
+        state.feed.push_back(endpoint.clone());
+    }
+
+    fn on_error(&self, url: &str, error: &Error) {
+        let mut state = self.state();
+        state.errors += 1;
+        // Failed fetches leave the queue; parse errors come after a fetch
+        if state.pending.remove(url) {
+            state.request(url, true);
+        }
+        if state.recent_errors.len() == ERRORS_SHOWN {
+            state.recent_errors.pop_front();
+        }
+        state.recent_errors.push_back(format!("{}: {}", url, error));
+    }
+
+    fn on_complete(&self, _target: &str, _total_endpoints: usize) {
+        let mut state = self.state();
+        // Assets skipped as already fetched never report back
+        state.pending.clear();
+        state.completed += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::buffer::Buffer;
+
+    /// The text of each line, without its styles
+    fn plain(lines: Vec<Line>) -> Vec<String> {
+        lines.iter().map(Line::to_string).collect()
+    }
+
+    #[test]
+    fn test_dashboard_counts_and_filters() {
+        let dashboard = Dashboard::new("https://example.com", vec![EndpointType::GraphQL]);
+        dashboard.on_asset_discovered("https://cdn.example.com/app.js");
+        dashboard.on_asset_discovered("https://cdn.example.com/vendor.js");
+        dashboard.on_asset_fetched("https://cdn.example.com/app.js", 2048);
+        dashboard.on_error(
+            "https://cdn.example.com/vendor.js",
+            &Error::Other("timed out".to_string()),
+        );
+
+        let mut rest = Endpoint::new("/api/v1/users".to_string(), EndpointType::Rest);
+        rest.method = Some("POST".to_string());
+        dashboard.on_endpoint_found(&rest);
+        dashboard.on_endpoint_found(&Endpoint::new(
+            "/graphql".to_string(),
+            EndpointType::GraphQL,
+        ));
+
+        let lines = plain(dashboard.state().lines(Instant::now(), 40));
+        let frame = lines.join("\n");
+        assert!(frame.contains("Queue 0"));
+        assert!(frame.contains("Fetched 1 (2 KB)"));
+        assert!(frame.contains("Errors 1"));
+        assert!(frame.contains("cdn.example.com"));
+        assert!(frame.contains("rest 1 · graphql 1"));
+        assert!(frame.contains("/graphql"));
+        assert!(!frame.contains("/api/v1/users"));
+        assert!(frame.contains("vendor.js: Unknown error: timed out"));
+
+        let state = dashboard.state();
+        let host = &state.hosts["cdn.example.com"];
+        assert_eq!((host.requests, host.errors), (2, 1));
+    }
+
+    #[test]
+    fn test_dashboard_lines_fit_the_screen() {
+        let dashboard = Dashboard::new("https://example.com", Vec::new());
+        dashboard.on_asset_fetched("https://example.com/app.js", 10);
+        dashboard.on_asset_fetched("https://example.com/vendor.js", 10);
+        for i in 0..50 {
+            dashboard.on_endpoint_found(&Endpoint::new(
+                format!("/api/item/{}", i),
+                EndpointType::Rest,
+            ));
+        }
+
+        let state = dashboard.state();
+        // The newest endpoints fill the rows left over
+        let lines = plain(state.lines(Instant::now(), 20));
+        assert_eq!(lines.len(), 20);
+        assert!(lines.last().unwrap().contains("/api/item/49"));
+        assert!(!lines.iter().any(|l| l.contains("/api/item/30 ")));
+        assert!(lines.iter().any(|l| l.contains("0.4")));
+
+        // Drawn later, the clock has moved on and the requests have aged out
+        let later = plain(state.lines(state.started + Duration::from_secs(3725), 20));
+        assert!(later[0].contains("[01:02:05]"));
+        assert!(later.iter().any(|l| l.contains("0.0")));
+    }
+
+    #[test]
+    fn test_frame_fits_the_terminal() {
+        let lines = vec![
+            Line::from("first"),
+            Line::from("a much longer second line"),
+            Line::from("third"),
+        ];
+        let mut terminal = Terminal::new(TestBackend::new(10, 2)).unwrap();
+        terminal.draw(|frame| draw(frame, lines)).unwrap();
+        terminal
+            .backend()
+            .assert_buffer(&Buffer::with_lines(["first     ", "a much lon"]));
+    }
+}
@@ -0,0 +1,440 @@
+use crate::cli::OutputFormat;
+use crate::error::{Error, Result};
+use crate::output::write_scan_result;
+use crate::types::{Endpoint, EndpointType, ScanResult};
+use clap::ValueEnum;
+use colored::*;
+use console::{truncate_str, Term};
+use std::collections::BTreeSet;
+use std::io::IsTerminal;
+use std::path::Path;
+
+/// Endpoints listed per page
+const PAGE_SIZE: usize = 20;
+
+const HELP: &str = "\
+  /TEXT, search TEXT     fuzzy search URLs (no text clears it); other text searches too
+  type TYPES             only these types, e.g. rest,graphql (no types clears it)
+  method METHODS         only these methods, e.g. post,put
+  tag TAGS               only endpoints with one of these tags
+  clear                  drop every filter and the search
+  n, p                   next and previous page
+  select N,M-K | all     mark endpoints by their number in the list
+  unselect N,M-K | all   unmark them
+  show N                 every field of one endpoint
+  export FILE [FORMAT]   write the selection, or every match if none, as FORMAT [default: from FILE's extension, else json]
+  help                   this list
+  q, quit                leave";
+
+/// What to do after a command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// Print this and prompt again
+    Continue(String),
+    /// Leave the explorer
+    Quit,
+}
+
+/// Post-scan prompt for triaging a result: fuzzy search, filters by type,
+/// method and tag, and export of a selected subset
+pub struct Explorer<'a> {
+    result: &'a ScanResult,
+    query: String,
+    types: Vec<EndpointType>,
+    methods: Vec<String>,
+    tags: Vec<String>,
+    /// Indices into the result's endpoints that pass, best match first
+    matches: Vec<usize>,
+    /// Indices into the result's endpoints
+    selected: BTreeSet<usize>,
+    page: usize,
+}
+
+impl<'a> Explorer<'a> {
+    pub fn new(result: &'a ScanResult) -> Self {
+        let mut explorer = Self {
+            result,
+            query: String::new(),
+            types: Vec::new(),
+            methods: Vec::new(),
+            tags: Vec::new(),
+            matches: Vec::new(),
+            selected: BTreeSet::new(),
+            page: 0,
+        };
+        explorer.refresh();
+        explorer
+    }
+
+    /// Whether there's a terminal to prompt on: stdin and stdout both
+    pub fn available() -> bool {
+        std::io::stdin().is_terminal() && Term::stdout().is_term()
+    }
+
+    /// Prompt on the terminal until the user quits or closes stdin
+    pub fn run(&mut self) -> Result<()> {
+        let term = Term::stdout();
+        term.write_line(&self.render())?;
+        term.write_line(&format!(
+            "{}",
+            "Type help for commands, q to leave.".dimmed()
+        ))?;
+        loop {
+            term.write_str(&format!("{} ", "explore>".bold().cyan()))?;
+            let line = match term.read_line() {
+                Ok(line) => line,
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(()),
+                Err(e) => return Err(e.into()),
+            };
+            match self.command(&line) {
+                Ok(Step::Continue(output)) => term.write_line(&output)?,
+                Ok(Step::Quit) => return Ok(()),
+                Err(e) => term.write_line(&format!("{} {}", "❌".red(), e))?,
+            }
+        }
+    }
+
+    /// Run one command line
+    pub fn command(&mut self, line: &str) -> Result<Step> {
+        let line = line.trim();
+        let (word, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let rest = rest.trim();
+
+        match word {
+            "" => return Ok(Step::Continue(self.render())),
+            "q" | "quit" | "exit" => return Ok(Step::Quit),
+            "help" | "?" => return Ok(Step::Continue(HELP.to_string())),
+            "n" | "next" => {
+                if (self.page + 1) * PAGE_SIZE < self.matches.len() {
+                    self.page += 1;
+                }
+                return Ok(Step::Continue(self.render()));
+            }
+            "p" | "prev" => {
+                self.page = self.page.saturating_sub(1);
+                return Ok(Step::Continue(self.render()));
+            }
+            "search" => self.query = rest.to_string(),
+            "type" => {
+                self.types = list(rest)
+                    .map(|t| t.parse().map_err(Error::ValidationError))
+                    .collect::<Result<_>>()?
+            }
+            "method" => self.methods = list(rest).map(|m| m.to_uppercase()).collect(),
+            "tag" => self.tags = list(rest).map(str::to_lowercase).collect(),
+            "clear" => {
+                self.query.clear();
+                self.types.clear();
+                self.methods.clear();
+                self.tags.clear();
+            }
+            "select" | "unselect" => {
+                let picked = self.pick(rest)?;
+                let count = picked.len();
+                if word == "select" {
+                    self.selected.extend(picked);
+                } else {
+                    self.selected.retain(|i| !picked.contains(i));
+                }
+                return Ok(Step::Continue(format!(
+                    "{} {} endpoints, {} selected in all",
+                    if word == "select" {
+                        "Selected"
+                    } else {
+                        "Unselected"
+                    },
+                    count,
+                    self.selected.len()
+                )));
+            }
+            "show" => {
+                let picked = self.pick(rest)?;
+                let shown: Vec<&Endpoint> =
+                    picked.iter().map(|&i| &self.result.endpoints[i]).collect();
+                return Ok(Step::Continue(serde_json::to_string_pretty(&shown)?));
+            }
+            "export" => return self.export(rest).map(Step::Continue),
+            _ if line.starts_with('/') => self.query = line[1..].trim().to_string(),
+            _ => self.query = line.to_string(),
+        }
+        self.refresh();
+        Ok(Step::Continue(self.render()))
+    }
+
+    /// Recompute the matches after a filter or the search changed
+    fn refresh(&mut self) {
+        let mut scored: Vec<(i64, usize)> = self
+            .result
+            .endpoints
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| self.passes(e))
+            .filter_map(|(i, e)| fuzzy_score(&self.query, &e.url).map(|score| (score, i)))
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        self.matches = scored.into_iter().map(|(_, i)| i).collect();
+        self.page = 0;
+    }
+
+    fn passes(&self, endpoint: &Endpoint) -> bool {
+        let method = endpoint.method.as_deref().unwrap_or("GET").to_uppercase();
+        (self.types.is_empty() || self.types.contains(&endpoint.endpoint_type))
+            && (self.methods.is_empty() || self.methods.contains(&method))
+            && (self.tags.is_empty() || self.tags.iter().any(|t| endpoint.has_tag(t)))
+    }
+
+    /// Endpoints named by list numbers such as `1,3-5`, or `all` matches
+    fn pick(&self, spec: &str) -> Result<Vec<usize>> {
+        if spec == "all" {
+            return Ok(self.matches.clone());
+        }
+        let invalid = |part: &str| {
+            Error::ValidationError(format!(
+                "'{}' isn't a number from 1 to {}",
+                part,
+                self.matches.len()
+            ))
+        };
+        let mut picked = Vec::new();
+        for part in list(spec) {
+            let (start, end) = part.split_once('-').unwrap_or((part, part));
+            let start: usize = start.trim().parse().map_err(|_| invalid(part))?;
+            let end: usize = end.trim().parse().map_err(|_| invalid(part))?;
+            if start == 0 || start > end || end > self.matches.len() {
+                return Err(invalid(part));
+            }
+            picked.extend(&self.matches[start - 1..end]);
+        }
+        if picked.is_empty() {
+            return Err(Error::ValidationError(
+                "name endpoints by number, e.g. 1,3-5, or all".to_string(),
+            ));
+        }
+        Ok(picked)
+    }
+
+    /// Write the selection, or every match, to a file
+    fn export(&self, args: &str) -> Result<String> {
+        let mut args = args.split_whitespace();
+        let Some(path) = args.next().map(Path::new) else {
+            return Err(Error::ValidationError(
+                "usage: export FILE [FORMAT]".to_string(),
+            ));
+        };
+        let format = match args.next() {
+            Some(name) => OutputFormat::from_str(name, true)
+                .map_err(|_| Error::ValidationError(format!("unknown format '{}'", name)))?,
+            None => path
+                .extension()
+                .and_then(|ext| OutputFormat::from_str(&ext.to_string_lossy(), true).ok())
+                .unwrap_or(OutputFormat::Json),
+        };
+
+        let indices: Vec<usize> = if self.selected.is_empty() {
+            self.matches.clone()
+        } else {
+            self.selected.iter().copied().collect()
+        };
+        let endpoints: Vec<Endpoint> = indices
+            .iter()
+            .map(|&i| self.result.endpoints[i].clone())
+            .collect();
+        let subset = ScanResult {
+            total_endpoints: endpoints.len(),
+            endpoints,
+            ..self.result.clone()
+        };
+        write_scan_result(&subset, Some(path), format)?;
+        Ok(format!(
+            "{} {} endpoints to {}",
+            "📄 Exported".green(),
+            subset.total_endpoints,
+            path.display()
+        ))
+    }
+
+    /// The current page of matches, with the filters above it
+    fn render(&self) -> String {
+        let mut filters = Vec::new();
+        if !self.query.is_empty() {
+            filters.push(format!("search '{}'", self.query));
+        }
+        if !self.types.is_empty() {
+            let types: Vec<&str> = self.types.iter().map(|t| t.as_str()).collect();
+            filters.push(format!("type {}", types.join(",")));
+        }
+        if !self.methods.is_empty() {
+            filters.push(format!("method {}", self.methods.join(",")));
+        }
+        if !self.tags.is_empty() {
+            filters.push(format!("tag {}", self.tags.join(",")));
+        }
+
+        let pages = self.matches.len().div_ceil(PAGE_SIZE).max(1);
+        let mut lines = vec![format!(
+            "{} of {} endpoints{}, {} selected — page {}/{}",
+            self.matches.len().to_string().bold(),
+            self.result.endpoints.len(),
+            if filters.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", filters.join(", "))
+            },
+            self.selected.len(),
+            self.page + 1,
+            pages
+        )];
+
+        let (_, cols) = Term::stdout().size();
+        let start = self.page * PAGE_SIZE;
+        for (n, &i) in self.matches.iter().enumerate().skip(start).take(PAGE_SIZE) {
+            let endpoint = &self.result.endpoints[i];
+            let method = endpoint.method.as_deref().unwrap_or("GET").to_uppercase();
+            let tags = endpoint
+                .tags
+                .as_ref()
+                .filter(|tags| !tags.is_empty())
+                .map(|tags| format!(" [{}]", tags.join(", ")).dimmed().to_string())
+                .unwrap_or_default();
+            let line = format!(
+                "{:>4} {} {:<7} {:<9} {}{}",
+                n + 1,
+                if self.selected.contains(&i) {
+                    "●".green()
+                } else {
+                    "○".dimmed()
+                },
+                method.bright_blue(),
+                endpoint.endpoint_type.as_str().dimmed(),
+                endpoint.url,
+                tags
+            );
+            lines.push(truncate_str(&line, cols as usize, "…").to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+/// Comma-separated values, trimmed, without empty ones
+fn list(s: &str) -> impl Iterator<Item = &str> {
+    s.split(',').map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// How well `text` matches a fuzzy `query`, or `None` if it doesn't
+///
+/// The query's characters must appear in order, ignoring case. Runs of
+/// consecutive characters and matches at the start of a path segment or
+/// word score higher, so `apiusr` ranks `/api/users` above
+/// `/static/app/img/user.png`. An empty query matches everything equally.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let mut query = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut run = 0;
+    for c in text.chars() {
+        let Some(&wanted) = query.peek() else { break };
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        if lower == wanted {
+            query.next();
+            run += 1;
+            score += 1 + run * 2;
+            if !previous.is_some_and(|p| p.is_alphanumeric()) {
+                score += 3;
+            }
+        } else {
+            run = 0;
+        }
+        previous = Some(c);
+    }
+    // Shorter URLs win ties
+    query
+        .peek()
+        .is_none()
+        .then(|| score * 1000 - text.len() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{SCHEMA_VERSION, TOOL_VERSION};
+
+    fn result() -> ScanResult {
+        let endpoints = vec![
+            Endpoint::new(
+                "/static/app/img/user.png".to_string(),
+                EndpointType::Unknown,
+            ),
+            Endpoint::new("/api/users".to_string(), EndpointType::Rest),
+            Endpoint::new("/api/admin/users".to_string(), EndpointType::Rest)
+                .with_method("DELETE".to_string())
+                .with_tag("admin"),
+            Endpoint::new("/graphql".to_string(), EndpointType::GraphQL)
+                .with_method("POST".to_string()),
+        ];
+        ScanResult {
+            schema_version: SCHEMA_VERSION,
+            tool_version: TOOL_VERSION.to_string(),
+            target: "https://example.com".to_string(),
+            timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+            total_endpoints: endpoints.len(),
+            endpoints,
+            findings: Vec::new(),
+            wordlist: Vec::new(),
+            technologies: Vec::new(),
+            stats: None,
+        }
+    }
+
+    fn urls(explorer: &Explorer) -> Vec<String> {
+        explorer
+            .matches
+            .iter()
+            .map(|&i| explorer.result.endpoints[i].url.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_explorer_filters_and_exports() {
+        colored::control::set_override(false);
+        let result = result();
+        let mut explorer = Explorer::new(&result);
+        assert_eq!(explorer.matches.len(), 4);
+
+        explorer.command("/apiusr").unwrap();
+        assert_eq!(
+            urls(&explorer),
+            vec!["/api/users", "/api/admin/users", "/static/app/img/user.png"]
+        );
+
+        explorer.command("type rest").unwrap();
+        explorer.command("method delete").unwrap();
+        assert_eq!(urls(&explorer), vec!["/api/admin/users"]);
+        explorer.command("clear").unwrap();
+        explorer.command("tag admin").unwrap();
+        assert_eq!(urls(&explorer), vec!["/api/admin/users"]);
+        assert!(explorer.command("type soap").is_err());
+
+        explorer.command("clear").unwrap();
+        let Step::Continue(output) = explorer.command("select 2-3").unwrap() else {
+            panic!("select quits");
+        };
+        assert!(output.contains("Selected 2 endpoints"));
+        assert!(explorer.command("select 9").is_err());
+        let Step::Continue(page) = explorer.command("").unwrap() else {
+            panic!("an empty line quits");
+        };
+        assert!(page.contains("4 of 4 endpoints, 2 selected"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("subset.json");
+        explorer
+            .command(&format!("export {}", path.display()))
+            .unwrap();
+        let exported: ScanResult =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let exported: Vec<&str> = exported.endpoints.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(exported, vec!["/api/users", "/api/admin/users"]);
+
+        assert_eq!(explorer.command("q").unwrap(), Step::Quit);
+    }
+}
@@ -1,26 +1,46 @@
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use crate::error::Error;
+use crate::observer::ScanObserver;
+use crate::util::lock;
+use indicatif::{MultiProgress, ProgressBar, ProgressState, ProgressStyle};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+/// Most asset bars shown at once; assets past that only count on the main bar
+const MAX_CHILD_BARS: usize = 8;
 
 /// Interactive UI manager using indicatif
 pub struct InteractiveUi {
     multi: MultiProgress,
     main_pb: ProgressBar,
+    /// A bar per asset being fetched or parsed, keyed by URL
+    children: Mutex<HashMap<String, ProgressBar>>,
+    /// Endpoints parsed so far, before deduplication
+    endpoints: AtomicUsize,
 }
 
 impl InteractiveUi {
-    /// Create a new interactive UI
     /// Create a new interactive UI
     pub fn new(total_steps: u64) -> Self {
         let multi = MultiProgress::new();
 
         let main_pb = multi.add(ProgressBar::new(total_steps));
         main_pb.set_style(ProgressStyle::default_bar()
-            .template("{spinner:.magenta} {prefix:.bold.cyan} [{elapsed_precise}] [{bar:40.gradient(blue,cyan)}] {pos}/{len} {msg}")
+            .template("{spinner:.magenta} {prefix:.bold.cyan} [{elapsed_precise}] [{bar:40.gradient(blue,cyan)}] {pos}/{len} ({rate}) {msg}")
             .expect("Failed to set progress style")
+            .with_key("rate", |state: &ProgressState, w: &mut dyn std::fmt::Write| {
+                let _ = write!(w, "{:.1} req/s", state.per_sec());
+            })
             .progress_chars("█▓▒░"));
 
         main_pb.set_prefix("Scanning");
 
-        Self { multi, main_pb }
+        Self {
+            multi,
+            main_pb,
+            children: Mutex::new(HashMap::new()),
+            endpoints: AtomicUsize::new(0),
+        }
     }
 
     /// Add a child progress bar
@@ -48,6 +68,114 @@ impl InteractiveUi {
 
     /// Finish UI
     pub fn finish(&self) {
+        self.clear_children();
         self.main_pb.finish_with_message("Done! ✨");
     }
+
+    fn children(&self) -> MutexGuard<'_, HashMap<String, ProgressBar>> {
+        lock(&self.children)
+    }
+
+    /// Move an asset's bar on to its next step
+    fn advance_child(&self, url: &str, message: String) {
+        if let Some(pb) = self.children().get(url) {
+            pb.inc(1);
+            pb.set_message(message);
+        }
+    }
+
+    fn remove_child(&self, url: &str) {
+        if let Some(pb) = self.children().remove(url) {
+            pb.finish_and_clear();
+        }
+    }
+
+    /// Clear the bars of assets that never finished, e.g. ones skipped as
+    /// already fetched
+    fn clear_children(&self) {
+        for (_, pb) in self.children().drain() {
+            pb.finish_and_clear();
+        }
+    }
+}
+
+/// The last path segment of an asset URL, which fits on a child bar
+fn asset_name(url: &str) -> &str {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    match path.trim_end_matches('/').rsplit('/').next() {
+        Some(name) if !name.is_empty() => name,
+        _ => url,
+    }
+}
+
+/// Drives the progress bars from scan events: one main step per fetched
+/// asset, and a two-step child bar (fetch, parse) per asset in flight
+impl ScanObserver for InteractiveUi {
+    fn on_asset_discovered(&self, url: &str) {
+        self.main_pb.inc_length(1);
+        self.set_main_message(&format!("Found {}", url));
+    }
+
+    fn on_asset_fetching(&self, url: &str) {
+        let mut children = self.children();
+        if children.len() < MAX_CHILD_BARS && !children.contains_key(url) {
+            let pb = self.add_child_pb(2, &format!("fetching {}", asset_name(url)));
+            children.insert(url.to_string(), pb);
+        }
+    }
+
+    fn on_asset_fetched(&self, url: &str, bytes: usize) {
+        self.inc_main();
+        self.set_main_message(&format!("Fetched {}", url));
+        self.advance_child(
+            url,
+            format!("parsing {} ({} KB)", asset_name(url), bytes.div_ceil(1024)),
+        );
+    }
+
+    fn on_asset_parsed(&self, url: &str, endpoints: usize) {
+        let total = self.endpoints.fetch_add(endpoints, Ordering::Relaxed) + endpoints;
+        self.main_pb
+            .set_prefix(format!("Scanning · {} endpoints", total));
+        self.remove_child(url);
+    }
+
+    fn on_error(&self, url: &str, error: &Error) {
+        self.remove_child(url);
+        let _ = self.multi.println(format!("  ✗ {}: {}", url, error));
+    }
+
+    fn on_complete(&self, _target: &str, total_endpoints: usize) {
+        self.clear_children();
+        self.main_pb
+            .finish_with_message(format!("Done! ✨ {} endpoints", total_endpoints));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_child_bars_follow_assets() {
+        let ui = InteractiveUi::new(0);
+        for i in 0..MAX_CHILD_BARS + 2 {
+            let url = format!("https://example.com/static/chunk-{}.js?v=1", i);
+            ui.on_asset_discovered(&url);
+            ui.on_asset_fetching(&url);
+        }
+        assert_eq!(ui.children().len(), MAX_CHILD_BARS);
+
+        let url = "https://example.com/static/chunk-0.js?v=1";
+        assert_eq!(asset_name(url), "chunk-0.js");
+        ui.on_asset_fetched(url, 2048);
+        assert_eq!(ui.children()[url].position(), 1);
+        ui.on_asset_parsed(url, 5);
+        assert!(!ui.children().contains_key(url));
+        assert_eq!(ui.endpoints.load(Ordering::Relaxed), 5);
+        assert_eq!(ui.main_pb.position(), 1);
+
+        ui.on_complete("https://example.com/", 5);
+        assert!(ui.children().is_empty());
+    }
 }
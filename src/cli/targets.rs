@@ -0,0 +1,38 @@
+use crate::error::Result;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+/// Read scan targets from a file, or from stdin when the path is `-`
+pub fn load_targets(path: &Path) -> Result<Vec<String>> {
+    if path == Path::new("-") {
+        read_targets(io::stdin().lock())
+    } else {
+        read_targets(BufReader::new(File::open(path)?))
+    }
+}
+
+/// Read one target per line, skipping blanks and `#` comments
+///
+/// Bare hosts (as produced by subdomain enumeration tools) get `https://`.
+/// Duplicates are dropped, keeping the first occurrence.
+pub fn read_targets<R: BufRead>(reader: R) -> Result<Vec<String>> {
+    let mut targets: Vec<String> = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let target = if line.contains("://") {
+            line.to_string()
+        } else {
+            format!("https://{}", line)
+        };
+        if !targets.contains(&target) {
+            targets.push(target);
+        }
+    }
+    Ok(targets)
+}
@@ -1,4 +1,183 @@
-use std::path::PathBuf;
+use crate::classify::load_rules;
+use crate::crawler::client::{merge_headers, parse_cookies};
+use crate::error::{Error, Result};
+use crate::parser::filters::TrailingSlashPolicy;
+use crate::parser::patterns::load_patterns;
+use crate::plugins::{Builtin, PluginErrorPolicy, PluginLimits, Script, TimeoutPolicy};
+use crate::types::Severity;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use url::Url;
+
+/// Named preset of scan settings
+///
+/// `passive` stays gentle: no probing, robots.txt respected, 2 requests per
+/// second and 2 at a time. `standard` is the default configuration.
+/// `aggressive` probes endpoints (with `OPTIONS`), ignores robots.txt, and
+/// allows 50 requests per second and 50 at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanProfile {
+    Passive,
+    #[default]
+    Standard,
+    Aggressive,
+}
+
+impl ScanProfile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScanProfile::Passive => "passive",
+            ScanProfile::Standard => "standard",
+            ScanProfile::Aggressive => "aggressive",
+        }
+    }
+}
+
+impl std::str::FromStr for ScanProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "passive" => Ok(ScanProfile::Passive),
+            "standard" => Ok(ScanProfile::Standard),
+            "aggressive" => Ok(ScanProfile::Aggressive),
+            _ => Err(format!(
+                "unknown profile '{}' (expected passive, standard or aggressive)",
+                s
+            )),
+        }
+    }
+}
+
+/// A plugin to load, with its place in the chain
+///
+/// Plugins run in ascending `priority`; ties keep the order they were
+/// given in. A disabled plugin is skipped without being loaded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PluginSpec {
+    /// Python plugin, native plugin library or WASM module
+    pub path: PathBuf,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub priority: i32,
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+}
+
+fn is_zero(priority: &i32) -> bool {
+    *priority == 0
+}
+
+fn enabled_default() -> bool {
+    true
+}
+
+impl PluginSpec {
+    /// An enabled plugin at the default priority, 0
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            priority: 0,
+            enabled: true,
+        }
+    }
+
+    /// Set the priority
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Enable or disable the plugin
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Whether `name` names this plugin, by file name or by file name
+    /// without the extension
+    fn is_named(path: &Path, name: &str) -> bool {
+        path.file_name().is_some_and(|n| n == name) || path.file_stem().is_some_and(|n| n == name)
+    }
+}
+
+/// Request settings for hosts matching a glob, in place of the scan's
+///
+/// `*` in the glob matches any run of characters, dots included, so
+/// `*.staging.example.com` matches every staging host and `api-*` every
+/// host starting with `api-`. Hosts are compared without their port and
+/// case-insensitively. Unset settings keep the scan's.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HostOverride {
+    /// Host glob the settings apply to
+    pub host: String,
+    /// Requests per second to the matching hosts, together
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<u32>,
+    /// Request timeout in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// Extra `Name: Value` headers, replacing the scan's of the same name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<String>,
+    /// Whether to honour the hosts' robots.txt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub respect_robots: Option<bool>,
+}
+
+impl HostOverride {
+    /// Settings for hosts matching a glob, all unset
+    pub fn new(host: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Set the requests per second to the matching hosts
+    pub fn with_rate_limit(mut self, rate_limit: u32) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Set the request timeout in seconds
+    pub fn with_timeout(mut self, timeout: u64) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Add an extra `Name: Value` header
+    pub fn with_header(mut self, header: impl Into<String>) -> Self {
+        self.headers.push(header.into());
+        self
+    }
+
+    /// Set whether to honour the hosts' robots.txt
+    pub fn with_respect_robots(mut self, respect: bool) -> Self {
+        self.respect_robots = Some(respect);
+        self
+    }
+
+    /// Whether the glob matches a host
+    pub fn matches(&self, host: &str) -> bool {
+        glob_matches(
+            self.host.to_ascii_lowercase().as_bytes(),
+            host.to_ascii_lowercase().as_bytes(),
+        )
+    }
+}
+
+/// Whether `text` matches a glob where `*` stands for any run of bytes
+fn glob_matches(glob: &[u8], text: &[u8]) -> bool {
+    match glob.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| glob_matches(rest, &text[skip..])),
+        Some((c, rest)) => text.first() == Some(c) && glob_matches(rest, &text[1..]),
+    }
+}
 
 /// Configuration for scanning operations
 #[derive(Debug, Clone)]
@@ -21,14 +200,131 @@ pub struct ScanConfig {
     /// Respect robots.txt
     pub respect_robots_txt: bool,
 
+    /// Settings for particular hosts, in place of the ones above; the
+    /// first entry matching a host applies
+    pub host_overrides: Vec<HostOverride>,
+
     /// Custom User-Agent header
     pub user_agent: Option<String>,
 
+    /// Extra `Name: Value` headers sent to the targets and included hosts
+    pub headers: Vec<String>,
+
+    /// Cookies sent to the targets and included hosts, as
+    /// `name=value; name=value`
+    pub cookies: Option<String>,
+
+    /// Proxy to send every request through (`http`, `https`, `socks5` or
+    /// `socks5h` URL)
+    pub proxy: Option<String>,
+
+    /// Proxy credentials, as `user:password`
+    pub proxy_auth: Option<String>,
+
     /// Filter pattern for endpoints
     pub filter_pattern: Option<String>,
 
-    /// Path to a Python plugin
-    pub plugin_path: Option<PathBuf>,
+    /// Python plugins and native plugin libraries to load
+    pub plugins: Vec<PluginSpec>,
+
+    /// Directory whose plugins are loaded after the listed ones, at
+    /// priority 0
+    pub plugin_dir: Option<PathBuf>,
+
+    /// Plugins to skip, by file name with or without the extension
+    pub disabled_plugins: Vec<String>,
+
+    /// Built-in plugins to run, before the loaded ones
+    pub builtin_plugins: Vec<Builtin>,
+
+    /// How long one plugin hook call may take
+    pub plugin_timeout: Option<Duration>,
+
+    /// What to do when a plugin hook overruns `plugin_timeout`
+    pub on_plugin_timeout: TimeoutPolicy,
+
+    /// Most endpoints one plugin may generate from an asset
+    pub plugin_max_generated: Option<usize>,
+
+    /// What to do when a plugin hook fails
+    pub plugin_errors: PluginErrorPolicy,
+
+    /// The one plugin `--format plugin` hands results to, rather than every
+    /// plugin that writes output
+    pub output_plugin: Option<String>,
+
+    /// Filter script endpoints must pass; see [`crate::plugins::script`]
+    pub script: Option<String>,
+
+    /// Path to a YAML/TOML file with extra extraction patterns
+    pub patterns_path: Option<PathBuf>,
+
+    /// Apply aggressive false-positive suppression
+    pub aggressive_filter: bool,
+
+    /// Path to a YAML/TOML file with extra tag rules
+    pub rules_path: Option<PathBuf>,
+
+    /// Keep only endpoints at or above this severity
+    pub min_severity: Option<Severity>,
+
+    /// Keep only endpoints carrying at least one of these tags (all if empty)
+    pub only_tags: Vec<String>,
+
+    /// Trailing-slash handling when canonicalizing endpoint URLs
+    pub trailing_slash: TrailingSlashPolicy,
+
+    /// Inputs larger than this many bytes are parsed in windows
+    pub max_parse_bytes: usize,
+
+    /// Window size in bytes for windowed parsing
+    pub parse_window_bytes: usize,
+
+    /// Probe discovered endpoints on the target's host after extraction
+    pub probe: bool,
+
+    /// Send `OPTIONS` to endpoints that responded to a probe
+    pub probe_options: bool,
+
+    /// Stop fetching and probing once a scan has run this long
+    pub max_duration: Option<Duration>,
+
+    /// Keep at most this many endpoints per scan
+    pub max_endpoints: Option<usize>,
+
+    /// Fetch at most this many assets across the crawler's lifetime
+    pub max_assets: Option<usize>,
+
+    /// Follow links to same-origin pages this many hops from the target
+    /// (0 crawls only the target page)
+    pub max_depth: usize,
+
+    /// Fetch at most this many pages per target when following links
+    pub max_pages: usize,
+
+    /// Remember at most this many visited URLs exactly (unbounded if unset)
+    pub max_visited: Option<usize>,
+
+    /// Keep URLs past `max_visited` in a fixed-size bloom filter
+    pub visited_bloom: bool,
+
+    /// Extensions to parse when walking directories (all parseable if empty)
+    pub file_extensions: Vec<String>,
+
+    /// Local files larger than this many bytes are skipped
+    pub max_file_bytes: Option<u64>,
+
+    /// Hosts (and their subdomains) to keep to; `*.host` for subdomains only
+    pub include_hosts: Vec<String>,
+
+    /// Hosts (and their subdomains) never to fetch from or report
+    pub exclude_hosts: Vec<String>,
+
+    /// Fetch and report only URLs matching one of these regexes
+    pub include_regex: Vec<String>,
+
+    /// Never fetch or report URLs matching one of these regexes
+    pub exclude_regex: Vec<String>,
 }
 
 impl Default for ScanConfig {
@@ -40,9 +336,46 @@ impl Default for ScanConfig {
             max_concurrent: 10,
             follow_redirects: true,
             respect_robots_txt: true,
+            host_overrides: Vec::new(),
             user_agent: Some("Endpointo/0.1.0".to_string()),
+            headers: Vec::new(),
+            cookies: None,
+            proxy: None,
+            proxy_auth: None,
             filter_pattern: None,
-            plugin_path: None,
+            plugins: Vec::new(),
+            plugin_dir: None,
+            disabled_plugins: Vec::new(),
+            builtin_plugins: Vec::new(),
+            plugin_timeout: None,
+            on_plugin_timeout: TimeoutPolicy::default(),
+            plugin_max_generated: None,
+            plugin_errors: PluginErrorPolicy::default(),
+            output_plugin: None,
+            script: None,
+            patterns_path: None,
+            aggressive_filter: false,
+            rules_path: None,
+            min_severity: None,
+            only_tags: Vec::new(),
+            trailing_slash: TrailingSlashPolicy::default(),
+            max_parse_bytes: 32 * 1024 * 1024,
+            parse_window_bytes: 4 * 1024 * 1024,
+            probe: false,
+            probe_options: false,
+            max_duration: None,
+            max_endpoints: None,
+            max_assets: None,
+            max_depth: 0,
+            max_pages: 100,
+            max_visited: None,
+            visited_bloom: false,
+            file_extensions: Vec::new(),
+            max_file_bytes: None,
+            include_hosts: Vec::new(),
+            exclude_hosts: Vec::new(),
+            include_regex: Vec::new(),
+            exclude_regex: Vec::new(),
         }
     }
 }
@@ -56,6 +389,30 @@ impl ScanConfig {
         }
     }
 
+    /// Apply a profile's presets
+    ///
+    /// Call this before the other setters so explicitly chosen values
+    /// override the profile's.
+    pub fn with_profile(mut self, profile: ScanProfile) -> Self {
+        let defaults = Self::default();
+        let (rate_limit, max_concurrent, respect_robots_txt, probe) = match profile {
+            ScanProfile::Passive => (2, 2, true, false),
+            ScanProfile::Standard => (
+                defaults.rate_limit,
+                defaults.max_concurrent,
+                defaults.respect_robots_txt,
+                defaults.probe,
+            ),
+            ScanProfile::Aggressive => (50, 50, false, true),
+        };
+        self.rate_limit = rate_limit;
+        self.max_concurrent = max_concurrent;
+        self.respect_robots_txt = respect_robots_txt;
+        self.probe = probe;
+        self.probe_options = probe;
+        self
+    }
+
     /// Set rate limit
     pub fn with_rate_limit(mut self, rate: Option<u32>) -> Self {
         if let Some(r) = rate {
@@ -92,21 +449,1197 @@ impl ScanConfig {
         self
     }
 
+    /// Add settings for particular hosts, after the ones already set
+    pub fn with_host_overrides(mut self, overrides: Vec<HostOverride>) -> Self {
+        self.host_overrides.extend(overrides);
+        self
+    }
+
+    /// The settings for a host, if an override matches it
+    pub fn host_override(&self, host: &str) -> Option<&HostOverride> {
+        self.host_overrides.iter().find(|o| o.matches(host))
+    }
+
+    /// Whether to honour robots.txt when crawling a URL
+    pub fn respects_robots(&self, url: &Url) -> bool {
+        url.host_str()
+            .and_then(|host| self.host_override(host))
+            .and_then(|o| o.respect_robots)
+            .unwrap_or(self.respect_robots_txt)
+    }
+
     /// Set custom user agent
     pub fn with_user_agent(mut self, ua: impl Into<String>) -> Self {
         self.user_agent = Some(ua.into());
         self
     }
 
+    /// Set the extra `Name: Value` headers sent to the targets
+    pub fn with_headers(mut self, headers: Vec<String>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Add an extra `Name: Value` header sent to the targets
+    pub fn with_header(mut self, header: impl Into<String>) -> Self {
+        self.headers.push(header.into());
+        self
+    }
+
+    /// Set the cookies sent to the targets
+    pub fn with_cookies(mut self, cookies: Option<String>) -> Self {
+        if let Some(c) = cookies {
+            self.cookies = Some(c);
+        }
+        self
+    }
+
+    /// Set the proxy to send every request through
+    pub fn with_proxy(mut self, proxy: Option<String>) -> Self {
+        if let Some(p) = proxy {
+            self.proxy = Some(p);
+        }
+        self
+    }
+
+    /// Set the proxy credentials, as `user:password`
+    pub fn with_proxy_auth(mut self, auth: Option<String>) -> Self {
+        if let Some(a) = auth {
+            self.proxy_auth = Some(a);
+        }
+        self
+    }
+
     /// Set filter pattern
     pub fn with_filter(mut self, pattern: String) -> Self {
         self.filter_pattern = Some(pattern);
         self
     }
 
-    /// Set plugin path
+    /// Add a plugin to load, at priority 0
     pub fn with_plugin(mut self, path: PathBuf) -> Self {
-        self.plugin_path = Some(path);
+        self.plugins.push(PluginSpec::new(path));
+        self
+    }
+
+    /// Add plugins to load
+    pub fn with_plugin_specs(mut self, specs: Vec<PluginSpec>) -> Self {
+        self.plugins.extend(specs);
+        self
+    }
+
+    /// Set a directory of plugins to load
+    pub fn with_plugin_dir(mut self, dir: Option<PathBuf>) -> Self {
+        if dir.is_some() {
+            self.plugin_dir = dir;
+        }
+        self
+    }
+
+    /// Skip plugins by file name, with or without the extension
+    pub fn with_disabled_plugins(mut self, names: Vec<String>) -> Self {
+        self.disabled_plugins.extend(names);
+        self
+    }
+
+    /// Enable built-in plugins; each runs once, in the order first enabled
+    pub fn with_builtin_plugins(mut self, builtins: Vec<Builtin>) -> Self {
+        for builtin in builtins {
+            if !self.builtin_plugins.contains(&builtin) {
+                self.builtin_plugins.push(builtin);
+            }
+        }
+        self
+    }
+
+    /// Set a time limit per plugin hook call
+    pub fn with_plugin_timeout(mut self, timeout: Option<Duration>) -> Self {
+        if timeout.is_some() {
+            self.plugin_timeout = timeout;
+        }
+        self
+    }
+
+    /// Set what happens when a plugin hook overruns its time limit
+    pub fn with_on_plugin_timeout(mut self, policy: Option<TimeoutPolicy>) -> Self {
+        if let Some(policy) = policy {
+            self.on_plugin_timeout = policy;
+        }
+        self
+    }
+
+    /// Set the most endpoints one plugin may generate from an asset
+    pub fn with_plugin_max_generated(mut self, max: Option<usize>) -> Self {
+        if max.is_some() {
+            self.plugin_max_generated = max;
+        }
+        self
+    }
+
+    /// Set what happens when a plugin hook fails
+    pub fn with_plugin_errors(mut self, policy: Option<PluginErrorPolicy>) -> Self {
+        if let Some(policy) = policy {
+            self.plugin_errors = policy;
+        }
+        self
+    }
+
+    /// Set the plugin that writes `--format plugin` output, by name
+    pub fn with_output_plugin(mut self, name: Option<String>) -> Self {
+        if name.is_some() {
+            self.output_plugin = name;
+        }
+        self
+    }
+
+    /// The limits plugin hooks run under
+    pub fn plugin_limits(&self) -> PluginLimits {
+        PluginLimits {
+            timeout: self.plugin_timeout,
+            on_timeout: self.on_plugin_timeout,
+            max_generated: self.plugin_max_generated,
+        }
+    }
+
+    /// The plugin files to load, in the order they run
+    ///
+    /// The listed plugins come first, then those in the plugin directory by
+    /// file name, skipping any already listed; the whole chain is then
+    /// ordered by priority. Disabled plugins are left out.
+    pub fn plugin_chain(&self) -> Result<Vec<PathBuf>> {
+        let mut specs = self.plugins.clone();
+        if let Some(dir) = &self.plugin_dir {
+            for info in crate::plugins::dev::list_plugins(dir)? {
+                let listed = specs.iter().any(|spec| same_file(&spec.path, &info.path));
+                if !listed {
+                    specs.push(PluginSpec::new(info.path));
+                }
+            }
+        }
+        specs.retain(|spec| {
+            spec.enabled
+                && !self
+                    .disabled_plugins
+                    .iter()
+                    .any(|name| PluginSpec::is_named(&spec.path, name))
+        });
+        specs.sort_by_key(|spec| spec.priority);
+        Ok(specs.into_iter().map(|spec| spec.path).collect())
+    }
+
+    /// Set a Rhai filter or transform script
+    pub fn with_script(mut self, script: Option<String>) -> Self {
+        if script.is_some() {
+            self.script = script;
+        }
+        self
+    }
+
+    /// Set custom patterns file
+    pub fn with_patterns(mut self, path: PathBuf) -> Self {
+        self.patterns_path = Some(path);
+        self
+    }
+
+    /// Enable/disable aggressive false-positive suppression
+    pub fn with_aggressive_filter(mut self, aggressive: bool) -> Self {
+        self.aggressive_filter = aggressive;
+        self
+    }
+
+    /// Set custom tag rules file
+    pub fn with_rules(mut self, path: PathBuf) -> Self {
+        self.rules_path = Some(path);
+        self
+    }
+
+    /// Set the minimum severity of reported endpoints
+    pub fn with_min_severity(mut self, severity: Option<Severity>) -> Self {
+        if let Some(s) = severity {
+            self.min_severity = Some(s);
+        }
+        self
+    }
+
+    /// Set the tags reported endpoints must carry one of
+    pub fn with_only_tags(mut self, tags: Vec<String>) -> Self {
+        self.only_tags = tags;
+        self
+    }
+
+    /// Set trailing-slash policy for URL canonicalization
+    pub fn with_trailing_slash(mut self, policy: TrailingSlashPolicy) -> Self {
+        self.trailing_slash = policy;
+        self
+    }
+
+    /// Set the size above which inputs are parsed in windows
+    pub fn with_max_parse_bytes(mut self, bytes: Option<usize>) -> Self {
+        if let Some(b) = bytes {
+            self.max_parse_bytes = b;
+        }
+        self
+    }
+
+    /// Set the window size for windowed parsing
+    pub fn with_parse_window(mut self, bytes: Option<usize>) -> Self {
+        if let Some(b) = bytes {
+            self.parse_window_bytes = b;
+        }
+        self
+    }
+
+    /// Enable/disable probing of discovered endpoints
+    pub fn with_probe(mut self, probe: bool) -> Self {
+        self.probe = probe;
+        self
+    }
+
+    /// Enable/disable asking probed endpoints for their allowed methods
+    pub fn with_probe_options(mut self, options: bool) -> Self {
+        self.probe_options = options;
+        self
+    }
+
+    /// Set a time limit per scan
+    pub fn with_max_duration(mut self, duration: Option<Duration>) -> Self {
+        if let Some(d) = duration {
+            self.max_duration = Some(d);
+        }
+        self
+    }
+
+    /// Set the maximum number of endpoints per scan
+    pub fn with_max_endpoints(mut self, max: Option<usize>) -> Self {
+        if let Some(m) = max {
+            self.max_endpoints = Some(m);
+        }
+        self
+    }
+
+    /// Set the maximum number of assets to fetch
+    pub fn with_max_assets(mut self, max: Option<usize>) -> Self {
+        if let Some(m) = max {
+            self.max_assets = Some(m);
+        }
+        self
+    }
+
+    /// Set how many link hops from the target to crawl
+    pub fn with_max_depth(mut self, depth: Option<usize>) -> Self {
+        if let Some(d) = depth {
+            self.max_depth = d;
+        }
+        self
+    }
+
+    /// Set the maximum number of pages to fetch per target
+    pub fn with_max_pages(mut self, max: Option<usize>) -> Self {
+        if let Some(m) = max {
+            self.max_pages = m;
+        }
+        self
+    }
+
+    /// Set the maximum number of visited URLs remembered exactly
+    pub fn with_max_visited(mut self, max: Option<usize>) -> Self {
+        if let Some(m) = max {
+            self.max_visited = Some(m);
+        }
+        self
+    }
+
+    /// Enable/disable the bloom filter tier for visited URLs
+    pub fn with_visited_bloom(mut self, enabled: bool) -> Self {
+        self.visited_bloom = enabled;
+        self
+    }
+
+    /// Set the extensions to parse when walking directories
+    pub fn with_file_extensions(mut self, extensions: Vec<String>) -> Self {
+        self.file_extensions = extensions;
+        self
+    }
+
+    /// Set the size above which local files are skipped
+    pub fn with_max_file_bytes(mut self, bytes: Option<u64>) -> Self {
+        if let Some(b) = bytes {
+            self.max_file_bytes = Some(b);
+        }
+        self
+    }
+
+    /// Set the hosts to keep crawling and results to
+    pub fn with_include_hosts(mut self, hosts: Vec<String>) -> Self {
+        self.include_hosts = hosts;
+        self
+    }
+
+    /// Set the hosts to leave out of crawling and results
+    pub fn with_exclude_hosts(mut self, hosts: Vec<String>) -> Self {
+        self.exclude_hosts = hosts;
+        self
+    }
+
+    /// Set the regexes in-scope URLs must match one of
+    pub fn with_include_regex(mut self, patterns: Vec<String>) -> Self {
+        self.include_regex = patterns;
+        self
+    }
+
+    /// Set the regexes that put a URL out of scope
+    pub fn with_exclude_regex(mut self, patterns: Vec<String>) -> Self {
+        self.exclude_regex = patterns;
+        self
+    }
+}
+
+/// Commented configuration file written by `endpointo config init`
+///
+/// Every setting is commented out at its default, so the file changes
+/// nothing until a line is uncommented.
+pub const DEFAULT_CONFIG: &str = r#"# Endpointo configuration
+#
+# Read from ./endpointo.toml, ~/.config/endpointo/config.toml or --config.
+# ENDPOINTO_<SETTING> environment variables (e.g. ENDPOINTO_RATE_LIMIT)
+# override these settings, and command-line flags override both. Relative
+# paths are resolved against this file's directory.
+
+[scan]
+# Preset applied before the settings below (passive, standard, aggressive)
+# profile = "standard"
+
+# Requests per second
+# rate_limit = 10
+
+# Request timeout in seconds
+# timeout = 30
+
+# Concurrent requests
+# threads = 10
+
+# Follow HTTP redirects
+# follow_redirects = true
+
+# Skip paths robots.txt disallows
+# respect_robots = true
+
+# User-Agent header
+# user_agent = "Endpointo/0.1.0"
+
+# Extra headers and cookies sent to the targets; credentials are better
+# kept in ENDPOINTO_HEADERS (one header per line, or ; before the next
+# header's name) and
+# ENDPOINTO_COOKIES than in a shared file. Repeated header names are merged
+# headers = ["Authorization: Bearer <token>"]
+# cookies = "session=<id>; theme=dark"
+
+# Only keep endpoints matching this pattern
+# filter = "/api/"
+
+# Python plugin, native plugin library (.so, .dylib, .dll) or WASM module to load
+# plugin = "plugins/example.py"
+
+# Load every plugin in a directory too, and skip some by name
+# plugin_dir = "plugins"
+# disabled_plugins = ["noisy"]
+
+# Built-in plugins to run: scope-filter, secret-redactor,
+# static-asset-dropper, tagger
+# builtin_plugins = ["scope-filter", "secret-redactor"]
+
+# Give up on a plugin hook call after this many seconds, then skip the call,
+# drop the plugin or abort the scan
+# plugin_timeout = 30
+# on_plugin_timeout = "skip"
+
+# Keep at most this many endpoints a plugin generates from one asset
+# plugin_max_generated = 1000
+
+# When a plugin hook fails: log it and keep the endpoint unchanged ("warn"),
+# drop the endpoint ("skip-endpoint") or fail the scan ("fail")
+# plugin_errors = "warn"
+
+# With --format plugin, hand results only to this plugin's write_output
+# output_plugin = "ticketing"
+
+# More plugins, run in ascending priority (default 0)
+# [[scan.plugins]]
+# path = "plugins/scope.py"
+# priority = -10
+#
+# [[scan.plugins]]
+# path = "plugins/tagger.so"
+# enabled = false
+
+# Rhai script that filters endpoints, or defines filter(e) and transform(e)
+# script = 'endpoint.url.contains("/api/") && endpoint.method != "OPTIONS"'
+
+# YAML/TOML files with extra extraction patterns and tag rules
+# patterns = "patterns.yaml"
+# rules = "rules.yaml"
+
+# Drop low-signal matches (locale codes, npm package paths, ...)
+# aggressive_filter = false
+
+# Keep only endpoints at or above this severity (info, low, medium, high, critical)
+# min_severity = "low"
+
+# Keep only endpoints with one of these tags
+# tags = ["auth", "admin"]
+
+# Parse inputs larger than this many bytes in windows of parse_window bytes
+# max_parse_bytes = 33554432
+# parse_window = 4194304
+
+# Probe discovered endpoints, and ask them for their allowed methods
+# probe = false
+# probe_options = false
+
+# Follow same-origin links this many hops from the target, fetching at
+# most max_pages pages; 0 crawls only the target page
+# max_depth = 0
+# max_pages = 100
+
+# Per-target limits
+# max_duration = 600
+# max_endpoints = 5000
+# max_assets = 500
+# max_visited = 100000
+# visited_bloom = false
+
+# Extensions to parse when walking directories, and the largest file to read
+# extensions = ["js", "html"]
+# max_file_bytes = 10485760
+
+# Scope for crawling and results: hosts include their subdomains, "*.host"
+# means only subdomains, regexes match whole URLs, and exclusions win
+# include_hosts = ["example.com"]
+# exclude_hosts = ["legacy.example.com"]
+# include_regex = ["/api/"]
+# exclude_regex = ["/logout"]
+
+# Gentler settings for some hosts: the first entry whose host glob matches
+# a host applies. Any of rate_limit, timeout, headers and respect_robots can
+# be set; headers replace the scan's of the same name.
+# [[scan.hosts]]
+# host = "*.staging.example.com"
+# rate_limit = 2
+# timeout = 60
+# headers = ["X-Env: staging"]
+# respect_robots = false
+"#;
+
+/// Whether two paths name the same file
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Settings a configuration file's `[scan]` table and `ENDPOINTO_<NAME>`
+/// environment variables can set
+pub const SETTING_KEYS: &[&str] = &[
+    "profile",
+    "rate_limit",
+    "timeout",
+    "threads",
+    "follow_redirects",
+    "respect_robots",
+    "user_agent",
+    "headers",
+    "cookies",
+    "filter",
+    "plugin",
+    "plugin_dir",
+    "disabled_plugins",
+    "builtin_plugins",
+    "plugin_timeout",
+    "on_plugin_timeout",
+    "plugin_max_generated",
+    "plugin_errors",
+    "output_plugin",
+    "script",
+    "patterns",
+    "rules",
+    "aggressive_filter",
+    "min_severity",
+    "tags",
+    "max_parse_bytes",
+    "parse_window",
+    "probe",
+    "probe_options",
+    "max_duration",
+    "max_endpoints",
+    "max_assets",
+    "max_depth",
+    "max_pages",
+    "max_visited",
+    "visited_bloom",
+    "extensions",
+    "max_file_bytes",
+    "include_hosts",
+    "exclude_hosts",
+    "include_regex",
+    "exclude_regex",
+];
+
+/// Settings whose environment variables hold comma-separated lists
+const LIST_KEYS: &[&str] = &[
+    "tags",
+    "extensions",
+    "include_hosts",
+    "exclude_hosts",
+    "disabled_plugins",
+    "builtin_plugins",
+];
+
+/// List settings whose environment variables hold a single item, since
+/// regexes can contain commas
+const ITEM_KEYS: &[&str] = &["include_regex", "exclude_regex"];
+
+/// List settings whose environment variables hold one header per line or
+/// per `;` starting a new header, since header values can contain commas
+const LINE_KEYS: &[&str] = &["headers"];
+
+/// Split a header variable into its headers: one per line, and at each `;`
+/// followed by a `Name:`, so `;` inside a value like `text/html;q=0.9`
+/// stays put
+fn split_headers(raw: &str) -> Vec<&str> {
+    let starts_header = |rest: &str| {
+        rest.split_once(':').is_some_and(|(name, _)| {
+            let name = name.trim();
+            !name.is_empty()
+                && name
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+        })
+    };
+    let mut headers = Vec::new();
+    for line in raw.lines() {
+        let mut start = 0;
+        for (i, _) in line.match_indices(';') {
+            if starts_header(&line[i + 1..]) {
+                headers.push(&line[start..i]);
+                start = i + 1;
+            }
+        }
+        headers.push(&line[start..]);
+    }
+    headers
+        .into_iter()
+        .map(str::trim)
+        .filter(|header| !header.is_empty())
+        .collect()
+}
+
+/// Settings whose environment variables are taken as they are
+const STRING_KEYS: &[&str] = &[
+    "profile",
+    "user_agent",
+    "cookies",
+    "filter",
+    "plugin",
+    "plugin_dir",
+    "on_plugin_timeout",
+    "plugin_errors",
+    "output_plugin",
+    "script",
+    "patterns",
+    "rules",
+    "min_severity",
+];
+
+/// Configuration file to use when none is named
+///
+/// `endpointo.toml` in the current directory wins over the user's
+/// `config.toml` in `$XDG_CONFIG_HOME/endpointo` (or `~/.config/endpointo`).
+pub fn find_config_file() -> Option<PathBuf> {
+    let local = PathBuf::from("endpointo.toml");
+    if local.is_file() {
+        return Some(local);
+    }
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(".config"))
+        })?;
+    let user = config_dir.join("endpointo").join("config.toml");
+    user.is_file().then_some(user)
+}
+
+/// Scan settings from the configuration file at `path` (if any), overridden
+/// by `ENDPOINTO_<NAME>` environment variables
+///
+/// Command-line flags are applied after these, so they win over both.
+pub fn load_settings(path: Option<&Path>) -> Result<ScanSettings> {
+    let file = match path {
+        Some(path) => ConfigFile::load(path)?.scan,
+        None => ScanSettings::default(),
+    };
+    Ok(file.merge(ScanSettings::from_env()?))
+}
+
+/// A configuration file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    /// Settings for scans
+    pub scan: ScanSettings,
+}
+
+/// Scan settings from a configuration file; unset ones keep their defaults
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScanSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<ScanProfile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threads: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub follow_redirects: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub respect_robots: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hosts: Option<Vec<HostOverride>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cookies: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugins: Option<Vec<PluginSpec>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin_dir: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled_plugins: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub builtin_plugins: Option<Vec<Builtin>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin_timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_plugin_timeout: Option<TimeoutPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin_max_generated: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin_errors: Option<PluginErrorPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_plugin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub patterns: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rules: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggressive_filter: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_severity: Option<Severity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_parse_bytes: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_window: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub probe: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub probe_options: Option<bool>,
+    /// Seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_duration: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_endpoints: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_assets: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_pages: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_visited: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visited_bloom: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_file_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_hosts: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_hosts: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_regex: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_regex: Option<Vec<String>>,
+}
+
+impl ConfigFile {
+    /// Read a TOML configuration file
+    ///
+    /// Relative plugin, plugin directory, pattern and rule paths are
+    /// resolved against the
+    /// file's directory. Syntax errors and unknown keys name the file and
+    /// the line they're on; malformed headers and cookies name the file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| Error::ValidationError(format!("{}: {}", path.display(), e)))?;
+        let mut file: ConfigFile = toml::from_str(&content)
+            .map_err(|e| Error::ValidationError(format!("{}: {}", path.display(), e)))?;
+        file.scan = file
+            .scan
+            .checked()
+            .map_err(|e| Error::ValidationError(format!("{}: scan.{}", path.display(), e)))?;
+
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        let scan = &mut file.scan;
+        let listed = scan.plugins.iter_mut().flatten().map(|spec| &mut spec.path);
+        for p in [
+            &mut scan.plugin,
+            &mut scan.plugin_dir,
+            &mut scan.patterns,
+            &mut scan.rules,
+        ]
+        .into_iter()
+        .flatten()
+        .chain(listed)
+        {
+            if p.is_relative() {
+                *p = dir.join(&*p);
+            }
+        }
+        Ok(file)
+    }
+
+    /// Problems that would make a scan fail or misbehave, empty if none
+    ///
+    /// Referenced pattern and rule files are loaded to check them too.
+    pub fn problems(&self) -> Vec<String> {
+        let scan = &self.scan;
+        let mut problems = Vec::new();
+        let positive = [
+            ("rate_limit", scan.rate_limit.map(|v| v as u64)),
+            ("timeout", scan.timeout),
+            ("threads", scan.threads.map(|v| v as u64)),
+            ("parse_window", scan.parse_window.map(|v| v as u64)),
+            ("max_pages", scan.max_pages.map(|v| v as u64)),
+            ("plugin_timeout", scan.plugin_timeout),
+        ];
+        for (key, value) in positive {
+            if value == Some(0) {
+                problems.push(format!("scan.{} must be greater than 0", key));
+            }
+        }
+        let max_parse_bytes = scan
+            .max_parse_bytes
+            .unwrap_or(ScanConfig::default().max_parse_bytes);
+        if scan.parse_window.is_some_and(|w| w > max_parse_bytes) {
+            problems.push("scan.parse_window must not exceed scan.max_parse_bytes".to_string());
+        }
+        // The aggressive profile turns probing on by itself
+        if scan.probe_options == Some(true)
+            && scan.probe != Some(true)
+            && scan.profile != Some(ScanProfile::Aggressive)
+        {
+            problems.push("scan.probe_options needs scan.probe = true".to_string());
+        }
+        if scan.visited_bloom == Some(true) && scan.max_visited.is_none() {
+            problems.push("scan.visited_bloom needs scan.max_visited".to_string());
+        }
+        if let Some(plugin) = &scan.plugin {
+            if !plugin.is_file() {
+                problems.push(format!("scan.plugin: no such file {}", plugin.display()));
+            }
+        }
+        for spec in scan.plugins.iter().flatten() {
+            if !spec.path.is_file() {
+                problems.push(format!(
+                    "scan.plugins: no such file {}",
+                    spec.path.display()
+                ));
+            }
+        }
+        if let Some(dir) = &scan.plugin_dir {
+            if !dir.is_dir() {
+                problems.push(format!(
+                    "scan.plugin_dir: no such directory {}",
+                    dir.display()
+                ));
+            }
+        }
+        if let Some(script) = &scan.script {
+            if let Err(e) = Script::parse(script) {
+                problems.push(format!("scan.script: {}", e));
+            }
+        }
+        if let Some(patterns) = &scan.patterns {
+            if let Err(e) = load_patterns(patterns) {
+                problems.push(format!("scan.patterns ({}): {}", patterns.display(), e));
+            }
+        }
+        if let Some(rules) = &scan.rules {
+            if let Err(e) = load_rules(rules) {
+                problems.push(format!("scan.rules ({}): {}", rules.display(), e));
+            }
+        }
+        for rule in scan.hosts.iter().flatten() {
+            if rule.host.trim().is_empty() {
+                problems.push("scan.hosts: an entry has no host".to_string());
+            }
+            if rule.rate_limit == Some(0) || rule.timeout == Some(0) {
+                problems.push(format!(
+                    "scan.hosts ({}): rate_limit and timeout must be greater than 0",
+                    rule.host
+                ));
+            }
+        }
+        for (key, patterns) in [
+            ("include_regex", &scan.include_regex),
+            ("exclude_regex", &scan.exclude_regex),
+        ] {
+            for pattern in patterns.iter().flatten() {
+                if let Err(e) = regex::Regex::new(pattern) {
+                    problems.push(format!("scan.{}: invalid regex '{}': {}", key, pattern, e));
+                }
+            }
+        }
+        problems
+    }
+
+    /// The file as TOML
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).map_err(|e| Error::Other(e.to_string()))
+    }
+}
+
+impl ScanSettings {
+    /// Settings from `ENDPOINTO_<NAME>` environment variables
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(std::env::vars())
+    }
+
+    /// Settings from the `ENDPOINTO_<NAME>` variables among `vars`
+    ///
+    /// Numbers and booleans are read as TOML, paths and names as they are,
+    /// `tags`, `extensions` and the scope hosts as comma-separated lists,
+    /// `headers` as one header per line or per `;` before a header name, and
+    /// the scope regexes as one item each.
+    pub fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> Result<Self> {
+        let mut table = toml::Table::new();
+        for (name, raw) in vars {
+            let Some(key) = name
+                .strip_prefix("ENDPOINTO_")
+                .map(str::to_ascii_lowercase)
+                .filter(|key| SETTING_KEYS.contains(&key.as_str()))
+            else {
+                continue;
+            };
+            let value = if LIST_KEYS.contains(&key.as_str()) {
+                toml::Value::Array(
+                    raw.split(',')
+                        .map(str::trim)
+                        .filter(|item| !item.is_empty())
+                        .map(|item| toml::Value::String(item.to_string()))
+                        .collect(),
+                )
+            } else if LINE_KEYS.contains(&key.as_str()) {
+                toml::Value::Array(
+                    split_headers(&raw)
+                        .into_iter()
+                        .map(|item| toml::Value::String(item.to_string()))
+                        .collect(),
+                )
+            } else if ITEM_KEYS.contains(&key.as_str()) {
+                toml::Value::Array(vec![toml::Value::String(raw)])
+            } else if STRING_KEYS.contains(&key.as_str()) {
+                toml::Value::String(raw)
+            } else {
+                toml::from_str::<toml::Table>(&format!("value = {}", raw))
+                    .ok()
+                    .and_then(|mut parsed| parsed.remove("value"))
+                    .unwrap_or(toml::Value::String(raw))
+            };
+            table.insert(key, value);
+        }
+        let settings: Self = toml::Value::Table(table)
+            .try_into()
+            .map_err(|e| Error::ValidationError(format!("ENDPOINTO_* variables: {}", e)))?;
+        settings
+            .checked()
+            .map_err(|e| Error::ValidationError(format!("ENDPOINTO_* variables: {}", e)))
+    }
+
+    /// These settings with their headers and cookies checked, and the values
+    /// of repeated header names merged
+    fn checked(mut self) -> std::result::Result<Self, String> {
+        let detail = |key: &str, e: Error| match e {
+            Error::ValidationError(message) => format!("{}: {}", key, message),
+            e => format!("{}: {}", key, e),
+        };
+        if let Some(headers) = &self.headers {
+            self.headers = Some(merge_headers(headers).map_err(|e| detail("headers", e))?);
+        }
+        for rule in self.hosts.iter_mut().flatten() {
+            rule.headers = merge_headers(&rule.headers)
+                .map_err(|e| detail(&format!("hosts ({})", rule.host), e))?;
+        }
+        if let Some(cookies) = &self.cookies {
+            parse_cookies(cookies).map_err(|e| detail("cookies", e))?;
+        }
+        Ok(self)
+    }
+
+    /// These settings, with the ones `other` sets taking precedence
+    pub fn merge(self, other: ScanSettings) -> ScanSettings {
+        ScanSettings {
+            profile: other.profile.or(self.profile),
+            rate_limit: other.rate_limit.or(self.rate_limit),
+            timeout: other.timeout.or(self.timeout),
+            threads: other.threads.or(self.threads),
+            follow_redirects: other.follow_redirects.or(self.follow_redirects),
+            respect_robots: other.respect_robots.or(self.respect_robots),
+            hosts: other.hosts.or(self.hosts),
+            user_agent: other.user_agent.or(self.user_agent),
+            headers: other.headers.or(self.headers),
+            cookies: other.cookies.or(self.cookies),
+            filter: other.filter.or(self.filter),
+            plugin: other.plugin.or(self.plugin),
+            plugins: other.plugins.or(self.plugins),
+            plugin_dir: other.plugin_dir.or(self.plugin_dir),
+            disabled_plugins: other.disabled_plugins.or(self.disabled_plugins),
+            builtin_plugins: other.builtin_plugins.or(self.builtin_plugins),
+            plugin_timeout: other.plugin_timeout.or(self.plugin_timeout),
+            on_plugin_timeout: other.on_plugin_timeout.or(self.on_plugin_timeout),
+            plugin_max_generated: other.plugin_max_generated.or(self.plugin_max_generated),
+            plugin_errors: other.plugin_errors.or(self.plugin_errors),
+            output_plugin: other.output_plugin.or(self.output_plugin),
+            script: other.script.or(self.script),
+            patterns: other.patterns.or(self.patterns),
+            rules: other.rules.or(self.rules),
+            aggressive_filter: other.aggressive_filter.or(self.aggressive_filter),
+            min_severity: other.min_severity.or(self.min_severity),
+            tags: other.tags.or(self.tags),
+            max_parse_bytes: other.max_parse_bytes.or(self.max_parse_bytes),
+            parse_window: other.parse_window.or(self.parse_window),
+            probe: other.probe.or(self.probe),
+            probe_options: other.probe_options.or(self.probe_options),
+            max_duration: other.max_duration.or(self.max_duration),
+            max_endpoints: other.max_endpoints.or(self.max_endpoints),
+            max_assets: other.max_assets.or(self.max_assets),
+            max_depth: other.max_depth.or(self.max_depth),
+            max_pages: other.max_pages.or(self.max_pages),
+            max_visited: other.max_visited.or(self.max_visited),
+            visited_bloom: other.visited_bloom.or(self.visited_bloom),
+            extensions: other.extensions.or(self.extensions),
+            max_file_bytes: other.max_file_bytes.or(self.max_file_bytes),
+            include_hosts: other.include_hosts.or(self.include_hosts),
+            exclude_hosts: other.exclude_hosts.or(self.exclude_hosts),
+            include_regex: other.include_regex.or(self.include_regex),
+            exclude_regex: other.exclude_regex.or(self.exclude_regex),
+        }
+    }
+
+    /// Use `profile` instead of the configured one, if given
+    pub fn with_profile(mut self, profile: Option<ScanProfile>) -> Self {
+        if profile.is_some() {
+            self.profile = profile;
+        }
         self
     }
+
+    /// Apply these settings to `config`, the profile first
+    pub fn apply(&self, mut config: ScanConfig) -> ScanConfig {
+        if let Some(profile) = self.profile {
+            config = config.with_profile(profile);
+        }
+        config = config
+            .with_rate_limit(self.rate_limit)
+            .with_timeout(self.timeout)
+            .with_max_concurrent(self.threads)
+            .with_min_severity(self.min_severity)
+            .with_max_parse_bytes(self.max_parse_bytes)
+            .with_parse_window(self.parse_window)
+            .with_max_duration(self.max_duration.map(Duration::from_secs))
+            .with_max_endpoints(self.max_endpoints)
+            .with_max_assets(self.max_assets)
+            .with_max_depth(self.max_depth)
+            .with_max_pages(self.max_pages)
+            .with_max_visited(self.max_visited)
+            .with_max_file_bytes(self.max_file_bytes)
+            .with_plugin_timeout(self.plugin_timeout.map(Duration::from_secs))
+            .with_on_plugin_timeout(self.on_plugin_timeout)
+            .with_plugin_max_generated(self.plugin_max_generated)
+            .with_plugin_errors(self.plugin_errors)
+            .with_output_plugin(self.output_plugin.clone());
+        if let Some(follow) = self.follow_redirects {
+            config = config.with_redirects(follow);
+        }
+        if let Some(respect) = self.respect_robots {
+            config = config.with_robots(respect);
+        }
+        if let Some(overrides) = &self.hosts {
+            config = config.with_host_overrides(overrides.clone());
+        }
+        if let Some(ua) = &self.user_agent {
+            config = config.with_user_agent(ua);
+        }
+        if let Some(headers) = &self.headers {
+            config = config.with_headers(headers.clone());
+        }
+        config = config.with_cookies(self.cookies.clone());
+        if let Some(filter) = &self.filter {
+            config = config.with_filter(filter.clone());
+        }
+        if let Some(plugin) = &self.plugin {
+            config = config.with_plugin(plugin.clone());
+        }
+        if let Some(specs) = &self.plugins {
+            config = config.with_plugin_specs(specs.clone());
+        }
+        config = config.with_plugin_dir(self.plugin_dir.clone());
+        if let Some(names) = &self.disabled_plugins {
+            config = config.with_disabled_plugins(names.clone());
+        }
+        if let Some(builtins) = &self.builtin_plugins {
+            config = config.with_builtin_plugins(builtins.clone());
+        }
+        config = config.with_script(self.script.clone());
+        if let Some(patterns) = &self.patterns {
+            config = config.with_patterns(patterns.clone());
+        }
+        if let Some(rules) = &self.rules {
+            config = config.with_rules(rules.clone());
+        }
+        if let Some(aggressive) = self.aggressive_filter {
+            config = config.with_aggressive_filter(aggressive);
+        }
+        if let Some(tags) = &self.tags {
+            config = config.with_only_tags(tags.clone());
+        }
+        if let Some(probe) = self.probe {
+            config = config.with_probe(probe);
+        }
+        if let Some(options) = self.probe_options {
+            config = config.with_probe_options(options);
+        }
+        if let Some(bloom) = self.visited_bloom {
+            config = config.with_visited_bloom(bloom);
+        }
+        if let Some(extensions) = &self.extensions {
+            config = config.with_file_extensions(extensions.clone());
+        }
+        if let Some(hosts) = &self.include_hosts {
+            config = config.with_include_hosts(hosts.clone());
+        }
+        if let Some(hosts) = &self.exclude_hosts {
+            config = config.with_exclude_hosts(hosts.clone());
+        }
+        if let Some(patterns) = &self.include_regex {
+            config = config.with_include_regex(patterns.clone());
+        }
+        if let Some(patterns) = &self.exclude_regex {
+            config = config.with_exclude_regex(patterns.clone());
+        }
+        config
+    }
+}
+
+/// `Name: Value` headers with their values hidden
+fn redact_headers(headers: &[String]) -> Vec<String> {
+    headers
+        .iter()
+        .map(|header| match header.split_once(':') {
+            Some((name, _)) => format!("{}: ***", name.trim()),
+            None => "***".to_string(),
+        })
+        .collect()
+}
+
+/// A `name=value; other=value` cookie string with its values hidden
+fn redact_cookies(cookies: &str) -> String {
+    cookies
+        .split(';')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) => format!("{}=***", name.trim()),
+            None => "***".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl From<&ScanConfig> for ScanSettings {
+    /// Every setting of `config`, for showing the effective configuration,
+    /// with header and cookie values hidden
+    fn from(config: &ScanConfig) -> Self {
+        Self {
+            profile: None,
+            rate_limit: Some(config.rate_limit),
+            timeout: Some(config.timeout_seconds),
+            threads: Some(config.max_concurrent),
+            follow_redirects: Some(config.follow_redirects),
+            respect_robots: Some(config.respect_robots_txt),
+            hosts: Some(
+                config
+                    .host_overrides
+                    .iter()
+                    .map(|rule| HostOverride {
+                        headers: redact_headers(&rule.headers),
+                        ..rule.clone()
+                    })
+                    .collect(),
+            ),
+            user_agent: config.user_agent.clone(),
+            headers: Some(redact_headers(&config.headers)),
+            cookies: config.cookies.as_deref().map(redact_cookies),
+            filter: config.filter_pattern.clone(),
+            plugin: None,
+            plugins: Some(config.plugins.clone()),
+            plugin_dir: config.plugin_dir.clone(),
+            disabled_plugins: Some(config.disabled_plugins.clone()),
+            builtin_plugins: Some(config.builtin_plugins.clone()),
+            plugin_timeout: config.plugin_timeout.map(|d| d.as_secs()),
+            on_plugin_timeout: Some(config.on_plugin_timeout),
+            plugin_max_generated: config.plugin_max_generated,
+            plugin_errors: Some(config.plugin_errors),
+            output_plugin: config.output_plugin.clone(),
+            script: config.script.clone(),
+            patterns: config.patterns_path.clone(),
+            rules: config.rules_path.clone(),
+            aggressive_filter: Some(config.aggressive_filter),
+            min_severity: config.min_severity,
+            tags: Some(config.only_tags.clone()),
+            max_parse_bytes: Some(config.max_parse_bytes),
+            parse_window: Some(config.parse_window_bytes),
+            probe: Some(config.probe),
+            probe_options: Some(config.probe_options),
+            max_duration: config.max_duration.map(|d| d.as_secs()),
+            max_endpoints: config.max_endpoints,
+            max_assets: config.max_assets,
+            max_depth: Some(config.max_depth),
+            max_pages: Some(config.max_pages),
+            max_visited: config.max_visited,
+            visited_bloom: Some(config.visited_bloom),
+            extensions: Some(config.file_extensions.clone()),
+            max_file_bytes: config.max_file_bytes,
+            include_hosts: Some(config.include_hosts.clone()),
+            exclude_hosts: Some(config.exclude_hosts.clone()),
+            include_regex: Some(config.include_regex.clone()),
+            exclude_regex: Some(config.exclude_regex.clone()),
+        }
+    }
 }
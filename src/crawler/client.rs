@@ -1,124 +1,631 @@
-use crate::config::ScanConfig;
+use crate::config::{HostOverride, ScanConfig};
 use crate::error::{Error, Result};
+use crate::parser::detect::SNIFF_BYTES;
+use crate::scope::Scope;
+use crate::types::ProbeResult;
+use crate::util::lock;
 use governor::{Quota, RateLimiter as GovernorLimiter};
 use nonzero_ext::nonzero;
 use reqwest::{header, Client};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
 use tracing::{debug, warn};
 use url::Url;
 
+/// A successful response with the details parsers need
+#[derive(Debug, Clone)]
+pub struct FetchResponse {
+    /// URL after redirects
+    pub final_url: String,
+
+    /// HTTP status code
+    pub status: u16,
+
+    /// `Content-Type` header, if any
+    pub content_type: Option<String>,
+
+    /// Response headers with lowercase names
+    pub headers: HashMap<String, String>,
+
+    /// Raw response body, or only its first [`SNIFF_BYTES`] when the rest
+    /// was spooled to disk
+    pub body: Vec<u8>,
+
+    /// Full body length in bytes
+    pub size: u64,
+
+    /// Unnamed temp file holding the whole body, rewound to its start, when
+    /// it was too large to keep in memory
+    pub spooled: Option<Arc<std::fs::File>>,
+}
+
+impl FetchResponse {
+    /// Body decoded as UTF-8, replacing invalid sequences
+    ///
+    /// Only the head of a spooled body is in memory to decode.
+    pub fn text(&self) -> String {
+        let (content, _, had_errors) = encoding_rs::UTF_8.decode(&self.body);
+        if had_errors {
+            warn!(
+                "Encoding errors detected in response from {}",
+                self.final_url
+            );
+        }
+        content.into_owned()
+    }
+}
+
+/// Parse a curl-style `Name: Value` header
+pub fn parse_header(header: &str) -> Result<(header::HeaderName, header::HeaderValue)> {
+    let invalid = || {
+        Error::ValidationError(format!(
+            "invalid header '{}' (expected Name: Value)",
+            header
+        ))
+    };
+    let (name, value) = header.split_once(':').ok_or_else(invalid)?;
+    Ok((
+        header::HeaderName::from_bytes(name.trim().as_bytes()).map_err(|_| invalid())?,
+        header::HeaderValue::from_str(value.trim()).map_err(|_| invalid())?,
+    ))
+}
+
+/// Parse curl-style `Name: Value` headers into one `Name: Value` line per
+/// name, in order, joining the values of a repeated name with `, ` (`; ` for
+/// `Cookie`)
+pub fn merge_headers(headers: &[String]) -> Result<Vec<String>> {
+    let mut merged: Vec<(header::HeaderName, String, String)> = Vec::new();
+    for raw in headers {
+        let (name, value) = parse_header(raw)?;
+        let value = value.to_str().unwrap_or_default().to_string();
+        match merged.iter_mut().find(|(seen, _, _)| *seen == name) {
+            Some((_, _, values)) => {
+                let separator = if name == header::COOKIE { "; " } else { ", " };
+                values.push_str(separator);
+                values.push_str(&value);
+            }
+            None => {
+                let spelled = raw.split_once(':').map_or("", |(n, _)| n.trim());
+                merged.push((name, spelled.to_string(), value));
+            }
+        }
+    }
+    Ok(merged
+        .into_iter()
+        .map(|(_, name, value)| format!("{}: {}", name, value))
+        .collect())
+}
+
+/// Parse a `name=value; other=value` cookie string into a `Cookie` value
+pub fn parse_cookies(cookies: &str) -> Result<header::HeaderValue> {
+    let invalid = || {
+        Error::ValidationError(format!(
+            "invalid cookies '{}' (expected name=value; name=value)",
+            cookies
+        ))
+    };
+    let pairs: Vec<&str> = cookies
+        .split(';')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .collect();
+    if pairs.is_empty()
+        || pairs
+            .iter()
+            .any(|pair| !matches!(pair.split_once('='), Some((name, _)) if !name.trim().is_empty()))
+    {
+        return Err(invalid());
+    }
+    header::HeaderValue::from_str(&pairs.join("; ")).map_err(|_| invalid())
+}
+
+/// Build the proxy every request goes through, with optional `user:password`
+/// credentials
+pub fn parse_proxy(proxy: &str, auth: Option<&str>) -> Result<reqwest::Proxy> {
+    let url = Url::parse(proxy)
+        .ok()
+        .filter(|url| {
+            matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h")
+                && url.host_str().is_some()
+        })
+        .ok_or_else(|| {
+            Error::ValidationError(format!(
+                "invalid proxy '{}' (expected an http, https, socks5 or socks5h URL)",
+                proxy
+            ))
+        })?;
+    let mut proxy = reqwest::Proxy::all(url)?;
+    if let Some(auth) = auth {
+        let (user, password) = auth.split_once(':').ok_or_else(|| {
+            Error::ValidationError("invalid proxy credentials (expected user:password)".to_string())
+        })?;
+        proxy = proxy.basic_auth(user, password);
+    }
+    Ok(proxy)
+}
+
+/// Default headers for every request: the User-Agent
+fn default_headers(config: &ScanConfig) -> Result<header::HeaderMap> {
+    let mut headers = header::HeaderMap::new();
+
+    if let Some(ua) = &config.user_agent {
+        headers.insert(
+            header::USER_AGENT,
+            header::HeaderValue::from_str(ua)
+                .map_err(|e| Error::ValidationError(format!("Invalid user agent: {}", e)))?,
+        );
+    }
+    Ok(headers)
+}
+
+/// Headers for requests to the target: extra headers, and cookies merged
+/// into any `Cookie` header given
+fn credential_headers(config: &ScanConfig) -> Result<header::HeaderMap> {
+    let mut headers = header::HeaderMap::new();
+
+    for raw in &config.headers {
+        let (name, value) = parse_header(raw)?;
+        headers.append(name, value);
+    }
+    if let Some(cookies) = &config.cookies {
+        let cookies = parse_cookies(cookies)?;
+        let value = match headers.remove(header::COOKIE) {
+            Some(existing) => header::HeaderValue::from_bytes(
+                &[existing.as_bytes(), b"; ", cookies.as_bytes()].concat(),
+            )
+            .map_err(|e| Error::ValidationError(format!("Invalid cookies: {}", e)))?,
+            None => cookies,
+        };
+        headers.insert(header::COOKIE, value);
+    }
+    Ok(headers)
+}
+
+/// Most redirects a fetch follows
+const MAX_REDIRECTS: usize = 10;
+
+/// Follow redirects on the same origin, stopping at a hop to another one so
+/// [`HttpClient::fetch`] can send it with that origin's headers
+fn same_origin_redirects() -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(|attempt| {
+        let same_origin = attempt
+            .previous()
+            .last()
+            .is_some_and(|from| from.origin() == attempt.url().origin());
+        if attempt.previous().len() > MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if same_origin {
+            attempt.follow()
+        } else {
+            attempt.stop()
+        }
+    })
+}
+
+/// Origin of a URL as `scheme://host:port`, if it has one
+fn origin_of(url: &str) -> Option<String> {
+    let origin = Url::parse(url).ok()?.origin();
+    origin.is_tuple().then(|| origin.ascii_serialization())
+}
+
+type Limiter = GovernorLimiter<
+    governor::state::direct::NotKeyed,
+    governor::state::InMemoryState,
+    governor::clock::DefaultClock,
+>;
+
+/// A requests-per-second limiter; 0 falls back to 10
+fn limiter(rate: u32) -> Limiter {
+    let rate = std::num::NonZeroU32::new(rate).unwrap_or(nonzero!(10u32));
+    GovernorLimiter::direct(Quota::per_second(rate))
+}
+
+/// A [`HostOverride`] ready to apply to requests
+struct HostSettings {
+    rule: HostOverride,
+    headers: header::HeaderMap,
+    timeout: Option<Duration>,
+    /// Shared by every host the rule matches; `None` uses the scan's
+    rate_limiter: Option<Limiter>,
+}
+
+impl HostSettings {
+    fn new(rule: &HostOverride) -> Result<Self> {
+        let mut headers = header::HeaderMap::new();
+        for raw in &rule.headers {
+            let (name, value) = parse_header(raw)?;
+            headers.append(name, value);
+        }
+        Ok(Self {
+            rule: rule.clone(),
+            headers,
+            timeout: rule.timeout.map(Duration::from_secs),
+            rate_limiter: rule.rate_limit.map(limiter),
+        })
+    }
+}
+
 /// HTTP client with rate limiting and retry logic
 pub struct HttpClient {
     client: Client,
-    rate_limiter: GovernorLimiter<
-        governor::state::direct::NotKeyed,
-        governor::state::InMemoryState,
-        governor::clock::DefaultClock,
-    >,
+    probe_client: Client,
+    rate_limiter: Limiter,
+    /// Settings for particular hosts, in the order they're matched
+    hosts: Vec<HostSettings>,
+    /// Extra headers and cookies, sent only to targets
+    credentials: header::HeaderMap,
+    /// Origins of the targets
+    targets: Mutex<HashSet<String>>,
+    /// Hosts given with `include_hosts`, which count as targets too
+    target_hosts: Option<Scope>,
+    follow_redirects: bool,
+    requests_made: AtomicUsize,
 }
 
 impl HttpClient {
     /// Create a new HTTP client
     pub fn new(config: &ScanConfig) -> Result<Self> {
-        let mut headers = header::HeaderMap::new();
-
-        if let Some(ua) = &config.user_agent {
-            headers.insert(
-                header::USER_AGENT,
-                header::HeaderValue::from_str(ua)
-                    .map_err(|e| Error::ValidationError(format!("Invalid user agent: {}", e)))?,
-            );
-        }
+        let headers = default_headers(config)?;
+        let proxy = config
+            .proxy
+            .as_deref()
+            .map(|proxy| parse_proxy(proxy, config.proxy_auth.as_deref()))
+            .transpose()?;
+        let builder = || {
+            let builder = Client::builder()
+                .timeout(Duration::from_secs(config.timeout_seconds))
+                .default_headers(headers.clone())
+                .danger_accept_invalid_certs(false); // Enforce TLS verification
+            match &proxy {
+                Some(proxy) => builder.proxy(proxy.clone()),
+                None => builder,
+            }
+        };
 
-        let client = Client::builder()
-            .timeout(Duration::from_secs(config.timeout_seconds))
+        let client = builder()
             .redirect(if config.follow_redirects {
-                reqwest::redirect::Policy::limited(10)
+                same_origin_redirects()
             } else {
                 reqwest::redirect::Policy::none()
             })
-            .default_headers(headers)
-            .danger_accept_invalid_certs(false) // Enforce TLS verification
             .build()?;
 
-        // Configure rate limiter
-        let rate = std::num::NonZeroU32::new(config.rate_limit).unwrap_or(nonzero!(10u32));
-        let quota = Quota::per_second(rate);
-        let rate_limiter = GovernorLimiter::direct(quota);
+        // Probes report redirects instead of following them
+        let probe_client = builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        let hosts = config
+            .host_overrides
+            .iter()
+            .map(HostSettings::new)
+            .collect::<Result<_>>()?;
+        let target_hosts = if config.include_hosts.is_empty() {
+            None
+        } else {
+            Some(Scope::new(&config.include_hosts, &[], &[], &[])?)
+        };
 
         Ok(Self {
             client,
-            rate_limiter,
+            probe_client,
+            rate_limiter: limiter(config.rate_limit),
+            hosts,
+            credentials: credential_headers(config)?,
+            targets: Mutex::new(origin_of(&config.target_url).into_iter().collect()),
+            target_hosts,
+            follow_redirects: config.follow_redirects,
+            requests_made: AtomicUsize::new(0),
         })
     }
 
+    /// Count a URL's origin as a target, so requests to it carry the extra
+    /// headers and cookies
+    pub fn add_target(&self, url: &str) {
+        if let Some(origin) = origin_of(url) {
+            lock(&self.targets).insert(origin);
+        }
+    }
+
+    /// Whether a URL is on a target's origin or an included host
+    fn is_target(&self, url: &str) -> bool {
+        origin_of(url).is_some_and(|origin| lock(&self.targets).contains(&origin))
+            || self
+                .target_hosts
+                .as_ref()
+                .is_some_and(|hosts| hosts.allows(url))
+    }
+
+    /// The settings for a URL's host, if an override matches it
+    fn host_settings(&self, url: &str) -> Option<&HostSettings> {
+        let url = Url::parse(url).ok()?;
+        let host = url.host_str()?;
+        self.hosts
+            .iter()
+            .find(|settings| settings.rule.matches(host))
+    }
+
+    /// Wait for the rate limiter of a URL's host, then build a request to
+    /// it with the host's settings
+    async fn request(
+        &self,
+        client: &Client,
+        method: reqwest::Method,
+        url: &str,
+    ) -> reqwest::RequestBuilder {
+        let settings = self.host_settings(url);
+        match settings.and_then(|s| s.rate_limiter.as_ref()) {
+            Some(rate_limiter) => rate_limiter.until_ready().await,
+            None => self.rate_limiter.until_ready().await,
+        }
+        self.requests_made.fetch_add(1, Ordering::Relaxed);
+
+        let mut request = client.request(method, url);
+        if !self.credentials.is_empty() && self.is_target(url) {
+            request = request.headers(self.credentials.clone());
+        }
+        if let Some(settings) = settings {
+            // Host headers replace the client's defaults and the extra
+            // headers of the same name
+            request = request.headers(settings.headers.clone());
+            if let Some(timeout) = settings.timeout {
+                request = request.timeout(timeout);
+            }
+        }
+        request
+    }
+
+    /// Number of HTTP requests sent by this client
+    pub fn requests_made(&self) -> usize {
+        self.requests_made.load(Ordering::Relaxed)
+    }
+
     /// Perform GET request with rate limiting
     pub async fn get(&self, url: &str) -> Result<String> {
-        // Wait for rate limiter
-        self.rate_limiter.until_ready().await;
+        Ok(self.fetch(url).await?.text())
+    }
 
-        debug!("Making GET request to {}", url);
+    /// Perform GET request with rate limiting, keeping response metadata
+    ///
+    /// Redirects to another origin are sent as new requests, so they carry
+    /// the extra headers and cookies only if that origin is a target too.
+    pub async fn fetch(&self, url: &str) -> Result<FetchResponse> {
+        self.fetch_spooled(url, usize::MAX).await
+    }
 
-        let response = self.client.get(url).send().await.map_err(|e| {
-            if e.is_timeout() {
-                Error::TimeoutError
-            } else if e.is_connect() {
-                Error::TlsError(format!("Connection error: {}", e))
-            } else {
-                Error::HttpError(e)
+    /// Like [`fetch`](Self::fetch), but once the body grows past
+    /// `max_in_memory` bytes it's written to an unnamed temp file as it
+    /// arrives instead of being held in memory
+    pub async fn fetch_spooled(&self, url: &str, max_in_memory: usize) -> Result<FetchResponse> {
+        let mut next = url.to_string();
+        let mut hops = 0;
+        let response = loop {
+            let request = self
+                .request(&self.client, reqwest::Method::GET, &next)
+                .await;
+
+            debug!("Making GET request to {}", next);
+            let response = request.send().await.map_err(|e| {
+                if e.is_timeout() {
+                    Error::TimeoutError
+                } else if e.is_connect() {
+                    Error::TlsError(format!("Connection error: {}", e))
+                } else {
+                    Error::HttpError(e)
+                }
+            })?;
+            match self.cross_origin_redirect(&response) {
+                Some(location) if hops < MAX_REDIRECTS => {
+                    debug!("Redirected from {} to {}", response.url(), location);
+                    hops += 1;
+                    next = location;
+                }
+                _ => break response,
             }
-        })?;
+        };
 
         // Check status code
         if !response.status().is_success() {
             warn!("HTTP {} for {}", response.status(), url);
-            return Err(Error::HttpError(reqwest::Error::from(
-                response.error_for_status().unwrap_err(),
-            )));
+            return Err(Error::HttpError(response.error_for_status().unwrap_err()));
         }
 
-        // Read response body with encoding detection
-        let bytes = response.bytes().await?;
-        let (content, _, had_errors) = encoding_rs::UTF_8.decode(&bytes);
+        let final_url = response.url().to_string();
+        let status = response.status().as_u16();
+        let headers: HashMap<String, String> = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|v| (name.as_str().to_string(), v.to_string()))
+            })
+            .collect();
+        let content_type = headers.get("content-type").cloned();
 
-        if had_errors {
-            warn!("Encoding errors detected in response from {}", url);
+        let mut response = response;
+        let mut body = Vec::new();
+        let mut size = 0;
+        let mut spool: Option<tokio::fs::File> = None;
+        while let Some(chunk) = response.chunk().await? {
+            size += chunk.len() as u64;
+            if let Some(file) = spool.as_mut() {
+                file.write_all(&chunk).await?;
+            } else if body.len() + chunk.len() > max_in_memory {
+                debug!("Spooling the body of {} to disk", final_url);
+                let mut file = tokio::fs::File::from_std(tempfile::tempfile()?);
+                file.write_all(&body).await?;
+                file.write_all(&chunk).await?;
+                // Keep just enough of the head to tell what the body is
+                let head = SNIFF_BYTES.saturating_sub(body.len()).min(chunk.len());
+                body.extend_from_slice(&chunk[..head]);
+                body.truncate(SNIFF_BYTES);
+                body.shrink_to_fit();
+                spool = Some(file);
+            } else {
+                body.extend_from_slice(&chunk);
+            }
         }
+        let spooled = match spool {
+            Some(mut file) => {
+                file.flush().await?;
+                file.rewind().await?;
+                Some(Arc::new(file.into_std().await))
+            }
+            None => None,
+        };
 
-        Ok(content.into_owned())
+        Ok(FetchResponse {
+            final_url,
+            status,
+            content_type,
+            headers,
+            body,
+            size,
+            spooled,
+        })
     }
 
-    /// Check robots.txt for URL
-    pub async fn check_robots_txt(&self, url: &Url) -> Result<bool> {
-        let robots_url = format!(
-            "{}://{}/robots.txt",
-            url.scheme(),
-            url.host_str().unwrap_or("")
-        );
+    /// Where a response redirects to, if the client is following redirects
+    /// and stopped at it because it leads to another origin
+    fn cross_origin_redirect(&self, response: &reqwest::Response) -> Option<String> {
+        if !self.follow_redirects || !response.status().is_redirection() {
+            return None;
+        }
+        let location = response.headers().get(header::LOCATION)?.to_str().ok()?;
+        let location = response.url().join(location).ok()?;
+        (location.origin() != response.url().origin()).then(|| location.to_string())
+    }
 
-        debug!("Checking robots.txt at {}", robots_url);
+    /// Probe a URL with HEAD, falling back to GET when HEAD isn't allowed
+    ///
+    /// Any status is a result; only transport failures are errors. Redirects
+    /// are not followed.
+    pub async fn probe(&self, url: &str) -> Result<ProbeResult> {
+        let mut response = self.send_probe(reqwest::Method::HEAD, url).await?;
+        if matches!(response.status().as_u16(), 405 | 501) {
+            response = self.send_probe(reqwest::Method::GET, url).await?;
+        }
 
-        // Fetch robots.txt
-        let robots_content = match self.get(&robots_url).await {
-            Ok(content) => content,
-            Err(Error::HttpError(_)) => {
+        let header = |name: header::HeaderName| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let redirect = if response.status().is_redirection() {
+            header(header::LOCATION).map(|location| {
+                Url::parse(url)
+                    .and_then(|base| base.join(&location))
+                    .map(|u| u.to_string())
+                    .unwrap_or(location)
+            })
+        } else {
+            None
+        };
+
+        Ok(ProbeResult {
+            status: response.status().as_u16(),
+            content_length: header(header::CONTENT_LENGTH).and_then(|v| v.parse().ok()),
+            content_type: header(header::CONTENT_TYPE),
+            redirect,
+            allowed_methods: Vec::new(),
+            redirect_chain: Vec::new(),
+            soft_404: false,
+        })
+    }
+
+    /// Ask a URL which methods it supports with an `OPTIONS` request
+    ///
+    /// Merges the `Allow` header with CORS `Access-Control-Allow-Methods`,
+    /// which servers usually only send when an `Origin` is given, so the
+    /// URL's own origin is sent. Methods are uppercased, sorted and unique.
+    pub async fn allowed_methods(&self, url: &str) -> Result<Vec<String>> {
+        let mut request = self
+            .request(&self.probe_client, reqwest::Method::OPTIONS, url)
+            .await;
+
+        debug!("Sending OPTIONS to {}", url);
+        if let Ok(parsed) = Url::parse(url) {
+            request = request.header(header::ORIGIN, parsed.origin().ascii_serialization());
+        }
+        let response = request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                Error::TimeoutError
+            } else {
+                Error::HttpError(e)
+            }
+        })?;
+
+        let mut methods: Vec<String> = [header::ALLOW, header::ACCESS_CONTROL_ALLOW_METHODS]
+            .into_iter()
+            .flat_map(|name| response.headers().get_all(name))
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|method| method.trim().to_uppercase())
+            .filter(|method| !method.is_empty())
+            .collect();
+        methods.sort();
+        methods.dedup();
+        Ok(methods)
+    }
+
+    /// Send a single rate-limited probe request
+    async fn send_probe(&self, method: reqwest::Method, url: &str) -> Result<reqwest::Response> {
+        let request = self.request(&self.probe_client, method.clone(), url).await;
+
+        debug!("Probing {} {}", method, url);
+        request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                Error::TimeoutError
+            } else {
+                Error::HttpError(e)
+            }
+        })
+    }
+
+    /// Check robots.txt for URL
+    pub async fn check_robots_txt(&self, url: &Url) -> Result<bool> {
+        let allowed = match self.robots_txt(url).await? {
+            Some(robots_content) => robots_allows(&robots_content, url),
+            None => {
                 // No robots.txt, allow crawling
                 debug!("No robots.txt found, allowing crawl");
-                return Ok(true);
+                true
             }
-            Err(e) => return Err(e),
         };
 
-        // Parse robots.txt using the robotstxt crate
-        let mut matcher = robotstxt::DefaultMatcher::default();
-        let user_agent = "Endpointo"; // We can make this configurable
-        let allowed =
-            matcher.one_agent_allowed_by_robots(&robots_content, user_agent, url.as_str());
-
         if !allowed {
             warn!("robots.txt disallows {}", url);
         }
 
         Ok(allowed)
     }
+
+    /// Fetch the robots.txt of a URL's host, or `None` if there is none
+    pub async fn robots_txt(&self, url: &Url) -> Result<Option<String>> {
+        // On the URL's origin, port included
+        let robots_url = url.join("/robots.txt")?.to_string();
+
+        debug!("Checking robots.txt at {}", robots_url);
+
+        match self.get(&robots_url).await {
+            Ok(content) => Ok(Some(content)),
+            Err(Error::HttpError(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Whether robots.txt content lets Endpointo fetch a URL
+pub fn robots_allows(robots_content: &str, url: &Url) -> bool {
+    // Parse robots.txt using the robotstxt crate
+    let mut matcher = robotstxt::DefaultMatcher::default();
+    let user_agent = "Endpointo"; // We can make this configurable
+    matcher.one_agent_allowed_by_robots(robots_content, user_agent, url.as_str())
 }
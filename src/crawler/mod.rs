@@ -1,94 +1,290 @@
 pub mod client;
 pub mod rate_limiter;
 pub mod robots;
+pub mod visited;
 
 use crate::config::ScanConfig;
 use crate::error::Result;
-use client::HttpClient;
-use dashmap::DashSet;
+use crate::scope::Scope;
+use crate::types::ProbeResult;
+use client::{FetchResponse, HttpClient};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::Semaphore;
 use tracing::{debug, info, warn};
 use url::Url;
+use visited::VisitedSet;
+
+lazy_static! {
+    // <a href="..."> links
+    static ref LINK_REGEX: Regex = Regex::new(r#"<a[^>]+href=["']([^"']+)["']"#).unwrap();
+
+    // <script src="..."> sources
+    static ref SCRIPT_REGEX: Regex = Regex::new(r#"<script[^>]+src=["']([^"']+)["']"#).unwrap();
+}
+
+/// Extensions of linked files that aren't pages, so aren't crawled
+const STATIC_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "svg", "ico", "webp", "css", "js", "mjs", "map", "json", "pdf",
+    "zip", "gz", "woff", "woff2", "ttf", "eot", "mp3", "mp4", "webm",
+];
+
+/// A crawled page and the script assets it references
+#[derive(Debug, Clone, Default)]
+pub struct CrawledPage {
+    /// Page HTML, empty if it couldn't be fetched
+    pub html: String,
+
+    /// Absolute URLs of the page's scripts
+    pub assets: Vec<String>,
+
+    /// Page response headers with lowercase names
+    pub headers: HashMap<String, String>,
+
+    /// Further pages reached by following links, as `(url, html)`, in
+    /// the order they were crawled
+    pub linked_pages: Vec<(String, String)>,
+}
 
 /// Async web crawler for discovering JavaScript assets
 pub struct Crawler {
     client: Arc<HttpClient>,
     config: ScanConfig,
-    visited: Arc<DashSet<String>>,
+    scope: Scope,
+    visited: VisitedSet,
     semaphore: Arc<Semaphore>,
+    assets_fetched: AtomicUsize,
+    assets_exhausted: AtomicBool,
+    pages_exhausted: AtomicBool,
 }
 
 impl Crawler {
     /// Create a new crawler
     pub fn new(config: ScanConfig) -> Result<Self> {
         let client = Arc::new(HttpClient::new(&config)?);
+        let scope = Scope::from_config(&config)?;
         let semaphore = Arc::new(Semaphore::new(config.max_concurrent));
-        let visited = Arc::new(DashSet::new());
+        let visited = VisitedSet::new(config.max_visited, config.visited_bloom);
 
         Ok(Self {
             client,
             config,
+            scope,
             visited,
             semaphore,
+            assets_fetched: AtomicUsize::new(0),
+            assets_exhausted: AtomicBool::new(false),
+            pages_exhausted: AtomicBool::new(false),
         })
     }
 
+    /// Number of HTTP requests sent so far, robots.txt included
+    pub fn requests_made(&self) -> usize {
+        self.client.requests_made()
+    }
+
+    /// Whether `max_assets` stopped an asset from being fetched
+    pub fn assets_exhausted(&self) -> bool {
+        self.assets_exhausted.load(Ordering::Relaxed)
+    }
+
+    /// Whether `max_pages` stopped a linked page from being crawled
+    pub fn pages_exhausted(&self) -> bool {
+        self.pages_exhausted.load(Ordering::Relaxed)
+    }
+
+    /// Mark a URL visited crawl-wide, so no target fetches it
+    pub fn mark_visited(&self, url: &str) {
+        self.visited.insert(url);
+    }
+
+    /// An empty set of visited URLs for one target's crawl
+    ///
+    /// Passed to [`Crawler::crawl_page_in`] and [`Crawler::fetch_asset_in`],
+    /// it keeps targets that share a page or asset from skipping it because
+    /// another target got there first. URLs marked visited on the crawler
+    /// are still skipped.
+    pub fn visits(&self) -> VisitedSet {
+        VisitedSet::new(self.config.max_visited, self.config.visited_bloom)
+    }
+
+    /// Whether `url` is new to both the crawler and `visited`, marking it
+    /// visited in the latter
+    fn first_visit(&self, visited: &VisitedSet, url: &str) -> bool {
+        !self.visited.contains(url) && visited.insert(url)
+    }
+
     /// Crawl a URL and discover assets
     pub async fn crawl(&self, url: &str) -> Result<Vec<String>> {
+        Ok(self.crawl_page(url).await?.assets)
+    }
+
+    /// Crawl a URL, keeping the page's HTML so it can be parsed too
+    ///
+    /// With a `max_depth` above 0, links to pages on the same origin are
+    /// followed breadth-first up to that many hops, fetching at most
+    /// `max_pages` pages in all; their scripts join the page's assets.
+    /// Pages are marked visited, so fetching them again returns nothing.
+    /// Fails if the page itself can't be fetched; linked pages that can't
+    /// be are skipped.
+    pub async fn crawl_page(&self, url: &str) -> Result<CrawledPage> {
+        self.crawl_page_in(url, &self.visited).await
+    }
+
+    /// Crawl a URL like [`Crawler::crawl_page`], marking pages visited in
+    /// `visited` rather than crawl-wide
+    pub async fn crawl_page_in(&self, url: &str, visited: &VisitedSet) -> Result<CrawledPage> {
         let parsed_url = Url::parse(url)?;
+        self.client.add_target(url);
 
         info!("Starting crawl of {}", url);
 
-        // Check robots.txt if enabled
-        if self.config.respect_robots_txt {
-            if !self.client.check_robots_txt(&parsed_url).await? {
-                warn!("robots.txt disallows crawling {}", url);
-                return Ok(Vec::new());
-            }
+        // Check robots.txt if enabled for the host; linked pages are on the
+        // same origin, so the same applies to them
+        let robots = if self.config.respects_robots(&parsed_url) {
+            self.client.robots_txt(&parsed_url).await?
+        } else {
+            None
+        };
+        if robots
+            .as_deref()
+            .is_some_and(|robots| !client::robots_allows(robots, &parsed_url))
+        {
+            warn!("robots.txt disallows crawling {}", url);
+            return Ok(CrawledPage::default());
         }
 
-        let mut assets = Vec::new();
+        // Fetch the main page; a failure fails the crawl, so the target is
+        // reported as failed rather than as having no endpoints
+        let (html, headers) = match self.fetch_html(url, visited).await? {
+            Some(response) => (response.text(), response.headers),
+            None => return Ok(CrawledPage::default()),
+        };
+
+        // Extract script tags
+        let mut assets = self.extract_scripts(&html, &parsed_url);
 
-        // Fetch the main page
-        if let Ok(html) = self.fetch_html(url).await {
-            // Extract script tags
-            assets.extend(self.extract_scripts(&html, &parsed_url));
+        // Follow links breadth-first, one depth at a time
+        let mut linked_pages = Vec::new();
+        let mut frontier = vec![(parsed_url.clone(), html.clone())];
+        let mut pages = 1;
+        for depth in 1..=self.config.max_depth {
+            let mut next = Vec::new();
+            for (page_url, page_html) in &frontier {
+                for link in self.extract_links(page_html, page_url, &parsed_url) {
+                    if pages >= self.config.max_pages {
+                        if !self.pages_exhausted.swap(true, Ordering::Relaxed) {
+                            warn!(
+                                "Page budget of {} exhausted, skipping the rest",
+                                self.config.max_pages
+                            );
+                        }
+                        break;
+                    }
+                    if robots
+                        .as_deref()
+                        .is_some_and(|robots| !client::robots_allows(robots, &link))
+                    {
+                        debug!("robots.txt disallows crawling {}", link);
+                        continue;
+                    }
+                    let response = match self.fetch_html(link.as_str(), visited).await {
+                        Ok(Some(response)) => response,
+                        Ok(None) => continue,
+                        Err(e) => {
+                            debug!("Failed to fetch linked page {}: {}", link, e);
+                            continue;
+                        }
+                    };
+                    pages += 1;
+                    if matches!(response.content_type.as_deref(), Some(ct) if !ct.contains("html"))
+                    {
+                        continue;
+                    }
+                    debug!("Crawled {} at depth {}", link, depth);
+                    let link_html = response.text();
+                    for asset in self.extract_scripts(&link_html, &link) {
+                        if !assets.contains(&asset) {
+                            assets.push(asset);
+                        }
+                    }
+                    linked_pages.push((link.to_string(), link_html.clone()));
+                    next.push((link, link_html));
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
         }
 
-        Ok(assets)
+        Ok(CrawledPage {
+            html,
+            assets,
+            headers,
+            linked_pages,
+        })
     }
 
-    /// Fetch HTML content from a URL
-    async fn fetch_html(&self, url: &str) -> Result<String> {
-        if self.visited.contains(url) {
+    /// Fetch a page from a URL, or `None` if it was already visited
+    async fn fetch_html(&self, url: &str, visited: &VisitedSet) -> Result<Option<FetchResponse>> {
+        if !self.first_visit(visited, url) {
             debug!("Already visited {}", url);
-            return Ok(String::new());
+            return Ok(None);
         }
 
-        self.visited.insert(url.to_string());
-
         // Acquire semaphore permit for concurrency control
         let _permit = self.semaphore.acquire().await.unwrap();
 
         debug!("Fetching {}", url);
-        self.client.get(url).await
+        self.client
+            .fetch_spooled(url, self.config.max_parse_bytes)
+            .await
+            .map(Some)
+    }
+
+    /// Extract links from HTML to pages worth crawling: same origin as
+    /// `start`, in scope, and not obviously static files
+    fn extract_links(&self, html: &str, base_url: &Url, start: &Url) -> Vec<Url> {
+        let mut links: Vec<Url> = Vec::new();
+        for cap in LINK_REGEX.captures_iter(html) {
+            let Ok(mut link) = base_url.join(&cap[1]) else {
+                continue;
+            };
+            link.set_fragment(None);
+            let is_static = link.path().rsplit_once('.').is_some_and(|(_, ext)| {
+                STATIC_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str())
+            });
+            if link.origin() == start.origin()
+                && !is_static
+                && self.scope.allows(link.as_str())
+                && !links.contains(&link)
+            {
+                links.push(link);
+            }
+        }
+        links
     }
 
-    /// Extract script sources from HTML
+    /// Extract in-scope script sources from HTML
     fn extract_scripts(&self, html: &str, base_url: &Url) -> Vec<String> {
         let mut scripts = Vec::new();
 
         // Simple regex-based extraction (can be improved with HTML parser)
-        let script_regex = regex::Regex::new(r#"<script[^>]+src=["']([^"']+)["']"#).unwrap();
-
-        for cap in script_regex.captures_iter(html) {
+        for cap in SCRIPT_REGEX.captures_iter(html) {
             if let Some(src) = cap.get(1) {
                 let script_url = src.as_str();
 
                 // Resolve relative URLs
                 if let Ok(absolute_url) = base_url.join(script_url) {
-                    scripts.push(absolute_url.to_string());
+                    if self.scope.allows(absolute_url.as_str()) {
+                        scripts.push(absolute_url.to_string());
+                    } else {
+                        debug!("Skipping out-of-scope asset {}", absolute_url);
+                    }
                 }
             }
         }
@@ -96,13 +292,56 @@ impl Crawler {
         scripts
     }
 
+    /// Fetch an asset with its response metadata
+    ///
+    /// A body above `max_parse_bytes` is spooled to disk rather than held
+    /// in memory. Returns `None` if the URL was already fetched during this crawl, or
+    /// once `max_assets` assets have been fetched.
+    pub async fn fetch_asset(&self, url: &str) -> Result<Option<FetchResponse>> {
+        self.fetch_asset_in(url, &self.visited).await
+    }
+
+    /// Fetch an asset like [`Crawler::fetch_asset`], marking it visited in
+    /// `visited` rather than crawl-wide
+    pub async fn fetch_asset_in(
+        &self,
+        url: &str,
+        visited: &VisitedSet,
+    ) -> Result<Option<FetchResponse>> {
+        if !self.first_visit(visited, url) {
+            return Ok(None);
+        }
+        if let Some(max) = self.config.max_assets {
+            if self.assets_fetched.fetch_add(1, Ordering::Relaxed) >= max {
+                if !self.assets_exhausted.swap(true, Ordering::Relaxed) {
+                    warn!("Asset budget of {} exhausted, skipping the rest", max);
+                }
+                return Ok(None);
+            }
+        }
+        let _permit = self.semaphore.acquire().await.unwrap();
+
+        debug!("Fetching asset {}", url);
+        self.client.fetch(url).await.map(Some)
+    }
+
+    /// Probe a discovered endpoint for its status, size, type and redirect
+    pub async fn probe(&self, url: &str) -> Result<ProbeResult> {
+        let _permit = self.semaphore.acquire().await.unwrap();
+        self.client.probe(url).await
+    }
+
+    /// Ask a discovered endpoint which methods it supports
+    pub async fn allowed_methods(&self, url: &str) -> Result<Vec<String>> {
+        let _permit = self.semaphore.acquire().await.unwrap();
+        self.client.allowed_methods(url).await
+    }
+
     /// Fetch JavaScript content
     pub async fn fetch_js(&self, url: &str) -> Result<String> {
-        if self.visited.contains(url) {
+        if !self.visited.insert(url) {
             return Ok(String::new());
         }
-
-        self.visited.insert(url.to_string());
         let _permit = self.semaphore.acquire().await.unwrap();
 
         debug!("Fetching JavaScript {}", url);
@@ -0,0 +1,188 @@
+//! Memory-bounded record of URLs already seen
+//!
+//! URLs are normalized (fragment dropped, scheme and host lowercased, default
+//! port removed) and stored as 64-bit hashes rather than strings. With a cap,
+//! the exact set is emptied whenever it fills; its hashes either move into a
+//! fixed-size bloom filter, if enabled, or are forgotten, so a URL seen long
+//! ago may be fetched again. The bloom filter never grows, but its false
+//! positive rate climbs as it fills, so a few unseen URLs may be skipped.
+
+use crate::util::lock;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use url::Url;
+
+/// Bloom filter bits per entry the exact set can hold, for ~1% false
+/// positives at 8x the cap
+const BLOOM_BITS_PER_ENTRY: usize = 80;
+
+/// Hash functions per bloom filter entry
+const BLOOM_HASHES: u64 = 7;
+
+/// Fixed-size bloom filter over URL hashes
+#[derive(Debug)]
+struct Bloom {
+    bits: Vec<u64>,
+}
+
+impl Bloom {
+    fn new(bits: usize) -> Self {
+        Self {
+            bits: vec![0; bits.div_ceil(64).max(1)],
+        }
+    }
+
+    /// Bit positions for a hash, by double hashing
+    fn positions(&self, hash: u64) -> impl Iterator<Item = usize> {
+        let len = self.bits.len() as u64 * 64;
+        let step = hash.rotate_left(32) | 1;
+        (0..BLOOM_HASHES).map(move |i| (hash.wrapping_add(i.wrapping_mul(step)) % len) as usize)
+    }
+
+    fn insert(&mut self, hash: u64) {
+        for bit in self.positions(hash) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    fn contains(&self, hash: u64) -> bool {
+        self.positions(hash)
+            .all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Tiers {
+    exact: HashSet<u64>,
+    bloom: Option<Bloom>,
+}
+
+/// Thread-safe set of seen keys with bounded memory
+#[derive(Debug)]
+pub struct VisitedSet {
+    tiers: Mutex<Tiers>,
+    cap: Option<usize>,
+}
+
+impl VisitedSet {
+    /// Create a set, holding at most `cap` exact entries if given
+    ///
+    /// With `bloom`, entries past the cap move to a bloom filter sized from
+    /// the cap; it is ignored without one.
+    pub fn new(cap: Option<usize>, bloom: bool) -> Self {
+        let bloom = cap
+            .filter(|_| bloom)
+            .map(|cap| Bloom::new(cap.max(1).saturating_mul(BLOOM_BITS_PER_ENTRY)));
+        Self {
+            tiers: Mutex::new(Tiers {
+                exact: HashSet::new(),
+                bloom,
+            }),
+            cap,
+        }
+    }
+
+    /// Mark a URL seen, returning whether it was new
+    pub fn insert(&self, url: &str) -> bool {
+        self.insert_key(url_key(url))
+    }
+
+    /// Whether a URL was seen
+    pub fn contains(&self, url: &str) -> bool {
+        let hash = url_key(url);
+        let tiers = lock(&self.tiers);
+        tiers.exact.contains(&hash) || tiers.bloom.as_ref().is_some_and(|b| b.contains(hash))
+    }
+
+    /// Mark any hashable key seen, returning whether it was new
+    pub fn insert_hashed<K: Hash>(&self, key: &K) -> bool {
+        self.insert_key(hash_of(key))
+    }
+
+    /// Number of keys in the exact set
+    pub fn len(&self) -> usize {
+        lock(&self.tiers).exact.len()
+    }
+
+    /// Whether the exact set is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn insert_key(&self, hash: u64) -> bool {
+        let mut tiers = lock(&self.tiers);
+        if tiers.bloom.as_ref().is_some_and(|b| b.contains(hash)) {
+            return false;
+        }
+        if !tiers.exact.insert(hash) {
+            return false;
+        }
+        if self.cap.is_some_and(|cap| tiers.exact.len() > cap) {
+            let Tiers { exact, bloom } = &mut *tiers;
+            if let Some(bloom) = bloom {
+                for hash in exact.iter() {
+                    bloom.insert(*hash);
+                }
+            }
+            exact.clear();
+            exact.insert(hash);
+        }
+        true
+    }
+}
+
+impl Default for VisitedSet {
+    fn default() -> Self {
+        Self::new(None, false)
+    }
+}
+
+/// Hash of a URL after normalization
+fn url_key(url: &str) -> u64 {
+    match Url::parse(url) {
+        Ok(mut parsed) => {
+            parsed.set_fragment(None);
+            hash_of(&parsed.as_str())
+        }
+        Err(_) => hash_of(&url),
+    }
+}
+
+fn hash_of<K: Hash + ?Sized>(key: &K) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visited_set() {
+        let visited = VisitedSet::default();
+        assert!(visited.insert("https://Example.com:443/app.js#top"));
+        assert!(!visited.insert("https://example.com/app.js"));
+        assert!(visited.contains("https://example.com/app.js#other"));
+
+        // Capped without a bloom filter, old URLs are forgotten
+        let visited = VisitedSet::new(Some(2), false);
+        for url in ["/a", "/b", "/c"] {
+            assert!(visited.insert(url));
+        }
+        assert_eq!(visited.len(), 1);
+        assert!(visited.insert("/a"));
+
+        // With one, they are still recognized
+        let visited = VisitedSet::new(Some(2), true);
+        for url in ["/a", "/b", "/c"] {
+            assert!(visited.insert(url));
+        }
+        assert_eq!(visited.len(), 1);
+        assert!(!visited.insert("/a"));
+        assert!(!visited.insert("/c"));
+        assert!(visited.insert("/d"));
+    }
+}
@@ -0,0 +1,298 @@
+//! Comparison of two scans' endpoints
+//!
+//! Endpoints are matched on their normalized URL (see
+//! [`EndpointFilter::dedup_key`], with ID-like path segments collapsed), HTTP
+//! method, and scan target, so the same API seen with different sample IDs or
+//! query values, or in a renamed chunk, is not reported as new.
+
+use crate::error::Result;
+use crate::output::file::{is_manifest, read_to_string, Manifest};
+use crate::parser::filters::EndpointFilter;
+use crate::parser::versions::endpoint_version;
+use crate::types::{Endpoint, ScanResult, SCHEMA_VERSION, TOOL_VERSION};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+/// Endpoints added, removed, and changed between two scans
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EndpointDiff {
+    /// Endpoints only in the new scan
+    pub added: Vec<Endpoint>,
+
+    /// Endpoints only in the old scan
+    pub removed: Vec<Endpoint>,
+
+    /// Endpoints in both scans whose details differ
+    pub changed: Vec<ChangedEndpoint>,
+}
+
+impl EndpointDiff {
+    /// Check whether the scans found the same endpoints
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// An endpoint present in both scans with different details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedEndpoint {
+    /// The endpoint as seen in the old scan
+    pub before: Endpoint,
+
+    /// The endpoint as seen in the new scan
+    pub after: Endpoint,
+
+    /// Names of the fields that differ (`type`, `params`, `body_params`, `api_version`)
+    pub changes: Vec<String>,
+}
+
+/// Compare two scans' endpoints
+///
+/// Added endpoints keep the order of `new`, removed and changed ones the
+/// order of `old`. Sources and line numbers are ignored since bundle names
+/// change between deployments.
+pub fn diff_endpoints(old: &[Endpoint], new: &[Endpoint]) -> EndpointDiff {
+    let filter = EndpointFilter::new();
+
+    let mut new_by_key: HashMap<String, &Endpoint> = HashMap::new();
+    for endpoint in new {
+        new_by_key
+            .entry(match_key(&filter, endpoint))
+            .or_insert(endpoint);
+    }
+
+    let mut diff = EndpointDiff::default();
+    let mut seen = HashSet::new();
+    for before in old {
+        let key = match_key(&filter, before);
+        if !seen.insert(key.clone()) {
+            continue;
+        }
+        match new_by_key.get(&key) {
+            Some(after) => {
+                let changes = changed_fields(before, after);
+                if !changes.is_empty() {
+                    diff.changed.push(ChangedEndpoint {
+                        before: before.clone(),
+                        after: (*after).clone(),
+                        changes,
+                    });
+                }
+            }
+            None => diff.removed.push(before.clone()),
+        }
+    }
+
+    for endpoint in new {
+        if seen.insert(match_key(&filter, endpoint)) {
+            diff.added.push(endpoint.clone());
+        }
+    }
+
+    diff
+}
+
+/// Load endpoints from a results file written by `scan` or `parse`
+///
+/// Accepts a full scan result or a bare endpoint list in JSON or YAML, and
+/// JSON Lines (`.jsonl`) with one endpoint per line, any of them gzipped
+/// (`.gz`). A shard manifest loads the endpoints of all its shards.
+pub fn load_endpoints(path: &Path) -> Result<Vec<Endpoint>> {
+    Ok(load_result(path)?.endpoints)
+}
+
+/// Load a results file as a scan result
+///
+/// Takes the same files as [`load_endpoints`]. Shard manifests keep their
+/// target, findings and stats; files holding only endpoints give a result
+/// with an empty target and timestamp.
+pub fn load_result(path: &Path) -> Result<ScanResult> {
+    let content = read_to_string(path)?;
+    if is_manifest(path) {
+        let manifest: Manifest = serde_json::from_str(&content)?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let mut endpoints = Vec::new();
+        for shard in &manifest.shards {
+            endpoints.extend(load_endpoints(&dir.join(&shard.file))?);
+        }
+        return Ok(ScanResult {
+            schema_version: manifest.schema_version,
+            tool_version: manifest.tool_version,
+            target: manifest.target,
+            timestamp: manifest.timestamp,
+            total_endpoints: endpoints.len(),
+            endpoints,
+            findings: manifest.findings,
+            wordlist: Vec::new(),
+            technologies: manifest.technologies,
+            stats: manifest.stats,
+        });
+    }
+    let extension = path
+        .to_str()
+        .map(|p| p.strip_suffix(".gz").unwrap_or(p))
+        .and_then(|p| Path::new(p).extension())
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+
+    let file: ResultsFile = match extension.as_deref() {
+        Some("jsonl") => ResultsFile::Endpoints(
+            content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect::<std::result::Result<_, _>>()?,
+        ),
+        Some("yaml") | Some("yml") => serde_yaml::from_str(&content)?,
+        _ => serde_json::from_str(&content)?,
+    };
+    Ok(match file {
+        ResultsFile::Result(result) => *result,
+        ResultsFile::Endpoints(endpoints) => ScanResult {
+            schema_version: SCHEMA_VERSION,
+            tool_version: TOOL_VERSION.to_string(),
+            target: String::new(),
+            timestamp: String::new(),
+            total_endpoints: endpoints.len(),
+            endpoints,
+            findings: Vec::new(),
+            wordlist: Vec::new(),
+            technologies: Vec::new(),
+            stats: None,
+        },
+    })
+}
+
+/// Shapes a results file can take
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ResultsFile {
+    Result(Box<ScanResult>),
+    Endpoints(Vec<Endpoint>),
+}
+
+/// Identity of an endpoint across scans: target, method, and normalized URL
+fn match_key(filter: &EndpointFilter, endpoint: &Endpoint) -> String {
+    let target = endpoint
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("target"))
+        .map(String::as_str)
+        .unwrap_or("");
+    format!("{} {}", target, endpoint_key(filter, endpoint))
+}
+
+/// Identity of an endpoint within one target: method and normalized URL
+/// with ID-like path segments collapsed
+pub(crate) fn endpoint_key(filter: &EndpointFilter, endpoint: &Endpoint) -> String {
+    let method = endpoint.method.as_deref().unwrap_or("GET").to_uppercase();
+    let url = filter.dedup_key(&endpoint.url);
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let path: Vec<&str> = path
+        .split('/')
+        .map(|segment| {
+            if is_id_segment(segment) {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect();
+    format!("{} {}?{}", method, path.join("/"), query)
+}
+
+/// Path segments that vary between calls: numbers, UUIDs and hashes, placeholders
+pub(crate) fn is_id_segment(segment: &str) -> bool {
+    if segment.is_empty() {
+        return false;
+    }
+    let numeric = segment.chars().all(|c| c.is_ascii_digit());
+    let hash_like = segment.len() >= 16
+        && segment.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+        && segment.chars().any(|c| c.is_ascii_digit());
+    let placeholder = segment.starts_with(':')
+        || segment.starts_with('{')
+        || segment.starts_with("${")
+        || segment.starts_with('<');
+    numeric || hash_like || placeholder
+}
+
+/// Fields that differ between two matched endpoints
+fn changed_fields(before: &Endpoint, after: &Endpoint) -> Vec<String> {
+    let mut changes = Vec::new();
+    if before.endpoint_type != after.endpoint_type {
+        changes.push("type".to_string());
+    }
+    if names(
+        before
+            .params
+            .as_deref()
+            .unwrap_or(&[])
+            .iter()
+            .map(String::as_str),
+    ) != names(
+        after
+            .params
+            .as_deref()
+            .unwrap_or(&[])
+            .iter()
+            .map(String::as_str),
+    ) {
+        changes.push("params".to_string());
+    }
+    if names(body_params(before)) != names(body_params(after)) {
+        changes.push("body_params".to_string());
+    }
+    if endpoint_version(before) != endpoint_version(after) {
+        changes.push("api_version".to_string());
+    }
+    changes
+}
+
+/// Body parameter names recorded by the params extractor
+fn body_params(endpoint: &Endpoint) -> impl Iterator<Item = &str> {
+    endpoint
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("body_params"))
+        .into_iter()
+        .flat_map(|params| params.split(','))
+}
+
+/// Order-insensitive set of non-empty names
+fn names<'a>(names: impl Iterator<Item = &'a str>) -> BTreeSet<&'a str> {
+    names.map(str::trim).filter(|n| !n.is_empty()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EndpointType;
+
+    #[test]
+    fn test_diff_endpoints() {
+        let old = vec![
+            Endpoint::new("/api/users/12".to_string(), EndpointType::Rest).with_source("a.js"),
+            Endpoint::new("/api/orders".to_string(), EndpointType::Rest)
+                .with_params(vec!["page".to_string()]),
+            Endpoint::new("/api/legacy".to_string(), EndpointType::Rest),
+        ];
+        let new = vec![
+            Endpoint::new("/api/users/34".to_string(), EndpointType::Rest).with_source("b.js"),
+            Endpoint::new("/api/orders".to_string(), EndpointType::Rest)
+                .with_params(vec!["page".to_string(), "status".to_string()]),
+            Endpoint::new("/api/orders".to_string(), EndpointType::Rest).with_method("POST"),
+        ];
+
+        let diff = diff_endpoints(&old, &new);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].method.as_deref(), Some("POST"));
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].url, "/api/legacy");
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].changes, vec!["params"]);
+
+        assert!(diff_endpoints(&new, &new).is_empty());
+    }
+}
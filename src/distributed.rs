@@ -0,0 +1,468 @@
+//! Distributed scanning through a TCP work queue
+//!
+//! A [`Coordinator`] hands targets out to workers one at a time and collects
+//! their results. Workers ([`run_worker`]) scan each target with their own
+//! configuration and send back the full scan result. Messages are JSON, one
+//! per line:
+//!
+//! - worker → coordinator: `{"type":"request"}`, then `{"type":"result",...}`
+//!   or `{"type":"failed",...}` for the target it was given
+//! - coordinator → worker: `{"type":"target","url":...}`, `{"type":"wait"}`
+//!   while other workers still hold the last targets, `{"type":"done"}`, or
+//!   `{"type":"denied"}` for a request without the coordinator's token
+//!
+//! With a token, a worker's first line must be a request carrying it, and
+//! is cut off after a few KiB; the connection is dropped otherwise.
+//!
+//! A target held by a worker that disconnects goes back on the queue, and
+//! results are only taken from the connection a target was handed to.
+//! Traffic is plain TCP: a token keeps strangers from taking targets, but
+//! the listener still belongs on a private network.
+
+use crate::config::ScanConfig;
+use crate::error::{Error, Result};
+use crate::fingerprint::merge as merge_technology;
+use crate::parser::filters::EndpointFilter;
+use crate::parser::params::build_wordlist;
+use crate::scanner::{endpoint_stats, Scanner};
+use crate::types::{Finding, ScanResult, SCHEMA_VERSION, TOOL_VERSION};
+use crate::util::{lock, secrets_match};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::Notify;
+use tracing::{debug, info, warn};
+
+/// How long a worker waits before asking again after a `wait`
+const WAIT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Longest line a worker may send before it has shown the token
+const AUTH_LINE_BYTES: usize = 4 * 1024;
+
+/// Longest line either side accepts, room for a large scan result
+const MAX_LINE_BYTES: usize = 256 * 1024 * 1024;
+
+/// A line of the work queue protocol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    /// Worker asks for a target, with the coordinator's token if it has one
+    Request {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<String>,
+    },
+    /// Coordinator assigns a target
+    Target { url: String },
+    /// No target free right now, ask again shortly
+    Wait,
+    /// Every target is finished
+    Done,
+    /// The request's token was missing or wrong
+    Denied,
+    /// Worker finished a target
+    Result {
+        target: String,
+        result: Box<ScanResult>,
+    },
+    /// Worker couldn't scan a target
+    Failed { target: String, error: String },
+}
+
+/// Queue state shared by the coordinator's connections
+#[derive(Default)]
+struct Queue {
+    pending: VecDeque<String>,
+    in_flight: HashSet<String>,
+    results: HashMap<String, std::result::Result<ScanResult, String>>,
+}
+
+/// Hands targets out to connecting workers and gathers their results
+pub struct Coordinator {
+    targets: Vec<String>,
+    queue: Arc<Mutex<Queue>>,
+    finished: Arc<Notify>,
+    token: Option<Arc<str>>,
+}
+
+impl Coordinator {
+    /// Create a coordinator for a list of targets; repeated targets are
+    /// scanned once
+    pub fn new(mut targets: Vec<String>) -> Self {
+        let mut seen = HashSet::new();
+        targets.retain(|target| seen.insert(target.clone()));
+        let queue = Queue {
+            pending: targets.iter().cloned().collect(),
+            ..Queue::default()
+        };
+        Self {
+            targets,
+            queue: Arc::new(Mutex::new(queue)),
+            finished: Arc::new(Notify::new()),
+            token: None,
+        }
+    }
+
+    /// Only hand targets to workers that send this token
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token.filter(|t| !t.is_empty()).map(Arc::from);
+        self
+    }
+
+    /// Serve workers on `listener` until every target has a result
+    ///
+    /// Results are returned in the order of the targets; a failed target
+    /// carries the worker's error.
+    pub async fn run(self, listener: TcpListener) -> Vec<(String, Result<ScanResult>)> {
+        info!(
+            "Coordinating {} targets on {}",
+            self.targets.len(),
+            listener
+                .local_addr()
+                .map(|a| a.to_string())
+                .unwrap_or_default()
+        );
+
+        if !self.targets.is_empty() {
+            let finished = self.finished.notified();
+            tokio::pin!(finished);
+            loop {
+                tokio::select! {
+                    _ = &mut finished => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, peer)) => {
+                            info!("Worker connected from {}", peer);
+                            let queue = self.queue.clone();
+                            let finished = self.finished.clone();
+                            let total = self.targets.len();
+                            let token = self.token.clone();
+                            tokio::spawn(async move {
+                                let served = serve_worker(stream, queue, finished, total, token);
+                                if let Err(e) = served.await {
+                                    warn!("Worker {} disconnected: {}", peer, e);
+                                }
+                            });
+                        }
+                        Err(e) => warn!("Failed to accept worker: {}", e),
+                    },
+                }
+            }
+        }
+
+        let mut queue = lock(&self.queue);
+        self.targets
+            .into_iter()
+            .map(|target| {
+                let result = match queue.results.remove(&target) {
+                    Some(Ok(result)) => Ok(result),
+                    Some(Err(e)) => Err(Error::Other(e)),
+                    None => Err(Error::Other("no result".to_string())),
+                };
+                (target, result)
+            })
+            .collect()
+    }
+}
+
+/// Talk to one worker until it leaves, requeueing targets it didn't finish
+async fn serve_worker(
+    stream: TcpStream,
+    queue: Arc<Mutex<Queue>>,
+    finished: Arc<Notify>,
+    total: usize,
+    token: Option<Arc<str>>,
+) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let mut assigned: Vec<String> = Vec::new();
+    let mut authenticated = token.is_none();
+
+    let served = async {
+        loop {
+            let limit = if authenticated {
+                MAX_LINE_BYTES
+            } else {
+                AUTH_LINE_BYTES
+            };
+            let Some(line) = read_line(&mut reader, limit).await? else {
+                break;
+            };
+            let message = serde_json::from_str::<Message>(&line)?;
+            // The first line must be a request carrying the token
+            if let (false, Some(token)) = (authenticated, &token) {
+                let given = match &message {
+                    Message::Request { token } => token.as_deref(),
+                    _ => None,
+                };
+                if !secrets_match(given.unwrap_or_default(), token) {
+                    warn!("Refusing a worker without the coordinator's token");
+                    send(&mut writer, &Message::Denied).await?;
+                    break;
+                }
+                authenticated = true;
+            }
+            let reply = match message {
+                Message::Request { .. } => {
+                    let mut queue = lock(&queue);
+                    match queue.pending.pop_front() {
+                        Some(url) => {
+                            queue.in_flight.insert(url.clone());
+                            assigned.push(url.clone());
+                            Message::Target { url }
+                        }
+                        None if queue.in_flight.is_empty() => Message::Done,
+                        None => Message::Wait,
+                    }
+                }
+                Message::Result { target, result } => {
+                    if take(&mut assigned, &target) {
+                        complete(&queue, &finished, total, &target, Ok(*result));
+                    }
+                    continue;
+                }
+                Message::Failed { target, error } => {
+                    if take(&mut assigned, &target) {
+                        complete(&queue, &finished, total, &target, Err(error));
+                    }
+                    continue;
+                }
+                other => {
+                    debug!("Ignoring unexpected {:?} from worker", other);
+                    continue;
+                }
+            };
+            let done = matches!(reply, Message::Done);
+            send(&mut writer, &reply).await?;
+            if done {
+                break;
+            }
+        }
+        Ok::<(), Error>(())
+    }
+    .await;
+
+    let mut queue = lock(&queue);
+    for target in assigned {
+        if queue.in_flight.remove(&target) {
+            info!("Requeueing {}", target);
+            queue.pending.push_back(target);
+        }
+    }
+    served
+}
+
+/// Remove `target` from a worker's assignments, if it was handed that target
+fn take(assigned: &mut Vec<String>, target: &str) -> bool {
+    match assigned.iter().position(|t| t == target) {
+        Some(i) => {
+            assigned.remove(i);
+            true
+        }
+        None => {
+            debug!(
+                "Ignoring result for {}, not assigned to this worker",
+                target
+            );
+            false
+        }
+    }
+}
+
+/// Record a target's outcome, signalling the coordinator after the last one
+fn complete(
+    queue: &Mutex<Queue>,
+    finished: &Notify,
+    total: usize,
+    target: &str,
+    result: std::result::Result<ScanResult, String>,
+) {
+    let mut queue = lock(queue);
+    if !queue.in_flight.remove(target) {
+        debug!("Ignoring result for unassigned target {}", target);
+        return;
+    }
+    queue.results.insert(target.to_string(), result);
+    if queue.results.len() == total {
+        finished.notify_one();
+    }
+}
+
+/// Pull targets from a coordinator and scan them until it has no more
+///
+/// Each target gets a fresh scanner built from `config` with the target
+/// filled in. `token` must match the coordinator's, if it has one. Returns
+/// how many targets this worker scanned.
+pub async fn run_worker(
+    addr: impl ToSocketAddrs,
+    config: ScanConfig,
+    token: Option<String>,
+) -> Result<usize> {
+    let stream = TcpStream::connect(addr).await?;
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let mut scanned = 0;
+
+    loop {
+        let request = Message::Request {
+            token: token.clone(),
+        };
+        send(&mut writer, &request).await?;
+        // A closed connection means the coordinator is finished
+        let Some(line) = read_line(&mut reader, MAX_LINE_BYTES).await? else {
+            break;
+        };
+        let url = match serde_json::from_str::<Message>(&line)? {
+            Message::Target { url } => url,
+            Message::Wait => {
+                tokio::time::sleep(WAIT_INTERVAL).await;
+                continue;
+            }
+            Message::Done => break,
+            Message::Denied => {
+                return Err(Error::ValidationError(
+                    "the coordinator refused this worker's token".to_string(),
+                ))
+            }
+            other => {
+                debug!("Ignoring unexpected {:?} from coordinator", other);
+                continue;
+            }
+        };
+
+        info!("Scanning {}", url);
+        let reply = match scan_target(&url, &config).await {
+            Ok(result) => Message::Result {
+                target: url,
+                result: Box::new(result),
+            },
+            Err(e) => Message::Failed {
+                target: url,
+                error: e.to_string(),
+            },
+        };
+        send(&mut writer, &reply).await?;
+        scanned += 1;
+    }
+
+    Ok(scanned)
+}
+
+/// Scan one target with a scanner of its own
+async fn scan_target(url: &str, config: &ScanConfig) -> Result<ScanResult> {
+    let mut config = config.clone();
+    config.target_url = url.to_string();
+    let scanner = Scanner::new(config)?;
+    let started = Utc::now();
+    let endpoints = match scanner.scan_urls(&[url.to_string()]).await.pop() {
+        Some((_, endpoints)) => endpoints?,
+        None => Vec::new(),
+    };
+    Ok(scanner.build_result(url, started, endpoints))
+}
+
+/// Merge per-target results into one, deduplicating across targets
+///
+/// Endpoints carry their target in metadata, so the same path on two
+/// targets stays two endpoints. File and request counts are summed.
+pub fn merge_results(
+    target: impl Into<String>,
+    started: DateTime<Utc>,
+    results: Vec<ScanResult>,
+) -> ScanResult {
+    let mut endpoints = Vec::new();
+    let mut findings: Vec<Finding> = Vec::new();
+    let mut technologies = Vec::new();
+    let (mut files_processed, mut requests_made) = (0, 0);
+    let mut budget_exhausted = None;
+    let mut plugin_errors = HashMap::new();
+
+    for result in results {
+        endpoints.extend(result.endpoints);
+        for finding in result.findings {
+            if !findings
+                .iter()
+                .any(|f| f.kind == finding.kind && f.value == finding.value)
+            {
+                findings.push(finding);
+            }
+        }
+        for technology in result.technologies {
+            merge_technology(&mut technologies, Some(technology));
+        }
+        if let Some(stats) = result.stats {
+            files_processed += stats.files_processed;
+            requests_made += stats.requests_made;
+            budget_exhausted = budget_exhausted.or(stats.budget_exhausted);
+            for (plugin, count) in stats.plugin_errors {
+                *plugin_errors.entry(plugin).or_insert(0) += count;
+            }
+        }
+    }
+    EndpointFilter::new().deduplicate(&mut endpoints);
+
+    let mut stats = endpoint_stats(&endpoints);
+    stats.files_processed = files_processed;
+    stats.requests_made = requests_made;
+    stats.duration_seconds = (Utc::now() - started)
+        .to_std()
+        .unwrap_or_default()
+        .as_secs_f64();
+    stats.budget_exhausted = budget_exhausted;
+    stats.plugin_errors = plugin_errors;
+
+    ScanResult {
+        schema_version: SCHEMA_VERSION,
+        tool_version: TOOL_VERSION.to_string(),
+        target: target.into(),
+        timestamp: started.to_rfc3339(),
+        total_endpoints: endpoints.len(),
+        findings,
+        wordlist: build_wordlist(&endpoints),
+        technologies,
+        stats: Some(stats),
+        endpoints,
+    }
+}
+
+/// Read one protocol line of at most `limit` bytes, or `None` once the
+/// connection closes
+///
+/// Fails on a longer line rather than buffering it.
+async fn read_line<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    limit: usize,
+) -> Result<Option<String>> {
+    let mut line = Vec::new();
+    reader
+        .take(limit as u64 + 1)
+        .read_until(b'\n', &mut line)
+        .await?;
+    if line.is_empty() {
+        return Ok(None);
+    }
+    if line.last() == Some(&b'\n') {
+        line.pop();
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+    } else if line.len() > limit {
+        return Err(Error::ValidationError(format!(
+            "line longer than {} bytes",
+            limit
+        )));
+    }
+    String::from_utf8(line)
+        .map(Some)
+        .map_err(|_| Error::ValidationError("line is not UTF-8".to_string()))
+}
+
+/// Write one protocol message as a line
+async fn send<W: AsyncWriteExt + Unpin>(writer: &mut W, message: &Message) -> Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    Ok(())
+}
@@ -0,0 +1,523 @@
+//! Preflight checks before a long scan
+//!
+//! `endpointo doctor` runs the parts of a scan most likely to fail late
+//! against the target with the scan's own settings: the configuration
+//! itself, the proxy, a first request, certificate trust, robots.txt, a
+//! short burst at the configured rate, and each plugin. Each check passes,
+//! warns, fails or is skipped, with a hint on what to change.
+
+use crate::config::ScanConfig;
+use crate::crawler::client::{robots_allows, HttpClient};
+use crate::error::{Error, Result};
+use crate::plugins::dev::hooks_in;
+use crate::plugins::native::is_library;
+use crate::plugins::{EndpointoPlugin, NativePlugin, PluginManager};
+use crate::types::ProbeResult;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// Requests sent to check the rate limit, at most
+pub const DEFAULT_BURST: usize = 10;
+
+/// Outcome of one check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    /// The scan will run, but maybe not as intended
+    Warn,
+    /// The scan will fail or find nothing
+    Fail,
+    /// Not applicable, or blocked by an earlier failure
+    Skip,
+}
+
+impl CheckStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "pass",
+            CheckStatus::Warn => "warn",
+            CheckStatus::Fail => "fail",
+            CheckStatus::Skip => "skip",
+        }
+    }
+}
+
+/// One diagnostic
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+
+    /// What to change, for warnings and failures
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+/// Runs the preflight checks for one target
+pub struct Doctor {
+    config: ScanConfig,
+    burst: usize,
+}
+
+impl Doctor {
+    /// Check the target in `config.target_url` with the config's settings
+    pub fn new(config: ScanConfig) -> Self {
+        Self {
+            config,
+            burst: DEFAULT_BURST,
+        }
+    }
+
+    /// Send at most this many requests to check the rate limit; 0 skips it
+    pub fn with_burst(mut self, burst: Option<usize>) -> Self {
+        if let Some(burst) = burst {
+            self.burst = burst;
+        }
+        self
+    }
+
+    /// Run every check, in order
+    pub async fn run(&self) -> Vec<Check> {
+        let mut checks = Vec::new();
+
+        let target = match Url::parse(&self.config.target_url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => url,
+            _ => {
+                checks.push(
+                    Check::new(
+                        "configuration",
+                        CheckStatus::Fail,
+                        format!("'{}' isn't an http(s) URL", self.config.target_url),
+                    )
+                    .with_hint("give the full URL, e.g. https://example.com"),
+                );
+                return checks;
+            }
+        };
+        let client = match HttpClient::new(&self.config) {
+            Ok(client) => client,
+            Err(e) => {
+                checks.push(
+                    Check::new("configuration", CheckStatus::Fail, e.to_string())
+                        .with_hint("fix the header, cookie or proxy flag named above"),
+                );
+                return checks;
+            }
+        };
+        checks.push(Check::new(
+            "configuration",
+            CheckStatus::Pass,
+            format!(
+                "{} req/s, {} concurrent, {}s timeout",
+                self.config.rate_limit, self.config.max_concurrent, self.config.timeout_seconds
+            ),
+        ));
+
+        checks.push(self.check_proxy().await);
+
+        let started = Instant::now();
+        let first = client.probe(target.as_str()).await;
+        let latency = started.elapsed();
+        checks.push(self.connectivity(&target, &first, latency));
+        checks.push(self.tls(&target, &first));
+
+        if first.is_ok() {
+            checks.push(self.check_robots(&client, &target).await);
+            checks.push(self.check_rate(&client, &target, latency).await);
+        } else {
+            for name in ["robots.txt", "rate limit"] {
+                checks.push(Check::new(
+                    name,
+                    CheckStatus::Skip,
+                    "the target couldn't be reached",
+                ));
+            }
+        }
+
+        checks.extend(self.check_plugins());
+        checks
+    }
+
+    async fn check_proxy(&self) -> Check {
+        let Some(proxy) = &self.config.proxy else {
+            return Check::new("proxy", CheckStatus::Skip, "no proxy configured");
+        };
+        let Some((host, port)) = Url::parse(proxy).ok().and_then(|url| {
+            let port = url.port_or_known_default().unwrap_or(1080);
+            Some((url.host_str()?.to_string(), port))
+        }) else {
+            return Check::new(
+                "proxy",
+                CheckStatus::Fail,
+                format!("'{}' has no host", proxy),
+            );
+        };
+
+        let timeout = Duration::from_secs(self.config.timeout_seconds.clamp(1, 10));
+        let address = format!("{}:{}", host, port);
+        match tokio::time::timeout(timeout, tokio::net::TcpStream::connect(&address)).await {
+            Ok(Ok(_)) => Check::new(
+                "proxy",
+                CheckStatus::Pass,
+                format!("{} is accepting connections", address),
+            ),
+            Ok(Err(e)) => Check::new(
+                "proxy",
+                CheckStatus::Fail,
+                format!("can't connect to {}: {}", address, e),
+            )
+            .with_hint("start the proxy or fix --proxy"),
+            Err(_) => Check::new(
+                "proxy",
+                CheckStatus::Fail,
+                format!("no answer from {} in {}s", address, timeout.as_secs()),
+            )
+            .with_hint("start the proxy or fix --proxy"),
+        }
+    }
+
+    fn connectivity(&self, target: &Url, first: &Result<ProbeResult>, latency: Duration) -> Check {
+        let probe = match first {
+            Ok(probe) => probe,
+            Err(e) => {
+                let chain = error_chain(e);
+                let check = Check::new("connectivity", CheckStatus::Fail, chain.clone());
+                let lower = chain.to_lowercase();
+                return if matches!(e, Error::TimeoutError) || lower.contains("timed out") {
+                    check.with_hint(format!(
+                        "raise --timeout (now {}s) or check the target is reachable from here",
+                        self.config.timeout_seconds
+                    ))
+                } else if lower.contains("dns") || lower.contains("lookup") {
+                    check.with_hint(format!(
+                        "check the host name '{}'",
+                        target.host_str().unwrap_or("")
+                    ))
+                } else if self.config.proxy.is_some()
+                    && ["proxy", "tunnel", "socks"]
+                        .iter()
+                        .any(|w| lower.contains(w))
+                {
+                    check.with_hint("see the proxy check")
+                } else if lower.contains("refused") {
+                    check.with_hint(format!(
+                        "nothing is listening on port {}",
+                        target.port_or_known_default().unwrap_or(0)
+                    ))
+                } else if is_certificate_error(&lower) {
+                    check.with_hint("see the tls check")
+                } else if self.config.proxy.is_some() {
+                    check.with_hint("see the proxy check")
+                } else {
+                    check
+                };
+            }
+        };
+
+        let detail = format!("HTTP {} in {} ms", probe.status, latency.as_millis());
+        match probe.status {
+            200..=399 => Check::new("connectivity", CheckStatus::Pass, detail),
+            401 | 403 => Check::new("connectivity", CheckStatus::Warn, detail).with_hint(
+                "the target refuses anonymous requests; pass credentials with -H or --cookie",
+            ),
+            429 => Check::new("connectivity", CheckStatus::Warn, detail)
+                .with_hint("the target is already throttling; lower --rate-limit"),
+            _ => Check::new("connectivity", CheckStatus::Warn, detail)
+                .with_hint("the start page errors, so the scan may find few assets"),
+        }
+    }
+
+    fn tls(&self, target: &Url, first: &Result<ProbeResult>) -> Check {
+        if target.scheme() != "https" {
+            return Check::new("tls", CheckStatus::Skip, "the target is plain HTTP");
+        }
+        match first {
+            Ok(_) => Check::new("tls", CheckStatus::Pass, "certificate verified"),
+            Err(e) => {
+                let chain = error_chain(e);
+                if !is_certificate_error(&chain.to_lowercase()) {
+                    return Check::new("tls", CheckStatus::Skip, "the target couldn't be reached");
+                }
+                let hint = if self.config.proxy.is_some() {
+                    "an intercepting proxy re-signs traffic; add its CA certificate to the system trust store"
+                } else {
+                    "certificates are always verified; add the target's CA to the system trust store"
+                };
+                Check::new("tls", CheckStatus::Fail, chain).with_hint(hint)
+            }
+        }
+    }
+
+    async fn check_robots(&self, client: &HttpClient, target: &Url) -> Check {
+        if !self.config.respects_robots(target) {
+            let why = if self.config.respect_robots_txt {
+                "ignored for this host (scan.hosts)"
+            } else {
+                "ignored (--ignore-robots)"
+            };
+            return Check::new("robots.txt", CheckStatus::Skip, why);
+        }
+        match client.robots_txt(target).await {
+            Ok(None) => Check::new("robots.txt", CheckStatus::Pass, "none; everything allowed"),
+            Ok(Some(content)) if robots_allows(&content, target) => {
+                let rules = content
+                    .lines()
+                    .filter(|l| l.trim_start().to_lowercase().starts_with("disallow:"))
+                    .count();
+                Check::new(
+                    "robots.txt",
+                    CheckStatus::Pass,
+                    format!("allows the target ({} disallow rules)", rules),
+                )
+            }
+            Ok(Some(_)) => Check::new(
+                "robots.txt",
+                CheckStatus::Warn,
+                "disallows the target, so the scan will fetch nothing",
+            )
+            .with_hint("scan with --ignore-robots if you're authorized to"),
+            Err(e) => Check::new("robots.txt", CheckStatus::Warn, error_chain(&e))
+                .with_hint("the scan will fail the same way; try --ignore-robots"),
+        }
+    }
+
+    /// Send a short burst at the configured rate and concurrency, and see
+    /// whether the target throttles it and whether latency allows the rate
+    async fn check_rate(&self, client: &HttpClient, target: &Url, latency: Duration) -> Check {
+        let rate = self.config.rate_limit.max(1) as usize;
+        let concurrency = self.config.max_concurrent.max(1);
+        let burst = self.burst.min(rate.max(3));
+        if burst == 0 {
+            return Check::new("rate limit", CheckStatus::Skip, "no burst requested");
+        }
+
+        let started = Instant::now();
+        let results: Vec<(Option<u16>, Duration)> = stream::iter(0..burst)
+            .map(|_| async {
+                let sent = Instant::now();
+                let status = client.probe(target.as_str()).await.ok().map(|p| p.status);
+                (status, sent.elapsed())
+            })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+        let elapsed = started.elapsed().as_secs_f64().max(0.001);
+
+        let throttled = results
+            .iter()
+            .filter(|(status, _)| matches!(status, Some(429 | 503)))
+            .count();
+        let failed = results
+            .iter()
+            .filter(|(status, _)| status.is_none())
+            .count();
+        let mut latencies: Vec<Duration> = results.iter().map(|(_, d)| *d).collect();
+        latencies.push(latency);
+        latencies.sort();
+        let median = latencies[latencies.len() / 2].as_secs_f64().max(0.001);
+        // Each slot finishes a request per round trip
+        let capacity = concurrency as f64 / median;
+
+        let detail = format!(
+            "{} requests at {:.1} req/s, median {} ms",
+            burst,
+            burst as f64 / elapsed,
+            (median * 1000.0).round()
+        );
+        if throttled > 0 {
+            return Check::new(
+                "rate limit",
+                CheckStatus::Warn,
+                format!("{}; {} throttled (429/503)", detail, throttled),
+            )
+            .with_hint(format!(
+                "lower --rate-limit below {} to avoid being blocked",
+                rate
+            ));
+        }
+        if failed > 0 {
+            return Check::new(
+                "rate limit",
+                CheckStatus::Warn,
+                format!("{}; {} failed", detail, failed),
+            )
+            .with_hint("the target drops requests under load; lower --rate-limit or --threads");
+        }
+        if capacity < rate as f64 * 0.8 {
+            let needed = (rate as f64 * median).ceil() as usize;
+            return Check::new(
+                "rate limit",
+                CheckStatus::Warn,
+                format!(
+                    "{}; {} concurrent requests reach only about {:.0} req/s",
+                    detail, concurrency, capacity
+                ),
+            )
+            .with_hint(format!(
+                "raise --threads to {} or lower --rate-limit to reach the rate",
+                needed
+            ));
+        }
+        Check::new("rate limit", CheckStatus::Pass, detail)
+    }
+
+    fn check_plugins(&self) -> Vec<Check> {
+        match self.config.plugin_chain() {
+            Ok(chain) if chain.is_empty() => vec![Check::new(
+                "plugin",
+                CheckStatus::Skip,
+                "no plugin configured",
+            )],
+            Ok(chain) => chain.iter().map(|path| self.check_plugin(path)).collect(),
+            Err(e) => vec![Check::new("plugin", CheckStatus::Fail, e.to_string())],
+        }
+    }
+
+    fn check_plugin(&self, path: &Path) -> Check {
+        if is_library(path) {
+            return match NativePlugin::load(path) {
+                Ok(plugin) => Check::new(
+                    "plugin",
+                    CheckStatus::Pass,
+                    format!(
+                        "{} loads as native plugin {}",
+                        path.display(),
+                        plugin.name()
+                    ),
+                ),
+                Err(e) => Check::new("plugin", CheckStatus::Fail, e.to_string())
+                    .with_hint("rebuild the plugin against this version of endpointo as a cdylib"),
+            };
+        }
+        let source = match std::fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) => {
+                return Check::new(
+                    "plugin",
+                    CheckStatus::Fail,
+                    format!("{}: {}", path.display(), e),
+                )
+            }
+        };
+        let hooks = hooks_in(&source);
+        if hooks.is_empty() {
+            return Check::new(
+                "plugin",
+                CheckStatus::Warn,
+                format!("{} defines no hooks", path.display()),
+            )
+            .with_hint(
+                "define generate_endpoints, filter_endpoint or transform_endpoint; see `endpointo plugins new`",
+            );
+        }
+        if !cfg!(feature = "python-plugins") {
+            return Check::new(
+                "plugin",
+                CheckStatus::Warn,
+                format!(
+                    "this build can't run Python plugins, so the scan will skip {}",
+                    path.display()
+                ),
+            )
+            .with_hint("rebuild with `--features python-plugins`");
+        }
+        match PluginManager::new().load_plugin(path) {
+            Ok(()) => Check::new(
+                "plugin",
+                CheckStatus::Pass,
+                format!("{} loads and defines {}", path.display(), hooks.join(", ")),
+            ),
+            Err(e) => Check::new("plugin", CheckStatus::Fail, e.to_string())
+                .with_hint("run `endpointo plugins test` on it for details"),
+        }
+    }
+}
+
+/// An error and its causes, which hold the useful part of reqwest errors
+fn error_chain(error: &Error) -> String {
+    let mut chain = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        let text = cause.to_string();
+        if !chain.contains(&text) {
+            chain.push_str(": ");
+            chain.push_str(&text);
+        }
+        source = cause.source();
+    }
+    chain
+}
+
+fn is_certificate_error(chain: &str) -> bool {
+    ["certificate", "unknownissuer", "self signed", "self-signed"]
+        .iter()
+        .any(|needle| chain.contains(needle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_doctor_checks() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("HEAD", "/")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let config = ScanConfig::new(server.url())
+            .with_robots(false)
+            .with_rate_limit(Some(100))
+            .with_max_concurrent(Some(1));
+        let checks = Doctor::new(config).with_burst(Some(3)).run().await;
+        let statuses: Vec<(&str, CheckStatus)> =
+            checks.iter().map(|c| (c.name, c.status)).collect();
+        assert_eq!(
+            statuses[..5],
+            [
+                ("configuration", CheckStatus::Pass),
+                ("proxy", CheckStatus::Skip),
+                ("connectivity", CheckStatus::Pass),
+                ("tls", CheckStatus::Skip),
+                ("robots.txt", CheckStatus::Skip),
+            ]
+        );
+        assert_eq!(statuses[6], ("plugin", CheckStatus::Skip));
+
+        // Nothing listens on the discard port
+        let config = ScanConfig::new("http://127.0.0.1:9/".to_string())
+            .with_proxy(Some("http://127.0.0.1:9".to_string()));
+        let checks = Doctor::new(config).run().await;
+        assert_eq!(checks[1].status, CheckStatus::Fail);
+        assert_eq!(checks[2].status, CheckStatus::Fail);
+        assert_eq!(checks[4].status, CheckStatus::Skip);
+
+        let config = ScanConfig::new("https://example.com".to_string())
+            .with_headers(vec!["no colon".to_string()]);
+        let checks = Doctor::new(config).run().await;
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, CheckStatus::Fail);
+    }
+}
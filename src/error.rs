@@ -18,9 +18,18 @@ pub enum Error {
     #[error("YAML serialization error: {0}")]
     YamlError(#[from] serde_yaml::Error),
 
+    #[error("TOML parse error: {0}")]
+    TomlError(#[from] toml::de::Error),
+
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] rusqlite::Error),
+
     #[error("XML serialization error: {0}")]
     XmlError(String),
 
+    #[error("Template error: {0}")]
+    TemplateError(String),
+
     #[error("URL parse error: {0}")]
     UrlParseError(#[from] url::ParseError),
 
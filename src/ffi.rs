@@ -0,0 +1,199 @@
+//! C interface, for embedding endpointo as a shared library
+//!
+//! Build the library with
+//! `cargo rustc --release --lib --crate-type cdylib` (or `staticlib`) and
+//! include `include/endpointo.h`. Every call takes and returns UTF-8,
+//! NUL-terminated strings; results are JSON objects, either
+//! `{"endpoints": [...]}` or `{"error": "..."}`, and must be released with
+//! [`endpointo_free_string`].
+//!
+//! Options are a JSON object with the keys of a configuration file's
+//! `[scan]` table, e.g. `{"profile": "aggressive", "rate_limit": 5}`, or
+//! NULL for the defaults.
+
+use crate::config::{ScanConfig, ScanSettings};
+use crate::error::{Error, Result};
+use crate::scanner::Scanner;
+use crate::types::Endpoint;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::OnceLock;
+
+/// The runtime URL scans run on, shared by every call
+fn runtime() -> Result<&'static tokio::runtime::Runtime> {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+    Ok(RUNTIME.get_or_init(|| runtime))
+}
+
+/// Borrow a C string argument
+///
+/// # Safety
+///
+/// `ptr` must be NULL or point to a NUL-terminated string.
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<Option<&'a str>> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map(Some)
+        .map_err(|_| Error::ValidationError(format!("{} is not valid UTF-8", name)))
+}
+
+/// Build a scanner from JSON options on top of `config`
+fn scanner(config: ScanConfig, options: Option<&str>) -> Result<Scanner> {
+    let settings: ScanSettings = match options {
+        Some(json) if !json.trim().is_empty() => serde_json::from_str(json)
+            .map_err(|e| Error::ValidationError(format!("invalid options: {}", e)))?,
+        _ => ScanSettings::default(),
+    };
+    Scanner::new(settings.apply(config))
+}
+
+/// Run `f`, turning its result, error or panic into a JSON string for C
+fn respond(f: impl FnOnce() -> Result<Vec<Endpoint>>) -> *mut c_char {
+    let json = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(endpoints)) => serde_json::json!({ "endpoints": endpoints }),
+        Ok(Err(e)) => serde_json::json!({ "error": e.to_string() }),
+        Err(_) => serde_json::json!({ "error": "internal error: endpointo panicked" }),
+    };
+    // serde_json escapes control characters, so there is no NUL to reject
+    CString::new(json.to_string())
+        .expect("JSON has no NUL bytes")
+        .into_raw()
+}
+
+/// Crawl `url` and return its endpoints as JSON
+///
+/// Blocks until the scan finishes. `options_json` may be NULL.
+///
+/// # Safety
+///
+/// `url` and `options_json` must each be NULL or point to a NUL-terminated
+/// string. The result must be freed with [`endpointo_free_string`].
+#[no_mangle]
+pub unsafe extern "C" fn endpointo_scan_url_json(
+    url: *const c_char,
+    options_json: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let url = str_arg(url, "url")?
+            .ok_or_else(|| Error::ValidationError("url is NULL".to_string()))?;
+        let options = str_arg(options_json, "options")?;
+        let scanner = scanner(ScanConfig::new(url.to_string()), options)?;
+        runtime()?.block_on(scanner.scan_url(url))
+    })
+}
+
+/// Parse `len` bytes of in-memory content and return its endpoints as JSON
+///
+/// `name` stands in for a file name: it is the endpoints' source and its
+/// extension, if any, picks the parser. It and `options_json` may be NULL.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes (or be NULL when `len` is 0),
+/// and `name` and `options_json` must each be NULL or point to a
+/// NUL-terminated string. The result must be freed with
+/// [`endpointo_free_string`].
+#[no_mangle]
+pub unsafe extern "C" fn endpointo_parse_buffer_json(
+    name: *const c_char,
+    data: *const u8,
+    len: usize,
+    options_json: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let content = match (data.is_null(), len) {
+            (_, 0) => &[][..],
+            (true, _) => return Err(Error::ValidationError("data is NULL".to_string())),
+            (false, len) => std::slice::from_raw_parts(data, len),
+        };
+        let name = str_arg(name, "name")?.unwrap_or("buffer");
+        let options = str_arg(options_json, "options")?;
+        scanner(ScanConfig::default(), options)?.parse_bytes(name, content)
+    })
+}
+
+/// Free a string returned by endpointo; NULL is ignored
+///
+/// # Safety
+///
+/// `s` must be NULL or a string returned by this library, not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn endpointo_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// The library version, as a static string that must not be freed
+#[no_mangle]
+pub extern "C" fn endpointo_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    /// Take ownership of a returned string and parse it
+    fn take(s: *mut c_char) -> serde_json::Value {
+        assert!(!s.is_null());
+        let json = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+        unsafe { endpointo_free_string(s) };
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_parse_buffer_json() {
+        let js = b"fetch('/api/v1/users'); axios.post('/api/v1/login');";
+        let name = CString::new("app.js").unwrap();
+        // The options from the module docs
+        let options = CString::new(r#"{"profile": "aggressive", "rate_limit": 5}"#).unwrap();
+        let result = take(unsafe {
+            endpointo_parse_buffer_json(name.as_ptr(), js.as_ptr(), js.len(), options.as_ptr())
+        });
+        let urls: Vec<&str> = result["endpoints"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["url"].as_str().unwrap())
+            .collect();
+        assert!(urls.contains(&"/api/v1/users"));
+        assert!(urls.contains(&"/api/v1/login"));
+        assert_eq!(result["endpoints"][0]["source"], "app.js");
+    }
+
+    #[test]
+    fn test_errors_are_json() {
+        let options = CString::new(r#"{"rate_limit": "fast"}"#).unwrap();
+        let result = take(unsafe {
+            endpointo_parse_buffer_json(ptr::null(), b"x".as_ptr(), 1, options.as_ptr())
+        });
+        assert!(result["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("Invalid input: invalid options"));
+
+        let result = take(unsafe { endpointo_scan_url_json(ptr::null(), ptr::null()) });
+        assert!(result["error"].as_str().unwrap().contains("url is NULL"));
+
+        let result =
+            take(unsafe { endpointo_parse_buffer_json(ptr::null(), ptr::null(), 4, ptr::null()) });
+        assert!(result["error"].as_str().unwrap().contains("data is NULL"));
+    }
+
+    #[test]
+    fn test_version() {
+        let version = unsafe { CStr::from_ptr(endpointo_version()) };
+        assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+    }
+}
@@ -0,0 +1,204 @@
+//! Technology fingerprinting from response headers, bundle signatures, and
+//! well-known paths
+
+use crate::types::{TechCategory, Technology};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// A technology recognized by a substring or regex in some input
+struct Signature {
+    name: &'static str,
+    category: TechCategory,
+    /// Matched against the input; capture group 1, if any, is the version
+    pattern: Regex,
+    evidence: &'static str,
+}
+
+impl Signature {
+    fn new(
+        name: &'static str,
+        category: TechCategory,
+        pattern: &str,
+        evidence: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            category,
+            pattern: Regex::new(pattern).unwrap(),
+            evidence,
+        }
+    }
+
+    /// Technology found in `input`, if the signature matches
+    fn detect(&self, input: &str, source: Option<&str>) -> Option<Technology> {
+        let caps = self.pattern.captures(input)?;
+        Some(Technology {
+            name: self.name.to_string(),
+            category: self.category,
+            version: caps.get(1).map(|m| m.as_str().to_string()),
+            evidence: self.evidence.to_string(),
+            source: source.map(str::to_string),
+        })
+    }
+}
+
+lazy_static! {
+    // Keyed by lowercase header name; a header's presence is enough for `.`
+    static ref HEADER_SIGNATURES: Vec<(&'static str, Signature)> = {
+        use TechCategory::*;
+        vec![
+            ("server", Signature::new("nginx", Server, r"(?i)\bnginx(?:/([\d.]+))?", "server header")),
+            ("server", Signature::new("OpenResty", Server, r"(?i)\bopenresty(?:/([\d.]+))?", "server header")),
+            ("server", Signature::new("Apache", Server, r"(?i)\bapache(?:/([\d.]+))?", "server header")),
+            ("server", Signature::new("Microsoft IIS", Server, r"(?i)\bmicrosoft-iis(?:/([\d.]+))?", "server header")),
+            ("server", Signature::new("Caddy", Server, r"(?i)\bcaddy\b", "server header")),
+            ("server", Signature::new("Envoy", Server, r"(?i)\benvoy\b", "server header")),
+            ("server", Signature::new("Cloudflare", Cdn, r"(?i)\bcloudflare\b", "server header")),
+            ("cf-ray", Signature::new("Cloudflare", Cdn, r".", "cf-ray header")),
+            ("x-amz-cf-id", Signature::new("Amazon CloudFront", Cdn, r".", "x-amz-cf-id header")),
+            ("via", Signature::new("Amazon CloudFront", Cdn, r"(?i)cloudfront", "via header")),
+            ("x-vercel-id", Signature::new("Vercel", Cdn, r".", "x-vercel-id header")),
+            ("x-served-by", Signature::new("Fastly", Cdn, r"(?i)cache-", "x-served-by header")),
+            ("x-akamai-transformed", Signature::new("Akamai", Cdn, r".", "x-akamai-transformed header")),
+            ("x-powered-by", Signature::new("Express", Server, r"(?i)\bexpress\b", "x-powered-by header")),
+            ("x-powered-by", Signature::new("Next.js", Framework, r"(?i)\bnext\.js(?:\s+([\d.]+))?", "x-powered-by header")),
+            ("x-powered-by", Signature::new("PHP", Server, r"(?i)\bphp(?:/([\d.]+))?", "x-powered-by header")),
+            ("x-powered-by", Signature::new("ASP.NET", Server, r"(?i)\basp\.net\b", "x-powered-by header")),
+            ("x-nextjs-cache", Signature::new("Next.js", Framework, r".", "x-nextjs-cache header")),
+            ("x-hasura-role", Signature::new("Hasura", GraphQL, r".", "x-hasura-role header")),
+        ]
+    };
+
+    static ref CONTENT_SIGNATURES: Vec<Signature> = {
+        use TechCategory::*;
+        vec![
+            Signature::new("Next.js", Framework, r"__NEXT_DATA__|/_next/static/", "__NEXT_DATA__"),
+            Signature::new("Nuxt.js", Framework, r"__NUXT__|/_nuxt/", "__NUXT__"),
+            Signature::new("Angular", Framework, r#"ng-version="([\d.]+)""#, "ng-version attribute"),
+            Signature::new("Angular", Framework, r"ɵɵdefineComponent|@angular/core", "Angular runtime"),
+            Signature::new("React", Framework, r"__REACT_DEVTOOLS_GLOBAL_HOOK__|_reactRootContainer|data-reactroot|react\.production\.min", "React runtime"),
+            Signature::new("Vue.js", Framework, r"__VUE__|__vue_app__|__VUE_DEVTOOLS_GLOBAL_HOOK__", "Vue runtime"),
+            Signature::new("Svelte", Framework, r"__svelte|svelte-[a-z0-9]{6}\b", "Svelte runtime"),
+            Signature::new("jQuery", Framework, r"jQuery (?:JavaScript Library )?v([\d.]+)", "jQuery banner"),
+            Signature::new("Apollo Client", GraphQL, r"__APOLLO_STATE__|ApolloClient|__APOLLO_CLIENT__", "Apollo Client"),
+            Signature::new("Relay", GraphQL, r"RelayModernEnvironment|__RELAY_STORE__", "Relay runtime"),
+            Signature::new("urql", GraphQL, r"@urql/core|\burql\b", "urql client"),
+            Signature::new("WordPress", Cms, r"/wp-content/|/wp-includes/", "wp-content path"),
+        ]
+    };
+
+    static ref PATH_SIGNATURES: Vec<Signature> = {
+        use TechCategory::*;
+        vec![
+            Signature::new("Next.js", Framework, r"/_next/", "/_next/ path"),
+            Signature::new("Nuxt.js", Framework, r"/_nuxt/", "/_nuxt/ path"),
+            Signature::new("WordPress", Cms, r"/wp-(?:content|includes|json)(?:/|$)", "/wp-* path"),
+            Signature::new("GraphQL", GraphQL, r"(?i)/graphql(?:$|[/?])", "/graphql endpoint"),
+            Signature::new("Hasura", GraphQL, r"/v1/graphql(?:$|[/?])", "/v1/graphql endpoint"),
+        ]
+    };
+}
+
+/// Detects frameworks and infrastructure from what a scan sees
+pub struct Fingerprinter;
+
+impl Fingerprinter {
+    /// Create a new fingerprinter
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Technologies revealed by response headers (lowercase names)
+    pub fn from_headers(
+        &self,
+        headers: &HashMap<String, String>,
+        source: Option<&str>,
+    ) -> Vec<Technology> {
+        let mut found = Vec::new();
+        for (header, signature) in HEADER_SIGNATURES.iter() {
+            if let Some(value) = headers.get(*header) {
+                merge(&mut found, signature.detect(value, source));
+            }
+        }
+        found
+    }
+
+    /// Technologies revealed by signatures in page or bundle content
+    pub fn from_content(&self, content: &str, source: Option<&str>) -> Vec<Technology> {
+        let mut found = Vec::new();
+        for signature in CONTENT_SIGNATURES.iter() {
+            merge(&mut found, signature.detect(content, source));
+        }
+        found
+    }
+
+    /// Technologies revealed by well-known asset or endpoint paths
+    pub fn from_url(&self, url: &str) -> Vec<Technology> {
+        let mut found = Vec::new();
+        for signature in PATH_SIGNATURES.iter() {
+            merge(&mut found, signature.detect(url, Some(url)));
+        }
+        found
+    }
+}
+
+impl Default for Fingerprinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Add a technology unless it's already known, filling in a missing version
+pub fn merge(technologies: &mut Vec<Technology>, technology: Option<Technology>) {
+    let Some(technology) = technology else {
+        return;
+    };
+    match technologies.iter_mut().find(|t| t.name == technology.name) {
+        Some(known) => {
+            if known.version.is_none() && technology.version.is_some() {
+                known.version = technology.version;
+                known.evidence = technology.evidence;
+                known.source = technology.source;
+            }
+        }
+        None => technologies.push(technology),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprints() {
+        let fingerprinter = Fingerprinter::new();
+
+        let headers: HashMap<String, String> = [
+            ("server", "nginx/1.25.3"),
+            ("cf-ray", "8a1b2c3d4e5f-AMS"),
+            ("x-powered-by", "Next.js"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let found = fingerprinter.from_headers(&headers, Some("https://example.com/"));
+        let names: Vec<_> = found.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["nginx", "Cloudflare", "Next.js"]);
+        assert_eq!(found[0].version.as_deref(), Some("1.25.3"));
+
+        let html = r#"<app-root ng-version="17.3.1"></app-root><script>ApolloClient</script>"#;
+        let found = fingerprinter.from_content(html, None);
+        assert_eq!(found[0].name, "Angular");
+        assert_eq!(found[0].version.as_deref(), Some("17.3.1"));
+        assert_eq!(found[1].category, TechCategory::GraphQL);
+
+        assert_eq!(
+            fingerprinter.from_url("https://example.com/graphql")[0].name,
+            "GraphQL"
+        );
+        assert!(fingerprinter
+            .from_url("https://example.com/graphqlish")
+            .is_empty());
+    }
+}
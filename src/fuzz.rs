@@ -0,0 +1,353 @@
+//! Wordlist-driven discovery of paths no asset mentions
+//!
+//! `endpointo fuzz` joins words onto the target's directory and onto the
+//! directories of endpoints an earlier scan found, then probes each
+//! candidate. Words come from a built-in list of common API and admin
+//! paths, user wordlists, and the path segments and parameter names mined
+//! from the earlier results. Candidates out of scope, disallowed by
+//! robots.txt, or already known are never requested, and soft 404s are
+//! told apart from hits the way `probe` does.
+
+use crate::config::ScanConfig;
+use crate::crawler::client::{robots_allows, HttpClient};
+use crate::error::Result;
+use crate::output::file::read_to_string;
+use crate::parser::params::build_wordlist;
+use crate::parser::patterns::PatternMatcher;
+use crate::probe::{probe_target, Prober};
+use crate::scanner::endpoint_stats;
+use crate::scope::Scope;
+use crate::types::{Endpoint, ScanResult, SCHEMA_VERSION, TOOL_VERSION};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeSet, HashSet};
+use std::path::PathBuf;
+use tracing::info;
+use url::{Position, Url};
+
+/// Candidates probed per run unless told otherwise
+pub const DEFAULT_MAX_REQUESTS: usize = 5000;
+
+/// Common API, documentation and admin paths
+pub const BUILTIN_WORDS: &[&str] = &[
+    ".env",
+    ".git/config",
+    ".well-known/openid-configuration",
+    ".well-known/security.txt",
+    "_debug",
+    "account",
+    "actuator",
+    "actuator/env",
+    "actuator/health",
+    "actuator/mappings",
+    "admin",
+    "api",
+    "api-docs",
+    "api/docs",
+    "api/swagger.json",
+    "api/v1",
+    "api/v2",
+    "auth",
+    "backup",
+    "config",
+    "config.json",
+    "console",
+    "dashboard",
+    "debug",
+    "docs",
+    "env",
+    "graphiql",
+    "graphql",
+    "health",
+    "healthz",
+    "info",
+    "internal",
+    "login",
+    "metrics",
+    "oauth",
+    "openapi.json",
+    "openapi.yaml",
+    "ping",
+    "private",
+    "readyz",
+    "redoc",
+    "register",
+    "rest",
+    "rpc",
+    "server-status",
+    "settings",
+    "status",
+    "swagger",
+    "swagger-ui",
+    "swagger-ui.html",
+    "swagger.json",
+    "test",
+    "token",
+    "upload",
+    "users",
+    "v1",
+    "v2",
+    "v3",
+    "version",
+    "ws",
+];
+
+/// Tag put on endpoints found by fuzzing
+pub const FUZZ_TAG: &str = "fuzz";
+
+/// Read a wordlist: one word per line, skipping blanks and `#` comments
+pub fn load_wordlist(path: &std::path::Path) -> Result<Vec<String>> {
+    Ok(read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Every word to try: the built-in list if wanted, the wordlist files, and
+/// the words mined from known endpoints, sorted and deduplicated
+pub fn build_words(
+    builtin: bool,
+    wordlists: &[PathBuf],
+    known: &[Endpoint],
+) -> Result<Vec<String>> {
+    let mut words: BTreeSet<String> = BTreeSet::new();
+    if builtin {
+        words.extend(BUILTIN_WORDS.iter().map(|w| w.to_string()));
+    }
+    for path in wordlists {
+        words.extend(load_wordlist(path)?);
+    }
+    words.extend(build_wordlist(known));
+    Ok(words.into_iter().filter_map(|w| clean_word(&w)).collect())
+}
+
+/// A word as a relative path, or `None` if it can't be one
+fn clean_word(word: &str) -> Option<String> {
+    let word = word.trim().trim_start_matches('/');
+    let unusable = word.is_empty()
+        || word.contains(char::is_whitespace)
+        || word.contains(['?', '#', '\\'])
+        || word.contains("://")
+        || word.split('/').any(|s| s == "..");
+    (!unusable).then(|| word.to_string())
+}
+
+/// The directory a URL is in, without query or fragment
+fn directory(url: &Url) -> Url {
+    let mut dir = url.join("./").unwrap_or_else(|_| url.clone());
+    dir.set_query(None);
+    dir.set_fragment(None);
+    dir
+}
+
+/// Brute-forces paths on a target and keeps the ones that exist
+pub struct Fuzzer {
+    prober: Prober,
+    client: HttpClient,
+    scope: Scope,
+    /// For its robots.txt settings, which can differ by host
+    config: ScanConfig,
+    max_requests: usize,
+}
+
+impl Fuzzer {
+    /// Fuzz with the config's rate limit, timeout, concurrency, auth,
+    /// proxy, scope and robots.txt setting
+    pub fn new(config: &ScanConfig) -> Result<Self> {
+        Ok(Self {
+            prober: Prober::new(config)?,
+            client: HttpClient::new(config)?,
+            scope: Scope::from_config(config)?,
+            config: config.clone(),
+            max_requests: DEFAULT_MAX_REQUESTS,
+        })
+    }
+
+    /// Probe at most this many candidates
+    pub fn with_max_requests(mut self, max: Option<usize>) -> Self {
+        if let Some(max) = max {
+            self.max_requests = max;
+        }
+        self
+    }
+
+    /// Whether to check the target for soft 404s first
+    pub fn with_soft_404(mut self, detect: bool) -> Self {
+        self.prober = self.prober.with_soft_404(detect);
+        self
+    }
+
+    /// Number of HTTP requests sent so far
+    pub fn requests_made(&self) -> usize {
+        self.prober.requests_made() + self.client.requests_made()
+    }
+
+    /// URLs to probe: each word under the target's directory, then under
+    /// the directories of known endpoints on the target's origin
+    ///
+    /// Known URLs, URLs out of scope and URLs `robots` disallows are left
+    /// out, and the list stops at the request limit.
+    pub fn candidates(
+        &self,
+        target: &Url,
+        words: &[String],
+        known: &[Endpoint],
+        robots: Option<&str>,
+    ) -> Vec<Url> {
+        let origin = target.origin();
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut dirs: BTreeSet<String> = BTreeSet::new();
+        for endpoint in known {
+            if let Some(url) = probe_target(endpoint, Some(target.as_str())) {
+                if url.origin() == origin {
+                    seen.insert(url[..Position::AfterPath].to_string());
+                    dirs.insert(directory(&url).to_string());
+                }
+            }
+        }
+
+        let first = directory(target);
+        dirs.remove(first.as_str());
+        let bases =
+            std::iter::once(first).chain(dirs.iter().filter_map(|dir| Url::parse(dir).ok()));
+
+        let mut candidates = Vec::new();
+        for base in bases {
+            for word in words {
+                if candidates.len() >= self.max_requests {
+                    return candidates;
+                }
+                let Ok(url) = base.join(word) else { continue };
+                if url.origin() != origin
+                    || !seen.insert(url[..Position::AfterPath].to_string())
+                    || !self.scope.allows(url.as_str())
+                    || robots.is_some_and(|content| !robots_allows(content, &url))
+                {
+                    continue;
+                }
+                candidates.push(url);
+            }
+        }
+        candidates
+    }
+
+    /// Probe candidate paths on `target` and return the ones that exist,
+    /// tagged `fuzz`
+    pub async fn fuzz(
+        &self,
+        target: &str,
+        words: &[String],
+        known: &[Endpoint],
+    ) -> Result<Vec<Endpoint>> {
+        let target = Url::parse(target)?;
+        self.client.add_target(target.as_str());
+        let robots = if self.config.respects_robots(&target) {
+            self.client.robots_txt(&target).await?
+        } else {
+            None
+        };
+        let candidates = self.candidates(&target, words, known, robots.as_deref());
+        info!("Fuzzing {} candidate paths on {}", candidates.len(), target);
+
+        let matcher = PatternMatcher::new();
+        let mut endpoints: Vec<Endpoint> = candidates
+            .iter()
+            .map(|url| {
+                Endpoint::new(
+                    url.to_string(),
+                    matcher.detect_endpoint_type(url.as_str(), ""),
+                )
+                .with_tag(FUZZ_TAG)
+            })
+            .collect();
+        self.prober
+            .probe_all(&mut endpoints, Some(target.as_str()))
+            .await;
+        endpoints.retain(|e| e.probe.as_ref().is_some_and(|p| p.is_live()));
+        Ok(endpoints)
+    }
+}
+
+/// A scan result of fuzzing hits, merged into the earlier results they
+/// were mined from if there are any
+pub fn fuzz_result(
+    target: &str,
+    base: Option<ScanResult>,
+    started: DateTime<Utc>,
+    hits: Vec<Endpoint>,
+    requests_made: usize,
+) -> ScanResult {
+    let mut result = base.unwrap_or_else(|| ScanResult {
+        schema_version: SCHEMA_VERSION,
+        tool_version: TOOL_VERSION.to_string(),
+        target: target.to_string(),
+        timestamp: String::new(),
+        total_endpoints: 0,
+        endpoints: Vec::new(),
+        findings: Vec::new(),
+        wordlist: Vec::new(),
+        technologies: Vec::new(),
+        stats: None,
+    });
+    if result.target.is_empty() {
+        result.target = target.to_string();
+    }
+    result.endpoints.extend(hits);
+
+    let mut stats = endpoint_stats(&result.endpoints);
+    stats.requests_made = requests_made;
+    stats.duration_seconds = (Utc::now() - started)
+        .to_std()
+        .unwrap_or_default()
+        .as_secs_f64();
+    result.timestamp = started.to_rfc3339();
+    result.total_endpoints = result.endpoints.len();
+    result.wordlist = build_wordlist(&result.endpoints);
+    result.stats = Some(stats);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EndpointType;
+
+    #[test]
+    fn test_candidates() {
+        let config = ScanConfig::default().with_exclude_regex(vec!["/private".to_string()]);
+        let fuzzer = Fuzzer::new(&config).unwrap().with_max_requests(Some(6));
+        let target = Url::parse("https://example.com/app/index.html?x=1").unwrap();
+        let known = vec![
+            Endpoint::new("/api/v1/users".to_string(), EndpointType::Rest),
+            Endpoint::new("/app/health".to_string(), EndpointType::Rest),
+            Endpoint::new(
+                "https://cdn.example.net/lib/x".to_string(),
+                EndpointType::Rest,
+            ),
+        ];
+        let words: Vec<String> = ["health", "private", "admin", "../etc", "/status"]
+            .iter()
+            .filter_map(|w| clean_word(w))
+            .collect();
+
+        let robots = "User-agent: *\nDisallow: /app/admin\n";
+        let urls: Vec<String> = fuzzer
+            .candidates(&target, &words, &known, Some(robots))
+            .iter()
+            .map(Url::to_string)
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://example.com/app/status",
+                "https://example.com/api/v1/health",
+                "https://example.com/api/v1/admin",
+                "https://example.com/api/v1/status",
+            ]
+        );
+
+        let capped = Fuzzer::new(&config).unwrap().with_max_requests(Some(2));
+        assert_eq!(capped.candidates(&target, &words, &known, None).len(), 2);
+    }
+}
@@ -0,0 +1,191 @@
+//! Conditions that fail a scan, for gating CI pipelines
+
+use crate::diff::diff_endpoints;
+use crate::error::{Error, Result};
+use crate::types::{Endpoint, FindingKind, ScanResult};
+use regex::Regex;
+
+/// Why a scan failed its gate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// Endpoints appeared that the baseline didn't have
+    NewEndpoints,
+    /// An endpoint matched a forbidden pattern
+    ForbiddenMatch,
+    /// A secret was found
+    Secrets,
+}
+
+impl FailureKind {
+    /// Process exit code for this failure; 1 stays for errors and 2 for
+    /// usage mistakes
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            FailureKind::NewEndpoints => 3,
+            FailureKind::ForbiddenMatch => 4,
+            FailureKind::Secrets => 5,
+        }
+    }
+}
+
+/// A failed condition with a human-readable reason
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub kind: FailureKind,
+    pub reason: String,
+}
+
+/// Conditions under which a scan fails
+///
+/// Nothing fails a scan unless a condition is set.
+#[derive(Debug, Clone, Default)]
+pub struct FailOn {
+    baseline: Option<Vec<Endpoint>>,
+    patterns: Vec<Regex>,
+    secrets: bool,
+}
+
+impl FailOn {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail when endpoints appear that aren't in these baseline endpoints
+    pub fn with_baseline(mut self, baseline: Option<Vec<Endpoint>>) -> Self {
+        if let Some(b) = baseline {
+            self.baseline = Some(b);
+        }
+        self
+    }
+
+    /// Fail when an endpoint URL matches one of these regexes
+    pub fn with_patterns(mut self, patterns: &[String]) -> Result<Self> {
+        for pattern in patterns {
+            self.patterns.push(Regex::new(pattern).map_err(|e| {
+                Error::ValidationError(format!(
+                    "invalid --fail-on-match regex '{}': {}",
+                    pattern, e
+                ))
+            })?);
+        }
+        Ok(self)
+    }
+
+    /// Fail when a secret is found
+    pub fn with_secrets(mut self, enabled: bool) -> Self {
+        self.secrets = enabled;
+        self
+    }
+
+    /// Whether no condition is set
+    pub fn is_empty(&self) -> bool {
+        self.baseline.is_none() && self.patterns.is_empty() && !self.secrets
+    }
+
+    /// Every condition a result fails, in the order new endpoints,
+    /// forbidden matches, secrets
+    pub fn check(&self, result: &ScanResult) -> Vec<Failure> {
+        let mut failures = Vec::new();
+
+        if let Some(baseline) = &self.baseline {
+            let added = diff_endpoints(baseline, &result.endpoints).added;
+            if !added.is_empty() {
+                failures.push(Failure {
+                    kind: FailureKind::NewEndpoints,
+                    reason: format!(
+                        "{} new endpoints since the baseline, e.g. {}",
+                        added.len(),
+                        added[0].url
+                    ),
+                });
+            }
+        }
+
+        if !self.patterns.is_empty() {
+            let matched: Vec<&Endpoint> = result
+                .endpoints
+                .iter()
+                .filter(|e| self.patterns.iter().any(|re| re.is_match(&e.url)))
+                .collect();
+            if !matched.is_empty() {
+                failures.push(Failure {
+                    kind: FailureKind::ForbiddenMatch,
+                    reason: format!(
+                        "{} endpoints match a forbidden pattern, e.g. {}",
+                        matched.len(),
+                        matched[0].url
+                    ),
+                });
+            }
+        }
+
+        if self.secrets {
+            let secrets = result
+                .findings
+                .iter()
+                .filter(|f| f.kind == FindingKind::Secret)
+                .count();
+            if secrets > 0 {
+                failures.push(Failure {
+                    kind: FailureKind::Secrets,
+                    reason: format!("{} secrets found", secrets),
+                });
+            }
+        }
+
+        failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EndpointType, Finding, SCHEMA_VERSION, TOOL_VERSION};
+
+    fn result(urls: &[&str]) -> ScanResult {
+        let endpoints: Vec<Endpoint> = urls
+            .iter()
+            .map(|url| Endpoint::new(url.to_string(), EndpointType::Rest))
+            .collect();
+        ScanResult {
+            schema_version: SCHEMA_VERSION,
+            tool_version: TOOL_VERSION.to_string(),
+            target: "https://example.com".to_string(),
+            timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+            total_endpoints: endpoints.len(),
+            endpoints,
+            findings: Vec::new(),
+            wordlist: Vec::new(),
+            technologies: Vec::new(),
+            stats: None,
+        }
+    }
+
+    #[test]
+    fn test_fail_on_conditions() {
+        let mut scanned = result(&["/api/users", "/api/debug/vars"]);
+        assert!(FailOn::new().check(&scanned).is_empty());
+
+        let gate = FailOn::new()
+            .with_baseline(Some(result(&["/api/users"]).endpoints))
+            .with_patterns(&["/debug/".to_string()])
+            .unwrap()
+            .with_secrets(true);
+        let kinds: Vec<FailureKind> = gate.check(&scanned).iter().map(|f| f.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![FailureKind::NewEndpoints, FailureKind::ForbiddenMatch]
+        );
+
+        scanned
+            .findings
+            .push(Finding::new(FindingKind::Secret, "AKIAIOSF…MPLE"));
+        let failures = gate.check(&scanned);
+        assert_eq!(failures[2].kind, FailureKind::Secrets);
+        assert_eq!(failures[2].kind.exit_code(), 5);
+
+        let unchanged = FailOn::new().with_baseline(Some(scanned.endpoints.clone()));
+        assert!(unchanged.check(&scanned).is_empty());
+        assert!(FailOn::new().with_patterns(&["(".to_string()]).is_err());
+    }
+}
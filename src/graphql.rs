@@ -0,0 +1,533 @@
+//! GraphQL schema introspection
+//!
+//! `endpointo graphql` sends the standard introspection query to a GraphQL
+//! endpoint, renders the schema as SDL, and turns every query, mutation and
+//! subscription field into a typed endpoint. Operations share the
+//! endpoint's URL, so each is told apart by a fragment naming it
+//! (`https://api.example.com/graphql#query.users`).
+
+use crate::error::{Error, Result};
+use crate::parser::params::build_wordlist;
+use crate::scanner::endpoint_stats;
+use crate::types::{Endpoint, EndpointType, ScanResult, SCHEMA_VERSION, TOOL_VERSION};
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use url::Url;
+
+/// Seconds to wait for the introspection response unless told otherwise
+const DEFAULT_TIMEOUT: u64 = 30;
+
+/// The introspection query, deep enough for `[[Type!]!]!`
+pub const INTROSPECTION_QUERY: &str = r#"query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types {
+      kind
+      name
+      description
+      fields(includeDeprecated: true) {
+        name
+        description
+        args { name description type { ...TypeRef } defaultValue }
+        type { ...TypeRef }
+        isDeprecated
+      }
+      inputFields { name description type { ...TypeRef } defaultValue }
+      interfaces { ...TypeRef }
+      enumValues(includeDeprecated: true) { name }
+      possibleTypes { ...TypeRef }
+    }
+  }
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType {
+    kind
+    name
+    ofType {
+      kind
+      name
+      ofType {
+        kind
+        name
+        ofType {
+          kind
+          name
+          ofType { kind name ofType { kind name } }
+        }
+      }
+    }
+  }
+}"#;
+
+/// Scalars every schema has, left out of rendered SDL
+const BUILTIN_SCALARS: [&str; 5] = ["String", "Int", "Float", "Boolean", "ID"];
+
+/// An introspected schema
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schema {
+    pub query_type: Option<NamedType>,
+    pub mutation_type: Option<NamedType>,
+    pub subscription_type: Option<NamedType>,
+    pub types: Vec<FullType>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NamedType {
+    pub name: String,
+}
+
+/// A type and its fields, input fields or values
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FullType {
+    pub kind: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub fields: Option<Vec<Field>>,
+    pub input_fields: Option<Vec<InputValue>>,
+    pub interfaces: Option<Vec<TypeRef>>,
+    pub enum_values: Option<Vec<NamedType>>,
+    pub possible_types: Option<Vec<TypeRef>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Field {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub args: Vec<InputValue>,
+    #[serde(rename = "type")]
+    pub field_type: TypeRef,
+    #[serde(default)]
+    pub is_deprecated: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputValue {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub value_type: TypeRef,
+    pub default_value: Option<String>,
+}
+
+/// A reference to a type, possibly wrapped in lists and non-null markers
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeRef {
+    pub kind: String,
+    pub name: Option<String>,
+    pub of_type: Option<Box<TypeRef>>,
+}
+
+impl std::fmt::Display for TypeRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.kind.as_str(), &self.of_type) {
+            ("NON_NULL", Some(inner)) => write!(f, "{}!", inner),
+            ("LIST", Some(inner)) => write!(f, "[{}]", inner),
+            _ => f.write_str(self.name.as_deref().unwrap_or("?")),
+        }
+    }
+}
+
+impl Schema {
+    fn get(&self, name: &str) -> Option<&FullType> {
+        self.types.iter().find(|t| t.name.as_deref() == Some(name))
+    }
+
+    /// Root operation types present in the schema, with their operation names
+    fn roots(&self) -> Vec<(&'static str, &FullType)> {
+        [
+            ("query", &self.query_type),
+            ("mutation", &self.mutation_type),
+            ("subscription", &self.subscription_type),
+        ]
+        .into_iter()
+        .filter_map(|(operation, root)| Some((operation, self.get(&root.as_ref()?.name)?)))
+        .collect()
+    }
+}
+
+pub use crate::crawler::client::parse_header;
+use crate::crawler::client::{parse_cookies, parse_proxy};
+
+/// Client introspecting a GraphQL endpoint
+pub struct Introspector {
+    url: Url,
+    headers: HeaderMap,
+    proxy: Option<reqwest::Proxy>,
+    timeout: Duration,
+}
+
+impl Introspector {
+    /// Introspect the GraphQL endpoint at `url`
+    pub fn new(url: &str) -> Result<Self> {
+        let url = Url::parse(url)?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(Error::ValidationError(format!(
+                "GraphQL URL must be http(s): {}",
+                url
+            )));
+        }
+        Ok(Self {
+            url,
+            headers: HeaderMap::new(),
+            proxy: None,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT),
+        })
+    }
+
+    /// Send these `Name: Value` headers, e.g. for authentication
+    pub fn with_headers(mut self, headers: &[String]) -> Result<Self> {
+        for header in headers {
+            let (name, value) = parse_header(header)?;
+            self.headers.append(name, value);
+        }
+        Ok(self)
+    }
+
+    /// Send these `name=value; name=value` cookie strings, joined into one
+    /// `Cookie` header
+    pub fn with_cookies(mut self, cookies: &[String]) -> Result<Self> {
+        if !cookies.is_empty() {
+            let value = parse_cookies(&cookies.join("; "))?;
+            self.headers.insert(reqwest::header::COOKIE, value);
+        }
+        Ok(self)
+    }
+
+    /// Send the request through this proxy, with optional `user:password`
+    /// credentials
+    pub fn with_proxy(mut self, proxy: Option<&str>, auth: Option<&str>) -> Result<Self> {
+        if let Some(proxy) = proxy {
+            self.proxy = Some(parse_proxy(proxy, auth)?);
+        }
+        Ok(self)
+    }
+
+    /// Give up on the response after this many seconds
+    pub fn with_timeout(mut self, seconds: Option<u64>) -> Self {
+        if let Some(s) = seconds {
+            self.timeout = Duration::from_secs(s);
+        }
+        self
+    }
+
+    /// The endpoint being introspected
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Run the introspection query
+    ///
+    /// Servers with introspection disabled usually answer with an `errors`
+    /// list and no data; the first message is returned as the error.
+    pub async fn introspect(&self) -> Result<Schema> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .default_headers(self.headers.clone());
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        let client = builder.build()?;
+        let response = client
+            .post(self.url.clone())
+            .json(&serde_json::json!({
+                "query": INTROSPECTION_QUERY,
+                "operationName": "IntrospectionQuery",
+            }))
+            .send()
+            .await?;
+        let status = response.status();
+        let body: IntrospectionResponse = response.json().await.map_err(|e| {
+            Error::Other(format!(
+                "GraphQL endpoint returned {} without a JSON body: {}",
+                status, e
+            ))
+        })?;
+        match body.data {
+            Some(data) => Ok(data.schema),
+            None => Err(Error::Other(format!(
+                "introspection failed: {}",
+                body.errors
+                    .first()
+                    .map(|e| e.message.as_str())
+                    .unwrap_or("no schema returned")
+            ))),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct IntrospectionResponse {
+    data: Option<IntrospectionData>,
+    #[serde(default)]
+    errors: Vec<GraphQlError>,
+}
+
+#[derive(Deserialize)]
+struct IntrospectionData {
+    #[serde(rename = "__schema")]
+    schema: Schema,
+}
+
+#[derive(Deserialize)]
+struct GraphQlError {
+    message: String,
+}
+
+/// An endpoint per root operation field
+///
+/// Each carries its operation (`query`, `mutation`, `subscription`), field,
+/// signature and return type in metadata, its argument names as
+/// parameters, and a `deprecated` tag when deprecated.
+pub fn operation_endpoints(schema: &Schema, url: &str) -> Vec<Endpoint> {
+    let url = url.split('#').next().unwrap_or(url);
+    let mut endpoints = Vec::new();
+    for (operation, root) in schema.roots() {
+        for field in root.fields.iter().flatten() {
+            let mut metadata = HashMap::from([
+                ("operation".to_string(), operation.to_string()),
+                ("field".to_string(), field.name.clone()),
+                ("signature".to_string(), signature(field)),
+                ("returns".to_string(), field.field_type.to_string()),
+            ]);
+            if let Some(description) = &field.description {
+                metadata.insert("description".to_string(), description.clone());
+            }
+            let mut endpoint = Endpoint::new(
+                format!("{}#{}.{}", url, operation, field.name),
+                EndpointType::GraphQL,
+            )
+            .with_method("POST")
+            .with_metadata(metadata);
+            if !field.args.is_empty() {
+                endpoint =
+                    endpoint.with_params(field.args.iter().map(|a| a.name.clone()).collect());
+            }
+            if field.is_deprecated {
+                endpoint.tags = Some(vec!["deprecated".to_string()]);
+            }
+            endpoints.push(endpoint);
+        }
+    }
+    endpoints
+}
+
+/// A scan result holding a schema's operations, as found at `url`
+pub fn schema_result(url: &str, started: DateTime<Utc>, schema: &Schema) -> ScanResult {
+    let endpoints = operation_endpoints(schema, url);
+    let mut stats = endpoint_stats(&endpoints);
+    stats.requests_made = 1;
+    stats.duration_seconds = (Utc::now() - started)
+        .to_std()
+        .unwrap_or_default()
+        .as_secs_f64();
+
+    ScanResult {
+        schema_version: SCHEMA_VERSION,
+        tool_version: TOOL_VERSION.to_string(),
+        target: url.to_string(),
+        timestamp: started.to_rfc3339(),
+        total_endpoints: endpoints.len(),
+        findings: Vec::new(),
+        wordlist: build_wordlist(&endpoints),
+        technologies: Vec::new(),
+        stats: Some(stats),
+        endpoints,
+    }
+}
+
+/// A field as written in SDL: `users(first: Int = 10): [User!]!`
+fn signature(field: &Field) -> String {
+    if field.args.is_empty() {
+        return format!("{}: {}", field.name, field.field_type);
+    }
+    let args: Vec<String> = field.args.iter().map(input_value).collect();
+    format!("{}({}): {}", field.name, args.join(", "), field.field_type)
+}
+
+fn input_value(value: &InputValue) -> String {
+    match &value.default_value {
+        Some(default) => format!("{}: {} = {}", value.name, value.value_type, default),
+        None => format!("{}: {}", value.name, value.value_type),
+    }
+}
+
+/// Render a schema as SDL, root types first
+///
+/// Introspection types and built-in scalars are left out.
+pub fn render_sdl(schema: &Schema) -> String {
+    let roots: Vec<&str> = schema
+        .roots()
+        .iter()
+        .filter_map(|(_, t)| t.name.as_deref())
+        .collect();
+    let mut types: Vec<&FullType> = schema
+        .types
+        .iter()
+        .filter(|t| {
+            t.name
+                .as_deref()
+                .is_some_and(|n| !n.starts_with("__") && !BUILTIN_SCALARS.contains(&n))
+        })
+        .collect();
+    types.sort_by_key(|t| {
+        let name = t.name.as_deref().unwrap_or_default();
+        (
+            roots.iter().position(|r| *r == name).unwrap_or(roots.len()),
+            name,
+        )
+    });
+
+    let blocks: Vec<String> = types.into_iter().map(render_type).collect();
+    let mut sdl = blocks.join("\n\n");
+    sdl.push('\n');
+    sdl
+}
+
+fn render_type(t: &FullType) -> String {
+    let name = t.name.as_deref().unwrap_or_default();
+    let mut out = String::new();
+    if let Some(description) = &t.description {
+        out.push_str(&format!("\"\"\"{}\"\"\"\n", description));
+    }
+    let keyword = match t.kind.as_str() {
+        "OBJECT" => "type",
+        "INTERFACE" => "interface",
+        "INPUT_OBJECT" => "input",
+        "ENUM" => "enum",
+        "UNION" => "union",
+        _ => "scalar",
+    };
+    out.push_str(&format!("{} {}", keyword, name));
+
+    match t.kind.as_str() {
+        "OBJECT" | "INTERFACE" => {
+            let interfaces: Vec<String> = t
+                .interfaces
+                .iter()
+                .flatten()
+                .map(|i| i.to_string())
+                .collect();
+            if !interfaces.is_empty() {
+                out.push_str(&format!(" implements {}", interfaces.join(" & ")));
+            }
+            let lines: Vec<String> = t
+                .fields
+                .iter()
+                .flatten()
+                .map(|f| {
+                    let deprecated = if f.is_deprecated { " @deprecated" } else { "" };
+                    format!("  {}{}", signature(f), deprecated)
+                })
+                .collect();
+            out.push_str(&format!(" {{\n{}\n}}", lines.join("\n")));
+        }
+        "INPUT_OBJECT" => {
+            let lines: Vec<String> = t
+                .input_fields
+                .iter()
+                .flatten()
+                .map(|v| format!("  {}", input_value(v)))
+                .collect();
+            out.push_str(&format!(" {{\n{}\n}}", lines.join("\n")));
+        }
+        "ENUM" => {
+            let lines: Vec<String> = t
+                .enum_values
+                .iter()
+                .flatten()
+                .map(|v| format!("  {}", v.name))
+                .collect();
+            out.push_str(&format!(" {{\n{}\n}}", lines.join("\n")));
+        }
+        "UNION" => {
+            let members: Vec<String> = t
+                .possible_types
+                .iter()
+                .flatten()
+                .map(|m| m.to_string())
+                .collect();
+            out.push_str(&format!(" = {}", members.join(" | ")));
+        }
+        _ => {}
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operations_and_sdl() {
+        let schema: Schema = serde_json::from_value(serde_json::json!({
+            "queryType": {"name": "Query"},
+            "mutationType": {"name": "Mutation"},
+            "subscriptionType": null,
+            "types": [
+                {"kind": "OBJECT", "name": "Query", "fields": [{
+                    "name": "users",
+                    "args": [{"name": "first", "type": {"kind": "SCALAR", "name": "Int"}, "defaultValue": "10"}],
+                    "type": {"kind": "NON_NULL", "ofType": {"kind": "LIST", "ofType":
+                        {"kind": "NON_NULL", "ofType": {"kind": "OBJECT", "name": "User"}}}}
+                }]},
+                {"kind": "OBJECT", "name": "Mutation", "fields": [{
+                    "name": "deleteUser",
+                    "args": [{"name": "id", "type": {"kind": "NON_NULL", "ofType": {"kind": "SCALAR", "name": "ID"}}}],
+                    "type": {"kind": "SCALAR", "name": "Boolean"},
+                    "isDeprecated": true
+                }]},
+                {"kind": "OBJECT", "name": "User", "fields": [
+                    {"name": "id", "args": [], "type": {"kind": "NON_NULL", "ofType": {"kind": "SCALAR", "name": "ID"}}},
+                    {"name": "role", "args": [], "type": {"kind": "ENUM", "name": "Role"}}
+                ]},
+                {"kind": "ENUM", "name": "Role", "enumValues": [{"name": "ADMIN"}, {"name": "USER"}]},
+                {"kind": "SCALAR", "name": "ID"},
+                {"kind": "OBJECT", "name": "__Schema", "fields": []}
+            ]
+        }))
+        .unwrap();
+
+        let endpoints = operation_endpoints(&schema, "https://example.com/graphql");
+        assert_eq!(endpoints.len(), 2);
+        assert_eq!(endpoints[0].url, "https://example.com/graphql#query.users");
+        let metadata = endpoints[0].metadata.as_ref().unwrap();
+        assert_eq!(metadata["signature"], "users(first: Int = 10): [User!]!");
+        assert_eq!(metadata["returns"], "[User!]!");
+        assert_eq!(
+            endpoints[1].metadata.as_ref().unwrap()["operation"],
+            "mutation"
+        );
+        assert_eq!(
+            endpoints[1].params.as_deref(),
+            Some(&["id".to_string()][..])
+        );
+        assert_eq!(
+            endpoints[1].tags.as_deref(),
+            Some(&["deprecated".to_string()][..])
+        );
+
+        assert_eq!(
+            render_sdl(&schema),
+            "type Query {\n  users(first: Int = 10): [User!]!\n}\n\n\
+             type Mutation {\n  deleteUser(id: ID!): Boolean @deprecated\n}\n\n\
+             enum Role {\n  ADMIN\n  USER\n}\n\n\
+             type User {\n  id: ID!\n  role: Role\n}\n"
+        );
+        assert!(parse_header("Authorization: Bearer abc").is_ok());
+        assert!(parse_header("no colon").is_err());
+    }
+}
@@ -0,0 +1,151 @@
+//! Expansion of `parse` inputs into the files to read
+//!
+//! Inputs can be files, directories (walked recursively), or glob patterns
+//! such as `static/**/*.js`, for shells that don't expand them.
+
+use crate::error::{Error, Result};
+use crate::parser::detect::{has_asset_extension, SNIFF_BYTES};
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+use walkdir::WalkDir;
+
+/// WebAssembly binary magic number
+const WASM_MAGIC: &[u8] = b"\0asm";
+
+/// Which files an input expands to
+#[derive(Debug, Clone, Default)]
+pub struct InputFilter {
+    /// Extensions to keep when walking directories (without the dot);
+    /// empty means every extension the parser recognizes
+    extensions: Vec<String>,
+
+    /// Files larger than this many bytes are skipped
+    max_file_bytes: Option<u64>,
+}
+
+impl InputFilter {
+    /// Create a filter keeping parseable assets of any size
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only keep these extensions when walking directories
+    pub fn with_extensions(mut self, extensions: Vec<String>) -> Self {
+        self.extensions = extensions
+            .into_iter()
+            .map(|e| e.trim_start_matches('.').to_lowercase())
+            .filter(|e| !e.is_empty())
+            .collect();
+        self
+    }
+
+    /// Skip files larger than this many bytes
+    pub fn with_max_file_bytes(mut self, max: Option<u64>) -> Self {
+        self.max_file_bytes = max;
+        self
+    }
+
+    /// Expand inputs into files, in input order without duplicates
+    ///
+    /// Directories are walked recursively, skipping hidden entries, and
+    /// only files with a matching extension are kept. Glob patterns keep
+    /// every match; wildcards don't match a leading dot. Oversized and binary files (other than WASM) are
+    /// skipped wherever they came from. Paths that don't exist are passed
+    /// through so reading them reports the error.
+    pub fn collect(&self, inputs: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        let mut seen = HashSet::new();
+        let mut keep = |path: PathBuf| {
+            if seen.insert(path.clone()) {
+                files.push(path);
+            }
+        };
+
+        for input in inputs {
+            if input.is_dir() {
+                let walker = WalkDir::new(input)
+                    .sort_by_file_name()
+                    .into_iter()
+                    .filter_entry(|e| e.depth() == 0 || !is_hidden(e.file_name()));
+                for entry in walker {
+                    let entry = entry.map_err(|e| Error::IoError(e.into()))?;
+                    let path = entry.path();
+                    if entry.file_type().is_file()
+                        && self.extension_matches(path)
+                        && self.readable(path)
+                    {
+                        keep(path.to_path_buf());
+                    }
+                }
+            } else if is_glob(input) {
+                let pattern = input.to_string_lossy();
+                let options = glob::MatchOptions {
+                    require_literal_leading_dot: true,
+                    ..Default::default()
+                };
+                let matches = glob::glob_with(&pattern, options)
+                    .map_err(|e| Error::ValidationError(format!("{}: {}", pattern, e)))?;
+                let mut matched = 0;
+                for path in matches.flatten() {
+                    matched += 1;
+                    if path.is_file() && self.readable(&path) {
+                        keep(path);
+                    }
+                }
+                if matched == 0 {
+                    warn!("No files match {}", pattern);
+                }
+            } else if !input.exists() || self.readable(input) {
+                keep(input.clone());
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Whether a walked file has one of the wanted extensions
+    fn extension_matches(&self, path: &Path) -> bool {
+        if self.extensions.is_empty() {
+            return has_asset_extension(&path.to_string_lossy());
+        }
+        path.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| self.extensions.contains(&e.to_lowercase()))
+    }
+
+    /// Whether a file is within the size limit and not binary
+    fn readable(&self, path: &Path) -> bool {
+        let Ok(metadata) = path.metadata() else {
+            return false;
+        };
+        if self.max_file_bytes.is_some_and(|max| metadata.len() > max) {
+            warn!("Skipping {}: {} bytes", path.display(), metadata.len());
+            return false;
+        }
+        if is_binary(path) {
+            debug!("Skipping binary file {}", path.display());
+            return false;
+        }
+        true
+    }
+}
+
+/// Check whether a path contains glob metacharacters
+fn is_glob(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?', '['])
+}
+
+/// Dotfiles and dot-directories such as `.git`
+fn is_hidden(name: &std::ffi::OsStr) -> bool {
+    name.to_str().is_some_and(|n| n.starts_with('.'))
+}
+
+/// Whether the head of a file has NUL bytes; WASM modules don't count
+fn is_binary(path: &Path) -> bool {
+    let mut head = Vec::new();
+    let read = std::fs::File::open(path)
+        .and_then(|file| file.take(SNIFF_BYTES as u64).read_to_end(&mut head));
+    read.is_ok() && !head.starts_with(WASM_MAGIC) && head.contains(&0)
+}
@@ -18,35 +18,58 @@
 //! ```no_run
 //! use endpointo::scanner::Scanner;
 //! use endpointo::config::ScanConfig;
-//! use endpointo::types::Endpoint;
+//! use endpointo::types::ScanResult;
 //!
 //! #[tokio::main]
 //! async fn main() -> anyhow::Result<()> {
 //!     let config = ScanConfig::new("https://example.com".to_string());
 //!     let scanner = Scanner::new(config)?;
 //!     
-//!     let results: Vec<Endpoint> = scanner.scan_url("https://example.com").await?;
-//!     println!("Found {} endpoints", results.len());
+//!     let result: ScanResult = scanner.scan().await?;
+//!     println!("Found {} endpoints", result.total_endpoints);
 //!     
 //!     Ok(())
 //! }
 //! ```
 
+pub mod classify;
 pub mod cli;
 pub mod config;
 pub mod crawler;
+pub mod diff;
+pub mod distributed;
+pub mod doctor;
 pub mod error;
+pub mod ffi;
+pub mod fingerprint;
+pub mod fuzz;
+pub mod gate;
+pub mod graphql;
+pub mod input;
+pub mod logging;
+pub mod observer;
 pub mod output;
 pub mod parser;
+pub mod passive;
+pub mod probe;
+pub mod report;
 pub mod scanner;
+pub mod scope;
+pub mod server;
+pub mod state;
+pub mod store;
 pub mod types;
+pub(crate) mod util;
+pub mod validate;
+pub mod watch;
 
 pub mod plugins;
 
-#[cfg(feature = "python-plugins")]
+#[cfg(feature = "python-bindings")]
 pub mod bridge;
 
 // Re-export commonly used types
 pub use error::{Error, Result};
-pub use scanner::Scanner;
-pub use types::{Endpoint, EndpointType, ScanResult};
+pub use observer::ScanObserver;
+pub use scanner::{Scanner, ScannerBuilder};
+pub use types::{Endpoint, EndpointType, Finding, ScanResult, Severity};
@@ -0,0 +1,233 @@
+//! Log filters and formats
+//!
+//! Logs go to stderr either as text or as JSON Lines, one object per event,
+//! for log collectors under orchestration. Which events are logged comes
+//! from layered directives: the default level, then `RUST_LOG`, then the
+//! verbosity flags, then `--log-filter`, with later directives replacing
+//! earlier ones for the same module.
+
+use crate::error::{Error, Result};
+use serde_json::{Map, Value};
+use std::fmt;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::filter::Directive;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::EnvFilter;
+
+/// Build the log filter
+///
+/// `verbosity` counts `-v` flags: one logs endpointo at debug, two logs it
+/// at trace and its libraries at debug. `quiet` keeps only warnings and
+/// errors. Directives in `env` (usually `RUST_LOG`) that don't parse are
+/// ignored; ones in `filters`, such as `endpointo::crawler=trace`, are
+/// errors.
+pub fn log_filter(
+    verbosity: u8,
+    quiet: bool,
+    env: Option<&str>,
+    filters: &[String],
+) -> Result<EnvFilter> {
+    let mut filter = EnvFilter::new("info");
+
+    for directive in env.unwrap_or("").split(',').map(str::trim) {
+        if let Ok(directive) = directive.parse::<Directive>() {
+            filter = filter.add_directive(directive);
+        }
+    }
+
+    let levels: &[&str] = match (quiet, verbosity) {
+        (true, _) => &["warn"],
+        (false, 0) => &[],
+        (false, 1) => &["endpointo=debug"],
+        (false, _) => &["debug", "endpointo=trace"],
+    };
+    for level in levels {
+        filter = filter.add_directive(level.parse().expect("valid directive"));
+    }
+
+    for directive in filters
+        .iter()
+        .flat_map(|f| f.split(','))
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+    {
+        let parsed = directive.parse::<Directive>().map_err(|e| {
+            Error::ValidationError(format!("invalid --log-filter '{}': {}", directive, e))
+        })?;
+        filter = filter.add_directive(parsed);
+    }
+
+    Ok(filter)
+}
+
+/// Formats each event as one JSON object per line, e.g.
+/// `{"level":"INFO","message":"…","target":"endpointo::scanner","timestamp":"…"}`
+///
+/// Fields other than the message are kept under `fields`, and the names of
+/// the spans the event happened in under `spans`, outermost first.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let metadata = event.metadata();
+        let mut visitor = JsonVisitor::default();
+        event.record(&mut visitor);
+
+        let mut line = Map::new();
+        line.insert(
+            "timestamp".to_string(),
+            Value::String(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Micros, true)),
+        );
+        line.insert(
+            "level".to_string(),
+            Value::String(metadata.level().to_string()),
+        );
+        line.insert(
+            "target".to_string(),
+            Value::String(metadata.target().to_string()),
+        );
+        line.insert(
+            "message".to_string(),
+            visitor.message.map(Value::String).unwrap_or(Value::Null),
+        );
+        if !visitor.fields.is_empty() {
+            line.insert("fields".to_string(), Value::Object(visitor.fields));
+        }
+        if let Some(scope) = ctx.event_scope() {
+            let spans: Vec<Value> = scope
+                .from_root()
+                .map(|span| Value::String(span.name().to_string()))
+                .collect();
+            line.insert("spans".to_string(), Value::Array(spans));
+        }
+
+        let json = serde_json::to_string(&line).map_err(|_| fmt::Error)?;
+        writeln!(writer, "{}", json)
+    }
+}
+
+/// Collects an event's fields as JSON values
+#[derive(Default)]
+struct JsonVisitor {
+    message: Option<String>,
+    fields: Map<String, Value>,
+}
+
+impl JsonVisitor {
+    fn insert(&mut self, field: &Field, value: Value) {
+        if field.name() == "message" {
+            self.message = Some(match value {
+                Value::String(s) => s,
+                other => other.to_string(),
+            });
+        } else {
+            self.fields.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+impl Visit for JsonVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, Value::String(value.to_string()));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, Value::Bool(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, Value::String(format!("{:?}", value)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_log_filter_layers() {
+        let filter = log_filter(0, false, Some("endpointo=debug,parser=loud"), &[]).unwrap();
+        assert_eq!(filter.to_string(), "endpointo=debug,info");
+
+        let filter = log_filter(
+            1,
+            false,
+            None,
+            &["endpointo=warn,endpointo::crawler=trace".to_string()],
+        )
+        .unwrap();
+        assert_eq!(
+            filter.to_string(),
+            "endpointo::crawler=trace,endpointo=warn,info"
+        );
+
+        let filter = log_filter(2, false, Some("error"), &[]).unwrap();
+        assert_eq!(filter.to_string(), "endpointo=trace,debug");
+        assert_eq!(log_filter(0, true, None, &[]).unwrap().to_string(), "warn");
+        assert!(log_filter(0, false, None, &["endpointo=loud".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_json_format() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .event_format(JsonFormat)
+            .with_writer(move || writer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("scan").entered();
+            tracing::warn!(status = 503, retry = true, "Fetch failed for {}", "/app.js");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let line: Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(line["level"], "WARN");
+        assert_eq!(line["target"], "endpointo::logging::tests");
+        assert_eq!(line["message"], "Fetch failed for /app.js");
+        assert_eq!(line["fields"]["status"], 503);
+        assert_eq!(line["fields"]["retry"], true);
+        assert_eq!(line["spans"][0], "scan");
+        assert!(line["timestamp"].as_str().unwrap().ends_with('Z'));
+    }
+}
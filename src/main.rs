@@ -1,13 +1,69 @@
 use anyhow::Result;
-use clap::Parser as _;
+use chrono::Utc;
+use clap::{CommandFactory as _, Parser as _};
 use colored::*;
-use endpointo::cli::{Cli, Commands, InteractiveUi};
-use endpointo::config::ScanConfig;
-use endpointo::output::{write_results, OutputFormat};
+use endpointo::classify::sort_by_severity;
+use endpointo::cli::{
+    completions, load_targets, Cli, Commands, ConfigCommand, Dashboard, Explorer, HttpArgs,
+    InteractiveUi, LogFormat, PluginArgs, PluginsCommand,
+};
+use endpointo::config::{
+    find_config_file, load_settings, ConfigFile, ScanConfig, ScanSettings, DEFAULT_CONFIG,
+};
+use endpointo::crawler::client::{merge_headers, parse_cookies};
+use endpointo::diff::{diff_endpoints, load_endpoints, load_result, EndpointDiff};
+use endpointo::distributed::{merge_results, run_worker, Coordinator};
+use endpointo::doctor::{CheckStatus, Doctor};
+use endpointo::fuzz::{build_words, fuzz_result, Fuzzer};
+use endpointo::gate::{FailOn, FailureKind};
+use endpointo::graphql::{render_sdl, schema_result, Introspector};
+use endpointo::logging::{log_filter, JsonFormat};
+use endpointo::output::burp::{burp_urls, BurpApi};
+use endpointo::output::diff::DiffFormat;
+use endpointo::output::elastic::Elasticsearch;
+use endpointo::output::fields::Field;
+use endpointo::output::file::{target_output_path, write_file, write_shards};
+use endpointo::output::openapi::{build_openapi, build_openapi_for_servers, serialize_openapi};
+use endpointo::output::report::ReportFormat;
+use endpointo::output::webhook::Webhook;
+use endpointo::output::{
+    check_fields, write_bare_result, write_diff, write_fields, write_findings, write_history,
+    write_report, write_scan_result, write_template, write_wordlists, JsonlWriter, OutputFormat,
+};
+use endpointo::parser::filters::EndpointFilter;
+use endpointo::parser::params::{build_wordlists, WordKind};
+use endpointo::passive::{parse_domain, passive_result, PassiveRecon};
+use endpointo::plugins::dev::{list_plugins, sample_endpoints, test_plugin, write_skeleton};
+use endpointo::plugins::{Builtin, PluginReloader};
+use endpointo::probe::{load_probe_input, Prober};
+use endpointo::report::{parse_since, Report};
 use endpointo::scanner::Scanner;
-use std::path::PathBuf;
+use endpointo::server::ApiServer;
+use endpointo::state::ScanState;
+use endpointo::store::ProjectStore;
+use endpointo::types::{Endpoint, ScanResult};
+use endpointo::validate::{check_live, validate_file};
+use endpointo::watch::{parse_interval, Watcher};
+use futures::StreamExt;
+use std::collections::HashSet;
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use tracing_subscriber::EnvFilter;
 
+/// Set by `--silent`, leaving stdout and stderr to results and errors
+static SILENT: AtomicBool = AtomicBool::new(false);
+
+/// Print a status message to stderr, so stdout only carries results
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if !SILENT.load(Ordering::Relaxed) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
 fn print_banner() {
     let banner = r#"
     _______  __    _  ______   _______  _______  ___   __    _  _______  _______ 
@@ -18,131 +74,2035 @@ fn print_banner() {
 |   |___ | | |   ||       ||   |    |       ||   | | | |   ||   |_| ||       |
 |_______||_|  |__||______| |___|    |_______||___| |_|  |__||_______||_______|
     "#;
-    println!("{}", banner.bright_cyan().bold());
-    println!(
+    status!("{}", banner.bright_cyan().bold());
+    status!(
         "  {} v{}\n",
         "API Endpoint Discovery Tool".italic().dimmed(),
         "0.1.0".bright_green()
     );
 }
 
+/// Scan several targets, reporting each one's outcome, and merge the results
+async fn scan_targets(scanner: &Scanner, targets: &[String]) -> Vec<Endpoint> {
+    let mut results: Vec<Endpoint> = scan_each(scanner, targets, &mut Checkpoint::default())
+        .await
+        .into_iter()
+        .flat_map(|(_, endpoints)| endpoints)
+        .collect();
+    scanner.deduplicate(&mut results);
+    results
+}
+
+/// Scan targets with one scanner, so they share its rate limit, keeping
+/// each target's endpoints apart; failed targets are reported and left out
+///
+/// Targets the checkpoint has finished are taken from it, and the others
+/// are recorded in it as they finish.
+async fn scan_each(
+    scanner: &Scanner,
+    targets: &[String],
+    checkpoint: &mut Checkpoint,
+) -> Vec<(String, Vec<Endpoint>)> {
+    let mut results = Vec::new();
+    let mut pending = Vec::new();
+    for target in targets {
+        match checkpoint.state.endpoints(target) {
+            Some(endpoints) => results.push((target.clone(), endpoints.to_vec())),
+            None => pending.push(target.clone()),
+        }
+    }
+    let mut scanned = std::pin::pin!(scanner.scan_urls_stream(&pending));
+    while let Some((target, result)) = scanned.next().await {
+        match result {
+            Ok(endpoints) => {
+                status!("{} {} ({} endpoints)", "✔".green(), target, endpoints.len());
+                checkpoint.record(scanner, &target, &endpoints);
+                results.push((target, endpoints));
+            }
+            Err(e) => eprintln!("{} {}: {}", "❌ Error scanning".red(), target, e),
+        }
+    }
+    results.sort_by_key(|(target, _)| targets.iter().position(|t| t == target));
+    results
+}
+
+/// A scan's progress, saved to its state file as each target finishes
+#[derive(Default)]
+struct Checkpoint {
+    state: ScanState,
+    path: Option<PathBuf>,
+}
+
+impl Checkpoint {
+    /// Start from `resume`'s state if given, saving to `path`
+    fn open(path: Option<PathBuf>, resume: Option<&Path>) -> Result<Self> {
+        let state = match resume {
+            Some(resume) => ScanState::load(resume)?,
+            None => ScanState::new(),
+        };
+        Ok(Self { state, path })
+    }
+
+    /// Record a finished target and save, if there's a state file
+    fn record(&mut self, scanner: &Scanner, target: &str, endpoints: &[Endpoint]) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        self.state.record(
+            target,
+            endpoints,
+            scanner.findings(),
+            scanner.technologies(),
+        );
+        if let Err(e) = self.state.save(path) {
+            eprintln!(
+                "{} {}: {}",
+                "❌ Error saving state".red(),
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Write a scan result as `--template`, `--fields`, `--bare` or `--format` ask
+fn write_scan_output(
+    result: &ScanResult,
+    output: Option<&Path>,
+    format: OutputFormat,
+    template: Option<&Path>,
+    fields: &[Field],
+    bare: bool,
+) -> Result<()> {
+    if let Some(template) = template {
+        write_template(result, template, output)?;
+    } else if !fields.is_empty() {
+        write_fields(&result.endpoints, fields, output, format)?;
+    } else if bare {
+        write_bare_result(result, output, format)?;
+    } else {
+        write_scan_result(result, output, format)?;
+    }
+    Ok(())
+}
+
+/// Build the CI gate from the `--fail-on-*` flags, loading the baseline
+fn fail_on(baseline: Option<PathBuf>, patterns: &[String], secrets: bool) -> Result<FailOn> {
+    let baseline = baseline.map(|path| load_endpoints(&path)).transpose()?;
+    Ok(FailOn::new()
+        .with_baseline(baseline)
+        .with_patterns(patterns)?
+        .with_secrets(secrets))
+}
+
+/// Exit with the first failed condition's status, after naming them all
+fn enforce(gate: &FailOn, result: &ScanResult) {
+    let failures = gate.check(result);
+    for failure in &failures {
+        eprintln!("{} {}", "❌ Failing:".red().bold(), failure.reason);
+    }
+    if let Some(first) = failures.first() {
+        std::process::exit(first.kind.exit_code());
+    }
+}
+
+/// Apply the HTTP flags that were given; `-H` headers and `--cookie`
+/// strings are checked and replace the configured ones, and proxy
+/// credentials only count when a proxy is set, so an exported
+/// `ENDPOINTO_PROXY_AUTH` doesn't get in the way of direct scans
+fn apply_http(mut config: ScanConfig, args: HttpArgs) -> Result<ScanConfig> {
+    if !args.headers.is_empty() {
+        config = config.with_headers(merge_headers(&args.headers)?);
+    }
+    if !args.cookies.is_empty() {
+        let cookies = args.cookies.join("; ");
+        parse_cookies(&cookies)?;
+        config = config.with_cookies(Some(cookies));
+    }
+    config = config
+        .with_rate_limit(args.rate_limit)
+        .with_timeout(args.timeout)
+        .with_max_concurrent(args.threads)
+        .with_proxy(args.proxy);
+    if config.proxy.is_some() {
+        config = config.with_proxy_auth(args.proxy_auth);
+    }
+    Ok(config)
+}
+
+/// Apply scope flags that were given, leaving configured ones otherwise
+fn apply_scope(
+    mut config: ScanConfig,
+    include_hosts: Vec<String>,
+    exclude_hosts: Vec<String>,
+    include_regex: Vec<String>,
+    exclude_regex: Vec<String>,
+) -> ScanConfig {
+    if !include_hosts.is_empty() {
+        config = config.with_include_hosts(include_hosts);
+    }
+    if !exclude_hosts.is_empty() {
+        config = config.with_exclude_hosts(exclude_hosts);
+    }
+    if !include_regex.is_empty() {
+        config = config.with_include_regex(include_regex);
+    }
+    if !exclude_regex.is_empty() {
+        config = config.with_exclude_regex(exclude_regex);
+    }
+    config
+}
+
+/// Apply the plugin flags; `--plugin` replaces the configured plugin list
+fn apply_plugins(mut config: ScanConfig, args: PluginArgs) -> ScanConfig {
+    if !args.plugin.is_empty() {
+        config.plugins.clear();
+        for path in args.plugin {
+            config = config.with_plugin(path);
+        }
+    }
+    config
+        .with_plugin_dir(args.plugin_dir)
+        .with_disabled_plugins(args.disable_plugin)
+        .with_builtin_plugins(args.enable_builtin)
+        .with_plugin_timeout(args.plugin_timeout.map(Duration::from_secs))
+        .with_on_plugin_timeout(args.on_plugin_timeout)
+        .with_plugin_max_generated(args.plugin_max_generated)
+        .with_plugin_errors(args.plugin_errors)
+        .with_output_plugin(args.output_plugin)
+}
+
+/// Re-scan targets on a schedule until interrupted, reporting new endpoints
+///
+/// The first run is the baseline. New endpoints from later runs are shown
+/// and, with an output path, appended to it as JSON Lines. With a project
+/// store, every run is also recorded there. Plugins whose files change
+/// between runs are reloaded.
+async fn watch_targets(
+    config: ScanConfig,
+    targets: &[String],
+    interval: Duration,
+    output: Option<&Path>,
+    mut store: Option<ProjectStore>,
+    sinks: &Sinks,
+) -> Result<()> {
+    let mut watcher = Watcher::new();
+    let mut writer = output.map(JsonlWriter::append).transpose()?;
+    let mut plugins = PluginReloader::new(&config);
+    plugins.reload()?;
+
+    loop {
+        // A fresh scanner per run, since crawlers remember visited URLs
+        let scanner = Scanner::builder(config.clone())
+            .with_plugin_reloader(&plugins)
+            .build()?;
+        let started = Utc::now();
+        let endpoints = scan_targets(&scanner, targets).await;
+        let checked = Utc::now().format("%Y-%m-%d %H:%M:%S");
+        if let Some(store) = store.as_mut() {
+            store.record(&scanner.build_result(targets.join(", "), started, endpoints.clone()))?;
+        }
+
+        match watcher.update(endpoints) {
+            None => status!(
+                "{} {} Baseline of {} endpoints",
+                "👀".bright_white(),
+                checked.to_string().dimmed(),
+                watcher.known().to_string().bold()
+            ),
+            Some(new) if new.is_empty() => status!(
+                "{} {} No new endpoints",
+                "👀".bright_white(),
+                checked.to_string().dimmed()
+            ),
+            Some(new) => {
+                status!(
+                    "{} {} {} new endpoints",
+                    "🆕".bright_white(),
+                    checked.to_string().dimmed(),
+                    new.len().to_string().bold().bright_green()
+                );
+                let diff = EndpointDiff {
+                    added: new.clone(),
+                    ..Default::default()
+                };
+                write_diff(&diff, None, DiffFormat::Text)?;
+                if let Some(writer) = writer.as_mut() {
+                    for endpoint in &new {
+                        writer.write(endpoint)?;
+                    }
+                }
+                let result = scanner.build_result(targets.join(", "), started, new);
+                sinks
+                    .send(&result, targets.first().map(String::as_str))
+                    .await;
+            }
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = tokio::signal::ctrl_c() => break,
+        }
+        reload_plugins(&mut plugins);
+    }
+
+    Ok(())
+}
+
+/// Reload plugins whose files changed, reporting what happened
+fn reload_plugins(plugins: &mut PluginReloader) {
+    match plugins.reload() {
+        Ok(changed) if changed.is_empty() => {}
+        Ok(changed) => status!(
+            "{} Reloaded plugins: {}",
+            "🔌".bright_white(),
+            changed
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Err(e) => status!(
+            "{} {}",
+            "⚠️  Plugin reload failed, keeping the previous plugins:".yellow(),
+            e
+        ),
+    }
+}
+
+/// Services results are handed to after a scan
+struct Sinks {
+    burp: Option<BurpApi>,
+    webhook: Option<Webhook>,
+    elasticsearch: Option<Elasticsearch>,
+}
+
+impl Sinks {
+    /// Send a result to every configured service
+    async fn send(&self, result: &ScanResult, base: Option<&str>) {
+        if let Some(burp) = &self.burp {
+            send_to_burp(burp, &result.endpoints, base).await;
+        }
+        if let Some(webhook) = &self.webhook {
+            send_to_webhook(webhook, result).await;
+        }
+        if let Some(elasticsearch) = &self.elasticsearch {
+            send_to_elasticsearch(elasticsearch, result).await;
+        }
+    }
+}
+
+/// Hand endpoints' URLs to Burp as a new scan, reporting the outcome
+///
+/// Failures are reported rather than returned, so an unreachable Burp
+/// doesn't lose the scan's results.
+async fn send_to_burp(burp: &BurpApi, endpoints: &[Endpoint], base: Option<&str>) {
+    let urls = burp_urls(endpoints, base);
+    if urls.is_empty() {
+        return;
+    }
+    match burp.scan(&urls).await {
+        Ok(task) => status!(
+            "{} {} URLs{}",
+            "🛰️  Sent to Burp:".dimmed(),
+            urls.len().to_string().bold(),
+            task.map(|t| format!(" (task {})", t)).unwrap_or_default()
+        ),
+        Err(e) => eprintln!("{} {}", "❌ Failed to send to Burp:".red(), e),
+    }
+}
+
+/// POST a scan result to a webhook, reporting the outcome
+///
+/// Like Burp, failures are reported rather than returned.
+async fn send_to_webhook(webhook: &Webhook, result: &ScanResult) {
+    match webhook.send(result).await {
+        Ok(requests) => status!(
+            "{} {} endpoints in {} requests",
+            "📨 Sent to webhook:".dimmed(),
+            result.endpoints.len().to_string().bold(),
+            requests
+        ),
+        Err(e) => eprintln!("{} {}", "❌ Failed to send to webhook:".red(), e),
+    }
+}
+
+/// Bulk-index a scan result's endpoints, reporting the outcome
+///
+/// Like Burp, failures are reported rather than returned.
+async fn send_to_elasticsearch(elasticsearch: &Elasticsearch, result: &ScanResult) {
+    if result.endpoints.is_empty() {
+        return;
+    }
+    match elasticsearch.index(result).await {
+        Ok(indexed) => status!(
+            "{} {} endpoints",
+            "🗄️  Indexed in Elasticsearch:".dimmed(),
+            indexed.to_string().bold()
+        ),
+        Err(e) => eprintln!("{} {}", "❌ Failed to index in Elasticsearch:".red(), e),
+    }
+}
+
+/// Open an existing project database
+fn open_project(path: &Path) -> Result<ProjectStore> {
+    if !path.is_file() {
+        anyhow::bail!("{}: project database not found", path.display());
+    }
+    Ok(ProjectStore::open(path)?)
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .init();
-
     // Parse CLI arguments
     let cli = Cli::parse();
+    SILENT.store(cli.silent, Ordering::Relaxed);
+    let quiet = cli.quiet || cli.silent;
+
+    // https://no-color.org: any non-empty NO_COLOR turns colors off
+    let color = !cli.no_color && !matches!(std::env::var_os("NO_COLOR"), Some(v) if !v.is_empty());
+    if !color {
+        colored::control::set_override(false);
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+    }
+
+    // The dashboard shows errors itself, and logs would scribble over it
+    let tui = matches!(cli.command, Commands::Scan { tui: true, .. }) && Dashboard::available();
+
+    // Initialize logging, on stderr like the status messages
+    // Progress bars would tear up log lines
+    let verbose_logs =
+        cli.verbose > 0 || !cli.log_filter.is_empty() || cli.log_format == LogFormat::Json;
+    let filter = if tui {
+        EnvFilter::new("off")
+    } else {
+        let env = std::env::var(EnvFilter::DEFAULT_ENV).ok();
+        log_filter(cli.verbose, quiet, env.as_deref(), &cli.log_filter)?
+    };
+    let logs = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    match cli.log_format {
+        LogFormat::Text => logs.with_ansi(color).init(),
+        LogFormat::Json => logs.event_format(JsonFormat).init(),
+    }
+
+    // Scripts and man pages are piped to files, so keep them clean
+    if !quiet
+        && !matches!(
+            cli.command,
+            Commands::Config { .. }
+                | Commands::Plugins { .. }
+                | Commands::Completions { .. }
+                | Commands::Man { .. }
+        )
+    {
+        print_banner();
+    }
 
-    print_banner();
+    let config_path = if cli.no_config {
+        None
+    } else {
+        cli.config.clone().or_else(find_config_file)
+    };
+    if let Some(path) = &config_path {
+        tracing::debug!("Using configuration {}", path.display());
+    }
+    // Read on first use, so a broken file only fails commands that scan
+    let settings = || load_settings(config_path.as_deref());
 
     match cli.command {
         Commands::Scan {
             url,
+            list,
             output,
+            output_dir,
             format,
-            rate_limit,
-            timeout,
-            threads,
+            template,
+            fields,
+            bare,
+            shard_size,
+            profile,
+            http,
+            ignore_robots,
             filter,
-            plugin,
+            include_host,
+            exclude_host,
+            include_regex,
+            exclude_regex,
+            plugins,
+            script,
+            patterns,
+            aggressive_filter,
+            rules,
+            min_severity,
+            tag,
+            sort_by_severity: sort_severity,
+            max_parse_bytes,
+            parse_window,
+            findings,
+            fail_on_new,
+            fail_on_match,
+            fail_on_secrets,
+            probe,
+            probe_options,
+            max_duration,
+            max_endpoints,
+            depth,
+            max_pages,
+            max_assets,
+            max_visited,
+            visited_bloom,
+            watch,
+            state,
+            resume,
+            tui: _,
+            tui_type,
+            explore,
+            burp_api,
+            webhook,
+            webhook_secret,
+            webhook_batch,
+            elasticsearch,
+            es_index,
+            es_user,
+            es_password,
+            es_api_key,
+            project,
         } => {
-            println!(
-                "{} {}...",
-                "🚀 Starting scan of".bright_white(),
-                url.bold().bright_blue()
-            );
+            let mut targets = url;
+            if let Some(list) = list {
+                for target in load_targets(&list)? {
+                    if !targets.contains(&target) {
+                        targets.push(target);
+                    }
+                }
+            }
+            if targets.is_empty() {
+                anyhow::bail!("no targets to scan");
+            }
+            if !fields.is_empty() {
+                check_fields(format.as_ref().unwrap_or(&OutputFormat::Json))?;
+            }
+            if shard_size.is_some()
+                && !matches!(
+                    format,
+                    None | Some(OutputFormat::Json | OutputFormat::Jsonl)
+                )
+            {
+                anyhow::bail!("--shard-size writes JSON Lines; use --format json or jsonl");
+            }
+
+            if let [target] = targets.as_slice() {
+                status!(
+                    "{} {}...",
+                    "🚀 Starting scan of".bright_white(),
+                    target.bold().bright_blue()
+                );
+            } else {
+                status!(
+                    "{} {} targets...",
+                    "🚀 Starting scan of".bright_white(),
+                    targets.len().to_string().bold().bright_blue()
+                );
+            }
+
+            let mut config = settings()?
+                .with_profile(profile)
+                .apply(ScanConfig::new(targets[0].clone()))
+                .with_min_severity(min_severity)
+                .with_max_parse_bytes(max_parse_bytes)
+                .with_parse_window(parse_window)
+                .with_max_duration(max_duration.map(Duration::from_secs))
+                .with_max_endpoints(max_endpoints)
+                .with_max_depth(depth)
+                .with_max_pages(max_pages)
+                .with_max_assets(max_assets)
+                .with_max_visited(max_visited);
+
+            // Switches only turn settings on, so the profile's and the
+            // configuration's stay otherwise
+            if aggressive_filter {
+                config = config.with_aggressive_filter(true);
+            }
+
+            if !tag.is_empty() {
+                config = config.with_only_tags(tag);
+            }
+
+            if visited_bloom {
+                config = config.with_visited_bloom(true);
+            }
+
+            if ignore_robots {
+                config = config.with_robots(false);
+            }
+
+            config = apply_http(config, http)?;
 
-            let mut config = ScanConfig::new(url.clone())
-                .with_rate_limit(rate_limit)
-                .with_timeout(timeout)
-                .with_max_concurrent(threads);
+            if probe {
+                config = config.with_probe(true);
+            }
+
+            if probe_options {
+                config = config.with_probe_options(true);
+            }
 
             if let Some(f) = filter {
                 config = config.with_filter(f);
             }
+            config = apply_scope(
+                config,
+                include_host,
+                exclude_host,
+                include_regex,
+                exclude_regex,
+            );
+
+            config = apply_plugins(config, plugins);
+            config = config.with_script(script);
+
+            if let Some(p) = patterns {
+                config = config.with_patterns(p);
+            }
+
+            if let Some(r) = rules {
+                config = config.with_rules(r);
+            }
+
+            let gate = fail_on(fail_on_new, &fail_on_match, fail_on_secrets)?;
+
+            let sinks = Sinks {
+                burp: burp_api.as_deref().map(BurpApi::new).transpose()?,
+                webhook: webhook
+                    .as_deref()
+                    .map(Webhook::new)
+                    .transpose()?
+                    .map(|w| w.with_secret(webhook_secret).with_batch(webhook_batch)),
+                elasticsearch: elasticsearch
+                    .as_deref()
+                    .map(Elasticsearch::new)
+                    .transpose()?
+                    .map(|es| {
+                        es.with_index(es_index)
+                            .with_basic_auth(es_user, es_password)
+                            .with_api_key(es_api_key)
+                    }),
+            };
 
-            if let Some(p) = plugin {
-                config = config.with_plugin(PathBuf::from(p));
+            if let Some(interval) = watch {
+                let interval = parse_interval(&interval)?;
+                let store = project.as_deref().map(ProjectStore::open).transpose()?;
+                return watch_targets(config, &targets, interval, output.as_deref(), store, &sinks)
+                    .await;
             }
 
             let mut scanner = Scanner::new(config)?;
+            if let Some(OutputFormat::Plugin) = format {
+                scanner.check_plugin_output()?;
+            }
 
-            // Use interactive UI if verbose logging is not enabled and stdout is a terminal
-            if std::env::var("RUST_LOG").is_err() {
-                scanner = scanner.with_ui(InteractiveUi::new(5));
+            let mut checkpoint =
+                Checkpoint::open(state.or_else(|| resume.clone()), resume.as_deref())?;
+            if resume.is_some() {
+                scanner.restore(&checkpoint.state);
+                status!(
+                    "{} {} of {} targets already scanned",
+                    "↩️  Resuming:".bright_white(),
+                    targets
+                        .iter()
+                        .filter(|t| checkpoint.state.is_done(t))
+                        .count()
+                        .to_string()
+                        .bold(),
+                    targets.len()
+                );
             }
 
-            let results: Vec<endpointo::types::Endpoint> = scanner.scan_url(&url).await?;
+            let explore = explore && {
+                let available = Explorer::available();
+                if !available {
+                    status!(
+                        "{}",
+                        "⚠️  --explore needs a terminal; printing results instead".yellow()
+                    );
+                }
+                available
+            };
+            // The explorer takes the place of results on stdout
+            let explored = explore && output.is_none();
+
             let output_format = format.unwrap_or(OutputFormat::Json);
-            write_results(&results, output.as_deref(), output_format)?;
+            // JSON Lines output streams a single target's endpoints as they're found
+            let streaming = matches!(output_format, OutputFormat::Jsonl)
+                && targets.len() == 1
+                && template.is_none()
+                && shard_size.is_none()
+                && output_dir.is_none()
+                && checkpoint.path.is_none()
+                && !(tui && output.is_none())
+                && !explored;
+
+            // The dashboard replaces the progress bars, for any number of targets
+            let dashboard = tui.then(|| Dashboard::new(targets.join(", "), tui_type));
+            if let Some(dashboard) = &dashboard {
+                scanner = scanner.with_observer(dashboard.clone());
+            } else if std::env::var("RUST_LOG").is_err()
+                && !verbose_logs
+                && targets.len() == 1
+                && !streaming
+                && !quiet
+            {
+                // Use interactive UI if verbose logging is not enabled and stdout is a terminal
+                scanner = scanner.with_ui(InteractiveUi::new(0));
+            }
+
+            let started = Utc::now();
+            let scanning = async {
+                // Each target's own endpoints, for scans of several
+                if streaming {
+                    let mut results = Vec::new();
+                    let mut writer =
+                        JsonlWriter::new(output.as_deref())?.with_fields(fields.clone());
+                    let mut stream = std::pin::pin!(scanner.scan_stream(&targets[0]));
+                    while let Some(item) = stream.next().await {
+                        let endpoint = item?;
+                        writer.write(&endpoint)?;
+                        results.push(endpoint);
+                    }
+                    writer.finish()?;
+                    Ok((
+                        scanner.build_result(targets[0].clone(), started, results),
+                        None,
+                    ))
+                } else if targets.len() == 1 {
+                    let result = match checkpoint.state.endpoints(&targets[0]) {
+                        Some(endpoints) => {
+                            scanner.build_result(targets[0].clone(), started, endpoints.to_vec())
+                        }
+                        None => {
+                            let result = scanner.scan().await?;
+                            checkpoint.record(&scanner, &targets[0], &result.endpoints);
+                            result
+                        }
+                    };
+                    Ok((result, None))
+                } else {
+                    let each = scan_each(&scanner, &targets, &mut checkpoint).await;
+                    let mut results: Vec<Endpoint> = each
+                        .iter()
+                        .flat_map(|(_, endpoints)| endpoints.iter().cloned())
+                        .collect();
+                    scanner.deduplicate(&mut results);
+                    let result = scanner.build_result(targets.join(", "), started, results);
+                    Ok::<_, anyhow::Error>((result, Some(each)))
+                }
+            };
+            let (mut result, per_target) = match dashboard.as_ref().map(Dashboard::show) {
+                Some(screen) => tokio::select! {
+                    scanned = scanning => {
+                        drop(screen);
+                        scanned?
+                    }
+                    // Give the terminal back before going
+                    _ = tokio::signal::ctrl_c() => {
+                        drop(screen);
+                        std::process::exit(130);
+                    }
+                },
+                None => scanning.await?,
+            };
+            if sort_severity && !streaming {
+                sort_by_severity(&mut result.endpoints);
+            }
+
+            let mut written = 0;
+            if let OutputFormat::Plugin = output_format {
+                let plugins = scanner.write_plugin_output(&result).await?;
+                status!(
+                    "{} {} plugin(s)",
+                    "🔌 Results written by".dimmed(),
+                    plugins.to_string().bold()
+                );
+            } else if let Some(dir) = &output_dir {
+                std::fs::create_dir_all(dir)?;
+                let mut taken = HashSet::new();
+                let results = match per_target {
+                    None => vec![result.clone()],
+                    Some(each) => each
+                        .into_iter()
+                        .map(|(target, endpoints)| {
+                            let mut result = scanner.build_result(target, started, endpoints);
+                            if sort_severity {
+                                sort_by_severity(&mut result.endpoints);
+                            }
+                            result
+                        })
+                        .collect(),
+                };
+                for target_result in &results {
+                    let path = target_output_path(
+                        dir,
+                        &target_result.target,
+                        output_format.extension(),
+                        &mut taken,
+                    );
+                    write_scan_output(
+                        target_result,
+                        Some(&path),
+                        output_format.clone(),
+                        template.as_deref(),
+                        &fields,
+                        bare,
+                    )?;
+                }
+                written = results.len();
+            } else if let (Some(size), Some(path)) = (shard_size, &output) {
+                let manifest = write_shards(&result, path, size, &fields)?;
+                status!(
+                    "{} {}",
+                    "📦 Sharded output, manifest:".dimmed(),
+                    manifest.display()
+                );
+            } else if !streaming && !explored {
+                write_scan_output(
+                    &result,
+                    output.as_deref(),
+                    output_format,
+                    template.as_deref(),
+                    &fields,
+                    bare,
+                )?;
+            }
+
+            if !result.findings.is_empty() || findings.is_some() {
+                write_findings(&result.findings, findings.as_deref())?;
+            }
+
+            let recorded = match &project {
+                Some(path) => Some(ProjectStore::open(path)?.record(&result)?),
+                None => None,
+            };
+            sinks.send(&result, Some(&targets[0])).await;
 
-            println!(
+            status!(
                 "\n{} Found {} endpoints",
                 "✅ Scan complete!".bright_green().bold(),
-                results.len().to_string().bold()
+                result.total_endpoints.to_string().bold()
             );
+            if !result.technologies.is_empty() {
+                let technologies: Vec<String> = result
+                    .technologies
+                    .iter()
+                    .map(|t| match &t.version {
+                        Some(version) => format!("{} {}", t.name, version),
+                        None => t.name.clone(),
+                    })
+                    .collect();
+                status!(
+                    "{} {}",
+                    "🧩 Technologies:".bright_white(),
+                    technologies.join(", ").bright_cyan()
+                );
+            }
+            if let Some(budget) = result
+                .stats
+                .as_ref()
+                .and_then(|s| s.budget_exhausted.as_deref())
+            {
+                status!(
+                    "{} {}",
+                    "⏱️  Budget exhausted, results are partial:".yellow(),
+                    budget.bold()
+                );
+            }
+            if let Some(stats) = result
+                .stats
+                .as_ref()
+                .filter(|s| !s.plugin_errors.is_empty())
+            {
+                let mut errors: Vec<String> = stats
+                    .plugin_errors
+                    .iter()
+                    .map(|(plugin, count)| format!("{} ({})", plugin, count))
+                    .collect();
+                errors.sort();
+                status!(
+                    "{} {}",
+                    "⚠️  Plugin hooks failed, results may be incomplete:".yellow(),
+                    errors.join(", ").bold()
+                );
+            }
             if let Some(output_path) = output {
-                println!(
+                status!(
                     "{} {}",
                     "📄 Results saved to:".dimmed(),
                     output_path.display().to_string().bright_white().underline()
                 );
             }
+            if let Some(dir) = output_dir {
+                status!(
+                    "{} {} ({} files)",
+                    "📁 Results saved to:".dimmed(),
+                    dir.display().to_string().bright_white().underline(),
+                    written
+                );
+            }
+            if let Some(findings_path) = findings {
+                status!(
+                    "{} {}",
+                    "🔐 Findings saved to:".dimmed(),
+                    findings_path
+                        .display()
+                        .to_string()
+                        .bright_white()
+                        .underline()
+                );
+            }
+            if let (Some(project_path), Some(added)) = (project, recorded) {
+                status!(
+                    "{} {} ({} new endpoints)",
+                    "🗄️  Recorded in project:".dimmed(),
+                    project_path
+                        .display()
+                        .to_string()
+                        .bright_white()
+                        .underline(),
+                    added.len().to_string().bright_green()
+                );
+            }
+            if explore {
+                Explorer::new(&result).run()?;
+            }
+            enforce(&gate, &result);
         }
 
         Commands::Parse {
+            inputs,
             files,
+            ext,
+            max_file_size,
             output,
             format,
+            template,
+            fields,
+            bare,
             filter,
-            plugin,
+            include_host,
+            exclude_host,
+            include_regex,
+            exclude_regex,
+            plugins,
+            script,
+            patterns,
+            aggressive_filter,
+            rules,
+            min_severity,
+            tag,
+            sort_by_severity: sort_severity,
+            max_parse_bytes,
+            parse_window,
+            findings,
+            fail_on_new,
+            fail_on_match,
+            fail_on_secrets,
         } => {
-            println!(
-                "{} {} files...",
-                "📂 Parsing".bright_white(),
-                files.len().to_string().bold().bright_blue()
-            );
-
-            let mut config = ScanConfig::default();
+            let mut config = settings()?
+                .apply(ScanConfig::default())
+                .with_min_severity(min_severity)
+                .with_max_parse_bytes(max_parse_bytes)
+                .with_parse_window(parse_window)
+                .with_max_file_bytes(max_file_size);
+            if aggressive_filter {
+                config = config.with_aggressive_filter(true);
+            }
+            if !tag.is_empty() {
+                config = config.with_only_tags(tag);
+            }
+            if !ext.is_empty() {
+                config = config.with_file_extensions(ext);
+            }
             if let Some(f) = filter {
                 config = config.with_filter(f);
             }
-            if let Some(p) = plugin {
-                config = config.with_plugin(PathBuf::from(p));
+            config = apply_scope(
+                config,
+                include_host,
+                exclude_host,
+                include_regex,
+                exclude_regex,
+            );
+            config = apply_plugins(config, plugins);
+            config = config.with_script(script);
+            if let Some(p) = patterns {
+                config = config.with_patterns(p);
+            }
+
+            if let Some(r) = rules {
+                config = config.with_rules(r);
             }
 
+            let gate = fail_on(fail_on_new, &fail_on_match, fail_on_secrets)?;
             let scanner = Scanner::new(config)?;
+            if let Some(OutputFormat::Plugin) = format {
+                scanner.check_plugin_output()?;
+            }
+            // `-` stands for stdin, which is read whole and parsed as one input
+            let (stdin, paths): (Vec<PathBuf>, Vec<PathBuf>) = inputs
+                .into_iter()
+                .chain(files)
+                .partition(|path| path == Path::new("-"));
+            let files = if paths.is_empty() {
+                Vec::new()
+            } else {
+                scanner.collect_files(&paths)?
+            };
+            let stdin = if stdin.is_empty() {
+                None
+            } else {
+                let mut content = Vec::new();
+                std::io::stdin().lock().read_to_end(&mut content)?;
+                Some(content)
+            };
+            if files.is_empty() && stdin.is_none() {
+                anyhow::bail!("no files to parse");
+            }
+            if !fields.is_empty() {
+                check_fields(format.as_ref().unwrap_or(&OutputFormat::Json))?;
+            }
 
+            status!(
+                "{} {} files...",
+                "📂 Parsing".bright_white(),
+                (files.len() + usize::from(stdin.is_some()))
+                    .to_string()
+                    .bold()
+                    .bright_blue()
+            );
+
+            let started = Utc::now();
             let mut all_results: Vec<endpointo::types::Endpoint> = Vec::new();
-            for file in files {
-                match scanner.parse_file(&file).await {
+            if let Some(content) = &stdin {
+                match scanner.parse_bytes("stdin", content) {
+                    Ok(results) => all_results.extend(results),
+                    Err(e) => eprintln!("{} stdin: {}", "❌ Error parsing".red(), e),
+                }
+            }
+            for (file, result) in files.iter().zip(scanner.parse_files(&files)) {
+                match result {
                     Ok(results) => all_results.extend(results),
                     Err(e) => eprintln!("{} {}: {}", "❌ Error parsing".red(), file.display(), e),
                 }
             }
 
+            scanner.deduplicate(&mut all_results);
+            let target = stdin
+                .as_ref()
+                .map(|_| "stdin".to_string())
+                .into_iter()
+                .chain(files.iter().map(|file| file.display().to_string()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let mut result = scanner.build_result(target, started, all_results);
+            if sort_severity {
+                sort_by_severity(&mut result.endpoints);
+            }
+
             // Write output
             let output_format = format.unwrap_or(OutputFormat::Json);
-            write_results(&all_results, output.as_deref(), output_format)?;
+            if let OutputFormat::Plugin = output_format {
+                let plugins = scanner.write_plugin_output(&result).await?;
+                status!(
+                    "{} {} plugin(s)",
+                    "🔌 Results written by".dimmed(),
+                    plugins.to_string().bold()
+                );
+            } else if let Some(template) = &template {
+                write_template(&result, template, output.as_deref())?;
+            } else if !fields.is_empty() {
+                write_fields(&result.endpoints, &fields, output.as_deref(), output_format)?;
+            } else if bare {
+                write_bare_result(&result, output.as_deref(), output_format)?;
+            } else {
+                write_scan_result(&result, output.as_deref(), output_format)?;
+            }
+
+            if !result.findings.is_empty() || findings.is_some() {
+                write_findings(&result.findings, findings.as_deref())?;
+            }
 
-            println!(
+            status!(
                 "\n{} Parsed {} endpoints",
                 "✅ Parse complete!".bright_green().bold(),
-                all_results.len().to_string().bold()
+                result.total_endpoints.to_string().bold()
             );
             if let Some(output_path) = output {
+                status!(
+                    "{} {}",
+                    "📄 Results saved to:".dimmed(),
+                    output_path.display().to_string().bright_white().underline()
+                );
+            }
+            if let Some(findings_path) = findings {
+                status!(
+                    "{} {}",
+                    "🔐 Findings saved to:".dimmed(),
+                    findings_path
+                        .display()
+                        .to_string()
+                        .bright_white()
+                        .underline()
+                );
+            }
+            enforce(&gate, &result);
+        }
+
+        Commands::Diff {
+            old,
+            new,
+            project,
+            target,
+            output,
+            format,
+            fail_on_new,
+        } => {
+            let (old, new) = match (project, old, new) {
+                (Some(project), _, _) => {
+                    let store = open_project(&project)?;
+                    let (previous, latest) = store.latest_scans(target.as_deref())?;
+                    status!(
+                        "{} {} ({} → {})",
+                        "🗄️  Comparing scans of".bright_white(),
+                        latest.target.bold().bright_blue(),
+                        previous.timestamp,
+                        latest.timestamp
+                    );
+                    (
+                        store.scan_endpoints(previous.id)?,
+                        store.scan_endpoints(latest.id)?,
+                    )
+                }
+                (None, Some(old), Some(new)) => (load_endpoints(&old)?, load_endpoints(&new)?),
+                _ => anyhow::bail!("diff needs OLD and NEW result files or --project"),
+            };
+            let diff = diff_endpoints(&old, &new);
+            let format = format.unwrap_or(match output {
+                Some(_) => DiffFormat::Json,
+                None => DiffFormat::Text,
+            });
+            write_diff(&diff, output.as_deref(), format)?;
+
+            status!(
+                "\n{} {} new, {} removed, {} changed",
+                "✅ Diff complete!".bright_green().bold(),
+                diff.added.len().to_string().bold(),
+                diff.removed.len().to_string().bold(),
+                diff.changed.len().to_string().bold()
+            );
+            if let Some(output_path) = output {
+                status!(
+                    "{} {}",
+                    "📄 Diff saved to:".dimmed(),
+                    output_path.display().to_string().bright_white().underline()
+                );
+            }
+            if fail_on_new && !diff.added.is_empty() {
+                eprintln!(
+                    "{} {} new endpoints",
+                    "❌ Failing:".red().bold(),
+                    diff.added.len()
+                );
+                std::process::exit(FailureKind::NewEndpoints.exit_code());
+            }
+        }
+
+        Commands::Validate {
+            input,
+            live,
+            base,
+            http,
+        } => {
+            let validation = validate_file(&input)?;
+            for problem in &validation.problems {
                 println!(
+                    "{} {}: {}",
+                    "❌".red(),
+                    problem.location.bold(),
+                    problem.message
+                );
+            }
+
+            let mut dead = Vec::new();
+            if let Some(sample) = live {
+                let base = base.or_else(|| {
+                    url::Url::parse(&validation.target)
+                        .is_ok()
+                        .then(|| validation.target.clone())
+                });
+                let config = apply_http(settings()?.apply(ScanConfig::default()), http)?;
+                let prober = Prober::new(&config)?;
+                let checked;
+                (checked, dead) =
+                    check_live(&prober, &validation.endpoints, sample, base.as_deref()).await;
+                for endpoint in &dead {
+                    println!(
+                        "{} {} ({})",
+                        "💀".red(),
+                        endpoint.url.bold(),
+                        endpoint.reason
+                    );
+                }
+                status!(
+                    "{} {} of {} sampled endpoints are dead",
+                    "📡 Live check:".bright_white(),
+                    dead.len().to_string().bold(),
+                    checked
+                );
+            }
+
+            status!(
+                "{} {} valid endpoints, {} problems",
+                if validation.problems.is_empty() && dead.is_empty() {
+                    "✅ Valid:".bright_green().bold()
+                } else {
+                    "❌ Invalid:".red().bold()
+                },
+                validation.endpoints.len().to_string().bold(),
+                validation.problems.len().to_string().bold()
+            );
+            if !validation.problems.is_empty() || !dead.is_empty() {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Fuzz {
+            url,
+            wordlist,
+            from,
+            no_builtin,
+            max_requests,
+            output,
+            format,
+            http,
+            ignore_robots,
+            include_regex,
+            exclude_regex,
+            no_soft_404,
+        } => {
+            let base = from.as_deref().map(load_result).transpose()?;
+            let known = base.as_ref().map(|r| r.endpoints.as_slice()).unwrap_or(&[]);
+            let words = build_words(!no_builtin, &wordlist, known)?;
+            if words.is_empty() {
+                anyhow::bail!("no words to try; give a --wordlist or --from results");
+            }
+
+            let mut config = apply_http(settings()?.apply(ScanConfig::new(url.clone())), http)?;
+            if ignore_robots {
+                config = config.with_robots(false);
+            }
+            config = apply_scope(config, Vec::new(), Vec::new(), include_regex, exclude_regex);
+            let fuzzer = Fuzzer::new(&config)?
+                .with_max_requests(max_requests)
+                .with_soft_404(!no_soft_404);
+
+            status!(
+                "{} {} with {} words...",
+                "🧨 Fuzzing".bright_white(),
+                url.bold(),
+                words.len().to_string().bold().bright_blue()
+            );
+            let started = Utc::now();
+            let hits = fuzzer.fuzz(&url, &words, known).await?;
+            for hit in &hits {
+                let status = hit.probe.as_ref().map_or(0, |p| p.status);
+                status!("  {} {} ({})", "🎯".green(), hit.url.bold(), status);
+            }
+            let found = hits.len();
+            let result = fuzz_result(&url, base, started, hits, fuzzer.requests_made());
+            write_scan_result(
+                &result,
+                output.as_deref(),
+                format.unwrap_or(OutputFormat::Json),
+            )?;
+
+            status!(
+                "\n{} {} paths found, {} requests sent",
+                "✅ Fuzz complete!".bright_green().bold(),
+                found.to_string().bold(),
+                fuzzer.requests_made()
+            );
+            if let Some(output_path) = output {
+                status!(
+                    "{} {}",
+                    "📄 Results saved to:".dimmed(),
+                    output_path.display().to_string().bright_white().underline()
+                );
+            }
+        }
+
+        Commands::Probe {
+            input,
+            base,
+            output,
+            format,
+            http,
+            options,
+            max_redirects,
+            no_soft_404,
+            live,
+        } => {
+            let mut result = load_probe_input(&input)?;
+            let base = base.or_else(|| {
+                url::Url::parse(&result.target)
+                    .is_ok()
+                    .then(|| result.target.clone())
+            });
+            status!(
+                "{} {} endpoints from {}...",
+                "📡 Probing".bright_white(),
+                result.endpoints.len().to_string().bold().bright_blue(),
+                input.display().to_string().bold()
+            );
+
+            let mut config = apply_http(settings()?.apply(ScanConfig::default()), http)?;
+            if options {
+                config = config.with_probe_options(true);
+            }
+            let prober = Prober::new(&config)?
+                .with_max_redirects(max_redirects)
+                .with_soft_404(!no_soft_404);
+            // A plain URL list names its targets itself
+            if result.target.is_empty() {
+                for endpoint in &result.endpoints {
+                    prober.add_target(&endpoint.url);
+                }
+            }
+            let probed = prober
+                .probe_all(&mut result.endpoints, base.as_deref())
+                .await;
+            if live {
+                result
+                    .endpoints
+                    .retain(|e| e.probe.as_ref().is_some_and(|p| p.is_live()));
+                result.total_endpoints = result.endpoints.len();
+            }
+            if result.timestamp.is_empty() {
+                result.timestamp = Utc::now().to_rfc3339();
+            }
+            write_scan_result(
+                &result,
+                output.as_deref(),
+                format.unwrap_or(OutputFormat::Json),
+            )?;
+
+            let soft_404s = result
+                .endpoints
+                .iter()
+                .filter(|e| e.probe.as_ref().is_some_and(|p| p.soft_404))
+                .count();
+            status!(
+                "\n{} {} endpoints responded ({} soft 404s), {} requests sent",
+                "✅ Probe complete!".bright_green().bold(),
+                probed.to_string().bold(),
+                soft_404s.to_string().bold(),
+                prober.requests_made()
+            );
+            if let Some(output_path) = output {
+                status!(
+                    "{} {}",
+                    "📄 Results saved to:".dimmed(),
+                    output_path.display().to_string().bright_white().underline()
+                );
+            }
+        }
+
+        Commands::Export {
+            input,
+            output,
+            format,
+            template,
+            fields,
+            bare,
+            shard_size,
+            base,
+        } => {
+            let output_format = format.unwrap_or(OutputFormat::Json);
+            if !fields.is_empty() {
+                check_fields(&output_format)?;
+            }
+            if shard_size.is_some()
+                && !matches!(output_format, OutputFormat::Json | OutputFormat::Jsonl)
+            {
+                anyhow::bail!("--shard-size writes JSON Lines; use --format json or jsonl");
+            }
+
+            let mut result = load_result(&input)?;
+            if let Some(base) = base {
+                result.target = base;
+            }
+
+            if let (Some(size), Some(path)) = (shard_size, &output) {
+                let manifest = write_shards(&result, path, size, &fields)?;
+                status!(
+                    "{} {}",
+                    "🗂️  Manifest saved to:".dimmed(),
+                    manifest.display().to_string().bright_white().underline()
+                );
+            } else if let Some(template) = &template {
+                write_template(&result, template, output.as_deref())?;
+            } else if !fields.is_empty() {
+                write_fields(&result.endpoints, &fields, output.as_deref(), output_format)?;
+            } else if bare {
+                write_bare_result(&result, output.as_deref(), output_format)?;
+            } else {
+                write_scan_result(&result, output.as_deref(), output_format)?;
+            }
+
+            if let Some(output_path) = output {
+                status!(
+                    "\n{} {} endpoints from {}",
+                    "✅ Exported".bright_green().bold(),
+                    result.total_endpoints.to_string().bold(),
+                    input.display()
+                );
+                status!(
+                    "{} {}",
+                    "📄 Results saved to:".dimmed(),
+                    output_path.display().to_string().bright_white().underline()
+                );
+            }
+        }
+
+        Commands::Spec {
+            inputs,
+            output,
+            title,
+            servers,
+            verified,
+        } => {
+            let mut targets = Vec::new();
+            let mut endpoints = Vec::new();
+            for input in &inputs {
+                let result = load_result(input)?;
+                if !result.target.is_empty() && !targets.contains(&result.target) {
+                    targets.push(result.target);
+                }
+                endpoints.extend(result.endpoints);
+            }
+            EndpointFilter::new().deduplicate(&mut endpoints);
+            if verified {
+                endpoints.retain(|e| e.probe.as_ref().is_some_and(|p| p.is_live()));
+            }
+
+            let title = title.unwrap_or_else(|| targets.join(", "));
+            let spec = if servers.is_empty() {
+                build_openapi(&title, &endpoints)
+            } else {
+                build_openapi_for_servers(&title, &endpoints, &servers)?
+            };
+            let document = serialize_openapi(&spec, output.as_deref())?;
+            match &output {
+                Some(path) => write_file(path, document.as_bytes())?,
+                None => print!("{}", document),
+            }
+
+            if let Some(output_path) = output {
+                let operations: usize = spec.paths.values().map(|methods| methods.len()).sum();
+                status!(
+                    "\n{} {} paths, {} operations from {} endpoints",
+                    "✅ Spec complete!".bright_green().bold(),
+                    spec.paths.len().to_string().bold(),
+                    operations.to_string().bold(),
+                    endpoints.len()
+                );
+                status!(
+                    "{} {}",
+                    "📄 Spec saved to:".dimmed(),
+                    output_path.display().to_string().bright_white().underline()
+                );
+            }
+        }
+
+        Commands::Wordlist {
+            inputs,
+            kind,
+            output,
+            dir,
+        } => {
+            let mut endpoints = Vec::new();
+            for input in &inputs {
+                endpoints.extend(load_endpoints(input)?);
+            }
+            let kinds = if kind.is_empty() {
+                WordKind::ALL.to_vec()
+            } else {
+                kind
+            };
+            let words = write_wordlists(
+                &build_wordlists(&endpoints),
+                &kinds,
+                output.as_deref(),
+                dir.as_deref(),
+            )?;
+
+            if let Some(path) = output.or(dir) {
+                status!(
+                    "\n{} {} words from {} endpoints",
+                    "✅ Wordlist complete!".bright_green().bold(),
+                    words.to_string().bold(),
+                    endpoints.len()
+                );
+                status!(
+                    "{} {}",
+                    "📄 Wordlist saved to:".dimmed(),
+                    path.display().to_string().bright_white().underline()
+                );
+            }
+        }
+
+        Commands::Graphql {
+            url,
+            schema: schema_path,
+            output,
+            format,
+            http,
+        } => {
+            let introspector = Introspector::new(&url)?
+                .with_headers(&http.headers)?
+                .with_cookies(&http.cookies)?
+                .with_proxy(http.proxy.as_deref(), http.proxy_auth.as_deref())?
+                .with_timeout(http.timeout);
+            status!(
+                "{} {}...",
+                "🔎 Introspecting".bright_white(),
+                introspector.url().as_str().bold().bright_blue()
+            );
+
+            let started = Utc::now();
+            let schema = introspector.introspect().await?;
+            let sdl = render_sdl(&schema);
+            match &schema_path {
+                Some(path) => write_file(path, sdl.as_bytes())?,
+                None => println!("\n{}", sdl),
+            }
+
+            let result = schema_result(&url, started, &schema);
+            write_scan_result(
+                &result,
+                output.as_deref(),
+                format.unwrap_or(OutputFormat::Json),
+            )?;
+
+            status!(
+                "\n{} Found {} operations",
+                "✅ Introspection complete!".bright_green().bold(),
+                result.total_endpoints.to_string().bold()
+            );
+            if let Some(path) = schema_path {
+                status!(
+                    "{} {}",
+                    "🧬 Schema saved to:".dimmed(),
+                    path.display().to_string().bright_white().underline()
+                );
+            }
+            if let Some(output_path) = output {
+                status!(
+                    "{} {}",
+                    "📄 Results saved to:".dimmed(),
+                    output_path.display().to_string().bright_white().underline()
+                );
+            }
+        }
+
+        Commands::Passive {
+            domain,
+            sources,
+            no_subdomains,
+            limit,
+            urlscan_key,
+            include_static,
+            output,
+            format,
+            timeout,
+        } => {
+            let domain = parse_domain(&domain)?;
+            let recon = PassiveRecon::new()
+                .with_sources(sources)
+                .with_subdomains(!no_subdomains)
+                .with_limit(limit)
+                .with_urlscan_key(urlscan_key)
+                .with_static(include_static)
+                .with_timeout(timeout);
+            let names: Vec<&str> = recon.sources().iter().map(|s| s.as_str()).collect();
+            status!(
+                "{} {} from {}...",
+                "🕰️ Collecting".bright_white(),
+                domain.bold().bright_blue(),
+                names.join(", ")
+            );
+
+            let started = Utc::now();
+            let (endpoints, outcomes) = recon.collect(&domain).await;
+            for (source, outcome) in &outcomes {
+                match outcome {
+                    Ok(count) => status!("  {} {} ({} URLs)", "✔".green(), source.as_str(), count),
+                    Err(e) => status!("  {} {}: {}", "❌".red(), source.as_str(), e),
+                }
+            }
+            if outcomes.iter().all(|(_, outcome)| outcome.is_err()) {
+                anyhow::bail!("every passive source failed");
+            }
+
+            let result = passive_result(&domain, started, endpoints, recon.requests_made());
+            write_scan_result(
+                &result,
+                output.as_deref(),
+                format.unwrap_or(OutputFormat::Json),
+            )?;
+
+            status!(
+                "\n{} Found {} unique endpoints",
+                "✅ Passive recon complete!".bright_green().bold(),
+                result.total_endpoints.to_string().bold()
+            );
+            if let Some(output_path) = output {
+                status!(
+                    "{} {}",
+                    "📄 Results saved to:".dimmed(),
+                    output_path.display().to_string().bright_white().underline()
+                );
+            }
+        }
+
+        Commands::Coordinate {
+            url,
+            list,
+            listen,
+            token,
+            output,
+            format,
+            bare,
+        } => {
+            let mut targets: Vec<String> = Vec::new();
+            let listed = match list {
+                Some(list) => load_targets(&list)?,
+                None => Vec::new(),
+            };
+            for target in url.into_iter().chain(listed) {
+                if !targets.contains(&target) {
+                    targets.push(target);
+                }
+            }
+            if targets.is_empty() {
+                anyhow::bail!("no targets to scan");
+            }
+
+            let listener = tokio::net::TcpListener::bind(&listen).await?;
+            status!(
+                "{} {} targets to workers on {}...",
+                "📡 Handing out".bright_white(),
+                targets.len().to_string().bold().bright_blue(),
+                listener.local_addr()?.to_string().bold()
+            );
+
+            let started = Utc::now();
+            let mut results = Vec::new();
+            for (target, result) in Coordinator::new(targets.clone())
+                .with_token(token)
+                .run(listener)
+                .await
+            {
+                match result {
+                    Ok(result) => {
+                        status!(
+                            "{} {} ({} endpoints)",
+                            "✔".green(),
+                            target,
+                            result.total_endpoints
+                        );
+                        results.push(result);
+                    }
+                    Err(e) => eprintln!("{} {}: {}", "❌ Error scanning".red(), target, e),
+                }
+            }
+            let result = merge_results(targets.join(", "), started, results);
+            let output_format = format.unwrap_or(OutputFormat::Json);
+            if bare {
+                write_bare_result(&result, output.as_deref(), output_format)?;
+            } else {
+                write_scan_result(&result, output.as_deref(), output_format)?;
+            }
+
+            status!(
+                "\n{} Found {} endpoints",
+                "✅ Scan complete!".bright_green().bold(),
+                result.total_endpoints.to_string().bold()
+            );
+            if let Some(output_path) = output {
+                status!(
                     "{} {}",
                     "📄 Results saved to:".dimmed(),
                     output_path.display().to_string().bright_white().underline()
                 );
             }
         }
+
+        Commands::Worker {
+            connect,
+            token,
+            profile,
+            http,
+            probe,
+        } => {
+            let mut config = apply_http(
+                settings()?
+                    .with_profile(profile)
+                    .apply(ScanConfig::default()),
+                http,
+            )?;
+            if probe {
+                config = config.with_probe(true);
+            }
+
+            status!(
+                "{} {}...",
+                "🛠️  Working for".bright_white(),
+                connect.bold().bright_blue()
+            );
+            let scanned = run_worker(connect.as_str(), config, token).await?;
+            status!(
+                "\n{} Scanned {} targets",
+                "✅ Work complete!".bright_green().bold(),
+                scanned.to_string().bold()
+            );
+        }
+
+        Commands::Serve {
+            listen,
+            token,
+            max_scans,
+            profile,
+            http,
+        } => {
+            let config = apply_http(
+                settings()?
+                    .with_profile(profile)
+                    .apply(ScanConfig::default()),
+                http,
+            )?;
+            let listener = tokio::net::TcpListener::bind(&listen).await?;
+            status!(
+                "{} http://{}{}",
+                "🌐 Serving the scan API on".bright_white(),
+                listener.local_addr()?.to_string().bold(),
+                if token.is_some() {
+                    " (bearer token required)"
+                } else {
+                    ""
+                }
+            );
+
+            let server = ApiServer::new(config)
+                .with_token(token)
+                .with_max_scans(max_scans);
+            tokio::select! {
+                result = server.serve(listener) => result?,
+                _ = tokio::signal::ctrl_c() => {
+                    status!("\n{}", "👋 Shutting down".dimmed());
+                }
+            }
+        }
+
+        Commands::History {
+            project,
+            target,
+            output,
+        } => {
+            let history = open_project(&project)?.history(target.as_deref())?;
+            write_history(&history, output.as_deref())?;
+
+            if let Some(output_path) = output {
+                status!(
+                    "{} {}",
+                    "📄 History saved to:".dimmed(),
+                    output_path.display().to_string().bright_white().underline()
+                );
+            }
+        }
+
+        Commands::Report {
+            inputs,
+            project,
+            target,
+            since,
+            output,
+            format,
+        } => {
+            let since = since.as_deref().map(parse_since).transpose()?;
+            let report = match project {
+                Some(project) => Report::from_store(
+                    &open_project(&project)?,
+                    target.as_deref(),
+                    since.as_deref(),
+                )?,
+                None => {
+                    let results = inputs
+                        .iter()
+                        .map(|path| load_result(path))
+                        .collect::<endpointo::error::Result<Vec<_>>>()?;
+                    Report::from_results(&results, since.as_deref())?
+                }
+            };
+            if report.targets.is_empty() {
+                anyhow::bail!("no scans to report on");
+            }
+            let format = format
+                .or_else(|| {
+                    output
+                        .as_deref()
+                        .and_then(|path| path.extension())
+                        .and_then(|ext| ext.to_str()?.parse().ok())
+                })
+                .unwrap_or(ReportFormat::Markdown);
+            write_report(&report, output.as_deref(), format)?;
+
+            status!(
+                "\n{} {} scans of {} targets: {} endpoints, {} new, {} no longer seen",
+                "✅ Report complete!".bright_green().bold(),
+                report.scans().to_string().bold(),
+                report.targets.len().to_string().bold(),
+                report.endpoints().to_string().bold(),
+                report.new_endpoints().to_string().bright_green(),
+                report.gone_endpoints().to_string().bright_red()
+            );
+            if let Some(output_path) = output {
+                status!(
+                    "{} {}",
+                    "📄 Report saved to:".dimmed(),
+                    output_path.display().to_string().bright_white().underline()
+                );
+            }
+        }
+
+        Commands::Config { action } => match action {
+            ConfigCommand::Init { path, force } => {
+                if path.exists() && !force {
+                    anyhow::bail!(
+                        "{} already exists; use --force to overwrite it",
+                        path.display()
+                    );
+                }
+                write_file(&path, DEFAULT_CONFIG.as_bytes())?;
+                status!(
+                    "{} {}",
+                    "✅ Configuration written to:".bright_green().bold(),
+                    path.display().to_string().bright_white().underline()
+                );
+            }
+            ConfigCommand::Show {
+                profile,
+                rate_limit,
+                timeout,
+                threads,
+            } => {
+                let settings = settings()?.with_profile(profile);
+                let effective = settings
+                    .apply(ScanConfig::default())
+                    .with_rate_limit(rate_limit)
+                    .with_timeout(timeout)
+                    .with_max_concurrent(threads);
+                let shown = ConfigFile {
+                    scan: ScanSettings {
+                        profile: settings.profile,
+                        ..ScanSettings::from(&effective)
+                    },
+                };
+                match &config_path {
+                    Some(path) => println!("# Read from {}", path.display()),
+                    None => println!("# No configuration file found"),
+                }
+                print!("{}", shown.to_toml()?);
+            }
+            ConfigCommand::Validate { path } => {
+                let Some(path) = path.or(config_path) else {
+                    anyhow::bail!(
+                        "no configuration file found; name one or run `endpointo config init`"
+                    );
+                };
+                let problems = ConfigFile::load(&path)?.problems();
+                if !problems.is_empty() {
+                    for problem in &problems {
+                        status!("  {} {}", "❌".red(), problem);
+                    }
+                    anyhow::bail!("{} has {} problem(s)", path.display(), problems.len());
+                }
+                status!(
+                    "{} {}",
+                    "✅ Configuration is valid:".bright_green().bold(),
+                    path.display().to_string().bright_white()
+                );
+            }
+        },
+
+        Commands::Doctor {
+            url,
+            http,
+            ignore_robots,
+            plugins,
+            burst,
+        } => {
+            let mut config = apply_http(settings()?.apply(ScanConfig::new(url.clone())), http)?;
+            if ignore_robots {
+                config = config.with_robots(false);
+            }
+            config = apply_plugins(config, plugins);
+
+            status!("{} {}...", "🩺 Checking".bright_white(), url.bold());
+            let checks = Doctor::new(config).with_burst(burst).run().await;
+            for check in &checks {
+                let icon = match check.status {
+                    CheckStatus::Pass => "✅".green(),
+                    CheckStatus::Warn => "⚠️ ".yellow(),
+                    CheckStatus::Fail => "❌".red(),
+                    CheckStatus::Skip => "➖".dimmed(),
+                };
+                println!("{} {:<14} {}", icon, check.name.bold(), check.detail);
+                if let Some(hint) = &check.hint {
+                    println!("   {} {}", "→".dimmed(), hint.bright_cyan());
+                }
+            }
+
+            let count = |status| checks.iter().filter(|c| c.status == status).count();
+            let failed = count(CheckStatus::Fail);
+            status!(
+                "\n{} {} passed, {} warnings, {} failed",
+                if failed == 0 {
+                    "✅ Ready to scan:".bright_green().bold()
+                } else {
+                    "❌ Not ready:".red().bold()
+                },
+                count(CheckStatus::Pass),
+                count(CheckStatus::Warn),
+                failed
+            );
+            if failed > 0 {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Plugins { action } => match action {
+            PluginsCommand::List { dir } => {
+                let plugins = list_plugins(&dir)?;
+                for plugin in &plugins {
+                    let hooks = if plugin.native {
+                        "native library".dimmed().to_string()
+                    } else if plugin.wasm {
+                        "WASM module".dimmed().to_string()
+                    } else if plugin.hooks.is_empty() {
+                        "no hooks".dimmed().to_string()
+                    } else {
+                        plugin.hooks.join(", ")
+                    };
+                    let api = match plugin.api_version {
+                        Some(version) => format!("  (plugin API {})", version).dimmed().to_string(),
+                        None => String::new(),
+                    };
+                    println!(
+                        "{}  {}{}",
+                        plugin.path.display().to_string().bold(),
+                        hooks,
+                        api
+                    );
+                }
+                for builtin in Builtin::ALL {
+                    println!(
+                        "{}  {}",
+                        format!("builtin:{}", builtin.as_str()).bold(),
+                        builtin.description().dimmed()
+                    );
+                }
+                status!(
+                    "{} {} in {}, {} built in (--enable-builtin)",
+                    "🔌 Plugins:".bright_white(),
+                    plugins.len().to_string().bold(),
+                    dir.display(),
+                    Builtin::ALL.len()
+                );
+            }
+            PluginsCommand::Test { file, input } => {
+                let endpoints = match input {
+                    Some(path) => load_result(&path)?.endpoints,
+                    None => sample_endpoints(),
+                };
+                let report = test_plugin(&file, &endpoints)?;
+                println!(
+                    "{} plugin API {}",
+                    "🔌".dimmed(),
+                    report.api_version.to_string().bold()
+                );
+                for hook in &report.hooks {
+                    if !hook.defined {
+                        println!("{} {} not defined", "➖".dimmed(), hook.hook.bold());
+                        continue;
+                    }
+                    println!(
+                        "{} {} called {} times, {} errors",
+                        if hook.errors.is_empty() {
+                            "✅".green()
+                        } else {
+                            "❌".red()
+                        },
+                        hook.hook.bold(),
+                        hook.calls,
+                        hook.errors.len()
+                    );
+                    for error in &hook.errors {
+                        println!("    {}", error);
+                    }
+                }
+                status!(
+                    "{} {} endpoints, {} URLs discovered, {} generated, {} dropped, {} changed",
+                    if report.errors() == 0 {
+                        "✅ Passed:".bright_green().bold()
+                    } else {
+                        "❌ Failed:".red().bold()
+                    },
+                    report.endpoints.to_string().bold(),
+                    report.discovered,
+                    report.generated,
+                    report.dropped,
+                    report.changed
+                );
+                if report.errors() > 0 {
+                    std::process::exit(1);
+                }
+            }
+            PluginsCommand::New { name, dir, force } => {
+                let path = write_skeleton(&dir, &name, force)?;
+                status!(
+                    "{} {}",
+                    "✅ Plugin written to:".bright_green().bold(),
+                    path.display().to_string().bright_white().underline()
+                );
+            }
+        },
+
+        Commands::Completions { shell } => {
+            print!("{}", completions::generate(shell, Cli::command()));
+        }
+
+        Commands::Man { output_dir } => match output_dir {
+            Some(dir) => {
+                completions::write_man_pages(Cli::command(), &dir)?;
+                status!(
+                    "{} {}",
+                    "✅ Man pages written to:".bright_green().bold(),
+                    dir.display().to_string().bright_white().underline()
+                );
+            }
+            None => print!("{}", completions::render_man(Cli::command())),
+        },
     }
 
     Ok(())
@@ -0,0 +1,32 @@
+use crate::error::Error;
+use crate::types::Endpoint;
+
+/// Receives progress events from a running scan
+///
+/// Register observers with `Scanner::with_observer`. Every method has an
+/// empty default, so implementations only override what they need. Calls
+/// can come from several tasks at once when targets are scanned
+/// concurrently.
+pub trait ScanObserver: Send + Sync {
+    /// An asset was found on a crawled page
+    fn on_asset_discovered(&self, _url: &str) {}
+
+    /// Downloading an asset started
+    fn on_asset_fetching(&self, _url: &str) {}
+
+    /// An asset was downloaded; `bytes` is the size of the body
+    fn on_asset_fetched(&self, _url: &str, _bytes: usize) {}
+
+    /// An asset was parsed; `endpoints` counts what it held before plugins,
+    /// filters, and deduplication
+    fn on_asset_parsed(&self, _url: &str, _endpoints: usize) {}
+
+    /// An endpoint made it through plugins, filters, and deduplication
+    fn on_endpoint_found(&self, _endpoint: &Endpoint) {}
+
+    /// Fetching or parsing `url` failed; the scan carries on without it
+    fn on_error(&self, _url: &str, _error: &Error) {}
+
+    /// The scan of `target` finished with `total_endpoints` unique endpoints
+    fn on_complete(&self, _target: &str, _total_endpoints: usize) {}
+}
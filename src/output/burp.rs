@@ -0,0 +1,175 @@
+//! Burp Suite integration
+//!
+//! Endpoints are exported as a Burp site map (the XML Burp writes with
+//! "Save items" and reads back with "Import"), each with a minimal request
+//! so it can be sent to Repeater or Intruder. With Burp Professional's REST
+//! API enabled, discovered URLs can also be handed to Burp as a new scan.
+
+use crate::error::{Error, Result};
+use crate::output::absolute_url;
+use crate::types::Endpoint;
+use base64::Engine;
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::HashSet;
+use url::Url;
+
+/// Absolute HTTP(S) URLs of endpoints, deduplicated in order
+///
+/// Relative URLs are resolved against the endpoint's `target` metadata,
+/// then `base`; ones that can't be resolved are left out.
+pub fn burp_urls(endpoints: &[Endpoint], base: Option<&str>) -> Vec<Url> {
+    let mut seen = HashSet::new();
+    endpoints
+        .iter()
+        .filter_map(|endpoint| absolute_url(endpoint, base))
+        .filter(|url| seen.insert(url.to_string()))
+        .collect()
+}
+
+/// Serialize endpoints as a Burp site map
+pub fn serialize_burp(endpoints: &[Endpoint], base: Option<&str>) -> String {
+    let now = Utc::now().format("%a %b %d %H:%M:%S UTC %Y").to_string();
+    let mut xml = String::from(r#"<?xml version="1.0"?>"#);
+    xml.push_str(&format!(
+        "\n<items burpVersion=\"\" exportTime=\"{}\">",
+        now
+    ));
+
+    let mut seen = HashSet::new();
+    for endpoint in endpoints {
+        let Some(url) = absolute_url(endpoint, base) else {
+            continue;
+        };
+        let method = endpoint
+            .method
+            .as_deref()
+            .unwrap_or("GET")
+            .to_ascii_uppercase();
+        if !seen.insert((method.clone(), url.to_string())) {
+            continue;
+        }
+        let host = url.host_str().unwrap_or_default();
+        let port = url.port_or_known_default().unwrap_or(80);
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        let extension = url
+            .path_segments()
+            .and_then(|mut s| s.next_back())
+            .and_then(|last| last.rsplit_once('.'))
+            .map_or("null", |(_, ext)| ext);
+        let host_header = match url.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        };
+        let request = format!(
+            "{} {} HTTP/1.1\r\nHost: {}\r\nAccept: */*\r\n\r\n",
+            method, path, host_header
+        );
+        let (status, length, mimetype) = match &endpoint.probe {
+            Some(probe) => (
+                probe.status.to_string(),
+                probe
+                    .content_length
+                    .map(|l| l.to_string())
+                    .unwrap_or_default(),
+                probe.content_type.clone().unwrap_or_default(),
+            ),
+            None => Default::default(),
+        };
+        let comment = endpoint.source.as_deref().unwrap_or_default();
+
+        xml.push_str("\n  <item>");
+        xml.push_str(&format!("\n    <time>{}</time>", now));
+        xml.push_str(&format!("\n    <url>{}</url>", cdata(url.as_str())));
+        xml.push_str(&format!("\n    <host ip=\"\">{}</host>", escape(host)));
+        xml.push_str(&format!("\n    <port>{}</port>", port));
+        xml.push_str(&format!("\n    <protocol>{}</protocol>", url.scheme()));
+        xml.push_str(&format!("\n    <method>{}</method>", cdata(&method)));
+        xml.push_str(&format!("\n    <path>{}</path>", cdata(&path)));
+        xml.push_str(&format!(
+            "\n    <extension>{}</extension>",
+            escape(extension)
+        ));
+        xml.push_str(&format!(
+            "\n    <request base64=\"true\">{}</request>",
+            cdata(&base64::engine::general_purpose::STANDARD.encode(request))
+        ));
+        xml.push_str(&format!("\n    <status>{}</status>", status));
+        xml.push_str(&format!(
+            "\n    <responselength>{}</responselength>",
+            length
+        ));
+        xml.push_str(&format!("\n    <mimetype>{}</mimetype>", escape(&mimetype)));
+        xml.push_str("\n    <response base64=\"true\"></response>");
+        xml.push_str(&format!("\n    <comment>{}</comment>", escape(comment)));
+        xml.push_str("\n  </item>");
+    }
+
+    xml.push_str("\n</items>\n");
+    xml
+}
+
+fn cdata(s: &str) -> String {
+    format!("<![CDATA[{}]]>", s.replace("]]>", "]]]]><![CDATA[>"))
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Body of a Burp REST API scan request
+#[derive(Serialize)]
+struct ScanRequest<'a> {
+    urls: Vec<&'a str>,
+}
+
+/// Client for Burp Professional's REST API
+pub struct BurpApi {
+    base: Url,
+    client: reqwest::Client,
+}
+
+impl BurpApi {
+    /// Connect to the API at `base`, including the API key if one is set
+    /// (e.g. `http://127.0.0.1:1337/<key>`)
+    pub fn new(base: &str) -> Result<Self> {
+        let mut base = Url::parse(base)?;
+        if !base.path().ends_with('/') {
+            base.set_path(&format!("{}/", base.path()));
+        }
+        Ok(Self {
+            base,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// Start a Burp scan of `urls`, returning its task ID
+    pub async fn scan(&self, urls: &[Url]) -> Result<Option<String>> {
+        let request = ScanRequest {
+            urls: urls.iter().map(Url::as_str).collect(),
+        };
+        let response = self
+            .client
+            .post(self.base.join("v0.1/scan")?)
+            .json(&request)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(Error::Other(format!(
+                "Burp API returned {}",
+                response.status()
+            )));
+        }
+        Ok(response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string))
+    }
+}
@@ -0,0 +1,196 @@
+//! Ready-to-run `curl` and HTTPie commands
+//!
+//! One command per endpoint with an HTTP(S) URL, for manual verification.
+//! Placeholder path segments (`:id`, `${id}`, `{id}`) become `<id>`, query
+//! parameters the URL doesn't carry are added as `name=<name>`, and known
+//! body keys are sent as JSON with `<key>` values. Everything is single
+//! quoted for POSIX shells.
+
+use crate::output::absolute_url;
+use crate::types::Endpoint;
+use url::Url;
+
+/// Which client the commands are for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandStyle {
+    Curl,
+    Httpie,
+}
+
+/// Serialize endpoints as shell commands, one per line
+///
+/// `headers` are added to every command. Relative URLs are resolved against
+/// the endpoint's target, then `base`.
+pub fn serialize_commands(
+    endpoints: &[Endpoint],
+    base: Option<&str>,
+    style: CommandStyle,
+    headers: &[(String, String)],
+) -> String {
+    endpoints
+        .iter()
+        .filter_map(|endpoint| {
+            let url = absolute_url(endpoint, base)?;
+            Some(command(endpoint, url, style, headers) + "\n")
+        })
+        .collect()
+}
+
+fn command(
+    endpoint: &Endpoint,
+    mut url: Url,
+    style: CommandStyle,
+    headers: &[(String, String)],
+) -> String {
+    let method = endpoint
+        .method
+        .as_deref()
+        .unwrap_or("GET")
+        .to_ascii_uppercase();
+
+    let path: Vec<String> = url.path().split('/').map(placeholder_segment).collect();
+    url.set_path(&path.join("/"));
+    let present: Vec<String> = url
+        .query_pairs()
+        .map(|(name, _)| name.into_owned())
+        .collect();
+    let missing: Vec<&String> = endpoint
+        .params
+        .iter()
+        .flatten()
+        .filter(|name| !present.contains(name))
+        .collect();
+    // HTTPie takes query parameters as `name==value` items
+    if style == CommandStyle::Curl && !missing.is_empty() {
+        let mut pairs = url.query_pairs_mut();
+        for name in &missing {
+            pairs.append_pair(name, &format!("<{}>", name));
+        }
+    }
+    let url = url.as_str().replace("%3C", "<").replace("%3E", ">");
+
+    let body_keys: Vec<&str> = endpoint
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("body_params"))
+        .map(|keys| keys.split(',').filter(|k| !k.is_empty()).collect())
+        .unwrap_or_default();
+
+    let mut parts: Vec<String> = Vec::new();
+    match style {
+        CommandStyle::Curl => {
+            parts.push("curl -i".to_string());
+            if method != "GET" {
+                parts.push(format!("-X {}", method));
+            }
+            for (name, value) in headers {
+                parts.push(format!("-H {}", quote(&format!("{}: {}", name, value))));
+            }
+            if !body_keys.is_empty() {
+                let body: serde_json::Map<String, serde_json::Value> = body_keys
+                    .iter()
+                    .map(|k| (k.to_string(), format!("<{}>", k).into()))
+                    .collect();
+                parts.push(format!("-H {}", quote("Content-Type: application/json")));
+                parts.push(format!(
+                    "--data {}",
+                    quote(&serde_json::Value::Object(body).to_string())
+                ));
+            }
+            parts.push(quote(&url));
+        }
+        CommandStyle::Httpie => {
+            parts.push(format!("http {}", method));
+            parts.push(quote(&url));
+            for (name, value) in headers {
+                parts.push(quote(&format!("{}:{}", name, value)));
+            }
+            for name in missing {
+                parts.push(quote(&format!("{}==<{}>", name, name)));
+            }
+            for key in body_keys {
+                parts.push(quote(&format!("{}=<{}>", key, key)));
+            }
+        }
+    }
+    parts.join(" ")
+}
+
+/// `<name>` for a placeholder path segment, the segment itself otherwise
+fn placeholder_segment(segment: &str) -> String {
+    let decoded = segment.replace("%7B", "{").replace("%7D", "}");
+    let is_placeholder = decoded.starts_with(':')
+        || decoded.starts_with('{')
+        || decoded.starts_with("${")
+        || decoded.starts_with("%3C");
+    if !is_placeholder {
+        return segment.to_string();
+    }
+    let name: String = decoded
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .collect();
+    format!("<{}>", if name.is_empty() { "id" } else { &name })
+}
+
+/// Single-quote a shell word
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EndpointType;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_commands() {
+        let endpoints = vec![
+            Endpoint::new("/api/users/:userId/posts".to_string(), EndpointType::Rest)
+                .with_params(vec!["page".to_string()]),
+            Endpoint::new(
+                "https://example.com/api/login".to_string(),
+                EndpointType::Rest,
+            )
+            .with_method("POST")
+            .with_metadata(HashMap::from([(
+                "body_params".to_string(),
+                "user".to_string(),
+            )])),
+        ];
+        let headers = vec![("Authorization".to_string(), "Bearer it's".to_string())];
+
+        let curl = serialize_commands(
+            &endpoints,
+            Some("https://example.com"),
+            CommandStyle::Curl,
+            &headers,
+        );
+        let lines: Vec<&str> = curl.lines().collect();
+        assert_eq!(
+            lines[0],
+            r"curl -i -H 'Authorization: Bearer it'\''s' 'https://example.com/api/users/<userId>/posts?page=<page>'"
+        );
+        assert_eq!(
+            lines[1],
+            r#"curl -i -X POST -H 'Authorization: Bearer it'\''s' -H 'Content-Type: application/json' --data '{"user":"<user>"}' 'https://example.com/api/login'"#
+        );
+
+        let httpie = serialize_commands(
+            &endpoints,
+            Some("https://example.com"),
+            CommandStyle::Httpie,
+            &[],
+        );
+        let lines: Vec<&str> = httpie.lines().collect();
+        assert_eq!(
+            lines[0],
+            "http GET 'https://example.com/api/users/<userId>/posts' 'page==<page>'"
+        );
+        assert_eq!(
+            lines[1],
+            "http POST 'https://example.com/api/login' 'user=<user>'"
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! Rendering of endpoint diffs
+//!
+//! Diffs print as colored `+`/`-`/`~` lines on the terminal, and can be
+//! written as JSON for automation or as Markdown for tickets and chat
+//! notifications.
+
+use crate::diff::EndpointDiff;
+use crate::error::Result;
+use crate::types::Endpoint;
+use colored::*;
+
+/// How a diff is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffFormat {
+    /// Colored lines for the terminal (plain when colors are off)
+    Text,
+    Json,
+    Markdown,
+}
+
+impl DiffFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiffFormat::Text => "text",
+            DiffFormat::Json => "json",
+            DiffFormat::Markdown => "markdown",
+        }
+    }
+}
+
+impl std::str::FromStr for DiffFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(DiffFormat::Text),
+            "json" => Ok(DiffFormat::Json),
+            "markdown" | "md" => Ok(DiffFormat::Markdown),
+            _ => Err(format!(
+                "unknown diff format '{}' (expected text, json or markdown)",
+                s
+            )),
+        }
+    }
+}
+
+/// Render a diff in the given format
+pub fn render_diff(diff: &EndpointDiff, format: DiffFormat) -> Result<String> {
+    Ok(match format {
+        DiffFormat::Text => render_text(diff),
+        DiffFormat::Json => serde_json::to_string_pretty(diff)?,
+        DiffFormat::Markdown => render_markdown(diff),
+    })
+}
+
+/// Method and URL of an endpoint
+fn describe(ep: &Endpoint) -> String {
+    format!(
+        "{:<7} {}",
+        ep.method.as_deref().unwrap_or("GET").to_uppercase(),
+        ep.url
+    )
+}
+
+fn render_text(diff: &EndpointDiff) -> String {
+    let mut text = format!(
+        "\n{}\n{}\n",
+        "🔀 Endpoint Changes".bold().bright_white().on_blue(),
+        "─".repeat(80).dimmed()
+    );
+    for ep in &diff.added {
+        text.push_str(&format!(
+            "{} {}\n",
+            "+".green().bold(),
+            describe(ep).green()
+        ));
+    }
+    for ep in &diff.removed {
+        text.push_str(&format!("{} {}\n", "-".red().bold(), describe(ep).red()));
+    }
+    for change in &diff.changed {
+        text.push_str(&format!(
+            "{} {} {}\n",
+            "~".yellow().bold(),
+            describe(&change.after).yellow(),
+            format!("[{}]", change.changes.join(", ")).dimmed()
+        ));
+    }
+    text.push_str(&format!(
+        "{}\n{} {}  {} {}  {} {}\n",
+        "─".repeat(80).dimmed(),
+        "Added:".bold(),
+        diff.added.len().to_string().bright_green(),
+        "Removed:".bold(),
+        diff.removed.len().to_string().bright_red(),
+        "Changed:".bold(),
+        diff.changed.len().to_string().bright_yellow()
+    ));
+    text
+}
+
+fn render_markdown(diff: &EndpointDiff) -> String {
+    let mut md = format!(
+        "## Endpoint changes\n\n**{}** added, **{}** removed, **{}** changed\n",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len()
+    );
+    let sections = [("Added", &diff.added), ("Removed", &diff.removed)];
+    for (title, endpoints) in sections {
+        if endpoints.is_empty() {
+            continue;
+        }
+        md.push_str(&format!(
+            "\n### {}\n\n| Method | URL | Type | Source |\n| --- | --- | --- | --- |\n",
+            title
+        ));
+        for ep in endpoints {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                ep.method.as_deref().unwrap_or("GET").to_uppercase(),
+                code(&ep.url),
+                ep.endpoint_type.as_str(),
+                ep.source.as_deref().map(cell).unwrap_or_default()
+            ));
+        }
+    }
+    if !diff.changed.is_empty() {
+        md.push_str("\n### Changed\n\n| Method | URL | Changes |\n| --- | --- | --- |\n");
+        for change in &diff.changed {
+            md.push_str(&format!(
+                "| {} | {} | {} |\n",
+                change
+                    .after
+                    .method
+                    .as_deref()
+                    .unwrap_or("GET")
+                    .to_uppercase(),
+                code(&change.after.url),
+                cell(&change.changes.join(", "))
+            ));
+        }
+    }
+    md
+}
+
+/// Inline code in a table cell
+pub(crate) fn code(s: &str) -> String {
+    format!("`{}`", s.replace('`', "'").replace('|', "\\|"))
+}
+
+/// Text safe to put in a table cell
+pub(crate) fn cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::diff_endpoints;
+    use crate::types::EndpointType;
+
+    #[test]
+    fn test_render_diff() {
+        let old = vec![
+            Endpoint::new("/api/users".to_string(), EndpointType::Rest),
+            Endpoint::new("/api/legacy".to_string(), EndpointType::Rest),
+        ];
+        let new = vec![
+            Endpoint::new("/api/users".to_string(), EndpointType::GraphQL),
+            Endpoint::new("/api/orders|v2".to_string(), EndpointType::Rest).with_method("post"),
+        ];
+        let diff = diff_endpoints(&old, &new);
+
+        colored::control::set_override(false);
+        let text = render_diff(&diff, DiffFormat::Text).unwrap();
+        assert!(text.contains("+ POST    /api/orders|v2\n"));
+        assert!(text.contains("- GET     /api/legacy\n"));
+        assert!(text.contains("~ GET     /api/users [type]\n"));
+
+        let md = render_diff(&diff, DiffFormat::Markdown).unwrap();
+        assert!(md.contains("**1** added, **1** removed, **1** changed"));
+        assert!(md.contains("| POST | `/api/orders\\|v2` | rest |  |\n"));
+        assert!(md.contains("### Removed"));
+        assert!(md.contains("| GET | `/api/users` | type |\n"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_diff(&diff, DiffFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["added"][0]["url"], "/api/orders|v2");
+        assert_eq!("md".parse::<DiffFormat>().unwrap(), DiffFormat::Markdown);
+    }
+}
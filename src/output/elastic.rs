@@ -0,0 +1,177 @@
+//! Elasticsearch / OpenSearch export
+//!
+//! Endpoints are bulk-indexed one document each, carrying the scan's
+//! target, timestamp and tool version next to the endpoint's own fields,
+//! plus the resolved URL and host for dashboards. Document IDs hash the
+//! target, method and URL, so re-scanning a target updates its documents
+//! instead of piling up duplicates.
+
+use crate::error::{Error, Result};
+use crate::output::absolute_url;
+use crate::types::{Endpoint, ScanResult};
+use base64::Engine;
+use ring::digest;
+use serde::Serialize;
+use url::Url;
+
+/// Index documents go to unless told otherwise
+pub const DEFAULT_INDEX: &str = "endpointo";
+
+/// Documents per bulk request
+const BULK_SIZE: usize = 1000;
+
+/// An endpoint as indexed
+#[derive(Serialize)]
+struct Document<'a> {
+    #[serde(rename = "@timestamp")]
+    timestamp: &'a str,
+    target: &'a str,
+    tool_version: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    host: Option<String>,
+    #[serde(flatten)]
+    endpoint: &'a Endpoint,
+}
+
+/// How requests authenticate
+enum Auth {
+    None,
+    Basic(String, Option<String>),
+    ApiKey(String),
+}
+
+/// Client for an Elasticsearch or OpenSearch cluster's bulk API
+pub struct Elasticsearch {
+    base: Url,
+    index: String,
+    auth: Auth,
+    client: reqwest::Client,
+}
+
+impl Elasticsearch {
+    /// Index into the cluster at `base` (e.g. `https://localhost:9200`)
+    pub fn new(base: &str) -> Result<Self> {
+        let mut base = Url::parse(base)?;
+        if !base.path().ends_with('/') {
+            base.set_path(&format!("{}/", base.path()));
+        }
+        Ok(Self {
+            base,
+            index: DEFAULT_INDEX.to_string(),
+            auth: Auth::None,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// Index name to write to
+    pub fn with_index(mut self, index: Option<String>) -> Self {
+        if let Some(index) = index {
+            self.index = index;
+        }
+        self
+    }
+
+    /// Authenticate with a username and password
+    pub fn with_basic_auth(mut self, user: Option<String>, password: Option<String>) -> Self {
+        if let Some(user) = user {
+            self.auth = Auth::Basic(user, password);
+        }
+        self
+    }
+
+    /// Authenticate with an API key, either encoded or as `id:key`
+    pub fn with_api_key(mut self, key: Option<String>) -> Self {
+        if let Some(key) = key {
+            let encoded = if key.contains(':') {
+                base64::engine::general_purpose::STANDARD.encode(key)
+            } else {
+                key
+            };
+            self.auth = Auth::ApiKey(encoded);
+        }
+        self
+    }
+
+    /// Index a scan result's endpoints, returning how many were indexed
+    pub async fn index(&self, result: &ScanResult) -> Result<usize> {
+        let url = self.base.join("_bulk")?;
+        for chunk in result.endpoints.chunks(BULK_SIZE) {
+            let mut request = self
+                .client
+                .post(url.clone())
+                .header(reqwest::header::CONTENT_TYPE, "application/x-ndjson")
+                .body(bulk_body(&self.index, result, chunk)?);
+            request = match &self.auth {
+                Auth::None => request,
+                Auth::Basic(user, password) => request.basic_auth(user, password.as_ref()),
+                Auth::ApiKey(key) => {
+                    request.header(reqwest::header::AUTHORIZATION, format!("ApiKey {}", key))
+                }
+            };
+            let response = request.send().await?;
+            let status = response.status();
+            if !status.is_success() {
+                return Err(Error::Other(format!("Elasticsearch returned {}", status)));
+            }
+            let body: serde_json::Value = response.json().await?;
+            if body["errors"].as_bool().unwrap_or(false) {
+                let reason = body["items"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .find_map(|item| item["index"]["error"]["reason"].as_str())
+                    .unwrap_or("unknown error");
+                return Err(Error::Other(format!(
+                    "Elasticsearch rejected documents: {}",
+                    reason
+                )));
+            }
+        }
+        Ok(result.endpoints.len())
+    }
+}
+
+/// Bulk API body indexing `endpoints` into `index`
+fn bulk_body(index: &str, result: &ScanResult, endpoints: &[Endpoint]) -> Result<String> {
+    let mut body = String::new();
+    for endpoint in endpoints {
+        let full_url = absolute_url(endpoint, Some(&result.target));
+        let method = endpoint.method.as_deref().unwrap_or("GET").to_uppercase();
+        let action = serde_json::json!({
+            "index": {
+                "_index": index,
+                "_id": document_id(&result.target, &method, &endpoint.url),
+            }
+        });
+        let document = Document {
+            timestamp: &result.timestamp,
+            target: &result.target,
+            tool_version: &result.tool_version,
+            host: full_url
+                .as_ref()
+                .and_then(|u| u.host_str())
+                .map(str::to_string),
+            full_url: full_url.map(String::from),
+            endpoint,
+        };
+        body.push_str(&action.to_string());
+        body.push('\n');
+        body.push_str(&serde_json::to_string(&document)?);
+        body.push('\n');
+    }
+    Ok(body)
+}
+
+/// Stable ID of an endpoint found on a target
+fn document_id(target: &str, method: &str, url: &str) -> String {
+    let hash = digest::digest(
+        &digest::SHA256,
+        format!("{}\n{}\n{}", target, method, url).as_bytes(),
+    );
+    hash.as_ref()[..16]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -0,0 +1,215 @@
+//! Field projection
+//!
+//! Restricts output to selected endpoint columns, so `--fields url` gives a
+//! plain URL list and `--fields url,method` a two-column one, without
+//! post-processing the JSON.
+
+use crate::types::Endpoint;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::Value;
+use std::fmt;
+
+/// A column of endpoint output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field {
+    Url,
+    Method,
+    Type,
+    Source,
+    Line,
+    Params,
+    Tags,
+    Severity,
+    Confidence,
+    Status,
+}
+
+/// Columns of CSV output when no fields are selected
+pub const DEFAULT_CSV_FIELDS: &[Field] = &[
+    Field::Url,
+    Field::Method,
+    Field::Type,
+    Field::Source,
+    Field::Line,
+];
+
+impl Field {
+    /// Name of the field as used on the command line and as output key
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Field::Url => "url",
+            Field::Method => "method",
+            Field::Type => "type",
+            Field::Source => "source",
+            Field::Line => "line",
+            Field::Params => "params",
+            Field::Tags => "tags",
+            Field::Severity => "severity",
+            Field::Confidence => "confidence",
+            Field::Status => "status",
+        }
+    }
+
+    /// The field's value for an endpoint, `null` when it has none
+    pub fn value(&self, endpoint: &Endpoint) -> Value {
+        let value = match self {
+            Field::Url => serde_json::to_value(&endpoint.url),
+            Field::Method => serde_json::to_value(&endpoint.method),
+            Field::Type => serde_json::to_value(&endpoint.endpoint_type),
+            Field::Source => serde_json::to_value(&endpoint.source),
+            Field::Line => serde_json::to_value(endpoint.line),
+            Field::Params => serde_json::to_value(&endpoint.params),
+            Field::Tags => serde_json::to_value(&endpoint.tags),
+            Field::Severity => serde_json::to_value(endpoint.severity),
+            Field::Confidence => serde_json::to_value(endpoint.confidence),
+            Field::Status => serde_json::to_value(endpoint.probe.as_ref().map(|p| p.status)),
+        };
+        value.unwrap_or(Value::Null)
+    }
+
+    /// The field's value as plain text: lists comma-joined, empty when unset
+    pub fn text(&self, endpoint: &Endpoint) -> String {
+        match self.value(endpoint) {
+            Value::Null => String::new(),
+            Value::String(s) => s,
+            Value::Array(items) => items
+                .iter()
+                .map(|item| match item {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(","),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Field {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "url" => Ok(Field::Url),
+            "method" => Ok(Field::Method),
+            "type" | "endpoint_type" => Ok(Field::Type),
+            "source" => Ok(Field::Source),
+            "line" => Ok(Field::Line),
+            "params" => Ok(Field::Params),
+            "tags" => Ok(Field::Tags),
+            "severity" => Ok(Field::Severity),
+            "confidence" => Ok(Field::Confidence),
+            "status" => Ok(Field::Status),
+            _ => Err(format!(
+                "unknown field '{}' (expected url, method, type, source, line, params, tags, severity, confidence or status)",
+                s
+            )),
+        }
+    }
+}
+
+/// An endpoint reduced to selected fields, serialized as an object with
+/// the fields in the order they were selected
+#[derive(Debug, Clone, Copy)]
+pub struct Projected<'a> {
+    endpoint: &'a Endpoint,
+    fields: &'a [Field],
+}
+
+impl Serialize for Projected<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for field in self.fields {
+            map.serialize_entry(field.as_str(), &field.value(self.endpoint))?;
+        }
+        map.end()
+    }
+}
+
+/// Project endpoints onto the selected fields
+pub fn project<'a>(endpoints: &'a [Endpoint], fields: &'a [Field]) -> Vec<Projected<'a>> {
+    endpoints
+        .iter()
+        .map(|endpoint| Projected { endpoint, fields })
+        .collect()
+}
+
+/// Selected fields as tab-separated lines, without a header
+pub fn serialize_fields_text(endpoints: &[Endpoint], fields: &[Field]) -> String {
+    endpoints
+        .iter()
+        .map(|endpoint| {
+            let columns: Vec<String> = fields.iter().map(|f| f.text(endpoint)).collect();
+            columns.join("\t") + "\n"
+        })
+        .collect()
+}
+
+/// Selected fields as CSV with a header row
+pub fn serialize_csv(endpoints: &[Endpoint], fields: &[Field]) -> String {
+    let header: Vec<&str> = fields.iter().map(Field::as_str).collect();
+    let mut csv = header.join(",") + "\n";
+    for endpoint in endpoints {
+        let row: Vec<String> = fields
+            .iter()
+            .map(|f| csv_escape(&f.text(endpoint)))
+            .collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_escape(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EndpointType;
+
+    #[test]
+    fn test_field_projection() {
+        let endpoints = vec![
+            Endpoint::new("/api/users".to_string(), EndpointType::Rest)
+                .with_method("POST")
+                .with_params(vec!["page".to_string(), "q".to_string()]),
+            Endpoint::new("/graphql".to_string(), EndpointType::GraphQL),
+        ];
+        let fields: Vec<Field> = "url,method,type,params"
+            .split(',')
+            .map(|f| f.parse().unwrap())
+            .collect();
+
+        let projected = project(&endpoints, &fields);
+        assert_eq!(
+            serde_json::to_string(&projected[0]).unwrap(),
+            r#"{"url":"/api/users","method":"POST","type":"rest","params":["page","q"]}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&projected[1]).unwrap(),
+            r#"{"url":"/graphql","method":null,"type":"graphql","params":null}"#
+        );
+
+        assert_eq!(
+            serialize_fields_text(&endpoints, &[Field::Url]),
+            "/api/users\n/graphql\n"
+        );
+        assert_eq!(
+            serialize_csv(&endpoints, &fields),
+            "url,method,type,params\n/api/users,POST,rest,\"page,q\"\n/graphql,,graphql,\n"
+        );
+        assert!("verb".parse::<Field>().is_err());
+    }
+}
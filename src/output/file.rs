@@ -0,0 +1,412 @@
+//! Output files: gzip compression and sharding
+//!
+//! Any output path ending in `.gz` is written gzip-compressed. For result
+//! sets too large for one file, [`ShardWriter`] splits endpoints into
+//! numbered JSON Lines shards (`results-0001.jsonl.gz`, ...) and writes a
+//! manifest (`results.manifest.json`) listing them next to the scan's
+//! target, stats and versions. Multi-target scans can instead write a file
+//! per target, named by [`target_output_path`].
+
+use crate::error::{Error, Result};
+use crate::output::fields::Field;
+use crate::output::JsonlWriter;
+use crate::types::{Endpoint, Finding, ScanResult, ScanStats, Technology};
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Stdout, Write};
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// Whether a path asks for gzip compression
+pub fn is_gzip(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "gz")
+}
+
+/// A destination for output, compressed when its path ends in `.gz`
+pub enum OutputFile {
+    Stdout(Stdout),
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<File>),
+}
+
+impl OutputFile {
+    /// Create (or truncate) a file
+    pub fn create(path: &Path) -> Result<Self> {
+        Ok(Self::wrap(path, File::create(path)?))
+    }
+
+    /// Append to a file, creating it if needed
+    ///
+    /// Appending to a gzip file adds a member, which gzip readers treat as
+    /// one continuous stream.
+    pub fn append(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::wrap(path, file))
+    }
+
+    fn wrap(path: &Path, file: File) -> Self {
+        if is_gzip(path) {
+            OutputFile::Gzip(GzEncoder::new(file, Compression::default()))
+        } else {
+            OutputFile::Plain(BufWriter::new(file))
+        }
+    }
+
+    /// Flush everything, ending the gzip stream if compressed
+    pub fn finish(self) -> Result<()> {
+        match self {
+            OutputFile::Stdout(mut out) => out.flush()?,
+            OutputFile::Plain(mut file) => file.flush()?,
+            OutputFile::Gzip(encoder) => {
+                encoder.finish()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Write for OutputFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            OutputFile::Stdout(out) => out.write(buf),
+            OutputFile::Plain(file) => file.write(buf),
+            OutputFile::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            OutputFile::Stdout(out) => out.flush(),
+            OutputFile::Plain(file) => file.flush(),
+            OutputFile::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Write `contents` to a file, compressed when its path ends in `.gz`
+pub fn write_file(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut file = OutputFile::create(path)?;
+    file.write_all(contents)?;
+    file.finish()
+}
+
+/// Read a file as text, decompressing it when its path ends in `.gz`
+pub fn read_to_string(path: &Path) -> Result<String> {
+    if !is_gzip(path) {
+        return Ok(std::fs::read_to_string(path)?);
+    }
+    let mut text = String::new();
+    MultiGzDecoder::new(File::open(path)?).read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// Path in `dir` for one target's results, e.g. `api.example.com_8443_v1.json`
+///
+/// Names come from the target's host, explicit port and path, with
+/// anything unsafe in a file name replaced by `_`. Names already in
+/// `taken` get a `-2`, `-3`, ... suffix, and the chosen one is added.
+pub fn target_output_path(
+    dir: &Path,
+    target: &str,
+    extension: &str,
+    taken: &mut HashSet<String>,
+) -> PathBuf {
+    let raw = match Url::parse(target) {
+        Ok(url) => {
+            let mut parts = vec![url.host_str().unwrap_or("target").to_string()];
+            parts.extend(url.port().map(|p| p.to_string()));
+            parts.extend(
+                url.path()
+                    .split('/')
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string),
+            );
+            parts.join("_")
+        }
+        Err(_) => target.to_string(),
+    };
+    let stem: String = raw
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let stem = stem.trim_matches(['.', '_']);
+    let stem = if stem.is_empty() { "target" } else { stem };
+
+    let mut name = stem.to_string();
+    let mut n = 1;
+    while !taken.insert(name.clone()) {
+        n += 1;
+        name = format!("{}-{}", stem, n);
+    }
+    if extension.is_empty() {
+        dir.join(name)
+    } else {
+        dir.join(format!("{}.{}", name, extension))
+    }
+}
+
+/// Whether a path names a shard manifest
+pub fn is_manifest(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.ends_with(".manifest.json"))
+}
+
+/// One shard listed in a manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Shard {
+    /// File name, relative to the manifest
+    pub file: String,
+    pub endpoints: usize,
+    /// Size on disk
+    pub bytes: u64,
+}
+
+/// Index of a sharded result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub schema_version: u32,
+    pub tool_version: String,
+    pub target: String,
+    pub timestamp: String,
+    pub total_endpoints: usize,
+    pub shard_size: usize,
+    pub shards: Vec<Shard>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub findings: Vec<Finding>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub technologies: Vec<Technology>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<ScanStats>,
+}
+
+/// Writes endpoints into numbered JSON Lines shards of a fixed size
+///
+/// For an output path `dir/results.jsonl.gz`, shards are
+/// `dir/results-0001.jsonl.gz`, ... and the manifest is
+/// `dir/results.manifest.json`. Shards are compressed when the path ends in
+/// `.gz`.
+pub struct ShardWriter {
+    dir: PathBuf,
+    stem: String,
+    extension: &'static str,
+    shard_size: usize,
+    fields: Vec<Field>,
+    current: Option<JsonlWriter>,
+    shards: Vec<Shard>,
+}
+
+impl ShardWriter {
+    /// Shard output for `path`, `shard_size` endpoints per file
+    pub fn new(path: &Path, shard_size: usize) -> Result<Self> {
+        if shard_size == 0 {
+            return Err(Error::ValidationError(
+                "shard size must be at least 1".to_string(),
+            ));
+        }
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| Error::ValidationError(format!("invalid output path: {:?}", path)))?;
+        let name = name.strip_suffix(".gz").unwrap_or(name);
+        let stem = match name.rsplit_once('.') {
+            Some((stem, _)) if !stem.is_empty() => stem,
+            _ => name,
+        };
+        let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        if !dir.as_os_str().is_empty() {
+            std::fs::create_dir_all(&dir)?;
+        }
+        Ok(Self {
+            dir,
+            stem: stem.to_string(),
+            extension: if is_gzip(path) { "jsonl.gz" } else { "jsonl" },
+            shard_size,
+            fields: Vec::new(),
+            current: None,
+            shards: Vec::new(),
+        })
+    }
+
+    /// Write only these fields of each endpoint (all when empty)
+    pub fn with_fields(mut self, fields: Vec<Field>) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    /// Path of the manifest written by [`finish`](Self::finish)
+    pub fn manifest_path(&self) -> PathBuf {
+        self.dir.join(format!("{}.manifest.json", self.stem))
+    }
+
+    /// Write one endpoint, starting a new shard when the current one is full
+    pub fn write(&mut self, endpoint: &Endpoint) -> Result<()> {
+        let full = self
+            .shards
+            .last()
+            .is_some_and(|shard| shard.endpoints >= self.shard_size);
+        if self.current.is_none() || full {
+            self.close_current()?;
+            let file = format!(
+                "{}-{:04}.{}",
+                self.stem,
+                self.shards.len() + 1,
+                self.extension
+            );
+            let writer = JsonlWriter::new(Some(&self.dir.join(&file)))?
+                .with_fields(self.fields.clone())
+                .buffered();
+            self.current = Some(writer);
+            self.shards.push(Shard {
+                file,
+                endpoints: 0,
+                bytes: 0,
+            });
+        }
+        if let (Some(writer), Some(shard)) = (self.current.as_mut(), self.shards.last_mut()) {
+            writer.write(endpoint)?;
+            shard.endpoints += 1;
+        }
+        Ok(())
+    }
+
+    fn close_current(&mut self) -> Result<()> {
+        if let Some(writer) = self.current.take() {
+            writer.finish()?;
+            if let Some(shard) = self.shards.last_mut() {
+                shard.bytes = std::fs::metadata(self.dir.join(&shard.file))?.len();
+            }
+        }
+        Ok(())
+    }
+
+    /// Close the last shard and write the manifest for `result`
+    pub fn finish(mut self, result: &ScanResult) -> Result<Manifest> {
+        self.close_current()?;
+        let manifest = Manifest {
+            schema_version: result.schema_version,
+            tool_version: result.tool_version.clone(),
+            target: result.target.clone(),
+            timestamp: result.timestamp.clone(),
+            total_endpoints: self.shards.iter().map(|s| s.endpoints).sum(),
+            shard_size: self.shard_size,
+            shards: self.shards.clone(),
+            findings: result.findings.clone(),
+            technologies: result.technologies.clone(),
+            stats: result.stats.clone(),
+        };
+        write_file(
+            &self.manifest_path(),
+            serde_json::to_string_pretty(&manifest)?.as_bytes(),
+        )?;
+        Ok(manifest)
+    }
+}
+
+/// Write a scan result's endpoints as shards, returning the manifest's path
+pub fn write_shards(
+    result: &ScanResult,
+    path: &Path,
+    shard_size: usize,
+    fields: &[Field],
+) -> Result<PathBuf> {
+    let mut writer = ShardWriter::new(path, shard_size)?.with_fields(fields.to_vec());
+    for endpoint in &result.endpoints {
+        writer.write(endpoint)?;
+    }
+    let manifest_path = writer.manifest_path();
+    writer.finish(result)?;
+    Ok(manifest_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EndpointType, SCHEMA_VERSION, TOOL_VERSION};
+    use flate2::read::GzDecoder;
+
+    #[test]
+    fn test_target_output_path() {
+        let dir = Path::new("out");
+        let mut taken = HashSet::new();
+        let path = |target: &str, taken: &mut HashSet<String>| {
+            target_output_path(dir, target, "json", taken)
+        };
+        assert_eq!(
+            path("https://api.example.com:8443/v1/", &mut taken),
+            dir.join("api.example.com_8443_v1.json")
+        );
+        assert_eq!(
+            path("https://example.com", &mut taken),
+            dir.join("example.com.json")
+        );
+        assert_eq!(
+            path("http://example.com/", &mut taken),
+            dir.join("example.com-2.json")
+        );
+        assert_eq!(
+            target_output_path(dir, "https://x.test/a?b=1", "", &mut taken),
+            dir.join("x.test_a")
+        );
+    }
+
+    #[test]
+    fn test_write_shards() {
+        let dir = tempfile::tempdir().unwrap();
+        let endpoints: Vec<Endpoint> = (0..5)
+            .map(|i| Endpoint::new(format!("/api/item/{}", i), EndpointType::Rest))
+            .collect();
+        let result = ScanResult {
+            schema_version: SCHEMA_VERSION,
+            tool_version: TOOL_VERSION.to_string(),
+            target: "https://example.com".to_string(),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            total_endpoints: endpoints.len(),
+            endpoints,
+            findings: Vec::new(),
+            wordlist: Vec::new(),
+            technologies: Vec::new(),
+            stats: None,
+        };
+
+        let manifest_path =
+            write_shards(&result, &dir.path().join("results.jsonl.gz"), 2, &[]).unwrap();
+        assert_eq!(manifest_path, dir.path().join("results.manifest.json"));
+        let manifest: Manifest =
+            serde_json::from_str(&std::fs::read_to_string(&manifest_path).unwrap()).unwrap();
+        let files: Vec<&str> = manifest.shards.iter().map(|s| s.file.as_str()).collect();
+        assert_eq!(
+            files,
+            [
+                "results-0001.jsonl.gz",
+                "results-0002.jsonl.gz",
+                "results-0003.jsonl.gz"
+            ]
+        );
+        assert_eq!(manifest.total_endpoints, 5);
+        assert_eq!(manifest.shards[2].endpoints, 1);
+        assert_eq!(
+            manifest.shards[0].bytes,
+            std::fs::metadata(dir.path().join("results-0001.jsonl.gz"))
+                .unwrap()
+                .len()
+        );
+
+        let mut text = String::new();
+        GzDecoder::new(File::open(dir.path().join("results-0002.jsonl.gz")).unwrap())
+            .read_to_string(&mut text)
+            .unwrap();
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.starts_with(r#"{"url":"/api/item/2""#));
+    }
+}
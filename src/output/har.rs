@@ -0,0 +1,238 @@
+//! HAR 1.2 output
+//!
+//! Each endpoint with an HTTP(S) URL becomes an entry with the request it
+//! implies: method, query string and, for known body keys, a JSON body with
+//! empty values. Probed endpoints carry the response the probe saw; others
+//! have status 0, which HAR viewers show as a request without a response.
+
+use crate::error::Result;
+use crate::output::absolute_url;
+use crate::types::Endpoint;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct Har {
+    log: Log,
+}
+
+#[derive(Serialize)]
+struct Log {
+    version: &'static str,
+    creator: Creator,
+    entries: Vec<Entry>,
+}
+
+#[derive(Serialize)]
+struct Creator {
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Entry {
+    started_date_time: String,
+    time: i64,
+    request: Request,
+    response: Response,
+    cache: Empty,
+    timings: Timings,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Request {
+    method: String,
+    url: String,
+    http_version: &'static str,
+    cookies: Vec<Empty>,
+    headers: Vec<Pair>,
+    query_string: Vec<Pair>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    post_data: Option<PostData>,
+    headers_size: i64,
+    body_size: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Response {
+    status: u16,
+    status_text: String,
+    http_version: &'static str,
+    cookies: Vec<Empty>,
+    headers: Vec<Pair>,
+    content: Content,
+    #[serde(rename = "redirectURL")]
+    redirect_url: String,
+    headers_size: i64,
+    body_size: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Content {
+    size: i64,
+    mime_type: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PostData {
+    mime_type: &'static str,
+    text: String,
+}
+
+#[derive(Serialize)]
+struct Pair {
+    name: String,
+    value: String,
+}
+
+#[derive(Serialize)]
+struct Empty {}
+
+#[derive(Serialize)]
+struct Timings {
+    send: i64,
+    wait: i64,
+    receive: i64,
+}
+
+/// Serialize endpoints as a HAR log whose entries start at `started`
+///
+/// Relative URLs are resolved against the endpoint's target, then `base`;
+/// endpoints without an HTTP(S) URL are left out.
+pub fn serialize_har(endpoints: &[Endpoint], base: Option<&str>, started: &str) -> Result<String> {
+    let entries = endpoints
+        .iter()
+        .filter_map(|endpoint| {
+            let url = absolute_url(endpoint, base)?;
+            Some(entry(endpoint, &url, started))
+        })
+        .collect();
+    let har = Har {
+        log: Log {
+            version: "1.2",
+            creator: Creator {
+                name: env!("CARGO_PKG_NAME"),
+                version: env!("CARGO_PKG_VERSION"),
+            },
+            entries,
+        },
+    };
+    Ok(serde_json::to_string_pretty(&har)?)
+}
+
+fn entry(endpoint: &Endpoint, url: &url::Url, started: &str) -> Entry {
+    let method = endpoint
+        .method
+        .as_deref()
+        .unwrap_or("GET")
+        .to_ascii_uppercase();
+    let host = match url.port() {
+        Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+        None => url.host_str().unwrap_or_default().to_string(),
+    };
+    let post_data = endpoint
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("body_params"))
+        .map(|keys| {
+            let body: serde_json::Map<String, serde_json::Value> = keys
+                .split(',')
+                .filter(|k| !k.is_empty())
+                .map(|k| (k.to_string(), serde_json::Value::String(String::new())))
+                .collect();
+            PostData {
+                mime_type: "application/json",
+                text: serde_json::Value::Object(body).to_string(),
+            }
+        });
+
+    let response = match &endpoint.probe {
+        Some(probe) => {
+            let mut headers = Vec::new();
+            if let Some(content_type) = &probe.content_type {
+                headers.push(pair("Content-Type", content_type));
+            }
+            if let Some(length) = probe.content_length {
+                headers.push(pair("Content-Length", &length.to_string()));
+            }
+            if let Some(location) = &probe.redirect {
+                headers.push(pair("Location", location));
+            }
+            if !probe.allowed_methods.is_empty() {
+                headers.push(pair("Allow", &probe.allowed_methods.join(", ")));
+            }
+            Response {
+                status: probe.status,
+                status_text: reqwest::StatusCode::from_u16(probe.status)
+                    .ok()
+                    .and_then(|s| s.canonical_reason())
+                    .unwrap_or_default()
+                    .to_string(),
+                http_version: "HTTP/1.1",
+                cookies: Vec::new(),
+                headers,
+                content: Content {
+                    size: probe.content_length.map_or(-1, |l| l as i64),
+                    mime_type: probe.content_type.clone().unwrap_or_default(),
+                },
+                redirect_url: probe.redirect.clone().unwrap_or_default(),
+                headers_size: -1,
+                body_size: -1,
+            }
+        }
+        None => Response {
+            status: 0,
+            status_text: String::new(),
+            http_version: "HTTP/1.1",
+            cookies: Vec::new(),
+            headers: Vec::new(),
+            content: Content {
+                size: 0,
+                mime_type: String::new(),
+            },
+            redirect_url: String::new(),
+            headers_size: -1,
+            body_size: -1,
+        },
+    };
+
+    Entry {
+        started_date_time: started.to_string(),
+        time: 0,
+        request: Request {
+            method,
+            url: url.to_string(),
+            http_version: "HTTP/1.1",
+            cookies: Vec::new(),
+            headers: vec![pair("Host", &host)],
+            query_string: url
+                .query_pairs()
+                .map(|(name, value)| pair(&name, &value))
+                .collect(),
+            post_data,
+            headers_size: -1,
+            body_size: -1,
+        },
+        response,
+        cache: Empty {},
+        timings: Timings {
+            send: 0,
+            wait: 0,
+            receive: 0,
+        },
+        comment: endpoint.source.as_ref().map(|s| format!("Found in {}", s)),
+    }
+}
+
+fn pair(name: &str, value: &str) -> Pair {
+    Pair {
+        name: name.to_string(),
+        value: value.to_string(),
+    }
+}
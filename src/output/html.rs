@@ -0,0 +1,485 @@
+//! HTML report
+//!
+//! A single self-contained page: charts of endpoint types, severities and
+//! hosts (inline SVG, no external scripts), an API version summary, search
+//! plus type and method filters, and a collapsible table per host. Each row
+//! has a button copying its URL, and rows with recorded code context expand
+//! to show it.
+
+use crate::output::{escape_xml, group_by_host};
+use crate::parser::versions::{endpoint_version, group_by_version, UNVERSIONED};
+use crate::types::{Endpoint, Severity};
+use std::collections::{BTreeMap, BTreeSet};
+use std::f64::consts::PI;
+
+const STYLE: &str = r#"    <style>
+        body { font-family: 'Segoe UI', Tahoma, Geneva, Verdana, sans-serif; margin: 20px; background-color: #f5f5f5; }
+        h1 { color: #333; }
+        .container { background: white; padding: 20px; border-radius: 8px; box-shadow: 0 2px 4px rgba(0,0,0,0.1); }
+        table { width: 100%; border-collapse: collapse; margin-top: 20px; }
+        th, td { padding: 12px; text-align: left; border-bottom: 1px solid #ddd; }
+        th { background-color: #f8f9fa; color: #333; cursor: pointer; }
+        tr.endpoint:hover { background-color: #f1f1f1; }
+        .badge { padding: 4px 8px; border-radius: 4px; font-size: 0.85em; font-weight: bold; }
+        .badge-rest { background: #e3f2fd; color: #1976d2; }
+        .badge-graphql { background: #f3e5f5; color: #7b1fa2; }
+        .badge-websocket { background: #e8f5e9; color: #388e3c; }
+        .badge-sse { background: #fff8e1; color: #f57f17; }
+        .badge-unknown { background: #eeeeee; color: #616161; }
+        .severity-critical, .severity-high { color: #c62828; font-weight: bold; }
+        .severity-medium { color: #ef6c00; }
+        .severity-low, .severity-info { color: #616161; }
+        .tag { display: inline-block; padding: 2px 6px; margin-right: 4px; border-radius: 4px; background: #fff3e0; color: #e65100; font-size: 0.85em; }
+        .filters { margin-bottom: 10px; }
+        .filters input, .filters select { padding: 10px; margin-right: 8px; border: 1px solid #ccc; border-radius: 4px; }
+        #search { width: 300px; }
+        .versions { margin-bottom: 16px; }
+        .version { display: inline-block; padding: 4px 10px; margin: 0 6px 6px 0; border-radius: 12px; background: #e8eaf6; color: #283593; font-size: 0.9em; }
+        .version-older { background: #ffebee; color: #c62828; }
+        .version-none { background: #eeeeee; color: #616161; }
+        .charts { display: flex; flex-wrap: wrap; gap: 24px; margin-bottom: 16px; }
+        .charts figure { margin: 0; }
+        .charts figcaption { font-weight: bold; color: #333; margin-bottom: 6px; }
+        .legend { list-style: none; padding: 0; margin: 6px 0 0; font-size: 0.9em; }
+        .legend span { display: inline-block; width: 10px; height: 10px; margin-right: 6px; border-radius: 2px; }
+        details.host { margin-top: 16px; }
+        details.host summary { font-size: 1.1em; font-weight: bold; cursor: pointer; padding: 8px 0; }
+        details.host .count { color: #757575; font-weight: normal; }
+        button.copy, button.expand { border: none; background: none; cursor: pointer; color: #757575; padding: 0 4px; }
+        button.copy:hover, button.expand:hover { color: #1976d2; }
+        tr.context td { background: #fafafa; padding: 8px 12px; }
+        tr.context pre { margin: 0; white-space: pre-wrap; word-break: break-all; font-size: 0.85em; color: #37474f; }
+    </style>
+"#;
+
+const SCRIPT: &str = r#"    <script>
+        function filterTable() {
+            var filter = document.getElementById("search").value.toUpperCase();
+            var type = document.getElementById("typeFilter").value;
+            var method = document.getElementById("methodFilter").value;
+            document.querySelectorAll("details.host").forEach(function (section) {
+                var shown = 0;
+                section.querySelectorAll("tr.endpoint").forEach(function (tr) {
+                    var found = tr.textContent.toUpperCase().indexOf(filter) > -1
+                        && (type === "" || tr.dataset.type === type)
+                        && (method === "" || tr.dataset.method === method);
+                    tr.style.display = found ? "" : "none";
+                    var context = contextRow(tr);
+                    if (context && !found) context.hidden = true;
+                    if (found) shown++;
+                });
+                section.style.display = shown > 0 ? "" : "none";
+            });
+        }
+
+        function contextRow(tr) {
+            var next = tr.nextElementSibling;
+            return next && next.classList.contains("context") ? next : null;
+        }
+
+        function toggleContext(button) {
+            var context = contextRow(button.closest("tr"));
+            context.hidden = !context.hidden;
+            button.textContent = context.hidden ? "▸" : "▾";
+        }
+
+        function copyUrl(button) {
+            navigator.clipboard.writeText(button.dataset.url).then(function () {
+                button.textContent = "✔";
+                setTimeout(function () { button.textContent = "📋"; }, 1000);
+            });
+        }
+
+        function sortTable(th, n) {
+            var tbody = th.closest("table").tBodies[0];
+            var dir = th.dataset.dir === "asc" ? "desc" : "asc";
+            th.dataset.dir = dir;
+            var rows = Array.prototype.slice.call(tbody.querySelectorAll("tr.endpoint"));
+            rows.sort(function (a, b) {
+                var x = a.cells[n].textContent.toLowerCase();
+                var y = b.cells[n].textContent.toLowerCase();
+                return (x < y ? -1 : x > y ? 1 : 0) * (dir === "asc" ? 1 : -1);
+            });
+            rows.forEach(function (tr) {
+                var context = contextRow(tr);
+                tbody.appendChild(tr);
+                if (context) tbody.appendChild(context);
+            });
+        }
+    </script>
+"#;
+
+/// Serialize endpoints as an HTML report
+pub fn serialize_html(endpoints: &[Endpoint]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n    <meta charset=\"utf-8\">\n    <title>Endpointo Report</title>\n",
+    );
+    html.push_str(STYLE);
+    html.push_str(
+        r#"</head>
+<body>
+    <div class="container">
+        <h1>🔍 Endpointo Scan Report</h1>
+"#,
+    );
+
+    // Rows are ordered by API version; every version below the newest is flagged
+    let versions = group_by_version(endpoints);
+    let hosts = group_by_host(versions.iter().flat_map(|(_, group)| group.iter().copied()));
+
+    html.push_str(&charts(endpoints, &hosts));
+
+    let newest = versions
+        .iter()
+        .rev()
+        .map(|(version, _)| version.as_str())
+        .find(|version| *version != UNVERSIONED);
+    if newest.is_some() {
+        html.push_str("        <div class=\"versions\"><strong>API versions:</strong> ");
+        for (version, group) in &versions {
+            let class = if version == UNVERSIONED {
+                "version version-none"
+            } else if Some(version.as_str()) != newest {
+                "version version-older"
+            } else {
+                "version"
+            };
+            html.push_str(&format!(
+                r#"<span class="{}">{} ({})</span>"#,
+                class,
+                escape_xml(version),
+                group.len()
+            ));
+        }
+        html.push_str("</div>\n");
+    }
+
+    html.push_str(&filters(endpoints));
+
+    // A collapsible section per origin
+    for (host, hosted) in &hosts {
+        html.push_str(&format!(
+            r#"        <details class="host" open>
+            <summary>{} <span class="count">({})</span></summary>
+            <table class="results">
+                <thead>
+                    <tr>
+                        <th onclick="sortTable(this, 0)">URL</th>
+                        <th onclick="sortTable(this, 1)">Type</th>
+                        <th onclick="sortTable(this, 2)">Method</th>
+                        <th onclick="sortTable(this, 3)">Version</th>
+                        <th onclick="sortTable(this, 4)">Severity</th>
+                        <th onclick="sortTable(this, 5)">Tags</th>
+                        <th onclick="sortTable(this, 6)">Source</th>
+                    </tr>
+                </thead>
+                <tbody>
+"#,
+            escape_xml(host),
+            hosted.len()
+        ));
+        for ep in hosted {
+            html.push_str(&row(ep));
+        }
+        html.push_str(
+            r#"                </tbody>
+            </table>
+        </details>
+"#,
+        );
+    }
+
+    html.push_str("    </div>\n\n");
+    html.push_str(SCRIPT);
+    html.push_str("</body>\n</html>");
+    html
+}
+
+/// Method shown for an endpoint, `GET` when none was found
+fn method(ep: &Endpoint) -> String {
+    ep.method.as_deref().unwrap_or("GET").to_ascii_uppercase()
+}
+
+/// Search box and type and method dropdowns, listing the values present
+fn filters(endpoints: &[Endpoint]) -> String {
+    let types: BTreeSet<&str> = endpoints.iter().map(|e| e.endpoint_type.as_str()).collect();
+    let methods: BTreeSet<String> = endpoints.iter().map(method).collect();
+
+    let mut html = String::from(
+        r#"        <div class="filters">
+            <input type="text" id="search" onkeyup="filterTable()" placeholder="Search URLs, methods, sources...">
+            <select id="typeFilter" onchange="filterTable()"><option value="">All types</option>"#,
+    );
+    for kind in types {
+        html.push_str(&format!(r#"<option value="{0}">{0}</option>"#, kind));
+    }
+    html.push_str(
+        r#"</select>
+            <select id="methodFilter" onchange="filterTable()"><option value="">All methods</option>"#,
+    );
+    for method in methods {
+        html.push_str(&format!(
+            r#"<option value="{0}">{0}</option>"#,
+            escape_xml(&method)
+        ));
+    }
+    html.push_str("</select>\n        </div>\n");
+    html
+}
+
+/// One endpoint row, followed by a hidden row with its code context if any
+fn row(ep: &Endpoint) -> String {
+    let badge_class = format!("badge-{}", ep.endpoint_type.as_str());
+    let severity = match ep.severity {
+        Some(severity) => format!(
+            r#"<span class="severity-{0}">{0}</span>"#,
+            severity.as_str()
+        ),
+        None => "-".to_string(),
+    };
+    let tags: String = ep
+        .tags
+        .iter()
+        .flatten()
+        .map(|tag| format!(r#"<span class="tag">{}</span>"#, escape_xml(tag)))
+        .collect();
+    let context = ep.metadata.as_ref().and_then(|m| m.get("context"));
+    let expand = if context.is_some() {
+        r#"<button class="expand" title="Show code" onclick="toggleContext(this)">▸</button>"#
+    } else {
+        ""
+    };
+    let url = escape_xml(&ep.url);
+
+    let mut html = format!(
+        r#"                    <tr class="endpoint" data-type="{}" data-method="{}">
+                        <td>{}{} <button class="copy" title="Copy URL" data-url="{}" onclick="copyUrl(this)">📋</button></td>
+                        <td><span class="badge {}">{:?}</span></td>
+                        <td>{}</td>
+                        <td>{}</td>
+                        <td>{}</td>
+                        <td>{}</td>
+                        <td>{}:{}</td>
+                    </tr>
+"#,
+        ep.endpoint_type.as_str(),
+        escape_xml(&method(ep)),
+        expand,
+        url,
+        url,
+        badge_class,
+        ep.endpoint_type,
+        escape_xml(ep.method.as_deref().unwrap_or("-")),
+        escape_xml(endpoint_version(ep).unwrap_or("-")),
+        severity,
+        tags,
+        escape_xml(ep.source.as_deref().unwrap_or("-")),
+        ep.line
+            .map(|l: usize| l.to_string())
+            .unwrap_or_else(|| "-".to_string())
+    );
+    if let Some(context) = context {
+        html.push_str(&format!(
+            r#"                    <tr class="context" hidden><td colspan="7"><pre>{}</pre></td></tr>
+"#,
+            escape_xml(context)
+        ));
+    }
+    html
+}
+
+/// Pie charts of endpoint types and severities and a bar chart of hosts
+fn charts(endpoints: &[Endpoint], hosts: &[(String, Vec<&Endpoint>)]) -> String {
+    if endpoints.is_empty() {
+        return String::new();
+    }
+    let mut html = String::from("        <div class=\"charts\">\n");
+
+    let mut types: BTreeMap<&str, usize> = BTreeMap::new();
+    for ep in endpoints {
+        *types.entry(ep.endpoint_type.as_str()).or_default() += 1;
+    }
+    let slices: Vec<(&str, usize, &str)> = types
+        .into_iter()
+        .map(|(kind, count)| (kind, count, type_color(kind)))
+        .collect();
+    html.push_str(&pie("Endpoint types", &slices));
+
+    let mut severities: BTreeMap<std::cmp::Reverse<Severity>, usize> = BTreeMap::new();
+    for severity in endpoints.iter().filter_map(|ep| ep.severity) {
+        *severities.entry(std::cmp::Reverse(severity)).or_default() += 1;
+    }
+    if !severities.is_empty() {
+        let slices: Vec<(&str, usize, &str)> = severities
+            .into_iter()
+            .map(|(severity, count)| (severity.0.as_str(), count, severity_color(severity.0)))
+            .collect();
+        html.push_str(&pie("Severity", &slices));
+    }
+
+    html.push_str(&bars("Endpoints per host", hosts));
+    html.push_str("        </div>\n");
+    html
+}
+
+fn type_color(kind: &str) -> &'static str {
+    match kind {
+        "rest" => "#1976d2",
+        "graphql" => "#7b1fa2",
+        "websocket" => "#388e3c",
+        "sse" => "#f57f17",
+        _ => "#9e9e9e",
+    }
+}
+
+fn severity_color(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "#b71c1c",
+        Severity::High => "#e53935",
+        Severity::Medium => "#ef6c00",
+        Severity::Low => "#fbc02d",
+        Severity::Info => "#9e9e9e",
+    }
+}
+
+/// Inline SVG pie chart with a legend
+fn pie(title: &str, slices: &[(&str, usize, &str)]) -> String {
+    const R: f64 = 60.0;
+    let total: usize = slices.iter().map(|(_, count, _)| count).sum();
+    let mut svg = format!(
+        r#"            <figure>
+                <figcaption>{}</figcaption>
+                <svg width="{size}" height="{size}" viewBox="0 0 {size} {size}" role="img">"#,
+        escape_xml(title),
+        size = 2.0 * R
+    );
+
+    let mut angle = -PI / 2.0;
+    for (label, count, color) in slices {
+        let fraction = *count as f64 / total as f64;
+        if fraction >= 1.0 {
+            svg.push_str(&format!(
+                r#"<circle cx="{R}" cy="{R}" r="{R}" fill="{}"><title>{} ({})</title></circle>"#,
+                color,
+                escape_xml(label),
+                count
+            ));
+            break;
+        }
+        let end = angle + fraction * 2.0 * PI;
+        let (x1, y1) = (R + R * angle.cos(), R + R * angle.sin());
+        let (x2, y2) = (R + R * end.cos(), R + R * end.sin());
+        svg.push_str(&format!(
+            r#"<path d="M{R},{R} L{:.2},{:.2} A{R},{R} 0 {},1 {:.2},{:.2} Z" fill="{}"><title>{} ({})</title></path>"#,
+            x1,
+            y1,
+            u8::from(fraction > 0.5),
+            x2,
+            y2,
+            color,
+            escape_xml(label),
+            count
+        ));
+        angle = end;
+    }
+    svg.push_str("</svg>\n                <ul class=\"legend\">");
+    for (label, count, color) in slices {
+        svg.push_str(&format!(
+            r#"<li><span style="background: {}"></span>{} ({})</li>"#,
+            color,
+            escape_xml(label),
+            count
+        ));
+    }
+    svg.push_str("</ul>\n            </figure>\n");
+    svg
+}
+
+/// Inline SVG horizontal bar chart of endpoints per host
+fn bars(title: &str, hosts: &[(String, Vec<&Endpoint>)]) -> String {
+    const LABEL_WIDTH: usize = 200;
+    const BAR_WIDTH: usize = 220;
+    const ROW_HEIGHT: usize = 22;
+    let max = hosts
+        .iter()
+        .map(|(_, group)| group.len())
+        .max()
+        .unwrap_or(1);
+    let height = hosts.len() * ROW_HEIGHT;
+
+    let mut svg = format!(
+        r#"            <figure>
+                <figcaption>{}</figcaption>
+                <svg width="{}" height="{}" role="img" font-size="12">"#,
+        escape_xml(title),
+        LABEL_WIDTH + BAR_WIDTH + 40,
+        height
+    );
+    for (i, (host, group)) in hosts.iter().enumerate() {
+        let y = i * ROW_HEIGHT;
+        let width = (group.len() * BAR_WIDTH).div_ceil(max).max(1);
+        let label: String = match host.strip_prefix("https://") {
+            Some(rest) => rest.to_string(),
+            None => host.clone(),
+        };
+        let label = if label.chars().count() > 30 {
+            format!("{}…", label.chars().take(29).collect::<String>())
+        } else {
+            label
+        };
+        svg.push_str(&format!(
+            r##"<text x="{}" y="{}" text-anchor="end"><title>{}</title>{}</text><rect x="{}" y="{}" width="{}" height="{}" fill="#1976d2"></rect><text x="{}" y="{}">{}</text>"##,
+            LABEL_WIDTH - 6,
+            y + 15,
+            escape_xml(host),
+            escape_xml(&label),
+            LABEL_WIDTH,
+            y + 4,
+            width,
+            ROW_HEIGHT - 8,
+            LABEL_WIDTH + width + 4,
+            y + 15,
+            group.len()
+        ));
+    }
+    svg.push_str("</svg>\n            </figure>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EndpointType;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_html_report() {
+        let endpoints = vec![
+            Endpoint::new(
+                "https://api.example.com/users".to_string(),
+                EndpointType::Rest,
+            )
+            .with_method("POST")
+            .with_severity(Severity::High)
+            .with_metadata(HashMap::from([(
+                "context".to_string(),
+                r#"fetch("https://api.example.com/users", {method: "POST"})"#.to_string(),
+            )])),
+            Endpoint::new("/graphql".to_string(), EndpointType::GraphQL),
+        ];
+        let html = serialize_html(&endpoints);
+
+        assert!(html.contains("<figcaption>Endpoint types</figcaption>"));
+        assert!(html.contains("<figcaption>Severity</figcaption>"));
+        assert!(html.contains(r#"<li><span style="background: #e53935"></span>high (1)</li>"#));
+        assert!(html.contains(r#"<option value="graphql">graphql</option>"#));
+        assert!(html.contains(r#"<option value="POST">POST</option>"#));
+        assert!(html.contains(r#"data-url="https://api.example.com/users""#));
+        assert!(html.contains(r#"<tr class="endpoint" data-type="rest" data-method="POST">"#));
+        assert!(html.contains(
+            "<pre>fetch(&quot;https://api.example.com/users&quot;, {method: &quot;POST&quot;})</pre>"
+        ));
+        // Only the row with context can expand
+        assert_eq!(html.matches(r#"<tr class="context" hidden>"#).count(), 1);
+    }
+}
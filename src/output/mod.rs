@@ -0,0 +1,799 @@
+pub mod burp;
+pub mod commands;
+pub mod diff;
+pub mod elastic;
+pub mod fields;
+pub mod file;
+pub mod har;
+pub mod html;
+pub mod nuclei;
+pub mod openapi;
+pub mod report;
+pub mod sarif;
+pub mod template;
+pub mod webhook;
+
+pub use crate::cli::OutputFormat;
+use crate::diff::EndpointDiff;
+use crate::error::{Error, Result};
+use crate::parser::params::{build_wordlist, WordKind, Wordlists};
+use crate::report::Report;
+use crate::store::ProjectHistory;
+use crate::types::{Endpoint, Finding, ScanResult, Severity};
+use burp::serialize_burp;
+use chrono::Utc;
+use colored::*;
+use commands::{serialize_commands, CommandStyle};
+use diff::{render_diff, DiffFormat};
+use fields::{project, serialize_csv, serialize_fields_text, Field, DEFAULT_CSV_FIELDS};
+use file::{write_file, OutputFile};
+use har::serialize_har;
+use html::serialize_html;
+use nuclei::{nuclei_groups, write_nuclei};
+use openapi::{build_openapi, serialize_openapi};
+use report::{render_report, ReportFormat};
+use sarif::serialize_sarif;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+use std::path::Path;
+use template::render_template;
+use url::Url;
+
+/// Title of OpenAPI documents built without a scan target
+const DEFAULT_API_TITLE: &str = "Discovered API";
+
+/// Write scan results to output
+pub fn write_results(
+    endpoints: &[Endpoint],
+    output_path: Option<&Path>,
+    format: OutputFormat,
+) -> Result<()> {
+    if let OutputFormat::Plugin = format {
+        // Only scans and parses load plugins, and they write this themselves
+        return Err(Error::ValidationError(
+            "plugin output is only available for scan and parse".to_string(),
+        ));
+    }
+    if let (Some(dir), OutputFormat::Nuclei) = (output_path, &format) {
+        write_nuclei(endpoints, None, dir)?;
+    } else if let Some(path) = output_path {
+        let output = match format {
+            OutputFormat::Json => serialize_json(endpoints)?,
+            OutputFormat::Jsonl => serialize_jsonl(endpoints)?,
+            OutputFormat::Yaml => serialize_yaml(endpoints)?,
+            OutputFormat::Csv => serialize_csv(endpoints, DEFAULT_CSV_FIELDS),
+            OutputFormat::Xml => serialize_xml(endpoints)?,
+            OutputFormat::Html => serialize_html(endpoints),
+            OutputFormat::Wordlist => serialize_wordlist(endpoints),
+            OutputFormat::Plain => serialize_plain(endpoints),
+            OutputFormat::Sarif => serialize_sarif(endpoints, &[])?,
+            OutputFormat::Openapi => {
+                serialize_openapi(&build_openapi(DEFAULT_API_TITLE, endpoints), output_path)?
+            }
+            OutputFormat::Burp => serialize_burp(endpoints, None),
+            OutputFormat::Har => serialize_har(endpoints, None, &Utc::now().to_rfc3339())?,
+            OutputFormat::Curl => serialize_commands(endpoints, None, CommandStyle::Curl, &[]),
+            OutputFormat::Httpie => serialize_commands(endpoints, None, CommandStyle::Httpie, &[]),
+            OutputFormat::Nuclei => unreachable!("nuclei output is a directory"),
+            OutputFormat::Plugin => unreachable!("plugins write their own output"),
+        };
+        write_file(path, output.as_bytes())?;
+    } else if let OutputFormat::Wordlist = format {
+        // Plain words so the list can be piped straight into a fuzzer
+        print!("{}", serialize_wordlist(endpoints));
+    } else if let OutputFormat::Plain = format {
+        print!("{}", serialize_plain(endpoints));
+    } else if let OutputFormat::Jsonl = format {
+        print!("{}", serialize_jsonl(endpoints)?);
+    } else if let OutputFormat::Csv = format {
+        print!("{}", serialize_csv(endpoints, DEFAULT_CSV_FIELDS));
+    } else if let OutputFormat::Sarif = format {
+        println!("{}", serialize_sarif(endpoints, &[])?);
+    } else if let OutputFormat::Openapi = format {
+        print!(
+            "{}",
+            serialize_openapi(&build_openapi(DEFAULT_API_TITLE, endpoints), None)?
+        );
+    } else if let OutputFormat::Burp = format {
+        print!("{}", serialize_burp(endpoints, None));
+    } else if let OutputFormat::Nuclei = format {
+        print!("{}", serialize_nuclei_targets(endpoints, None));
+    } else if let Some(style) = command_style(&format) {
+        print!("{}", serialize_commands(endpoints, None, style, &[]));
+    } else if let OutputFormat::Har = format {
+        println!(
+            "{}",
+            serialize_har(endpoints, None, &Utc::now().to_rfc3339())?
+        );
+    } else {
+        display_to_terminal(endpoints);
+    }
+
+    Ok(())
+}
+
+/// Write a full scan result to output
+///
+/// JSON, YAML and XML files get the whole result, provenance and stats
+/// included (JSON and YAML with findings too). SARIF carries findings
+/// alongside endpoints, OpenAPI documents are titled after the target, and
+/// Burp site maps, HAR logs, shell commands and nuclei targets resolve
+/// relative URLs against it; other formats and terminal output only carry
+/// the endpoints.
+pub fn write_scan_result(
+    result: &ScanResult,
+    output_path: Option<&Path>,
+    format: OutputFormat,
+) -> Result<()> {
+    let Some(path) = output_path else {
+        if let OutputFormat::Sarif = format {
+            println!("{}", serialize_sarif(&result.endpoints, &result.findings)?);
+            return Ok(());
+        }
+        if let OutputFormat::Openapi = format {
+            let spec = build_openapi(&result.target, &result.endpoints);
+            print!("{}", serialize_openapi(&spec, None)?);
+            return Ok(());
+        }
+        if let OutputFormat::Burp = format {
+            print!(
+                "{}",
+                serialize_burp(&result.endpoints, Some(&result.target))
+            );
+            return Ok(());
+        }
+        if let Some(style) = command_style(&format) {
+            print!(
+                "{}",
+                serialize_commands(&result.endpoints, Some(&result.target), style, &[])
+            );
+            return Ok(());
+        }
+        return write_results(&result.endpoints, output_path, format);
+    };
+    if let OutputFormat::Nuclei = format {
+        write_nuclei(&result.endpoints, Some(&result.target), path)?;
+        return Ok(());
+    }
+    let output = match format {
+        OutputFormat::Json => serialize_json(result)?,
+        OutputFormat::Yaml => serialize_yaml(result)?,
+        OutputFormat::Xml => serialize_xml_result(result)?,
+        OutputFormat::Sarif => serialize_sarif(&result.endpoints, &result.findings)?,
+        OutputFormat::Openapi => serialize_openapi(
+            &build_openapi(&result.target, &result.endpoints),
+            output_path,
+        )?,
+        OutputFormat::Burp => serialize_burp(&result.endpoints, Some(&result.target)),
+        OutputFormat::Har => {
+            serialize_har(&result.endpoints, Some(&result.target), &result.timestamp)?
+        }
+        OutputFormat::Curl | OutputFormat::Httpie => serialize_commands(
+            &result.endpoints,
+            Some(&result.target),
+            command_style(&format).unwrap_or(CommandStyle::Curl),
+            &[],
+        ),
+        _ => return write_results(&result.endpoints, output_path, format),
+    };
+    write_file(path, output.as_bytes())?;
+    Ok(())
+}
+
+/// Check that field selection applies to an output format
+pub fn check_fields(format: &OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl | OutputFormat::Yaml | OutputFormat::Csv => Ok(()),
+        _ => Err(Error::ValidationError(
+            "--fields applies to json, jsonl, yaml, csv and terminal output".to_string(),
+        )),
+    }
+}
+
+/// Write a scan result without its envelope: JSON, YAML and XML files get
+/// a bare list of endpoints, other formats are written as usual
+pub fn write_bare_result(
+    result: &ScanResult,
+    output_path: Option<&Path>,
+    format: OutputFormat,
+) -> Result<()> {
+    match format {
+        OutputFormat::Json | OutputFormat::Yaml | OutputFormat::Xml => {
+            write_results(&result.endpoints, output_path, format)
+        }
+        _ => write_scan_result(result, output_path, format),
+    }
+}
+
+/// Write only the selected fields of each endpoint
+///
+/// JSON, JSON Lines, YAML and CSV are written as usual with each endpoint
+/// reduced to the fields; terminal output becomes tab-separated lines, so a
+/// single field gives a plain list.
+pub fn write_fields(
+    endpoints: &[Endpoint],
+    fields: &[Field],
+    output_path: Option<&Path>,
+    format: OutputFormat,
+) -> Result<()> {
+    let projected = project(endpoints, fields);
+    let output = match (&format, output_path) {
+        (OutputFormat::Json, None) => serialize_fields_text(endpoints, fields),
+        (OutputFormat::Json, Some(_)) => serde_json::to_string_pretty(&projected)?,
+        (OutputFormat::Jsonl, _) => serialize_jsonl(&projected)?,
+        (OutputFormat::Yaml, _) => serde_yaml::to_string(&projected)?,
+        (OutputFormat::Csv, _) => serialize_csv(endpoints, fields),
+        _ => return check_fields(&format),
+    };
+    match output_path {
+        Some(path) => {
+            write_file(path, output.as_bytes())?;
+        }
+        None => print!("{}", output),
+    }
+    Ok(())
+}
+
+/// Render a scan result with a user-supplied Tera template, replacing the
+/// output format
+pub fn write_template(
+    result: &ScanResult,
+    template: &Path,
+    output_path: Option<&Path>,
+) -> Result<()> {
+    let output = render_template(result, template)?;
+    match output_path {
+        Some(path) => {
+            write_file(path, output.as_bytes())?;
+        }
+        None => print!("{}", output),
+    }
+    Ok(())
+}
+
+/// Absolute HTTP(S) URL of an endpoint
+///
+/// Relative URLs are resolved against the endpoint's `target` metadata,
+/// then `base`; `None` if that fails or the URL isn't HTTP(S).
+pub(crate) fn absolute_url(endpoint: &Endpoint, base: Option<&str>) -> Option<Url> {
+    let base = endpoint
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("target"))
+        .map(String::as_str)
+        .or(base)
+        .and_then(|b| Url::parse(b).ok());
+    let url = match Url::parse(&endpoint.url) {
+        Ok(url) => url,
+        Err(url::ParseError::RelativeUrlWithoutBase) => base?.join(&endpoint.url).ok()?,
+        Err(_) => return None,
+    };
+    matches!(url.scheme(), "http" | "https").then_some(url)
+}
+
+/// Group of endpoints whose URL and target are both relative
+pub const RELATIVE_HOST: &str = "(relative)";
+
+/// Origin an endpoint is served from: its own for absolute URLs, its
+/// target's for relative ones
+fn endpoint_origin(endpoint: &Endpoint) -> Option<String> {
+    let target = endpoint.metadata.as_ref().and_then(|m| m.get("target"));
+    let url = Url::parse(&endpoint.url)
+        .ok()
+        .or_else(|| Url::parse(target?).ok())?;
+    let origin = url.origin();
+    origin.is_tuple().then(|| origin.ascii_serialization())
+}
+
+/// Group endpoints by origin, sorted by origin with relative endpoints last;
+/// endpoints keep their order within a group
+pub fn group_by_host<'a>(
+    endpoints: impl IntoIterator<Item = &'a Endpoint>,
+) -> Vec<(String, Vec<&'a Endpoint>)> {
+    let mut groups: BTreeMap<String, Vec<&Endpoint>> = BTreeMap::new();
+    let mut relative = Vec::new();
+    for endpoint in endpoints {
+        match endpoint_origin(endpoint) {
+            Some(origin) => groups.entry(origin).or_default().push(endpoint),
+            None => relative.push(endpoint),
+        }
+    }
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    if !relative.is_empty() {
+        groups.push((RELATIVE_HOST.to_string(), relative));
+    }
+    groups
+}
+
+/// Display endpoints to terminal with colors and formatting
+fn display_to_terminal(endpoints: &[Endpoint]) {
+    println!(
+        "\n{}",
+        "🔍 Discovered Endpoints".bold().bright_white().on_blue()
+    );
+    println!("{}", "─".repeat(80).dimmed());
+
+    // Multi-host scans get a heading per origin
+    let groups = group_by_host(endpoints);
+    for (host, group) in &groups {
+        if groups.len() > 1 {
+            println!(
+                "\n{} {}",
+                format!("🌐 {}", host).bold().bright_cyan(),
+                format!("({})", group.len()).dimmed()
+            );
+        }
+        for ep in group {
+            print_endpoint(ep);
+        }
+    }
+
+    println!("{}", "─".repeat(80).dimmed());
+    println!(
+        "{} {}",
+        "Total endpoints:".bold(),
+        endpoints.len().to_string().bright_green()
+    );
+}
+
+/// One line of terminal output
+fn print_endpoint(ep: &Endpoint) {
+    let method = ep.method.as_deref().unwrap_or("GET").to_uppercase();
+    let method_colored = match method.as_str() {
+        "GET" => method.green(),
+        "POST" => method.blue(),
+        "PUT" => method.yellow(),
+        "DELETE" => method.red(),
+        _ => method.normal(),
+    };
+
+    let type_badge = match ep.endpoint_type {
+        crate::types::EndpointType::Rest => " REST ".black().on_bright_blue(),
+        crate::types::EndpointType::GraphQL => " GQL  ".black().on_bright_magenta(),
+        crate::types::EndpointType::WebSocket => "  WS  ".black().on_bright_green(),
+        crate::types::EndpointType::Sse => " SSE  ".black().on_bright_yellow(),
+        _ => " UNK  ".black().on_white(),
+    };
+
+    let status = match &ep.probe {
+        Some(probe) => {
+            let code = probe.status.to_string();
+            let code = match probe.status {
+                200..=299 => code.green(),
+                300..=399 => code.cyan(),
+                400..=499 => code.yellow(),
+                _ => code.red(),
+            };
+            if probe.soft_404 {
+                format!("[{} {}] ", code, "soft 404".dimmed())
+            } else {
+                format!("[{}] ", code)
+            }
+        }
+        None => String::new(),
+    };
+
+    let severity = match ep.severity {
+        Some(severity) => {
+            let label = format!("[{}] ", severity.as_str().to_uppercase());
+            let label = match severity {
+                Severity::Critical | Severity::High => label.red().bold(),
+                Severity::Medium => label.yellow(),
+                Severity::Low | Severity::Info => label.dimmed(),
+            };
+            format!("{}", label)
+        }
+        None => String::new(),
+    };
+    let tags = match &ep.tags {
+        Some(tags) if !tags.is_empty() => {
+            format!(" {}", format!("[{}]", tags.join(", ")).yellow())
+        }
+        _ => String::new(),
+    };
+
+    println!(
+        "{} {:<7} {}{}{}{} {}",
+        type_badge,
+        method_colored.bold(),
+        severity,
+        status,
+        ep.url.bright_white(),
+        tags,
+        format!("({})", ep.source.as_deref().unwrap_or("-")).dimmed()
+    );
+}
+
+/// Write findings to a JSON file, or display them when no path is given
+pub fn write_findings(findings: &[Finding], output_path: Option<&Path>) -> Result<()> {
+    if let Some(path) = output_path {
+        let output = serde_json::to_string_pretty(findings).map_err(Error::from)?;
+        write_file(path, output.as_bytes())?;
+    } else {
+        display_findings(findings);
+    }
+
+    Ok(())
+}
+
+/// Write the differences between two scans to a file, or print them
+pub fn write_diff(
+    diff: &EndpointDiff,
+    output_path: Option<&Path>,
+    format: DiffFormat,
+) -> Result<()> {
+    let output = render_diff(diff, format)?;
+    if let Some(path) = output_path {
+        write_file(path, output.as_bytes())?;
+    } else {
+        print!("{}", output);
+    }
+
+    Ok(())
+}
+
+/// Write a multi-scan report to a file, or print it
+pub fn write_report(
+    report: &Report,
+    output_path: Option<&Path>,
+    format: ReportFormat,
+) -> Result<()> {
+    let output = render_report(report, format)?;
+    if let Some(path) = output_path {
+        write_file(path, output.as_bytes())?;
+    } else {
+        print!("{}", output);
+    }
+
+    Ok(())
+}
+
+/// Write fuzzing wordlists, one word per line
+///
+/// With a directory, each kind goes to its own `<kind>.txt` (`paths.txt`,
+/// `params.txt`, `files.txt`); otherwise the kinds are merged into one list
+/// written to `output_path`, or printed. Returns the number of words written.
+pub fn write_wordlists(
+    wordlists: &Wordlists,
+    kinds: &[WordKind],
+    output_path: Option<&Path>,
+    dir: Option<&Path>,
+) -> Result<usize> {
+    if let Some(dir) = dir {
+        std::fs::create_dir_all(dir)?;
+        let mut written = 0;
+        for &kind in kinds {
+            let words = wordlists.get(kind);
+            write_file(
+                &dir.join(format!("{}.txt", kind.as_str())),
+                word_lines(words).as_bytes(),
+            )?;
+            written += words.len();
+        }
+        return Ok(written);
+    }
+    let words = wordlists.merged(kinds);
+    let output = word_lines(&words);
+    match output_path {
+        Some(path) => write_file(path, output.as_bytes())?,
+        None => print!("{}", output),
+    }
+    Ok(words.len())
+}
+
+/// Words one per line
+fn word_lines<'a>(words: impl IntoIterator<Item = &'a String>) -> String {
+    words
+        .into_iter()
+        .map(|word| format!("{}\n", word))
+        .collect()
+}
+
+/// Write a project's recorded scans and endpoints
+///
+/// Saved as JSON when a path is given, otherwise printed to the terminal.
+pub fn write_history(history: &ProjectHistory, output_path: Option<&Path>) -> Result<()> {
+    if let Some(path) = output_path {
+        let output = serialize_json(history)?;
+        write_file(path, output.as_bytes())?;
+    } else {
+        display_history(history);
+    }
+
+    Ok(())
+}
+
+/// Display a project's history to terminal with colors and formatting
+fn display_history(history: &ProjectHistory) {
+    println!("\n{}", "🗄️  Recorded Scans".bold().bright_white().on_blue());
+    println!("{}", "─".repeat(80).dimmed());
+    for scan in &history.scans {
+        println!(
+            "{} {} {} endpoints, {} new",
+            scan.timestamp.dimmed(),
+            scan.target.bright_white(),
+            scan.total_endpoints.to_string().bold(),
+            scan.new_endpoints.to_string().bright_green()
+        );
+    }
+
+    println!(
+        "\n{}",
+        "🔍 Stored Endpoints".bold().bright_white().on_blue()
+    );
+    println!("{}", "─".repeat(80).dimmed());
+    for stored in &history.endpoints {
+        let ep = &stored.endpoint;
+        println!(
+            "{:<7} {} {}",
+            ep.method.as_deref().unwrap_or("GET").to_uppercase().bold(),
+            ep.url.bright_white(),
+            format!(
+                "(first seen {}, last seen {}, {} scans)",
+                stored.first_seen, stored.last_seen, stored.times_seen
+            )
+            .dimmed()
+        );
+    }
+
+    println!("{}", "─".repeat(80).dimmed());
+    println!(
+        "{} {}  {} {}",
+        "Scans:".bold(),
+        history.scans.len().to_string().bright_green(),
+        "Endpoints:".bold(),
+        history.endpoints.len().to_string().bright_green()
+    );
+}
+
+/// Display findings to terminal with colors and formatting
+fn display_findings(findings: &[Finding]) {
+    println!(
+        "\n{}",
+        "🔐 Internal Infrastructure Findings"
+            .bold()
+            .bright_white()
+            .on_red()
+    );
+    println!("{}", "─".repeat(80).dimmed());
+
+    for finding in findings {
+        let badge = match finding.kind {
+            crate::types::FindingKind::EnvVar => " ENV  ".black().on_bright_cyan(),
+            crate::types::FindingKind::InternalHost => " HOST ".black().on_bright_red(),
+            crate::types::FindingKind::PrivateIp => "  IP  ".black().on_bright_red(),
+            crate::types::FindingKind::Secret => "SECRET".black().on_bright_yellow(),
+        };
+
+        println!(
+            "{} {} {} {}",
+            badge,
+            finding.value.bright_white(),
+            format!("[{}]", finding.tags.join(", ")).yellow(),
+            format!("({})", finding.source.as_deref().unwrap_or("-")).dimmed()
+        );
+    }
+
+    println!("{}", "─".repeat(80).dimmed());
+    println!(
+        "{} {}",
+        "Total findings:".bold(),
+        findings.len().to_string().bright_red()
+    );
+}
+
+/// Serialize to JSON
+fn serialize_json<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    serde_json::to_string_pretty(value).map_err(Error::from)
+}
+
+/// Serialize to JSON Lines
+fn serialize_jsonl<T: Serialize>(items: &[T]) -> Result<String> {
+    let mut output = String::new();
+    for item in items {
+        output.push_str(&serde_json::to_string(item)?);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Writes endpoints as JSON Lines while a scan is still running
+///
+/// Each line is flushed as it is written so downstream tools see results
+/// immediately. Files ending in `.gz` are gzip-compressed.
+pub struct JsonlWriter {
+    out: OutputFile,
+    fields: Vec<Field>,
+    flush: bool,
+}
+
+impl JsonlWriter {
+    /// Write to a file, or stdout when no path is given
+    pub fn new(output_path: Option<&Path>) -> Result<Self> {
+        let out = match output_path {
+            Some(path) => OutputFile::create(path)?,
+            None => OutputFile::Stdout(std::io::stdout()),
+        };
+        Ok(Self {
+            out,
+            fields: Vec::new(),
+            flush: true,
+        })
+    }
+
+    /// Append to a file, creating it if needed
+    pub fn append(output_path: &Path) -> Result<Self> {
+        Ok(Self {
+            out: OutputFile::append(output_path)?,
+            fields: Vec::new(),
+            flush: true,
+        })
+    }
+
+    /// Don't flush after every line, for files nobody reads until they're done
+    pub fn buffered(mut self) -> Self {
+        self.flush = false;
+        self
+    }
+
+    /// Write only these fields of each endpoint (all when empty)
+    pub fn with_fields(mut self, fields: Vec<Field>) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    /// Write one endpoint as a line
+    pub fn write(&mut self, endpoint: &Endpoint) -> Result<()> {
+        if self.fields.is_empty() {
+            serde_json::to_writer(&mut self.out, endpoint)?;
+        } else {
+            let projected = project(std::slice::from_ref(endpoint), &self.fields);
+            serde_json::to_writer(&mut self.out, &projected[0])?;
+        }
+        self.out.write_all(b"\n")?;
+        if self.flush {
+            self.out.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Flush the remaining output, ending the gzip stream if compressed
+    pub fn finish(self) -> Result<()> {
+        self.out.finish()
+    }
+}
+
+/// Client style of the command formats
+fn command_style(format: &OutputFormat) -> Option<CommandStyle> {
+    match format {
+        OutputFormat::Curl => Some(CommandStyle::Curl),
+        OutputFormat::Httpie => Some(CommandStyle::Httpie),
+        _ => None,
+    }
+}
+
+/// Every endpoint as a nuclei target, one URL per line
+fn serialize_nuclei_targets(endpoints: &[Endpoint], base: Option<&str>) -> String {
+    nuclei_groups(endpoints, base)
+        .remove("all")
+        .unwrap_or_default()
+        .into_iter()
+        .map(|url| url + "\n")
+        .collect()
+}
+
+/// Serialize to a wordlist, one word per line
+fn serialize_wordlist(endpoints: &[Endpoint]) -> String {
+    build_wordlist(endpoints)
+        .into_iter()
+        .map(|word| word + "\n")
+        .collect()
+}
+
+/// Serialize to a URL list, one per line, each URL once
+fn serialize_plain(endpoints: &[Endpoint]) -> String {
+    let mut seen = HashSet::new();
+    let mut output = String::new();
+    for endpoint in endpoints {
+        if seen.insert(endpoint.url.as_str()) {
+            output.push_str(&endpoint.url);
+            output.push('\n');
+        }
+    }
+    output
+}
+
+/// Serialize to YAML
+fn serialize_yaml<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    serde_yaml::to_string(value).map_err(Error::from)
+}
+
+/// Serialize to XML
+fn serialize_xml(endpoints: &[Endpoint]) -> Result<String> {
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push('\n');
+    xml.push_str(&xml_endpoints(endpoints));
+    Ok(xml)
+}
+
+/// Serialize a scan result to XML, with the endpoints under a `<scan>`
+/// element carrying its provenance and stats
+fn serialize_xml_result(result: &ScanResult) -> Result<String> {
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push_str(&format!(
+        "\n<scan schema_version=\"{}\" tool_version=\"{}\">",
+        result.schema_version,
+        escape_xml(&result.tool_version)
+    ));
+    xml.push_str(&format!(
+        "\n  <target>{}</target>",
+        escape_xml(&result.target)
+    ));
+    xml.push_str(&format!(
+        "\n  <timestamp>{}</timestamp>",
+        escape_xml(&result.timestamp)
+    ));
+    xml.push_str(&format!(
+        "\n  <total_endpoints>{}</total_endpoints>",
+        result.total_endpoints
+    ));
+    if let Some(stats) = &result.stats {
+        xml.push_str(&format!(
+            "\n  <stats files_processed=\"{}\" requests_made=\"{}\" duration_seconds=\"{:.3}\"/>",
+            stats.files_processed, stats.requests_made, stats.duration_seconds
+        ));
+    }
+    for line in xml_endpoints(&result.endpoints).lines() {
+        xml.push_str("\n  ");
+        xml.push_str(line);
+    }
+    xml.push_str("\n</scan>");
+    Ok(xml)
+}
+
+/// `<endpoints>` element listing each endpoint
+fn xml_endpoints(endpoints: &[Endpoint]) -> String {
+    let mut xml = String::from("<endpoints>");
+
+    for endpoint in endpoints {
+        xml.push_str("\n  <endpoint>");
+        xml.push_str(&format!("\n    <url>{}</url>", escape_xml(&endpoint.url)));
+
+        if let Some(method) = &endpoint.method {
+            xml.push_str(&format!("\n    <method>{}</method>", escape_xml(method)));
+        }
+
+        xml.push_str(&format!("\n    <type>{:?}</type>", endpoint.endpoint_type));
+
+        if let Some(source) = &endpoint.source {
+            xml.push_str(&format!("\n    <source>{}</source>", escape_xml(source)));
+        }
+
+        if let Some(severity) = endpoint.severity {
+            xml.push_str(&format!("\n    <severity>{}</severity>", severity.as_str()));
+        }
+
+        if let Some(tags) = endpoint.tags.as_ref().filter(|tags| !tags.is_empty()) {
+            xml.push_str("\n    <tags>");
+            for tag in tags {
+                xml.push_str(&format!("<tag>{}</tag>", escape_xml(tag)));
+            }
+            xml.push_str("</tags>");
+        }
+
+        xml.push_str("\n  </endpoint>");
+    }
+
+    xml.push_str("\n</endpoints>");
+    xml
+}
+
+/// Escape XML special characters
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,215 @@
+//! Nuclei target lists and templates
+//!
+//! Endpoints are written as target lists for `nuclei -l`, one per group:
+//! `all.txt`, one per endpoint type (`graphql.txt`, ...) and one per tag
+//! (`admin.txt`, ...). Endpoints a probe verified also get a simple template
+//! each under `templates/`, requesting the same path on `{{BaseURL}}` and
+//! matching the status the probe saw.
+
+use crate::error::Result;
+use crate::output::absolute_url;
+use crate::types::{Endpoint, Severity};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Files written by a nuclei export
+#[derive(Debug, Clone, Default)]
+pub struct NucleiExport {
+    /// Target list files, by group name
+    pub lists: BTreeMap<String, usize>,
+
+    /// Number of templates written
+    pub templates: usize,
+}
+
+/// Group endpoints' absolute HTTP(S) URLs by endpoint type and tag
+///
+/// The `all` group holds every URL. Relative URLs are resolved against the
+/// endpoint's target, then `base`.
+pub fn nuclei_groups(endpoints: &[Endpoint], base: Option<&str>) -> BTreeMap<String, Vec<String>> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+    for endpoint in endpoints {
+        let Some(url) = absolute_url(endpoint, base) else {
+            continue;
+        };
+        let url = url.to_string();
+        let names = ["all", endpoint.endpoint_type.as_str()]
+            .into_iter()
+            .map(str::to_string)
+            .chain(endpoint.tags.iter().flatten().map(|t| slug(t)));
+        for name in names {
+            if seen.insert((name.clone(), url.clone())) {
+                groups.entry(name).or_default().push(url.clone());
+            }
+        }
+    }
+    groups
+}
+
+/// Whether a probe showed the endpoint exists: it answered, with anything
+/// but a not-found or server error
+fn is_verified(endpoint: &Endpoint) -> bool {
+    endpoint
+        .probe
+        .as_ref()
+        .is_some_and(|p| p.status < 400 || matches!(p.status, 401 | 403 | 405))
+}
+
+#[derive(Serialize)]
+struct Template {
+    id: String,
+    info: TemplateInfo,
+    http: Vec<HttpRequest>,
+}
+
+#[derive(Serialize)]
+struct TemplateInfo {
+    name: String,
+    author: &'static str,
+    severity: &'static str,
+    tags: String,
+}
+
+#[derive(Serialize)]
+struct HttpRequest {
+    method: String,
+    path: Vec<String>,
+    matchers: Vec<Matcher>,
+}
+
+#[derive(Serialize)]
+struct Matcher {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    status: Vec<u16>,
+}
+
+/// Nuclei template re-checking a verified endpoint, or `None` for endpoints
+/// that weren't verified or aren't HTTP(S)
+pub fn nuclei_template(endpoint: &Endpoint, base: Option<&str>) -> Option<String> {
+    serde_yaml::to_string(&build_template(endpoint, base)?).ok()
+}
+
+fn build_template(endpoint: &Endpoint, base: Option<&str>) -> Option<Template> {
+    let probe = endpoint.probe.as_ref().filter(|_| is_verified(endpoint))?;
+    let url = absolute_url(endpoint, base)?;
+    let path = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let method = endpoint
+        .method
+        .as_deref()
+        .unwrap_or("GET")
+        .to_ascii_uppercase();
+
+    let mut tags = vec!["endpointo".to_string()];
+    tags.extend(endpoint.tags.iter().flatten().map(|t| slug(t)));
+    Some(Template {
+        id: format!("endpointo-{}", slug(&format!("{} {}", method, url.path()))),
+        info: TemplateInfo {
+            name: format!("{} {}", method, path),
+            author: "endpointo",
+            severity: endpoint.severity.as_ref().map_or("info", Severity::as_str),
+            tags: tags.join(","),
+        },
+        http: vec![HttpRequest {
+            method,
+            path: vec![format!("{{{{BaseURL}}}}{}", path)],
+            matchers: vec![Matcher {
+                kind: "status",
+                status: vec![probe.status],
+            }],
+        }],
+    })
+}
+
+/// Write target lists, and templates for verified endpoints, into `dir`
+pub fn write_nuclei(
+    endpoints: &[Endpoint],
+    base: Option<&str>,
+    dir: &Path,
+) -> Result<NucleiExport> {
+    fs::create_dir_all(dir)?;
+    let mut export = NucleiExport::default();
+    for (name, urls) in nuclei_groups(endpoints, base) {
+        let mut list = urls.join("\n");
+        list.push('\n');
+        fs::write(dir.join(format!("{}.txt", name)), list)?;
+        export.lists.insert(name, urls.len());
+    }
+
+    let mut ids = HashSet::new();
+    for endpoint in endpoints {
+        let Some(template) = build_template(endpoint, base) else {
+            continue;
+        };
+        if !ids.insert(template.id.clone()) {
+            continue;
+        }
+        let templates = dir.join("templates");
+        fs::create_dir_all(&templates)?;
+        fs::write(
+            templates.join(format!("{}.yaml", template.id)),
+            serde_yaml::to_string(&template)?,
+        )?;
+        export.templates += 1;
+    }
+    Ok(export)
+}
+
+/// Lowercase ASCII letters and digits joined by dashes
+fn slug(s: &str) -> String {
+    s.to_ascii_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EndpointType, ProbeResult};
+
+    #[test]
+    fn test_nuclei_export() {
+        let mut admin = Endpoint::new("/admin/users".to_string(), EndpointType::Rest)
+            .with_method("DELETE")
+            .with_severity(Severity::High);
+        admin.tags = Some(vec!["admin".to_string()]);
+        admin.probe = Some(ProbeResult {
+            status: 403,
+            content_length: None,
+            content_type: None,
+            redirect: None,
+            allowed_methods: Vec::new(),
+            redirect_chain: Vec::new(),
+            soft_404: false,
+        });
+        let endpoints = vec![
+            admin,
+            Endpoint::new(
+                "https://example.com/graphql".to_string(),
+                EndpointType::GraphQL,
+            ),
+            Endpoint::new("/api/items".to_string(), EndpointType::Rest),
+        ];
+
+        let groups = nuclei_groups(&endpoints, Some("https://example.com"));
+        assert_eq!(groups["all"].len(), 3);
+        assert_eq!(groups["admin"], vec!["https://example.com/admin/users"]);
+        assert_eq!(groups["graphql"], vec!["https://example.com/graphql"]);
+        assert_eq!(groups["rest"].len(), 2);
+
+        let template = nuclei_template(&endpoints[0], Some("https://example.com")).unwrap();
+        assert!(template.contains("id: endpointo-delete-admin-users"));
+        assert!(template.contains("severity: high"));
+        assert!(template.contains("- '{{BaseURL}}/admin/users'"));
+        assert!(template.contains("- 403"));
+        assert!(nuclei_template(&endpoints[2], Some("https://example.com")).is_none());
+    }
+}
@@ -0,0 +1,471 @@
+//! OpenAPI 3 document generation from discovered REST endpoints
+//!
+//! Paths are templated where a segment looks like an ID or placeholder
+//! (`/users/42` and `/users/:id` both become `/users/{id}`), methods default
+//! to `get`, query parameters come from the URL and the endpoint's params,
+//! and request bodies from the `body_params` metadata. Every host an
+//! absolute URL points at becomes a server.
+
+use crate::diff::is_id_segment;
+use crate::error::{Error, Result};
+use crate::types::{Endpoint, EndpointType};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use url::Url;
+
+const OPENAPI_VERSION: &str = "3.0.3";
+
+/// Methods OpenAPI has an operation slot for
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// An OpenAPI 3 document
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenApi {
+    pub openapi: &'static str,
+    pub info: Info,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<Server>,
+    pub paths: BTreeMap<String, BTreeMap<String, Operation>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Info {
+    pub title: String,
+    pub version: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Server {
+    pub url: String,
+}
+
+/// One method on one path
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Operation {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<Parameter>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<RequestBody>,
+    pub responses: BTreeMap<String, Response>,
+    /// Assets the operation was found in
+    #[serde(rename = "x-endpointo-sources", skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<String>,
+}
+
+impl Operation {
+    /// An operation with nothing but its path parameters
+    fn new(path_params: &[String]) -> Self {
+        Self {
+            tags: Vec::new(),
+            parameters: path_params
+                .iter()
+                .map(|name| Parameter {
+                    name: name.clone(),
+                    location: "path",
+                    required: true,
+                    schema: Schema::string(),
+                })
+                .collect(),
+            request_body: None,
+            responses: BTreeMap::new(),
+            sources: Vec::new(),
+        }
+    }
+
+    /// Merge in what an endpoint tells about the operation
+    fn add(&mut self, endpoint: &Endpoint, query: &[String]) {
+        let query_names = query.iter().chain(endpoint.params.iter().flatten());
+        for name in query_names {
+            let exists = self
+                .parameters
+                .iter()
+                .any(|p| p.location == "query" && &p.name == name);
+            if !exists && !name.is_empty() {
+                self.parameters.push(Parameter {
+                    name: name.clone(),
+                    location: "query",
+                    required: false,
+                    schema: Schema::string(),
+                });
+            }
+        }
+
+        let body_params = endpoint
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("body_params"))
+            .map(|keys| keys.split(',').filter(|k| !k.is_empty()));
+        if let Some(keys) = body_params {
+            let body = self.request_body.get_or_insert_with(|| RequestBody {
+                content: BTreeMap::from([(
+                    "application/json".to_string(),
+                    MediaType {
+                        schema: Schema {
+                            kind: "object",
+                            ..Schema::default()
+                        },
+                    },
+                )]),
+            });
+            for media in body.content.values_mut() {
+                for key in keys.clone() {
+                    media
+                        .schema
+                        .properties
+                        .insert(key.to_string(), Schema::string());
+                }
+            }
+        }
+
+        for tag in endpoint.tags.iter().flatten() {
+            if !self.tags.contains(tag) {
+                self.tags.push(tag.clone());
+            }
+        }
+        if let Some(source) = &endpoint.source {
+            if !self.sources.contains(source) {
+                self.sources.push(source.clone());
+            }
+        }
+        if let Some(probe) = &endpoint.probe {
+            self.responses.insert(
+                probe.status.to_string(),
+                Response {
+                    description: "Observed when probing".to_string(),
+                },
+            );
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Parameter {
+    pub name: String,
+    #[serde(rename = "in")]
+    pub location: &'static str,
+    pub required: bool,
+    pub schema: Schema,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct Schema {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, Schema>,
+}
+
+impl Schema {
+    fn string() -> Self {
+        Self {
+            kind: "string",
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestBody {
+    pub content: BTreeMap<String, MediaType>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MediaType {
+    pub schema: Schema,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Response {
+    pub description: String,
+}
+
+/// Assemble an OpenAPI document from the REST endpoints among `endpoints`
+///
+/// Endpoints that aren't REST, or whose URL is neither absolute nor
+/// root-relative, are left out. Endpoints landing on the same path and
+/// method are merged. An endpoint without a method is taken to be one of
+/// the calls on its path that have one, and only becomes a `get` when
+/// there are none.
+pub fn build_openapi(title: &str, endpoints: &[Endpoint]) -> OpenApi {
+    let mut servers = BTreeSet::new();
+    let mut located = Vec::new();
+    for endpoint in endpoints {
+        if endpoint.endpoint_type != EndpointType::Rest {
+            continue;
+        }
+        let Some((server, path, query)) = split_url(&endpoint.url) else {
+            continue;
+        };
+        if let Some(server) = server {
+            servers.insert(server);
+        }
+        let method = endpoint
+            .method
+            .as_deref()
+            .map(str::to_lowercase)
+            .filter(|m| METHODS.contains(&m.as_str()));
+        let (path, path_params) = template_path(&path);
+        located.push((path, path_params, query, method, endpoint));
+    }
+    // Explicit methods first, so method-less endpoints can join them
+    located.sort_by_key(|(_, _, _, method, _)| method.is_none());
+
+    let mut paths: BTreeMap<String, BTreeMap<String, Operation>> = BTreeMap::new();
+    for (path, path_params, query, method, endpoint) in located {
+        let methods = match method {
+            Some(method) => vec![method],
+            None => match paths.get(&path) {
+                Some(operations) => operations.keys().cloned().collect(),
+                None => vec!["get".to_string()],
+            },
+        };
+        for method in methods {
+            let operation = paths
+                .entry(path.clone())
+                .or_default()
+                .entry(method)
+                .or_insert_with(|| Operation::new(&path_params));
+            operation.add(endpoint, &query);
+        }
+    }
+
+    for operation in paths.values_mut().flat_map(|methods| methods.values_mut()) {
+        if operation.responses.is_empty() {
+            operation.responses.insert(
+                "default".to_string(),
+                Response {
+                    description: "Response not observed".to_string(),
+                },
+            );
+        }
+    }
+
+    OpenApi {
+        openapi: OPENAPI_VERSION,
+        info: Info {
+            title: title.to_string(),
+            version: "0.0.0".to_string(),
+            description: format!(
+                "Reconstructed from client-side code by {} {}",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION")
+            ),
+        },
+        servers: servers.into_iter().map(|url| Server { url }).collect(),
+        paths,
+    }
+}
+
+/// Build an OpenAPI document listing only the given servers
+///
+/// Absolute endpoints on other origins are left out, while root-relative
+/// ones are taken to be on the listed servers. Servers are listed as given,
+/// without a trailing slash.
+pub fn build_openapi_for_servers(
+    title: &str,
+    endpoints: &[Endpoint],
+    servers: &[String],
+) -> Result<OpenApi> {
+    let mut origins = BTreeSet::new();
+    for server in servers {
+        let url = Url::parse(server)?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(Error::ValidationError(format!(
+                "server URL must be http(s): {}",
+                server
+            )));
+        }
+        origins.insert(url.origin().ascii_serialization());
+    }
+    let selected: Vec<Endpoint> = endpoints
+        .iter()
+        .filter(|endpoint| match split_url(&endpoint.url) {
+            Some((Some(server), _, _)) => origins.contains(&server),
+            _ => true,
+        })
+        .cloned()
+        .collect();
+
+    let mut spec = build_openapi(title, &selected);
+    spec.servers = servers
+        .iter()
+        .map(|url| Server {
+            url: url.trim_end_matches('/').to_string(),
+        })
+        .collect();
+    Ok(spec)
+}
+
+/// Serialize an OpenAPI document, as JSON for `.json` paths and YAML otherwise
+pub fn serialize_openapi(spec: &OpenApi, output_path: Option<&Path>) -> Result<String> {
+    match output_path
+        .and_then(|p| p.extension())
+        .and_then(|e| e.to_str())
+    {
+        Some("json") => Ok(serde_json::to_string_pretty(spec)?),
+        _ => Ok(serde_yaml::to_string(spec)?),
+    }
+}
+
+/// Split a URL into its server, path and query parameter names
+///
+/// Protocol-relative URLs are assumed to be HTTPS. Returns `None` for
+/// anything that isn't an HTTP(S) URL or a root-relative path.
+fn split_url(url: &str) -> Option<(Option<String>, String, Vec<String>)> {
+    let absolute = match url.strip_prefix("//") {
+        Some(rest) => Some(format!("https://{}", rest)),
+        None if url.starts_with('/') => None,
+        None => Some(url.to_string()),
+    };
+
+    let (server, path, query) = match absolute {
+        Some(absolute) => {
+            let parsed = Url::parse(&absolute).ok()?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return None;
+            }
+            let server = parsed.origin().ascii_serialization();
+            let query = parsed.query().unwrap_or("").to_string();
+            (Some(server), parsed.path().to_string(), query)
+        }
+        None => {
+            let rest = url.split('#').next().unwrap_or("");
+            let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+            (None, path.to_string(), query.to_string())
+        }
+    };
+
+    let names = query
+        .split('&')
+        .map(|pair| pair.split('=').next().unwrap_or("").to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+    Some((server, path, names))
+}
+
+/// Replace ID-like and placeholder segments with `{name}` templates
+///
+/// Placeholders keep their name (`:userId`, `${userId}` → `{userId}`);
+/// other IDs are named `id`, `id2`, ... in order.
+fn template_path(path: &str) -> (String, Vec<String>) {
+    let mut names: Vec<String> = Vec::new();
+    let segments: Vec<String> = path
+        .split('/')
+        .map(|segment| {
+            if !is_id_segment(segment) {
+                return segment.to_string();
+            }
+            let named: String = segment
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+                .collect();
+            let placeholder = !segment
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphanumeric());
+            let mut name = if placeholder && !named.is_empty() {
+                named
+            } else {
+                "id".to_string()
+            };
+            if names.contains(&name) {
+                let base = name.clone();
+                let mut n = 2;
+                while names.contains(&name) {
+                    name = format!("{}{}", base, n);
+                    n += 1;
+                }
+            }
+            names.push(name.clone());
+            format!("{{{}}}", name)
+        })
+        .collect();
+    (segments.join("/"), names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ProbeResult;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_build_openapi() {
+        let endpoints = vec![
+            Endpoint::new(
+                "https://api.example.com/v1/users/42?expand=team".to_string(),
+                EndpointType::Rest,
+            ),
+            Endpoint::new(
+                "/v1/users/:userId/posts/${postId}".to_string(),
+                EndpointType::Rest,
+            )
+            .with_method("DELETE".to_string()),
+            Endpoint::new("/v1/users".to_string(), EndpointType::Rest)
+                .with_method("POST".to_string())
+                .with_metadata(HashMap::from([(
+                    "body_params".to_string(),
+                    "email,name".to_string(),
+                )])),
+            Endpoint::new(
+                "wss://api.example.com/live".to_string(),
+                EndpointType::WebSocket,
+            ),
+        ];
+        let mut probed = endpoints[0].clone();
+        probed.probe = Some(ProbeResult {
+            status: 200,
+            content_length: None,
+            content_type: None,
+            redirect: None,
+            allowed_methods: Vec::new(),
+            redirect_chain: Vec::new(),
+            soft_404: false,
+        });
+
+        let spec = build_openapi("example", &[endpoints.clone(), vec![probed]].concat());
+        assert_eq!(spec.servers.len(), 1);
+        assert_eq!(spec.servers[0].url, "https://api.example.com");
+        assert_eq!(spec.paths.len(), 3);
+
+        let get = &spec.paths["/v1/users/{id}"]["get"];
+        let params: Vec<_> = get
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_str(), p.location))
+            .collect();
+        assert_eq!(params, vec![("id", "path"), ("expand", "query")]);
+        assert!(get.responses.contains_key("200"));
+
+        let other = Endpoint::new(
+            "https://cdn.example.com/v1/assets".to_string(),
+            EndpointType::Rest,
+        );
+        let spec = build_openapi_for_servers(
+            "example",
+            &[endpoints.clone(), vec![other]].concat(),
+            &["https://api.example.com/".to_string()],
+        )
+        .unwrap();
+        assert_eq!(spec.servers[0].url, "https://api.example.com");
+        assert!(!spec.paths.contains_key("/v1/assets"));
+        assert!(spec.paths.contains_key("/v1/users/{id}"));
+
+        let delete = &spec.paths["/v1/users/{userId}/posts/{postId}"]["delete"];
+        assert_eq!(delete.parameters.len(), 2);
+
+        let body = spec.paths["/v1/users"]["post"]
+            .request_body
+            .as_ref()
+            .unwrap();
+        let properties = &body.content["application/json"].schema.properties;
+        assert!(properties.contains_key("email") && properties.contains_key("name"));
+    }
+}
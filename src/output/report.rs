@@ -0,0 +1,398 @@
+//! Rendering of multi-scan reports
+//!
+//! Markdown for tickets and wikis, a self-contained HTML page with a trend
+//! chart per target for clients, or JSON for automation.
+
+use crate::error::Result;
+use crate::output::diff::{cell, code};
+use crate::output::escape_xml;
+use crate::report::{Report, TargetReport};
+use crate::store::{ScanRecord, StoredEndpoint};
+
+/// How a report is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Html,
+    Markdown,
+    Json,
+}
+
+impl ReportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "html",
+            ReportFormat::Markdown => "markdown",
+            ReportFormat::Json => "json",
+        }
+    }
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "html" | "htm" => Ok(ReportFormat::Html),
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err(format!(
+                "unknown report format '{}' (expected html, markdown or json)",
+                s
+            )),
+        }
+    }
+}
+
+/// Render a report in the given format
+pub fn render_report(report: &Report, format: ReportFormat) -> Result<String> {
+    Ok(match format {
+        ReportFormat::Html => render_html(report),
+        ReportFormat::Markdown => render_markdown(report),
+        ReportFormat::Json => serde_json::to_string_pretty(report)?,
+    })
+}
+
+/// Method shown for an endpoint, `GET` when none was found
+fn method(stored: &StoredEndpoint) -> String {
+    stored
+        .endpoint
+        .method
+        .as_deref()
+        .unwrap_or("GET")
+        .to_uppercase()
+}
+
+/// What counts as new, for headings
+fn period(report: &Report) -> String {
+    match &report.since {
+        Some(since) => format!("since {}", since),
+        None => "in the latest scan".to_string(),
+    }
+}
+
+/// Endpoint counts of a target's scans as a sparkline, e.g. `▁▃▃█`
+fn sparkline(scans: &[ScanRecord]) -> String {
+    const TICKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = scans.iter().map(|s| s.total_endpoints).max().unwrap_or(0);
+    scans
+        .iter()
+        .map(|s| {
+            TICKS[(s.total_endpoints * (TICKS.len() - 1))
+                .checked_div(max)
+                .unwrap_or(0)]
+        })
+        .collect()
+}
+
+fn render_markdown(report: &Report) -> String {
+    let mut md = format!(
+        "# Endpointo monitoring report\n\nGenerated {} from {} scans of {} targets: **{}** endpoints, **{}** new {}, **{}** no longer seen\n",
+        report.generated,
+        report.scans(),
+        report.targets.len(),
+        report.endpoints(),
+        report.new_endpoints(),
+        period(report),
+        report.gone_endpoints()
+    );
+
+    for target in &report.targets {
+        md.push_str(&format!(
+            "\n## {}\n\n{} endpoints over {} scans {}\n\n| Scan | Endpoints | New |\n| --- | ---: | ---: |\n",
+            target.target,
+            target.endpoints,
+            target.scans.len(),
+            sparkline(&target.scans)
+        ));
+        for scan in &target.scans {
+            md.push_str(&format!(
+                "| {} | {} | {} |\n",
+                scan.timestamp, scan.total_endpoints, scan.new_endpoints
+            ));
+        }
+
+        if !target.new.is_empty() {
+            md.push_str(&format!(
+                "\n### New endpoints {}\n\n| Method | URL | Type | Severity | Tags | First seen |\n| --- | --- | --- | --- | --- | --- |\n",
+                period(report)
+            ));
+            for stored in &target.new {
+                let ep = &stored.endpoint;
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} |\n",
+                    method(stored),
+                    code(&ep.url),
+                    ep.endpoint_type.as_str(),
+                    ep.severity.map(|s| s.as_str()).unwrap_or("-"),
+                    cell(&ep.tags.as_deref().unwrap_or_default().join(", ")),
+                    stored.first_seen
+                ));
+            }
+        }
+
+        if !target.gone.is_empty() {
+            md.push_str(
+                "\n### No longer seen\n\n| Method | URL | Type | Last seen |\n| --- | --- | --- | --- |\n",
+            );
+            for stored in &target.gone {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} |\n",
+                    method(stored),
+                    code(&stored.endpoint.url),
+                    stored.endpoint.endpoint_type.as_str(),
+                    stored.last_seen
+                ));
+            }
+        }
+
+        md.push_str(
+            "\n### Hosts\n\n| Host | Endpoints | New | Gone | Types |\n| --- | ---: | ---: | ---: | --- |\n",
+        );
+        for host in &target.hosts {
+            let types: Vec<String> = host
+                .types
+                .iter()
+                .map(|(kind, count)| format!("{} {}", kind, count))
+                .collect();
+            md.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                cell(&host.host),
+                host.endpoints,
+                host.new,
+                host.gone,
+                types.join(", ")
+            ));
+        }
+    }
+    md
+}
+
+const STYLE: &str = r#"    <style>
+        body { font-family: 'Segoe UI', Tahoma, Geneva, Verdana, sans-serif; margin: 20px; background-color: #f5f5f5; }
+        h1, h2, h3 { color: #333; }
+        .container { background: white; padding: 20px; border-radius: 8px; box-shadow: 0 2px 4px rgba(0,0,0,0.1); }
+        .cards { display: flex; flex-wrap: wrap; gap: 16px; margin: 16px 0; }
+        .card { padding: 12px 20px; border-radius: 8px; background: #e3f2fd; color: #1976d2; }
+        .card strong { display: block; font-size: 1.6em; }
+        .card-new { background: #e8f5e9; color: #388e3c; }
+        .card-gone { background: #ffebee; color: #c62828; }
+        section.target { margin-top: 32px; border-top: 1px solid #ddd; }
+        table { width: 100%; border-collapse: collapse; margin-top: 12px; }
+        th, td { padding: 8px 12px; text-align: left; border-bottom: 1px solid #ddd; }
+        th { background-color: #f8f9fa; color: #333; }
+        td.num, th.num { text-align: right; }
+        .muted { color: #757575; }
+    </style>
+"#;
+
+fn render_html(report: &Report) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n    <meta charset=\"utf-8\">\n    <title>Endpointo Monitoring Report</title>\n",
+    );
+    html.push_str(STYLE);
+    html.push_str(&format!(
+        r#"</head>
+<body>
+    <div class="container">
+        <h1>📈 Endpointo Monitoring Report</h1>
+        <p class="muted">Generated {} from {} scans of {} targets</p>
+        <div class="cards">
+            <div class="card"><strong>{}</strong>endpoints</div>
+            <div class="card card-new"><strong>{}</strong>new {}</div>
+            <div class="card card-gone"><strong>{}</strong>no longer seen</div>
+        </div>
+"#,
+        escape_xml(&report.generated),
+        report.scans(),
+        report.targets.len(),
+        report.endpoints(),
+        report.new_endpoints(),
+        escape_xml(&period(report)),
+        report.gone_endpoints()
+    ));
+
+    for target in &report.targets {
+        html.push_str(&target_html(report, target));
+    }
+
+    html.push_str("    </div>\n</body>\n</html>");
+    html
+}
+
+fn target_html(report: &Report, target: &TargetReport) -> String {
+    let mut html = format!(
+        r#"        <section class="target">
+            <h2>{}</h2>
+            <p class="muted">{} endpoints over {} scans</p>
+"#,
+        escape_xml(&target.target),
+        target.endpoints,
+        target.scans.len()
+    );
+    html.push_str(&trend_chart(&target.scans));
+
+    if !target.new.is_empty() {
+        html.push_str(&format!(
+            "            <h3>New endpoints {}</h3>\n",
+            escape_xml(&period(report))
+        ));
+        html.push_str(&endpoint_table(&target.new, "First seen", |s| {
+            &s.first_seen
+        }));
+    }
+    if !target.gone.is_empty() {
+        html.push_str("            <h3>No longer seen</h3>\n");
+        html.push_str(&endpoint_table(&target.gone, "Last seen", |s| &s.last_seen));
+    }
+
+    html.push_str(
+        r#"            <h3>Hosts</h3>
+            <table>
+                <thead><tr><th>Host</th><th class="num">Endpoints</th><th class="num">New</th><th class="num">Gone</th><th>Types</th></tr></thead>
+                <tbody>
+"#,
+    );
+    for host in &target.hosts {
+        let types: Vec<String> = host
+            .types
+            .iter()
+            .map(|(kind, count)| format!("{} {}", kind, count))
+            .collect();
+        html.push_str(&format!(
+            "                    <tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td>{}</td></tr>\n",
+            escape_xml(&host.host),
+            host.endpoints,
+            host.new,
+            host.gone,
+            escape_xml(&types.join(", "))
+        ));
+    }
+    html.push_str("                </tbody>\n            </table>\n        </section>\n");
+    html
+}
+
+/// A table of endpoints with one date column
+fn endpoint_table(
+    endpoints: &[StoredEndpoint],
+    date_heading: &str,
+    date: impl Fn(&StoredEndpoint) -> &String,
+) -> String {
+    let mut html = format!(
+        r#"            <table>
+                <thead><tr><th>Method</th><th>URL</th><th>Type</th><th>Severity</th><th>Tags</th><th>{}</th></tr></thead>
+                <tbody>
+"#,
+        date_heading
+    );
+    for stored in endpoints {
+        let ep = &stored.endpoint;
+        html.push_str(&format!(
+            "                    <tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape_xml(&method(stored)),
+            escape_xml(&ep.url),
+            ep.endpoint_type.as_str(),
+            ep.severity.map(|s| s.as_str()).unwrap_or("-"),
+            escape_xml(&ep.tags.as_deref().unwrap_or_default().join(", ")),
+            escape_xml(date(stored))
+        ));
+    }
+    html.push_str("                </tbody>\n            </table>\n");
+    html
+}
+
+/// Inline SVG column chart of endpoints per scan, new ones stacked on top
+fn trend_chart(scans: &[ScanRecord]) -> String {
+    const HEIGHT: usize = 120;
+    const COLUMN: usize = 28;
+    const GAP: usize = 8;
+    let max = scans
+        .iter()
+        .map(|s| s.total_endpoints)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let mut svg = format!(
+        r#"            <svg width="{}" height="{}" role="img" font-size="11">"#,
+        scans.len() * (COLUMN + GAP),
+        HEIGHT
+    );
+    for (i, scan) in scans.iter().enumerate() {
+        let x = i * (COLUMN + GAP);
+        let total = scan.total_endpoints * HEIGHT / max;
+        let new = scan.new_endpoints.min(scan.total_endpoints) * HEIGHT / max;
+        svg.push_str(&format!(
+            r##"<g><title>{}: {} endpoints, {} new</title><rect x="{}" y="{}" width="{}" height="{}" fill="#1976d2"></rect><rect x="{}" y="{}" width="{}" height="{}" fill="#66bb6a"></rect></g>"##,
+            escape_xml(&scan.timestamp),
+            scan.total_endpoints,
+            scan.new_endpoints,
+            x,
+            HEIGHT - total,
+            COLUMN,
+            total - new,
+            x,
+            HEIGHT - total,
+            COLUMN,
+            new
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Endpoint, EndpointType, ScanResult, SCHEMA_VERSION, TOOL_VERSION};
+
+    fn result(timestamp: &str, urls: &[&str]) -> ScanResult {
+        let endpoints: Vec<Endpoint> = urls
+            .iter()
+            .map(|url| Endpoint::new(url.to_string(), EndpointType::Rest))
+            .collect();
+        ScanResult {
+            schema_version: SCHEMA_VERSION,
+            tool_version: TOOL_VERSION.to_string(),
+            target: "https://example.com".to_string(),
+            timestamp: timestamp.to_string(),
+            total_endpoints: endpoints.len(),
+            endpoints,
+            findings: Vec::new(),
+            wordlist: Vec::new(),
+            technologies: Vec::new(),
+            stats: None,
+        }
+    }
+
+    #[test]
+    fn test_render_report() {
+        let report = Report::from_results(
+            &[
+                result("2026-01-01T00:00:00+00:00", &["/api/a", "/api/old"]),
+                result("2026-01-02T00:00:00+00:00", &["/api/a", "/api/b|c"]),
+            ],
+            None,
+        )
+        .unwrap();
+
+        let md = render_report(&report, ReportFormat::Markdown).unwrap();
+        assert!(md.contains("**3** endpoints, **1** new in the latest scan, **1** no longer seen"));
+        assert!(md.contains("3 endpoints over 2 scans ██\n"));
+        assert!(md.contains("| GET | `/api/b\\|c` | rest | - |  | 2026-01-02T00:00:00+00:00 |\n"));
+        assert!(md.contains("| GET | `/api/old` | rest | 2026-01-01T00:00:00+00:00 |\n"));
+        assert!(md.contains("| https://example.com | 3 | 1 | 1 | rest 3 |\n"));
+
+        let html = render_report(&report, ReportFormat::Html).unwrap();
+        assert!(html.contains("<h2>https://example.com</h2>"));
+        assert!(html.contains("<title>2026-01-02T00:00:00+00:00: 2 endpoints, 1 new</title>"));
+        assert!(html.contains("<td>/api/b|c</td>"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_report(&report, ReportFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["targets"][0]["new"][0]["endpoint"]["url"], "/api/b|c");
+        assert_eq!(
+            "md".parse::<ReportFormat>().unwrap(),
+            ReportFormat::Markdown
+        );
+    }
+}
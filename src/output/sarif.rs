@@ -0,0 +1,271 @@
+//! SARIF 2.1.0 output for code scanning platforms
+//!
+//! Each endpoint type and finding kind is a rule, so GitHub code scanning
+//! and Azure DevOps can group and filter results by category. Locations
+//! point at the original file of sourcemap-resolved endpoints, otherwise at
+//! the asset the endpoint or finding was found in.
+
+use crate::types::{Endpoint, EndpointType, Finding, FindingKind, Severity};
+use serde::Serialize;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// A rule per result category: ID, name, description and default level
+const RULES: &[(&str, &str, &str, &str)] = &[
+    (
+        "endpoint/rest",
+        "RestEndpoint",
+        "REST API endpoint referenced by client code",
+        "note",
+    ),
+    (
+        "endpoint/graphql",
+        "GraphqlEndpoint",
+        "GraphQL endpoint referenced by client code",
+        "note",
+    ),
+    (
+        "endpoint/websocket",
+        "WebsocketEndpoint",
+        "WebSocket endpoint referenced by client code",
+        "note",
+    ),
+    (
+        "endpoint/sse",
+        "SseEndpoint",
+        "Server-Sent Events stream referenced by client code",
+        "note",
+    ),
+    (
+        "endpoint/unknown",
+        "UnknownEndpoint",
+        "URL of unknown kind referenced by client code",
+        "note",
+    ),
+    (
+        "finding/env-var",
+        "EnvironmentVariable",
+        "Build-time environment variable reference shipped to clients",
+        "warning",
+    ),
+    (
+        "finding/internal-host",
+        "InternalHost",
+        "Hostname on an internal or non-public domain",
+        "warning",
+    ),
+    (
+        "finding/private-ip",
+        "PrivateIp",
+        "Private, loopback or link-local IP address",
+        "warning",
+    ),
+    (
+        "finding/secret",
+        "Secret",
+        "Credential in a well-known token format shipped to clients",
+        "error",
+    ),
+];
+
+#[derive(Serialize)]
+struct Log<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run<'a>>,
+}
+
+#[derive(Serialize)]
+struct Run<'a> {
+    tool: Tool,
+    results: Vec<SarifResult<'a>>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<Rule>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: &'static str,
+    name: &'static str,
+    short_description: Text,
+    default_configuration: Configuration,
+}
+
+#[derive(Serialize)]
+struct Configuration {
+    level: &'static str,
+}
+
+#[derive(Serialize)]
+struct Text {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult<'a> {
+    rule_id: &'static str,
+    rule_index: usize,
+    level: &'static str,
+    message: Text,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    locations: Vec<Location<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    properties: Option<Properties<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location<'a> {
+    physical_location: PhysicalLocation<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation<'a> {
+    artifact_location: ArtifactLocation<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<Region>,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation<'a> {
+    uri: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+}
+
+#[derive(Serialize)]
+struct Properties<'a> {
+    tags: &'a [String],
+}
+
+/// Serialize endpoints and findings as a SARIF log with a single run
+pub fn serialize_sarif(
+    endpoints: &[Endpoint],
+    findings: &[Finding],
+) -> crate::error::Result<String> {
+    let results = endpoints
+        .iter()
+        .map(endpoint_result)
+        .chain(findings.iter().map(finding_result))
+        .collect();
+
+    let log = Log {
+        schema: SCHEMA,
+        version: "2.1.0",
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: env!("CARGO_PKG_NAME"),
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: env!("CARGO_PKG_REPOSITORY"),
+                    rules: RULES
+                        .iter()
+                        .map(|&(id, name, description, level)| Rule {
+                            id,
+                            name,
+                            short_description: Text {
+                                text: description.to_string(),
+                            },
+                            default_configuration: Configuration { level },
+                        })
+                        .collect(),
+                },
+            },
+            results,
+        }],
+    };
+    Ok(serde_json::to_string_pretty(&log)?)
+}
+
+fn endpoint_result(endpoint: &Endpoint) -> SarifResult<'_> {
+    let rule_index = match endpoint.endpoint_type {
+        EndpointType::Rest => 0,
+        EndpointType::GraphQL => 1,
+        EndpointType::WebSocket => 2,
+        EndpointType::Sse => 3,
+        EndpointType::Unknown => 4,
+    };
+    let level = match endpoint.severity {
+        Some(Severity::Critical | Severity::High) => "error",
+        Some(Severity::Medium) => "warning",
+        Some(Severity::Low | Severity::Info) | None => "note",
+    };
+    let text = match &endpoint.method {
+        Some(method) => format!("{} {}", method, endpoint.url),
+        None => endpoint.url.clone(),
+    };
+    // Sourcemap-resolved endpoints point at the original file, whose lines
+    // their line numbers count
+    let uri = endpoint
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("original_source"))
+        .or(endpoint.source.as_ref());
+
+    SarifResult {
+        rule_id: RULES[rule_index].0,
+        rule_index,
+        level,
+        message: Text { text },
+        locations: location(uri.map(String::as_str), endpoint.line),
+        properties: endpoint
+            .tags
+            .as_deref()
+            .filter(|tags| !tags.is_empty())
+            .map(|tags| Properties { tags }),
+    }
+}
+
+fn finding_result(finding: &Finding) -> SarifResult<'_> {
+    let rule_index = match finding.kind {
+        FindingKind::EnvVar => 5,
+        FindingKind::InternalHost => 6,
+        FindingKind::PrivateIp => 7,
+        FindingKind::Secret => 8,
+    };
+    SarifResult {
+        rule_id: RULES[rule_index].0,
+        rule_index,
+        level: RULES[rule_index].3,
+        message: Text {
+            text: format!("{}: {}", RULES[rule_index].2, finding.value),
+        },
+        locations: location(finding.source.as_deref(), finding.line),
+        properties: (!finding.tags.is_empty()).then_some(Properties {
+            tags: &finding.tags,
+        }),
+    }
+}
+
+fn location(uri: Option<&str>, line: Option<usize>) -> Vec<Location<'_>> {
+    uri.map(|uri| Location {
+        physical_location: PhysicalLocation {
+            artifact_location: ArtifactLocation { uri },
+            region: line
+                .filter(|&l| l > 0)
+                .map(|start_line| Region { start_line }),
+        },
+    })
+    .into_iter()
+    .collect()
+}
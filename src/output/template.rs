@@ -0,0 +1,103 @@
+//! User-templated output
+//!
+//! A Tera template is rendered with the scan result as its context:
+//! `schema_version`, `tool_version`, `target`, `timestamp`,
+//! `total_endpoints`, `endpoints`, `findings`, `wordlist`, `technologies`
+//! and `stats` (null when not collected).
+//! Templates whose file name ends in `.html`, `.htm` or `.xml` have
+//! variables HTML-escaped; others are rendered as-is.
+
+use crate::error::{Error, Result};
+use crate::types::ScanResult;
+use std::error::Error as _;
+use std::fs;
+use std::path::Path;
+use tera::{Context, Tera};
+
+/// Render the template at `path` with `result` as its context
+pub fn render_template(result: &ScanResult, path: &Path) -> Result<String> {
+    let source = fs::read_to_string(path)?;
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "template".to_string());
+
+    let mut tera = Tera::default();
+    tera.add_raw_template(&name, &source)
+        .map_err(template_error)?;
+    let mut context = Context::from_serialize(result).map_err(template_error)?;
+    // Empty collections are left out of the serialized result; keep them
+    // defined so templates can loop over them unconditionally
+    for key in ["findings", "wordlist", "technologies"] {
+        if !context.contains_key(key) {
+            context.insert(key, &Vec::<()>::new());
+        }
+    }
+    if !context.contains_key("stats") {
+        context.insert("stats", &());
+    }
+    tera.render(&name, &context).map_err(template_error)
+}
+
+/// Tera puts the useful part of its errors (line, unknown variable, ...)
+/// in the source chain
+fn template_error(e: tera::Error) -> Error {
+    let mut message = e.to_string();
+    let mut source = e.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    Error::TemplateError(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Endpoint, EndpointType, SCHEMA_VERSION, TOOL_VERSION};
+
+    #[test]
+    fn test_render_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = ScanResult {
+            schema_version: SCHEMA_VERSION,
+            tool_version: TOOL_VERSION.to_string(),
+            target: "https://example.com".to_string(),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            total_endpoints: 1,
+            endpoints: vec![Endpoint::new(
+                "/api/<users>".to_string(),
+                EndpointType::Rest,
+            )],
+            findings: Vec::new(),
+            wordlist: Vec::new(),
+            technologies: Vec::new(),
+            stats: None,
+        };
+
+        let text = dir.path().join("ticket.tera");
+        fs::write(
+            &text,
+            "{{ target }}: {{ total_endpoints }}\n\
+             {% for e in endpoints %}- {{ e.url }}\n{% endfor %}\
+             {% for f in findings %}{{ f.value }}{% endfor %}",
+        )
+        .unwrap();
+        assert_eq!(
+            render_template(&result, &text).unwrap(),
+            "https://example.com: 1\n- /api/<users>\n"
+        );
+
+        let html = dir.path().join("report.html");
+        fs::write(&html, "{{ endpoints[0].url }}").unwrap();
+        assert!(render_template(&result, &html)
+            .unwrap()
+            .ends_with("&lt;users&gt;"));
+
+        let broken = dir.path().join("broken.tera");
+        fs::write(&broken, "{{ missing }}").unwrap();
+        let err = render_template(&result, &broken).unwrap_err().to_string();
+        assert!(err.contains("missing"), "{}", err);
+    }
+}
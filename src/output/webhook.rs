@@ -0,0 +1,122 @@
+//! Webhook output sink
+//!
+//! Results are POSTed to a webhook URL, either as one JSON scan result or
+//! as NDJSON batches of endpoints for receivers that ingest line by line.
+//! With a secret, each request carries an `X-Endpointo-Signature` header
+//! holding `sha256=` and the hex HMAC-SHA256 of the body, so receivers can
+//! check where it came from.
+
+use crate::error::{Error, Result};
+use crate::types::{Endpoint, ScanResult};
+use ring::hmac;
+use url::Url;
+
+/// Header carrying the body's HMAC-SHA256 signature
+pub const SIGNATURE_HEADER: &str = "X-Endpointo-Signature";
+
+/// Endpoints per NDJSON request unless told otherwise
+const DEFAULT_BATCH: usize = 500;
+
+/// Signature of `body` under `secret`, as sent in [`SIGNATURE_HEADER`]
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, body);
+    let hex: String = tag.as_ref().iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+/// Client POSTing results to a webhook
+pub struct Webhook {
+    url: Url,
+    secret: Option<String>,
+    batch: Option<usize>,
+    client: reqwest::Client,
+}
+
+impl Webhook {
+    /// Post to `url`, sending whole scan results
+    pub fn new(url: &str) -> Result<Self> {
+        let url = Url::parse(url)?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(Error::ValidationError(format!(
+                "webhook URL must be http(s): {}",
+                url
+            )));
+        }
+        Ok(Self {
+            url,
+            secret: None,
+            batch: None,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// Sign request bodies with this secret
+    pub fn with_secret(mut self, secret: Option<String>) -> Self {
+        self.secret = secret.filter(|s| !s.is_empty());
+        self
+    }
+
+    /// Send endpoints as NDJSON, this many per request (0 for the default)
+    pub fn with_batch(mut self, batch: Option<usize>) -> Self {
+        self.batch = batch.map(|n| if n == 0 { DEFAULT_BATCH } else { n });
+        self
+    }
+
+    /// Send a scan result, returning how many requests were made
+    pub async fn send(&self, result: &ScanResult) -> Result<usize> {
+        let Some(batch) = self.batch else {
+            self.post(serde_json::to_vec(result)?, "application/json")
+                .await?;
+            return Ok(1);
+        };
+        let mut sent = 0;
+        for chunk in result.endpoints.chunks(batch) {
+            self.post(ndjson(chunk)?, "application/x-ndjson").await?;
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
+    async fn post(&self, body: Vec<u8>, content_type: &str) -> Result<()> {
+        let mut request = self
+            .client
+            .post(self.url.clone())
+            .header(reqwest::header::CONTENT_TYPE, content_type);
+        if let Some(secret) = &self.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, &body));
+        }
+        let response = request.body(body).send().await?;
+        if !response.status().is_success() {
+            return Err(Error::Other(format!(
+                "webhook returned {}",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Endpoints as JSON Lines
+fn ndjson(endpoints: &[Endpoint]) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    for endpoint in endpoints {
+        serde_json::to_writer(&mut body, endpoint)?;
+        body.push(b'\n');
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -0,0 +1,395 @@
+use crate::parser::extractor::{AssetContext, AssetKind, EndpointExtractor};
+use crate::parser::patterns::PatternMatcher;
+use crate::types::{Confidence, Endpoint, EndpointType};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::sync::Arc;
+
+lazy_static! {
+    // Bare multi-segment paths in prose: "hit /internal/debug/reset"
+    static ref COMMENT_PATH: Regex =
+        Regex::new(r#"(?:^|[\s(\[,:=])(/[A-Za-z0-9_.-]+(?:/[A-Za-z0-9_.{}:-]+)+/?)"#).unwrap();
+
+    // <!-- ... -->
+    static ref HTML_COMMENT: Regex = Regex::new(r#"(?s)<!--(.*?)-->"#).unwrap();
+
+    // <script ...> ... </script>, the script's body in the first group
+    static ref INLINE_SCRIPT: Regex =
+        Regex::new(r#"(?is)<script\b[^>]*>(.*?)</script\s*>"#).unwrap();
+
+    // /* ... */ (CSS has no line comments; `//` starts protocol-relative URLs)
+    static ref CSS_COMMENT: Regex = Regex::new(r#"(?s)/\*(.*?)\*/"#).unwrap();
+}
+
+/// Tag applied to endpoints that were only found inside comments
+pub const COMMENT_TAG: &str = "comment";
+
+/// A comment found in source code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    /// 1-based line the comment starts on
+    pub line: usize,
+
+    /// Comment text without delimiters
+    pub text: String,
+}
+
+/// Lexer state while splitting code from comments
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    Code,
+    SingleQuote,
+    DoubleQuote,
+    Template,
+    Regex { in_class: bool },
+    LineComment,
+    BlockComment,
+}
+
+/// Scanner that separates comments from code and mines them for endpoints
+pub struct CommentScanner;
+
+impl CommentScanner {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Split JavaScript into code with comments blanked out and the comments
+    ///
+    /// The returned code keeps byte offsets and line numbers of the input.
+    pub fn split_js(&self, content: &str) -> (String, Vec<Comment>) {
+        let bytes = content.as_bytes();
+        let mut code = bytes.to_vec();
+        let mut comments = Vec::new();
+
+        let mut state = State::Code;
+        let mut line = 1;
+        let mut comment_start = 0;
+        let mut comment_line = 1;
+        let mut last_significant: Option<u8> = None;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let b = bytes[i];
+            let next = bytes.get(i + 1).copied();
+
+            match state {
+                State::Code => match b {
+                    b'\'' => state = State::SingleQuote,
+                    b'"' => state = State::DoubleQuote,
+                    b'`' => state = State::Template,
+                    b'/' if next == Some(b'/') || next == Some(b'*') => {
+                        state = if next == Some(b'/') {
+                            State::LineComment
+                        } else {
+                            State::BlockComment
+                        };
+                        comment_start = i;
+                        comment_line = line;
+                        i += 2;
+                        continue;
+                    }
+                    b'/' if starts_regex(last_significant) => {
+                        state = State::Regex { in_class: false }
+                    }
+                    _ => {}
+                },
+                State::SingleQuote | State::DoubleQuote => {
+                    let quote = if state == State::SingleQuote {
+                        b'\''
+                    } else {
+                        b'"'
+                    };
+                    if b == b'\\' {
+                        line += usize::from(next == Some(b'\n'));
+                        i += 2;
+                        continue;
+                    }
+                    if b == quote || b == b'\n' {
+                        state = State::Code;
+                    }
+                }
+                State::Template => {
+                    if b == b'\\' {
+                        line += usize::from(next == Some(b'\n'));
+                        i += 2;
+                        continue;
+                    }
+                    if b == b'`' {
+                        state = State::Code;
+                    }
+                }
+                State::Regex { in_class } => match b {
+                    b'\\' => {
+                        i += 2;
+                        continue;
+                    }
+                    b'[' => state = State::Regex { in_class: true },
+                    b']' => state = State::Regex { in_class: false },
+                    b'/' if !in_class => state = State::Code,
+                    b'\n' => state = State::Code,
+                    _ => {}
+                },
+                State::LineComment => {
+                    if b == b'\n' {
+                        comments.push(Comment {
+                            line: comment_line,
+                            text: content[comment_start + 2..i].to_string(),
+                        });
+                        blank(&mut code[comment_start..i]);
+                        state = State::Code;
+                    }
+                }
+                State::BlockComment => {
+                    if b == b'*' && next == Some(b'/') {
+                        comments.push(Comment {
+                            line: comment_line,
+                            text: content[comment_start + 2..i].to_string(),
+                        });
+                        blank(&mut code[comment_start..i + 2]);
+                        state = State::Code;
+                        i += 2;
+                        continue;
+                    }
+                }
+            }
+
+            if b == b'\n' {
+                line += 1;
+            }
+            if state == State::Code && !b.is_ascii_whitespace() {
+                last_significant = Some(b);
+            }
+            i += 1;
+        }
+
+        // Comment running to end of input
+        if matches!(state, State::LineComment | State::BlockComment) {
+            let text_start = (comment_start + 2).min(bytes.len());
+            comments.push(Comment {
+                line: comment_line,
+                text: content[text_start..].to_string(),
+            });
+            blank(&mut code[comment_start..]);
+        }
+
+        // Only ASCII bytes were replaced, with ASCII spaces, over whole
+        // comment spans, so the result is still valid UTF-8
+        let code = String::from_utf8(code).unwrap_or_else(|_| content.to_string());
+        (code, comments)
+    }
+
+    /// Split HTML into markup with `<!-- -->` comments, and `//` and `/* */`
+    /// comments in inline scripts, blanked out and the comments
+    pub fn split_html(&self, content: &str) -> (String, Vec<Comment>) {
+        let (mut code, mut comments) = split_delimited(content, &HTML_COMMENT);
+
+        let markup = code.clone();
+        for body in INLINE_SCRIPT
+            .captures_iter(&markup)
+            .filter_map(|c| c.get(1))
+        {
+            let (script, script_comments) = self.split_js(body.as_str());
+            if script_comments.is_empty() {
+                continue;
+            }
+            let line = markup[..body.start()].matches('\n').count();
+            comments.extend(script_comments.into_iter().map(|comment| Comment {
+                line: line + comment.line,
+                ..comment
+            }));
+            code.replace_range(body.range(), &script);
+        }
+        comments.sort_by_key(|comment| comment.line);
+
+        (code, comments)
+    }
+
+    /// Split CSS into rules with `/* */` comments blanked out and the comments
+    pub fn split_css(&self, content: &str) -> (String, Vec<Comment>) {
+        split_delimited(content, &CSS_COMMENT)
+    }
+
+    /// Extract endpoints mentioned in comments but absent from the code
+    pub fn extract(
+        &self,
+        comments: &[Comment],
+        code: &str,
+        pattern_matcher: &PatternMatcher,
+        source: Option<&str>,
+    ) -> Vec<Endpoint> {
+        let mut endpoints = Vec::new();
+
+        for comment in comments {
+            let mut urls = pattern_matcher.find_urls(&comment.text);
+            for cap in COMMENT_PATH.captures_iter(&comment.text) {
+                urls.push(cap[1].trim_end_matches(['.', ':', ',']).to_string());
+            }
+
+            for url in urls {
+                if code.contains(url.as_str()) || endpoints.iter().any(|e: &Endpoint| e.url == url)
+                {
+                    continue;
+                }
+
+                let endpoint_type = if url.starts_with("ws") {
+                    EndpointType::WebSocket
+                } else if url.contains("graphql") {
+                    EndpointType::GraphQL
+                } else {
+                    EndpointType::Rest
+                };
+
+                let mut endpoint = Endpoint::new(url, endpoint_type)
+                    .with_line(comment.line)
+                    .with_confidence(Confidence::Low)
+                    .with_tag(COMMENT_TAG);
+                if let Some(src) = source {
+                    endpoint = endpoint.with_source(src);
+                }
+                endpoints.push(endpoint);
+            }
+        }
+
+        endpoints
+    }
+}
+
+impl Default for CommentScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Extractor for URLs and paths that only survive in comments
+pub struct CommentExtractor {
+    scanner: CommentScanner,
+    pattern_matcher: Arc<PatternMatcher>,
+}
+
+impl CommentExtractor {
+    pub fn new(pattern_matcher: Arc<PatternMatcher>) -> Self {
+        Self {
+            scanner: CommentScanner::new(),
+            pattern_matcher,
+        }
+    }
+}
+
+impl EndpointExtractor for CommentExtractor {
+    fn name(&self) -> &str {
+        "comments"
+    }
+
+    fn supports(&self, kind: AssetKind) -> bool {
+        kind.is_script() || kind == AssetKind::Css
+    }
+
+    fn extract(&self, _content: &str, ctx: &AssetContext) -> Vec<Endpoint> {
+        self.scanner
+            .extract(ctx.comments, ctx.code, &self.pattern_matcher, ctx.source)
+    }
+}
+
+/// Blank out every match of a comment regex whose first group is the comment text
+fn split_delimited(content: &str, comment: &Regex) -> (String, Vec<Comment>) {
+    let mut code = content.to_string();
+    let mut comments = Vec::new();
+
+    for cap in comment.captures_iter(content) {
+        let (Some(whole), Some(text)) = (cap.get(0), cap.get(1)) else {
+            continue;
+        };
+        comments.push(Comment {
+            line: content[..whole.start()].matches('\n').count() + 1,
+            text: text.as_str().to_string(),
+        });
+        let blanked: String = whole
+            .as_str()
+            .chars()
+            .map(|c| match c {
+                '\n' => "\n".to_string(),
+                _ => " ".repeat(c.len_utf8()),
+            })
+            .collect();
+        code.replace_range(whole.range(), &blanked);
+    }
+
+    (code, comments)
+}
+
+/// Whether a `/` following this byte starts a regex literal rather than a division
+fn starts_regex(previous: Option<u8>) -> bool {
+    match previous {
+        None => true,
+        Some(b) => b"(,=:[!&|?{};+-*%<>~^".contains(&b),
+    }
+}
+
+/// Replace non-newline bytes with spaces
+fn blank(bytes: &mut [u8]) {
+    for b in bytes.iter_mut() {
+        if *b != b'\n' {
+            *b = b' ';
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_js_comments() {
+        let scanner = CommentScanner::new();
+        let content = "const a = \"https://x.com/y\"; // TODO hit /internal/debug/reset\n\
+                       const re = /\\/\\/not-a-comment/g;\n\
+                       /* staging: https://staging.example.com/api */ go('/api/ok');";
+
+        let (code, comments) = scanner.split_js(content);
+
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].line, 1);
+        assert!(comments[0].text.contains("/internal/debug/reset"));
+        assert_eq!(comments[1].line, 3);
+        assert!(code.contains("\"https://x.com/y\""));
+        assert!(code.contains("not-a-comment"));
+        assert!(code.contains("go('/api/ok')"));
+        assert!(!code.contains("staging"));
+        assert_eq!(code.len(), content.len());
+    }
+
+    #[test]
+    fn test_split_html_inline_script_comments() {
+        let scanner = CommentScanner::new();
+        let content = "<html>\n<!-- old: /legacy/login -->\n<script>\n\
+                       fetch('/api/live'); // TODO drop /internal/debug/reset\n\
+                       /* staging: https://staging.example.com/api */\n\
+                       </script>\n<p>// not a comment in markup</p>\n</html>";
+
+        let (code, comments) = scanner.split_html(content);
+
+        let lines: Vec<usize> = comments.iter().map(|c| c.line).collect();
+        assert_eq!(lines, [2, 4, 5]);
+        assert!(comments[1].text.contains("/internal/debug/reset"));
+        assert!(comments[2].text.contains("staging.example.com"));
+        assert!(code.contains("fetch('/api/live');"));
+        assert!(code.contains("// not a comment in markup"));
+        assert!(!code.contains("debug") && !code.contains("staging") && !code.contains("legacy"));
+        assert_eq!(code.len(), content.len());
+
+        let endpoints =
+            scanner.extract(&comments, &code, &PatternMatcher::new(), Some("index.html"));
+        let debug = endpoints
+            .iter()
+            .find(|e| e.url == "/internal/debug/reset")
+            .unwrap();
+        assert_eq!(debug.line, Some(4));
+        assert_eq!(debug.confidence, Some(Confidence::Low));
+        assert!(debug
+            .tags
+            .as_ref()
+            .unwrap()
+            .contains(&COMMENT_TAG.to_string()));
+    }
+}
@@ -0,0 +1,243 @@
+use crate::parser::extractor::{AssetContext, AssetKind, EndpointExtractor};
+use crate::parser::patterns::PatternMatcher;
+use crate::types::{Confidence, Endpoint};
+use base64::engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+lazy_static! {
+    // Quoted literals made only of base64 (standard or URL-safe) characters
+    static ref BASE64_LITERAL: Regex =
+        Regex::new(r#"['"`]([A-Za-z0-9+/_-]{16,}={0,2})['"`]"#).unwrap();
+
+    // Quoted literals made only of hex digits
+    static ref HEX_LITERAL: Regex = Regex::new(r#"['"`]((?:[0-9a-fA-F]{2}){8,})['"`]"#).unwrap();
+
+    // Unquoted values, as left behind by a previous decoding layer
+    static ref BASE64_VALUE: Regex = Regex::new(r#"^[A-Za-z0-9+/_-]{16,}={0,2}$"#).unwrap();
+    static ref HEX_VALUE: Regex = Regex::new(r#"^(?:[0-9a-fA-F]{2}){8,}$"#).unwrap();
+}
+
+/// How many layers of nested base64/hex encoding are unwrapped
+const MAX_DECODE_DEPTH: usize = 2;
+
+/// Longest literal we try to decode
+const MAX_LITERAL_BYTES: usize = 64 * 1024;
+
+/// Encoding a recovered string was hidden with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Base64,
+    Hex,
+}
+
+impl Encoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Base64 => "base64",
+            Encoding::Hex => "hex",
+        }
+    }
+}
+
+/// Decoder for base64/hex string literals used to hide URLs in bundles
+pub struct EncodedStringDecoder;
+
+impl EncodedStringDecoder {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Decode every encoded literal in the content that yields readable text
+    pub fn decode_literals(&self, content: &str) -> Vec<(Encoding, String)> {
+        let mut decoded = Vec::new();
+
+        for cap in HEX_LITERAL.captures_iter(content) {
+            let literal = &cap[1];
+            if literal.len() <= MAX_LITERAL_BYTES {
+                if let Some(text) = decode_hex(literal).and_then(into_readable) {
+                    decoded.push((Encoding::Hex, text));
+                }
+            }
+        }
+
+        for cap in BASE64_LITERAL.captures_iter(content) {
+            let literal = &cap[1];
+            // Pure hex literals were handled above
+            if literal.len() > MAX_LITERAL_BYTES || HEX_LITERAL.is_match(&cap[0]) {
+                continue;
+            }
+            if let Some(text) = decode_base64(literal).and_then(into_readable) {
+                decoded.push((Encoding::Base64, text));
+            }
+        }
+
+        decoded
+    }
+
+    /// Decode a bare value that is itself entirely base64 or hex
+    pub fn decode_value(&self, value: &str) -> Option<(Encoding, String)> {
+        let value = value.trim();
+        if value.len() > MAX_LITERAL_BYTES {
+            return None;
+        }
+
+        if HEX_VALUE.is_match(value) {
+            decode_hex(value)
+                .and_then(into_readable)
+                .map(|text| (Encoding::Hex, text))
+        } else if BASE64_VALUE.is_match(value) {
+            decode_base64(value)
+                .and_then(into_readable)
+                .map(|text| (Encoding::Base64, text))
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for EncodedStringDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Extractor that re-runs the patterns over base64/hex encoded string literals
+pub struct EncodedStringExtractor {
+    decoder: EncodedStringDecoder,
+    pattern_matcher: Arc<PatternMatcher>,
+}
+
+impl EncodedStringExtractor {
+    pub fn new(pattern_matcher: Arc<PatternMatcher>) -> Self {
+        Self {
+            decoder: EncodedStringDecoder::new(),
+            pattern_matcher,
+        }
+    }
+
+    /// Extract endpoints hidden inside encoded string literals, unwrapping
+    /// up to `depth` nested layers of encoding
+    fn decoded_endpoints(
+        &self,
+        content: &str,
+        source: Option<&str>,
+        depth: usize,
+    ) -> Vec<Endpoint> {
+        let mut endpoints = Vec::new();
+        if depth == 0 {
+            return endpoints;
+        }
+
+        let mut layers = self.decoder.decode_literals(content);
+        layers.extend(self.decoder.decode_value(content));
+
+        for (encoding, decoded) in layers {
+            let mut found: Vec<Endpoint> = self
+                .pattern_matcher
+                .find_urls(&decoded)
+                .into_iter()
+                .map(|url| {
+                    let endpoint_type = self.pattern_matcher.detect_endpoint_type(&url, &decoded);
+                    Endpoint::new(url, endpoint_type)
+                })
+                .collect();
+            found.extend(self.pattern_matcher.find_api_endpoints(&decoded, None));
+
+            // Decoded literals are often a bare path or URL the patterns can't anchor on
+            let trimmed = decoded.trim();
+            if found.is_empty()
+                && (trimmed.starts_with('/') || trimmed.contains("://"))
+                && !trimmed.contains(char::is_whitespace)
+            {
+                let endpoint_type = self.pattern_matcher.detect_endpoint_type(trimmed, trimmed);
+                found.push(Endpoint::new(trimmed.to_string(), endpoint_type));
+            }
+
+            for mut endpoint in found {
+                let mut metadata = HashMap::new();
+                metadata.insert("encoding".to_string(), encoding.as_str().to_string());
+                endpoint = endpoint
+                    .with_metadata(metadata)
+                    .with_confidence(Confidence::Medium);
+                if let Some(src) = source {
+                    endpoint = endpoint.with_source(src);
+                }
+                endpoints.push(endpoint);
+            }
+
+            endpoints.extend(self.decoded_endpoints(&decoded, source, depth - 1));
+        }
+
+        endpoints
+    }
+}
+
+impl EndpointExtractor for EncodedStringExtractor {
+    fn name(&self) -> &str {
+        "encoded"
+    }
+
+    fn supports(&self, kind: AssetKind) -> bool {
+        kind.is_script() || kind == AssetKind::Json
+    }
+
+    fn extract(&self, _content: &str, ctx: &AssetContext) -> Vec<Endpoint> {
+        self.decoded_endpoints(ctx.code, ctx.source, MAX_DECODE_DEPTH)
+    }
+}
+
+fn decode_base64(literal: &str) -> Option<Vec<u8>> {
+    let unpadded = literal.trim_end_matches('=');
+    if unpadded.contains(['-', '_']) {
+        URL_SAFE_NO_PAD.decode(unpadded).ok()
+    } else {
+        STANDARD_NO_PAD.decode(unpadded).ok()
+    }
+}
+
+fn decode_hex(literal: &str) -> Option<Vec<u8>> {
+    (0..literal.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&literal[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Keep decoded bytes only if they read as mostly printable UTF-8 text
+fn into_readable(bytes: Vec<u8>) -> Option<String> {
+    let text = String::from_utf8(bytes).ok()?;
+    let total = text.chars().count();
+    let printable = text
+        .chars()
+        .filter(|c| !c.is_control() || c.is_whitespace())
+        .count();
+
+    (total > 0 && printable * 10 >= total * 9).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_base64_and_hex_literals() {
+        let decoder = EncodedStringDecoder::new();
+        let content = r#"
+            var a = atob("aHR0cHM6Ly9hcGkuaGlkZGVuLmV4YW1wbGUuY29tL3YxL3VzZXJz");
+            var b = "7773733a2f2f72742e6578616d706c652e636f6d2f66656564";
+            var sha = "da39a3ee5e6b4b0d3255bfef95601890afd80709";
+        "#;
+
+        let decoded = decoder.decode_literals(content);
+        assert!(decoded.contains(&(
+            Encoding::Base64,
+            "https://api.hidden.example.com/v1/users".to_string()
+        )));
+        assert!(decoded.contains(&(Encoding::Hex, "wss://rt.example.com/feed".to_string())));
+        // Binary digests are not readable text and are dropped
+        assert_eq!(decoded.len(), 2);
+    }
+}
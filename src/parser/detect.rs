@@ -0,0 +1,169 @@
+use crate::parser::extractor::AssetKind;
+
+/// WebAssembly binary magic number
+const WASM_MAGIC: &[u8] = b"\0asm";
+
+/// How much of the content is looked at when sniffing
+pub const SNIFF_BYTES: usize = 4096;
+
+/// Work out what kind of asset this is
+///
+/// Binary WASM is recognized by its magic number first. After that a
+/// specific response `Content-Type` wins, then the extension of `location`
+/// (a URL or file path), then sniffing the start of the content. Anything
+/// still unknown is treated as JavaScript.
+pub fn detect_asset_kind(
+    location: Option<&str>,
+    content_type: Option<&str>,
+    content: &[u8],
+) -> AssetKind {
+    if content.starts_with(WASM_MAGIC) {
+        return AssetKind::Wasm;
+    }
+
+    let head = &content[..content.len().min(SNIFF_BYTES)];
+    let sniffed_sourcemap = looks_like_sourcemap(head);
+
+    if let Some(kind) = content_type.and_then(kind_from_content_type) {
+        // Sourcemaps are usually served as plain JSON
+        return match kind {
+            AssetKind::Json if sniffed_sourcemap => AssetKind::SourceMap,
+            kind => kind,
+        };
+    }
+
+    if let Some(kind) = location.and_then(kind_from_extension) {
+        return match kind {
+            AssetKind::Json if sniffed_sourcemap => AssetKind::SourceMap,
+            kind => kind,
+        };
+    }
+
+    sniff(head, sniffed_sourcemap)
+}
+
+/// Map a `Content-Type` header to a kind; generic types give `None`
+fn kind_from_content_type(content_type: &str) -> Option<AssetKind> {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_lowercase();
+
+    match mime.as_str() {
+        "application/wasm" => Some(AssetKind::Wasm),
+        "text/html" | "application/xhtml+xml" => Some(AssetKind::Html),
+        "text/css" => Some(AssetKind::Css),
+        "application/typescript" | "text/typescript" | "application/x-typescript" => {
+            Some(AssetKind::TypeScript)
+        }
+        m if m.contains("javascript") || m.contains("ecmascript") => Some(AssetKind::JavaScript),
+        "application/json" | "text/json" => Some(AssetKind::Json),
+        m if m.ends_with("+json") => Some(AssetKind::Json),
+        _ => None,
+    }
+}
+
+/// Check whether a URL or path has an extension of a parseable asset
+pub fn has_asset_extension(location: &str) -> bool {
+    kind_from_extension(location).is_some()
+}
+
+/// Map the extension of a URL or path to a kind
+fn kind_from_extension(location: &str) -> Option<AssetKind> {
+    let path = location.split(['?', '#']).next().unwrap_or(location);
+    let file = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    let (_, extension) = file.rsplit_once('.')?;
+
+    match extension.to_lowercase().as_str() {
+        "js" | "mjs" | "cjs" | "jsx" => Some(AssetKind::JavaScript),
+        "ts" | "tsx" | "mts" | "cts" => Some(AssetKind::TypeScript),
+        "html" | "htm" | "xhtml" => Some(AssetKind::Html),
+        "css" => Some(AssetKind::Css),
+        "json" | "webmanifest" => Some(AssetKind::Json),
+        "map" => Some(AssetKind::SourceMap),
+        "wasm" => Some(AssetKind::Wasm),
+        _ => None,
+    }
+}
+
+/// Guess the kind from the start of the content
+fn sniff(head: &[u8], sniffed_sourcemap: bool) -> AssetKind {
+    let text = String::from_utf8_lossy(head);
+    let trimmed = text.trim_start_matches('\u{feff}').trim_start();
+    let lower = trimmed
+        .get(..trimmed.len().min(64))
+        .unwrap_or("")
+        .to_lowercase();
+
+    if sniffed_sourcemap {
+        AssetKind::SourceMap
+    } else if ["<!doctype html", "<html", "<head", "<body"]
+        .iter()
+        .any(|tag| lower.starts_with(tag))
+    {
+        AssetKind::Html
+    } else if trimmed.starts_with('{') && trimmed[1..].trim_start().starts_with('"') {
+        AssetKind::Json
+    } else {
+        AssetKind::JavaScript
+    }
+}
+
+/// Whether the head of the content is a sourcemap's opening keys
+fn looks_like_sourcemap(head: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(head);
+    text.trim_start().starts_with('{')
+        && text.contains("\"version\"")
+        && (text.contains("\"mappings\"") || text.contains("\"sources\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_asset_kind() {
+        let js = b"fetch('/api/users');";
+        let map = br#"{"version":3,"sources":["src/app.ts"],"sourcesContent":["x"]}"#;
+
+        assert_eq!(
+            detect_asset_kind(Some("https://x.com/app.js?v=3"), None, js),
+            AssetKind::JavaScript
+        );
+        assert_eq!(
+            detect_asset_kind(Some("src/api.service.ts"), None, js),
+            AssetKind::TypeScript
+        );
+        // Content-Type wins over a misleading extension
+        assert_eq!(
+            detect_asset_kind(Some("/page.js"), Some("text/html; charset=utf-8"), b"<p>"),
+            AssetKind::Html
+        );
+        assert_eq!(
+            detect_asset_kind(Some("/app.js.map"), None, map),
+            AssetKind::SourceMap
+        );
+        assert_eq!(
+            detect_asset_kind(None, Some("application/json"), map),
+            AssetKind::SourceMap
+        );
+        assert_eq!(
+            detect_asset_kind(Some("/module"), None, b"\0asm\x01\0\0\0"),
+            AssetKind::Wasm
+        );
+        assert_eq!(
+            detect_asset_kind(None, None, b"\n<!DOCTYPE html><html>"),
+            AssetKind::Html
+        );
+        assert_eq!(
+            detect_asset_kind(None, None, br#"{ "routes": [] }"#),
+            AssetKind::Json
+        );
+        assert_eq!(
+            detect_asset_kind(Some("/styles/main.css"), Some("text/plain"), b"a{}"),
+            AssetKind::Css
+        );
+    }
+}
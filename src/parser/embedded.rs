@@ -0,0 +1,434 @@
+use crate::parser::extractor::{AssetContext, AssetKind, EndpointExtractor};
+use crate::types::{Confidence, Endpoint, EndpointType};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::debug;
+
+lazy_static! {
+    // JSON.parse('...') / JSON.parse("...") with a string literal argument
+    static ref JSON_PARSE_REGEX: Regex = Regex::new(
+        r#"JSON\.parse\(\s*(?:'((?:[^'\\]|\\.)*)'|"((?:[^"\\]|\\.)*)")\s*\)"#
+    ).unwrap();
+}
+
+/// Largest embedded blob we attempt to decode
+const MAX_BLOB_BYTES: usize = 2 * 1024 * 1024;
+
+/// Smallest blob worth decoding; anything shorter can't carry a useful config
+const MIN_BLOB_BYTES: usize = 16;
+
+/// HTTP methods recognised as OpenAPI operation keys
+const OPENAPI_METHODS: [&str; 8] = [
+    "get", "post", "put", "delete", "patch", "head", "options", "trace",
+];
+
+/// Firebase config keys that hold endpoint-like values
+const FIREBASE_URL_KEYS: [&str; 3] = ["databaseURL", "authDomain", "storageBucket"];
+
+/// Extensions of static assets that are not worth reporting from configs
+const STATIC_EXTENSIONS: [&str; 10] = [
+    ".js", ".css", ".png", ".jpg", ".jpeg", ".gif", ".svg", ".woff", ".woff2", ".ico",
+];
+
+/// Extractor for structured configs embedded in bundles or served as files
+///
+/// Recognises OpenAPI/Swagger specs, Firebase configs and generic JSON blobs
+/// such as route manifests or GraphQL codegen artifacts. Values recovered
+/// from decoded structures are reported with high confidence.
+pub struct EmbeddedConfigExtractor;
+
+impl EmbeddedConfigExtractor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract endpoints from JSON/YAML structures found in the content
+    pub fn extract(&self, content: &str, source: Option<&str>) -> Vec<Endpoint> {
+        let mut endpoints = Vec::new();
+
+        for value in self.find_documents(content) {
+            endpoints.extend(self.endpoints_from_value(&value));
+        }
+
+        endpoints
+            .into_iter()
+            .map(|ep| {
+                let ep = ep.with_confidence(Confidence::High);
+                match source {
+                    Some(src) => ep.with_source(src),
+                    None => ep,
+                }
+            })
+            .collect()
+    }
+
+    /// Locate and decode every structured document in the content
+    fn find_documents(&self, content: &str) -> Vec<Value> {
+        let trimmed = content.trim_start();
+
+        // The whole file is a JSON document
+        if trimmed.starts_with('{') || trimmed.starts_with('[') {
+            if let Ok(value) = serde_json::from_str::<Value>(trimmed) {
+                return vec![value];
+            }
+        }
+
+        // The whole file is a YAML API description
+        if trimmed.starts_with("openapi:") || trimmed.starts_with("swagger:") {
+            if let Ok(value) = serde_yaml::from_str::<Value>(trimmed) {
+                return vec![value];
+            }
+        }
+
+        let mut documents = Vec::new();
+        let mut literal_ranges = Vec::new();
+
+        // JSON.parse('...') string literals
+        for cap in JSON_PARSE_REGEX.captures_iter(content) {
+            let Some(literal) = cap.get(1).or_else(|| cap.get(2)) else {
+                continue;
+            };
+            literal_ranges.push(literal.range());
+            if literal.len() > MAX_BLOB_BYTES {
+                continue;
+            }
+            if let Ok(value) = serde_json::from_str::<Value>(&unescape_js(literal.as_str())) {
+                documents.push(value);
+            }
+        }
+
+        // Object literals written with quoted keys, i.e. inlined JSON
+        let bytes = content.as_bytes();
+        let mut pos = 0;
+        while let Some(offset) = content[pos..].find("{\"") {
+            let start = pos + offset;
+            if let Some(range) = literal_ranges.iter().find(|r| r.contains(&start)) {
+                pos = range.end;
+                continue;
+            }
+            match find_object_end(bytes, start) {
+                Some(end) if end - start >= MIN_BLOB_BYTES => {
+                    if let Ok(value) = serde_json::from_str::<Value>(&content[start..end]) {
+                        debug!("Decoded embedded JSON blob ({} bytes)", end - start);
+                        documents.push(value);
+                        pos = end;
+                        continue;
+                    }
+                    pos = start + 1;
+                }
+                _ => pos = start + 1,
+            }
+        }
+
+        documents
+    }
+
+    /// Turn a decoded document into endpoints
+    fn endpoints_from_value(&self, value: &Value) -> Vec<Endpoint> {
+        if let Some(endpoints) = self.openapi_endpoints(value) {
+            return endpoints;
+        }
+        if let Some(endpoints) = self.firebase_endpoints(value) {
+            return endpoints;
+        }
+
+        let mut endpoints = Vec::new();
+        collect_urls(value, &mut endpoints);
+        endpoints
+    }
+
+    /// Endpoints from an OpenAPI 3 or Swagger 2 document
+    fn openapi_endpoints(&self, value: &Value) -> Option<Vec<Endpoint>> {
+        let obj = value.as_object()?;
+        if !obj.contains_key("openapi") && !obj.contains_key("swagger") {
+            return None;
+        }
+        let paths = obj.get("paths")?.as_object()?;
+
+        let base = obj
+            .get("servers")
+            .and_then(|s| s.get(0))
+            .and_then(|s| s.get("url"))
+            .and_then(Value::as_str)
+            .or_else(|| obj.get("basePath").and_then(Value::as_str))
+            .unwrap_or("")
+            .trim_end_matches('/');
+
+        let mut endpoints = Vec::new();
+        for (path, item) in paths {
+            let Some(operations) = item.as_object() else {
+                continue;
+            };
+            let shared_params = parameter_names(item.get("parameters"));
+
+            for (method, operation) in operations {
+                if !OPENAPI_METHODS.contains(&method.as_str()) {
+                    continue;
+                }
+
+                let mut params = shared_params.clone();
+                for name in parameter_names(operation.get("parameters")) {
+                    if !params.contains(&name) {
+                        params.push(name);
+                    }
+                }
+
+                let url = format!("{}{}", base, path);
+                let mut endpoint = Endpoint::new(url.clone(), endpoint_type_for(&url))
+                    .with_method(method.to_uppercase())
+                    .with_metadata(embedded_metadata("openapi"));
+                if !params.is_empty() {
+                    endpoint = endpoint.with_params(params);
+                }
+                endpoints.push(endpoint);
+            }
+        }
+
+        Some(endpoints)
+    }
+
+    /// Endpoints from a Firebase web config
+    fn firebase_endpoints(&self, value: &Value) -> Option<Vec<Endpoint>> {
+        let obj = value.as_object()?;
+        if !obj.contains_key("apiKey") || !obj.contains_key("projectId") {
+            return None;
+        }
+
+        let endpoints = FIREBASE_URL_KEYS
+            .iter()
+            .filter_map(|key| obj.get(*key).and_then(Value::as_str))
+            .filter(|v| !v.is_empty())
+            .map(|v| {
+                let url = if v.contains("://") {
+                    v.to_string()
+                } else {
+                    format!("https://{}", v)
+                };
+                Endpoint::new(url, EndpointType::Rest).with_metadata(embedded_metadata("firebase"))
+            })
+            .collect();
+
+        Some(endpoints)
+    }
+}
+
+impl Default for EmbeddedConfigExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EndpointExtractor for EmbeddedConfigExtractor {
+    fn name(&self) -> &str {
+        "embedded"
+    }
+
+    fn supports(&self, kind: AssetKind) -> bool {
+        kind.is_script() || kind == AssetKind::Json
+    }
+
+    fn extract(&self, content: &str, ctx: &AssetContext) -> Vec<Endpoint> {
+        self.extract(content, ctx.source)
+    }
+}
+
+/// Walk a generic document and collect URL- or path-like strings
+fn collect_urls(value: &Value, endpoints: &mut Vec<Endpoint>) {
+    match value {
+        Value::String(s) if looks_like_endpoint(s) => {
+            endpoints.push(
+                Endpoint::new(s.clone(), endpoint_type_for(s))
+                    .with_metadata(embedded_metadata("json")),
+            );
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_urls(item, endpoints);
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map {
+                // Route manifests key their entries by path
+                if looks_like_endpoint(key) {
+                    endpoints.push(
+                        Endpoint::new(key.clone(), endpoint_type_for(key))
+                            .with_metadata(embedded_metadata("json")),
+                    );
+                }
+                collect_urls(item, endpoints);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Names of OpenAPI parameter objects
+fn parameter_names(params: Option<&Value>) -> Vec<String> {
+    params
+        .and_then(Value::as_array)
+        .map(|list| {
+            list.iter()
+                .filter_map(|p| p.get("name").and_then(Value::as_str))
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether a decoded string value is worth reporting
+fn looks_like_endpoint(s: &str) -> bool {
+    if s.len() < 2 || s.chars().any(char::is_whitespace) {
+        return false;
+    }
+
+    let lower = s.to_lowercase();
+    if STATIC_EXTENSIONS.iter().any(|ext| lower.ends_with(ext)) {
+        return false;
+    }
+
+    let is_url = ["http://", "https://", "ws://", "wss://"]
+        .iter()
+        .any(|scheme| lower.starts_with(scheme));
+    let is_path = s.starts_with('/')
+        && !s.starts_with("//")
+        && s[1..].chars().next().is_some_and(|c| c.is_alphanumeric());
+
+    is_url || is_path
+}
+
+fn endpoint_type_for(url: &str) -> EndpointType {
+    if url.contains("graphql") {
+        EndpointType::GraphQL
+    } else if url.starts_with("ws") {
+        EndpointType::WebSocket
+    } else {
+        EndpointType::Rest
+    }
+}
+
+fn embedded_metadata(kind: &str) -> HashMap<String, String> {
+    let mut metadata = HashMap::new();
+    metadata.insert("embedded".to_string(), kind.to_string());
+    metadata
+}
+
+/// Find the byte index just past the object starting at `start`
+fn find_object_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, &b) in bytes.iter().enumerate().skip(start) {
+        if i - start > MAX_BLOB_BYTES {
+            return None;
+        }
+
+        if in_string {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match b {
+            b'"' => in_string = true,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Undo JavaScript string literal escaping
+fn unescape_js(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(decoded) => out.push(decoded),
+                    None => {
+                        out.push_str("\\u");
+                        out.push_str(&hex);
+                    }
+                }
+            }
+            // Keep JSON escapes intact so the result still parses
+            Some('"') => out.push_str("\\\""),
+            Some('\\') => out.push_str("\\\\"),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_openapi_spec() {
+        let extractor = EmbeddedConfigExtractor::new();
+        let content = r#"
+            var spec = {"openapi":"3.0.0","servers":[{"url":"https://api.example.com/v2"}],
+              "paths":{"/users/{id}":{"parameters":[{"name":"id","in":"path"}],
+                "get":{"parameters":[{"name":"fields","in":"query"}]},"delete":{}}}};
+        "#;
+
+        let endpoints = extractor.extract(content, Some("bundle.js"));
+        assert_eq!(endpoints.len(), 2);
+
+        let get = endpoints
+            .iter()
+            .find(|e| e.method.as_deref() == Some("GET"))
+            .unwrap();
+        assert_eq!(get.url, "https://api.example.com/v2/users/{id}");
+        assert_eq!(
+            get.params,
+            Some(vec!["id".to_string(), "fields".to_string()])
+        );
+        assert_eq!(get.confidence, Some(Confidence::High));
+    }
+
+    #[test]
+    fn test_json_parse_literal_and_firebase() {
+        let extractor = EmbeddedConfigExtractor::new();
+        let content = r#"
+            const routes = JSON.parse('{"home":"/dashboard","api":"https://internal.example.com/rpc","logo":"/img/logo.png"}');
+            firebase.initializeApp({"apiKey":"AIza","projectId":"demo","authDomain":"demo.firebaseapp.com","databaseURL":"https://demo.firebaseio.com"});
+        "#;
+
+        let endpoints = extractor.extract(content, None);
+        let urls: Vec<&str> = endpoints.iter().map(|e| e.url.as_str()).collect();
+
+        assert!(urls.contains(&"/dashboard"));
+        assert!(urls.contains(&"https://internal.example.com/rpc"));
+        assert!(urls.contains(&"https://demo.firebaseapp.com"));
+        assert!(urls.contains(&"https://demo.firebaseio.com"));
+        assert!(!urls.contains(&"/img/logo.png"));
+    }
+}
@@ -0,0 +1,73 @@
+use crate::parser::comments::Comment;
+use crate::types::Endpoint;
+
+/// Kind of asset being parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetKind {
+    JavaScript,
+    TypeScript,
+    Html,
+    Css,
+    Json,
+    SourceMap,
+    Wasm,
+}
+
+impl AssetKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssetKind::JavaScript => "javascript",
+            AssetKind::TypeScript => "typescript",
+            AssetKind::Html => "html",
+            AssetKind::Css => "css",
+            AssetKind::Json => "json",
+            AssetKind::SourceMap => "sourcemap",
+            AssetKind::Wasm => "wasm",
+        }
+    }
+
+    /// Whether the asset carries executable script (HTML through inline scripts)
+    pub fn is_script(&self) -> bool {
+        matches!(
+            self,
+            AssetKind::JavaScript | AssetKind::TypeScript | AssetKind::Html
+        )
+    }
+}
+
+/// What extractors know about the asset they are run on
+#[derive(Debug, Clone, Copy)]
+pub struct AssetContext<'a> {
+    /// Where the asset came from (URL or file path)
+    pub source: Option<&'a str>,
+
+    /// Kind of asset
+    pub kind: AssetKind,
+
+    /// The asset with comments blanked out; same byte offsets as the raw content
+    pub code: &'a str,
+
+    /// Comments removed from `code`
+    pub comments: &'a [Comment],
+}
+
+/// A pass that pulls endpoints out of an asset
+///
+/// `Parser` runs every registered extractor over each asset, in
+/// registration order, then merges the results. Endpoints reported earlier
+/// win on type and first-seen fields when two extractors find the same URL
+/// and method, so specific extractors are registered before generic ones.
+///
+/// Downstream crates can add their own with `Parser::with_extractor`.
+pub trait EndpointExtractor: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &str;
+
+    /// Whether to run on assets of this kind; script-bearing assets by default
+    fn supports(&self, kind: AssetKind) -> bool {
+        kind.is_script()
+    }
+
+    /// Extract endpoints from the raw `content` of an asset
+    fn extract(&self, content: &str, ctx: &AssetContext) -> Vec<Endpoint>;
+}
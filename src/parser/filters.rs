@@ -1,9 +1,145 @@
+use crate::types::Endpoint;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+use url::Url;
+
+lazy_static! {
+    // application/json, text/html; charset=utf-8, image/svg+xml, ...
+    static ref MIME_TYPE: Regex = Regex::new(
+        r#"^(?:application|text|image|audio|video|font|multipart|message|model)/[\w.+-]+(?:\s*;.*)?$"#
+    ).unwrap();
+
+    // 1.2.3, v16.14.0, 2.0.0-beta.1
+    static ref SEMVER: Regex = Regex::new(r#"^/?v?\d+\.\d+\.\d+(?:[-+][\w.]+)?$"#).unwrap();
+
+    // en-US, pt_BR, /de-DE/
+    static ref LOCALE: Regex = Regex::new(r#"^/?[a-z]{2}[-_][A-Za-z]{2}/?$"#).unwrap();
+
+    // @scope/package paths and version-pinned package specifiers (react@18.2.0)
+    static ref NPM_PACKAGE: Regex =
+        Regex::new(r#"^/?(?:@[\w.-]+/[\w.-]+|(?:npm/)?[\w.-]+@\d+(?:\.\d+)*)(?:/|$)"#).unwrap();
+}
+
+/// Extensions of static assets that are never API endpoints
+const STATIC_EXTENSIONS: [&str; 7] = [".jpg", ".png", ".gif", ".css", ".woff", ".ttf", ".ico"];
+
+/// Extensions of static assets that are rarely interesting (aggressive mode)
+const AGGRESSIVE_STATIC_EXTENSIONS: [&str; 10] = [
+    ".jpeg", ".svg", ".webp", ".woff2", ".eot", ".otf", ".mp4", ".mp3", ".map", ".scss",
+];
+
+/// Markers of paths that only exist inside bundler/sourcemap internals
+const SOURCEMAP_INTERNAL_MARKERS: [&str; 7] = [
+    "webpack://",
+    "webpack/",
+    "(webpack)",
+    "turbopack://",
+    "rollup://",
+    "vite/",
+    "/node_modules/",
+];
+
+/// Heuristic filter that suppresses strings which look like URLs but aren't
+///
+/// The default rules reject MIME types, semver strings, bundler/sourcemap
+/// internal paths and static assets. Aggressive mode additionally rejects
+/// locale codes, npm package paths, protocol-relative matches without a host
+/// and other low-signal matches, at the cost of occasionally dropping a real
+/// endpoint.
+#[derive(Debug, Clone, Copy)]
+pub struct FalsePositiveFilter {
+    aggressive: bool,
+}
+
+impl FalsePositiveFilter {
+    pub fn new(aggressive: bool) -> Self {
+        Self { aggressive }
+    }
+
+    /// Whether aggressive rules are enabled
+    pub fn is_aggressive(&self) -> bool {
+        self.aggressive
+    }
+
+    /// Check whether a candidate URL is most likely a false positive
+    pub fn is_false_positive(&self, url: &str) -> bool {
+        if url.len() < 4 {
+            return true;
+        }
+
+        let lower = url.to_lowercase();
+
+        if STATIC_EXTENSIONS.iter().any(|ext| lower.ends_with(ext))
+            || MIME_TYPE.is_match(&lower)
+            || SEMVER.is_match(url)
+            || SOURCEMAP_INTERNAL_MARKERS
+                .iter()
+                .any(|marker| lower.contains(marker))
+        {
+            return true;
+        }
+
+        // Protocol-relative matches are mostly `//comments`; require a dotted host
+        if let Some(rest) = url.strip_prefix("//") {
+            let host = rest.split('/').next().unwrap_or("");
+            if !host.contains('.') {
+                return true;
+            }
+        }
+
+        if !self.aggressive {
+            return false;
+        }
+
+        let path = lower.split(['?', '#']).next().unwrap_or("");
+
+        AGGRESSIVE_STATIC_EXTENSIONS
+            .iter()
+            .any(|ext| path.ends_with(ext))
+            || LOCALE.is_match(url)
+            || NPM_PACKAGE.is_match(url)
+            // No alphanumeric content at all: "/./", "/-/", ...
+            || !url.chars().any(|c| c.is_ascii_alphanumeric())
+            // Template residue like "/${a}" or "/{{b}}" with nothing else
+            || url
+                .trim_start_matches('/')
+                .starts_with(['$', '{'])
+    }
+}
+
+impl Default for FalsePositiveFilter {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+/// How trailing slashes on paths are treated when canonicalizing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrailingSlashPolicy {
+    /// `/api/users/` and `/api/users` are the same endpoint
+    #[default]
+    Strip,
+    /// Trailing slashes are significant
+    Keep,
+}
+
 /// Filters for endpoint results
-pub struct EndpointFilter;
+pub struct EndpointFilter {
+    trailing_slash: TrailingSlashPolicy,
+}
 
 impl EndpointFilter {
     pub fn new() -> Self {
-        Self
+        Self {
+            trailing_slash: TrailingSlashPolicy::default(),
+        }
+    }
+
+    /// Set the trailing-slash policy
+    pub fn with_trailing_slash(mut self, policy: TrailingSlashPolicy) -> Self {
+        self.trailing_slash = policy;
+        self
     }
 
     /// Apply filter to endpoint URL
@@ -13,16 +149,290 @@ impl EndpointFilter {
         url.contains(pattern)
     }
 
-    /// Deduplicate endpoints
-    pub fn deduplicate(&self, endpoints: &mut Vec<crate::types::Endpoint>) {
-        use std::collections::HashSet;
-        let mut seen = HashSet::new();
-        endpoints.retain(|e| seen.insert(e.url.clone()));
+    /// Canonicalize a URL so equivalent spellings compare equal
+    ///
+    /// Lowercases scheme and host, drops default ports and fragments, sorts
+    /// query parameters by name and applies the trailing-slash policy.
+    /// Root-relative and dot-relative paths are resolved against `base` when
+    /// it is an HTTP(S) URL; anything that isn't URL- or path-shaped is
+    /// returned unchanged.
+    pub fn canonicalize(&self, url: &str, base: Option<&str>) -> String {
+        let base = base
+            .and_then(|b| Url::parse(b).ok())
+            .filter(|b| matches!(b.scheme(), "http" | "https"));
+
+        let resolved = if url.starts_with("//") {
+            url.to_string()
+        } else if url.starts_with('/') {
+            match &base {
+                Some(b) => format!("{}{}", b.origin().ascii_serialization(), url),
+                None => url.to_string(),
+            }
+        } else if url.starts_with("./") || url.starts_with("../") {
+            match base.as_ref().and_then(|b| b.join(url).ok()) {
+                Some(joined) => joined.to_string(),
+                None => return url.to_string(),
+            }
+        } else if split_scheme(url).is_some() {
+            url.to_string()
+        } else {
+            return url.to_string();
+        };
+
+        self.normalize(&resolved)
+    }
+
+    /// Canonicalize every endpoint URL against the asset it came from
+    pub fn canonicalize_all(&self, endpoints: &mut [Endpoint]) {
+        for ep in endpoints.iter_mut() {
+            ep.url = self.canonicalize(&ep.url, ep.source.as_deref());
+        }
+    }
+
+    /// Deduplicate endpoints by canonical URL and method
+    ///
+    /// Later duplicates are merged into the first occurrence (see
+    /// [`Endpoint::merge`]). A sighting without a method merges with one
+    /// that has a method, so `fetch("/api/x")` and `axios.get("/api/x")`
+    /// give one `GET` endpoint; only distinct known methods stay apart.
+    pub fn deduplicate(&self, endpoints: &mut Vec<Endpoint>) {
+        let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut unique: Vec<Endpoint> = Vec::with_capacity(endpoints.len());
+
+        for ep in endpoints.drain(..) {
+            let group = index.entry(self.dedup_key(&ep.url)).or_default();
+            let same = group
+                .iter()
+                .copied()
+                .find(|&i| unique[i].method == ep.method)
+                .or_else(|| {
+                    group
+                        .iter()
+                        .copied()
+                        .find(|&i| unique[i].method.is_none() || ep.method.is_none())
+                });
+            match same {
+                Some(i) => {
+                    if unique[i].method.is_none() {
+                        unique[i].method = ep.method.clone();
+                    }
+                    unique[i].merge(ep);
+                }
+                None => {
+                    group.push(unique.len());
+                    unique.push(ep);
+                }
+            }
+        }
+
+        *endpoints = unique;
+    }
+
+    /// Canonical URL with query values removed, so the same API called with
+    /// different sample values deduplicates
+    pub fn dedup_key(&self, url: &str) -> String {
+        let canonical = self.canonicalize(url, None);
+        match canonical.split_once('?') {
+            Some((path, query)) => {
+                let names: Vec<&str> = query
+                    .split('&')
+                    .map(|p| p.split('=').next().unwrap_or(""))
+                    .collect();
+                format!("{}?{}", path, names.join("&"))
+            }
+            None => canonical,
+        }
+    }
+
+    /// Normalize an absolute, protocol-relative or root-relative URL
+    fn normalize(&self, url: &str) -> String {
+        let (prefix, rest) = match split_scheme(url) {
+            Some((scheme, after)) => {
+                let (authority, rest) = split_authority(after);
+                let scheme = scheme.to_lowercase();
+                let authority = strip_default_port(&scheme, &authority.to_lowercase());
+                (format!("{}://{}", scheme, authority), rest)
+            }
+            None => match url.strip_prefix("//") {
+                Some(after) => {
+                    let (authority, rest) = split_authority(after);
+                    (format!("//{}", authority.to_lowercase()), rest)
+                }
+                None => (String::new(), url),
+            },
+        };
+
+        let rest = rest.split('#').next().unwrap_or("");
+        let (path, query) = match rest.split_once('?') {
+            Some((p, q)) => (p, Some(q)),
+            None => (rest, None),
+        };
+
+        let mut path = if path.is_empty() && !prefix.is_empty() {
+            "/".to_string()
+        } else {
+            path.to_string()
+        };
+        if self.trailing_slash == TrailingSlashPolicy::Strip && path.len() > 1 {
+            while path.len() > 1 && path.ends_with('/') {
+                path.pop();
+            }
+        }
+
+        let mut normalized = format!("{}{}", prefix, path);
+        if let Some(query) = query {
+            let mut pairs: Vec<&str> = query.split('&').filter(|p| !p.is_empty()).collect();
+            pairs.sort_by_key(|p| p.split('=').next().unwrap_or(""));
+            if !pairs.is_empty() {
+                normalized.push('?');
+                normalized.push_str(&pairs.join("&"));
+            }
+        }
+
+        normalized
     }
 }
 
+/// Split `scheme://rest` for the network schemes endpoints use
+fn split_scheme(url: &str) -> Option<(&str, &str)> {
+    let (scheme, rest) = url.split_once("://")?;
+    matches!(
+        scheme.to_lowercase().as_str(),
+        "http" | "https" | "ws" | "wss"
+    )
+    .then_some((scheme, rest))
+}
+
+/// Split `authority/path?query` into authority and the remainder
+fn split_authority(s: &str) -> (&str, &str) {
+    let end = s.find(['/', '?', '#']).unwrap_or(s.len());
+    s.split_at(end)
+}
+
+fn strip_default_port(scheme: &str, authority: &str) -> String {
+    let default = match scheme {
+        "http" | "ws" => ":80",
+        "https" | "wss" => ":443",
+        _ => return authority.to_string(),
+    };
+    authority
+        .strip_suffix(default)
+        .unwrap_or(authority)
+        .to_string()
+}
+
 impl Default for EndpointFilter {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Confidence, EndpointType};
+
+    #[test]
+    fn test_default_false_positives() {
+        let filter = FalsePositiveFilter::default();
+
+        assert!(filter.is_false_positive("application/json"));
+        assert!(filter.is_false_positive("text/html; charset=utf-8"));
+        assert!(filter.is_false_positive("/v16.14.0"));
+        assert!(filter.is_false_positive("webpack:///./src/api/client.js"));
+        assert!(filter.is_false_positive("/static/node_modules/lodash/index.js"));
+        assert!(filter.is_false_positive("//TODO"));
+        assert!(filter.is_false_positive("/img/logo.png"));
+
+        assert!(!filter.is_false_positive("/api/v1/users"));
+        assert!(!filter.is_false_positive("//cdn.example.com/api/config"));
+        assert!(!filter.is_false_positive("https://api.example.com/text/search"));
+        // Locale codes are only dropped in aggressive mode
+        assert!(!filter.is_false_positive("/en-US"));
+    }
+
+    #[test]
+    fn test_aggressive_false_positives() {
+        let filter = FalsePositiveFilter::new(true);
+
+        assert!(filter.is_false_positive("/en-US"));
+        assert!(filter.is_false_positive("@babel/runtime/helpers/esm"));
+        assert!(filter.is_false_positive("/npm/react@18.2.0/umd/react.js"));
+        assert!(filter.is_false_positive("/${path}"));
+        assert!(filter.is_false_positive("/icons/arrow.svg"));
+
+        assert!(!filter.is_false_positive("/api/v1/users"));
+        assert!(!filter.is_false_positive("/graphql"));
+        assert!(!filter.is_false_positive("/image/upload"));
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let filter = EndpointFilter::new();
+        let asset = Some("https://cdn.example.com/static/js/main.js");
+
+        assert_eq!(
+            filter.canonicalize("HTTPS://API.Example.com:443/Users/?b=2&a=1#top", None),
+            "https://api.example.com/Users?a=1&b=2"
+        );
+        assert_eq!(
+            filter.canonicalize("/api/users/", asset),
+            "https://cdn.example.com/api/users"
+        );
+        assert_eq!(
+            filter.canonicalize("../api/items", asset),
+            "https://cdn.example.com/static/api/items"
+        );
+        assert_eq!(
+            filter.canonicalize("/api/users/", Some("app.js")),
+            "/api/users"
+        );
+        assert_eq!(
+            filter.canonicalize("channel:orders", asset),
+            "channel:orders"
+        );
+
+        let keep = EndpointFilter::new().with_trailing_slash(TrailingSlashPolicy::Keep);
+        assert_eq!(keep.canonicalize("/api/users/", None), "/api/users/");
+    }
+
+    #[test]
+    fn test_deduplicate_merges_params() {
+        let filter = EndpointFilter::new();
+        let mut endpoints = vec![
+            Endpoint::new("/api/search?q=1".to_string(), EndpointType::Rest)
+                .with_params(vec!["q".to_string()]),
+            Endpoint::new("/api/search/?q=2".to_string(), EndpointType::Rest)
+                .with_params(vec!["q".to_string(), "page".to_string()])
+                .with_confidence(Confidence::High),
+            Endpoint::new("/api/search".to_string(), EndpointType::Rest).with_method("POST"),
+        ];
+
+        filter.deduplicate(&mut endpoints);
+
+        assert_eq!(endpoints.len(), 2);
+        assert_eq!(
+            endpoints[0].params,
+            Some(vec!["q".to_string(), "page".to_string()])
+        );
+        assert_eq!(endpoints[0].confidence, Some(Confidence::High));
+    }
+
+    #[test]
+    fn test_deduplicate_folds_unknown_methods() {
+        let filter = EndpointFilter::new();
+        let mut endpoints = vec![
+            Endpoint::new("/api/x".to_string(), EndpointType::Rest).with_tag("fetch"),
+            Endpoint::new("/api/x".to_string(), EndpointType::Rest).with_method("GET"),
+            Endpoint::new("/api/x".to_string(), EndpointType::Rest).with_method("POST"),
+            Endpoint::new("/api/x/".to_string(), EndpointType::Rest).with_tag("late"),
+        ];
+
+        filter.deduplicate(&mut endpoints);
+
+        let methods: Vec<Option<&str>> = endpoints.iter().map(|e| e.method.as_deref()).collect();
+        assert_eq!(methods, [Some("GET"), Some("POST")]);
+        assert!(endpoints[0].has_tag("fetch") && endpoints[0].has_tag("late"));
+        assert!(!endpoints[1].has_tag("fetch"));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::parser::extractor::{AssetContext, EndpointExtractor};
+use crate::types::{Confidence, Endpoint, EndpointType};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+
+lazy_static! {
+    // this.http.get<User[]>('/api/users') / this.httpClient.request<T>('PATCH', url)
+    static ref ANGULAR_CALL: Regex = Regex::new(
+        r#"\b(?:this\.)?_?(?:http|httpClient)\.(get|post|put|patch|delete|head|options|jsonp|request)\s*(?:<[^()'"`;]{0,200}>)?\(\s*(?:['"`]([A-Za-z]+)['"`]\s*,\s*)?['"`]([^'"`]+)['"`]"#
+    ).unwrap();
+
+    // $.getJSON('/x') / jQuery.post("/x", data)
+    static ref JQUERY_SHORTHAND: Regex = Regex::new(
+        r#"(?:\$|\bjQuery)\.(getJSON|get|post)\(\s*['"`]([^'"`]+)['"`]"#
+    ).unwrap();
+
+    // $.ajax({ url: '/x', type: 'POST' })
+    static ref JQUERY_AJAX: Regex =
+        Regex::new(r#"(?:\$|\bjQuery)\.ajax\(\s*\{([^}]*)\}"#).unwrap();
+    static ref AJAX_URL: Regex = Regex::new(r#"\burl\s*:\s*['"`]([^'"`]+)['"`]"#).unwrap();
+    static ref AJAX_METHOD: Regex =
+        Regex::new(r#"\b(?:type|method)\s*:\s*['"`]([A-Za-z]+)['"`]"#).unwrap();
+
+    // superagent.get('/x') / request.del('/x') / superagent('GET', '/x')
+    static ref SUPERAGENT_CALL: Regex = Regex::new(
+        r#"\b(superagent|request|agent)\.(get|post|put|patch|del|delete|head|options)\(\s*['"`]([^'"`]+)['"`]"#
+    ).unwrap();
+    static ref SUPERAGENT_DIRECT: Regex = Regex::new(
+        r#"\bsuperagent\(\s*['"`]([A-Za-z]+)['"`]\s*,\s*['"`]([^'"`]+)['"`]"#
+    ).unwrap();
+
+    // const api = ky.extend({ prefixUrl: 'https://api.example.com' })
+    static ref KY_INSTANCE: Regex = Regex::new(
+        r#"\b(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*=\s*ky\.(?:extend|create)\(\s*\{[^}]*?\bprefixUrl\s*:\s*['"`]([^'"`]+)['"`]"#
+    ).unwrap();
+
+    // receiver.get('users') style calls, resolved against ky instances
+    static ref METHOD_CALL: Regex = Regex::new(
+        r#"\b([A-Za-z_$][\w$]*)\.(get|post|put|patch|delete|head)\(\s*['"`]([^'"`]+)['"`]"#
+    ).unwrap();
+}
+
+/// Extractor for HTTP client libraries beyond fetch/axios
+///
+/// Understands Angular `HttpClient` (including generic type arguments),
+/// jQuery shorthands and `$.ajax`, superagent, and `ky` instances created
+/// with a `prefixUrl`. The method comes from the call form, so these
+/// endpoints are reported with high confidence.
+pub struct FrameworkExtractor;
+
+impl FrameworkExtractor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract endpoints from known HTTP client call forms
+    pub fn extract(&self, content: &str, source: Option<&str>) -> Vec<Endpoint> {
+        let mut endpoints = Vec::new();
+        let mut push = |url: &str, method: &str, client: &str| {
+            endpoints.push(self.endpoint(url, method, client, source));
+        };
+
+        for cap in ANGULAR_CALL.captures_iter(content) {
+            let method = match (&cap[1], cap.get(2)) {
+                ("request", Some(m)) => m.as_str(),
+                ("request", None) => continue,
+                ("jsonp", _) => "GET",
+                (m, _) => m,
+            };
+            push(&cap[3], method, "angular");
+        }
+
+        for cap in JQUERY_SHORTHAND.captures_iter(content) {
+            let method = if &cap[1] == "post" { "POST" } else { "GET" };
+            push(&cap[2], method, "jquery");
+        }
+
+        for cap in JQUERY_AJAX.captures_iter(content) {
+            let Some(url) = AJAX_URL.captures(&cap[1]) else {
+                continue;
+            };
+            let method = AJAX_METHOD
+                .captures(&cap[1])
+                .map(|m| m[1].to_string())
+                .unwrap_or_else(|| "GET".to_string());
+            push(&url[1], &method, "jquery");
+        }
+
+        // `request` and `agent` are only superagent when the library is present
+        let has_superagent = content.contains("superagent");
+        for cap in SUPERAGENT_CALL.captures_iter(content) {
+            if &cap[1] != "superagent" && !has_superagent {
+                continue;
+            }
+            let method = if &cap[2] == "del" { "delete" } else { &cap[2] };
+            push(&cap[3], method, "superagent");
+        }
+        for cap in SUPERAGENT_DIRECT.captures_iter(content) {
+            push(&cap[2], &cap[1], "superagent");
+        }
+
+        let ky_instances: HashMap<String, String> = KY_INSTANCE
+            .captures_iter(content)
+            .map(|cap| (cap[1].to_string(), cap[2].to_string()))
+            .collect();
+        for prefix in ky_instances.values() {
+            let mut endpoint = Endpoint::new(prefix.clone(), EndpointType::Rest)
+                .with_confidence(Confidence::High)
+                .with_metadata(client_metadata("ky"));
+            if let Some(src) = source {
+                endpoint = endpoint.with_source(src);
+            }
+            endpoints.push(endpoint);
+        }
+        for cap in METHOD_CALL.captures_iter(content) {
+            let receiver = &cap[1];
+            let path = &cap[3];
+            if receiver == "ky" && is_url_like(path) {
+                endpoints.push(self.endpoint(path, &cap[2], "ky", source));
+            } else if let Some(prefix) = ky_instances.get(receiver) {
+                let url = join_prefix(prefix, path);
+                endpoints.push(self.endpoint(&url, &cap[2], "ky", source));
+            }
+        }
+
+        endpoints.retain(|e| is_url_like(&e.url));
+        endpoints
+    }
+
+    fn endpoint(&self, url: &str, method: &str, client: &str, source: Option<&str>) -> Endpoint {
+        let endpoint_type = if url.contains("graphql") {
+            EndpointType::GraphQL
+        } else {
+            EndpointType::Rest
+        };
+
+        let mut endpoint = Endpoint::new(url.to_string(), endpoint_type)
+            .with_method(method.to_uppercase())
+            .with_confidence(Confidence::High)
+            .with_metadata(client_metadata(client));
+        if let Some(src) = source {
+            endpoint = endpoint.with_source(src);
+        }
+        endpoint
+    }
+}
+
+impl Default for FrameworkExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EndpointExtractor for FrameworkExtractor {
+    fn name(&self) -> &str {
+        "frameworks"
+    }
+
+    fn extract(&self, _content: &str, ctx: &AssetContext) -> Vec<Endpoint> {
+        self.extract(ctx.code, ctx.source)
+    }
+}
+
+fn client_metadata(client: &str) -> HashMap<String, String> {
+    let mut metadata = HashMap::new();
+    metadata.insert("client".to_string(), client.to_string());
+    metadata
+}
+
+/// Absolute URLs and root-relative paths; bare words like `'id'` are not endpoints
+pub(crate) fn is_url_like(url: &str) -> bool {
+    url.starts_with('/') || url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// ky resolves instance paths against `prefixUrl` with exactly one slash between
+fn join_prefix(prefix: &str, path: &str) -> String {
+    format!(
+        "{}/{}",
+        prefix.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn methods_by_url(endpoints: &[Endpoint]) -> Vec<(String, String)> {
+        endpoints
+            .iter()
+            .map(|e| (e.url.clone(), e.method.clone().unwrap_or_default()))
+            .collect()
+    }
+
+    #[test]
+    fn test_angular_and_jquery_calls() {
+        let extractor = FrameworkExtractor::new();
+        let content = r#"
+            this.http.get<Array<User>>('/api/users').subscribe();
+            this.http.post<{ id: number }>("/api/users", body);
+            this.httpClient.request<void>('PATCH', '/api/users/1');
+            $.getJSON("/legacy/feed.json", render);
+            jQuery.post('/legacy/save', form);
+            $.ajax({ url: "/legacy/remove", type: "DELETE" });
+            $.get("id");
+        "#;
+
+        let found = methods_by_url(&extractor.extract(content, None));
+        for expected in [
+            ("/api/users", "GET"),
+            ("/api/users", "POST"),
+            ("/api/users/1", "PATCH"),
+            ("/legacy/feed.json", "GET"),
+            ("/legacy/save", "POST"),
+            ("/legacy/remove", "DELETE"),
+        ] {
+            assert!(
+                found.contains(&(expected.0.to_string(), expected.1.to_string())),
+                "missing {:?}",
+                expected
+            );
+        }
+        assert_eq!(found.len(), 6);
+    }
+
+    #[test]
+    fn test_superagent_and_ky_calls() {
+        let extractor = FrameworkExtractor::new();
+        let content = r#"
+            import request from "superagent";
+            request.del("/api/sessions/current").end();
+            superagent("PUT", "/api/profile");
+            const api = ky.extend({ prefixUrl: "https://api.example.com/v2/" });
+            api.get("orders").json();
+            ky.post("/api/track", { json: event });
+        "#;
+
+        let endpoints = extractor.extract(content, Some("bundle.js"));
+        let found = methods_by_url(&endpoints);
+        for expected in [
+            ("/api/sessions/current", "DELETE"),
+            ("/api/profile", "PUT"),
+            ("https://api.example.com/v2/orders", "GET"),
+            ("/api/track", "POST"),
+        ] {
+            assert!(
+                found.contains(&(expected.0.to_string(), expected.1.to_string())),
+                "missing {:?}",
+                expected
+            );
+        }
+        assert!(endpoints
+            .iter()
+            .any(|e| e.url == "https://api.example.com/v2/" && e.method.is_none()));
+        assert_eq!(
+            endpoints[0].metadata.as_ref().unwrap()["client"],
+            "superagent"
+        );
+    }
+}
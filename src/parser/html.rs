@@ -0,0 +1,270 @@
+use crate::parser::extractor::{AssetContext, AssetKind, EndpointExtractor};
+use crate::types::{Confidence, Endpoint, EndpointType};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+
+lazy_static! {
+    // <form ...> ... </form>
+    static ref FORM_REGEX: Regex = Regex::new(r#"(?is)<form\b([^>]*)>(.*?)</form\s*>"#).unwrap();
+
+    // Form controls carrying a name attribute
+    static ref INPUT_REGEX: Regex =
+        Regex::new(r#"(?is)<(?:input|select|textarea|button)\b([^>]*)>"#).unwrap();
+
+    // Any opening tag, used for htmx and data-* attributes
+    static ref TAG_REGEX: Regex = Regex::new(r#"(?s)<([a-zA-Z][a-zA-Z0-9-]*)\b([^>]*)>"#).unwrap();
+
+    // name="value", name='value' or name=value
+    static ref ATTR_REGEX: Regex = Regex::new(
+        r#"([a-zA-Z_:][-a-zA-Z0-9_:.]*)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'=<>`]+))"#
+    ).unwrap();
+
+    // <meta http-equiv="refresh" content="0; url=/next">
+    static ref META_REFRESH_URL: Regex = Regex::new(r#"(?i)url\s*=\s*['"]?([^'"\s;]+)"#).unwrap();
+}
+
+/// htmx request attributes and the HTTP method they imply
+const HTMX_ATTRIBUTES: [(&str, &str); 5] = [
+    ("hx-get", "GET"),
+    ("hx-post", "POST"),
+    ("hx-put", "PUT"),
+    ("hx-patch", "PATCH"),
+    ("hx-delete", "DELETE"),
+];
+
+/// Data attributes commonly used to carry endpoint URLs
+const DATA_ATTRIBUTES: [&str; 4] = ["data-url", "data-endpoint", "data-href", "data-action"];
+
+/// Extractor for endpoints declared in HTML markup
+///
+/// Picks up form actions (with their method and input names), htmx request
+/// attributes, `data-url`/`data-endpoint` style attributes and meta refresh
+/// targets.
+pub struct HtmlExtractor;
+
+impl HtmlExtractor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract endpoints from HTML content
+    pub fn extract(&self, content: &str, source: Option<&str>) -> Vec<Endpoint> {
+        let mut endpoints = Vec::new();
+
+        endpoints.extend(self.extract_forms(content));
+        endpoints.extend(self.extract_attributes(content));
+
+        endpoints
+            .into_iter()
+            .map(|ep| {
+                // Markup declares these explicitly, so they are not guesses
+                let ep = ep.with_confidence(Confidence::High);
+                match source {
+                    Some(src) => ep.with_source(src),
+                    None => ep,
+                }
+            })
+            .collect()
+    }
+
+    /// Extract `<form>` actions together with method and input names
+    fn extract_forms(&self, content: &str) -> Vec<Endpoint> {
+        let mut endpoints = Vec::new();
+
+        for cap in FORM_REGEX.captures_iter(content) {
+            let attrs = parse_attributes(&cap[1]);
+            let Some(action) = attrs.get("action").filter(|a| is_endpoint_value(a)) else {
+                continue;
+            };
+
+            let method = attrs
+                .get("method")
+                .map(|m| m.to_uppercase())
+                .unwrap_or_else(|| "GET".to_string());
+
+            let mut params: Vec<String> = Vec::new();
+            for input in INPUT_REGEX.captures_iter(&cap[2]) {
+                if let Some(name) = parse_attributes(&input[1]).remove("name") {
+                    if !name.is_empty() && !params.contains(&name) {
+                        params.push(name);
+                    }
+                }
+            }
+
+            let mut endpoint = Endpoint::new(action.clone(), EndpointType::Rest)
+                .with_method(method)
+                .with_metadata(element_metadata("form"));
+
+            if !params.is_empty() {
+                endpoint = endpoint.with_params(params);
+            }
+
+            endpoints.push(endpoint);
+        }
+
+        endpoints
+    }
+
+    /// Extract htmx, data-* and meta refresh targets from any tag
+    fn extract_attributes(&self, content: &str) -> Vec<Endpoint> {
+        let mut endpoints = Vec::new();
+
+        for cap in TAG_REGEX.captures_iter(content) {
+            let tag = cap[1].to_lowercase();
+            let attrs = parse_attributes(&cap[2]);
+
+            for (attr, method) in HTMX_ATTRIBUTES {
+                if let Some(url) = attrs.get(attr).filter(|u| is_endpoint_value(u)) {
+                    let mut endpoint = Endpoint::new(url.clone(), EndpointType::Rest)
+                        .with_method(method)
+                        .with_metadata(element_metadata("htmx"));
+
+                    if let Some(name) = attrs.get("name").filter(|n| !n.is_empty()) {
+                        endpoint = endpoint.with_params(vec![name.clone()]);
+                    }
+
+                    endpoints.push(endpoint);
+                }
+            }
+
+            for attr in DATA_ATTRIBUTES {
+                if let Some(url) = attrs.get(attr).filter(|u| is_endpoint_value(u)) {
+                    endpoints.push(
+                        Endpoint::new(url.clone(), EndpointType::Rest)
+                            .with_metadata(element_metadata("data-attribute")),
+                    );
+                }
+            }
+
+            if tag == "meta"
+                && attrs
+                    .get("http-equiv")
+                    .is_some_and(|v| v.eq_ignore_ascii_case("refresh"))
+            {
+                if let Some(url) = attrs
+                    .get("content")
+                    .and_then(|c| META_REFRESH_URL.captures(c))
+                    .map(|c| c[1].to_string())
+                {
+                    endpoints.push(
+                        Endpoint::new(url, EndpointType::Rest)
+                            .with_method("GET")
+                            .with_metadata(element_metadata("meta-refresh")),
+                    );
+                }
+            }
+        }
+
+        endpoints
+    }
+}
+
+impl Default for HtmlExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EndpointExtractor for HtmlExtractor {
+    fn name(&self) -> &str {
+        "html"
+    }
+
+    fn supports(&self, kind: AssetKind) -> bool {
+        kind == AssetKind::Html
+    }
+
+    fn extract(&self, _content: &str, ctx: &AssetContext) -> Vec<Endpoint> {
+        self.extract(ctx.code, ctx.source)
+    }
+}
+
+/// Parse the attribute section of a tag into a lowercase-keyed map
+fn parse_attributes(raw: &str) -> HashMap<String, String> {
+    ATTR_REGEX
+        .captures_iter(raw)
+        .map(|cap| {
+            let value = cap
+                .get(2)
+                .or_else(|| cap.get(3))
+                .or_else(|| cap.get(4))
+                .map(|m| m.as_str())
+                .unwrap_or("");
+            (cap[1].to_lowercase(), decode_entities(value.trim()))
+        })
+        .collect()
+}
+
+/// Decode the handful of HTML entities that commonly appear in URLs
+fn decode_entities(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Skip empty values, fragments and javascript: pseudo-URLs
+fn is_endpoint_value(value: &str) -> bool {
+    let lower = value.to_lowercase();
+    !value.is_empty()
+        && !value.starts_with('#')
+        && !lower.starts_with("javascript:")
+        && !lower.starts_with("mailto:")
+}
+
+fn element_metadata(element: &str) -> HashMap<String, String> {
+    let mut metadata = HashMap::new();
+    metadata.insert("element".to_string(), element.to_string());
+    metadata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_form_extraction() {
+        let extractor = HtmlExtractor::new();
+        let html = r#"
+            <form action="/account/login?next=/home&amp;x=1" method="post">
+                <input type="text" name="username">
+                <input type='password' name='password'>
+                <button type="submit">Login</button>
+            </form>
+        "#;
+
+        let endpoints = extractor.extract(html, Some("index.html"));
+        assert_eq!(endpoints.len(), 1);
+        assert_eq!(endpoints[0].url, "/account/login?next=/home&x=1");
+        assert_eq!(endpoints[0].method.as_deref(), Some("POST"));
+        assert_eq!(
+            endpoints[0].params,
+            Some(vec!["username".to_string(), "password".to_string()])
+        );
+        assert_eq!(endpoints[0].source.as_deref(), Some("index.html"));
+    }
+
+    #[test]
+    fn test_htmx_data_and_meta_refresh() {
+        let extractor = HtmlExtractor::new();
+        let html = r##"
+            <meta http-equiv="refresh" content="5; url=/maintenance">
+            <button hx-delete="/api/items/1">Delete</button>
+            <div data-endpoint="/api/widgets" data-url="#"></div>
+            <a href="javascript:void(0)" hx-get="javascript:void(0)">noop</a>
+        "##;
+
+        let endpoints = extractor.extract(html, None);
+        let urls: Vec<&str> = endpoints.iter().map(|e| e.url.as_str()).collect();
+
+        assert_eq!(urls.len(), 3);
+        assert!(urls.contains(&"/maintenance"));
+        assert!(urls.contains(&"/api/widgets"));
+        assert!(endpoints
+            .iter()
+            .any(|e| e.url == "/api/items/1" && e.method.as_deref() == Some("DELETE")));
+    }
+}
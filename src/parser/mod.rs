@@ -1,89 +1,415 @@
+pub mod comments;
+pub mod decode;
+pub mod detect;
+pub mod embedded;
+pub mod extractor;
 pub mod filters;
+pub mod findings;
+pub mod frameworks;
+pub mod html;
 pub mod js_parser;
+pub mod params;
 pub mod patterns;
 pub mod sourcemap;
+pub mod sse;
+pub mod versions;
+pub mod websocket;
+pub mod window;
+pub mod wrappers;
 
 use crate::error::Result;
-use crate::types::Endpoint;
-use js_parser::JsParser;
-use patterns::PatternMatcher;
+use crate::types::{Endpoint, EndpointType, Finding};
+use aho_corasick::AhoCorasick;
+use comments::{CommentExtractor, CommentScanner};
+use decode::EncodedStringExtractor;
+use embedded::EmbeddedConfigExtractor;
+use extractor::{AssetContext, AssetKind, EndpointExtractor};
+use filters::FalsePositiveFilter;
+use findings::FindingsScanner;
+use frameworks::FrameworkExtractor;
+use html::HtmlExtractor;
+use params::BodyParamExtractor;
+use patterns::{CustomPattern, PatternExtractor, PatternMatcher};
+use sourcemap::SourceMapExtractor;
+use sse::{floor_char_boundary, SseExtractor};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Arc;
 use tracing::{debug, info};
+use websocket::WebSocketExtractor;
+use window::WindowReader;
+
+/// Endpoints and findings extracted from one asset
+#[derive(Debug, Clone, Default)]
+pub struct ParsedAsset {
+    pub endpoints: Vec<Endpoint>,
+    pub findings: Vec<Finding>,
+}
 
 /// Main parser for extracting endpoints from web assets
+///
+/// A registry of `EndpointExtractor`s run over every asset in order. The
+/// built-in ones cover markup, embedded configs, socket and SSE clients,
+/// HTTP client libraries, request parameters, the URL/API patterns,
+/// encoded literals, and comments; more can be appended with `with_extractor`.
 pub struct Parser {
-    _js_parser: JsParser,
-    pattern_matcher: PatternMatcher,
+    extractors: Vec<Box<dyn EndpointExtractor>>,
+    comment_scanner: CommentScanner,
+    sourcemap_extractor: SourceMapExtractor,
+    findings_scanner: FindingsScanner,
+    false_positive_filter: FalsePositiveFilter,
 }
 
 impl Parser {
     /// Create a new parser
     pub fn new() -> Self {
+        Self::with_pattern_matcher(PatternMatcher::new())
+    }
+
+    /// Create a parser that also applies user-supplied patterns
+    pub fn with_custom_patterns(patterns: Vec<CustomPattern>) -> Self {
+        Self::with_pattern_matcher(PatternMatcher::with_custom_patterns(patterns))
+    }
+
+    /// Create a parser with the built-in extractors around the given matcher
+    fn with_pattern_matcher(pattern_matcher: PatternMatcher) -> Self {
+        let pattern_matcher = Arc::new(pattern_matcher);
+
+        // Specific extractors first: their type and method win when merging
+        let extractors: Vec<Box<dyn EndpointExtractor>> = vec![
+            Box::new(HtmlExtractor::new()),
+            Box::new(EmbeddedConfigExtractor::new()),
+            Box::new(WebSocketExtractor::new()),
+            Box::new(SseExtractor::new()),
+            Box::new(FrameworkExtractor::new()),
+            Box::new(BodyParamExtractor::new()),
+            Box::new(PatternExtractor::new(pattern_matcher.clone())),
+            Box::new(EncodedStringExtractor::new(pattern_matcher.clone())),
+            Box::new(CommentExtractor::new(pattern_matcher)),
+        ];
+
         Self {
-            _js_parser: JsParser::new(),
-            pattern_matcher: PatternMatcher::new(),
+            extractors,
+            comment_scanner: CommentScanner::new(),
+            sourcemap_extractor: SourceMapExtractor::new(),
+            findings_scanner: FindingsScanner::new(),
+            false_positive_filter: FalsePositiveFilter::default(),
         }
     }
 
+    /// Register an additional extractor, run after the existing ones
+    pub fn with_extractor(mut self, extractor: impl EndpointExtractor + 'static) -> Self {
+        self.extractors.push(Box::new(extractor));
+        self
+    }
+
+    /// Register an already boxed extractor
+    pub(crate) fn with_boxed_extractor(mut self, extractor: Box<dyn EndpointExtractor>) -> Self {
+        self.extractors.push(extractor);
+        self
+    }
+
+    /// Names of the registered extractors, in run order
+    pub fn extractor_names(&self) -> Vec<&str> {
+        self.extractors.iter().map(|e| e.name()).collect()
+    }
+
+    /// Enable/disable aggressive false-positive suppression
+    pub fn with_aggressive_filter(mut self, aggressive: bool) -> Self {
+        self.false_positive_filter = FalsePositiveFilter::new(aggressive);
+        self
+    }
+
+    /// Parse an asset of the given kind and extract endpoints
+    ///
+    /// Use `detect::detect_asset_kind` to pick the kind from the asset's
+    /// location, content type, and content.
+    pub fn parse(
+        &self,
+        content: &str,
+        kind: AssetKind,
+        source: Option<&str>,
+    ) -> Result<Vec<Endpoint>> {
+        info!("Parsing {} ({} bytes)", kind.as_str(), content.len());
+
+        let endpoints = match kind {
+            AssetKind::SourceMap => self.extract_sourcemap(content, source),
+            kind => self.extract_asset(content, kind, source),
+        };
+        let final_endpoints = self.dedup(endpoints);
+
+        debug!("Found {} unique endpoints", final_endpoints.len());
+        Ok(final_endpoints)
+    }
+
+    /// Parse raw bytes of an asset, as fetched or read from disk
+    pub fn parse_bytes(
+        &self,
+        bytes: &[u8],
+        kind: AssetKind,
+        source: Option<&str>,
+    ) -> Result<Vec<Endpoint>> {
+        self.parse(&String::from_utf8_lossy(bytes), kind, source)
+    }
+
+    /// Parse an asset and also scan it for leaked internal infrastructure
+    ///
+    /// Findings (environment variables, internal hosts, private IPs) are
+    /// kept apart from endpoints; see `findings::FindingsScanner`.
+    pub fn analyze(
+        &self,
+        content: &str,
+        kind: AssetKind,
+        source: Option<&str>,
+    ) -> Result<ParsedAsset> {
+        Ok(ParsedAsset {
+            endpoints: self.parse(content, kind, source)?,
+            findings: self.scan_findings(content, kind, source),
+        })
+    }
+
+    /// Analyze raw bytes of an asset, as fetched or read from disk
+    pub fn analyze_bytes(
+        &self,
+        bytes: &[u8],
+        kind: AssetKind,
+        source: Option<&str>,
+    ) -> Result<ParsedAsset> {
+        self.analyze(&String::from_utf8_lossy(bytes), kind, source)
+    }
+
     /// Parse JavaScript content and extract endpoints
     pub fn parse_js(&self, content: &str, source: Option<&str>) -> Result<Vec<Endpoint>> {
-        info!("Parsing JavaScript ({} bytes)", content.len());
+        self.parse(content, AssetKind::JavaScript, source)
+    }
 
-        let mut endpoints = Vec::new();
+    /// Parse an HTML document and extract endpoints
+    ///
+    /// Markup-level endpoints (forms, htmx and data attributes, meta refresh)
+    /// are extracted first, then the document is run through the JavaScript
+    /// patterns to pick up inline scripts.
+    pub fn parse_html(&self, content: &str, source: Option<&str>) -> Result<Vec<Endpoint>> {
+        self.parse(content, AssetKind::Html, source)
+    }
+
+    /// Parse an asset from a reader in overlapping windows of `window_bytes`
+    ///
+    /// Memory use is bounded by the window size rather than the input size.
+    /// Line numbers are reported relative to the whole input. Sourcemaps are
+    /// scanned as escaped script text since they can't be decoded piecewise.
+    pub fn parse_reader<R: Read>(
+        &self,
+        reader: R,
+        kind: AssetKind,
+        source: Option<&str>,
+        window_bytes: usize,
+    ) -> Result<Vec<Endpoint>> {
+        Ok(self
+            .read_windows(reader, kind, source, window_bytes, false)?
+            .endpoints)
+    }
+
+    /// Analyze an asset from a reader in overlapping windows of `window_bytes`
+    pub fn analyze_reader<R: Read>(
+        &self,
+        reader: R,
+        kind: AssetKind,
+        source: Option<&str>,
+        window_bytes: usize,
+    ) -> Result<ParsedAsset> {
+        self.read_windows(reader, kind, source, window_bytes, true)
+    }
+
+    /// Run the extractors, and optionally the findings scan, window by window
+    fn read_windows<R: Read>(
+        &self,
+        reader: R,
+        kind: AssetKind,
+        source: Option<&str>,
+        window_bytes: usize,
+        with_findings: bool,
+    ) -> Result<ParsedAsset> {
+        let window_kind = match kind {
+            AssetKind::SourceMap => AssetKind::JavaScript,
+            kind => kind,
+        };
 
-        // 1. Regex-based extraction using PatternMatcher
-        let urls = self.pattern_matcher.find_urls(content);
+        let mut windows = WindowReader::new(reader, window_bytes);
+        let mut endpoints = Vec::new();
+        let mut findings: Vec<Finding> = Vec::new();
+        let mut count = 0;
 
-        for url in urls {
-            // Use PatternMatcher's type detection
-            let endpoint_type = self.pattern_matcher.detect_endpoint_type(&url, content);
-            let mut endpoint = Endpoint::new(url.clone(), endpoint_type);
+        while let Some(window) = windows.next_window()? {
+            count += 1;
+            let found = self.extract_asset(&window.text, window_kind, source);
+            endpoints.extend(found.into_iter().map(|mut ep| {
+                ep.line = ep.line.map(|line| line + window.line_offset);
+                ep
+            }));
 
-            if let Some(src) = source {
-                endpoint = endpoint.with_source(src);
+            if with_findings {
+                for mut finding in self.scan_findings(&window.text, window_kind, source) {
+                    if findings
+                        .iter()
+                        .any(|f| f.kind == finding.kind && f.value == finding.value)
+                    {
+                        continue;
+                    }
+                    finding.line = finding.line.map(|line| line + window.line_offset);
+                    findings.push(finding);
+                }
             }
+        }
+
+        // Matches in the overlap between windows are found twice
+        let final_endpoints = self.dedup(endpoints);
 
-            // Extract query parameters
-            if let Some(params) = self.extract_params(&url) {
-                endpoint = endpoint.with_params(params);
+        debug!(
+            "Found {} unique endpoints across {} windows",
+            final_endpoints.len(),
+            count
+        );
+        Ok(ParsedAsset {
+            endpoints: final_endpoints,
+            findings,
+        })
+    }
+
+    /// Scan an asset for findings, looking inside sourcemaps and WASM strings
+    fn scan_findings(&self, content: &str, kind: AssetKind, source: Option<&str>) -> Vec<Finding> {
+        match kind {
+            AssetKind::Wasm => self
+                .findings_scanner
+                .scan(&printable_strings(content), source),
+            AssetKind::SourceMap => {
+                let sources = self.sourcemap_extractor.embedded_sources(content);
+                if sources.is_empty() {
+                    return self.findings_scanner.scan(content, source);
+                }
+
+                let mut findings: Vec<Finding> = Vec::new();
+                for (_, text) in sources {
+                    for finding in self.findings_scanner.scan(&text, source) {
+                        if !findings
+                            .iter()
+                            .any(|f| f.kind == finding.kind && f.value == finding.value)
+                        {
+                            findings.push(finding);
+                        }
+                    }
+                }
+                findings
             }
+            _ => self.findings_scanner.scan(content, source),
+        }
+    }
+
+    /// Separate code from comments as the asset's syntax dictates and run the extractors
+    fn extract_asset(&self, content: &str, kind: AssetKind, source: Option<&str>) -> Vec<Endpoint> {
+        let (code, comments) = match kind {
+            AssetKind::JavaScript | AssetKind::TypeScript => self.comment_scanner.split_js(content),
+            AssetKind::Html => self.comment_scanner.split_html(content),
+            AssetKind::Css => self.comment_scanner.split_css(content),
+            AssetKind::Wasm => (printable_strings(content), Vec::new()),
+            AssetKind::Json | AssetKind::SourceMap => (content.to_string(), Vec::new()),
+        };
+        // A WASM binary's only meaningful content is its strings
+        let content = match kind {
+            AssetKind::Wasm => code.as_str(),
+            _ => content,
+        };
 
-            endpoints.push(endpoint);
+        let ctx = AssetContext {
+            source,
+            kind,
+            code: &code,
+            comments: &comments,
+        };
+        let mut endpoints = self.extract(content, &ctx);
+        if kind != AssetKind::Wasm {
+            locate_lines(content, &mut endpoints);
+            add_context(content, &mut endpoints);
         }
+        endpoints
+    }
 
-        // 2. Extract API endpoints specifically (with method detection)
-        endpoints.extend(self.pattern_matcher.find_api_endpoints(content, source));
+    /// Parse the original sources embedded in a sourcemap
+    ///
+    /// Endpoints keep the map as their source and record the original file
+    /// in `original_source` metadata. Maps without `sourcesContent` are
+    /// scanned as plain JSON.
+    fn extract_sourcemap(&self, content: &str, source: Option<&str>) -> Vec<Endpoint> {
+        let sources = self.sourcemap_extractor.embedded_sources(content);
+        if sources.is_empty() {
+            return self.extract_asset(content, AssetKind::Json, source);
+        }
 
-        // 3. Deduplicate endpoints
-        let mut final_endpoints: Vec<Endpoint> = Vec::new();
-        for ep in endpoints {
-            if !final_endpoints
-                .iter()
-                .any(|e| e.url == ep.url && e.method == ep.method)
-            {
-                final_endpoints.push(ep);
+        let mut endpoints = Vec::new();
+        for (name, text) in sources {
+            let kind = match detect::detect_asset_kind(Some(&name), None, text.as_bytes()) {
+                AssetKind::SourceMap | AssetKind::Wasm | AssetKind::Json => AssetKind::JavaScript,
+                kind => kind,
+            };
+            for mut endpoint in self.extract_asset(&text, kind, source) {
+                let mut metadata = endpoint.metadata.take().unwrap_or_default();
+                metadata
+                    .entry("original_source".to_string())
+                    .or_insert_with(|| name.clone());
+                endpoints.push(endpoint.with_metadata(metadata));
             }
         }
-
-        debug!("Found {} unique endpoints", final_endpoints.len());
-        Ok(final_endpoints)
+        endpoints
     }
 
-    /// Extract query parameters from URL
-    fn extract_params(&self, url: &str) -> Option<Vec<String>> {
-        if let Some(query_start) = url.find('?') {
-            let query = &url[query_start + 1..];
-            let params: Vec<String> = query
-                .split('&')
-                .filter(|p| !p.is_empty())
-                .filter_map(|p| p.split('=').next())
-                .map(|s| s.to_string())
-                .collect();
+    /// Run every registered extractor over the asset
+    fn extract(&self, content: &str, ctx: &AssetContext) -> Vec<Endpoint> {
+        let mut endpoints = Vec::new();
+        for extractor in self.extractors.iter().filter(|e| e.supports(ctx.kind)) {
+            let found = extractor.extract(content, ctx);
+            debug!(
+                "{} extractor found {} endpoints",
+                extractor.name(),
+                found.len()
+            );
+            endpoints.extend(found);
+        }
 
-            if !params.is_empty() {
-                return Some(params);
+        // The generic passes see SSE streams as plain REST
+        let sse_urls: Vec<String> = endpoints
+            .iter()
+            .filter(|e| e.endpoint_type == EndpointType::Sse)
+            .map(|e| e.url.clone())
+            .collect();
+        for endpoint in &mut endpoints {
+            if endpoint.endpoint_type == EndpointType::Rest && sse_urls.contains(&endpoint.url) {
+                endpoint.endpoint_type = EndpointType::Sse;
             }
         }
-        None
+
+        versions::annotate_versions(ctx.code, &mut endpoints);
+
+        endpoints
+    }
+
+    /// Drop false positives and merge endpoints sharing the same URL and
+    /// method into the first occurrence
+    fn dedup(&self, endpoints: Vec<Endpoint>) -> Vec<Endpoint> {
+        let mut final_endpoints: Vec<Endpoint> = Vec::new();
+        let mut index: HashMap<(String, Option<String>), usize> = HashMap::new();
+        for ep in endpoints {
+            if self.false_positive_filter.is_false_positive(&ep.url) {
+                continue;
+            }
+            match index.entry((ep.url.clone(), ep.method.clone())) {
+                Entry::Occupied(slot) => final_endpoints[*slot.get()].merge(ep),
+                Entry::Vacant(slot) => {
+                    slot.insert(final_endpoints.len());
+                    final_endpoints.push(ep);
+                }
+            }
+        }
+        final_endpoints
     }
 }
 
@@ -92,3 +418,122 @@ impl Default for Parser {
         Self::new()
     }
 }
+
+/// Set each endpoint without a line to the line its URL first appears on
+///
+/// All URLs are searched for in a single pass. URLs that were rewritten
+/// during extraction (resolved against a base, decoded) aren't found and
+/// stay without one.
+fn locate_lines(content: &str, endpoints: &mut [Endpoint]) {
+    let missing: Vec<usize> = (0..endpoints.len())
+        .filter(|&i| endpoints[i].line.is_none() && !endpoints[i].url.is_empty())
+        .collect();
+    if missing.is_empty() {
+        return;
+    }
+    let Ok(urls) = AhoCorasick::new(missing.iter().map(|&i| endpoints[i].url.as_str())) else {
+        return;
+    };
+
+    let newlines: Vec<usize> = content.match_indices('\n').map(|(i, _)| i).collect();
+    let mut remaining = missing.len();
+    for found in urls.find_overlapping_iter(content) {
+        let endpoint = &mut endpoints[missing[found.pattern().as_usize()]];
+        // `/api/users` inside `/api/users/42` isn't where it appears
+        let continues = content[found.end()..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphanumeric() || "/_-.".contains(c));
+        if endpoint.line.is_some() || continues {
+            continue;
+        }
+        endpoint.line = Some(newlines.partition_point(|&nl| nl < found.start()) + 1);
+        remaining -= 1;
+        if remaining == 0 {
+            break;
+        }
+    }
+}
+
+/// Bytes of code kept on each side of an endpoint's URL in its context
+const CONTEXT_RADIUS: usize = 60;
+
+/// Record the code around each endpoint with a line as `context` metadata
+///
+/// Minified bundles put everything on one line, so only a window around the
+/// URL is kept, with `…` marking what was cut.
+fn add_context(content: &str, endpoints: &mut [Endpoint]) {
+    let starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    for endpoint in endpoints.iter_mut() {
+        let has_context = endpoint
+            .metadata
+            .as_ref()
+            .is_some_and(|m| m.contains_key("context"));
+        let Some(&start) = endpoint.line.and_then(|l| starts.get(l.wrapping_sub(1))) else {
+            continue;
+        };
+        if has_context {
+            continue;
+        }
+        let end = content[start..]
+            .find('\n')
+            .map_or(content.len(), |i| start + i);
+        let line = &content[start..end];
+        let (from, to) = match line.find(&endpoint.url) {
+            Some(i) => (
+                i.saturating_sub(CONTEXT_RADIUS),
+                (i + endpoint.url.len() + CONTEXT_RADIUS).min(line.len()),
+            ),
+            None => (0, (2 * CONTEXT_RADIUS).min(line.len())),
+        };
+        let from = ceil_char_boundary(line, from);
+        let to = floor_char_boundary(line, to);
+        let mut context = line[from..to].trim().to_string();
+        if context.is_empty() {
+            continue;
+        }
+        if from > 0 {
+            context.insert(0, '…');
+        }
+        if to < line.len() {
+            context.push('…');
+        }
+        endpoint
+            .metadata
+            .get_or_insert_with(HashMap::new)
+            .insert("context".to_string(), context);
+    }
+}
+
+/// Smallest char boundary at or after `index`
+fn ceil_char_boundary(s: &str, mut index: usize) -> usize {
+    while !s.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+/// Minimum length of a printable run kept from binary content
+const MIN_STRING_LEN: usize = 6;
+
+/// Printable ASCII runs of binary content, one per line, like `strings(1)`
+fn printable_strings(content: &str) -> String {
+    let mut strings = String::new();
+    let mut run = String::new();
+
+    for c in content.chars().chain(std::iter::once('\0')) {
+        if c.is_ascii_graphic() || c == ' ' {
+            run.push(c);
+        } else {
+            if run.len() >= MIN_STRING_LEN {
+                strings.push_str(&run);
+                strings.push('\n');
+            }
+            run.clear();
+        }
+    }
+
+    strings
+}
@@ -0,0 +1,363 @@
+use crate::parser::extractor::{AssetContext, EndpointExtractor};
+use crate::parser::frameworks::is_url_like;
+use crate::types::{Confidence, Endpoint, EndpointType};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap};
+
+lazy_static! {
+    // axios.post('/api/users', { name, email }) / api.put(url, JSON.stringify({ ... }))
+    static ref CALL_WITH_BODY: Regex = Regex::new(
+        r#"\.(post|put|patch)\(\s*['"`]([^'"`]+)['"`]\s*,\s*(?:JSON\.stringify\(\s*)?\{([^{}]*)\}"#
+    ).unwrap();
+
+    // fetch('/api/login', { method: 'POST', body: JSON.stringify({ user, pass }) })
+    static ref FETCH_WITH_BODY: Regex = Regex::new(
+        r#"\bfetch\(\s*['"`]([^'"`]+)['"`]\s*,\s*\{([^{}]*?)\bbody\s*:\s*(?:JSON\.stringify\(\s*)?\{([^{}]*)\}"#
+    ).unwrap();
+
+    // axios.get('/api/search', { params: { q, page } })
+    static ref CALL_WITH_QUERY: Regex = Regex::new(
+        r#"\.(get|delete)\(\s*['"`]([^'"`]+)['"`]\s*,\s*\{[^{}]*?\bparams\s*:\s*\{([^{}]*)\}"#
+    ).unwrap();
+
+    static ref FETCH_METHOD: Regex =
+        Regex::new(r#"\bmethod\s*:\s*['"`]([A-Za-z]+)['"`]"#).unwrap();
+
+    static ref IDENTIFIER: Regex = Regex::new(r"^[A-Za-z_$][\w$-]{0,63}$").unwrap();
+    static ref PATH_WORD: Regex = Regex::new(r"^[A-Za-z0-9._~-]{1,64}$").unwrap();
+}
+
+/// How far past a fetch body to look for a `method` option
+const METHOD_LOOKAHEAD: usize = 200;
+
+/// Extractor for the keys of request bodies and query option objects
+///
+/// Body keys are recorded in the `body_params` metadata (comma separated)
+/// and `params` option keys as query parameters, so both survive merging
+/// with the endpoint the other extractors report for the same call.
+pub struct BodyParamExtractor;
+
+impl BodyParamExtractor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract endpoints carrying parameter names from call sites
+    pub fn extract(&self, content: &str, source: Option<&str>) -> Vec<Endpoint> {
+        let mut endpoints = Vec::new();
+
+        for cap in CALL_WITH_BODY.captures_iter(content) {
+            endpoints.push(self.with_body(&cap[2], &cap[1], &cap[3], source));
+        }
+
+        for cap in FETCH_WITH_BODY.captures_iter(content) {
+            let end = cap.get(0).map_or(0, |m| m.end());
+            let tail = content[end..]
+                .char_indices()
+                .take_while(|(i, _)| *i < METHOD_LOOKAHEAD)
+                .take_while(|(_, c)| *c != '}')
+                .last()
+                .map_or("", |(i, c)| &content[end..end + i + c.len_utf8()]);
+            // A body means the request isn't a GET even without an explicit method
+            let method = FETCH_METHOD
+                .captures(&cap[2])
+                .or_else(|| FETCH_METHOD.captures(tail))
+                .map(|m| m[1].to_string())
+                .unwrap_or_else(|| "POST".to_string());
+            endpoints.push(self.with_body(&cap[1], &method, &cap[3], source));
+        }
+
+        for cap in CALL_WITH_QUERY.captures_iter(content) {
+            let keys = object_keys(&cap[3]);
+            if keys.is_empty() {
+                continue;
+            }
+            endpoints.push(self.endpoint(&cap[2], &cap[1], source).with_params(keys));
+        }
+
+        endpoints.retain(|e| is_url_like(&e.url));
+        endpoints
+    }
+
+    fn with_body(&self, url: &str, method: &str, body: &str, source: Option<&str>) -> Endpoint {
+        let endpoint = self.endpoint(url, method, source);
+        let keys = object_keys(body);
+        if keys.is_empty() {
+            return endpoint;
+        }
+
+        let mut metadata = HashMap::new();
+        metadata.insert("body_params".to_string(), keys.join(","));
+        endpoint.with_metadata(metadata)
+    }
+
+    fn endpoint(&self, url: &str, method: &str, source: Option<&str>) -> Endpoint {
+        let mut endpoint = Endpoint::new(url.to_string(), EndpointType::Rest)
+            .with_method(method.to_uppercase())
+            .with_confidence(Confidence::Medium);
+        if let Some(src) = source {
+            endpoint = endpoint.with_source(src);
+        }
+        endpoint
+    }
+}
+
+impl Default for BodyParamExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EndpointExtractor for BodyParamExtractor {
+    fn name(&self) -> &str {
+        "params"
+    }
+
+    fn extract(&self, _content: &str, ctx: &AssetContext) -> Vec<Endpoint> {
+        self.extract(ctx.code, ctx.source)
+    }
+}
+
+/// Keys of a flat object literal body: `a: 1, "b": x, c` gives `a, b, c`
+fn object_keys(body: &str) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for part in body.split(',') {
+        let part = part.trim();
+        if part.starts_with("...") {
+            continue;
+        }
+        let key = part
+            .split(':')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .trim_matches(|c| c == '"' || c == '\'' || c == '`');
+        if IDENTIFIER.is_match(key) && !keys.iter().any(|k| k == key) {
+            keys.push(key.to_string());
+        }
+    }
+    keys
+}
+
+/// A kind of word in a fuzzing wordlist
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordKind {
+    /// Directory-like path segments, for content discovery
+    Paths,
+    /// Query parameter names and body keys
+    Params,
+    /// Final path segments with an extension, such as `config.json`
+    Files,
+}
+
+impl WordKind {
+    pub const ALL: [WordKind; 3] = [WordKind::Paths, WordKind::Params, WordKind::Files];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WordKind::Paths => "paths",
+            WordKind::Params => "params",
+            WordKind::Files => "files",
+        }
+    }
+}
+
+impl std::str::FromStr for WordKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "paths" | "path" => Ok(WordKind::Paths),
+            "params" | "param" | "parameters" => Ok(WordKind::Params),
+            "files" | "file" | "filenames" => Ok(WordKind::Files),
+            _ => Err(format!(
+                "unknown wordlist kind '{}' (expected paths, params or files)",
+                s
+            )),
+        }
+    }
+}
+
+/// Sorted, deduplicated fuzzing words from endpoints, by kind
+#[derive(Debug, Default)]
+pub struct Wordlists {
+    pub paths: BTreeSet<String>,
+    pub params: BTreeSet<String>,
+    pub files: BTreeSet<String>,
+}
+
+impl Wordlists {
+    /// Words of one kind
+    pub fn get(&self, kind: WordKind) -> &BTreeSet<String> {
+        match kind {
+            WordKind::Paths => &self.paths,
+            WordKind::Params => &self.params,
+            WordKind::Files => &self.files,
+        }
+    }
+
+    /// Words of any of the given kinds, sorted and deduplicated
+    pub fn merged(&self, kinds: &[WordKind]) -> Vec<String> {
+        let words: BTreeSet<&String> = kinds.iter().flat_map(|&kind| self.get(kind)).collect();
+        words.into_iter().cloned().collect()
+    }
+}
+
+/// Build fuzzing wordlists from endpoints
+///
+/// Parameters are query parameter names (from `params` and the URL itself)
+/// and body keys from `body_params` metadata. Path segments are split into
+/// files (a final segment with an extension) and paths (everything else).
+/// Placeholders, numeric IDs, and hash-like segments are left out.
+pub fn build_wordlists(endpoints: &[Endpoint]) -> Wordlists {
+    let mut lists = Wordlists::default();
+
+    for endpoint in endpoints {
+        lists
+            .params
+            .extend(endpoint.params.iter().flatten().cloned());
+
+        if let Some(body) = endpoint
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("body_params"))
+        {
+            lists.params.extend(body.split(',').map(str::to_string));
+        }
+
+        let url = endpoint.url.split('#').next().unwrap_or("");
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+
+        lists.params.extend(
+            query
+                .split('&')
+                .filter_map(|pair| pair.split('=').next())
+                .filter(|key| IDENTIFIER.is_match(key))
+                .map(str::to_string),
+        );
+
+        // Drop the scheme and host of absolute URLs
+        let path = match path.split_once("://") {
+            Some((_, rest)) => rest.find('/').map_or("", |i| &rest[i..]),
+            None => path,
+        };
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        for (i, segment) in segments.iter().enumerate() {
+            if !is_path_word(segment) {
+                continue;
+            }
+            if i + 1 == segments.len() && is_file_name(segment) {
+                lists.files.insert(segment.to_string());
+            } else {
+                lists.paths.insert(segment.to_string());
+            }
+        }
+    }
+
+    lists.params.retain(|w| !w.is_empty());
+    lists
+}
+
+/// Build a sorted, deduplicated fuzzing wordlist from endpoints
+///
+/// All kinds of [`build_wordlists`] in one list.
+pub fn build_wordlist(endpoints: &[Endpoint]) -> Vec<String> {
+    build_wordlists(endpoints).merged(&WordKind::ALL)
+}
+
+/// Whether a segment looks like a file name: a short extension with a
+/// letter in it (`.env` included), so versions like `v1.2` stay paths
+fn is_file_name(segment: &str) -> bool {
+    match segment.rsplit_once('.') {
+        Some((_, ext)) => {
+            (1..=5).contains(&ext.len())
+                && ext.chars().all(|c| c.is_ascii_alphanumeric())
+                && ext.chars().any(|c| c.is_ascii_alphabetic())
+        }
+        None => false,
+    }
+}
+
+/// Path segments worth fuzzing: not IDs, hashes, or template placeholders
+fn is_path_word(segment: &str) -> bool {
+    if !PATH_WORD.is_match(segment) || segment.chars().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+    let hash_like = segment.len() >= 16
+        && segment.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+        && segment.chars().any(|c| c.is_ascii_digit());
+    !hash_like
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_and_query_keys() {
+        let extractor = BodyParamExtractor::new();
+        let content = r#"
+            axios.post('/api/users', { name, email: form.email, ...extra });
+            fetch("/api/login", { body: JSON.stringify({ "username": u, password: p }), method: "PUT" });
+            api.get('/api/search', { params: { q: term, page } });
+        "#;
+
+        let endpoints = extractor.extract(content, None);
+        let users = endpoints.iter().find(|e| e.url == "/api/users").unwrap();
+        assert_eq!(users.method.as_deref(), Some("POST"));
+        assert_eq!(
+            users.metadata.as_ref().unwrap()["body_params"],
+            "name,email"
+        );
+
+        let login = endpoints.iter().find(|e| e.url == "/api/login").unwrap();
+        assert_eq!(login.method.as_deref(), Some("PUT"));
+        assert_eq!(
+            login.metadata.as_ref().unwrap()["body_params"],
+            "username,password"
+        );
+
+        let search = endpoints.iter().find(|e| e.url == "/api/search").unwrap();
+        assert_eq!(
+            search.params.as_deref(),
+            Some(&["q".to_string(), "page".to_string()][..])
+        );
+    }
+
+    #[test]
+    fn test_build_wordlist() {
+        let mut metadata = HashMap::new();
+        metadata.insert("body_params".to_string(), "email,password".to_string());
+        let endpoints = vec![
+            Endpoint::new(
+                "https://api.example.com/v2/users/42/avatar?size=64&fmt=png".to_string(),
+                EndpointType::Rest,
+            ),
+            Endpoint::new("/api/orders/${id}/items".to_string(), EndpointType::Rest)
+                .with_params(vec!["expand".to_string()])
+                .with_metadata(metadata),
+            Endpoint::new(
+                "/static/3f2a9c8e1b7d4f60a5e2/app.js".to_string(),
+                EndpointType::Rest,
+            ),
+        ];
+
+        assert_eq!(
+            build_wordlist(&endpoints),
+            vec![
+                "api", "app.js", "avatar", "email", "expand", "fmt", "items", "orders", "password",
+                "size", "static", "users", "v2",
+            ]
+        );
+
+        let lists = build_wordlists(&endpoints);
+        assert_eq!(lists.files.iter().collect::<Vec<_>>(), ["app.js"]);
+        assert_eq!(
+            lists.params.iter().collect::<Vec<_>>(),
+            ["email", "expand", "fmt", "password", "size"]
+        );
+        assert!(lists.paths.contains("avatar") && !lists.paths.contains("app.js"));
+        assert!(!is_file_name("v1.2") && is_file_name(".env") && is_file_name("config.json"));
+    }
+}
@@ -1,6 +1,14 @@
-use crate::types::{Endpoint, EndpointType};
+use crate::error::{Error, Result};
+use crate::parser::extractor::{AssetContext, AssetKind, EndpointExtractor};
+use crate::parser::filters::FalsePositiveFilter;
+use crate::types::{Confidence, Endpoint, EndpointType};
+use aho_corasick::AhoCorasick;
 use lazy_static::lazy_static;
-use regex::Regex;
+use regex::{Regex, RegexSet};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
 
 lazy_static! {
     // URL patterns
@@ -8,22 +16,25 @@ lazy_static! {
         r#"(?:https?://|//)[^\s"'<>{}|\\\^`\[\]]+|/(?:api|v\d+|graphql)[^\s"'<>]*"#
     ).unwrap();
 
-    // API endpoint patterns
-    static ref API_PATTERNS: Vec<Regex> = vec![
-        // REST API patterns
-        Regex::new(r#"['"`]/(api|rest|v\d+)/[^'"`\s]+"#).unwrap(),
-        Regex::new(r#"['"`]/[^'"`\s]*/(users?|auth|login|logout|register)[^'"`\s]*"#).unwrap(),
-
-        // GraphQL patterns
-        Regex::new(r#"['"`][^'"`\s]*graphql[^'"`\s]*"#).unwrap(),
-        Regex::new(r#"mutation\s+\w+|query\s+\w+"#).unwrap(),
-
-        // WebSocket patterns
-        Regex::new(r#"wss?://[^\s"'<>{}|\\\^`\[\]]+"#).unwrap(),
-
-        // Common API endpoints
-        Regex::new(r#"['"`]/(data|fetch|submit|update|delete|create|get)[^'"`\s]*"#).unwrap(),
-    ];
+    // API endpoint patterns, compiled individually for capture extraction
+    static ref API_PATTERNS: Vec<Regex> = API_PATTERN_DEFS
+        .iter()
+        .map(|def| Regex::new(def.regex).unwrap())
+        .collect();
+
+    // The same patterns as one set, to learn which ones match in a single pass
+    static ref API_PATTERN_SET: RegexSet =
+        RegexSet::new(API_PATTERN_DEFS.iter().map(|def| def.regex)).unwrap();
+
+    // Literal anchors of every pattern, with the pattern each one belongs to
+    static ref API_PREFILTER: (AhoCorasick, Vec<usize>) = {
+        let (anchors, owners): (Vec<&str>, Vec<usize>) = API_PATTERN_DEFS
+            .iter()
+            .enumerate()
+            .flat_map(|(i, def)| def.anchors.iter().map(move |a| (*a, i)))
+            .unzip();
+        (AhoCorasick::new(anchors).unwrap(), owners)
+    };
 
     // HTTP methods
     static ref HTTP_METHODS: Regex = Regex::new(
@@ -34,12 +45,141 @@ lazy_static! {
     static ref QUERY_PARAM: Regex = Regex::new(r#"\?([^&\s'"]+)"#).unwrap();
 }
 
+/// A built-in API pattern and literals that any match must contain
+struct ApiPatternDef {
+    regex: &'static str,
+    anchors: &'static [&'static str],
+}
+
+const API_PATTERN_DEFS: [ApiPatternDef; 6] = [
+    // REST API patterns
+    ApiPatternDef {
+        regex: r#"['"`]/(api|rest|v\d+)/[^'"`\s]+"#,
+        anchors: &["/api/", "/rest/", "/v"],
+    },
+    ApiPatternDef {
+        regex: r#"['"`]/[^'"`\s]*/(users?|auth|login|logout|register)[^'"`\s]*"#,
+        anchors: &["/user", "/auth", "/login", "/logout", "/register"],
+    },
+    // GraphQL patterns
+    ApiPatternDef {
+        regex: r#"['"`][^'"`\s]*graphql[^'"`\s]*"#,
+        anchors: &["graphql"],
+    },
+    ApiPatternDef {
+        regex: r#"mutation\s+\w+|query\s+\w+"#,
+        anchors: &["mutation", "query"],
+    },
+    // WebSocket patterns
+    ApiPatternDef {
+        regex: r#"wss?://[^\s"'<>{}|\\\^`\[\]]+"#,
+        anchors: &["ws://", "wss://"],
+    },
+    // Common API endpoints
+    ApiPatternDef {
+        regex: r#"['"`]/(data|fetch|submit|update|delete|create|get)[^'"`\s]*"#,
+        anchors: &[
+            "/data", "/fetch", "/submit", "/update", "/delete", "/create", "/get",
+        ],
+    },
+];
+
+/// A user-supplied extraction pattern
+#[derive(Debug, Clone)]
+pub struct CustomPattern {
+    /// Name reported in endpoint metadata
+    pub name: String,
+
+    /// Compiled pattern; the first capture group is used as the URL if present
+    pub regex: Regex,
+
+    /// Type assigned to matches
+    pub endpoint_type: EndpointType,
+
+    /// Confidence assigned to matches
+    pub confidence: Confidence,
+}
+
+/// On-disk pattern file layout
+#[derive(Debug, Deserialize)]
+struct PatternFile {
+    patterns: Vec<PatternDefinition>,
+}
+
+/// A single pattern entry in a pattern file
+#[derive(Debug, Deserialize)]
+struct PatternDefinition {
+    name: String,
+    regex: String,
+    #[serde(default = "default_pattern_type")]
+    endpoint_type: EndpointType,
+    #[serde(default = "default_pattern_confidence")]
+    confidence: Confidence,
+}
+
+fn default_pattern_type() -> EndpointType {
+    EndpointType::Rest
+}
+
+fn default_pattern_confidence() -> Confidence {
+    Confidence::Medium
+}
+
+/// Load custom patterns from a YAML or TOML file
+///
+/// The format is chosen by extension (`.toml` for TOML, anything else is
+/// read as YAML). Each entry needs a `name` and `regex`; `endpoint_type`
+/// defaults to `rest` and `confidence` to `medium`.
+pub fn load_patterns(path: &Path) -> Result<Vec<CustomPattern>> {
+    let content = std::fs::read_to_string(path)?;
+
+    let file: PatternFile = match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => toml::from_str(&content)?,
+        _ => serde_yaml::from_str(&content)?,
+    };
+
+    file.patterns
+        .into_iter()
+        .map(|def| {
+            let regex = Regex::new(&def.regex).map_err(|e| {
+                Error::ValidationError(format!("Invalid pattern '{}': {}", def.name, e))
+            })?;
+            Ok(CustomPattern {
+                name: def.name,
+                regex,
+                endpoint_type: def.endpoint_type,
+                confidence: def.confidence,
+            })
+        })
+        .collect()
+}
+
 /// Pattern matcher for extracting endpoints from code
-pub struct PatternMatcher;
+///
+/// Built-in patterns are gated twice before running with captures: an
+/// aho-corasick pass over their literal anchors, then a `RegexSet` pass
+/// that confirms which of the remaining candidates actually match.
+pub struct PatternMatcher {
+    custom_patterns: Vec<CustomPattern>,
+    custom_set: Option<RegexSet>,
+}
 
 impl PatternMatcher {
     pub fn new() -> Self {
-        Self
+        Self {
+            custom_patterns: Vec::new(),
+            custom_set: None,
+        }
+    }
+
+    /// Create a matcher that also runs the given custom patterns
+    pub fn with_custom_patterns(patterns: Vec<CustomPattern>) -> Self {
+        // Each pattern already compiled on its own, so the set compiles too
+        let custom_set = RegexSet::new(patterns.iter().map(|p| p.regex.as_str())).ok();
+        Self {
+            custom_patterns: patterns,
+            custom_set,
+        }
     }
 
     /// Find all URLs in the content
@@ -67,8 +207,8 @@ impl PatternMatcher {
     pub fn find_api_endpoints(&self, content: &str, source: Option<&str>) -> Vec<Endpoint> {
         let mut endpoints = Vec::new();
 
-        for pattern in API_PATTERNS.iter() {
-            for cap in pattern.captures_iter(content) {
+        for index in self.matching_api_patterns(content) {
+            for cap in API_PATTERNS[index].captures_iter(content) {
                 if let Some(matched) = cap.get(0) {
                     let url = matched
                         .as_str()
@@ -98,14 +238,84 @@ impl PatternMatcher {
             }
         }
 
+        endpoints.extend(self.find_custom_endpoints(content, source));
+
+        endpoints
+    }
+
+    /// Indices of built-in API patterns that match somewhere in the content
+    fn matching_api_patterns(&self, content: &str) -> Vec<usize> {
+        let (prefilter, owners) = &*API_PREFILTER;
+
+        let mut candidates = vec![false; API_PATTERN_DEFS.len()];
+        for m in prefilter.find_overlapping_iter(content) {
+            candidates[owners[m.pattern().as_usize()]] = true;
+            if !candidates.contains(&false) {
+                break;
+            }
+        }
+        if !candidates.contains(&true) {
+            return Vec::new();
+        }
+
+        API_PATTERN_SET
+            .matches(content)
+            .into_iter()
+            .filter(|&i| candidates[i])
+            .collect()
+    }
+
+    /// Find endpoints matched by user-supplied patterns
+    fn find_custom_endpoints(&self, content: &str, source: Option<&str>) -> Vec<Endpoint> {
+        let mut endpoints = Vec::new();
+        let Some(set) = &self.custom_set else {
+            return endpoints;
+        };
+
+        for index in set.matches(content).into_iter() {
+            let pattern = &self.custom_patterns[index];
+            for cap in pattern.regex.captures_iter(content) {
+                let Some(matched) = cap.get(1).or_else(|| cap.get(0)) else {
+                    continue;
+                };
+                if matched.as_str().is_empty() {
+                    continue;
+                }
+
+                let mut metadata = HashMap::new();
+                metadata.insert("pattern".to_string(), pattern.name.clone());
+
+                let mut endpoint =
+                    Endpoint::new(matched.as_str().to_string(), pattern.endpoint_type.clone())
+                        .with_confidence(pattern.confidence)
+                        .with_metadata(metadata);
+
+                if let Some(method) = self.find_http_method_near(content, matched.start()) {
+                    endpoint = endpoint.with_method(method);
+                }
+
+                if let Some(src) = source {
+                    endpoint = endpoint.with_source(src);
+                }
+
+                endpoints.push(endpoint);
+            }
+        }
+
         endpoints
     }
 
     /// Find HTTP method near a position in the content
     fn find_http_method_near(&self, content: &str, pos: usize) -> Option<String> {
-        // Look backward and forward 100 characters
-        let start = pos.saturating_sub(100);
-        let end = std::cmp::min(pos + 100, content.len());
+        // Look backward and forward 100 bytes, widened to char boundaries
+        let mut start = pos.saturating_sub(100);
+        while !content.is_char_boundary(start) {
+            start -= 1;
+        }
+        let mut end = std::cmp::min(pos + 100, content.len());
+        while !content.is_char_boundary(end) {
+            end += 1;
+        }
         let snippet = &content[start..end];
 
         HTTP_METHODS
@@ -116,20 +326,7 @@ impl PatternMatcher {
 
     /// Validate if a string is a valid URL
     fn is_valid_url(&self, url: &str) -> bool {
-        // Filter out common false positives
-        if url.len() < 4 {
-            return false;
-        }
-
-        // Exclude common file extensions that are not endpoints
-        let extensions = [".jpg", ".png", ".gif", ".css", ".woff", ".ttf", ".ico"];
-        for ext in &extensions {
-            if url.ends_with(ext) {
-                return false;
-            }
-        }
-
-        true
+        !FalsePositiveFilter::default().is_false_positive(url)
     }
 
     /// Detect endpoint type based on URL and context
@@ -150,6 +347,81 @@ impl Default for PatternMatcher {
     }
 }
 
+/// Extractor running the URL and API patterns (built-in and custom)
+pub struct PatternExtractor {
+    pattern_matcher: Arc<PatternMatcher>,
+}
+
+impl PatternExtractor {
+    pub fn new(pattern_matcher: Arc<PatternMatcher>) -> Self {
+        Self { pattern_matcher }
+    }
+
+    /// Extract query parameters from URL
+    fn extract_params(&self, url: &str) -> Option<Vec<String>> {
+        if let Some(query_start) = url.find('?') {
+            let query = &url[query_start + 1..];
+            let params: Vec<String> = query
+                .split('&')
+                .filter(|p| !p.is_empty())
+                .filter_map(|p| p.split('=').next())
+                .map(|s| s.to_string())
+                .collect();
+
+            if !params.is_empty() {
+                return Some(params);
+            }
+        }
+        None
+    }
+}
+
+impl EndpointExtractor for PatternExtractor {
+    fn name(&self) -> &str {
+        "patterns"
+    }
+
+    fn supports(&self, _kind: AssetKind) -> bool {
+        true
+    }
+
+    fn extract(&self, _content: &str, ctx: &AssetContext) -> Vec<Endpoint> {
+        let code = ctx.code;
+        let mut endpoints = Vec::new();
+
+        // Regex-based URL extraction
+        for url in self.pattern_matcher.find_urls(code) {
+            let endpoint_type = self.pattern_matcher.detect_endpoint_type(&url, code);
+            let mut endpoint =
+                Endpoint::new(url.clone(), endpoint_type).with_confidence(Confidence::Medium);
+
+            if let Some(src) = ctx.source {
+                endpoint = endpoint.with_source(src);
+            }
+
+            // Extract query parameters
+            if let Some(params) = self.extract_params(&url) {
+                endpoint = endpoint.with_params(params);
+            }
+
+            endpoints.push(endpoint);
+        }
+
+        // API endpoints specifically (with method detection)
+        endpoints.extend(
+            self.pattern_matcher
+                .find_api_endpoints(code, ctx.source)
+                .into_iter()
+                .map(|ep| match ep.confidence {
+                    Some(_) => ep,
+                    None => ep.with_confidence(Confidence::Medium),
+                }),
+        );
+
+        endpoints
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,4 +447,22 @@ mod tests {
         let endpoints = matcher.find_api_endpoints(content, None);
         assert!(!endpoints.is_empty());
     }
+
+    #[test]
+    fn test_prefilter_selects_candidate_patterns() {
+        let matcher = PatternMatcher::new();
+
+        assert!(matcher.matching_api_patterns("const x = 1;").is_empty());
+        // Anchor present but no full match: the set rejects it
+        assert!(matcher.matching_api_patterns("a /api/ b").is_empty());
+        assert_eq!(
+            matcher.matching_api_patterns(r#"ws = "wss://rt.example.com/feed""#),
+            vec![4]
+        );
+
+        // Method lookup around multibyte text must not split a char
+        let content = format!("{}post(\"/api/v1/ü\")", "é".repeat(60));
+        let endpoints = matcher.find_api_endpoints(&content, None);
+        assert_eq!(endpoints[0].method.as_deref(), Some("POST"));
+    }
 }
@@ -23,6 +23,24 @@ impl SourceMapExtractor {
         None
     }
 
+    /// Original sources embedded in a sourcemap's `sourcesContent`, by name
+    pub fn embedded_sources(&self, content: &str) -> Vec<(String, String)> {
+        match SourceMap::from_reader(content.as_bytes()) {
+            Ok(sm) => sm
+                .sources()
+                .enumerate()
+                .filter_map(|(i, source)| {
+                    sm.get_source_contents(i as u32)
+                        .map(|text| (source.to_string(), text.to_string()))
+                })
+                .collect(),
+            Err(e) => {
+                warn!("Failed to parse sourcemap: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
     /// Parse sourcemap content
     pub fn parse_sourcemap(&self, content: &str) -> Result<Vec<Endpoint>> {
         let mut endpoints = Vec::new();
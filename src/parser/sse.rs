@@ -0,0 +1,159 @@
+use crate::parser::extractor::{AssetContext, EndpointExtractor};
+use crate::types::{Confidence, Endpoint, EndpointType};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+
+lazy_static! {
+    // new EventSource("/stream") / new EventSourcePolyfill(url, { withCredentials: true })
+    static ref NEW_EVENT_SOURCE: Regex = Regex::new(
+        r#"new\s+(?:[\w$]+\.)?((?:Native|Polyfill)?EventSource(?:Polyfill)?)\(\s*['"`]([^'"`]+)['"`]\s*(,\s*\{[^}]*withCredentials\s*:\s*true)?"#
+    ).unwrap();
+
+    // Accept: "text/event-stream" and friends
+    static ref EVENT_STREAM: Regex = Regex::new(r#"text/event-stream"#).unwrap();
+
+    // Quoted absolute URLs and root-relative paths
+    static ref URL_LITERAL: Regex =
+        Regex::new(r#"['"`]((?:https?://|/)[^'"`\s]+)['"`]"#).unwrap();
+}
+
+/// How far before a `text/event-stream` reference the request URL is looked for
+const EVENT_STREAM_LOOKBEHIND: usize = 300;
+
+/// Extractor for Server-Sent Events streams
+///
+/// Picks up `EventSource` clients directly, and `fetch`/XHR calls that ask
+/// for `text/event-stream` by taking the closest URL literal before the
+/// content type.
+pub struct SseExtractor;
+
+impl SseExtractor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract SSE endpoints from the content
+    pub fn extract(&self, content: &str, source: Option<&str>) -> Vec<Endpoint> {
+        let mut endpoints: Vec<Endpoint> = Vec::new();
+
+        for cap in NEW_EVENT_SOURCE.captures_iter(content) {
+            let url = cap[2].to_string();
+            if endpoints.iter().any(|e| e.url == url) {
+                continue;
+            }
+
+            let mut metadata = HashMap::new();
+            metadata.insert("client".to_string(), cap[1].to_string());
+            if cap.get(3).is_some() {
+                metadata.insert("with_credentials".to_string(), "true".to_string());
+            }
+
+            // EventSource can only issue GETs
+            let endpoint = self
+                .endpoint(url, metadata, Confidence::High, source)
+                .with_method("GET");
+            endpoints.push(endpoint);
+        }
+
+        for m in EVENT_STREAM.find_iter(content) {
+            let start =
+                floor_char_boundary(content, m.start().saturating_sub(EVENT_STREAM_LOOKBEHIND));
+            let Some(url) = URL_LITERAL
+                .captures_iter(&content[start..m.start()])
+                .last()
+                .map(|cap| cap[1].to_string())
+            else {
+                continue;
+            };
+            if endpoints.iter().any(|e| e.url == url) {
+                continue;
+            }
+
+            let mut metadata = HashMap::new();
+            metadata.insert("client".to_string(), "fetch".to_string());
+
+            endpoints.push(self.endpoint(url, metadata, Confidence::Medium, source));
+        }
+
+        endpoints
+    }
+
+    fn endpoint(
+        &self,
+        url: String,
+        metadata: HashMap<String, String>,
+        confidence: Confidence,
+        source: Option<&str>,
+    ) -> Endpoint {
+        let mut endpoint = Endpoint::new(url, EndpointType::Sse)
+            .with_metadata(metadata)
+            .with_confidence(confidence);
+        if let Some(src) = source {
+            endpoint = endpoint.with_source(src);
+        }
+        endpoint
+    }
+}
+
+impl Default for SseExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EndpointExtractor for SseExtractor {
+    fn name(&self) -> &str {
+        "sse"
+    }
+
+    fn extract(&self, _content: &str, ctx: &AssetContext) -> Vec<Endpoint> {
+        self.extract(ctx.code, ctx.source)
+    }
+}
+
+/// Largest char boundary at or before `index`
+pub(crate) fn floor_char_boundary(s: &str, mut index: usize) -> usize {
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_source_and_event_stream_fetch() {
+        let extractor = SseExtractor::new();
+        let content = r#"
+            const es = new EventSource("/api/notifications/stream", { withCredentials: true });
+            const poly = new EventSourcePolyfill('https://push.example.com/feed');
+            fetch("/api/llm/completions", {
+                method: "POST",
+                headers: { Accept: "text/event-stream" },
+            });
+            const MIME = "text/event-stream";
+        "#;
+
+        let endpoints = extractor.extract(content, Some("app.js"));
+        let urls: Vec<&str> = endpoints.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "/api/notifications/stream",
+                "https://push.example.com/feed",
+                "/api/llm/completions",
+            ]
+        );
+        assert!(endpoints
+            .iter()
+            .all(|e| e.endpoint_type == EndpointType::Sse));
+
+        let metadata = endpoints[0].metadata.as_ref().unwrap();
+        assert_eq!(metadata["client"], "EventSource");
+        assert_eq!(metadata["with_credentials"], "true");
+        assert_eq!(endpoints[2].confidence, Some(Confidence::Medium));
+    }
+}
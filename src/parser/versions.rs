@@ -0,0 +1,186 @@
+use crate::parser::sse::floor_char_boundary;
+use crate::types::Endpoint;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+lazy_static! {
+    // /v1/, /V2.1/, /api/v3 at the end of the path
+    static ref PATH_VERSION: Regex =
+        Regex::new(r"/([vV]\d+(?:\.\d+)*)(?:[/?#]|$)").unwrap();
+
+    // ?api-version=2023-01-01 / &apiVersion=2
+    static ref QUERY_VERSION: Regex = Regex::new(
+        r"[?&](?:api-version|api_version|apiVersion|apiversion)=([A-Za-z0-9._-]+)"
+    ).unwrap();
+
+    // application/vnd.github.v3+json / application/vnd.acme+json; version=2
+    static ref VND_MEDIA_TYPE: Regex =
+        Regex::new(r"application/vnd\.[A-Za-z0-9.+-]+(?:\s*;\s*version=[\d.]+)?").unwrap();
+    static ref VND_VERSION: Regex =
+        Regex::new(r"[.-][vV](\d+(?:\.\d+)*)\b|version=(\d+(?:\.\d+)*)").unwrap();
+
+    // Quoted absolute URLs and root-relative paths
+    static ref URL_LITERAL: Regex =
+        Regex::new(r#"['"`]((?:https?://|/)[^'"`\s]+)['"`]"#).unwrap();
+}
+
+/// How far before a vendor media type the request URL is looked for
+const MEDIA_TYPE_LOOKBEHIND: usize = 300;
+
+/// Group name for endpoints without a detected version
+pub const UNVERSIONED: &str = "unversioned";
+
+/// Record the API version of each endpoint in its metadata
+///
+/// Sets `api_version` and `api_version_source` (`path`, `query`, or
+/// `accept`). Path and query versions come from the URL itself; a vendor
+/// media type such as `application/vnd.github.v3+json` is attributed to
+/// the closest URL literal before it in `code`.
+pub fn annotate_versions(code: &str, endpoints: &mut [Endpoint]) {
+    let mut accepted: HashMap<String, String> = HashMap::new();
+    for m in VND_MEDIA_TYPE.find_iter(code) {
+        let Some(version) = media_type_version(m.as_str()) else {
+            continue;
+        };
+        let start = floor_char_boundary(code, m.start().saturating_sub(MEDIA_TYPE_LOOKBEHIND));
+        if let Some(cap) = URL_LITERAL.captures_iter(&code[start..m.start()]).last() {
+            accepted.entry(cap[1].to_string()).or_insert(version);
+        }
+    }
+
+    for endpoint in endpoints.iter_mut() {
+        let detected = url_version(&endpoint.url).or_else(|| {
+            accepted
+                .get(&endpoint.url)
+                .map(|version| (version.clone(), "accept"))
+        });
+        let Some((version, from)) = detected else {
+            continue;
+        };
+
+        let metadata = endpoint.metadata.get_or_insert_with(HashMap::new);
+        if !metadata.contains_key("api_version") {
+            metadata.insert("api_version".to_string(), version);
+            metadata.insert("api_version_source".to_string(), from.to_string());
+        }
+    }
+}
+
+/// Version token in a URL's path or `api-version` style query parameter
+pub fn url_version(url: &str) -> Option<(String, &'static str)> {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    if let Some(cap) = PATH_VERSION.captures(path) {
+        return Some((cap[1].to_lowercase(), "path"));
+    }
+    QUERY_VERSION
+        .captures(url)
+        .map(|cap| (cap[1].to_string(), "query"))
+}
+
+/// Version carried by a vendor media type, normalized to `vN`
+fn media_type_version(media_type: &str) -> Option<String> {
+    let cap = VND_VERSION.captures(media_type)?;
+    let number = cap.get(1).or_else(|| cap.get(2))?;
+    Some(format!("v{}", number.as_str()))
+}
+
+/// Detected API version of an endpoint
+pub fn endpoint_version(endpoint: &Endpoint) -> Option<&str> {
+    endpoint
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("api_version"))
+        .map(String::as_str)
+}
+
+/// Group endpoints by API version, oldest version first and unversioned last
+pub fn group_by_version(endpoints: &[Endpoint]) -> Vec<(String, Vec<&Endpoint>)> {
+    let mut groups: BTreeMap<String, Vec<&Endpoint>> = BTreeMap::new();
+    for endpoint in endpoints {
+        let version = endpoint_version(endpoint).unwrap_or(UNVERSIONED);
+        groups
+            .entry(version.to_string())
+            .or_default()
+            .push(endpoint);
+    }
+
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    groups.sort_by(
+        |(a, _), (b, _)| match (a == UNVERSIONED, b == UNVERSIONED) {
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            _ => compare_versions(a, b),
+        },
+    );
+    groups
+}
+
+/// Order versions numerically (`v2` < `v10`), falling back to text
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let numbers = |v: &str| -> Vec<u64> {
+        v.trim_start_matches(['v', 'V'])
+            .split(['.', '-'])
+            .map_while(|part| part.parse().ok())
+            .collect()
+    };
+    numbers(a).cmp(&numbers(b)).then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EndpointType;
+
+    #[test]
+    fn test_version_detection_and_grouping() {
+        let code = r#"
+            fetch("/api/v1/users");
+            fetch("https://api.example.com/V2.1/orders?limit=10");
+            fetch("/management/items?api-version=2023-05-01");
+            fetch("/repos", { headers: { Accept: "application/vnd.github.v3+json" } });
+            fetch("/api/v10/health");
+            fetch("/static/app.js");
+        "#;
+        let mut endpoints: Vec<Endpoint> = [
+            "/api/v1/users",
+            "https://api.example.com/V2.1/orders?limit=10",
+            "/management/items?api-version=2023-05-01",
+            "/repos",
+            "/api/v10/health",
+            "/static/app.js",
+        ]
+        .iter()
+        .map(|url| Endpoint::new(url.to_string(), EndpointType::Rest))
+        .collect();
+
+        annotate_versions(code, &mut endpoints);
+
+        let versions: Vec<Option<&str>> = endpoints.iter().map(endpoint_version).collect();
+        assert_eq!(
+            versions,
+            vec![
+                Some("v1"),
+                Some("v2.1"),
+                Some("2023-05-01"),
+                Some("v3"),
+                Some("v10"),
+                None
+            ]
+        );
+        assert_eq!(
+            endpoints[3].metadata.as_ref().unwrap()["api_version_source"],
+            "accept"
+        );
+
+        let order: Vec<String> = group_by_version(&endpoints)
+            .into_iter()
+            .map(|(version, _)| version)
+            .collect();
+        assert_eq!(
+            order,
+            vec!["v1", "v2.1", "v3", "v10", "2023-05-01", UNVERSIONED]
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use crate::parser::extractor::{AssetContext, EndpointExtractor};
+use crate::types::{Confidence, Endpoint, EndpointType};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+
+lazy_static! {
+    // new WebSocket("wss://...", ["proto1", "proto2"]) / new WebSocket(url, "proto")
+    static ref NEW_WEBSOCKET: Regex = Regex::new(
+        r#"new\s+WebSocket\(\s*['"`]([^'"`]+)['"`]\s*(?:,\s*(\[[^\]]*\]|['"`][^'"`]+['"`]))?"#
+    ).unwrap();
+
+    // io("https://rt.example.com") / io.connect('/chat') / io()
+    static ref SOCKET_IO_CLIENT: Regex = Regex::new(
+        r#"\bio(?:\.connect)?\(\s*(?:['"`]([^'"`]*)['"`]|\)|\{)"#
+    ).unwrap();
+
+    // Bare ws:// and wss:// literals
+    static ref WS_LITERAL: Regex = Regex::new(r#"['"`](wss?://[^'"`\s]+)['"`]"#).unwrap();
+
+    // receiver.emit("event", ...) / receiver.on('event', ...)
+    static ref SOCKET_EVENT: Regex = Regex::new(
+        r#"\b([A-Za-z_$][\w$]*)\.(emit|on|once)\(\s*['"`]([^'"`\s]+)['"`]"#
+    ).unwrap();
+
+    // Quoted strings inside a subprotocol list
+    static ref QUOTED: Regex = Regex::new(r#"['"`]([^'"`]+)['"`]"#).unwrap();
+}
+
+/// socket.io default mount path, used for `io()` calls without a URL
+const SOCKET_IO_DEFAULT_PATH: &str = "/socket.io/";
+
+/// Lifecycle events every socket client has; not part of the app's message surface
+const LIFECYCLE_EVENTS: [&str; 12] = [
+    "connect",
+    "connection",
+    "disconnect",
+    "disconnecting",
+    "connect_error",
+    "connect_timeout",
+    "reconnect",
+    "reconnect_attempt",
+    "reconnect_error",
+    "reconnect_failed",
+    "error",
+    "open",
+];
+
+/// A socket client found in the code
+struct SocketClient {
+    url: String,
+    transport: &'static str,
+    subprotocols: Vec<String>,
+}
+
+/// Extractor for WebSocket and socket.io clients and their message surface
+///
+/// Records subprotocols passed to `new WebSocket(...)` and the event names
+/// the app emits and listens for, attaching them as endpoint metadata.
+pub struct WebSocketExtractor;
+
+impl WebSocketExtractor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract socket endpoints with event and subprotocol metadata
+    pub fn extract(&self, content: &str, source: Option<&str>) -> Vec<Endpoint> {
+        let clients = self.find_clients(content);
+        if clients.is_empty() {
+            return Vec::new();
+        }
+
+        let uses_socket_io = clients.iter().any(|c| c.transport == "socket.io");
+        let (emitted, received) = self.find_events(content, uses_socket_io);
+
+        clients
+            .into_iter()
+            .map(|client| {
+                let mut metadata = HashMap::new();
+                metadata.insert("transport".to_string(), client.transport.to_string());
+                if !client.subprotocols.is_empty() {
+                    metadata.insert("subprotocols".to_string(), client.subprotocols.join(","));
+                }
+                if !emitted.is_empty() {
+                    metadata.insert("events_emitted".to_string(), emitted.join(","));
+                }
+                if !received.is_empty() {
+                    metadata.insert("events_received".to_string(), received.join(","));
+                }
+
+                let mut endpoint = Endpoint::new(client.url, EndpointType::WebSocket)
+                    .with_metadata(metadata)
+                    .with_confidence(Confidence::High);
+                if let Some(src) = source {
+                    endpoint = endpoint.with_source(src);
+                }
+                endpoint
+            })
+            .collect()
+    }
+
+    /// Find WebSocket and socket.io client URLs
+    fn find_clients(&self, content: &str) -> Vec<SocketClient> {
+        let mut clients: Vec<SocketClient> = Vec::new();
+
+        for cap in NEW_WEBSOCKET.captures_iter(content) {
+            let subprotocols = cap
+                .get(2)
+                .map(|list| {
+                    QUOTED
+                        .captures_iter(list.as_str())
+                        .map(|c| c[1].to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            clients.push(SocketClient {
+                url: cap[1].to_string(),
+                transport: "websocket",
+                subprotocols,
+            });
+        }
+
+        for cap in SOCKET_IO_CLIENT.captures_iter(content) {
+            let url = cap
+                .get(1)
+                .map(|m| m.as_str())
+                .filter(|u| !u.is_empty())
+                .unwrap_or(SOCKET_IO_DEFAULT_PATH)
+                .to_string();
+            if !clients.iter().any(|c| c.url == url) {
+                clients.push(SocketClient {
+                    url,
+                    transport: "socket.io",
+                    subprotocols: Vec::new(),
+                });
+            }
+        }
+
+        for cap in WS_LITERAL.captures_iter(content) {
+            let url = cap[1].to_string();
+            if !clients.iter().any(|c| c.url == url) {
+                clients.push(SocketClient {
+                    url,
+                    transport: "websocket",
+                    subprotocols: Vec::new(),
+                });
+            }
+        }
+
+        clients
+    }
+
+    /// Collect emitted and received event names
+    ///
+    /// `on`/`once` are only trusted on socket-like receivers since DOM and
+    /// jQuery handlers share the same call shape; `emit` is accepted on any
+    /// receiver once socket.io is known to be in use.
+    fn find_events(&self, content: &str, uses_socket_io: bool) -> (Vec<String>, Vec<String>) {
+        let mut emitted: Vec<String> = Vec::new();
+        let mut received: Vec<String> = Vec::new();
+
+        for cap in SOCKET_EVENT.captures_iter(content) {
+            let receiver = cap[1].to_lowercase();
+            let event = cap[3].to_string();
+            if LIFECYCLE_EVENTS.contains(&event.as_str()) {
+                continue;
+            }
+
+            let socket_like = receiver.contains("socket") || receiver == "io" || receiver == "ws";
+            let list = match &cap[2] {
+                "emit" if socket_like || uses_socket_io => &mut emitted,
+                "on" | "once" if socket_like => &mut received,
+                _ => continue,
+            };
+            if !list.contains(&event) {
+                list.push(event);
+            }
+        }
+
+        (emitted, received)
+    }
+}
+
+impl Default for WebSocketExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EndpointExtractor for WebSocketExtractor {
+    fn name(&self) -> &str {
+        "websocket"
+    }
+
+    fn extract(&self, _content: &str, ctx: &AssetContext) -> Vec<Endpoint> {
+        self.extract(ctx.code, ctx.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_socket_io_events() {
+        let extractor = WebSocketExtractor::new();
+        let content = r##"
+            const socket = io("https://rt.example.com", { transports: ["websocket"] });
+            socket.on("connect", () => {});
+            socket.on("order:updated", render);
+            socket.emit("order:create", payload);
+            n.emit("cart:sync", items);
+            $("#btn").on("click", submit);
+        "##;
+
+        let endpoints = extractor.extract(content, Some("app.js"));
+        assert_eq!(endpoints.len(), 1);
+
+        let metadata = endpoints[0].metadata.as_ref().unwrap();
+        assert_eq!(endpoints[0].url, "https://rt.example.com");
+        assert_eq!(endpoints[0].endpoint_type, EndpointType::WebSocket);
+        assert_eq!(metadata["transport"], "socket.io");
+        assert_eq!(metadata["events_emitted"], "order:create,cart:sync");
+        assert_eq!(metadata["events_received"], "order:updated");
+    }
+
+    #[test]
+    fn test_websocket_subprotocols() {
+        let extractor = WebSocketExtractor::new();
+        let content =
+            r#"const ws = new WebSocket("wss://stream.example.com/v1", ["graphql-ws", "json"]);"#;
+
+        let endpoints = extractor.extract(content, None);
+        assert_eq!(endpoints.len(), 1);
+        assert_eq!(
+            endpoints[0].metadata.as_ref().unwrap()["subprotocols"],
+            "graphql-ws,json"
+        );
+        assert!(extractor.extract("fetch('/api')", None).is_empty());
+    }
+}
@@ -0,0 +1,156 @@
+use crate::error::Result;
+use std::io::Read;
+
+/// Bytes shared between consecutive windows
+///
+/// Any match shorter than this that straddles a window boundary is seen
+/// whole in the following window.
+pub const WINDOW_OVERLAP_BYTES: usize = 64 * 1024;
+
+/// Bytes that never occur inside a URL match, so cutting after one of them
+/// cannot truncate a match in the current window
+const CUT_DELIMITERS: &[u8] = b"\n\r\t \"'`";
+
+/// A slice of the input handed to the parser
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Window {
+    /// Window text
+    pub text: String,
+
+    /// Number of lines before the start of the window
+    pub line_offset: usize,
+}
+
+/// Reader that yields overlapping windows of bounded size
+///
+/// Only one window is held in memory at a time, so inputs larger than
+/// memory can be parsed piece by piece.
+pub struct WindowReader<R: Read> {
+    reader: R,
+    buffer: Vec<u8>,
+    window_bytes: usize,
+    overlap_bytes: usize,
+    line_offset: usize,
+    eof: bool,
+}
+
+impl<R: Read> WindowReader<R> {
+    /// Create a reader producing windows of at most `window_bytes`
+    pub fn new(reader: R, window_bytes: usize) -> Self {
+        let window_bytes = window_bytes.max(1024);
+        Self {
+            reader,
+            buffer: Vec::with_capacity(window_bytes),
+            window_bytes,
+            // Keep at least three quarters of each window as fresh input
+            overlap_bytes: WINDOW_OVERLAP_BYTES.min(window_bytes / 4),
+            line_offset: 0,
+            eof: false,
+        }
+    }
+
+    /// Read the next window, or `None` once the input is exhausted
+    pub fn next_window(&mut self) -> Result<Option<Window>> {
+        if self.eof && self.buffer.is_empty() {
+            return Ok(None);
+        }
+
+        let wanted = self.window_bytes - self.buffer.len();
+        let read = (&mut self.reader)
+            .take(wanted as u64)
+            .read_to_end(&mut self.buffer)?;
+        if read < wanted {
+            self.eof = true;
+        }
+        if self.buffer.is_empty() {
+            return Ok(None);
+        }
+
+        let cut = if self.eof {
+            self.buffer.len()
+        } else {
+            self.find_cut()
+        };
+        let window = Window {
+            text: String::from_utf8_lossy(&self.buffer[..cut]).into_owned(),
+            line_offset: self.line_offset,
+        };
+
+        if self.eof {
+            self.buffer.clear();
+        } else {
+            // Start the next window `overlap_bytes` before the cut, on a char boundary
+            let mut next_start = cut.saturating_sub(self.overlap_bytes).max(1);
+            while next_start < cut && is_continuation(self.buffer[next_start]) {
+                next_start += 1;
+            }
+            self.line_offset += count_newlines(&self.buffer[..next_start]);
+            self.buffer.drain(..next_start);
+        }
+
+        Ok(Some(window))
+    }
+
+    /// Where to end the current window
+    ///
+    /// Prefers the last delimiter in the overlap region; otherwise cuts at
+    /// the end of the buffer, backing off an incomplete UTF-8 sequence.
+    fn find_cut(&self) -> usize {
+        let len = self.buffer.len();
+        let search_from = len.saturating_sub(self.overlap_bytes);
+        if let Some(pos) = self.buffer[search_from..]
+            .iter()
+            .rposition(|b| CUT_DELIMITERS.contains(b))
+        {
+            return search_from + pos + 1;
+        }
+
+        match std::str::from_utf8(&self.buffer) {
+            Err(e) if e.error_len().is_none() && e.valid_up_to() > 0 => e.valid_up_to(),
+            _ => len,
+        }
+    }
+}
+
+fn is_continuation(b: u8) -> bool {
+    b & 0xC0 == 0x80
+}
+
+fn count_newlines(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&b| b == b'\n').count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_overlap_and_track_lines() {
+        let mut content = String::new();
+        for i in 0..400 {
+            content.push_str(&format!("fetch(\"/api/v1/item/{}\");\n", i));
+        }
+
+        let mut reader = WindowReader::new(content.as_bytes(), 2048);
+        let mut windows = Vec::new();
+        while let Some(window) = reader.next_window().unwrap() {
+            assert!(window.text.len() <= 2048);
+            windows.push(window);
+        }
+
+        assert!(windows.len() > 1);
+        assert_eq!(windows[0].line_offset, 0);
+        for pair in windows.windows(2) {
+            let tail = &pair[0].text[pair[0].text.len() - 64..];
+            assert!(pair[1].text.contains(tail));
+            // Line offsets point at the same line in the original content
+            let first_line = pair[1].text.lines().next().unwrap();
+            let original = content.lines().nth(pair[1].line_offset).unwrap();
+            assert!(original.ends_with(first_line));
+        }
+        for i in 0..400 {
+            let url = format!("\"/api/v1/item/{}\"", i);
+            assert!(windows.iter().any(|w| w.text.contains(&url)));
+        }
+    }
+}
@@ -0,0 +1,393 @@
+use crate::parser::sse::floor_char_boundary;
+use crate::types::{Confidence, Endpoint, EndpointType};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+
+lazy_static! {
+    // function apiCall(path, ...) / const apiCall = (path) => / x.apiCall = function (path)
+    static ref WRAPPER_DEF: Regex = Regex::new(
+        r"(?:\bfunction\s+([A-Za-z_$][\w$]*)\s*\(\s*([A-Za-z_$][\w$]*)|([A-Za-z_$][\w$]*)\s*[:=]\s*(?:async\s+)?(?:function\s*[\w$]*\s*\(\s*([A-Za-z_$][\w$]*)|\(\s*([A-Za-z_$][\w$]*)[^()]*\)\s*=>|([A-Za-z_$][\w$]*)\s*=>))"
+    ).unwrap();
+
+    // The HTTP call inside a wrapper body
+    static ref CLIENT_CALL: Regex = Regex::new(
+        r"\b(?:fetch|axios(?:\.(get|post|put|patch|delete|head))?|\$\.ajax|(?:this\.)?http\.(get|post|put|patch|delete|head)|request)\s*\("
+    ).unwrap();
+
+    static ref METHOD_OPTION: Regex =
+        Regex::new(r#"\bmethod\s*:\s*['"`]([A-Za-z]+)['"`]"#).unwrap();
+    static ref URL_OPTION: Regex = Regex::new(r"\burl\s*:\s*").unwrap();
+
+    // const API_BASE = "https://api.example.com"
+    static ref STRING_CONST: Regex = Regex::new(
+        r#"\b(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*=\s*['"`]([^'"`$]*)['"`]"#
+    ).unwrap();
+    // { apiBase: "https://api.example.com" }
+    static ref URL_PROPERTY: Regex =
+        Regex::new(r#"\b([A-Za-z_$][\w$]*)\s*:\s*['"`](https?://[^'"`\s]+)['"`]"#).unwrap();
+}
+
+/// How much of a wrapper's body is searched for the HTTP call
+const WRAPPER_BODY_LIMIT: usize = 600;
+
+/// Minified names this short collide across chunks
+const MIN_WRAPPER_NAME_LEN: usize = 3;
+
+/// Names that are HTTP clients or common methods rather than app wrappers
+const RESERVED_NAMES: [&str; 12] = [
+    "fetch", "request", "get", "post", "put", "patch", "delete", "head", "then", "catch", "map",
+    "ajax",
+];
+
+const HTTP_METHODS: [&str; 7] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+/// A function that forwards its first argument to an HTTP client
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrapperDef {
+    /// Name the wrapper is called by
+    pub name: String,
+
+    /// Base URL prepended to the path, when it could be resolved
+    pub base: Option<String>,
+
+    /// HTTP method fixed by the wrapper, if any
+    pub method: Option<String>,
+
+    /// Asset the wrapper was defined in
+    pub source: Option<String>,
+}
+
+/// Finds HTTP wrapper functions so their calls can be resolved across assets
+///
+/// Code-split apps often define a helper like `apiCall(path)` in a vendor
+/// chunk and call it with literal paths from app chunks. Collect
+/// definitions from every asset with `find_wrappers`, then resolve calls
+/// in each asset with a `WrapperSet` built from the combined list.
+pub struct WrapperResolver;
+
+impl WrapperResolver {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Find wrapper definitions in an asset
+    pub fn find_wrappers(&self, content: &str, source: Option<&str>) -> Vec<WrapperDef> {
+        let constants = string_constants(content);
+        let mut wrappers: Vec<WrapperDef> = Vec::new();
+
+        for cap in WRAPPER_DEF.captures_iter(content) {
+            let (Some(name), Some(param)) = (
+                cap.get(1).or_else(|| cap.get(3)),
+                cap.get(2)
+                    .or_else(|| cap.get(4))
+                    .or_else(|| cap.get(5))
+                    .or_else(|| cap.get(6)),
+            ) else {
+                continue;
+            };
+            let name = name.as_str();
+            if name.len() < MIN_WRAPPER_NAME_LEN
+                || RESERVED_NAMES.contains(&name)
+                || wrappers.iter().any(|w| w.name == name)
+            {
+                continue;
+            }
+
+            let start = cap.get(0).map_or(0, |m| m.end());
+            let end = floor_char_boundary(content, (start + WRAPPER_BODY_LIMIT).min(content.len()));
+            let body = &content[start..end];
+
+            if let Some((base, method)) = forwarded_call(body, param.as_str(), &constants) {
+                wrappers.push(WrapperDef {
+                    name: name.to_string(),
+                    base,
+                    method,
+                    source: source.map(str::to_string),
+                });
+            }
+        }
+
+        wrappers
+    }
+}
+
+impl Default for WrapperResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Wrapper definitions compiled for matching their call sites
+pub struct WrapperSet {
+    wrappers: HashMap<String, WrapperDef>,
+    calls: Option<Regex>,
+}
+
+impl WrapperSet {
+    /// Compile a set of wrappers; the first definition of a name wins
+    pub fn new(defs: Vec<WrapperDef>) -> Self {
+        let mut wrappers: HashMap<String, WrapperDef> = HashMap::new();
+        for def in defs {
+            wrappers.entry(def.name.clone()).or_insert(def);
+        }
+
+        // Matches `apiCall("/x")`, `api.apiCall("/x")` and `Object(r.apiCall)("/x")`
+        let mut names: Vec<String> = wrappers.keys().map(|n| regex::escape(n)).collect();
+        names.sort();
+        let calls = (!names.is_empty())
+            .then(|| {
+                Regex::new(&format!(
+                    r#"(?:^|[^\w$])({})\s*\)?\s*\(\s*['"`]([^'"`]+)['"`]\s*(?:,\s*['"`]([A-Za-z]+)['"`])?"#,
+                    names.join("|")
+                ))
+                .ok()
+            })
+            .flatten();
+
+        Self { wrappers, calls }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.wrappers.is_empty()
+    }
+
+    /// Endpoints from calls to the wrappers with a literal path in this asset
+    pub fn resolve(&self, content: &str, source: Option<&str>) -> Vec<Endpoint> {
+        let Some(calls) = &self.calls else {
+            return Vec::new();
+        };
+        let mut endpoints = Vec::new();
+
+        for cap in calls.captures_iter(content) {
+            let wrapper = &self.wrappers[&cap[1]];
+            let path = &cap[2];
+            if !path.starts_with('/') && !path.starts_with("http") && wrapper.base.is_none() {
+                continue;
+            }
+
+            let url = match &wrapper.base {
+                Some(base) if !path.starts_with("http") => join(base, path),
+                _ => path.to_string(),
+            };
+            let method = cap
+                .get(3)
+                .map(|m| m.as_str().to_uppercase())
+                .filter(|m| HTTP_METHODS.contains(&m.as_str()))
+                .or_else(|| wrapper.method.clone());
+
+            endpoints.push(endpoint(url, method, wrapper, source));
+        }
+
+        endpoints
+    }
+}
+
+fn endpoint(
+    url: String,
+    method: Option<String>,
+    wrapper: &WrapperDef,
+    source: Option<&str>,
+) -> Endpoint {
+    let endpoint_type = if url.contains("graphql") {
+        EndpointType::GraphQL
+    } else {
+        EndpointType::Rest
+    };
+
+    let mut metadata = HashMap::new();
+    metadata.insert("wrapper".to_string(), wrapper.name.clone());
+    if let Some(defined_in) = &wrapper.source {
+        metadata.insert("wrapper_source".to_string(), defined_in.clone());
+    }
+
+    let mut endpoint = Endpoint::new(url, endpoint_type)
+        .with_confidence(Confidence::Medium)
+        .with_metadata(metadata)
+        .with_tag("wrapper");
+    if let Some(method) = method {
+        endpoint = endpoint.with_method(method);
+    }
+    if let Some(src) = source {
+        endpoint = endpoint.with_source(src);
+    }
+    endpoint
+}
+
+/// Base URL and method of the first HTTP call in `body` that uses `param`
+///
+/// Returns `None` when the body doesn't forward the parameter to a client.
+fn forwarded_call(
+    body: &str,
+    param: &str,
+    constants: &HashMap<String, String>,
+) -> Option<(Option<String>, Option<String>)> {
+    let param_re = Regex::new(&format!(
+        r"(?:^|[^\w$.])({})(?:[^\w$]|$)",
+        regex::escape(param)
+    ))
+    .ok()?;
+
+    for cap in CLIENT_CALL.captures_iter(body) {
+        let args_start = cap.get(0)?.end();
+        let args = &body[args_start..];
+        let Some(found) = param_re.captures(args).and_then(|c| c.get(1)) else {
+            continue;
+        };
+        // The param has to be part of this call's arguments
+        if args[..found.start()].contains([')', ';']) {
+            continue;
+        }
+
+        let mut prefix = &args[..found.start()];
+        if let Some(url_option) = URL_OPTION.find_iter(prefix).last() {
+            prefix = &prefix[url_option.end()..];
+        }
+        let prefix = prefix.trim_start_matches('{');
+
+        let method = cap
+            .get(1)
+            .or_else(|| cap.get(2))
+            .map(|m| m.as_str().to_uppercase())
+            .or_else(|| METHOD_OPTION.captures(args).map(|m| m[1].to_uppercase()));
+
+        return Some((resolve_prefix(prefix, constants), method));
+    }
+
+    None
+}
+
+/// Evaluate the string expression before the path argument
+///
+/// Handles literals, known constants and template literals joined with
+/// `+`; anything else leaves the base unresolved.
+fn resolve_prefix(prefix: &str, constants: &HashMap<String, String>) -> Option<String> {
+    let prefix = prefix.trim().trim_end_matches("${").trim_end();
+    let prefix = prefix.trim_end_matches('+').trim();
+    if prefix.is_empty() {
+        return None;
+    }
+
+    let mut base = String::new();
+    for token in prefix.split('+').map(str::trim) {
+        if let Some(template) = token.strip_prefix('`') {
+            base.push_str(&resolve_template(
+                template.trim_end_matches('`'),
+                constants,
+            )?);
+        } else if token.len() >= 2
+            && (token.starts_with('"') || token.starts_with('\''))
+            && token.ends_with(&token[..1])
+        {
+            base.push_str(&token[1..token.len() - 1]);
+        } else {
+            base.push_str(lookup(token, constants)?);
+        }
+    }
+
+    (!base.is_empty()).then_some(base)
+}
+
+/// Evaluate a template literal body made of text and `${CONSTANT}` parts
+fn resolve_template(template: &str, constants: &HashMap<String, String>) -> Option<String> {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find("${") {
+        out.push_str(&rest[..open]);
+        let close = rest[open..].find('}')? + open;
+        out.push_str(lookup(rest[open + 2..close].trim(), constants)?);
+        rest = &rest[close + 1..];
+    }
+    out.push_str(rest);
+    Some(out)
+}
+
+/// Value of a constant, by its last member segment (`config.API_BASE`)
+fn lookup<'a>(expr: &str, constants: &'a HashMap<String, String>) -> Option<&'a str> {
+    let name = expr.rsplit('.').next().unwrap_or(expr);
+    constants.get(name).map(String::as_str)
+}
+
+fn string_constants(content: &str) -> HashMap<String, String> {
+    let mut constants = HashMap::new();
+    for cap in STRING_CONST
+        .captures_iter(content)
+        .chain(URL_PROPERTY.captures_iter(content))
+    {
+        constants
+            .entry(cap[1].to_string())
+            .or_insert_with(|| cap[2].to_string());
+    }
+    constants
+}
+
+/// Join a base URL and a path with exactly one slash between
+fn join(base: &str, path: &str) -> String {
+    match (base.ends_with('/'), path.starts_with('/')) {
+        (true, true) => format!("{}{}", base, &path[1..]),
+        (false, false) => format!("{}/{}", base, path),
+        _ => format!("{}{}", base, path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrappers_resolve_across_assets() {
+        let resolver = WrapperResolver::new();
+        let vendor = r#"
+            const API_BASE = "https://api.example.com/v1";
+            export function apiCall(path, options) {
+                return fetch(API_BASE + path, options).then(r => r.json());
+            }
+            export const postJson = async (endpoint, body) => {
+                return axios.post(`${API_BASE}/internal${endpoint}`, body);
+            };
+            function fmt(s) { return s.trim(); }
+        "#;
+        let app = r#"
+            function getJson(url) { return fetch(url).then(r => r.json()); }
+            getJson("/api/health");
+            apiCall("/users/me");
+            Object(o.apiCall)('/orders', "DELETE");
+            postJson("/events", payload);
+            fmt("/not/an/endpoint");
+        "#;
+
+        let mut wrappers = resolver.find_wrappers(vendor, Some("vendor.js"));
+        assert_eq!(wrappers.len(), 2);
+        assert_eq!(
+            wrappers[0].base.as_deref(),
+            Some("https://api.example.com/v1")
+        );
+        assert_eq!(wrappers[1].method.as_deref(), Some("POST"));
+
+        // A wrapper passing the path straight through has no base
+        let local = resolver.find_wrappers(app, Some("app.js"));
+        assert_eq!(local[0].name, "getJson");
+        assert_eq!(local[0].base, None);
+        wrappers.extend(local);
+
+        let endpoints = WrapperSet::new(wrappers).resolve(app, Some("app.js"));
+        let found: Vec<(&str, Option<&str>)> = endpoints
+            .iter()
+            .map(|e| (e.url.as_str(), e.method.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("/api/health", None),
+                ("https://api.example.com/v1/users/me", None),
+                ("https://api.example.com/v1/orders", Some("DELETE")),
+                ("https://api.example.com/v1/internal/events", Some("POST")),
+            ]
+        );
+        assert_eq!(endpoints[1].source.as_deref(), Some("app.js"));
+        assert_eq!(
+            endpoints[1].metadata.as_ref().unwrap()["wrapper_source"],
+            "vendor.js"
+        );
+    }
+}
@@ -0,0 +1,479 @@
+//! Passive reconnaissance from third-party archives
+//!
+//! `endpointo passive` collects URLs a domain has served from archives
+//! that crawled it earlier — the Wayback Machine's CDX API, the latest
+//! Common Crawl index, and urlscan.io's search — without sending a single
+//! request to the target. URLs outside the domain and static files (images,
+//! fonts, stylesheets, media) are dropped, and the rest become endpoints
+//! whose `source` names the archive they came from.
+
+use crate::error::{Error, Result};
+use crate::parser::filters::EndpointFilter;
+use crate::parser::params::build_wordlist;
+use crate::parser::patterns::PatternMatcher;
+use crate::scanner::endpoint_stats;
+use crate::types::{Endpoint, ScanResult, SCHEMA_VERSION, TOOL_VERSION};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tracing::{debug, info};
+use url::Url;
+
+/// URLs requested from each source unless told otherwise
+pub const DEFAULT_LIMIT: usize = 10_000;
+
+/// Seconds to wait for each source unless told otherwise; archives are slow
+const DEFAULT_TIMEOUT: u64 = 60;
+
+/// Extensions of files that can't be API endpoints
+const STATIC_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "svg", "ico", "webp", "bmp", "css", "woff", "woff2", "ttf", "eot",
+    "otf", "mp4", "mp3", "webm", "avi", "mov", "wav",
+];
+
+/// An archive URLs are collected from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PassiveSource {
+    /// The Wayback Machine's CDX API
+    Wayback,
+    /// The most recent Common Crawl index
+    CommonCrawl,
+    /// urlscan.io's search API
+    Urlscan,
+}
+
+impl PassiveSource {
+    pub const ALL: [PassiveSource; 3] = [
+        PassiveSource::Wayback,
+        PassiveSource::CommonCrawl,
+        PassiveSource::Urlscan,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PassiveSource::Wayback => "wayback",
+            PassiveSource::CommonCrawl => "commoncrawl",
+            PassiveSource::Urlscan => "urlscan",
+        }
+    }
+
+    /// Where the source's API lives
+    fn default_base(&self) -> &'static str {
+        match self {
+            PassiveSource::Wayback => "https://web.archive.org",
+            PassiveSource::CommonCrawl => "https://index.commoncrawl.org",
+            PassiveSource::Urlscan => "https://urlscan.io",
+        }
+    }
+}
+
+impl std::str::FromStr for PassiveSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "wayback" | "archive" => Ok(PassiveSource::Wayback),
+            "commoncrawl" | "cc" => Ok(PassiveSource::CommonCrawl),
+            "urlscan" => Ok(PassiveSource::Urlscan),
+            _ => Err(format!(
+                "unknown passive source '{}' (expected wayback, commoncrawl or urlscan)",
+                s
+            )),
+        }
+    }
+}
+
+/// Domain to look up from a bare domain or a URL
+pub fn parse_domain(input: &str) -> Result<String> {
+    let input = input.trim();
+    let host = if input.contains("://") {
+        Url::parse(input)?.host_str().map(str::to_string)
+    } else {
+        Some(input.trim_end_matches('/').to_string())
+    };
+    match host {
+        Some(host) if !host.is_empty() && !host.contains(['/', ' ', '*']) => {
+            Ok(host.trim_start_matches("www.").to_ascii_lowercase())
+        }
+        _ => Err(Error::ValidationError(format!(
+            "expected a domain such as example.com: {}",
+            input
+        ))),
+    }
+}
+
+/// Collects a domain's URLs from passive sources
+pub struct PassiveRecon {
+    sources: Vec<PassiveSource>,
+    bases: HashMap<PassiveSource, String>,
+    subdomains: bool,
+    limit: usize,
+    include_static: bool,
+    urlscan_key: Option<String>,
+    client: reqwest::Client,
+    timeout: Duration,
+    requests_made: AtomicUsize,
+}
+
+impl Default for PassiveRecon {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PassiveRecon {
+    /// Query every source, subdomains included
+    pub fn new() -> Self {
+        Self {
+            sources: PassiveSource::ALL.to_vec(),
+            bases: HashMap::new(),
+            subdomains: true,
+            limit: DEFAULT_LIMIT,
+            include_static: false,
+            urlscan_key: None,
+            client: reqwest::Client::new(),
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT),
+            requests_made: AtomicUsize::new(0),
+        }
+    }
+
+    /// Query only these sources (all when empty)
+    pub fn with_sources(mut self, sources: Vec<PassiveSource>) -> Self {
+        if !sources.is_empty() {
+            self.sources = sources;
+        }
+        self
+    }
+
+    /// Send a source's requests to another base URL, e.g. a mirror
+    pub fn with_base_url(mut self, source: PassiveSource, base: impl Into<String>) -> Self {
+        self.bases.insert(source, base.into());
+        self
+    }
+
+    /// Whether to include URLs on subdomains
+    pub fn with_subdomains(mut self, subdomains: bool) -> Self {
+        self.subdomains = subdomains;
+        self
+    }
+
+    /// Ask each source for at most this many URLs
+    pub fn with_limit(mut self, limit: Option<usize>) -> Self {
+        if let Some(l) = limit {
+            self.limit = l.max(1);
+        }
+        self
+    }
+
+    /// Keep images, fonts, stylesheets and media too
+    pub fn with_static(mut self, include: bool) -> Self {
+        self.include_static = include;
+        self
+    }
+
+    /// urlscan.io API key, for higher quotas and private results
+    pub fn with_urlscan_key(mut self, key: Option<String>) -> Self {
+        self.urlscan_key = key.filter(|k| !k.is_empty());
+        self
+    }
+
+    /// Give up on a source after this many seconds
+    pub fn with_timeout(mut self, seconds: Option<u64>) -> Self {
+        if let Some(s) = seconds {
+            self.timeout = Duration::from_secs(s);
+        }
+        self
+    }
+
+    /// Sources that will be queried
+    pub fn sources(&self) -> &[PassiveSource] {
+        &self.sources
+    }
+
+    /// Number of requests sent to the sources so far
+    pub fn requests_made(&self) -> usize {
+        self.requests_made.load(Ordering::Relaxed)
+    }
+
+    /// Collect endpoints for `domain`, with each source's outcome
+    ///
+    /// A failing source doesn't stop the others; its error is returned in
+    /// place of its URL count.
+    pub async fn collect(
+        &self,
+        domain: &str,
+    ) -> (Vec<Endpoint>, Vec<(PassiveSource, Result<usize>)>) {
+        let matcher = PatternMatcher::new();
+        let mut endpoints = Vec::new();
+        let mut outcomes = Vec::new();
+        for &source in &self.sources {
+            info!("Querying {} for {}", source.as_str(), domain);
+            let urls = match self.fetch(source, domain).await {
+                Ok(urls) => urls,
+                Err(e) => {
+                    debug!("{} failed: {}", source.as_str(), e);
+                    outcomes.push((source, Err(e)));
+                    continue;
+                }
+            };
+            let kept: Vec<String> = urls
+                .into_iter()
+                .filter(|url| self.keep(url, domain))
+                .collect();
+            outcomes.push((source, Ok(kept.len())));
+            endpoints.extend(kept.into_iter().map(|url| {
+                let endpoint_type = matcher.detect_endpoint_type(&url, "");
+                Endpoint::new(url, endpoint_type).with_source(source.as_str())
+            }));
+        }
+        EndpointFilter::new().deduplicate(&mut endpoints);
+        (endpoints, outcomes)
+    }
+
+    /// Whether a collected URL belongs in the results
+    fn keep(&self, url: &str, domain: &str) -> bool {
+        let Ok(parsed) = Url::parse(url) else {
+            return false;
+        };
+        let Some(host) = parsed.host_str().map(str::to_ascii_lowercase) else {
+            return false;
+        };
+        let host = host.trim_start_matches("www.");
+        let in_scope =
+            host == domain || (self.subdomains && host.ends_with(&format!(".{}", domain)));
+        if !in_scope || !matches!(parsed.scheme(), "http" | "https") {
+            return false;
+        }
+        if self.include_static {
+            return true;
+        }
+        let extension = parsed
+            .path()
+            .rsplit_once('.')
+            .map(|(_, ext)| ext.to_ascii_lowercase());
+        !extension.is_some_and(|ext| STATIC_EXTENSIONS.contains(&ext.as_str()))
+    }
+
+    fn base(&self, source: PassiveSource) -> &str {
+        self.bases
+            .get(&source)
+            .map(String::as_str)
+            .unwrap_or(source.default_base())
+            .trim_end_matches('/')
+    }
+
+    /// Fetch raw URLs from one source
+    async fn fetch(&self, source: PassiveSource, domain: &str) -> Result<Vec<String>> {
+        let pattern = if self.subdomains {
+            format!("*.{}/*", domain)
+        } else {
+            format!("{}/*", domain)
+        };
+        let limit = self.limit.to_string();
+        match source {
+            PassiveSource::Wayback => {
+                let url = format!("{}/cdx/search/cdx", self.base(source));
+                let body = self
+                    .get(
+                        source,
+                        &url,
+                        &[
+                            ("url", pattern.as_str()),
+                            ("fl", "original"),
+                            ("collapse", "urlkey"),
+                            ("output", "text"),
+                            ("limit", limit.as_str()),
+                        ],
+                    )
+                    .await?
+                    .text()
+                    .await?;
+                Ok(body
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string)
+                    .collect())
+            }
+            PassiveSource::CommonCrawl => {
+                let collections: Vec<Collection> = self
+                    .get(source, &format!("{}/collinfo.json", self.base(source)), &[])
+                    .await?
+                    .json()
+                    .await?;
+                let latest = collections
+                    .first()
+                    .ok_or_else(|| Error::Other("Common Crawl listed no indexes".to_string()))?;
+                let response = self
+                    .send(
+                        source,
+                        &latest.cdx_api,
+                        &[
+                            ("url", pattern.as_str()),
+                            ("fl", "url"),
+                            ("output", "json"),
+                            ("limit", limit.as_str()),
+                        ],
+                    )
+                    .await?;
+                // The index answers 404 when it has no captures for the domain
+                if response.status() == reqwest::StatusCode::NOT_FOUND {
+                    return Ok(Vec::new());
+                }
+                let body = check_status(source, response)?.text().await?;
+                Ok(body
+                    .lines()
+                    .filter_map(|line| serde_json::from_str::<Capture>(line).ok())
+                    .map(|capture| capture.url)
+                    .collect())
+            }
+            PassiveSource::Urlscan => {
+                let field = if self.subdomains {
+                    "domain"
+                } else {
+                    "page.domain"
+                };
+                let query = format!("{}:{}", field, domain);
+                let size = self.limit.min(10_000).to_string();
+                let search: UrlscanSearch = self
+                    .get(
+                        source,
+                        &format!("{}/api/v1/search/", self.base(source)),
+                        &[("q", query.as_str()), ("size", size.as_str())],
+                    )
+                    .await?
+                    .json()
+                    .await?;
+                Ok(search
+                    .results
+                    .into_iter()
+                    .flat_map(|r| [r.page.and_then(|p| p.url), r.task.and_then(|t| t.url)])
+                    .flatten()
+                    .collect())
+            }
+        }
+    }
+
+    /// Send a GET to one of a source's URLs
+    async fn send(
+        &self,
+        source: PassiveSource,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> Result<reqwest::Response> {
+        let mut request = self.client.get(url).query(query).timeout(self.timeout);
+        if let (PassiveSource::Urlscan, Some(key)) = (source, &self.urlscan_key) {
+            request = request.header("API-Key", key);
+        }
+        self.requests_made.fetch_add(1, Ordering::Relaxed);
+        Ok(request.send().await?)
+    }
+
+    /// Like [`send`](Self::send), but failing on an error status
+    async fn get(
+        &self,
+        source: PassiveSource,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> Result<reqwest::Response> {
+        check_status(source, self.send(source, url, query).await?)
+    }
+}
+
+/// Turn an error status from `source` into an error
+fn check_status(source: PassiveSource, response: reqwest::Response) -> Result<reqwest::Response> {
+    if !response.status().is_success() {
+        return Err(Error::Other(format!(
+            "{} returned {}",
+            source.as_str(),
+            response.status()
+        )));
+    }
+    Ok(response)
+}
+
+/// A scan result of passively collected endpoints for `domain`
+pub fn passive_result(
+    domain: &str,
+    started: DateTime<Utc>,
+    endpoints: Vec<Endpoint>,
+    requests_made: usize,
+) -> ScanResult {
+    let mut stats = endpoint_stats(&endpoints);
+    stats.requests_made = requests_made;
+    stats.duration_seconds = (Utc::now() - started)
+        .to_std()
+        .unwrap_or_default()
+        .as_secs_f64();
+
+    ScanResult {
+        schema_version: SCHEMA_VERSION,
+        tool_version: TOOL_VERSION.to_string(),
+        target: domain.to_string(),
+        timestamp: started.to_rfc3339(),
+        total_endpoints: endpoints.len(),
+        findings: Vec::new(),
+        wordlist: build_wordlist(&endpoints),
+        technologies: Vec::new(),
+        stats: Some(stats),
+        endpoints,
+    }
+}
+
+/// A Common Crawl index
+#[derive(Deserialize)]
+struct Collection {
+    #[serde(rename = "cdx-api")]
+    cdx_api: String,
+}
+
+/// A Common Crawl capture
+#[derive(Deserialize)]
+struct Capture {
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct UrlscanSearch {
+    #[serde(default)]
+    results: Vec<UrlscanResult>,
+}
+
+#[derive(Deserialize)]
+struct UrlscanResult {
+    page: Option<UrlscanUrl>,
+    task: Option<UrlscanUrl>,
+}
+
+#[derive(Deserialize)]
+struct UrlscanUrl {
+    url: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_and_static_filtering() {
+        assert_eq!(
+            parse_domain("https://www.Example.com/app").unwrap(),
+            "example.com"
+        );
+        assert_eq!(parse_domain("example.com").unwrap(), "example.com");
+        assert!(parse_domain("*.example.com").is_err());
+
+        let recon = PassiveRecon::new();
+        assert!(recon.keep("https://example.com/api/users?id=1", "example.com"));
+        assert!(recon.keep("http://api.example.com/v1", "example.com"));
+        assert!(!recon.keep("https://example.com/logo.PNG", "example.com"));
+        assert!(!recon.keep("https://notexample.com/api", "example.com"));
+        assert!(!recon.keep("ftp://example.com/file", "example.com"));
+
+        let recon = PassiveRecon::new().with_subdomains(false).with_static(true);
+        assert!(!recon.keep("http://api.example.com/v1", "example.com"));
+        assert!(recon.keep("https://www.example.com/logo.png", "example.com"));
+    }
+}
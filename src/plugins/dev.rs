@@ -0,0 +1,746 @@
+//! Tools for writing plugins: listing, test runs and scaffolding
+//!
+//! A scan logs plugin errors and carries on, which makes a broken hook
+//! easy to miss. `endpointo plugins test` runs a plugin against sample
+//! endpoints instead and reports every call that failed.
+
+#[cfg(feature = "python-plugins")]
+use super::python::{dict_to_endpoint, endpoint_to_dict_in, PythonPlugin};
+#[cfg(feature = "python-plugins")]
+use pyo3::prelude::*;
+#[cfg(feature = "python-plugins")]
+use pyo3::types::PyDict;
+
+use super::native::is_library;
+#[cfg(feature = "wasm-plugins")]
+use super::WasmPlugin;
+use super::{
+    check_discovered, check_generated, is_wasm, AssetInfo, NativePlugin, PLUGIN_API_VERSION,
+};
+use crate::error::{Error, Result};
+use crate::types::{Endpoint, EndpointType};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// Hooks endpointo calls, in the order it calls them
+pub const HOOKS: &[&str] = &[
+    "discover_urls",
+    "generate_endpoints",
+    "filter_endpoint",
+    "transform_endpoint",
+    "write_output",
+];
+
+/// Target `plugins test` passes to `discover_urls`
+pub const SAMPLE_TARGET: &str = "https://example.com/";
+
+/// Asset `plugins test` passes to `generate_endpoints`, as its name and content
+pub const SAMPLE_ASSET: (&str, &str) = (
+    "https://example.com/static/app.js",
+    r#"const api = axios.create({ baseURL: "/api/v1" });
+api.get("/users");
+fetch("https://example.com/api/v2/orders?id=1", { method: "POST" });
+// route: /internal/reports
+"#,
+);
+
+lazy_static::lazy_static! {
+    static ref TOP_LEVEL_DEF: Regex = Regex::new(r"(?m)^def\s+([A-Za-z_][A-Za-z0-9_]*)\s*\(").unwrap();
+    static ref API_DECLARATION: Regex =
+        Regex::new(r"(?m)^ENDPOINTO_PLUGIN_API\s*=\s*(\d+)\s*(?:#.*)?$").unwrap();
+    static ref PLUGIN_NAME: Regex = Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").unwrap();
+}
+
+/// A plugin file and the hooks it defines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginInfo {
+    pub path: PathBuf,
+    /// Hooks a Python plugin defines; a native library's or WASM module's
+    /// aren't listed
+    pub hooks: Vec<&'static str>,
+    /// Plugin API version a Python plugin declares, if it declares one
+    pub api_version: Option<u32>,
+    /// Whether the file is a native plugin library
+    pub native: bool,
+    /// Whether the file is a WASM module
+    pub wasm: bool,
+}
+
+/// Hooks a plugin's source defines at the top level
+///
+/// This reads the source rather than running it, so it works in builds
+/// without Python support, but it won't see hooks assigned at runtime.
+pub fn hooks_in(source: &str) -> Vec<&'static str> {
+    let defined: Vec<&str> = TOP_LEVEL_DEF
+        .captures_iter(source)
+        .filter_map(|c| c.get(1).map(|m| m.as_str()))
+        .collect();
+    HOOKS
+        .iter()
+        .copied()
+        .filter(|hook| defined.contains(hook))
+        .collect()
+}
+
+/// The plugin API version a plugin's source declares as
+/// `ENDPOINTO_PLUGIN_API`, read the way [`hooks_in`] reads hooks
+pub fn api_version_in(source: &str) -> Option<u32> {
+    API_DECLARATION
+        .captures(source)
+        .and_then(|c| c[1].parse().ok())
+}
+
+/// The `.py` plugins, native plugin libraries and `.wasm` modules in a
+/// directory, sorted by file name
+///
+/// Libraries and modules aren't loaded, since loading one runs its code.
+pub fn list_plugins(dir: &Path) -> Result<Vec<PluginInfo>> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| Error::ValidationError(format!("{}: {}", dir.display(), e)))?;
+    let mut plugins = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        if path.extension().is_some_and(|ext| ext == "py") {
+            let source = std::fs::read_to_string(&path)?;
+            plugins.push(PluginInfo {
+                hooks: hooks_in(&source),
+                api_version: api_version_in(&source),
+                path,
+                native: false,
+                wasm: false,
+            });
+        } else if is_library(&path) || is_wasm(&path) {
+            plugins.push(PluginInfo {
+                hooks: Vec::new(),
+                api_version: None,
+                native: is_library(&path),
+                wasm: is_wasm(&path),
+                path,
+            });
+        }
+    }
+    plugins.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(plugins)
+}
+
+/// The asset `plugins test` says the endpoints from [`SAMPLE_ASSET`] were
+/// found in, as if it had been fetched
+pub fn sample_asset() -> AssetInfo {
+    let (source, content) = SAMPLE_ASSET;
+    AssetInfo {
+        final_url: Some(source.to_string()),
+        status: Some(200),
+        content_type: Some("application/javascript".to_string()),
+        headers: [(
+            "content-type".to_string(),
+            "application/javascript".to_string(),
+        )]
+        .into_iter()
+        .collect(),
+        ..AssetInfo::from_content(source, content.as_bytes())
+    }
+}
+
+/// The sample asset if an endpoint was found in it
+fn asset_of<'a>(endpoint: &Endpoint, asset: &'a AssetInfo) -> Option<&'a AssetInfo> {
+    (endpoint.source.as_deref() == Some(asset.url.as_str())).then_some(asset)
+}
+
+/// Endpoints `plugins test` runs a plugin against when given none
+pub fn sample_endpoints() -> Vec<Endpoint> {
+    vec![
+        Endpoint::new("/api/v1/users".to_string(), EndpointType::Rest)
+            .with_method("GET".to_string())
+            .with_source("https://example.com/static/app.js".to_string())
+            .with_line(12),
+        Endpoint::new(
+            "https://example.com/api/v2/orders?id=1&status=open".to_string(),
+            EndpointType::Rest,
+        )
+        .with_method("POST".to_string())
+        .with_params(vec!["id".to_string(), "status".to_string()]),
+        Endpoint::new("/admin/settings".to_string(), EndpointType::Rest),
+        Endpoint::new("/graphql".to_string(), EndpointType::GraphQL)
+            .with_method("POST".to_string()),
+        Endpoint::new(
+            "wss://example.com/socket".to_string(),
+            EndpointType::WebSocket,
+        ),
+        Endpoint::new("/events/stream".to_string(), EndpointType::Sse),
+    ]
+}
+
+/// What one hook did during a test run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookReport {
+    pub hook: &'static str,
+    pub defined: bool,
+    pub calls: usize,
+    /// Failed calls, each with the endpoint URL it failed on
+    pub errors: Vec<String>,
+}
+
+/// What a plugin did to the endpoints it was tested against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginTest {
+    /// Plugin API version the plugin targets
+    pub api_version: u32,
+    pub hooks: Vec<HookReport>,
+    pub endpoints: usize,
+    /// URLs `discover_urls` returned for the sample target that passed
+    /// [`check_discovered`](super::check_discovered)
+    pub discovered: usize,
+    /// Endpoints `generate_endpoints` returned for the sample asset that
+    /// passed [`check_generated`](super::check_generated)
+    pub generated: usize,
+    /// Endpoints `filter_endpoint` dropped
+    pub dropped: usize,
+    /// Endpoints `transform_endpoint` changed
+    pub changed: usize,
+}
+
+impl PluginTest {
+    /// Every failed call across the hooks
+    pub fn errors(&self) -> usize {
+        self.hooks.iter().map(|h| h.errors.len()).sum()
+    }
+}
+
+/// Run a plugin's hooks against endpoints the way a scan would, recording
+/// each failure instead of skipping past it
+pub fn test_plugin(path: &Path, endpoints: &[Endpoint]) -> Result<PluginTest> {
+    if is_library(path) {
+        test_native_plugin(&NativePlugin::load(path)?, endpoints)
+    } else if is_wasm(path) {
+        test_wasm_plugin(path, endpoints)
+    } else {
+        test_python_plugin(path, endpoints)
+    }
+}
+
+/// Hooks of a plugin endpointo calls with JSON, native or WASM
+trait CompiledPlugin {
+    fn api_version(&self) -> u32;
+    fn defines(&self, hook: &str) -> bool;
+    fn try_discover(&self, target: &str) -> Result<Vec<String>>;
+    fn try_generate(&self, content: &str, source: &str) -> Result<Vec<Endpoint>>;
+    fn try_filter(&self, endpoint: &Endpoint, asset: Option<&AssetInfo>) -> Result<bool>;
+    fn try_transform(&self, endpoint: &Endpoint, asset: Option<&AssetInfo>) -> Result<Endpoint>;
+}
+
+impl CompiledPlugin for NativePlugin {
+    fn api_version(&self) -> u32 {
+        self.api_version()
+    }
+
+    // Libraries built against API 1 have no discover_urls in their vtable
+    fn defines(&self, hook: &str) -> bool {
+        hook != "discover_urls" || self.has_api(2)
+    }
+
+    fn try_discover(&self, target: &str) -> Result<Vec<String>> {
+        self.try_discover(target)
+    }
+
+    fn try_generate(&self, content: &str, source: &str) -> Result<Vec<Endpoint>> {
+        self.try_generate(content, source)
+    }
+
+    fn try_filter(&self, endpoint: &Endpoint, asset: Option<&AssetInfo>) -> Result<bool> {
+        self.try_filter(endpoint, asset)
+    }
+
+    fn try_transform(&self, endpoint: &Endpoint, asset: Option<&AssetInfo>) -> Result<Endpoint> {
+        self.try_transform(endpoint, asset)
+    }
+}
+
+#[cfg(feature = "wasm-plugins")]
+impl CompiledPlugin for WasmPlugin {
+    fn api_version(&self) -> u32 {
+        PLUGIN_API_VERSION
+    }
+
+    fn defines(&self, hook: &str) -> bool {
+        self.hooks().contains(&hook)
+    }
+
+    fn try_discover(&self, _target: &str) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    fn try_generate(&self, content: &str, source: &str) -> Result<Vec<Endpoint>> {
+        self.try_generate(content, source)
+    }
+
+    fn try_filter(&self, endpoint: &Endpoint, asset: Option<&AssetInfo>) -> Result<bool> {
+        self.try_filter(endpoint, asset)
+    }
+
+    fn try_transform(&self, endpoint: &Endpoint, asset: Option<&AssetInfo>) -> Result<Endpoint> {
+        self.try_transform(endpoint, asset)
+    }
+}
+
+/// Run a native plugin's hooks against endpoints
+pub fn test_native_plugin(plugin: &NativePlugin, endpoints: &[Endpoint]) -> Result<PluginTest> {
+    test_compiled_plugin(plugin, endpoints)
+}
+
+#[cfg(feature = "wasm-plugins")]
+fn test_wasm_plugin(path: &Path, endpoints: &[Endpoint]) -> Result<PluginTest> {
+    test_compiled_plugin(&WasmPlugin::load(path)?, endpoints)
+}
+
+#[cfg(not(feature = "wasm-plugins"))]
+fn test_wasm_plugin(_path: &Path, _endpoints: &[Endpoint]) -> Result<PluginTest> {
+    Err(Error::PluginError(
+        "WASM plugins are disabled; rebuild with --features wasm-plugins".to_string(),
+    ))
+}
+
+fn test_compiled_plugin(
+    plugin: &impl CompiledPlugin,
+    endpoints: &[Endpoint],
+) -> Result<PluginTest> {
+    let mut discover = HookReport {
+        hook: "discover_urls",
+        defined: plugin.defines("discover_urls"),
+        calls: 0,
+        errors: Vec::new(),
+    };
+    let mut discovered = 0;
+    if discover.defined {
+        discover.calls += 1;
+        match plugin.try_discover(SAMPLE_TARGET) {
+            Ok(urls) => discovered = check_all_discovered(urls, &mut discover),
+            Err(e) => discover.errors.push(format!("{}: {}", SAMPLE_TARGET, e)),
+        }
+    }
+    let (source, content) = SAMPLE_ASSET;
+    let mut generate = HookReport {
+        hook: "generate_endpoints",
+        defined: plugin.defines("generate_endpoints"),
+        calls: 0,
+        errors: Vec::new(),
+    };
+    let mut generated = 0;
+    if generate.defined {
+        generate.calls += 1;
+        match plugin.try_generate(content, source) {
+            Ok(new_endpoints) => {
+                generated = check_all_generated(source, new_endpoints, &mut generate)
+            }
+            Err(e) => generate.errors.push(format!("{}: {}", source, e)),
+        }
+    }
+    let mut filter = HookReport {
+        hook: "filter_endpoint",
+        defined: plugin.defines("filter_endpoint"),
+        calls: 0,
+        errors: Vec::new(),
+    };
+    let mut transform = HookReport {
+        hook: "transform_endpoint",
+        defined: plugin.defines("transform_endpoint"),
+        calls: 0,
+        errors: Vec::new(),
+    };
+    let mut dropped = 0;
+    let mut changed = 0;
+    let asset = sample_asset();
+
+    for endpoint in endpoints {
+        let asset = asset_of(endpoint, &asset);
+        if filter.defined {
+            filter.calls += 1;
+            match plugin.try_filter(endpoint, asset) {
+                Ok(true) => {}
+                Ok(false) => {
+                    dropped += 1;
+                    continue;
+                }
+                Err(e) => filter.errors.push(format!("{}: {}", endpoint.url, e)),
+            }
+        }
+
+        if !transform.defined {
+            continue;
+        }
+        transform.calls += 1;
+        match plugin.try_transform(endpoint, asset) {
+            // The whole endpoint crosses into the plugin and back
+            Ok(new_endpoint) => {
+                if serde_json::to_value(&new_endpoint)? != serde_json::to_value(endpoint)? {
+                    changed += 1;
+                }
+            }
+            Err(e) => transform.errors.push(format!("{}: {}", endpoint.url, e)),
+        }
+    }
+
+    Ok(PluginTest {
+        api_version: plugin.api_version(),
+        hooks: vec![discover, generate, filter, transform],
+        endpoints: endpoints.len(),
+        discovered,
+        generated,
+        dropped,
+        changed,
+    })
+}
+
+/// Count generated endpoints that pass the checks a scan applies, recording
+/// the rest as errors
+fn check_all_generated(source: &str, endpoints: Vec<Endpoint>, report: &mut HookReport) -> usize {
+    let mut passed = 0;
+    for endpoint in endpoints {
+        match check_generated(endpoint) {
+            Ok(_) => passed += 1,
+            Err(e) => report.errors.push(format!("{}: {}", source, e)),
+        }
+    }
+    passed
+}
+
+/// Count discovered URLs that pass the checks a scan applies, recording the
+/// rest as errors
+fn check_all_discovered(urls: Vec<String>, report: &mut HookReport) -> usize {
+    let mut passed = 0;
+    for url in urls {
+        match check_discovered(SAMPLE_TARGET, &url) {
+            Ok(_) => passed += 1,
+            Err(e) => report.errors.push(format!("{}: {}", SAMPLE_TARGET, e)),
+        }
+    }
+    passed
+}
+
+#[cfg(feature = "python-plugins")]
+fn test_python_plugin(path: &Path, endpoints: &[Endpoint]) -> Result<PluginTest> {
+    let plugin = PythonPlugin::load(path)?;
+    let api_version = plugin.api_version();
+
+    Python::with_gil(|py| {
+        let plugin = plugin.module().bind(py);
+        let discover_fn = plugin.getattr("discover_urls").ok();
+        let generate_fn = plugin.getattr("generate_endpoints").ok();
+        let filter_fn = plugin.getattr("filter_endpoint").ok();
+        let transform_fn = plugin.getattr("transform_endpoint").ok();
+        let mut discover = HookReport {
+            hook: "discover_urls",
+            defined: discover_fn.is_some(),
+            calls: 0,
+            errors: Vec::new(),
+        };
+        let mut discovered = 0;
+        if let Some(discover_fn) = &discover_fn {
+            discover.calls += 1;
+            match discover_fn
+                .call1((SAMPLE_TARGET,))
+                .and_then(|urls| urls.extract::<Vec<String>>())
+            {
+                Ok(urls) => discovered = check_all_discovered(urls, &mut discover),
+                Err(e) => discover.errors.push(format!("{}: {}", SAMPLE_TARGET, e)),
+            }
+        }
+
+        let (source, content) = SAMPLE_ASSET;
+        let mut generate = HookReport {
+            hook: "generate_endpoints",
+            defined: generate_fn.is_some(),
+            calls: 0,
+            errors: Vec::new(),
+        };
+        let mut generated = 0;
+        if let Some(generate_fn) = &generate_fn {
+            generate.calls += 1;
+            match generate_fn.call1((content, source)).and_then(|r| r.iter()) {
+                Ok(items) => {
+                    let mut new_endpoints = Vec::new();
+                    for item in items {
+                        match item {
+                            Ok(item) => match item.downcast::<PyDict>() {
+                                Ok(dict) => match dict_to_endpoint(dict) {
+                                    Ok(endpoint) => new_endpoints.push(endpoint),
+                                    Err(e) => generate.errors.push(format!("{}: {}", source, e)),
+                                },
+                                Err(_) => generate.errors.push(format!(
+                                    "{}: returned {} instead of a dict",
+                                    source,
+                                    item.get_type()
+                                )),
+                            },
+                            Err(e) => generate.errors.push(format!("{}: {}", source, e)),
+                        }
+                    }
+                    generated = check_all_generated(source, new_endpoints, &mut generate);
+                }
+                Err(e) => generate.errors.push(format!("{}: {}", source, e)),
+            }
+        }
+
+        let mut filter = HookReport {
+            hook: "filter_endpoint",
+            defined: filter_fn.is_some(),
+            calls: 0,
+            errors: Vec::new(),
+        };
+        let mut transform = HookReport {
+            hook: "transform_endpoint",
+            defined: transform_fn.is_some(),
+            calls: 0,
+            errors: Vec::new(),
+        };
+        let mut dropped = 0;
+        let mut changed = 0;
+        let asset = sample_asset();
+
+        for endpoint in endpoints {
+            let asset = asset_of(endpoint, &asset);
+            if let Some(filter_fn) = &filter_fn {
+                filter.calls += 1;
+                match filter_fn.call1((endpoint_to_dict_in(py, endpoint, asset),)) {
+                    Ok(result) => match result.extract::<bool>() {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            dropped += 1;
+                            continue;
+                        }
+                        Err(_) => filter.errors.push(format!(
+                            "{}: returned {} instead of a bool",
+                            endpoint.url,
+                            result.get_type()
+                        )),
+                    },
+                    Err(e) => filter.errors.push(format!("{}: {}", endpoint.url, e)),
+                }
+            }
+
+            if let Some(transform_fn) = &transform_fn {
+                transform.calls += 1;
+                match transform_fn.call1((endpoint_to_dict_in(py, endpoint, asset),)) {
+                    Ok(result) => match result.downcast::<PyDict>() {
+                        Ok(dict) => match dict_to_endpoint(dict) {
+                            Ok(new_endpoint) => {
+                                // Only these fields cross into Python and back
+                                if new_endpoint.url != endpoint.url
+                                    || new_endpoint.method != endpoint.method
+                                    || new_endpoint.endpoint_type != endpoint.endpoint_type
+                                    || new_endpoint.source != endpoint.source
+                                    || new_endpoint.line != endpoint.line
+                                    || new_endpoint.params != endpoint.params
+                                {
+                                    changed += 1;
+                                }
+                            }
+                            Err(e) => transform.errors.push(format!("{}: {}", endpoint.url, e)),
+                        },
+                        Err(_) => transform.errors.push(format!(
+                            "{}: returned {} instead of a dict",
+                            endpoint.url,
+                            result.get_type()
+                        )),
+                    },
+                    Err(e) => transform.errors.push(format!("{}: {}", endpoint.url, e)),
+                }
+            }
+        }
+
+        Ok(PluginTest {
+            api_version,
+            hooks: vec![discover, generate, filter, transform],
+            endpoints: endpoints.len(),
+            discovered,
+            generated,
+            dropped,
+            changed,
+        })
+    })
+}
+
+#[cfg(not(feature = "python-plugins"))]
+fn test_python_plugin(_path: &Path, _endpoints: &[Endpoint]) -> Result<PluginTest> {
+    Err(Error::PluginError(
+        "this build can't run Python plugins; rebuild with `--features python-plugins`".to_string(),
+    ))
+}
+
+/// Source of a new plugin with every hook stubbed out
+pub fn skeleton(name: &str) -> String {
+    format!(
+        r#""""
+{name}: an endpointo plugin
+
+Load it with `endpointo scan <url> --plugin {name}.py` and check it with
+`endpointo plugins test {name}.py`.
+"""
+
+# The plugin API this plugin is written for; discover_urls and write_output
+# need version 2
+ENDPOINTO_PLUGIN_API = {api}
+
+
+def discover_urls(target):
+    """
+    Name more assets to fetch and parse for a scan target, such as scripts
+    an internal asset inventory knows of but no page links to.
+
+    Args:
+        target (str): the URL being scanned
+
+    Returns:
+        list: URLs (str), absolute or relative to the target; only http(s)
+        URLs in the scan's scope are fetched
+    """
+    return []
+
+
+def generate_endpoints(content, source):
+    """
+    Find endpoints the built-in parser can't in an asset.
+
+    Args:
+        content (str): the asset's text
+        source (str): its URL or file path
+
+    Returns:
+        list: endpoint dicts with at least a url, which must be an absolute
+        http(s) or ws(s) URL or a path starting with /
+    """
+    return []
+
+
+def filter_endpoint(endpoint):
+    """
+    Decide whether to keep an endpoint.
+
+    Args:
+        endpoint (dict): url, method, endpoint_type, source, line, params,
+            and asset when the asset it was found in is known: url,
+            final_url, status, content_type, headers, content_hash, size
+
+    Returns:
+        bool: True to keep the endpoint, False to drop it
+    """
+    return True
+
+
+def transform_endpoint(endpoint):
+    """
+    Change an endpoint that filter_endpoint kept.
+
+    Args:
+        endpoint (dict): the same keys filter_endpoint gets
+
+    Returns:
+        dict: the endpoint to keep; it must still have a url
+    """
+    return endpoint
+
+
+def write_output(result):
+    """
+    Ship the scan's result somewhere, for `--format plugin`. Delete this
+    hook if the plugin doesn't write output.
+
+    Args:
+        result (dict): the result as --format json writes it: target,
+            timestamp, total_endpoints, endpoints, findings, stats, ...
+    """
+    pass
+"#,
+        api = PLUGIN_API_VERSION
+    )
+}
+
+/// Write a new plugin skeleton to `<dir>/<name>.py`
+pub fn write_skeleton(dir: &Path, name: &str, force: bool) -> Result<PathBuf> {
+    let name = name.strip_suffix(".py").unwrap_or(name);
+    if !PLUGIN_NAME.is_match(name) {
+        return Err(Error::ValidationError(format!(
+            "invalid plugin name '{}': use letters, digits and underscores, not starting with a digit",
+            name
+        )));
+    }
+    let path = dir.join(format!("{}.py", name));
+    if path.exists() && !force {
+        return Err(Error::ValidationError(format!(
+            "{} already exists; use --force to overwrite it",
+            path.display()
+        )));
+    }
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(&path, skeleton(name))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skeleton_defines_every_hook() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_skeleton(dir.path(), "my_filter.py", false).unwrap();
+        assert_eq!(path, dir.path().join("my_filter.py"));
+        assert!(write_skeleton(dir.path(), "my_filter", false).is_err());
+        assert!(write_skeleton(dir.path(), "my_filter", true).is_ok());
+        assert!(write_skeleton(dir.path(), "2fast", false).is_err());
+
+        std::fs::write(
+            dir.path().join("helper.py"),
+            "def transform_endpoint(endpoint):\n    def filter_endpoint(e):\n        pass\n",
+        )
+        .unwrap();
+        let plugins = list_plugins(dir.path()).unwrap();
+        assert_eq!(plugins.len(), 2);
+        assert_eq!(plugins[0].hooks, vec!["transform_endpoint"]);
+        assert_eq!(plugins[0].api_version, None);
+        assert_eq!(plugins[1].hooks, HOOKS.to_vec());
+        assert_eq!(plugins[1].api_version, Some(PLUGIN_API_VERSION));
+    }
+
+    #[test]
+    fn test_api_version_in() {
+        assert_eq!(api_version_in("ENDPOINTO_PLUGIN_API = 2\n"), Some(2));
+        assert_eq!(
+            api_version_in("import re\nENDPOINTO_PLUGIN_API=1  # old\n"),
+            Some(1)
+        );
+        assert_eq!(api_version_in("    ENDPOINTO_PLUGIN_API = 2\n"), None);
+        assert_eq!(api_version_in("ENDPOINTO_PLUGIN_API = \"2\"\n"), None);
+        assert_eq!(
+            api_version_in("def filter_endpoint(e):\n    return True\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_list_includes_native_libraries() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("libapi_only.so"), "").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "").unwrap();
+        write_skeleton(dir.path(), "tagger", false).unwrap();
+
+        let plugins = list_plugins(dir.path()).unwrap();
+        assert_eq!(plugins.len(), 2);
+        assert!(plugins[0].native && plugins[0].hooks.is_empty());
+        assert!(!plugins[1].native);
+    }
+
+    #[test]
+    fn test_list_includes_wasm_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("api_only.wasm"), "").unwrap();
+        write_skeleton(dir.path(), "tagger", false).unwrap();
+
+        let plugins = list_plugins(dir.path()).unwrap();
+        assert_eq!(plugins.len(), 2);
+        assert!(plugins[0].wasm && !plugins[0].native);
+        assert!(!plugins[1].wasm);
+    }
+}
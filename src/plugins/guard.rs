@@ -0,0 +1,435 @@
+//! Time and output limits on plugin hooks
+//!
+//! A thread can't be stopped safely from outside, so a hook that overruns
+//! its time limit is left running on a thread of its own while the scan
+//! moves on as the [`TimeoutPolicy`] says. Until that call returns, later
+//! calls to the same plugin count as timed out too, so a plugin stuck in a
+//! loop costs one thread rather than one per call.
+
+use super::{AssetInfo, Batch, EndpointoPlugin};
+use crate::error::{Error, Result};
+use crate::types::{Endpoint, ScanResult};
+use crate::util::lock;
+use serde::{Deserialize, Serialize};
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing::{debug, error, warn};
+
+/// What happens when a plugin hook overruns its time limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeoutPolicy {
+    /// Carry on as if the plugin didn't define the hook, for that call
+    #[default]
+    Skip,
+    /// Stop calling the plugin for the rest of the scan
+    Drop,
+    /// Fail the scan
+    Abort,
+}
+
+impl TimeoutPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeoutPolicy::Skip => "skip",
+            TimeoutPolicy::Drop => "drop",
+            TimeoutPolicy::Abort => "abort",
+        }
+    }
+}
+
+impl std::str::FromStr for TimeoutPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "skip" => Ok(TimeoutPolicy::Skip),
+            "drop" => Ok(TimeoutPolicy::Drop),
+            "abort" => Ok(TimeoutPolicy::Abort),
+            _ => Err(format!(
+                "unknown timeout policy '{}' (expected skip, drop or abort)",
+                s
+            )),
+        }
+    }
+}
+
+/// Limits on every plugin's hooks; the default sets none
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PluginLimits {
+    /// How long one hook call may take
+    pub timeout: Option<Duration>,
+    /// What to do when a call takes longer
+    pub on_timeout: TimeoutPolicy,
+    /// Most endpoints one `generate_endpoints` call may add; the rest are
+    /// dropped
+    pub max_generated: Option<usize>,
+}
+
+impl PluginLimits {
+    /// Whether any limit is set
+    pub fn is_set(&self) -> bool {
+        self.timeout.is_some() || self.max_generated.is_some()
+    }
+}
+
+/// A plugin whose hooks run under [`PluginLimits`]
+pub(crate) struct Guarded {
+    plugin: Arc<dyn EndpointoPlugin>,
+    limits: PluginLimits,
+    /// Calls that timed out and are still running
+    stuck: Arc<AtomicUsize>,
+    dropped: AtomicBool,
+    /// Why the scan should stop, shared with the plugin manager
+    abort: Arc<Mutex<Option<String>>>,
+}
+
+impl Guarded {
+    pub(crate) fn new(
+        plugin: Box<dyn EndpointoPlugin>,
+        limits: PluginLimits,
+        abort: Arc<Mutex<Option<String>>>,
+    ) -> Self {
+        Self {
+            plugin: Arc::from(plugin),
+            limits,
+            stuck: Arc::new(AtomicUsize::new(0)),
+            dropped: AtomicBool::new(false),
+            abort,
+        }
+    }
+
+    /// Run a hook within the time limit, or `None` if it didn't finish or
+    /// the plugin was dropped
+    ///
+    /// A panic in the hook is resumed here, as if it had run on this thread.
+    fn call<T, F>(&self, hook: &str, timeout: Duration, f: F) -> Option<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn EndpointoPlugin) -> T + Send + 'static,
+    {
+        if self.dropped.load(Ordering::Relaxed) {
+            return None;
+        }
+        if self.stuck.load(Ordering::Acquire) > 0 {
+            debug!(
+                "Plugin {} is still running an earlier call; skipping {}",
+                self.plugin.name(),
+                hook
+            );
+            self.overran(hook, timeout);
+            return None;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let plugin = self.plugin.clone();
+        let stuck = self.stuck.clone();
+        // Set when this call is given up on; the result is sent under its
+        // lock, so exactly one side sees the call as stuck
+        let abandoned = Arc::new(Mutex::new(false));
+        let gave_up = abandoned.clone();
+        let spawned = thread::Builder::new()
+            .name(format!("plugin {}", self.plugin.name()))
+            .spawn(move || {
+                let result = catch_unwind(AssertUnwindSafe(|| f(&*plugin)));
+                let abandoned = lock(&gave_up);
+                if *abandoned {
+                    stuck.fetch_sub(1, Ordering::Release);
+                }
+                let _ = tx.send(result);
+            });
+        if let Err(e) = spawned {
+            error!("Plugin {}: can't run {}: {}", self.plugin.name(), hook, e);
+            return None;
+        }
+
+        let result = match rx.recv_timeout(timeout) {
+            Err(RecvTimeoutError::Timeout) => {
+                let mut abandoned = lock(&abandoned);
+                match rx.try_recv() {
+                    Ok(result) => result,
+                    Err(_) => {
+                        *abandoned = true;
+                        self.stuck.fetch_add(1, Ordering::Release);
+                        drop(abandoned);
+                        self.overran(hook, timeout);
+                        return None;
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return None,
+            Ok(result) => result,
+        };
+        match result {
+            Ok(value) => Some(value),
+            Err(panic) => resume_unwind(panic),
+        }
+    }
+
+    /// Apply the timeout policy
+    fn overran(&self, hook: &str, timeout: Duration) {
+        let message = format!(
+            "plugin {}: {} took longer than {:?}",
+            self.plugin.name(),
+            hook,
+            timeout
+        );
+        match self.limits.on_timeout {
+            TimeoutPolicy::Skip => warn!("Skipping {}", message),
+            TimeoutPolicy::Drop => {
+                if !self.dropped.swap(true, Ordering::Relaxed) {
+                    warn!("Dropping {}", message);
+                }
+            }
+            TimeoutPolicy::Abort => {
+                let mut abort = lock(&self.abort);
+                if abort.is_none() {
+                    error!("Aborting: {}", message);
+                    *abort = Some(message);
+                }
+            }
+        }
+    }
+}
+
+impl EndpointoPlugin for Guarded {
+    fn name(&self) -> &str {
+        self.plugin.name()
+    }
+
+    fn filter_endpoint(&self, endpoint: &Endpoint) -> bool {
+        self.filter_endpoint_in(endpoint, None)
+    }
+
+    fn transform_endpoint(&self, endpoint: Endpoint) -> Endpoint {
+        self.transform_endpoint_in(endpoint, None)
+    }
+
+    fn filter_endpoint_in(&self, endpoint: &Endpoint, asset: Option<&AssetInfo>) -> bool {
+        let mut batch = vec![(endpoint.clone(), asset.cloned().map(Arc::new))];
+        self.filter_batch(&mut batch);
+        !batch.is_empty()
+    }
+
+    fn transform_endpoint_in(&self, endpoint: Endpoint, asset: Option<&AssetInfo>) -> Endpoint {
+        let batch = vec![(endpoint, asset.cloned().map(Arc::new))];
+        let (endpoint, _) = self
+            .transform_batch(batch)
+            .pop()
+            .expect("a transform keeps every endpoint");
+        endpoint
+    }
+
+    fn filter_batch(&self, batch: &mut Batch) {
+        for (_, e) in self.try_filter_batch(batch) {
+            error!("Plugin filter_endpoint error: {}", e);
+        }
+    }
+
+    fn transform_batch(&self, batch: Batch) -> Batch {
+        let (batch, failures) = self.try_transform_batch(batch);
+        for (_, e) in failures {
+            error!("Plugin transform_endpoint error: {}", e);
+        }
+        batch
+    }
+
+    fn try_filter_batch(&self, batch: &mut Batch) -> Vec<(usize, Error)> {
+        let Some(timeout) = self.limits.timeout else {
+            return self.plugin.try_filter_batch(batch);
+        };
+        let mut input = batch.clone();
+        let filtered = self.call("filter_endpoint", timeout, move |plugin| {
+            let failures = plugin.try_filter_batch(&mut input);
+            (input, failures)
+        });
+        match filtered {
+            Some((kept, failures)) => {
+                *batch = kept;
+                failures
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn try_transform_batch(&self, batch: Batch) -> (Batch, Vec<(usize, Error)>) {
+        let Some(timeout) = self.limits.timeout else {
+            return self.plugin.try_transform_batch(batch);
+        };
+        let input = batch.clone();
+        self.call("transform_endpoint", timeout, move |plugin| {
+            plugin.try_transform_batch(input)
+        })
+        .filter(|(transformed, _)| transformed.len() == batch.len())
+        .unwrap_or((batch, Vec::new()))
+    }
+
+    fn generate_endpoints(&self, content: &str, source: &str) -> Vec<Endpoint> {
+        self.try_generate_endpoints(content, source)
+            .unwrap_or_else(|e| {
+                error!("Plugin generate_endpoints error: {}", e);
+                Vec::new()
+            })
+    }
+
+    fn discover_urls(&self, target: &str) -> Vec<String> {
+        self.try_discover_urls(target).unwrap_or_else(|e| {
+            error!("Plugin discover_urls error: {}", e);
+            Vec::new()
+        })
+    }
+
+    fn try_generate_endpoints(&self, content: &str, source: &str) -> Result<Vec<Endpoint>> {
+        let mut endpoints = match self.limits.timeout {
+            None => self.plugin.try_generate_endpoints(content, source)?,
+            Some(timeout) => {
+                let (content, source) = (content.to_string(), source.to_string());
+                self.call("generate_endpoints", timeout, move |plugin| {
+                    plugin.try_generate_endpoints(&content, &source)
+                })
+                .transpose()?
+                .unwrap_or_default()
+            }
+        };
+        if let Some(max) = self.limits.max_generated {
+            if endpoints.len() > max {
+                warn!(
+                    "Plugin {} generated {} endpoints from {}; keeping the first {}",
+                    self.plugin.name(),
+                    endpoints.len(),
+                    source,
+                    max
+                );
+                endpoints.truncate(max);
+            }
+        }
+        Ok(endpoints)
+    }
+
+    fn try_discover_urls(&self, target: &str) -> Result<Vec<String>> {
+        let Some(timeout) = self.limits.timeout else {
+            return self.plugin.try_discover_urls(target);
+        };
+        let target = target.to_string();
+        Ok(self
+            .call("discover_urls", timeout, move |plugin| {
+                plugin.try_discover_urls(&target)
+            })
+            .transpose()?
+            .unwrap_or_default())
+    }
+
+    // Writing output is the scan's last step, so it isn't timed
+    fn writes_output(&self) -> bool {
+        self.plugin.writes_output()
+    }
+
+    fn write_output(&self, result: &ScanResult) -> Result<()> {
+        self.plugin.write_output(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EndpointType;
+
+    /// Sleeps in every hook for as long as the URL says, in milliseconds
+    struct Sleepy;
+
+    impl EndpointoPlugin for Sleepy {
+        fn name(&self) -> &str {
+            "sleepy"
+        }
+
+        fn filter_endpoint(&self, endpoint: &Endpoint) -> bool {
+            let ms = endpoint.url.trim_start_matches('/').parse().unwrap_or(0);
+            thread::sleep(Duration::from_millis(ms));
+            false
+        }
+
+        fn generate_endpoints(&self, content: &str, _source: &str) -> Vec<Endpoint> {
+            content
+                .split_whitespace()
+                .map(|url| Endpoint::new(url.to_string(), EndpointType::Rest))
+                .collect()
+        }
+    }
+
+    fn guarded(on_timeout: TimeoutPolicy) -> (Guarded, Arc<Mutex<Option<String>>>) {
+        let abort = Arc::new(Mutex::new(None));
+        let limits = PluginLimits {
+            timeout: Some(Duration::from_millis(100)),
+            on_timeout,
+            max_generated: Some(2),
+        };
+        (Guarded::new(Box::new(Sleepy), limits, abort.clone()), abort)
+    }
+
+    fn endpoint(url: &str) -> Endpoint {
+        Endpoint::new(url.to_string(), EndpointType::Rest)
+    }
+
+    #[test]
+    fn test_timeout_policies() {
+        let (plugin, abort) = guarded(TimeoutPolicy::Skip);
+        assert!(!plugin.filter_endpoint(&endpoint("/0")));
+        // The slow call is skipped, and so is the next while it still runs
+        assert!(plugin.filter_endpoint(&endpoint("/400")));
+        assert!(plugin.filter_endpoint(&endpoint("/0")));
+        thread::sleep(Duration::from_millis(500));
+        assert!(!plugin.filter_endpoint(&endpoint("/0")));
+        assert!(abort.lock().unwrap().is_none());
+
+        let (plugin, _) = guarded(TimeoutPolicy::Drop);
+        assert!(plugin.filter_endpoint(&endpoint("/400")));
+        thread::sleep(Duration::from_millis(500));
+        assert!(plugin.filter_endpoint(&endpoint("/0")));
+        assert!(plugin.generate_endpoints("/a", "app.js").is_empty());
+
+        let (plugin, abort) = guarded(TimeoutPolicy::Abort);
+        assert!(plugin.filter_endpoint(&endpoint("/400")));
+        let reason = abort.lock().unwrap().clone().unwrap();
+        assert!(reason.contains("sleepy: filter_endpoint took longer than"));
+    }
+
+    #[test]
+    fn test_concurrent_calls_are_not_timeouts() {
+        let (plugin, abort) = guarded(TimeoutPolicy::Abort);
+        // Both calls run at once, each well within the limit
+        thread::scope(|s| {
+            let calls: Vec<_> = (0..2)
+                .map(|_| s.spawn(|| plugin.filter_endpoint(&endpoint("/50"))))
+                .collect();
+            for call in calls {
+                assert!(!call.join().unwrap());
+            }
+        });
+        assert!(abort.lock().unwrap().is_none());
+        assert!(!plugin.filter_endpoint(&endpoint("/0")));
+    }
+
+    #[test]
+    fn test_generated_endpoints_are_capped() {
+        let (plugin, _) = guarded(TimeoutPolicy::Skip);
+        let generated = plugin.generate_endpoints("/a /b /c", "app.js");
+        let urls: Vec<&str> = generated.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(urls, ["/a", "/b"]);
+    }
+
+    #[test]
+    fn test_policy_names() {
+        for policy in [
+            TimeoutPolicy::Skip,
+            TimeoutPolicy::Drop,
+            TimeoutPolicy::Abort,
+        ] {
+            assert_eq!(policy.as_str().parse::<TimeoutPolicy>(), Ok(policy));
+        }
+        assert!("kill".parse::<TimeoutPolicy>().is_err());
+    }
+}
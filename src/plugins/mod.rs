@@ -1,200 +1,996 @@
+pub mod builtin;
+pub mod dev;
+pub mod guard;
+pub mod native;
 #[cfg(feature = "python-plugins")]
-use pyo3::prelude::*;
-#[cfg(feature = "python-plugins")]
-use pyo3::types::PyDict;
+pub mod python;
+pub mod reload;
+pub mod script;
+#[cfg(feature = "wasm-plugins")]
+pub mod wasm;
 
-#[cfg(feature = "python-plugins")]
-use crate::error::Error;
-use crate::error::Result;
-use crate::types::Endpoint;
+use crate::crawler::client::FetchResponse;
+use crate::error::{Error, Result};
+use crate::parser::filters::FalsePositiveFilter;
+use crate::types::{Endpoint, ScanResult};
+use crate::util::lock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
-use tracing::info;
+use std::sync::{Arc, Mutex};
+use tracing::{error, info, warn};
+use url::Url;
 
+pub use self::builtin::Builtin;
+pub use self::guard::{PluginLimits, TimeoutPolicy};
+pub use self::native::NativePlugin;
 #[cfg(feature = "python-plugins")]
-use tracing::error;
+pub use self::python::PythonPlugin;
+pub use self::reload::PluginReloader;
+pub use self::script::Script;
+#[cfg(feature = "wasm-plugins")]
+pub use self::wasm::WasmPlugin;
+
+/// Version of the plugin API this endpointo implements
+///
+/// Python plugins declare the version they target as `ENDPOINTO_PLUGIN_API`
+/// at module level; native libraries carry it in their vtable. Version 2
+/// added `discover_urls` and `write_output`.
+pub const PLUGIN_API_VERSION: u32 = 2;
+
+/// Oldest plugin API version still loaded, and the one Python plugins that
+/// don't declare a version target
+pub const MIN_PLUGIN_API_VERSION: u32 = 1;
+
+/// The plugin API version that added a hook
+pub fn hook_api_version(hook: &str) -> u32 {
+    match hook {
+        "discover_urls" | "write_output" => 2,
+        _ => 1,
+    }
+}
+
+/// Why a plugin targeting an API version can't be loaded, if it can't
+fn check_api_version(version: u32) -> std::result::Result<(), String> {
+    if (MIN_PLUGIN_API_VERSION..=PLUGIN_API_VERSION).contains(&version) {
+        Ok(())
+    } else {
+        Err(format!(
+            "targets plugin API {} but this endpointo supports {} to {}",
+            version, MIN_PLUGIN_API_VERSION, PLUGIN_API_VERSION
+        ))
+    }
+}
+
+/// The asset an endpoint was found in, as plugins see it
+///
+/// Fetched assets carry their response; local files only their content's
+/// hash and size. Endpoints are matched to their asset by `source`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetInfo {
+    /// URL or path the asset was read from, the endpoints' `source`
+    pub url: String,
+
+    /// URL after redirects
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_url: Option<String>,
+
+    /// HTTP status code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+
+    /// `Content-Type` header, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+
+    /// Response headers with lowercase names
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+
+    /// SHA-256 of the content as lowercase hex; not computed for files
+    /// parsed in windows
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+
+    /// Content length in bytes
+    pub size: u64,
+}
+
+impl AssetInfo {
+    /// An asset known only by its content, such as a local file
+    pub fn from_content(url: &str, content: &[u8]) -> Self {
+        Self {
+            url: url.to_string(),
+            content_hash: Some(sha256_hex(content)),
+            size: content.len() as u64,
+            ..Self::default()
+        }
+    }
+
+    /// A fetched asset, hashed only if its body was kept in memory
+    pub fn from_response(url: &str, response: &FetchResponse) -> Self {
+        let content = match response.spooled {
+            Some(_) => Self {
+                url: url.to_string(),
+                size: response.size,
+                ..Self::default()
+            },
+            None => Self::from_content(url, &response.body),
+        };
+        Self {
+            final_url: Some(response.final_url.clone()),
+            status: Some(response.status),
+            content_type: response.content_type.clone(),
+            headers: response.headers.clone(),
+            ..content
+        }
+    }
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, content);
+    digest
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Endpoints with the assets they were found in, as the batch hooks get them
+pub type Batch = Vec<(Endpoint, Option<Arc<AssetInfo>>)>;
+
+/// Hooks a Rust plugin implements, each defaulting to doing nothing
+///
+/// Implement it in a `cdylib` crate and export it with
+/// [`declare_plugin!`](crate::declare_plugin) to load it at runtime (see
+/// [`native`]), or register it directly with
+/// [`ScannerBuilder::with_plugin`](crate::scanner::ScannerBuilder::with_plugin).
+pub trait EndpointoPlugin: Send + Sync {
+    /// Name used in logs and errors
+    fn name(&self) -> &str;
+
+    /// Whether to keep an endpoint
+    fn filter_endpoint(&self, _endpoint: &Endpoint) -> bool {
+        true
+    }
+
+    /// Rewrite an endpoint the filter kept
+    fn transform_endpoint(&self, endpoint: Endpoint) -> Endpoint {
+        endpoint
+    }
+
+    /// Whether to keep an endpoint, knowing the asset it was found in when
+    /// there is one; defaults to [`filter_endpoint`](Self::filter_endpoint)
+    fn filter_endpoint_in(&self, endpoint: &Endpoint, _asset: Option<&AssetInfo>) -> bool {
+        self.filter_endpoint(endpoint)
+    }
+
+    /// Rewrite an endpoint, knowing the asset it was found in when there is
+    /// one; defaults to [`transform_endpoint`](Self::transform_endpoint)
+    fn transform_endpoint_in(&self, endpoint: Endpoint, _asset: Option<&AssetInfo>) -> Endpoint {
+        self.transform_endpoint(endpoint)
+    }
+
+    /// Drop the endpoints of a batch the filter doesn't keep
+    ///
+    /// Defaults to [`filter_endpoint_in`](Self::filter_endpoint_in) on each;
+    /// override it to set up something costly once per batch.
+    fn filter_batch(&self, batch: &mut Batch) {
+        batch.retain(|(endpoint, asset)| self.filter_endpoint_in(endpoint, asset.as_deref()));
+    }
+
+    /// Rewrite every endpoint of a batch
+    ///
+    /// Defaults to [`transform_endpoint_in`](Self::transform_endpoint_in) on
+    /// each.
+    fn transform_batch(&self, batch: Batch) -> Batch {
+        batch
+            .into_iter()
+            .map(|(endpoint, asset)| {
+                (
+                    self.transform_endpoint_in(endpoint, asset.as_deref()),
+                    asset,
+                )
+            })
+            .collect()
+    }
+
+    /// [`filter_batch`](Self::filter_batch) reporting the hook's failures
+    /// instead of guessing, for plugins whose hooks can fail such as Python
+    /// and native ones
+    ///
+    /// An endpoint whose filter failed stays in the batch, and the error is
+    /// returned with its index there, for the manager's
+    /// [`PluginErrorPolicy`] to settle. Defaults to `filter_batch`, which
+    /// never fails.
+    fn try_filter_batch(&self, batch: &mut Batch) -> Vec<(usize, Error)> {
+        self.filter_batch(batch);
+        Vec::new()
+    }
+
+    /// [`transform_batch`](Self::transform_batch) reporting the hook's
+    /// failures: an endpoint whose transform failed is left as it was, and
+    /// the error returned with its index. Defaults to `transform_batch`.
+    fn try_transform_batch(&self, batch: Batch) -> (Batch, Vec<(usize, Error)>) {
+        (self.transform_batch(batch), Vec::new())
+    }
+
+    /// Find endpoints of its own in a parsed asset's content
+    ///
+    /// The ones that pass [`check_generated`] go through the filters and
+    /// transforms like the parser's.
+    fn generate_endpoints(&self, _content: &str, _source: &str) -> Vec<Endpoint> {
+        Vec::new()
+    }
+
+    /// URLs of more assets to fetch and parse for a scan target, from
+    /// sources of the plugin's own such as an asset inventory
+    ///
+    /// Called once per target after its page is crawled. The URLs that pass
+    /// [`check_discovered`] and the scan's scope join the page's scripts.
+    fn discover_urls(&self, _target: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// [`generate_endpoints`](Self::generate_endpoints) reporting the
+    /// hook's failure; defaults to `generate_endpoints`
+    fn try_generate_endpoints(&self, content: &str, source: &str) -> Result<Vec<Endpoint>> {
+        Ok(self.generate_endpoints(content, source))
+    }
+
+    /// [`discover_urls`](Self::discover_urls) reporting the hook's failure;
+    /// defaults to `discover_urls`
+    fn try_discover_urls(&self, target: &str) -> Result<Vec<String>> {
+        Ok(self.discover_urls(target))
+    }
+
+    /// Whether the plugin writes results itself, as the `plugin` output
+    /// format; the default doesn't
+    fn writes_output(&self) -> bool {
+        false
+    }
+
+    /// Write a scan's result wherever the plugin ships results, for
+    /// `--format plugin`
+    ///
+    /// Only called when [`writes_output`](Self::writes_output) is true.
+    fn write_output(&self, _result: &ScanResult) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A plugin shared between scanners, as [`PluginReloader`] keeps them
+impl<P: EndpointoPlugin + ?Sized> EndpointoPlugin for Arc<P> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn filter_endpoint(&self, endpoint: &Endpoint) -> bool {
+        (**self).filter_endpoint(endpoint)
+    }
+
+    fn transform_endpoint(&self, endpoint: Endpoint) -> Endpoint {
+        (**self).transform_endpoint(endpoint)
+    }
+
+    fn filter_endpoint_in(&self, endpoint: &Endpoint, asset: Option<&AssetInfo>) -> bool {
+        (**self).filter_endpoint_in(endpoint, asset)
+    }
+
+    fn transform_endpoint_in(&self, endpoint: Endpoint, asset: Option<&AssetInfo>) -> Endpoint {
+        (**self).transform_endpoint_in(endpoint, asset)
+    }
+
+    fn filter_batch(&self, batch: &mut Batch) {
+        (**self).filter_batch(batch)
+    }
+
+    fn transform_batch(&self, batch: Batch) -> Batch {
+        (**self).transform_batch(batch)
+    }
+
+    fn try_filter_batch(&self, batch: &mut Batch) -> Vec<(usize, Error)> {
+        (**self).try_filter_batch(batch)
+    }
+
+    fn try_transform_batch(&self, batch: Batch) -> (Batch, Vec<(usize, Error)>) {
+        (**self).try_transform_batch(batch)
+    }
+
+    fn generate_endpoints(&self, content: &str, source: &str) -> Vec<Endpoint> {
+        (**self).generate_endpoints(content, source)
+    }
+
+    fn discover_urls(&self, target: &str) -> Vec<String> {
+        (**self).discover_urls(target)
+    }
+
+    fn try_generate_endpoints(&self, content: &str, source: &str) -> Result<Vec<Endpoint>> {
+        (**self).try_generate_endpoints(content, source)
+    }
+
+    fn try_discover_urls(&self, target: &str) -> Result<Vec<String>> {
+        (**self).try_discover_urls(target)
+    }
+
+    fn writes_output(&self) -> bool {
+        (**self).writes_output()
+    }
+
+    fn write_output(&self, result: &ScanResult) -> Result<()> {
+        (**self).write_output(result)
+    }
+}
+
+/// Whether a file is a WASM module (`.wasm`)
+pub fn is_wasm(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "wasm")
+}
+
+/// Load a plugin from file: a dynamic library (`.so`, `.dylib`, `.dll`) as
+/// a native plugin, a `.wasm` module as a WASM plugin, anything else as
+/// Python; `None` when WASM or Python plugins are disabled
+///
+/// With `fresh`, a library is loaded from a copy, so a rebuilt one replaces
+/// the image the process already has open (see [`NativePlugin::load_copy`]).
+fn open_plugin(path: &Path, fresh: bool) -> Result<Option<Box<dyn EndpointoPlugin>>> {
+    if native::is_library(path) {
+        info!("Loading native plugin from: {}", path.display());
+        let plugin = match fresh {
+            true => NativePlugin::load_copy(path)?,
+            false => NativePlugin::load(path)?,
+        };
+        return Ok(Some(Box::new(plugin)));
+    }
+
+    if is_wasm(path) {
+        #[cfg(feature = "wasm-plugins")]
+        let plugin: Option<Box<dyn EndpointoPlugin>> = {
+            info!("Loading WASM plugin from: {}", path.display());
+            Some(Box::new(WasmPlugin::load(path)?))
+        };
+
+        #[cfg(not(feature = "wasm-plugins"))]
+        let plugin = {
+            info!(
+                "WASM plugins are disabled. Skipping plugin: {}",
+                path.display()
+            );
+            None
+        };
+
+        return Ok(plugin);
+    }
 
-/// Plugin manager handles loading and executing Python plugins
-pub struct PluginManager {
     #[cfg(feature = "python-plugins")]
-    plugins: Vec<Py<PyAny>>,
+    let plugin: Option<Box<dyn EndpointoPlugin>> = Some(Box::new(PythonPlugin::load(path)?));
+
+    #[cfg(not(feature = "python-plugins"))]
+    let plugin = {
+        info!(
+            "Python plugins are disabled. Skipping plugin: {}",
+            path.display()
+        );
+        None
+    };
+
+    Ok(plugin)
+}
+
+/// What happens when a plugin hook fails, say on an exception in a Python
+/// plugin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PluginErrorPolicy {
+    /// Log it and carry on as if the hook had kept the endpoint unchanged
+    #[default]
+    Warn,
+    /// Drop the endpoint the filter or transform failed on
+    SkipEndpoint,
+    /// Fail the scan
+    Fail,
+}
+
+impl PluginErrorPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PluginErrorPolicy::Warn => "warn",
+            PluginErrorPolicy::SkipEndpoint => "skip-endpoint",
+            PluginErrorPolicy::Fail => "fail",
+        }
+    }
+}
+
+impl std::str::FromStr for PluginErrorPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "warn" => Ok(PluginErrorPolicy::Warn),
+            "skip-endpoint" => Ok(PluginErrorPolicy::SkipEndpoint),
+            "fail" => Ok(PluginErrorPolicy::Fail),
+            _ => Err(format!(
+                "unknown plugin error policy '{}' (expected warn, skip-endpoint or fail)",
+                s
+            )),
+        }
+    }
+}
+
+/// Plugin manager runs plugins of every kind, in the order they were added
+pub struct PluginManager {
+    plugins: Vec<Box<dyn EndpointoPlugin>>,
+    limits: PluginLimits,
+    on_error: PluginErrorPolicy,
+    /// Failed hook calls so far, by plugin
+    errors: Mutex<HashMap<String, usize>>,
+    /// Why a hook that overran or failed asked to abort the scan
+    abort: Arc<Mutex<Option<String>>>,
 }
 
 impl PluginManager {
     /// Create a new plugin manager
     pub fn new() -> Self {
         Self {
-            #[cfg(feature = "python-plugins")]
             plugins: Vec::new(),
+            limits: PluginLimits::default(),
+            on_error: PluginErrorPolicy::default(),
+            errors: Mutex::new(HashMap::new()),
+            abort: Arc::new(Mutex::new(None)),
         }
     }
 
-    /// Load a Python plugin from file
-    pub fn load_plugin(&mut self, _path: &Path) -> Result<()> {
-        #[cfg(feature = "python-plugins")]
-        {
-            info!("Loading plugin from: {}", _path.display());
+    /// Run the hooks of plugins added from now on under these limits
+    pub fn with_limits(mut self, limits: PluginLimits) -> Self {
+        self.limits = limits;
+        self
+    }
 
-            let code = std::fs::read_to_string(_path)?;
-            let filename = _path
-                .file_name()
-                .and_then(|s| s.to_str())
-                .unwrap_or("plugin.py");
+    /// Settle failed hook calls this way
+    pub fn with_error_policy(mut self, policy: PluginErrorPolicy) -> Self {
+        self.on_error = policy;
+        self
+    }
 
-            Python::with_gil(|py| -> Result<()> {
-                let module = PyModule::from_code_bound(py, &code, filename, filename)
-                    .map_err(|e| Error::PluginError(format!("Failed to parse plugin: {}", e)))?;
+    /// Register a Rust plugin
+    pub fn add_plugin(&mut self, plugin: Box<dyn EndpointoPlugin>) {
+        if self.limits.is_set() {
+            let guarded = guard::Guarded::new(plugin, self.limits, self.abort.clone());
+            self.plugins.push(Box::new(guarded));
+        } else {
+            self.plugins.push(plugin);
+        }
+    }
 
-                self.plugins.push(module.into());
-                Ok(())
-            })?;
+    /// An error if a hook overran its time limit under
+    /// [`TimeoutPolicy::Abort`] or failed under [`PluginErrorPolicy::Fail`];
+    /// once one has, every later check fails
+    pub fn check(&self) -> Result<()> {
+        match &*lock(&self.abort) {
+            Some(reason) => Err(Error::PluginError(format!("scan aborted: {}", reason))),
+            None => Ok(()),
         }
+    }
 
-        #[cfg(not(feature = "python-plugins"))]
-        {
-            info!(
-                "Python plugins are disabled. Skipping plugin: {}",
-                _path.display()
-            );
+    /// How many hook calls of each plugin have failed, for plugins with any
+    pub fn error_counts(&self) -> HashMap<String, usize> {
+        lock(&self.errors).clone()
+    }
+
+    /// Count a failed hook call and apply the error policy, returning
+    /// whether to drop what the hook was called on
+    fn failed(&self, plugin: &str, hook: &str, subject: &str, error: &Error) -> bool {
+        *lock(&self.errors).entry(plugin.to_string()).or_insert(0) += 1;
+        let message = format!(
+            "plugin {}: {} failed on {}: {}",
+            plugin, hook, subject, error
+        );
+        match self.on_error {
+            PluginErrorPolicy::Warn => warn!("{}", message),
+            PluginErrorPolicy::SkipEndpoint => warn!("Skipping {}", message),
+            PluginErrorPolicy::Fail => {
+                let mut abort = lock(&self.abort);
+                if abort.is_none() {
+                    error!("Aborting: {}", message);
+                    *abort = Some(message);
+                }
+            }
         }
+        self.on_error == PluginErrorPolicy::SkipEndpoint
+    }
+
+    /// Apply the error policy to a batch hook's failures
+    fn settle(&self, plugin: &str, hook: &str, batch: &mut Batch, failures: Vec<(usize, Error)>) {
+        let mut skipped = Vec::new();
+        for (index, error) in failures {
+            let Some((endpoint, _)) = batch.get(index) else {
+                continue;
+            };
+            if self.failed(plugin, hook, &endpoint.url, &error) {
+                skipped.push(index);
+            }
+        }
+        if !skipped.is_empty() {
+            let mut index = 0;
+            batch.retain(|_| {
+                index += 1;
+                !skipped.contains(&(index - 1))
+            });
+        }
+    }
 
+    /// Names of the plugins, in the order they run
+    pub fn names(&self) -> Vec<&str> {
+        self.plugins.iter().map(|p| p.name()).collect()
+    }
+
+    /// Load a plugin from file: a dynamic library (`.so`, `.dylib`,
+    /// `.dll`) as a native plugin, anything else as Python
+    pub fn load_plugin(&mut self, path: &Path) -> Result<()> {
+        if let Some(plugin) = open_plugin(path, false)? {
+            self.add_plugin(plugin);
+        }
         Ok(())
     }
 
-    /// Execute filter_endpoint on all plugins
-    pub fn filter_endpoint(&self, _endpoint: &Endpoint) -> bool {
-        #[cfg(feature = "python-plugins")]
-        {
-            return Python::with_gil(|py| {
-                for plugin in &self.plugins {
-                    let plugin = plugin.bind(py);
-                    if let Ok(filter_fn) = plugin.getattr("filter_endpoint") {
-                        let ep_dict = self.endpoint_to_dict(py, _endpoint);
-                        match filter_fn.call1((ep_dict,)) {
-                            Ok(result) => {
-                                if let Ok(keep) = result.extract::<bool>() {
-                                    if !keep {
-                                        return false;
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                error!("Plugin filter_endpoint error: {}", e);
-                            }
-                        }
-                    }
+    /// Whether any plugins are loaded
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Execute filter_endpoint on all plugins, stopping at the first that
+    /// drops the endpoint
+    pub fn filter_endpoint(&self, endpoint: &Endpoint, asset: Option<&AssetInfo>) -> bool {
+        self.plugins
+            .iter()
+            .all(|p| p.filter_endpoint_in(endpoint, asset))
+    }
+
+    /// Execute transform_endpoint on all plugins, each seeing the previous
+    /// one's result
+    pub fn transform_endpoint(&self, endpoint: Endpoint, asset: Option<&AssetInfo>) -> Endpoint {
+        self.plugins.iter().fold(endpoint, |endpoint, p| {
+            p.transform_endpoint_in(endpoint, asset)
+        })
+    }
+
+    /// Run a batch through every plugin's filter, then every plugin's
+    /// transform, settling failed calls by the error policy
+    ///
+    /// This can block for as long as the plugins take, so async code should
+    /// call it on a blocking thread.
+    pub fn process(&self, mut batch: Batch) -> Vec<Endpoint> {
+        for plugin in &self.plugins {
+            if batch.is_empty() {
+                break;
+            }
+            let failures = plugin.try_filter_batch(&mut batch);
+            self.settle(plugin.name(), "filter_endpoint", &mut batch, failures);
+        }
+        for plugin in &self.plugins {
+            let (transformed, failures) = plugin.try_transform_batch(batch);
+            batch = transformed;
+            self.settle(plugin.name(), "transform_endpoint", &mut batch, failures);
+        }
+        batch.into_iter().map(|(endpoint, _)| endpoint).collect()
+    }
+
+    /// Execute generate_endpoints on all plugins, which see the content as
+    /// text, keeping the endpoints that pass [`check_generated`]
+    pub fn generate_endpoints(&self, content: &[u8], source: &str) -> Vec<Endpoint> {
+        if self.plugins.is_empty() {
+            return Vec::new();
+        }
+        let content = String::from_utf8_lossy(content);
+        self.plugins
+            .iter()
+            .flat_map(|p| {
+                p.try_generate_endpoints(&content, source)
+                    .unwrap_or_else(|e| {
+                        self.failed(p.name(), "generate_endpoints", source, &e);
+                        Vec::new()
+                    })
+            })
+            .filter_map(|endpoint| match check_generated(endpoint) {
+                Ok(endpoint) => Some(endpoint),
+                Err(e) => {
+                    warn!("Dropping generated endpoint from {}: {}", source, e);
+                    None
                 }
-                true
-            });
+            })
+            .collect()
+    }
+
+    /// Execute discover_urls on all plugins, keeping each URL that passes
+    /// [`check_discovered`] once, in plugin order
+    pub fn discover_urls(&self, target: &str) -> Vec<String> {
+        let mut urls: Vec<String> = Vec::new();
+        let discovered = self.plugins.iter().flat_map(|p| {
+            p.try_discover_urls(target).unwrap_or_else(|e| {
+                self.failed(p.name(), "discover_urls", target, &e);
+                Vec::new()
+            })
+        });
+        for url in discovered {
+            match check_discovered(target, &url) {
+                Ok(url) if !urls.contains(&url) => urls.push(url),
+                Ok(_) => {}
+                Err(e) => warn!("Dropping URL discovered for {}: {}", target, e),
+            }
         }
+        urls
+    }
 
-        #[cfg(not(feature = "python-plugins"))]
-        true
+    /// Names of the plugins that write output, in the order they run
+    pub fn output_writers(&self) -> Vec<&str> {
+        self.plugins
+            .iter()
+            .filter(|p| p.writes_output())
+            .map(|p| p.name())
+            .collect()
+    }
+
+    /// The plugins that would write output: the one named `name` if given,
+    /// else every plugin that writes output
+    ///
+    /// Fails if there are none, so `--format plugin` can't quietly write
+    /// nothing.
+    fn writers(&self, name: Option<&str>) -> Result<Vec<&dyn EndpointoPlugin>> {
+        let writers: Vec<&dyn EndpointoPlugin> = self
+            .plugins
+            .iter()
+            .map(|p| p.as_ref())
+            .filter(|p| p.writes_output())
+            .filter(|p| name.is_none() || name.is_some_and(|name| is_named(p.name(), name)))
+            .collect();
+        if writers.is_empty() {
+            let writing = self.output_writers();
+            return Err(Error::PluginError(match name {
+                Some(name) if !writing.is_empty() => format!(
+                    "no loaded plugin named {} writes output (plugins that do: {})",
+                    name,
+                    writing.join(", ")
+                ),
+                _ => "no loaded plugin writes output (define write_output)".to_string(),
+            }));
+        }
+        Ok(writers)
+    }
+
+    /// Check that [`write_output`](Self::write_output) would have a plugin
+    /// to write with, before a scan rather than after it
+    pub fn check_output(&self, name: Option<&str>) -> Result<()> {
+        self.writers(name).map(|_| ())
+    }
+
+    /// Hand a result to the plugin named `name` if given, else to every
+    /// plugin that writes output, stopping at the first that fails
+    ///
+    /// Returns how many plugins wrote the result.
+    pub fn write_output(&self, result: &ScanResult, name: Option<&str>) -> Result<usize> {
+        let writers = self.writers(name)?;
+        for plugin in &writers {
+            plugin.write_output(result).map_err(|e| {
+                let message = match e {
+                    Error::PluginError(message) => message,
+                    e => e.to_string(),
+                };
+                Error::PluginError(format!(
+                    "{}: write_output failed: {}",
+                    plugin.name(),
+                    message
+                ))
+            })?;
+        }
+        Ok(writers.len())
     }
+}
+
+/// Whether a plugin goes by `name`, with or without a Python plugin's `.py`
+fn is_named(plugin: &str, name: &str) -> bool {
+    plugin == name || plugin.strip_suffix(".py") == Some(name)
+}
 
-    /// Execute transform_endpoint on all plugins
-    pub fn transform_endpoint(&self, mut endpoint: Endpoint) -> Endpoint {
-        #[cfg(feature = "python-plugins")]
-        {
-            Python::with_gil(|py| {
-                for plugin in &self.plugins {
-                    let plugin = plugin.bind(py);
-                    if let Ok(transform_fn) = plugin.getattr("transform_endpoint") {
-                        let ep_dict = self.endpoint_to_dict(py, &endpoint);
-                        match transform_fn.call1((ep_dict,)) {
-                            Ok(result) => {
-                                if let Ok(new_ep_dict) = result.downcast_bound::<PyDict>() {
-                                    if let Ok(new_ep) = self.dict_to_endpoint(new_ep_dict) {
-                                        endpoint = new_ep;
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                error!("Plugin transform_endpoint error: {}", e);
-                            }
-                        }
-                    }
+/// [`EndpointoPlugin::try_filter_batch`] for a plugin whose filter runs
+/// per endpoint and can fail: failed endpoints are kept
+fn filter_each<F>(batch: &mut Batch, filter: F) -> Vec<(usize, Error)>
+where
+    F: Fn(&Endpoint, Option<&AssetInfo>) -> Result<bool>,
+{
+    let mut failures = Vec::new();
+    let mut kept = 0;
+    batch.retain(|(endpoint, asset)| {
+        let keep = filter(endpoint, asset.as_deref()).unwrap_or_else(|e| {
+            failures.push((kept, e));
+            true
+        });
+        kept += usize::from(keep);
+        keep
+    });
+    failures
+}
+
+/// [`EndpointoPlugin::try_transform_batch`] for a plugin whose transform
+/// runs per endpoint and can fail: failed endpoints are left as they were
+fn transform_each<F>(batch: Batch, transform: F) -> (Batch, Vec<(usize, Error)>)
+where
+    F: Fn(&Endpoint, Option<&AssetInfo>) -> Result<Endpoint>,
+{
+    let mut failures = Vec::new();
+    let batch = batch
+        .into_iter()
+        .enumerate()
+        .map(
+            |(index, (endpoint, asset))| match transform(&endpoint, asset.as_deref()) {
+                Ok(transformed) => (transformed, asset),
+                Err(e) => {
+                    failures.push((index, e));
+                    (endpoint, asset)
                 }
-            });
+            },
+        )
+        .collect();
+    (batch, failures)
+}
+
+impl Default for PluginManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Check an endpoint a plugin generated before it joins the results
+///
+/// The URL must be an absolute `http`, `https`, `ws` or `wss` URL or a path
+/// starting with `/`, without whitespace, and not something the parser
+/// would reject as a false positive. A method is upper-cased and must be a
+/// plain word.
+pub fn check_generated(mut endpoint: Endpoint) -> Result<Endpoint> {
+    let invalid = |msg: String| Err(Error::PluginError(msg));
+    let url = endpoint.url.as_str();
+    if url.is_empty() || url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return invalid(format!("invalid URL {:?}", url));
+    }
+    if !url.starts_with('/') {
+        match Url::parse(url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https" | "ws" | "wss") => {}
+            _ => {
+                return invalid(format!(
+                    "{} is not an HTTP(S) or WebSocket URL or a path",
+                    url
+                ))
+            }
         }
+    }
+    if FalsePositiveFilter::default().is_false_positive(url) {
+        return invalid(format!("{} looks like a false positive", url));
+    }
+    if let Some(method) = &endpoint.method {
+        if method.is_empty() || !method.chars().all(|c| c.is_ascii_alphabetic()) {
+            return invalid(format!("{}: invalid method {:?}", url, method));
+        }
+        endpoint.method = Some(method.to_ascii_uppercase());
+    }
+    Ok(endpoint)
+}
 
-        #[cfg(not(feature = "python-plugins"))]
-        {
-            let _ = &mut endpoint; // Prevent unused_mut warning if feature is off
+/// Check a URL a plugin discovered for a target, resolving a relative one
+/// against the target
+///
+/// The result must be an `http` or `https` URL; its fragment is dropped.
+pub fn check_discovered(target: &str, url: &str) -> Result<String> {
+    let mut resolved = match Url::parse(url) {
+        Ok(url) => url,
+        Err(url::ParseError::RelativeUrlWithoutBase) => Url::parse(target)
+            .and_then(|target| target.join(url))
+            .map_err(|e| Error::PluginError(format!("{}: {}", url, e)))?,
+        Err(e) => return Err(Error::PluginError(format!("{}: {}", url, e))),
+    };
+    if !matches!(resolved.scheme(), "http" | "https") {
+        return Err(Error::PluginError(format!("{} is not an HTTP(S) URL", url)));
+    }
+    resolved.set_fragment(None);
+    Ok(resolved.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EndpointType;
+
+    #[test]
+    fn test_check_generated() {
+        let endpoint = |url: &str| Endpoint::new(url.to_string(), EndpointType::Rest);
+
+        let checked =
+            check_generated(endpoint("/internal/v1/jobs").with_method("post".to_string()));
+        assert_eq!(checked.unwrap().method.as_deref(), Some("POST"));
+        assert!(check_generated(endpoint("wss://example.com/live")).is_ok());
+        for url in ["", "javascript:alert(1)", "api/users", "/a b", "/app.css"] {
+            assert!(check_generated(endpoint(url)).is_err(), "{}", url);
         }
+        assert!(check_generated(endpoint("/api").with_method("GET /".to_string())).is_err());
+    }
 
-        endpoint
+    #[test]
+    fn test_check_discovered() {
+        let target = "https://example.com/app/";
+        assert_eq!(
+            check_discovered(target, "static/extra.js#x").unwrap(),
+            "https://example.com/app/static/extra.js"
+        );
+        assert_eq!(
+            check_discovered(target, "https://cdn.example.net/v2.js").unwrap(),
+            "https://cdn.example.net/v2.js"
+        );
+        for url in [
+            "ftp://example.com/a.js",
+            "javascript:void(0)",
+            "http://[::1",
+        ] {
+            assert!(check_discovered(target, url).is_err(), "{}", url);
+        }
     }
 
-    /// Convert Endpoint to Python dictionary
-    #[cfg(feature = "python-plugins")]
-    fn endpoint_to_dict<'py>(&self, py: Python<'py>, endpoint: &Endpoint) -> Bound<'py, PyDict> {
-        let dict = PyDict::new_bound(py);
-        let _ = dict.set_item("url", &endpoint.url).ok();
-        let _ = dict.set_item("method", &endpoint.method).ok();
-        let _ = dict
-            .set_item(
-                "endpoint_type",
-                format!("{:?}", endpoint.endpoint_type).to_lowercase(),
+    #[test]
+    fn test_api_versions() {
+        assert!(check_api_version(MIN_PLUGIN_API_VERSION).is_ok());
+        assert!(check_api_version(PLUGIN_API_VERSION).is_ok());
+        assert_eq!(
+            check_api_version(PLUGIN_API_VERSION + 1).unwrap_err(),
+            format!(
+                "targets plugin API {} but this endpointo supports 1 to {}",
+                PLUGIN_API_VERSION + 1,
+                PLUGIN_API_VERSION
             )
-            .ok();
-        let _ = dict.set_item("source", &endpoint.source).ok();
-        let _ = dict.set_item("line", endpoint.line).ok();
-        let _ = dict.set_item("params", &endpoint.params).ok();
-        dict
+        );
+        assert!(check_api_version(0).is_err());
+
+        assert_eq!(hook_api_version("filter_endpoint"), 1);
+        assert_eq!(hook_api_version("discover_urls"), 2);
+        // Every hook came with a version this endpointo supports
+        for hook in dev::HOOKS {
+            assert!(
+                check_api_version(hook_api_version(hook)).is_ok(),
+                "{}",
+                hook
+            );
+        }
     }
 
-    /// Convert Python dictionary to Endpoint
-    #[cfg(feature = "python-plugins")]
-    fn dict_to_endpoint(&self, dict: &Bound<'_, PyDict>) -> Result<Endpoint> {
-        let url: String = dict
-            .get_item("url")
-            .and_then(|p| p.map(|item| item.extract::<String>().ok()).flatten())
-            .ok_or_else(|| Error::PluginError("Missing url in transformed endpoint".to_string()))?;
-
-        let endpoint_type_str: String = dict
-            .get_item("endpoint_type")
-            .and_then(|p| p.map(|item| item.extract::<String>().ok()).flatten())
-            .unwrap_or_else(|| "unknown".to_string());
-
-        let endpoint_type = match endpoint_type_str.as_str() {
-            "rest" => crate::types::EndpointType::Rest,
-            "graphql" => crate::types::EndpointType::GraphQL,
-            "websocket" => crate::types::EndpointType::WebSocket,
-            _ => crate::types::EndpointType::Unknown,
-        };
+    #[test]
+    fn test_plugins_run_in_order() {
+        struct Append(&'static str);
 
-        let mut endpoint = Endpoint::new(url, endpoint_type);
+        impl EndpointoPlugin for Append {
+            fn name(&self) -> &str {
+                self.0
+            }
 
-        if let Some(item) = dict.get_item("method").and_then(|i| i.ok()) {
-            if let Ok(method) = item.extract::<String>() {
-                endpoint = endpoint.with_method(method);
+            fn filter_endpoint(&self, endpoint: &Endpoint) -> bool {
+                !endpoint.url.ends_with(self.0)
             }
-        }
 
-        if let Some(item) = dict.get_item("source").and_then(|i| i.ok()) {
-            if let Ok(source) = item.extract::<String>() {
-                endpoint = endpoint.with_source(source);
+            fn transform_endpoint(&self, endpoint: Endpoint) -> Endpoint {
+                let url = format!("{}/{}", endpoint.url, self.0);
+                Endpoint { url, ..endpoint }
             }
         }
 
-        if let Some(item) = dict.get_item("line").and_then(|i| i.ok()) {
-            if let Ok(line) = item.extract::<usize>() {
-                endpoint = endpoint.with_line(line);
+        let mut manager = PluginManager::new();
+        manager.add_plugin(Box::new(Append("a")));
+        manager.add_plugin(Box::new(Append("b")));
+        assert_eq!(manager.names(), ["a", "b"]);
+
+        let endpoint = Endpoint::new("/api".to_string(), EndpointType::Rest);
+        assert_eq!(manager.transform_endpoint(endpoint, None).url, "/api/a/b");
+        let endpoint = Endpoint::new("/x/b".to_string(), EndpointType::Rest);
+        assert!(!manager.filter_endpoint(&endpoint, None));
+
+        let batch = ["/api", "/x/a", "/y"]
+            .into_iter()
+            .map(|url| (Endpoint::new(url.to_string(), EndpointType::Rest), None))
+            .collect();
+        let urls: Vec<String> = manager.process(batch).into_iter().map(|e| e.url).collect();
+        assert_eq!(urls, ["/api/a/b", "/y/a/b"]);
+    }
+
+    #[test]
+    fn test_plugin_error_policy() {
+        /// Filters and transforms that fail on URLs naming the hook
+        struct Failing;
+
+        impl EndpointoPlugin for Failing {
+            fn name(&self) -> &str {
+                "failing"
             }
-        }
 
-        if let Some(item) = dict.get_item("params").and_then(|i| i.ok()) {
-            if let Ok(params) = item.extract::<Vec<String>>() {
-                endpoint = endpoint.with_params(params);
+            fn try_filter_batch(&self, batch: &mut Batch) -> Vec<(usize, Error)> {
+                filter_each(batch, |endpoint, _| match endpoint.url.as_str() {
+                    "/filter" => Err(Error::PluginError("filter".to_string())),
+                    url => Ok(url != "/drop"),
+                })
+            }
+
+            fn try_transform_batch(&self, batch: Batch) -> (Batch, Vec<(usize, Error)>) {
+                transform_each(batch, |endpoint, _| match endpoint.url.as_str() {
+                    "/transform" => Err(Error::PluginError("transform".to_string())),
+                    _ => Ok(endpoint.clone().with_tag("seen")),
+                })
+            }
+
+            fn try_discover_urls(&self, _target: &str) -> Result<Vec<String>> {
+                Err(Error::PluginError("discover".to_string()))
             }
         }
 
-        Ok(endpoint)
+        let batch = || -> Batch {
+            ["/drop", "/filter", "/ok", "/transform"]
+                .into_iter()
+                .map(|url| (Endpoint::new(url.to_string(), EndpointType::Rest), None))
+                .collect()
+        };
+        let run = |policy| {
+            let mut manager = PluginManager::new().with_error_policy(policy);
+            manager.add_plugin(Box::new(Failing));
+            let endpoints: Vec<(String, bool)> = manager
+                .process(batch())
+                .into_iter()
+                .map(|e| (e.url.clone(), e.has_tag("seen")))
+                .collect();
+            (endpoints, manager)
+        };
+
+        let (endpoints, manager) = run(PluginErrorPolicy::Warn);
+        assert_eq!(
+            endpoints,
+            [
+                ("/filter".to_string(), true),
+                ("/ok".to_string(), true),
+                ("/transform".to_string(), false),
+            ]
+        );
+        assert!(manager.check().is_ok());
+        assert!(manager.discover_urls("https://example.com/").is_empty());
+        assert_eq!(manager.error_counts().get("failing"), Some(&3));
+
+        let (endpoints, manager) = run(PluginErrorPolicy::SkipEndpoint);
+        assert_eq!(endpoints, [("/ok".to_string(), true)]);
+        assert!(manager.check().is_ok());
+        assert_eq!(manager.error_counts().get("failing"), Some(&2));
+
+        let (_, manager) = run(PluginErrorPolicy::Fail);
+        let e = manager.check().unwrap_err();
+        assert!(
+            e.to_string()
+                .contains("failing: filter_endpoint failed on /filter"),
+            "{}",
+            e
+        );
+
+        assert_eq!(
+            "skip_endpoint".parse::<PluginErrorPolicy>(),
+            Ok(PluginErrorPolicy::SkipEndpoint)
+        );
+        assert!("ignore".parse::<PluginErrorPolicy>().is_err());
     }
-}
 
-impl Default for PluginManager {
-    fn default() -> Self {
-        Self::new()
+    #[test]
+    fn test_asset_info() {
+        let asset = AssetInfo::from_content("app.js", b"abc");
+        assert_eq!(
+            asset.content_hash.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(asset.size, 3);
+        assert_eq!(asset.status, None);
+        assert_eq!(
+            serde_json::to_value(&asset).unwrap(),
+            serde_json::json!({
+                "url": "app.js",
+                "content_hash": "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+                "size": 3
+            })
+        );
     }
 }
@@ -0,0 +1,728 @@
+//! Native plugins loaded from dynamic libraries
+//!
+//! A native plugin is a `cdylib` crate that implements [`EndpointoPlugin`]
+//! and exports it with [`declare_plugin!`](crate::declare_plugin):
+//!
+//! ```ignore
+//! use endpointo::plugins::EndpointoPlugin;
+//! use endpointo::types::Endpoint;
+//!
+//! #[derive(Default)]
+//! struct ApiOnly;
+//!
+//! impl EndpointoPlugin for ApiOnly {
+//!     fn name(&self) -> &str {
+//!         "api_only"
+//!     }
+//!
+//!     fn filter_endpoint(&self, endpoint: &Endpoint) -> bool {
+//!         endpoint.url.contains("/api/")
+//!     }
+//! }
+//!
+//! endpointo::declare_plugin!(ApiOnly::default());
+//! ```
+//!
+//! The library and the scanner talk through [`PluginVTable`], a C ABI that
+//! passes endpoints as JSON, so a plugin doesn't have to be built with the
+//! same compiler as the `endpointo` binary that loads it. Panics inside a
+//! hook are caught on the plugin side and reported as errors.
+//!
+//! An endpoint's [`AssetInfo`] rides along in its JSON under an `asset` key,
+//! which libraries built before it existed ignore.
+//!
+//! Later API versions only add functions to the end of the vtable, so a
+//! library built for an older one still loads, without the newer hooks.
+
+use super::dev::HOOKS;
+use super::{
+    check_api_version, filter_each, hook_api_version, transform_each, AssetInfo, Batch,
+    EndpointoPlugin,
+};
+use crate::error::{Error, Result};
+use crate::types::{Endpoint, ScanResult};
+use libloading::Library;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+use tracing::error;
+
+// The vtable's version is the plugin API version; libraries built for one
+// outside the supported range are refused
+pub use super::{MIN_PLUGIN_API_VERSION, PLUGIN_API_VERSION};
+
+/// Symbol a plugin library exports, returning its vtable
+pub const ENTRY_SYMBOL: &str = "endpointo_plugin";
+
+/// File extensions of loadable libraries on this platform
+pub const LIBRARY_EXTENSIONS: &[&str] = &["so", "dylib", "dll"];
+
+/// The functions a plugin library hands the scanner
+///
+/// Strings cross as NUL-terminated UTF-8; endpoints as their JSON form.
+/// Strings returned by the plugin are released with `free_string`.
+#[repr(C)]
+pub struct PluginVTable {
+    /// Always first, so it can be read before trusting the rest
+    pub api_version: u32,
+    pub instance: *mut c_void,
+    /// The plugin's name
+    pub name: unsafe extern "C" fn(*const c_void) -> *mut c_char,
+    /// 1 to keep the endpoint, 0 to drop it, -1 on error
+    pub filter: unsafe extern "C" fn(*const c_void, *const c_char) -> i32,
+    /// The transformed endpoint, or null on error
+    pub transform: unsafe extern "C" fn(*const c_void, *const c_char) -> *mut c_char,
+    /// A JSON array of new endpoints found in `len` bytes of content from
+    /// a source, or null on error
+    pub generate:
+        unsafe extern "C" fn(*const c_void, *const u8, usize, *const c_char) -> *mut c_char,
+    pub free_string: unsafe extern "C" fn(*mut c_char),
+    /// Drop the instance and the vtable itself
+    pub drop: unsafe extern "C" fn(*mut PluginVTable),
+    /// 1 if the plugin writes output, else 0 (API 2)
+    pub writes_output: unsafe extern "C" fn(*const c_void) -> i32,
+    /// Write a scan result given as JSON; null on success, else the error
+    /// message (API 2)
+    pub write_output: unsafe extern "C" fn(*const c_void, *const c_char) -> *mut c_char,
+    /// A JSON array of URLs to fetch for a scan target, or null on error
+    /// (API 2)
+    pub discover: unsafe extern "C" fn(*const c_void, *const c_char) -> *mut c_char,
+}
+
+/// Whether a path names a dynamic library rather than a Python plugin
+pub fn is_library(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| LIBRARY_EXTENSIONS.contains(&ext))
+}
+
+/// Export a plugin's hooks behind a vtable
+///
+/// Called by the entry point [`declare_plugin!`](crate::declare_plugin)
+/// generates; plugins don't need to call it themselves.
+pub fn export<P: EndpointoPlugin + 'static>(plugin: P) -> *mut PluginVTable {
+    Box::into_raw(Box::new(PluginVTable {
+        api_version: PLUGIN_API_VERSION,
+        instance: Box::into_raw(Box::new(plugin)) as *mut c_void,
+        name: name_shim::<P>,
+        filter: filter_shim::<P>,
+        transform: transform_shim::<P>,
+        generate: generate_shim::<P>,
+        free_string: free_string_shim,
+        drop: drop_shim::<P>,
+        writes_output: writes_output_shim::<P>,
+        write_output: write_output_shim::<P>,
+        discover: discover_shim::<P>,
+    }))
+}
+
+/// Export a type implementing [`EndpointoPlugin`] from a `cdylib` crate
+///
+/// Takes an expression building the plugin, evaluated once when the
+/// library is loaded.
+#[macro_export]
+macro_rules! declare_plugin {
+    ($plugin:expr) => {
+        #[no_mangle]
+        pub extern "C" fn endpointo_plugin() -> *mut $crate::plugins::native::PluginVTable {
+            $crate::plugins::native::export($plugin)
+        }
+    };
+}
+
+fn into_c_string(s: String) -> *mut c_char {
+    CString::new(s).map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// An endpoint's JSON, with the asset it was found in under `asset`
+fn endpoint_json(endpoint: &Endpoint, asset: Option<&AssetInfo>) -> Result<CString> {
+    let mut value = serde_json::to_value(endpoint)?;
+    if let (Some(asset), Some(object)) = (asset, value.as_object_mut()) {
+        object.insert("asset".to_string(), serde_json::to_value(asset)?);
+    }
+    CString::new(value.to_string()).map_err(|e| Error::PluginError(e.to_string()))
+}
+
+unsafe fn read_endpoint(json: *const c_char) -> Option<(Endpoint, Option<AssetInfo>)> {
+    let json = CStr::from_ptr(json).to_str().ok()?;
+    let mut value: serde_json::Value = serde_json::from_str(json).ok()?;
+    let asset = value
+        .as_object_mut()?
+        .remove("asset")
+        .and_then(|asset| serde_json::from_value(asset).ok());
+    Some((serde_json::from_value(value).ok()?, asset))
+}
+
+unsafe extern "C" fn name_shim<P: EndpointoPlugin>(instance: *const c_void) -> *mut c_char {
+    let plugin = &*(instance as *const P);
+    catch_unwind(AssertUnwindSafe(|| plugin.name().to_string()))
+        .map_or(std::ptr::null_mut(), into_c_string)
+}
+
+unsafe extern "C" fn filter_shim<P: EndpointoPlugin>(
+    instance: *const c_void,
+    endpoint: *const c_char,
+) -> i32 {
+    let plugin = &*(instance as *const P);
+    let Some((endpoint, asset)) = read_endpoint(endpoint) else {
+        return -1;
+    };
+    catch_unwind(AssertUnwindSafe(|| {
+        plugin.filter_endpoint_in(&endpoint, asset.as_ref())
+    }))
+    .map_or(-1, i32::from)
+}
+
+unsafe extern "C" fn transform_shim<P: EndpointoPlugin>(
+    instance: *const c_void,
+    endpoint: *const c_char,
+) -> *mut c_char {
+    let plugin = &*(instance as *const P);
+    let Some((endpoint, asset)) = read_endpoint(endpoint) else {
+        return std::ptr::null_mut();
+    };
+    catch_unwind(AssertUnwindSafe(|| {
+        plugin.transform_endpoint_in(endpoint, asset.as_ref())
+    }))
+    .ok()
+    .and_then(|endpoint| serde_json::to_string(&endpoint).ok())
+    .map_or(std::ptr::null_mut(), into_c_string)
+}
+
+unsafe extern "C" fn generate_shim<P: EndpointoPlugin>(
+    instance: *const c_void,
+    content: *const u8,
+    len: usize,
+    source: *const c_char,
+) -> *mut c_char {
+    let plugin = &*(instance as *const P);
+    let content = String::from_utf8_lossy(std::slice::from_raw_parts(content, len));
+    let source = CStr::from_ptr(source).to_string_lossy();
+    catch_unwind(AssertUnwindSafe(|| {
+        plugin.generate_endpoints(&content, &source)
+    }))
+    .ok()
+    .and_then(|endpoints| serde_json::to_string(&endpoints).ok())
+    .map_or(std::ptr::null_mut(), into_c_string)
+}
+
+unsafe extern "C" fn writes_output_shim<P: EndpointoPlugin>(instance: *const c_void) -> i32 {
+    let plugin = &*(instance as *const P);
+    catch_unwind(AssertUnwindSafe(|| plugin.writes_output())).map_or(0, i32::from)
+}
+
+unsafe extern "C" fn write_output_shim<P: EndpointoPlugin>(
+    instance: *const c_void,
+    result: *const c_char,
+) -> *mut c_char {
+    let plugin = &*(instance as *const P);
+    let result = match CStr::from_ptr(result)
+        .to_str()
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str(json).map_err(|e| e.to_string()))
+    {
+        Ok(result) => result,
+        Err(e) => return into_c_string(format!("unreadable result: {}", e)),
+    };
+    match catch_unwind(AssertUnwindSafe(|| plugin.write_output(&result))) {
+        Ok(Ok(())) => std::ptr::null_mut(),
+        Ok(Err(Error::PluginError(message))) => into_c_string(message),
+        Ok(Err(e)) => into_c_string(e.to_string()),
+        Err(_) => into_c_string("panicked".to_string()),
+    }
+}
+
+unsafe extern "C" fn discover_shim<P: EndpointoPlugin>(
+    instance: *const c_void,
+    target: *const c_char,
+) -> *mut c_char {
+    let plugin = &*(instance as *const P);
+    let target = CStr::from_ptr(target).to_string_lossy();
+    catch_unwind(AssertUnwindSafe(|| plugin.discover_urls(&target)))
+        .ok()
+        .and_then(|urls| serde_json::to_string(&urls).ok())
+        .map_or(std::ptr::null_mut(), into_c_string)
+}
+
+unsafe extern "C" fn free_string_shim(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+unsafe extern "C" fn drop_shim<P: EndpointoPlugin>(vtable: *mut PluginVTable) {
+    let vtable = Box::from_raw(vtable);
+    let _ = catch_unwind(AssertUnwindSafe(|| {
+        drop(Box::from_raw(vtable.instance as *mut P))
+    }));
+}
+
+/// A plugin loaded from a dynamic library
+pub struct NativePlugin {
+    name: String,
+    vtable: *mut PluginVTable,
+    /// The library's API version, which says which hooks its vtable has
+    api_version: u32,
+    // Dropped after the vtable, whose code lives in the library
+    _library: Option<Library>,
+}
+
+// The vtable's instance is a `Send + Sync` plugin, and the library handle
+// is only closed on drop
+unsafe impl Send for NativePlugin {}
+unsafe impl Sync for NativePlugin {}
+
+impl NativePlugin {
+    /// Load a plugin library and check it targets this plugin API
+    pub fn load(path: &Path) -> Result<Self> {
+        let fail = |msg: String| Error::PluginError(format!("{}: {}", path.display(), msg));
+
+        // SAFETY: loading runs the library's initializers, which is what
+        // loading a plugin asks for
+        let library = unsafe { Library::new(path) }.map_err(|e| {
+            // The Unix loader's message names the file already
+            let msg = e.to_string();
+            if msg.contains(path.to_string_lossy().as_ref()) {
+                Error::PluginError(msg)
+            } else {
+                fail(msg)
+            }
+        })?;
+        // SAFETY: the symbol is the entry point `declare_plugin!` defines
+        let entry =
+            unsafe { library.get::<extern "C" fn() -> *mut PluginVTable>(ENTRY_SYMBOL.as_bytes()) }
+                .map_err(|_| {
+                    fail(format!(
+                        "no `{}` symbol; export the plugin with `endpointo::declare_plugin!`",
+                        ENTRY_SYMBOL
+                    ))
+                })?;
+        let vtable = entry();
+        if vtable.is_null() {
+            return Err(fail("the plugin failed to initialize".to_string()));
+        }
+        // SAFETY: every vtable version starts with the API version
+        let version = unsafe { (*vtable).api_version };
+        if let Err(e) = check_api_version(version) {
+            // Its drop function can't be trusted either, so leak the plugin
+            std::mem::forget(library);
+            return Err(fail(e));
+        }
+        Self::from_vtable(vtable, Some(library))
+    }
+
+    /// Load a plugin library from a private copy of it
+    ///
+    /// The loader hands back the image it already has open for a path, so a
+    /// rebuilt library would keep running its old code for as long as a
+    /// plugin loaded from it is alive; and overwriting an open library's
+    /// file in place changes the code under it.
+    pub fn load_copy(path: &Path) -> Result<Self> {
+        let fail = |e: std::io::Error| Error::PluginError(format!("{}: {}", path.display(), e));
+        // A fresh directory only this user can write to, so nobody can plant
+        // a file or symlink where the copy goes before it's loaded
+        let dir = tempfile::Builder::new()
+            .prefix("endpointo-plugin-")
+            .tempdir()
+            .map_err(fail)?;
+        let copy = dir.path().join(path.file_name().unwrap_or_default());
+        std::fs::copy(path, &copy).map_err(fail)?;
+        let plugin = Self::load(&copy);
+        // The open image doesn't need its file; where an open library can't
+        // be removed, the copy is left in the temp directory
+        let _ = dir.close();
+        plugin.map_err(|e| match e {
+            Error::PluginError(msg) => Error::PluginError(msg.replace(
+                copy.to_string_lossy().as_ref(),
+                path.to_string_lossy().as_ref(),
+            )),
+            e => e,
+        })
+    }
+
+    fn from_vtable(vtable: *mut PluginVTable, library: Option<Library>) -> Result<Self> {
+        let mut plugin = Self {
+            name: String::new(),
+            vtable,
+            // SAFETY: every vtable version starts with the API version
+            api_version: unsafe { (*vtable).api_version },
+            _library: library,
+        };
+        // SAFETY: the vtable is valid until `drop`
+        plugin.name = unsafe {
+            let name = ((*vtable).name)((*vtable).instance);
+            plugin.take_string(name)
+        }
+        .ok_or_else(|| Error::PluginError("the plugin has no name".to_string()))?;
+        Ok(plugin)
+    }
+
+    /// Copy a string the plugin returned and free it
+    unsafe fn take_string(&self, s: *mut c_char) -> Option<String> {
+        if s.is_null() {
+            return None;
+        }
+        let owned = CStr::from_ptr(s).to_str().ok().map(str::to_string);
+        ((*self.vtable).free_string)(s);
+        owned
+    }
+
+    fn error(&self, hook: &str, detail: &str) -> Error {
+        Error::PluginError(format!("{}: {} {}", self.name, hook, detail))
+    }
+
+    /// Whether the plugin keeps an endpoint
+    pub fn try_filter(&self, endpoint: &Endpoint, asset: Option<&AssetInfo>) -> Result<bool> {
+        let json = endpoint_json(endpoint, asset)
+            .map_err(|e| self.error("filter_endpoint", &e.to_string()))?;
+        // SAFETY: the vtable is valid until `drop`
+        match unsafe { ((*self.vtable).filter)((*self.vtable).instance, json.as_ptr()) } {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(self.error("filter_endpoint", "failed")),
+        }
+    }
+
+    /// The endpoint as the plugin transforms it
+    pub fn try_transform(
+        &self,
+        endpoint: &Endpoint,
+        asset: Option<&AssetInfo>,
+    ) -> Result<Endpoint> {
+        let json = endpoint_json(endpoint, asset)
+            .map_err(|e| self.error("transform_endpoint", &e.to_string()))?;
+        // SAFETY: the vtable is valid until `drop`
+        let transformed = unsafe {
+            let s = ((*self.vtable).transform)((*self.vtable).instance, json.as_ptr());
+            self.take_string(s)
+        }
+        .ok_or_else(|| self.error("transform_endpoint", "failed"))?;
+        serde_json::from_str(&transformed)
+            .map_err(|e| self.error("transform_endpoint", &format!("returned {}", e)))
+    }
+
+    /// Endpoints the plugin finds in content from a source
+    pub fn try_generate(&self, content: &str, source: &str) -> Result<Vec<Endpoint>> {
+        let source = CString::new(source.replace('\0', ""))
+            .map_err(|e| self.error("generate_endpoints", &e.to_string()))?;
+        // SAFETY: the vtable is valid until `drop`, and content outlives the call
+        let generated = unsafe {
+            let s = ((*self.vtable).generate)(
+                (*self.vtable).instance,
+                content.as_ptr(),
+                content.len(),
+                source.as_ptr(),
+            );
+            self.take_string(s)
+        }
+        .ok_or_else(|| self.error("generate_endpoints", "failed"))?;
+        serde_json::from_str(&generated)
+            .map_err(|e| self.error("generate_endpoints", &format!("returned {}", e)))
+    }
+
+    /// URLs the plugin would have a scan of `target` fetch
+    pub fn try_discover(&self, target: &str) -> Result<Vec<String>> {
+        if !self.has_api(2) {
+            return Ok(Vec::new());
+        }
+        let target = CString::new(target.replace('\0', ""))
+            .map_err(|e| self.error("discover_urls", &e.to_string()))?;
+        // SAFETY: the vtable is valid until `drop`, and has this hook from API 2
+        let urls = unsafe {
+            let s = ((*self.vtable).discover)((*self.vtable).instance, target.as_ptr());
+            self.take_string(s)
+        }
+        .ok_or_else(|| self.error("discover_urls", "failed"))?;
+        serde_json::from_str(&urls)
+            .map_err(|e| self.error("discover_urls", &format!("returned {}", e)))
+    }
+
+    /// The plugin API version the library was built for
+    pub fn api_version(&self) -> u32 {
+        self.api_version
+    }
+
+    /// Hooks the library's vtable has, in the order endpointo calls them
+    ///
+    /// A vtable has every hook of its API version, whether the plugin
+    /// implements it or keeps the trait's default.
+    pub fn hooks(&self) -> Vec<&'static str> {
+        HOOKS
+            .iter()
+            .copied()
+            .filter(|hook| self.has_api(hook_api_version(hook)))
+            .collect()
+    }
+
+    /// Whether the library's vtable has the hooks of an API version
+    pub(super) fn has_api(&self, version: u32) -> bool {
+        self.api_version >= version
+    }
+}
+
+impl EndpointoPlugin for NativePlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn filter_endpoint(&self, endpoint: &Endpoint) -> bool {
+        self.filter_endpoint_in(endpoint, None)
+    }
+
+    fn transform_endpoint(&self, endpoint: Endpoint) -> Endpoint {
+        self.transform_endpoint_in(endpoint, None)
+    }
+
+    fn filter_endpoint_in(&self, endpoint: &Endpoint, asset: Option<&AssetInfo>) -> bool {
+        self.try_filter(endpoint, asset).unwrap_or_else(|e| {
+            error!("Plugin filter_endpoint error: {}", e);
+            true
+        })
+    }
+
+    fn transform_endpoint_in(&self, endpoint: Endpoint, asset: Option<&AssetInfo>) -> Endpoint {
+        match self.try_transform(&endpoint, asset) {
+            Ok(transformed) => transformed,
+            Err(e) => {
+                error!("Plugin transform_endpoint error: {}", e);
+                endpoint
+            }
+        }
+    }
+
+    fn try_filter_batch(&self, batch: &mut Batch) -> Vec<(usize, Error)> {
+        filter_each(batch, |endpoint, asset| self.try_filter(endpoint, asset))
+    }
+
+    fn try_transform_batch(&self, batch: Batch) -> (Batch, Vec<(usize, Error)>) {
+        transform_each(batch, |endpoint, asset| self.try_transform(endpoint, asset))
+    }
+
+    fn generate_endpoints(&self, content: &str, source: &str) -> Vec<Endpoint> {
+        self.try_generate(content, source).unwrap_or_else(|e| {
+            error!("Plugin generate_endpoints error: {}", e);
+            Vec::new()
+        })
+    }
+
+    fn discover_urls(&self, target: &str) -> Vec<String> {
+        self.try_discover(target).unwrap_or_else(|e| {
+            error!("Plugin discover_urls error: {}", e);
+            Vec::new()
+        })
+    }
+
+    fn try_generate_endpoints(&self, content: &str, source: &str) -> Result<Vec<Endpoint>> {
+        self.try_generate(content, source)
+    }
+
+    fn try_discover_urls(&self, target: &str) -> Result<Vec<String>> {
+        self.try_discover(target)
+    }
+
+    fn writes_output(&self) -> bool {
+        // SAFETY: the vtable is valid until `drop`, and has this hook from API 2
+        self.has_api(2) && unsafe { ((*self.vtable).writes_output)((*self.vtable).instance) == 1 }
+    }
+
+    fn write_output(&self, result: &ScanResult) -> Result<()> {
+        if !self.has_api(2) {
+            return Ok(());
+        }
+        let json = CString::new(serde_json::to_string(result)?)
+            .map_err(|e| Error::PluginError(e.to_string()))?;
+        // SAFETY: the vtable is valid until `drop`, and has this hook from API 2
+        let message = unsafe {
+            let s = ((*self.vtable).write_output)((*self.vtable).instance, json.as_ptr());
+            self.take_string(s)
+        };
+        match message {
+            None => Ok(()),
+            Some(message) => Err(Error::PluginError(message)),
+        }
+    }
+}
+
+impl Drop for NativePlugin {
+    fn drop(&mut self) {
+        // SAFETY: the vtable hasn't been dropped yet
+        unsafe { ((*self.vtable).drop)(self.vtable) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EndpointType;
+
+    struct Versioned;
+
+    impl EndpointoPlugin for Versioned {
+        fn name(&self) -> &str {
+            "versioned"
+        }
+
+        fn filter_endpoint(&self, endpoint: &Endpoint) -> bool {
+            if endpoint.url.contains("panic") {
+                panic!("bad endpoint");
+            }
+            !endpoint.url.starts_with("/static/")
+        }
+
+        fn transform_endpoint(&self, endpoint: Endpoint) -> Endpoint {
+            let mut metadata = endpoint.metadata.clone().unwrap_or_default();
+            metadata.insert("reviewed_by".to_string(), "versioned".to_string());
+            endpoint.with_metadata(metadata).with_tag("versioned")
+        }
+
+        fn transform_endpoint_in(&self, endpoint: Endpoint, asset: Option<&AssetInfo>) -> Endpoint {
+            match asset.and_then(|asset| asset.status) {
+                Some(status) => self
+                    .transform_endpoint(endpoint)
+                    .with_tag(format!("status-{}", status)),
+                None => self.transform_endpoint(endpoint),
+            }
+        }
+
+        fn generate_endpoints(&self, content: &str, source: &str) -> Vec<Endpoint> {
+            content
+                .split_whitespace()
+                .filter(|word| word.starts_with("api:"))
+                .map(|word| {
+                    Endpoint::new(format!("/{}", &word[4..]), EndpointType::Rest)
+                        .with_source(source.to_string())
+                })
+                .collect()
+        }
+
+        fn discover_urls(&self, target: &str) -> Vec<String> {
+            vec![format!("{}/inventory.js", target.trim_end_matches('/'))]
+        }
+
+        fn writes_output(&self) -> bool {
+            true
+        }
+
+        fn write_output(&self, result: &ScanResult) -> Result<()> {
+            match result.target.as_str() {
+                "down" => Err(Error::PluginError("ticketing is down".to_string())),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_api_2_hooks_cross_the_vtable() {
+        let plugin = NativePlugin::from_vtable(export(Versioned), None).unwrap();
+        assert!(plugin.writes_output());
+        let result = |target: &str| ScanResult {
+            schema_version: 1,
+            tool_version: String::new(),
+            target: target.to_string(),
+            timestamp: String::new(),
+            total_endpoints: 0,
+            endpoints: Vec::new(),
+            findings: Vec::new(),
+            wordlist: Vec::new(),
+            technologies: Vec::new(),
+            stats: None,
+        };
+        assert!(plugin.write_output(&result("https://example.com")).is_ok());
+        let e = plugin.write_output(&result("down")).unwrap_err();
+        assert_eq!(e.to_string(), "Plugin error: ticketing is down");
+
+        // A library built for API 1 has none of these hooks to call
+        let vtable = export(Versioned);
+        unsafe { (*vtable).api_version = 1 };
+        let plugin = NativePlugin::from_vtable(vtable, None).unwrap();
+        assert_eq!(plugin.api_version(), 1);
+        assert_eq!(
+            plugin.hooks(),
+            [
+                "generate_endpoints",
+                "filter_endpoint",
+                "transform_endpoint"
+            ]
+        );
+        assert!(!plugin.writes_output());
+        assert!(plugin.write_output(&result("down")).is_ok());
+        assert!(plugin.discover_urls("https://example.com/").is_empty());
+    }
+
+    #[test]
+    fn test_hooks_cross_the_vtable() {
+        let plugin = NativePlugin::from_vtable(export(Versioned), None).unwrap();
+        assert_eq!(plugin.name(), "versioned");
+
+        let endpoint = Endpoint::new("/api/users".to_string(), EndpointType::Rest);
+        assert!(plugin.filter_endpoint(&endpoint));
+        assert!(!plugin.filter_endpoint(&Endpoint::new(
+            "/static/app.js".to_string(),
+            EndpointType::Unknown
+        )));
+        // A panic is an error, and the endpoint is kept
+        let panicky = Endpoint::new("/panic".to_string(), EndpointType::Rest);
+        assert!(plugin.try_filter(&panicky, None).is_err());
+        assert!(plugin.filter_endpoint(&panicky));
+
+        let transformed = plugin.transform_endpoint(endpoint.clone());
+        assert_eq!(transformed.url, "/api/users");
+        assert_eq!(transformed.tags, Some(vec!["versioned".to_string()]));
+        // Metadata crosses both ways, keeping what the plugin didn't touch
+        let annotated = endpoint.clone().with_metadata(
+            [("client".to_string(), "axios".to_string())]
+                .into_iter()
+                .collect(),
+        );
+        let metadata = plugin.transform_endpoint(annotated).metadata.unwrap();
+        assert_eq!(metadata["client"], "axios");
+        assert_eq!(metadata["reviewed_by"], "versioned");
+        let asset = AssetInfo {
+            status: Some(200),
+            ..AssetInfo::from_content("app.js", b"")
+        };
+        let transformed = plugin.transform_endpoint_in(endpoint, Some(&asset));
+        assert_eq!(
+            transformed.tags,
+            Some(vec!["versioned".to_string(), "status-200".to_string()])
+        );
+
+        assert_eq!(
+            plugin.discover_urls("https://example.com/"),
+            ["https://example.com/inventory.js"]
+        );
+
+        let generated = plugin.generate_endpoints("x api:orders y api:carts", "app.js");
+        assert_eq!(generated.len(), 2);
+        assert_eq!(generated[1].url, "/carts");
+        assert_eq!(generated[1].source.as_deref(), Some("app.js"));
+    }
+
+    #[test]
+    fn test_load_errors() {
+        assert!(is_library(Path::new("plugins/libapi_only.so")));
+        assert!(!is_library(Path::new("plugins/dedup.py")));
+
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("libmissing.so");
+        assert!(matches!(
+            NativePlugin::load(&missing),
+            Err(Error::PluginError(_))
+        ));
+        let not_a_library = dir.path().join("libfake.so");
+        std::fs::write(&not_a_library, "not a library").unwrap();
+        assert!(NativePlugin::load(&not_a_library).is_err());
+
+        // Errors from loading a copy name the file it was copied from
+        let message = NativePlugin::load_copy(&not_a_library)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(
+            message.contains(&*not_a_library.to_string_lossy()),
+            "{}",
+            message
+        );
+        assert!(NativePlugin::load_copy(&missing).is_err());
+    }
+}
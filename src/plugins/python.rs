@@ -0,0 +1,449 @@
+//! Python plugins, run through pyo3
+//!
+//! A Python plugin is a module defining any of `discover_urls`,
+//! `generate_endpoints`, `filter_endpoint`, `transform_endpoint` and
+//! `write_output`; see `plugins/README.md`. It declares the plugin API
+//! version it targets as `ENDPOINTO_PLUGIN_API`, 1 if it doesn't, and may
+//! only define the hooks that version has.
+//! Endpoints cross as dicts with the keys `url`, `method`,
+//! `endpoint_type`, `source`, `line`, `params` and `metadata`, plus `asset`
+//! describing the asset they were found in when it's known. Keys a plugin
+//! adds beyond these come back in `metadata`. `write_output` gets the whole
+//! scan result as the dict its JSON form decodes to.
+
+use super::dev::HOOKS;
+use super::{
+    check_api_version, filter_each, hook_api_version, transform_each, AssetInfo, Batch,
+    EndpointoPlugin, MIN_PLUGIN_API_VERSION,
+};
+use crate::error::{Error, Result};
+use crate::types::{Endpoint, ScanResult};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::{error, info};
+
+/// A plugin loaded from a Python file
+pub struct PythonPlugin {
+    name: String,
+    module: Py<PyAny>,
+    api_version: u32,
+    /// Hooks the module defines, found when it's loaded
+    hooks: Vec<&'static str>,
+}
+
+impl PythonPlugin {
+    /// Load a Python plugin from file
+    pub fn load(path: &Path) -> Result<Self> {
+        info!("Loading plugin from: {}", path.display());
+
+        let code = std::fs::read_to_string(path)?;
+        let filename = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("plugin.py");
+
+        let fail = |msg: String| Error::PluginError(format!("{}: {}", path.display(), msg));
+
+        Python::with_gil(|py| -> Result<Self> {
+            let module = PyModule::from_code_bound(py, &code, filename, filename)
+                .map_err(|e| Error::PluginError(format!("Failed to parse plugin: {}", e)))?;
+
+            let api_version = match module.getattr("ENDPOINTO_PLUGIN_API") {
+                Ok(version) => version
+                    .extract::<u32>()
+                    .map_err(|_| fail("ENDPOINTO_PLUGIN_API must be a whole number".to_string()))?,
+                Err(_) => MIN_PLUGIN_API_VERSION,
+            };
+            check_api_version(api_version).map_err(fail)?;
+
+            let mut hooks = Vec::new();
+            for &hook in HOOKS {
+                let Ok(hook_fn) = module.getattr(hook) else {
+                    continue;
+                };
+                if !hook_fn.is_callable() {
+                    return Err(fail(format!("{} is not a function", hook)));
+                }
+                let needs = hook_api_version(hook);
+                if needs > api_version {
+                    return Err(fail(format!(
+                        "{} needs plugin API {}, but the plugin targets {}; set ENDPOINTO_PLUGIN_API = {}",
+                        hook, needs, api_version, needs
+                    )));
+                }
+                hooks.push(hook);
+            }
+
+            Ok(Self {
+                name: filename.to_string(),
+                module: module.into_any().unbind(),
+                api_version,
+                hooks,
+            })
+        })
+    }
+
+    /// The plugin API version the plugin targets
+    pub fn api_version(&self) -> u32 {
+        self.api_version
+    }
+
+    /// Hooks the plugin defines, in the order endpointo calls them
+    pub fn hooks(&self) -> &[&'static str] {
+        &self.hooks
+    }
+
+    /// A hook the plugin defines, or `None`
+    fn hook<'py>(&self, py: Python<'py>, hook: &str) -> Option<Bound<'py, PyAny>> {
+        if !self.hooks.contains(&hook) {
+            return None;
+        }
+        self.module.bind(py).getattr(hook).ok()
+    }
+
+    /// The loaded module
+    pub fn module(&self) -> &Py<PyAny> {
+        &self.module
+    }
+
+    fn error(&self, hook: &str, detail: impl std::fmt::Display) -> Error {
+        Error::PluginError(format!("{}: {} {}", self.name, hook, detail))
+    }
+
+    /// Whether the plugin keeps an endpoint
+    pub fn try_filter(&self, endpoint: &Endpoint, asset: Option<&AssetInfo>) -> Result<bool> {
+        Python::with_gil(|py| {
+            let Some(filter_fn) = self.hook(py, "filter_endpoint") else {
+                return Ok(true);
+            };
+            let result = filter_fn
+                .call1((endpoint_to_dict_in(py, endpoint, asset),))
+                .map_err(|e| self.error("filter_endpoint", format!("raised {}", e)))?;
+            result.extract::<bool>().map_err(|_| {
+                self.error(
+                    "filter_endpoint",
+                    format!("returned {} instead of a bool", result.get_type()),
+                )
+            })
+        })
+    }
+
+    /// The endpoint as the plugin transforms it
+    pub fn try_transform(
+        &self,
+        endpoint: &Endpoint,
+        asset: Option<&AssetInfo>,
+    ) -> Result<Endpoint> {
+        Python::with_gil(|py| {
+            let Some(transform_fn) = self.hook(py, "transform_endpoint") else {
+                return Ok(endpoint.clone());
+            };
+            let result = transform_fn
+                .call1((endpoint_to_dict_in(py, endpoint, asset),))
+                .map_err(|e| self.error("transform_endpoint", format!("raised {}", e)))?;
+            let dict = result.downcast::<PyDict>().map_err(|_| {
+                self.error(
+                    "transform_endpoint",
+                    format!("returned {} instead of a dict", result.get_type()),
+                )
+            })?;
+            dict_to_endpoint(dict).map_err(|e| self.error("transform_endpoint", e))
+        })
+    }
+
+    /// Endpoints the plugin finds in content from a source
+    pub fn try_generate(&self, content: &str, source: &str) -> Result<Vec<Endpoint>> {
+        Python::with_gil(|py| {
+            let Some(generate_fn) = self.hook(py, "generate_endpoints") else {
+                return Ok(Vec::new());
+            };
+            let items = generate_fn
+                .call1((content, source))
+                .and_then(|r| r.iter())
+                .map_err(|e| self.error("generate_endpoints", format!("raised {}", e)))?;
+            let mut endpoints = Vec::new();
+            for item in items {
+                let item =
+                    item.map_err(|e| self.error("generate_endpoints", format!("raised {}", e)))?;
+                let dict = item.downcast::<PyDict>().map_err(|_| {
+                    self.error(
+                        "generate_endpoints",
+                        format!("returned {} instead of a dict", item.get_type()),
+                    )
+                })?;
+                endpoints
+                    .push(dict_to_endpoint(dict).map_err(|e| self.error("generate_endpoints", e))?);
+            }
+            Ok(endpoints)
+        })
+    }
+
+    /// URLs of more assets the plugin knows of for a target
+    pub fn try_discover(&self, target: &str) -> Result<Vec<String>> {
+        Python::with_gil(|py| {
+            let Some(discover_fn) = self.hook(py, "discover_urls") else {
+                return Ok(Vec::new());
+            };
+            discover_fn
+                .call1((target,))
+                .and_then(|urls| urls.extract::<Vec<String>>())
+                .map_err(|e| self.error("discover_urls", format!("raised {}", e)))
+        })
+    }
+}
+
+impl EndpointoPlugin for PythonPlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn filter_endpoint(&self, endpoint: &Endpoint) -> bool {
+        self.filter_endpoint_in(endpoint, None)
+    }
+
+    fn transform_endpoint(&self, endpoint: Endpoint) -> Endpoint {
+        self.transform_endpoint_in(endpoint, None)
+    }
+
+    fn filter_endpoint_in(&self, endpoint: &Endpoint, asset: Option<&AssetInfo>) -> bool {
+        self.try_filter(endpoint, asset).unwrap_or_else(|e| {
+            error!("Plugin filter_endpoint error: {}", e);
+            true
+        })
+    }
+
+    fn transform_endpoint_in(&self, endpoint: Endpoint, asset: Option<&AssetInfo>) -> Endpoint {
+        match self.try_transform(&endpoint, asset) {
+            Ok(transformed) => transformed,
+            Err(e) => {
+                error!("Plugin transform_endpoint error: {}", e);
+                endpoint
+            }
+        }
+    }
+
+    // The GIL is taken once per batch; the per-endpoint calls inside then
+    // reuse it
+    fn filter_batch(&self, batch: &mut Batch) {
+        for (_, e) in self.try_filter_batch(batch) {
+            error!("Plugin filter_endpoint error: {}", e);
+        }
+    }
+
+    fn transform_batch(&self, batch: Batch) -> Batch {
+        let (batch, failures) = self.try_transform_batch(batch);
+        for (_, e) in failures {
+            error!("Plugin transform_endpoint error: {}", e);
+        }
+        batch
+    }
+
+    fn try_filter_batch(&self, batch: &mut Batch) -> Vec<(usize, Error)> {
+        Python::with_gil(|_| filter_each(batch, |endpoint, asset| self.try_filter(endpoint, asset)))
+    }
+
+    fn try_transform_batch(&self, batch: Batch) -> (Batch, Vec<(usize, Error)>) {
+        Python::with_gil(|_| {
+            transform_each(batch, |endpoint, asset| self.try_transform(endpoint, asset))
+        })
+    }
+
+    fn generate_endpoints(&self, content: &str, source: &str) -> Vec<Endpoint> {
+        self.try_generate(content, source).unwrap_or_else(|e| {
+            error!("Plugin generate_endpoints error: {}", e);
+            Vec::new()
+        })
+    }
+
+    fn discover_urls(&self, target: &str) -> Vec<String> {
+        self.try_discover(target).unwrap_or_else(|e| {
+            error!("Plugin discover_urls error: {}", e);
+            Vec::new()
+        })
+    }
+
+    fn try_generate_endpoints(&self, content: &str, source: &str) -> Result<Vec<Endpoint>> {
+        self.try_generate(content, source)
+    }
+
+    fn try_discover_urls(&self, target: &str) -> Result<Vec<String>> {
+        self.try_discover(target)
+    }
+
+    fn writes_output(&self) -> bool {
+        self.hooks.contains(&"write_output")
+    }
+
+    fn write_output(&self, result: &ScanResult) -> Result<()> {
+        let json = serde_json::to_string(result)?;
+        Python::with_gil(|py| {
+            let plugin = self.module.bind(py);
+            let result = py.import_bound("json")?.getattr("loads")?.call1((json,))?;
+            plugin.getattr("write_output")?.call1((result,))?;
+            Ok::<_, PyErr>(())
+        })
+        .map_err(|e| Error::PluginError(e.to_string()))
+    }
+}
+
+/// Convert Endpoint to Python dictionary
+pub(crate) fn endpoint_to_dict<'py>(py: Python<'py>, endpoint: &Endpoint) -> Bound<'py, PyDict> {
+    let dict = PyDict::new_bound(py);
+    let _ = dict.set_item("url", &endpoint.url).ok();
+    let _ = dict.set_item("method", &endpoint.method).ok();
+    let _ = dict
+        .set_item(
+            "endpoint_type",
+            format!("{:?}", endpoint.endpoint_type).to_lowercase(),
+        )
+        .ok();
+    let _ = dict.set_item("source", &endpoint.source).ok();
+    let _ = dict.set_item("line", endpoint.line).ok();
+    let _ = dict.set_item("params", &endpoint.params).ok();
+    let _ = dict
+        .set_item("metadata", endpoint.metadata.clone().unwrap_or_default())
+        .ok();
+    dict
+}
+
+/// Convert Endpoint to Python dictionary, with the asset it was found in
+/// under `asset`
+pub(crate) fn endpoint_to_dict_in<'py>(
+    py: Python<'py>,
+    endpoint: &Endpoint,
+    asset: Option<&AssetInfo>,
+) -> Bound<'py, PyDict> {
+    let dict = endpoint_to_dict(py, endpoint);
+    if let Some(asset) = asset {
+        let _ = dict.set_item("asset", asset_to_dict(py, asset)).ok();
+    }
+    dict
+}
+
+/// Convert AssetInfo to Python dictionary
+fn asset_to_dict<'py>(py: Python<'py>, asset: &AssetInfo) -> Bound<'py, PyDict> {
+    let dict = PyDict::new_bound(py);
+    let _ = dict.set_item("url", &asset.url).ok();
+    let _ = dict.set_item("final_url", &asset.final_url).ok();
+    let _ = dict.set_item("status", asset.status).ok();
+    let _ = dict.set_item("content_type", &asset.content_type).ok();
+    let _ = dict.set_item("headers", &asset.headers).ok();
+    let _ = dict.set_item("content_hash", &asset.content_hash).ok();
+    let _ = dict.set_item("size", asset.size).ok();
+    dict
+}
+
+/// Keys of an endpoint dict that aren't kept in `metadata` when they come
+/// back; `asset` is read-only
+const ENDPOINT_KEYS: &[&str] = &[
+    "url",
+    "method",
+    "endpoint_type",
+    "source",
+    "line",
+    "params",
+    "metadata",
+    "asset",
+];
+
+/// A metadata value as the string an endpoint keeps: strings as they are,
+/// anything else as JSON, or as `str()` if it isn't serializable. `None`
+/// removes the key.
+fn metadata_value(value: &Bound<'_, PyAny>) -> Option<String> {
+    if value.is_none() {
+        return None;
+    }
+    if let Ok(s) = value.extract::<String>() {
+        return Some(s);
+    }
+    value
+        .py()
+        .import_bound("json")
+        .and_then(|json| json.call_method1("dumps", (value,)))
+        .and_then(|s| s.extract::<String>())
+        .or_else(|_| value.str().map(|s| s.to_string()))
+        .ok()
+}
+
+/// Collect an endpoint dict's `metadata` and the keys a plugin added
+/// outside it, which win over `metadata` entries of the same name
+fn dict_metadata(dict: &Bound<'_, PyDict>) -> HashMap<String, String> {
+    let mut metadata = HashMap::new();
+    let mut add = |key: Bound<'_, PyAny>, value: Bound<'_, PyAny>| {
+        if let Ok(key) = key.extract::<String>() {
+            match metadata_value(&value) {
+                Some(value) => metadata.insert(key, value),
+                None => metadata.remove(&key),
+            };
+        }
+    };
+    if let Some(item) = dict.get_item("metadata").ok().flatten() {
+        if let Ok(map) = item.downcast::<PyDict>() {
+            for (key, value) in map.iter() {
+                add(key, value);
+            }
+        }
+    }
+    for (key, value) in dict.iter() {
+        let known = key
+            .extract::<String>()
+            .is_ok_and(|key| ENDPOINT_KEYS.contains(&key.as_str()));
+        if !known {
+            add(key, value);
+        }
+    }
+    metadata
+}
+
+/// A key's value as `T`, or `None` when the key is missing or holds
+/// something else
+fn dict_get<'py, T: FromPyObject<'py>>(dict: &Bound<'py, PyDict>, key: &str) -> Result<Option<T>> {
+    Ok(dict
+        .get_item(key)
+        .map_err(|e| Error::PluginError(e.to_string()))?
+        .and_then(|value| value.extract().ok()))
+}
+
+/// Convert Python dictionary to Endpoint
+pub(crate) fn dict_to_endpoint(dict: &Bound<'_, PyDict>) -> Result<Endpoint> {
+    let url: String = dict_get(dict, "url")?
+        .ok_or_else(|| Error::PluginError("Missing url in transformed endpoint".to_string()))?;
+
+    let endpoint_type_str: String =
+        dict_get(dict, "endpoint_type")?.unwrap_or_else(|| "unknown".to_string());
+
+    let endpoint_type = match endpoint_type_str.as_str() {
+        "rest" => crate::types::EndpointType::Rest,
+        "graphql" => crate::types::EndpointType::GraphQL,
+        "websocket" => crate::types::EndpointType::WebSocket,
+        "sse" => crate::types::EndpointType::Sse,
+        _ => crate::types::EndpointType::Unknown,
+    };
+
+    let mut endpoint = Endpoint::new(url, endpoint_type);
+
+    if let Some(method) = dict_get::<String>(dict, "method")? {
+        endpoint = endpoint.with_method(method);
+    }
+
+    if let Some(source) = dict_get::<String>(dict, "source")? {
+        endpoint = endpoint.with_source(source);
+    }
+
+    if let Some(line) = dict_get::<usize>(dict, "line")? {
+        endpoint = endpoint.with_line(line);
+    }
+
+    if let Some(params) = dict_get::<Vec<String>>(dict, "params")? {
+        endpoint = endpoint.with_params(params);
+    }
+
+    let metadata = dict_metadata(dict);
+    if !metadata.is_empty() {
+        endpoint = endpoint.with_metadata(metadata);
+    }
+
+    Ok(endpoint)
+}
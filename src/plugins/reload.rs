@@ -0,0 +1,177 @@
+//! Reloading plugins between scans when their files change
+//!
+//! `serve` and `watch` run scan after scan in one process. A
+//! [`PluginReloader`] keeps their plugins loaded and, before each scan,
+//! checks the configured plugin chain for files that were edited, added to
+//! the plugin directory or removed. If any were, the whole chain is loaded
+//! again; if that fails, say on a syntax error mid-edit, the plugins loaded
+//! before stay in use and the next check tries again.
+
+use super::{open_plugin, EndpointoPlugin};
+use crate::config::ScanConfig;
+use crate::error::Result;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// What a plugin file looked like when it was loaded, to notice edits
+#[derive(Debug, Clone, PartialEq)]
+struct Stamp {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl Stamp {
+    /// The file's stamp, or `None` if it's gone
+    fn of(path: &std::path::Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+/// A configuration's plugins, kept loaded between scans and reloaded when
+/// their files change
+///
+/// Pass it to [`ScannerBuilder::with_plugin_reloader`](crate::scanner::ScannerBuilder::with_plugin_reloader)
+/// so scanners use these plugins instead of loading the files themselves.
+pub struct PluginReloader {
+    config: ScanConfig,
+    plugins: Vec<Arc<dyn EndpointoPlugin>>,
+    /// The chain's files as they were when last loaded; `None` until then
+    stamps: Option<Vec<(PathBuf, Option<Stamp>)>>,
+}
+
+impl PluginReloader {
+    /// Track the plugins of a configuration; nothing is loaded until the
+    /// first [`reload`](Self::reload)
+    pub fn new(config: &ScanConfig) -> Self {
+        Self {
+            config: config.clone(),
+            plugins: Vec::new(),
+            stamps: None,
+        }
+    }
+
+    /// Whether the plugins have been loaded
+    pub fn is_loaded(&self) -> bool {
+        self.stamps.is_some()
+    }
+
+    /// Load the plugin chain if it hasn't been yet or any of its files
+    /// changed, returning the files that did (every one on the first load)
+    ///
+    /// On an error the plugins loaded before stay in use, and the next call
+    /// tries again.
+    pub fn reload(&mut self) -> Result<Vec<PathBuf>> {
+        let stamps: Vec<(PathBuf, Option<Stamp>)> = self
+            .config
+            .plugin_chain()?
+            .into_iter()
+            .map(|path| {
+                let stamp = Stamp::of(&path);
+                (path, stamp)
+            })
+            .collect();
+        let changed = match &self.stamps {
+            Some(old) => changed_files(old, &stamps),
+            None => stamps.iter().map(|(path, _)| path.clone()).collect(),
+        };
+        if self.is_loaded() && changed.is_empty() {
+            return Ok(changed);
+        }
+
+        let mut plugins = Vec::new();
+        for (path, _) in &stamps {
+            // Libraries load from private copies: the old one is still
+            // open, and one overwritten in place would crash the process
+            if let Some(plugin) = open_plugin(path, true)? {
+                plugins.push(Arc::from(plugin));
+            }
+        }
+        self.plugins = plugins;
+        self.stamps = Some(stamps);
+        Ok(changed)
+    }
+
+    /// The loaded plugins, in the order they run
+    pub fn plugins(&self) -> &[Arc<dyn EndpointoPlugin>] {
+        &self.plugins
+    }
+}
+
+/// Files edited, added or removed between two looks at a chain, in chain
+/// order with removed files last
+fn changed_files(
+    old: &[(PathBuf, Option<Stamp>)],
+    new: &[(PathBuf, Option<Stamp>)],
+) -> Vec<PathBuf> {
+    let mut changed: Vec<PathBuf> = new
+        .iter()
+        .filter(|entry| !old.contains(entry))
+        .map(|(path, _)| path.clone())
+        .collect();
+    for (path, _) in old {
+        if !new.iter().any(|(p, _)| p == path) {
+            changed.push(path.clone());
+        }
+    }
+    // Only the order changed, e.g. a priority in the configuration
+    if changed.is_empty() && old.len() == new.len() && old != new {
+        changed = new.iter().map(|(path, _)| path.clone()).collect();
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reload_tracks_plugin_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("first.py");
+        std::fs::write(&first, "def filter_endpoint(e):\n    return True\n").unwrap();
+        let config = ScanConfig::default().with_plugin_dir(Some(dir.path().to_path_buf()));
+        let mut reloader = PluginReloader::new(&config);
+        assert!(!reloader.is_loaded());
+
+        assert_eq!(reloader.reload().unwrap(), vec![first.clone()]);
+        assert!(reloader.is_loaded());
+        assert!(reloader.reload().unwrap().is_empty());
+
+        std::fs::write(&first, "def filter_endpoint(e):\n    return False\n").unwrap();
+        assert_eq!(reloader.reload().unwrap(), vec![first.clone()]);
+
+        let second = dir.path().join("second.py");
+        std::fs::write(&second, "").unwrap();
+        assert_eq!(reloader.reload().unwrap(), vec![second.clone()]);
+
+        std::fs::remove_file(&first).unwrap();
+        assert_eq!(reloader.reload().unwrap(), vec![first]);
+        assert!(reloader.reload().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_failed_reload_keeps_plugins() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = ScanConfig::default().with_plugin_dir(Some(dir.path().to_path_buf()));
+        let mut reloader = PluginReloader::new(&config);
+        reloader.reload().unwrap();
+
+        // Not a library the loader can open
+        let broken = dir.path().join("broken.so");
+        std::fs::write(&broken, "half-built").unwrap();
+        let e = reloader.reload().unwrap_err();
+        assert!(e.to_string().contains("broken.so"), "{}", e);
+        assert!(!e.to_string().contains("endpointo-"), "{}", e);
+        assert!(reloader.is_loaded());
+        // Still changed, so the next check tries again
+        assert!(reloader.reload().is_err());
+
+        std::fs::remove_file(&broken).unwrap();
+        assert!(reloader.reload().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,455 @@
+//! Filter and transform scripts in [Rhai](https://rhai.rs), with no
+//! interpreter to install
+//!
+//! A script is a boolean expression over the endpoint, such as
+//!
+//! ```text
+//! endpoint.url.contains("/admin") && endpoint.method != "GET"
+//! ```
+//!
+//! Endpoints it evaluates to `false` for are dropped. A script can instead
+//! define `fn filter(endpoint)` returning `true` or `false`, and
+//! `fn transform(endpoint)` returning the endpoint with its `url`, `method`,
+//! `params` or `tags` changed.
+//!
+//! `endpoint` is an object map of `url`, `method`, `type`, `source`,
+//! `line`, `params`, `tags`, `severity`, `status` (the probe's), `host` and
+//! `path`; missing ones are empty strings, empty arrays or 0. Besides Rhai's
+//! own string and array functions, strings have `matches(regex)`.
+//!
+//! Scripts run sandboxed: Rhai has no file, network or process access, and
+//! the engine caps operations, call depth and the size of strings, arrays
+//! and maps, so a runaway script fails instead of hanging the scan. Scripts
+//! are compiled and tried on a sample endpoint when parsed, so a mistake is
+//! reported before the scan starts rather than once per endpoint.
+
+use super::{filter_each, transform_each, AssetInfo, Batch, EndpointoPlugin};
+use crate::error::{Error, Result};
+use crate::types::{Endpoint, EndpointType, ProbeResult};
+use crate::util::lock;
+use regex::Regex;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::error;
+use url::Url;
+
+/// Operations one hook call may run before it's stopped
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// Longest string, array or map a script may build
+const MAX_SIZE: usize = 1 << 20;
+
+/// A compiled filter or transform script
+pub struct Script {
+    source: String,
+    engine: Engine,
+    ast: AST,
+    filter_fn: bool,
+    transform_fn: bool,
+}
+
+impl std::fmt::Debug for Script {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Script")
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Script {
+    /// Compile a script and try it on a sample endpoint
+    pub fn parse(source: &str) -> Result<Self> {
+        let engine = sandboxed_engine();
+        let mut scope = Scope::new();
+        scope.push("endpoint", Map::new());
+        let ast = engine
+            .compile_with_scope(&scope, source)
+            .map_err(|e| script_error(&e.to_string()))?;
+        let defines = |name: &str| {
+            ast.iter_functions()
+                .any(|f| f.name == name && f.params.len() == 1)
+        };
+        let script = Self {
+            source: source.to_string(),
+            filter_fn: defines("filter"),
+            transform_fn: defines("transform"),
+            engine,
+            ast,
+        };
+
+        let sample = sample_endpoint();
+        script.try_filter(&sample)?;
+        if script.transform_fn {
+            script.try_transform(&sample)?;
+        }
+        Ok(script)
+    }
+
+    /// The script's text
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether an endpoint passes the script; one it fails on is dropped
+    pub fn matches(&self, endpoint: &Endpoint) -> bool {
+        self.try_filter(endpoint).unwrap_or(false)
+    }
+
+    fn try_filter(&self, endpoint: &Endpoint) -> Result<bool> {
+        let result = if self.filter_fn {
+            self.engine.call_fn::<Dynamic>(
+                &mut Scope::new(),
+                &self.ast,
+                "filter",
+                (to_map(endpoint),),
+            )
+        } else {
+            let mut scope = Scope::new();
+            scope.push("endpoint", to_map(endpoint));
+            self.engine
+                .eval_ast_with_scope::<Dynamic>(&mut scope, &self.ast)
+        }
+        .map_err(|e| script_error(&e.to_string()))?;
+
+        match result.as_bool() {
+            Ok(keep) => Ok(keep),
+            // A script of nothing but a transform keeps everything
+            Err(_) if result.is_unit() && self.transform_fn && !self.filter_fn => Ok(true),
+            Err(ty) => Err(script_error(&format!(
+                "the script is {}, but must be true or false",
+                ty
+            ))),
+        }
+    }
+
+    fn try_transform(&self, endpoint: &Endpoint) -> Result<Endpoint> {
+        if !self.transform_fn {
+            return Ok(endpoint.clone());
+        }
+        let result = self
+            .engine
+            .call_fn::<Dynamic>(
+                &mut Scope::new(),
+                &self.ast,
+                "transform",
+                (to_map(endpoint),),
+            )
+            .map_err(|e| script_error(&e.to_string()))?;
+        let ty = result.type_name();
+        let map = result.try_cast::<Map>().ok_or_else(|| {
+            script_error(&format!("transform() returned {}, not the endpoint", ty))
+        })?;
+        from_map(endpoint.clone(), &map)
+    }
+}
+
+impl EndpointoPlugin for Script {
+    fn name(&self) -> &str {
+        "script"
+    }
+
+    fn filter_endpoint(&self, endpoint: &Endpoint) -> bool {
+        self.try_filter(endpoint).unwrap_or_else(|e| {
+            error!("Script filter error: {}", e);
+            true
+        })
+    }
+
+    fn transform_endpoint(&self, endpoint: Endpoint) -> Endpoint {
+        match self.try_transform(&endpoint) {
+            Ok(transformed) => transformed,
+            Err(e) => {
+                error!("Script transform error: {}", e);
+                endpoint
+            }
+        }
+    }
+
+    fn try_filter_batch(&self, batch: &mut Batch) -> Vec<(usize, Error)> {
+        filter_each(batch, |endpoint, _: Option<&AssetInfo>| {
+            self.try_filter(endpoint)
+        })
+    }
+
+    fn try_transform_batch(&self, batch: Batch) -> (Batch, Vec<(usize, Error)>) {
+        transform_each(batch, |endpoint, _: Option<&AssetInfo>| {
+            self.try_transform(endpoint)
+        })
+    }
+}
+
+fn script_error(msg: &str) -> Error {
+    Error::ValidationError(format!("invalid script: {}", msg))
+}
+
+/// An engine with Rhai's standard library, `matches`, and limits on what a
+/// script can run and allocate
+fn sandboxed_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_strict_variables(true)
+        .set_fail_on_invalid_map_property(true)
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(32)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(MAX_SIZE)
+        .set_max_array_size(MAX_SIZE)
+        .set_max_map_size(MAX_SIZE)
+        .disable_symbol("eval");
+    engine.on_print(|_| {});
+    engine.on_debug(|_, _, _| {});
+
+    // Regexes are compiled once, not once per endpoint
+    let regexes: Arc<Mutex<HashMap<String, Regex>>> = Arc::default();
+    engine.register_fn(
+        "matches",
+        move |text: &str, pattern: &str| -> std::result::Result<bool, Box<EvalAltResult>> {
+            let mut regexes = lock(&regexes);
+            if !regexes.contains_key(pattern) {
+                let regex = Regex::new(pattern)
+                    .map_err(|e| format!("invalid regex '{}': {}", pattern, e))?;
+                regexes.insert(pattern.to_string(), regex);
+            }
+            Ok(regexes[pattern].is_match(text))
+        },
+    );
+    engine
+}
+
+/// An endpoint with every property set, to try scripts on
+fn sample_endpoint() -> Endpoint {
+    let mut endpoint = Endpoint::new(
+        "https://example.com/api/v1/users?id=1".to_string(),
+        EndpointType::Rest,
+    )
+    .with_method("GET".to_string())
+    .with_source("https://example.com/app.js".to_string())
+    .with_line(1)
+    .with_params(vec!["id".to_string()])
+    .with_tag("api");
+    endpoint.probe = Some(ProbeResult::default());
+    endpoint
+}
+
+/// The endpoint as the script's `endpoint` object map
+fn to_map(endpoint: &Endpoint) -> Map {
+    let text = |s: &Option<String>| Dynamic::from(s.clone().unwrap_or_default());
+    let list = |l: &Option<Vec<String>>| {
+        Dynamic::from_array(
+            l.iter()
+                .flatten()
+                .cloned()
+                .map(Dynamic::from)
+                .collect::<Array>(),
+        )
+    };
+    let parsed = Url::parse(&endpoint.url).ok();
+    let host = parsed
+        .as_ref()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default();
+    let path = match &parsed {
+        Some(url) => url.path().to_string(),
+        None => endpoint
+            .url
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+    };
+
+    let mut map = Map::new();
+    map.insert("url".into(), Dynamic::from(endpoint.url.clone()));
+    map.insert("method".into(), text(&endpoint.method));
+    map.insert(
+        "type".into(),
+        Dynamic::from(endpoint.endpoint_type.as_str().to_string()),
+    );
+    map.insert("source".into(), text(&endpoint.source));
+    map.insert(
+        "line".into(),
+        Dynamic::from_int(endpoint.line.unwrap_or(0) as i64),
+    );
+    map.insert("params".into(), list(&endpoint.params));
+    map.insert("tags".into(), list(&endpoint.tags));
+    map.insert(
+        "severity".into(),
+        Dynamic::from(
+            endpoint
+                .severity
+                .map(|s| s.as_str().to_string())
+                .unwrap_or_default(),
+        ),
+    );
+    map.insert(
+        "status".into(),
+        Dynamic::from_int(endpoint.probe.as_ref().map_or(0, |p| p.status as i64)),
+    );
+    map.insert("host".into(), Dynamic::from(host));
+    map.insert("path".into(), Dynamic::from(path));
+    map
+}
+
+/// `endpoint` with the writable properties of a transformed map: `url`,
+/// `method`, `params` and `tags`
+fn from_map(mut endpoint: Endpoint, map: &Map) -> Result<Endpoint> {
+    let text = |key: &str| -> Result<Option<String>> {
+        map.get(key)
+            .map(|value| {
+                value.clone().into_string().map_err(|ty| {
+                    script_error(&format!("transform() set {} to {}, not a string", key, ty))
+                })
+            })
+            .transpose()
+    };
+    let list = |key: &str| -> Result<Option<Vec<String>>> {
+        let Some(value) = map.get(key) else {
+            return Ok(None);
+        };
+        let wrong = |ty: &str| {
+            script_error(&format!(
+                "transform() set {} to {}, not an array of strings",
+                key, ty
+            ))
+        };
+        let items = value
+            .clone()
+            .into_array()
+            .map_err(&wrong)?
+            .into_iter()
+            .map(|item| item.into_string().map_err(&wrong))
+            .collect::<Result<Vec<_>>>()?;
+        Ok((!items.is_empty()).then_some(items))
+    };
+
+    if let Some(url) = text("url")? {
+        if url.is_empty() {
+            return Err(script_error("transform() set url to an empty string"));
+        }
+        endpoint.url = url;
+    }
+    if let Some(method) = text("method")? {
+        endpoint.method = (!method.is_empty()).then_some(method);
+    }
+    if map.contains_key("params") {
+        endpoint.params = list("params")?;
+    }
+    if map.contains_key("tags") {
+        endpoint.tags = list("tags")?;
+    }
+    Ok(endpoint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(script: &str, endpoint: &Endpoint) -> bool {
+        Script::parse(script).unwrap().matches(endpoint)
+    }
+
+    #[test]
+    fn test_script_filters() {
+        let endpoint = Endpoint::new(
+            "https://api.example.com/Admin/users?id=1".to_string(),
+            EndpointType::Rest,
+        )
+        .with_method("POST".to_string())
+        .with_line(42)
+        .with_tag("admin");
+
+        assert!(matches(r#"endpoint.url.contains("/Admin")"#, &endpoint));
+        assert!(matches(
+            r#"endpoint.path.to_lower().starts_with("/admin") && endpoint.method != "GET""#,
+            &endpoint
+        ));
+        assert!(matches(
+            "endpoint.host == \"api.example.com\" // the API host\n && endpoint.line >= 40",
+            &endpoint
+        ));
+        assert!(matches(
+            r#""admin" in endpoint.tags && !endpoint.params.contains("x")"#,
+            &endpoint
+        ));
+        assert!(matches(
+            r#"endpoint.url.matches("users\\?id=\\d+$")"#,
+            &endpoint
+        ));
+        assert!(matches(
+            r#"endpoint.type == "rest" && (endpoint.status == 0 || endpoint.source.is_empty())"#,
+            &endpoint
+        ));
+        assert!(!matches(
+            r#"endpoint.method.len() > 4 || endpoint.tags.is_empty()"#,
+            &endpoint
+        ));
+        assert!(matches(
+            "fn filter(e) { let admin = e.path.starts_with(\"/Admin\"); admin && e.line > 40 }",
+            &endpoint
+        ));
+    }
+
+    #[test]
+    fn test_script_transforms() {
+        let script = Script::parse(
+            r#"
+            fn transform(e) {
+                e.url = e.url.to_lower();
+                e.tags.push("scripted");
+                e.method = "";
+                e
+            }
+            "#,
+        )
+        .unwrap();
+        let endpoint = Endpoint::new("/API/Users".to_string(), EndpointType::Rest)
+            .with_method("GET".to_string());
+        // A transform-only script keeps every endpoint
+        assert!(script.filter_endpoint(&endpoint));
+        let transformed = script.transform_endpoint(endpoint);
+        assert_eq!(transformed.url, "/api/users");
+        assert_eq!(transformed.tags, Some(vec!["scripted".to_string()]));
+        assert_eq!(transformed.method, None);
+
+        let (batch, failures) =
+            Script::parse(r#"fn transform(e) { e.url = e.line.to_string(); e }"#)
+                .unwrap()
+                .try_transform_batch(vec![(
+                    Endpoint::new("/a".to_string(), EndpointType::Rest),
+                    None,
+                )]);
+        assert!(failures.is_empty());
+        assert_eq!(batch[0].0.url, "0");
+    }
+
+    #[test]
+    fn test_script_errors_are_caught_when_parsed() {
+        for (script, error) in [
+            ("endpoint.url", "must be true or false"),
+            ("endpoint.url.contains(1)", "contains"),
+            ("endpoint.urls.contains(\"a\")", "urls"),
+            ("url.contains(\"a\")", "url"),
+            ("endpoint.url.matches(\"(\")", "invalid regex"),
+            ("endpoint.url.contains(\"a\"", "')'"),
+            ("endpoint.url == \"a", "string"),
+            ("fn transform(e) { 1 }", "returned i64, not the endpoint"),
+            (
+                "fn transform(e) { e.tags = 1; e }",
+                "not an array of strings",
+            ),
+        ] {
+            let message = Script::parse(script).unwrap_err().to_string();
+            assert!(message.contains(error), "{}: {}", script, message);
+        }
+    }
+
+    #[test]
+    fn test_scripts_are_sandboxed() {
+        // Runaway loops hit the operation limit instead of hanging
+        let message = Script::parse("loop {}; true").unwrap_err().to_string();
+        assert!(message.contains("operations"), "{}", message);
+        let message = Script::parse(r#"eval("true")"#).unwrap_err().to_string();
+        assert!(message.contains("eval"), "{}", message);
+    }
+}
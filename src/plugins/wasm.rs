@@ -0,0 +1,422 @@
+//! WASM plugins run in a wasmtime sandbox
+//!
+//! A WASM plugin is a core WebAssembly module, built from Rust for
+//! `wasm32-unknown-unknown` or from any language that targets it, exporting
+//! its memory and an allocator:
+//!
+//! - `memory`
+//! - `endpointo_alloc(len: i32) -> i32`, space for the host to write `len`
+//!   bytes of input into
+//!
+//! and any of these, each optional:
+//!
+//! - `endpointo_name() -> i64`, the plugin's name; the file stem otherwise
+//! - `filter_endpoint(ptr: i32, len: i32) -> i32`, given an endpoint as JSON:
+//!   1 to keep it, 0 to drop it, anything else on error
+//! - `transform_endpoint(ptr: i32, len: i32) -> i64`, the endpoint as JSON
+//!   rewritten, or 0 on error
+//! - `generate_endpoints(ptr: i32, len: i32) -> i64`, given
+//!   `{"content": ..., "source": ...}`, a JSON array of new endpoints, or 0
+//!   on error
+//!
+//! Strings a module returns are packed into an `i64` as `ptr << 32 | len`.
+//! Endpoints are the JSON the native plugin ABI passes too, an asset's
+//! [`AssetInfo`] included under `asset`.
+//!
+//! Modules get no host functions, so they have no file, network, clock or
+//! process access: one that imports anything, WASI included, is refused
+//! when loaded. Every hook call runs in a fresh instance with capped
+//! memory and fuel, so a module can't keep state between endpoints, leak
+//! memory across a scan or spin forever.
+
+use super::{filter_each, transform_each, AssetInfo, Batch, EndpointoPlugin};
+use crate::error::{Error, Result};
+use crate::types::Endpoint;
+use std::path::Path;
+use tracing::error;
+use wasmtime::{
+    Config, Engine, Instance, InstancePre, Linker, Memory, Module, Store, StoreLimits,
+    StoreLimitsBuilder,
+};
+
+/// Hooks a module can export, in the order endpointo calls them
+pub const WASM_HOOKS: &[&str] = &[
+    "generate_endpoints",
+    "filter_endpoint",
+    "transform_endpoint",
+];
+
+/// Fuel, roughly instructions, one hook call may burn before it's stopped
+const FUEL: u64 = 100_000_000;
+
+/// Most linear memory one hook call may grow to
+const MAX_MEMORY: usize = 64 << 20;
+
+/// A plugin loaded from a WASM module
+pub struct WasmPlugin {
+    name: String,
+    engine: Engine,
+    module: InstancePre<StoreLimits>,
+    hooks: Vec<&'static str>,
+}
+
+impl WasmPlugin {
+    /// Compile a module and check it exports what the host needs and
+    /// imports nothing
+    pub fn load(path: &Path) -> Result<Self> {
+        let fail = |msg: String| Error::PluginError(format!("{}: {}", path.display(), msg));
+
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(|e| fail(e.to_string()))?;
+        let module = Module::from_file(&engine, path).map_err(|e| fail(format!("{:#}", e)))?;
+        if let Some(import) = module.imports().next() {
+            return Err(fail(format!(
+                "imports {}::{}, but WASM plugins get no host functions",
+                import.module(),
+                import.name()
+            )));
+        }
+        for export in ["memory", "endpointo_alloc"] {
+            if module.get_export(export).is_none() {
+                return Err(fail(format!("no `{}` export", export)));
+            }
+        }
+        let hooks = WASM_HOOKS
+            .iter()
+            .copied()
+            .filter(|hook| module.get_export(hook).is_some())
+            .collect();
+        let module = Linker::new(&engine)
+            .instantiate_pre(&module)
+            .map_err(|e| fail(e.to_string()))?;
+
+        let mut plugin = Self {
+            name: path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+            engine,
+            module,
+            hooks,
+        };
+        let (mut store, instance) = plugin.instantiate().map_err(|e| fail(e.to_string()))?;
+        if let Ok(name_fn) = instance.get_typed_func::<(), i64>(&mut store, "endpointo_name") {
+            let name = name_fn
+                .call(&mut store, ())
+                .map_err(|e| e.to_string())
+                .and_then(|packed| read(&mut store, &instance, packed))
+                .map_err(|e| fail(format!("endpointo_name {}", e)))?;
+            plugin.name = String::from_utf8_lossy(&name).into_owned();
+        }
+        Ok(plugin)
+    }
+
+    /// Hooks the module exports, in the order endpointo calls them
+    pub fn hooks(&self) -> &[&'static str] {
+        &self.hooks
+    }
+
+    /// A fresh instance of the module with its memory and fuel capped
+    fn instantiate(&self) -> std::result::Result<(Store<StoreLimits>, Instance), String> {
+        let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY).build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(FUEL).map_err(|e| e.to_string())?;
+        let instance = self
+            .module
+            .instantiate(&mut store)
+            .map_err(|e| e.to_string())?;
+        Ok((store, instance))
+    }
+
+    /// Call a hook taking `input` in a fresh instance
+    fn call<R: wasmtime::WasmResults>(
+        &self,
+        hook: &str,
+        input: &[u8],
+        then: impl FnOnce(&mut Store<StoreLimits>, &Instance, R) -> std::result::Result<Vec<u8>, String>,
+    ) -> Result<Vec<u8>> {
+        let run = || {
+            let (mut store, instance) = self.instantiate()?;
+            let len = i32::try_from(input.len()).map_err(|_| "input too large".to_string())?;
+            let alloc = instance
+                .get_typed_func::<i32, i32>(&mut store, "endpointo_alloc")
+                .map_err(|e| e.to_string())?;
+            let ptr = alloc
+                .call(&mut store, len)
+                .map_err(|e| format!("{:#}", e))?;
+            memory(&mut store, &instance)?
+                .write(&mut store, ptr as u32 as usize, input)
+                .map_err(|_| "endpointo_alloc returned memory out of bounds".to_string())?;
+            let hook_fn = instance
+                .get_typed_func::<(i32, i32), R>(&mut store, hook)
+                .map_err(|e| e.to_string())?;
+            let result = hook_fn
+                .call(&mut store, (ptr, len))
+                .map_err(|e| format!("{:#}", e))?;
+            then(&mut store, &instance, result)
+        };
+        run().map_err(|detail| self.error(hook, &detail))
+    }
+
+    fn error(&self, hook: &str, detail: &str) -> Error {
+        Error::PluginError(format!("{}: {} {}", self.name, hook, detail))
+    }
+
+    /// Whether the plugin keeps an endpoint
+    pub fn try_filter(&self, endpoint: &Endpoint, asset: Option<&AssetInfo>) -> Result<bool> {
+        if !self.hooks.contains(&"filter_endpoint") {
+            return Ok(true);
+        }
+        let json = endpoint_json(endpoint, asset)?;
+        let keep = self.call::<i32>("filter_endpoint", &json, |_, _, keep| match keep {
+            0 | 1 => Ok(vec![keep as u8]),
+            other => Err(format!("returned {}, expected 0 or 1", other)),
+        })?;
+        Ok(keep == [1])
+    }
+
+    /// The endpoint as the plugin transforms it
+    pub fn try_transform(
+        &self,
+        endpoint: &Endpoint,
+        asset: Option<&AssetInfo>,
+    ) -> Result<Endpoint> {
+        if !self.hooks.contains(&"transform_endpoint") {
+            return Ok(endpoint.clone());
+        }
+        let json = endpoint_json(endpoint, asset)?;
+        let transformed = self.call("transform_endpoint", &json, read)?;
+        serde_json::from_slice(&transformed)
+            .map_err(|e| self.error("transform_endpoint", &format!("returned bad JSON: {}", e)))
+    }
+
+    /// Endpoints the plugin finds in an asset's content
+    pub fn try_generate(&self, content: &str, source: &str) -> Result<Vec<Endpoint>> {
+        if !self.hooks.contains(&"generate_endpoints") {
+            return Ok(Vec::new());
+        }
+        let input = serde_json::to_vec(&serde_json::json!({
+            "content": content,
+            "source": source,
+        }))?;
+        let generated = self.call("generate_endpoints", &input, read)?;
+        serde_json::from_slice(&generated)
+            .map_err(|e| self.error("generate_endpoints", &format!("returned bad JSON: {}", e)))
+    }
+}
+
+/// The endpoint's JSON, with its asset under `asset`
+fn endpoint_json(endpoint: &Endpoint, asset: Option<&AssetInfo>) -> Result<Vec<u8>> {
+    let mut value = serde_json::to_value(endpoint)?;
+    if let (Some(asset), Some(object)) = (asset, value.as_object_mut()) {
+        object.insert("asset".to_string(), serde_json::to_value(asset)?);
+    }
+    Ok(serde_json::to_vec(&value)?)
+}
+
+fn memory(
+    store: &mut Store<StoreLimits>,
+    instance: &Instance,
+) -> std::result::Result<Memory, String> {
+    instance
+        .get_memory(&mut *store, "memory")
+        .ok_or_else(|| "no `memory` export".to_string())
+}
+
+/// The bytes a module returned as `ptr << 32 | len`; 0 means it failed
+fn read(
+    store: &mut Store<StoreLimits>,
+    instance: &Instance,
+    packed: i64,
+) -> std::result::Result<Vec<u8>, String> {
+    if packed == 0 {
+        return Err("failed".to_string());
+    }
+    let (ptr, len) = (
+        (packed as u64 >> 32) as usize,
+        packed as u64 as u32 as usize,
+    );
+    let memory = memory(store, instance)?;
+    memory
+        .data(&*store)
+        .get(ptr..ptr + len)
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| "returned memory out of bounds".to_string())
+}
+
+impl EndpointoPlugin for WasmPlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn filter_endpoint(&self, endpoint: &Endpoint) -> bool {
+        self.filter_endpoint_in(endpoint, None)
+    }
+
+    fn transform_endpoint(&self, endpoint: Endpoint) -> Endpoint {
+        self.transform_endpoint_in(endpoint, None)
+    }
+
+    fn filter_endpoint_in(&self, endpoint: &Endpoint, asset: Option<&AssetInfo>) -> bool {
+        self.try_filter(endpoint, asset).unwrap_or_else(|e| {
+            error!("Plugin filter_endpoint error: {}", e);
+            true
+        })
+    }
+
+    fn transform_endpoint_in(&self, endpoint: Endpoint, asset: Option<&AssetInfo>) -> Endpoint {
+        match self.try_transform(&endpoint, asset) {
+            Ok(transformed) => transformed,
+            Err(e) => {
+                error!("Plugin transform_endpoint error: {}", e);
+                endpoint
+            }
+        }
+    }
+
+    fn try_filter_batch(&self, batch: &mut Batch) -> Vec<(usize, Error)> {
+        filter_each(batch, |endpoint, asset| self.try_filter(endpoint, asset))
+    }
+
+    fn try_transform_batch(&self, batch: Batch) -> (Batch, Vec<(usize, Error)>) {
+        transform_each(batch, |endpoint, asset| self.try_transform(endpoint, asset))
+    }
+
+    fn generate_endpoints(&self, content: &str, source: &str) -> Vec<Endpoint> {
+        self.try_generate(content, source).unwrap_or_else(|e| {
+            error!("Plugin generate_endpoints error: {}", e);
+            Vec::new()
+        })
+    }
+
+    fn try_generate_endpoints(&self, content: &str, source: &str) -> Result<Vec<Endpoint>> {
+        self.try_generate(content, source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::PluginManager;
+    use crate::types::EndpointType;
+
+    /// Keeps endpoints whose URL ends in `s`, and capitalizes that `s`
+    ///
+    /// An endpoint's JSON has its keys sorted, so it ends `"url":"..."}` and
+    /// the URL's last character is 3 bytes from the end.
+    const ENDS_IN_S: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (global $next (mut i32) (i32.const 1024))
+          (data (i32.const 16) "ends-in-s")
+          (func (export "endpointo_alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+          (func (export "endpointo_name") (result i64)
+            (i64.or (i64.shl (i64.const 16) (i64.const 32)) (i64.const 9)))
+          (func (export "filter_endpoint") (param $ptr i32) (param $len i32) (result i32)
+            (i32.eq (i32.load8_u (i32.sub (i32.add (local.get $ptr) (local.get $len)) (i32.const 3)))
+                    (i32.const 115)))
+          (func (export "transform_endpoint") (param $ptr i32) (param $len i32) (result i64)
+            (i32.store8 (i32.sub (i32.add (local.get $ptr) (local.get $len)) (i32.const 3))
+                        (i32.const 83))
+            (i64.or
+              (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+              (i64.extend_i32_u (local.get $len)))))
+    "#;
+
+    fn module(dir: &tempfile::TempDir, wat: &str) -> std::path::PathBuf {
+        let path = dir.path().join("plugin.wasm");
+        std::fs::write(&path, wat).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_filter_and_transform_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = module(&dir, ENDS_IN_S);
+        let mut manager = PluginManager::new();
+        manager.load_plugin(&path).unwrap();
+        assert_eq!(manager.names(), ["ends-in-s"]);
+
+        let batch = ["/api/users", "/static/app.css", "/admin"]
+            .into_iter()
+            .map(|url| {
+                let endpoint = Endpoint::new(url.to_string(), EndpointType::Rest)
+                    .with_method("POST".to_string())
+                    .with_tag("seen");
+                (endpoint, None)
+            })
+            .collect();
+        let kept = manager.process(batch);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].url, "/api/userS");
+        assert_eq!(kept[1].url, "/static/app.csS");
+        // The rest of the endpoint survives the trip through the module
+        assert_eq!(kept[0].method.as_deref(), Some("POST"));
+        assert_eq!(kept[0].tags, Some(vec!["seen".to_string()]));
+    }
+
+    #[test]
+    fn test_modules_are_sandboxed() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let wasi = module(
+            &dir,
+            r#"(module
+                 (import "wasi_snapshot_preview1" "fd_write"
+                   (func (param i32 i32 i32 i32) (result i32)))
+                 (memory (export "memory") 1)
+                 (func (export "endpointo_alloc") (param i32) (result i32) (i32.const 0)))"#,
+        );
+        let message = WasmPlugin::load(&wasi).err().unwrap().to_string();
+        assert!(
+            message.contains("imports wasi_snapshot_preview1::fd_write"),
+            "{}",
+            message
+        );
+
+        // A hook that never returns runs out of fuel instead of hanging
+        let spin = module(
+            &dir,
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func (export "endpointo_alloc") (param i32) (result i32) (i32.const 0))
+                 (func (export "filter_endpoint") (param i32 i32) (result i32)
+                   (loop $forever (br $forever))
+                   (i32.const 1)))"#,
+        );
+        let plugin = WasmPlugin::load(&spin).unwrap();
+        assert_eq!(plugin.hooks(), ["filter_endpoint"]);
+        let endpoint = Endpoint::new("/api".to_string(), EndpointType::Rest);
+        let message = plugin.try_filter(&endpoint, None).unwrap_err().to_string();
+        assert!(message.contains("plugin: filter_endpoint"), "{}", message);
+        assert!(message.contains("fuel"), "{}", message);
+    }
+
+    #[test]
+    fn test_filter_result_must_be_0_or_1() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = module(
+            &dir,
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func (export "endpointo_alloc") (param i32) (result i32) (i32.const 0))
+                 (func (export "filter_endpoint") (param i32 i32) (result i32)
+                   (i32.const 257)))"#,
+        );
+        let plugin = WasmPlugin::load(&path).unwrap();
+        let endpoint = Endpoint::new("/api".to_string(), EndpointType::Rest);
+        let message = plugin.try_filter(&endpoint, None).unwrap_err().to_string();
+        assert!(
+            message.contains("returned 257, expected 0 or 1"),
+            "{}",
+            message
+        );
+    }
+}
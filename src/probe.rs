@@ -0,0 +1,298 @@
+//! Live verification of endpoints from earlier results
+//!
+//! `endpointo probe` checks endpoints without extracting them again, so
+//! results can be verified later or with different credentials. Each
+//! HTTP(S) URL is probed as during a scan (HEAD, GET if refused), redirects
+//! are followed hop by hop to record the chain, and each host is first
+//! asked for a path that can't exist so catch-all answers (soft 404s) can
+//! be told apart from real endpoints.
+
+use crate::config::ScanConfig;
+use crate::crawler::client::HttpClient;
+use crate::diff::load_result;
+use crate::error::Result;
+use crate::output::absolute_url;
+use crate::output::file::read_to_string;
+use crate::parser::patterns::PatternMatcher;
+use crate::types::{Endpoint, ProbeResult, RedirectHop, ScanResult, SCHEMA_VERSION, TOOL_VERSION};
+use futures::stream::{self, StreamExt};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tracing::{debug, info};
+use url::Url;
+
+/// Redirects followed per endpoint unless told otherwise
+pub const DEFAULT_MAX_REDIRECTS: usize = 5;
+
+/// Relative difference in size under which a response matches a host's
+/// not-found answer
+const SOFT_404_SIZE_TOLERANCE: f64 = 0.05;
+
+/// Load endpoints to probe from a results file or a plain list of URLs
+///
+/// JSON, JSON Lines and YAML files (optionally gzipped) and shard manifests
+/// are read as results. Anything else is taken as one URL per line,
+/// skipping blanks and `#` comments.
+pub fn load_probe_input(path: &Path) -> Result<ScanResult> {
+    let name = path.to_string_lossy().to_lowercase();
+    let name = name.strip_suffix(".gz").unwrap_or(&name);
+    if [".json", ".jsonl", ".yaml", ".yml"]
+        .iter()
+        .any(|ext| name.ends_with(ext))
+    {
+        return load_result(path);
+    }
+    let content = read_to_string(path)?;
+    let matcher = PatternMatcher::new();
+    let endpoints: Vec<Endpoint> = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|url| Endpoint::new(url.to_string(), matcher.detect_endpoint_type(url, "")))
+        .collect();
+    Ok(ScanResult {
+        schema_version: SCHEMA_VERSION,
+        tool_version: TOOL_VERSION.to_string(),
+        target: String::new(),
+        timestamp: String::new(),
+        total_endpoints: endpoints.len(),
+        endpoints,
+        findings: Vec::new(),
+        wordlist: Vec::new(),
+        technologies: Vec::new(),
+        stats: None,
+    })
+}
+
+/// Probes endpoints, following redirects and flagging soft 404s
+pub struct Prober {
+    client: HttpClient,
+    concurrency: usize,
+    options: bool,
+    max_redirects: usize,
+    soft_404: bool,
+}
+
+impl Prober {
+    /// Probe with the config's rate limit, timeout, concurrency and
+    /// `probe_options`
+    pub fn new(config: &ScanConfig) -> Result<Self> {
+        Ok(Self {
+            client: HttpClient::new(config)?,
+            concurrency: config.max_concurrent.max(1),
+            options: config.probe_options,
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            soft_404: true,
+        })
+    }
+
+    /// Follow at most this many redirects per endpoint (0 to not follow)
+    pub fn with_max_redirects(mut self, max: Option<usize>) -> Self {
+        if let Some(max) = max {
+            self.max_redirects = max;
+        }
+        self
+    }
+
+    /// Whether to check each host for soft 404s first
+    pub fn with_soft_404(mut self, detect: bool) -> Self {
+        self.soft_404 = detect;
+        self
+    }
+
+    /// Number of HTTP requests sent so far
+    pub fn requests_made(&self) -> usize {
+        self.client.requests_made()
+    }
+
+    /// Count a URL's origin as a target, so probes to it carry the extra
+    /// headers and cookies
+    pub fn add_target(&self, url: &str) {
+        self.client.add_target(url);
+    }
+
+    /// Probe endpoints in place, returning how many got a response
+    ///
+    /// Relative URLs are resolved against the endpoint's target, then
+    /// `base`. Endpoints that can't be resolved, aren't HTTP(S), or have
+    /// template placeholders are left alone, as are their earlier probes.
+    /// Extra headers and cookies go only to the origins of the targets and
+    /// `base`.
+    pub async fn probe_all(&self, endpoints: &mut [Endpoint], base: Option<&str>) -> usize {
+        for target in endpoints
+            .iter()
+            .filter_map(|e| e.metadata.as_ref()?.get("target"))
+            .map(String::as_str)
+            .chain(base)
+        {
+            self.client.add_target(target);
+        }
+        let probes: Vec<(usize, Url)> = endpoints
+            .iter()
+            .enumerate()
+            .filter_map(|(i, endpoint)| probe_target(endpoint, base).map(|url| (i, url)))
+            .collect();
+        info!("Probing {} endpoints", probes.len());
+
+        let baselines = if self.soft_404 {
+            let origins: HashSet<String> = probes
+                .iter()
+                .map(|(_, url)| url.origin().ascii_serialization())
+                .collect();
+            self.not_found_baselines(origins).await
+        } else {
+            HashMap::new()
+        };
+
+        let results: Vec<_> = stream::iter(probes)
+            .map(|(i, url)| async move { (i, self.probe(&url).await, url) })
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await;
+        let mut probed = 0;
+        for (i, result, url) in results {
+            match result {
+                Ok(mut probe) => {
+                    if let Some(baseline) = baselines.get(&url.origin().ascii_serialization()) {
+                        probe.soft_404 = is_soft_404(&probe, baseline);
+                    }
+                    endpoints[i].probe = Some(probe);
+                    probed += 1;
+                }
+                Err(e) => debug!("Probe of {} failed: {}", url, e),
+            }
+        }
+        probed
+    }
+
+    /// Probe one URL, following its redirects
+    async fn probe(&self, url: &Url) -> Result<ProbeResult> {
+        let mut probe = self.client.probe(url.as_str()).await?;
+        let mut seen = HashSet::from([url.to_string()]);
+        let mut next = probe.redirect.clone();
+        while let Some(location) = next.take() {
+            let is_http = location.starts_with("http://") || location.starts_with("https://");
+            if probe.redirect_chain.len() >= self.max_redirects
+                || !is_http
+                || !seen.insert(location.clone())
+            {
+                break;
+            }
+            match self.client.probe(&location).await {
+                Ok(hop) => {
+                    next = hop.redirect;
+                    probe.redirect_chain.push(RedirectHop {
+                        url: location,
+                        status: hop.status,
+                    });
+                }
+                Err(e) => debug!("Following redirect to {} failed: {}", location, e),
+            }
+        }
+        if self.options && !matches!(probe.status, 404 | 410) {
+            match self.client.allowed_methods(url.as_str()).await {
+                Ok(methods) => probe.allowed_methods = methods,
+                Err(e) => debug!("OPTIONS for {} failed: {}", url, e),
+            }
+        }
+        Ok(probe)
+    }
+
+    /// Each origin's answer for a random path that shouldn't exist
+    async fn not_found_baselines(&self, origins: HashSet<String>) -> HashMap<String, ProbeResult> {
+        let rng = SystemRandom::new();
+        stream::iter(origins)
+            .map(|origin| {
+                let mut nonce = [0u8; 8];
+                let _ = rng.fill(&mut nonce);
+                let path: String = nonce.iter().map(|b| format!("{:02x}", b)).collect();
+                async move {
+                    let url = format!("{}/endpointo-{}", origin, path);
+                    match self.client.probe(&url).await {
+                        Ok(baseline) => Some((origin, baseline)),
+                        Err(e) => {
+                            debug!("Soft 404 check of {} failed: {}", origin, e);
+                            None
+                        }
+                    }
+                }
+            })
+            .buffer_unordered(self.concurrency)
+            .filter_map(futures::future::ready)
+            .collect()
+            .await
+    }
+}
+
+/// URL to probe for an endpoint, if it has a usable one
+pub(crate) fn probe_target(endpoint: &Endpoint, base: Option<&str>) -> Option<Url> {
+    if endpoint.url.contains(['{', '}', '$', '*', '<', '>']) {
+        return None;
+    }
+    let url = absolute_url(endpoint, base)?;
+    if url.path_segments()?.any(|s| s.starts_with(':')) {
+        return None;
+    }
+    Some(url)
+}
+
+/// Whether a response matches the host's answer for a missing path
+///
+/// Hosts answering 404 or 410 for missing paths have no soft 404s. Others
+/// match on status plus the redirect target (ignoring its query, which
+/// often echoes the path) or content type and roughly equal size.
+fn is_soft_404(probe: &ProbeResult, baseline: &ProbeResult) -> bool {
+    if matches!(baseline.status, 404 | 410) || probe.status != baseline.status {
+        return false;
+    }
+    if let Some(redirect) = &baseline.redirect {
+        let strip = |u: &str| u.split('?').next().unwrap_or_default().to_string();
+        return probe.redirect.as_deref().map(strip) == Some(strip(redirect));
+    }
+    if probe.content_type != baseline.content_type {
+        return false;
+    }
+    match (probe.content_length, baseline.content_length) {
+        (Some(a), Some(b)) => {
+            let larger = a.max(b).max(1) as f64;
+            (a as f64 - b as f64).abs() / larger <= SOFT_404_SIZE_TOLERANCE
+        }
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_soft_404() {
+        let response = |status, length, redirect: Option<&str>| ProbeResult {
+            status,
+            content_length: length,
+            content_type: Some("text/html".to_string()),
+            redirect: redirect.map(str::to_string),
+            ..Default::default()
+        };
+
+        let catch_all = response(200, Some(1000), None);
+        assert!(is_soft_404(&response(200, Some(1020), None), &catch_all));
+        assert!(!is_soft_404(&response(200, Some(4000), None), &catch_all));
+        assert!(!is_soft_404(&response(403, Some(1000), None), &catch_all));
+
+        let login = response(302, None, Some("https://example.com/login?next=/x"));
+        assert!(is_soft_404(
+            &response(302, None, Some("https://example.com/login?next=/api")),
+            &login
+        ));
+        assert!(!is_soft_404(
+            &response(302, None, Some("https://example.com/api/")),
+            &login
+        ));
+
+        let proper = response(404, Some(1000), None);
+        assert!(!is_soft_404(&response(404, Some(1000), None), &proper));
+    }
+}
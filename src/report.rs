@@ -0,0 +1,288 @@
+//! Reports across many scans
+//!
+//! `endpointo report` summarizes a monitoring engagement: for each target,
+//! the endpoint count of every recorded scan, the endpoints that appeared
+//! recently, the ones later scans stopped finding, and a summary per host.
+//! It reads a project database, or result files which are recorded into a
+//! throwaway one first so endpoints are matched across scans the same way.
+
+use crate::error::{Error, Result};
+use crate::output::RELATIVE_HOST;
+use crate::store::{ProjectHistory, ProjectStore, ScanRecord, StoredEndpoint};
+use crate::types::ScanResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use url::Url;
+
+/// Everything a report shows
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    /// When the report was generated
+    pub generated: String,
+
+    /// Endpoints first seen at or after this are new; without it, the ones
+    /// the latest scan of their target found first
+    pub since: Option<String>,
+
+    pub targets: Vec<TargetReport>,
+}
+
+/// One target's scans, new and vanished endpoints, and hosts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetReport {
+    pub target: String,
+
+    /// Recorded scans, oldest first
+    pub scans: Vec<ScanRecord>,
+
+    /// Endpoints any scan found
+    pub endpoints: usize,
+
+    /// Endpoints that appeared in the reported period
+    pub new: Vec<StoredEndpoint>,
+
+    /// Endpoints earlier scans found but the latest didn't
+    pub gone: Vec<StoredEndpoint>,
+
+    /// Per-origin counts, sorted by origin
+    pub hosts: Vec<HostSummary>,
+}
+
+/// Endpoint counts for one origin
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HostSummary {
+    pub host: String,
+    pub endpoints: usize,
+    pub new: usize,
+    pub gone: usize,
+
+    /// Endpoints by type
+    pub types: BTreeMap<String, usize>,
+}
+
+impl Report {
+    /// Report on a project's recorded scans, of one target or all of them
+    pub fn from_store(
+        store: &ProjectStore,
+        target: Option<&str>,
+        since: Option<&str>,
+    ) -> Result<Self> {
+        Ok(Self::from_history(&store.history(target)?, since))
+    }
+
+    /// Report on result files' scans, recorded in timestamp order
+    pub fn from_results(results: &[ScanResult], since: Option<&str>) -> Result<Self> {
+        let mut results: Vec<&ScanResult> = results.iter().collect();
+        results.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        let mut store = ProjectStore::open_in_memory()?;
+        for result in results {
+            store.record(result)?;
+        }
+        Self::from_store(&store, None, since)
+    }
+
+    /// Report on a project history
+    pub fn from_history(history: &ProjectHistory, since: Option<&str>) -> Self {
+        let mut scans: BTreeMap<&str, Vec<ScanRecord>> = BTreeMap::new();
+        for scan in &history.scans {
+            scans
+                .entry(scan.target.as_str())
+                .or_default()
+                .push(scan.clone());
+        }
+        let mut endpoints: BTreeMap<&str, Vec<&StoredEndpoint>> = BTreeMap::new();
+        for stored in &history.endpoints {
+            endpoints
+                .entry(stored.target.as_str())
+                .or_default()
+                .push(stored);
+        }
+
+        let targets = scans
+            .into_iter()
+            .map(|(target, scans)| {
+                target_report(
+                    target,
+                    scans,
+                    endpoints.remove(target).unwrap_or_default(),
+                    since,
+                )
+            })
+            .collect();
+        Self {
+            generated: chrono::Utc::now().to_rfc3339(),
+            since: since.map(str::to_string),
+            targets,
+        }
+    }
+
+    /// Recorded scans across targets
+    pub fn scans(&self) -> usize {
+        self.targets.iter().map(|t| t.scans.len()).sum()
+    }
+
+    /// Endpoints across targets
+    pub fn endpoints(&self) -> usize {
+        self.targets.iter().map(|t| t.endpoints).sum()
+    }
+
+    /// New endpoints across targets
+    pub fn new_endpoints(&self) -> usize {
+        self.targets.iter().map(|t| t.new.len()).sum()
+    }
+
+    /// Vanished endpoints across targets
+    pub fn gone_endpoints(&self) -> usize {
+        self.targets.iter().map(|t| t.gone.len()).sum()
+    }
+}
+
+/// Check a `--since` value, a date or an RFC 3339 time, and put it in the
+/// form scan timestamps are compared in
+pub fn parse_since(since: &str) -> Result<String> {
+    if chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d").is_ok() {
+        return Ok(since.to_string());
+    }
+    chrono::DateTime::parse_from_rfc3339(since)
+        .map(|time| time.with_timezone(&chrono::Utc).to_rfc3339())
+        .map_err(|_| {
+            Error::ValidationError(format!(
+                "invalid --since '{}': expected a date such as 2026-01-31 or an RFC 3339 time",
+                since
+            ))
+        })
+}
+
+fn target_report(
+    target: &str,
+    scans: Vec<ScanRecord>,
+    endpoints: Vec<&StoredEndpoint>,
+    since: Option<&str>,
+) -> TargetReport {
+    let latest = scans.last().map(|s| s.timestamp.as_str()).unwrap_or("");
+    // Timestamps are RFC 3339, so a date such as 2026-01-01 compares as the
+    // start of that day
+    let cutoff = match since {
+        Some(since) => Some(since),
+        // The first scan is the baseline, not news
+        None if scans.len() > 1 => Some(latest),
+        None => None,
+    };
+    let is_new = |stored: &StoredEndpoint| cutoff.is_some_and(|c| stored.first_seen.as_str() >= c);
+    let is_gone = |stored: &StoredEndpoint| stored.last_seen.as_str() < latest;
+
+    let mut hosts: BTreeMap<String, HostSummary> = BTreeMap::new();
+    for stored in &endpoints {
+        let host = host(stored);
+        let summary = hosts.entry(host.clone()).or_insert_with(|| HostSummary {
+            host,
+            ..Default::default()
+        });
+        summary.endpoints += 1;
+        summary.new += usize::from(is_new(stored));
+        summary.gone += usize::from(is_gone(stored));
+        *summary
+            .types
+            .entry(stored.endpoint.endpoint_type.as_str().to_string())
+            .or_default() += 1;
+    }
+    // Relative endpoints sort last, as on the terminal
+    let mut hosts: Vec<HostSummary> = hosts.into_values().collect();
+    hosts.sort_by_key(|h| h.host == RELATIVE_HOST);
+
+    TargetReport {
+        target: target.to_string(),
+        endpoints: endpoints.len(),
+        new: endpoints
+            .iter()
+            .filter(|s| is_new(s))
+            .map(|s| (*s).clone())
+            .collect(),
+        gone: endpoints
+            .iter()
+            .filter(|s| is_gone(s))
+            .map(|s| (*s).clone())
+            .collect(),
+        hosts,
+        scans,
+    }
+}
+
+/// Origin of a stored endpoint, resolving relative URLs against its target
+fn host(stored: &StoredEndpoint) -> String {
+    let url = Url::parse(&stored.endpoint.url)
+        .or_else(|_| Url::parse(&stored.target).and_then(|t| t.join(&stored.endpoint.url)));
+    match url.map(|url| url.origin()) {
+        Ok(origin) if origin.is_tuple() => origin.ascii_serialization(),
+        _ => RELATIVE_HOST.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Endpoint, EndpointType, SCHEMA_VERSION, TOOL_VERSION};
+
+    fn result(timestamp: &str, urls: &[&str]) -> ScanResult {
+        let endpoints: Vec<Endpoint> = urls
+            .iter()
+            .map(|url| Endpoint::new(url.to_string(), EndpointType::Rest))
+            .collect();
+        ScanResult {
+            schema_version: SCHEMA_VERSION,
+            tool_version: TOOL_VERSION.to_string(),
+            target: "https://example.com".to_string(),
+            timestamp: timestamp.to_string(),
+            total_endpoints: endpoints.len(),
+            endpoints,
+            findings: Vec::new(),
+            wordlist: Vec::new(),
+            technologies: Vec::new(),
+            stats: None,
+        }
+    }
+
+    #[test]
+    fn test_report_from_results() {
+        // Given out of order, recorded oldest first
+        let results = vec![
+            result(
+                "2026-01-03T00:00:00+00:00",
+                &["/api/a", "/api/c", "https://cdn.example.net/x"],
+            ),
+            result("2026-01-01T00:00:00+00:00", &["/api/a", "/api/b"]),
+            result("2026-01-02T00:00:00+00:00", &["/api/a", "/api/b"]),
+        ];
+        let report = Report::from_results(&results, None).unwrap();
+        assert_eq!(report.targets.len(), 1);
+        assert_eq!(report.scans(), 3);
+        assert_eq!(report.endpoints(), 4);
+
+        let target = &report.targets[0];
+        let new: Vec<&str> = target.new.iter().map(|s| s.endpoint.url.as_str()).collect();
+        assert_eq!(new, vec!["/api/c", "https://cdn.example.net/x"]);
+        assert_eq!(target.gone[0].endpoint.url, "/api/b");
+        assert_eq!(
+            target
+                .hosts
+                .iter()
+                .map(|h| h.host.as_str())
+                .collect::<Vec<_>>(),
+            vec!["https://cdn.example.net", "https://example.com"]
+        );
+        assert_eq!(target.hosts[1].endpoints, 3);
+        assert_eq!(target.hosts[1].new, 1);
+        assert_eq!(target.hosts[1].gone, 1);
+
+        let report = Report::from_results(&results, Some("2026-01-02")).unwrap();
+        assert_eq!(report.new_endpoints(), 2);
+        let report = Report::from_results(&results[1..2], None).unwrap();
+        assert_eq!(report.new_endpoints(), 0);
+
+        assert_eq!(
+            parse_since("2026-01-02T02:00:00+02:00").unwrap(),
+            "2026-01-02T00:00:00+00:00"
+        );
+        assert!(parse_since("last week").is_err());
+    }
+}
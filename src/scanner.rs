@@ -1,117 +1,479 @@
+use crate::classify::{load_rules, Classifier};
 use crate::cli::InteractiveUi;
 use crate::config::ScanConfig;
-use crate::crawler::Crawler;
-use crate::error::Result;
-use crate::parser::Parser;
-use crate::plugins::PluginManager;
-use crate::types::Endpoint;
-use std::path::Path;
+use crate::crawler::client::FetchResponse;
+use crate::crawler::visited::VisitedSet;
+use crate::crawler::{CrawledPage, Crawler};
+use crate::error::{Error, Result};
+use crate::fingerprint::{merge as merge_technology, Fingerprinter};
+use crate::input::InputFilter;
+use crate::observer::ScanObserver;
+use crate::parser::detect::{detect_asset_kind, SNIFF_BYTES};
+use crate::parser::extractor::{AssetKind, EndpointExtractor};
+use crate::parser::filters::EndpointFilter;
+use crate::parser::params::build_wordlist;
+use crate::parser::patterns::load_patterns;
+use crate::parser::versions::group_by_version;
+use crate::parser::window::WindowReader;
+use crate::parser::wrappers::{WrapperResolver, WrapperSet};
+use crate::parser::{ParsedAsset, Parser};
+use crate::plugins::{AssetInfo, Batch, EndpointoPlugin, PluginManager, PluginReloader, Script};
+use crate::scope::Scope;
+use crate::state::ScanState;
+use crate::types::{
+    Endpoint, Finding, ScanResult, ScanStats, Technology, SCHEMA_VERSION, TOOL_VERSION,
+};
+use crate::util::lock;
+use chrono::{DateTime, Utc};
+use futures::future;
+use futures::stream::{self, Stream, StreamExt};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tokio::fs;
-use tracing::{debug, error, info};
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
+use url::Url;
 
 /// Main scanner orchestrator
 pub struct Scanner {
     crawler: Crawler,
+    config: ScanConfig,
+    /// Shared with the blocking threads the plugins run on
+    plugin_manager: Arc<PluginManager>,
+    /// Shared with the blocking threads assets are parsed on
+    analyzer: Arc<Analyzer>,
+    endpoint_filter: EndpointFilter,
+    classifier: Classifier,
+    scope: Scope,
+    observers: Vec<Arc<dyn ScanObserver>>,
+    budget_exhausted: Mutex<Option<String>>,
+}
+
+/// The parsing side of a scanner: turns pages, assets and files into
+/// endpoints, recording what it learns along the way
+struct Analyzer {
     parser: Parser,
+    plugin_manager: Arc<PluginManager>,
+    findings: Mutex<Vec<Finding>>,
+    fingerprinter: Fingerprinter,
+    technologies: Mutex<Vec<Technology>>,
+    /// Assets parsed so far by source, for the plugins' per-endpoint hooks
+    assets: Mutex<HashMap<String, Arc<AssetInfo>>>,
+    wrapper_resolver: WrapperResolver,
+    files_processed: AtomicUsize,
+    max_parse_bytes: usize,
+    parse_window_bytes: usize,
+}
+
+/// Builds a `Scanner`, validating its configuration first
+pub struct ScannerBuilder {
     config: ScanConfig,
-    plugin_manager: PluginManager,
-    ui: Option<InteractiveUi>,
+    observers: Vec<Arc<dyn ScanObserver>>,
+    extractors: Vec<Box<dyn EndpointExtractor>>,
+    plugins: Vec<Box<dyn EndpointoPlugin>>,
+    /// The configured plugins, already loaded
+    loaded_plugins: Option<Vec<Arc<dyn EndpointoPlugin>>>,
 }
 
-impl Scanner {
-    /// Create a new scanner
-    pub fn new(config: ScanConfig) -> Result<Self> {
+impl ScannerBuilder {
+    /// Start building a scanner for a configuration
+    pub fn new(config: ScanConfig) -> Self {
+        Self {
+            config,
+            observers: Vec::new(),
+            extractors: Vec::new(),
+            plugins: Vec::new(),
+            loaded_plugins: None,
+        }
+    }
+
+    /// Register an observer notified of scan progress
+    pub fn with_observer(mut self, observer: impl ScanObserver + 'static) -> Self {
+        self.observers.push(Arc::new(observer));
+        self
+    }
+
+    /// Register an additional extractor with the scanner's parser
+    pub fn with_extractor(mut self, extractor: impl EndpointExtractor + 'static) -> Self {
+        self.extractors.push(Box::new(extractor));
+        self
+    }
+
+    /// Register a Rust plugin, run after the configured plugin
+    pub fn with_plugin(mut self, plugin: impl EndpointoPlugin + 'static) -> Self {
+        self.plugins.push(Box::new(plugin));
+        self
+    }
+
+    /// Use the configured plugins a reloader has loaded instead of loading
+    /// the files again
+    pub fn with_plugin_reloader(mut self, reloader: &PluginReloader) -> Self {
+        self.loaded_plugins = Some(reloader.plugins().to_vec());
+        self
+    }
+
+    /// Validate the configuration and build the scanner
+    ///
+    /// Fails if the rate limit, concurrency, timeout or page budget is zero, the target
+    /// isn't an HTTP(S) URL (an empty target is allowed for parsing local
+    /// files), a scope regex is invalid, the filter script doesn't parse, or
+    /// the plugin, patterns or rules file can't be loaded.
+    pub fn build(self) -> Result<Scanner> {
+        let config = self.config;
+        validate(&config, self.loaded_plugins.is_none())?;
+
+        let scope = Scope::from_config(&config)?;
         let crawler = Crawler::new(config.clone())?;
-        let parser = Parser::new();
-        let mut plugin_manager = PluginManager::new();
+        let mut parser = match &config.patterns_path {
+            Some(path) => Parser::with_custom_patterns(load_patterns(path)?),
+            None => Parser::new(),
+        }
+        .with_aggressive_filter(config.aggressive_filter);
+        for extractor in self.extractors {
+            parser = parser.with_boxed_extractor(extractor);
+        }
+
+        let mut plugin_manager = PluginManager::new()
+            .with_limits(config.plugin_limits())
+            .with_error_policy(config.plugin_errors);
+        for builtin in &config.builtin_plugins {
+            plugin_manager.add_plugin(builtin.plugin(&config));
+        }
+        match self.loaded_plugins {
+            Some(plugins) => {
+                for plugin in plugins {
+                    plugin_manager.add_plugin(Box::new(plugin));
+                }
+            }
+            None => {
+                for path in config.plugin_chain()? {
+                    plugin_manager.load_plugin(&path)?;
+                }
+            }
+        }
+        if let Some(script) = &config.script {
+            plugin_manager.add_plugin(Box::new(Script::parse(script)?));
+        }
+        for plugin in self.plugins {
+            plugin_manager.add_plugin(plugin);
+        }
 
-        if let Some(plugin_path) = &config.plugin_path {
-            let _ = plugin_manager.load_plugin(plugin_path);
+        let endpoint_filter = EndpointFilter::new().with_trailing_slash(config.trailing_slash);
+        let mut classifier = Classifier::new();
+        if let Some(rules_path) = &config.rules_path {
+            classifier = classifier.with_rules(load_rules(rules_path)?);
         }
 
-        Ok(Self {
-            crawler,
+        let plugin_manager = Arc::new(plugin_manager);
+        let analyzer = Analyzer {
             parser,
+            plugin_manager: plugin_manager.clone(),
+            findings: Mutex::new(Vec::new()),
+            fingerprinter: Fingerprinter::new(),
+            technologies: Mutex::new(Vec::new()),
+            assets: Mutex::new(HashMap::new()),
+            wrapper_resolver: WrapperResolver::new(),
+            files_processed: AtomicUsize::new(0),
+            max_parse_bytes: config.max_parse_bytes,
+            parse_window_bytes: config.parse_window_bytes,
+        };
+
+        Ok(Scanner {
+            crawler,
             config,
             plugin_manager,
-            ui: None,
+            analyzer: Arc::new(analyzer),
+            endpoint_filter,
+            classifier,
+            scope,
+            observers: self.observers,
+            budget_exhausted: Mutex::new(None),
         })
     }
+}
+
+/// Check a configuration for values a scanner can't run with, and with
+/// `plugin_files`, that its plugin files exist
+fn validate(config: &ScanConfig, plugin_files: bool) -> Result<()> {
+    let invalid = |msg: String| Err(Error::ValidationError(msg));
+
+    if config.rate_limit == 0 {
+        return invalid("rate limit must be at least 1 request per second".to_string());
+    }
+    if config.max_concurrent == 0 {
+        return invalid("concurrency must be at least 1".to_string());
+    }
+    if config.timeout_seconds == 0 {
+        return invalid("timeout must be at least 1 second".to_string());
+    }
+    if config.max_pages == 0 {
+        return invalid("page budget must be at least 1 page".to_string());
+    }
+    for rule in &config.host_overrides {
+        if rule.host.trim().is_empty() {
+            return invalid("host override without a host glob".to_string());
+        }
+        if rule.rate_limit == Some(0) {
+            return invalid(format!(
+                "{}: rate limit must be at least 1 request per second",
+                rule.host
+            ));
+        }
+        if rule.timeout == Some(0) {
+            return invalid(format!("{}: timeout must be at least 1 second", rule.host));
+        }
+    }
+    if config.plugin_timeout.is_some_and(|t| t.is_zero()) {
+        return invalid("plugin timeout must be greater than 0".to_string());
+    }
+    if !config.target_url.is_empty() {
+        let url = Url::parse(&config.target_url)
+            .map_err(|e| Error::ValidationError(format!("{}: {}", config.target_url, e)))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return invalid(format!(
+                "{}: only http and https targets are supported",
+                url
+            ));
+        }
+    }
+    let plugins = config
+        .plugins
+        .iter()
+        .map(|spec| &spec.path)
+        .filter(|_| plugin_files);
+    for path in [&config.patterns_path, &config.rules_path]
+        .into_iter()
+        .flatten()
+        .chain(plugins)
+    {
+        if !path.is_file() {
+            return invalid(format!("{}: file not found", path.display()));
+        }
+    }
+    if let Some(dir) = config.plugin_dir.as_ref().filter(|_| plugin_files) {
+        if !dir.is_dir() {
+            return invalid(format!("{}: directory not found", dir.display()));
+        }
+    }
+    Ok(())
+}
+
+impl Scanner {
+    /// Create a new scanner
+    ///
+    /// Shorthand for `ScannerBuilder::new(config).build()`.
+    pub fn new(config: ScanConfig) -> Result<Self> {
+        ScannerBuilder::new(config).build()
+    }
+
+    /// Start building a scanner with observers or extra extractors
+    pub fn builder(config: ScanConfig) -> ScannerBuilder {
+        ScannerBuilder::new(config)
+    }
 
     /// Set interactive UI
-    pub fn with_ui(mut self, ui: InteractiveUi) -> Self {
-        self.ui = Some(ui);
+    pub fn with_ui(self, ui: InteractiveUi) -> Self {
+        self.with_observer(ui)
+    }
+
+    /// Register an observer notified of scan progress
+    pub fn with_observer(mut self, observer: impl ScanObserver + 'static) -> Self {
+        self.observers.push(Arc::new(observer));
+        self
+    }
+
+    /// Register an additional extractor with the scanner's parser
+    pub fn with_extractor(mut self, extractor: impl EndpointExtractor + 'static) -> Self {
+        let analyzer = Arc::get_mut(&mut self.analyzer)
+            .expect("extractors are added before the scanner parses anything");
+        analyzer.parser = std::mem::take(&mut analyzer.parser).with_extractor(extractor);
         self
     }
 
+    /// Scan the configured target and report the endpoints with scan statistics
+    pub async fn scan(&self) -> Result<ScanResult> {
+        let started = Utc::now();
+        let endpoints = self.scan_url(&self.config.target_url).await?;
+        Ok(self.build_result(self.config.target_url.clone(), started, endpoints))
+    }
+
+    /// Wrap endpoints gathered by this scanner in a `ScanResult`
+    ///
+    /// `started` is when the scan began. File and request counts cover
+    /// everything this scanner has done, so reuse a scanner across targets
+    /// only when cumulative stats are wanted.
+    pub fn build_result(
+        &self,
+        target: impl Into<String>,
+        started: DateTime<Utc>,
+        endpoints: Vec<Endpoint>,
+    ) -> ScanResult {
+        let duration = (Utc::now() - started).to_std().unwrap_or_default();
+        let mut stats = endpoint_stats(&endpoints);
+        stats.files_processed = self.analyzer.files_processed.load(Ordering::Relaxed);
+        stats.requests_made = self.crawler.requests_made();
+        stats.plugin_errors = self.plugin_manager.error_counts();
+        stats.duration_seconds = duration.as_secs_f64();
+        stats.budget_exhausted = lock(&self.budget_exhausted)
+            .clone()
+            .or_else(|| {
+                self.crawler
+                    .assets_exhausted()
+                    .then(|| "max_assets".to_string())
+            })
+            .or_else(|| {
+                self.crawler
+                    .pages_exhausted()
+                    .then(|| "max_pages".to_string())
+            });
+
+        ScanResult {
+            schema_version: SCHEMA_VERSION,
+            tool_version: TOOL_VERSION.to_string(),
+            target: target.into(),
+            timestamp: started.to_rfc3339(),
+            total_endpoints: endpoints.len(),
+            findings: self.findings(),
+            wordlist: build_wordlist(&endpoints),
+            technologies: self.technologies_for(&endpoints),
+            stats: Some(stats),
+            endpoints,
+        }
+    }
+
     /// Scan a URL and extract endpoints
+    ///
+    /// Assets are fetched up to `max_concurrent` at a time and handed to as
+    /// many parse workers as they arrive, through a bounded channel; each
+    /// is parsed on a blocking thread, along with the plugins'
+    /// `generate_endpoints` hooks.
     pub async fn scan_url(&self, url: &str) -> Result<Vec<Endpoint>> {
         info!("Starting scan of {}", url);
 
-        if let Some(ui) = &self.ui {
-            ui.set_main_message(&format!("Scanning {}", url));
-        }
-
+        let deadline = self.deadline();
         let mut all_endpoints = Vec::new();
 
-        // 1. Crawl the URL to find JavaScript assets
-        let assets = self.crawler.crawl(url).await?;
-        info!("Found {} JavaScript assets", assets.len());
-
-        if let Some(ui) = &self.ui {
-            ui.inc_main();
-            ui.set_main_message(&format!("Found {} JS files", assets.len()));
+        // 1. Crawl the URL to find JavaScript assets, adding any the plugins know of
+        let visited = &self.crawler.visits();
+        let mut page = self.crawler.crawl_page_in(url, visited).await?;
+        self.discover_assets(url, &mut page.assets).await?;
+        info!("Found {} JavaScript assets", page.assets.len());
+        for asset_url in &page.assets {
+            self.notify(|o| o.on_asset_discovered(asset_url));
         }
 
-        // 2. Parse the main page first for inline scripts/endpoints
-        if let Ok(html) = self.crawler.fetch_js(url).await {
-            if let Ok(endpoints) = self.parser.parse_js(&html, Some(url)) {
-                all_endpoints.extend(endpoints);
-            }
-        }
+        // 2. Parse the main page and linked pages for markup and inline scripts
+        self.analyzer.fingerprint_page(url, &page);
+        self.analyzer.record_page(url, &page);
+        let mut pages = vec![(url.to_string(), std::mem::take(&mut page.html))];
+        pages.append(&mut page.linked_pages);
+        all_endpoints.extend(self.parse_pages(pages).await);
 
-        // 3. Parse each discovered asset
-        for asset_url in assets {
-            if let Some(ui) = &self.ui {
-                ui.set_main_message(&format!("Parsing {}", asset_url));
-            }
+        // 3. Fetch the discovered assets concurrently, handing each response
+        //    to the parse workers as it arrives. The channel is bounded, so
+        //    fetching waits when parsing falls behind instead of holding
+        //    every body in memory.
+        let workers = self.config.max_concurrent.max(1);
+        let (sender, mut receiver) = mpsc::channel::<(usize, String, FetchResponse)>(workers);
+        let fetch = async move {
+            stream::iter(page.assets)
+                .enumerate()
+                .take_while(|_| future::ready(!self.out_of_time(deadline)))
+                .map(|(i, asset_url)| async move {
+                    self.notify(|o| o.on_asset_fetching(&asset_url));
+                    let result = self.crawler.fetch_asset_in(&asset_url, visited).await;
+                    (i, asset_url, result)
+                })
+                .buffer_unordered(workers)
+                .for_each(|(i, asset_url, result)| {
+                    let sender = sender.clone();
+                    async move {
+                        match result {
+                            Ok(Some(response)) => {
+                                self.notify(|o| {
+                                    o.on_asset_fetched(&asset_url, response.size as usize)
+                                });
+                                // Only fails if the parse workers are gone
+                                let _ = sender.send((i, asset_url, response)).await;
+                            }
+                            Ok(None) => debug!("Skipping already fetched {}", asset_url),
+                            Err(e) => {
+                                error!("Failed to fetch {}: {}", asset_url, e);
+                                self.notify(|o| o.on_error(&asset_url, &e));
+                            }
+                        }
+                    }
+                })
+                .await;
+        };
 
-            match self.crawler.fetch_js(&asset_url).await {
-                Ok(js_content) => match self.parser.parse_js(&js_content, Some(&asset_url)) {
+        // 4. Parse the assets as they're fetched; results keep the crawl order
+        let parse = stream::poll_fn(|cx| receiver.poll_recv(cx))
+            .map(|(i, asset_url, response)| async move {
+                let (result, script) = self.parse_fetched(asset_url.clone(), response).await;
+                let endpoints = match result {
                     Ok(endpoints) => {
                         debug!("Extracted {} endpoints from {}", endpoints.len(), asset_url);
-                        all_endpoints.extend(endpoints);
+                        self.notify(|o| o.on_asset_parsed(&asset_url, endpoints.len()));
+                        endpoints
                     }
                     Err(e) => {
                         error!("Failed to parse {}: {}", asset_url, e);
+                        self.notify(|o| o.on_error(&asset_url, &e));
+                        Vec::new()
                     }
-                },
-                Err(e) => {
-                    error!("Failed to fetch {}: {}", asset_url, e);
-                }
-            }
+                };
+                (i, endpoints, script.map(|text| (asset_url, text)))
+            })
+            .buffer_unordered(workers)
+            .collect::<Vec<_>>();
+        let ((), mut parsed) = tokio::join!(fetch, parse);
+        parsed.sort_by_key(|(i, _, _)| *i);
+        let mut scripts = Vec::new();
+        for (_, endpoints, script) in parsed {
+            all_endpoints.extend(endpoints);
+            scripts.extend(script);
         }
 
-        // 4. Transform endpoints using plugins
-        let mut processed_endpoints = Vec::new();
-        for ep in all_endpoints {
-            if self.plugin_manager.filter_endpoint(&ep) {
-                let transformed = self.plugin_manager.transform_endpoint(ep);
-                processed_endpoints.push(transformed);
-            }
+        // 5. Resolve calls to HTTP wrappers defined in another asset
+        let resolved = self.resolve_wrappers(scripts).await;
+        if !resolved.is_empty() {
+            debug!("Resolved {} wrapper calls", resolved.len());
+            all_endpoints.extend(resolved);
         }
 
-        // 5. Apply config-based filter if specified
+        // 6. Canonicalize URLs against the asset they were found in
+        self.endpoint_filter.canonicalize_all(&mut all_endpoints);
+
+        // 7. Transform endpoints using plugins, then tag them
+        let mut processed_endpoints = self.apply_plugins_async(all_endpoints).await?;
+        self.classify(&mut processed_endpoints);
+
+        // 8. Apply config-based filter and scope if specified
         if let Some(filter) = &self.config.filter_pattern {
             processed_endpoints.retain(|e| e.url.contains(filter));
         }
+        self.retain_in_scope(Some(url), &mut processed_endpoints);
 
-        // 6. Final deduplication (just in case)
-        processed_endpoints.dedup_by(|a, b| a.url == b.url && a.method == b.method);
+        // 9. Deduplicate across assets
+        self.endpoint_filter.deduplicate(&mut processed_endpoints);
+        self.cap_endpoints(&mut processed_endpoints, 0);
+
+        // 10. Probe what's left on the target's host
+        if self.config.probe {
+            self.probe_endpoints(url, &mut processed_endpoints, deadline)
+                .await;
+        }
 
-        if let Some(ui) = &self.ui {
-            ui.finish();
+        for endpoint in &processed_endpoints {
+            self.notify(|o| o.on_endpoint_found(endpoint));
         }
+        self.notify(|o| o.on_complete(url, processed_endpoints.len()));
 
         info!(
             "Total unique endpoints found: {}",
@@ -120,24 +482,911 @@ impl Scanner {
         Ok(processed_endpoints)
     }
 
+    /// Scan a URL, yielding endpoints as each asset is parsed
+    ///
+    /// Assets are fetched up to `max_concurrent` at a time and parsed as
+    /// they arrive; endpoints from the page itself and from wrapper calls
+    /// that span assets follow at the end. Each endpoint is yielded once,
+    /// the first time it is seen, so unlike `scan_url` later sightings
+    /// aren't merged into it. A failed crawl is yielded as an error;
+    /// failures on single assets are logged and skipped.
+    pub fn scan_stream<'a>(&'a self, url: &'a str) -> impl Stream<Item = Result<Endpoint>> + 'a {
+        info!("Starting streaming scan of {}", url);
+
+        let deadline = self.deadline();
+        let found = Arc::new(AtomicUsize::new(0));
+        let counted = found.clone();
+        let budgeted = found.clone();
+
+        let scripts: Arc<Mutex<Vec<(String, String)>>> = Arc::default();
+        let collected = scripts.clone();
+
+        let page_html: Arc<Mutex<Vec<(String, String)>>> = Arc::default();
+        let crawled_html = page_html.clone();
+
+        let visited = Arc::new(self.crawler.visits());
+        let fetched = visited.clone();
+
+        let crawl = async move {
+            let mut page = self.crawler.crawl_page_in(url, &visited).await?;
+            self.discover_assets(url, &mut page.assets).await?;
+            Ok(page)
+        };
+        let crawled = stream::once(crawl).flat_map(move |crawled| {
+            let assets: Vec<Result<String>> = match crawled {
+                Ok(mut page) => {
+                    self.analyzer.fingerprint_page(url, &page);
+                    self.analyzer.record_page(url, &page);
+                    let mut pages = vec![(url.to_string(), std::mem::take(&mut page.html))];
+                    pages.append(&mut page.linked_pages);
+                    *lock(&crawled_html) = pages;
+                    page.assets.into_iter().map(Ok).collect()
+                }
+                Err(e) => vec![Err(e)],
+            };
+            stream::iter(assets)
+        });
+
+        // Stop taking assets once the time or endpoint budget runs out
+        let assets = crawled
+            .take_while(move |_| {
+                let full = self
+                    .config
+                    .max_endpoints
+                    .is_some_and(|max| budgeted.load(Ordering::Relaxed) >= max);
+                future::ready(!full && !self.out_of_time(deadline))
+            })
+            .map(move |asset| {
+                let scripts = collected.clone();
+                let visited = fetched.clone();
+                async move {
+                    let asset_url = asset?;
+                    self.notify(|o| o.on_asset_discovered(&asset_url));
+                    self.notify(|o| o.on_asset_fetching(&asset_url));
+                    let response = match self.crawler.fetch_asset_in(&asset_url, &visited).await {
+                        Ok(Some(response)) => response,
+                        Ok(None) => return Ok(Vec::new()),
+                        Err(e) => {
+                            error!("Failed to fetch {}: {}", asset_url, e);
+                            self.notify(|o| o.on_error(&asset_url, &e));
+                            return Ok(Vec::new());
+                        }
+                    };
+                    self.notify(|o| o.on_asset_fetched(&asset_url, response.size as usize));
+
+                    let (parsed, script) = self.parse_fetched(asset_url.clone(), response).await;
+                    if let Some(text) = script {
+                        lock(&scripts).push((asset_url.clone(), text));
+                    }
+                    Ok(match parsed {
+                        Ok(endpoints) => {
+                            self.notify(|o| o.on_asset_parsed(&asset_url, endpoints.len()));
+                            endpoints
+                        }
+                        Err(e) => {
+                            error!("Failed to parse {}: {}", asset_url, e);
+                            self.notify(|o| o.on_error(&asset_url, &e));
+                            Vec::new()
+                        }
+                    })
+                }
+            })
+            .buffered(self.config.max_concurrent.max(1));
+
+        // The pages' HTML is kept by the crawl
+        let main_page = stream::once(async move {
+            let pages = std::mem::take(&mut *lock(&page_html));
+            Ok(self.parse_pages(pages).await)
+        });
+
+        // Runs once every asset has been seen
+        let wrapper_calls = stream::once(async move {
+            let scripts = std::mem::take(&mut *lock(&scripts));
+            Ok(self.resolve_wrappers(scripts).await)
+        });
+
+        let complete = stream::once(async move {
+            self.notify(|o| o.on_complete(url, found.load(Ordering::Relaxed)));
+            Ok(Vec::new())
+        });
+
+        let seen = VisitedSet::new(self.config.max_visited, self.config.visited_bloom);
+        assets
+            .chain(main_page)
+            .chain(wrapper_calls)
+            .chain(complete)
+            .then(move |batch: Result<Vec<Endpoint>>| async move {
+                let mut endpoints = batch?;
+                self.endpoint_filter.canonicalize_all(&mut endpoints);
+                self.apply_plugins_async(endpoints).await
+            })
+            .map(move |batch: Result<Vec<Endpoint>>| {
+                let mut processed = batch?;
+                self.classify(&mut processed);
+                if let Some(filter) = &self.config.filter_pattern {
+                    processed.retain(|e| e.url.contains(filter));
+                }
+                self.retain_in_scope(Some(url), &mut processed);
+                processed.retain(|e| {
+                    seen.insert_hashed(&(self.endpoint_filter.dedup_key(&e.url), &e.method))
+                });
+                self.cap_endpoints(&mut processed, counted.load(Ordering::Relaxed));
+                counted.fetch_add(processed.len(), Ordering::Relaxed);
+                Ok(processed)
+            })
+            .then(move |batch: Result<Vec<Endpoint>>| async move {
+                let mut endpoints = batch?;
+                if self.config.probe {
+                    self.probe_endpoints(url, &mut endpoints, deadline).await;
+                }
+                Ok(endpoints)
+            })
+            .flat_map(move |batch: Result<Vec<Endpoint>>| {
+                let items: Vec<Result<Endpoint>> = match batch {
+                    Ok(endpoints) => endpoints
+                        .into_iter()
+                        .inspect(|e| self.notify(|o| o.on_endpoint_found(e)))
+                        .map(Ok)
+                        .collect(),
+                    Err(e) => vec![Err(e)],
+                };
+                stream::iter(items)
+            })
+    }
+
+    /// Scan several URLs, up to `max_concurrent` at a time
+    ///
+    /// Targets share the crawler, so its request budget and rate limit are
+    /// global rather than per target. Each target keeps its own visited
+    /// pages and assets, so a script several targets load is fetched and
+    /// parsed for each of them. Each endpoint carries its target in
+    /// `target` metadata. Results are returned in the order of `urls`.
+    pub async fn scan_urls(&self, urls: &[String]) -> Vec<(String, Result<Vec<Endpoint>>)> {
+        let mut results: Vec<(String, Result<Vec<Endpoint>>)> =
+            self.scan_urls_stream(urls).collect().await;
+        results.sort_by_key(|(url, _)| urls.iter().position(|u| u == url));
+        results
+    }
+
+    /// Scan several URLs like [`Scanner::scan_urls`], yielding each
+    /// target's endpoints as soon as it finishes
+    pub fn scan_urls_stream<'a>(
+        &'a self,
+        urls: &'a [String],
+    ) -> impl Stream<Item = (String, Result<Vec<Endpoint>>)> + 'a {
+        info!("Scanning {} targets", urls.len());
+
+        stream::iter(urls)
+            .map(move |url| async move {
+                let result = self.scan_url(url).await.map(|endpoints| {
+                    endpoints
+                        .into_iter()
+                        .map(|endpoint| label_target(endpoint, url))
+                        .collect()
+                });
+                (url.clone(), result)
+            })
+            .buffer_unordered(self.config.max_concurrent.max(1))
+    }
+
+    /// Pick up where a checkpointed scan left off
+    ///
+    /// The findings and technologies of finished targets are kept.
+    /// Skipping the finished targets is up to the caller; their pages and
+    /// assets aren't marked visited, so pending targets that share a
+    /// script still fetch and parse it.
+    pub fn restore(&self, state: &ScanState) {
+        self.analyzer.record_findings(state.findings.clone());
+        self.analyzer
+            .record_technologies(state.technologies.clone());
+    }
+
     /// Parse a local file and extract endpoints
     pub async fn parse_file(&self, path: &Path) -> Result<Vec<Endpoint>> {
         info!("Parsing file: {}", path.display());
 
-        let content = fs::read_to_string(path).await?;
+        let analyzer = self.analyzer.clone();
+        let owned = path.to_path_buf();
+        let mut endpoints = if fs::metadata(path).await?.len() > self.config.max_parse_bytes as u64
+        {
+            blocking(move || analyzer.parse_large_file(&owned)).await?
+        } else {
+            let content = fs::read(path).await?;
+            blocking(move || analyzer.parse_content(&owned, &content)).await?
+        };
+        self.endpoint_filter.canonicalize_all(&mut endpoints);
+
+        let mut processed_endpoints = self.apply_plugins_async(endpoints).await?;
+        self.classify(&mut processed_endpoints);
+        self.retain_in_scope(None, &mut processed_endpoints);
+        self.endpoint_filter.deduplicate(&mut processed_endpoints);
+        Ok(processed_endpoints)
+    }
+
+    /// Parse content that isn't on disk, such as piped stdin
+    ///
+    /// `name` stands in for the file path: it is the endpoints' source and
+    /// its extension, if any, picks the parser; otherwise the kind is sniffed.
+    pub fn parse_bytes(&self, name: &str, content: &[u8]) -> Result<Vec<Endpoint>> {
+        info!("Parsing {} ({} bytes)", name, content.len());
+
+        let mut endpoints = self.analyzer.parse_content(Path::new(name), content)?;
+        self.endpoint_filter.canonicalize_all(&mut endpoints);
+        let mut processed_endpoints = self.apply_plugins(endpoints)?;
+        self.classify(&mut processed_endpoints);
+        self.retain_in_scope(None, &mut processed_endpoints);
+        self.endpoint_filter.deduplicate(&mut processed_endpoints);
+        Ok(processed_endpoints)
+    }
+
+    /// Expand files, directories, and glob patterns into the files to parse
+    ///
+    /// Uses the configured extensions and file size limit; see
+    /// [`InputFilter::collect`].
+    pub fn collect_files(&self, inputs: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        InputFilter::new()
+            .with_extensions(self.config.file_extensions.clone())
+            .with_max_file_bytes(self.config.max_file_bytes)
+            .collect(inputs)
+    }
+
+    /// Parse every matching file under a directory
+    ///
+    /// Results are paired with their files, in path order.
+    pub fn parse_dir(&self, dir: &Path) -> Result<Vec<(PathBuf, Result<Vec<Endpoint>>)>> {
+        info!("Parsing directory: {}", dir.display());
+
+        let files = self.collect_files(&[dir.to_path_buf()])?;
+        let results = self.parse_files(&files);
+        Ok(files.into_iter().zip(results).collect())
+    }
+
+    /// Parse several local files in parallel
+    ///
+    /// Reading and parsing run on the rayon pool; plugins then run on the
+    /// calling thread. Calls to HTTP wrappers defined in one file are
+    /// resolved in all of them. Results are returned in the order of `paths`.
+    pub fn parse_files(&self, paths: &[PathBuf]) -> Vec<Result<Vec<Endpoint>>> {
+        info!("Parsing {} files", paths.len());
+
+        let parsed: Vec<Result<(Vec<Endpoint>, Option<String>)>> = paths
+            .par_iter()
+            .map(|path| {
+                if std::fs::metadata(path)?.len() > self.config.max_parse_bytes as u64 {
+                    let mut endpoints = self.analyzer.parse_large_file(path)?;
+                    self.endpoint_filter.canonicalize_all(&mut endpoints);
+                    return Ok((endpoints, None));
+                }
+                let content = std::fs::read(path)?;
+                let mut endpoints = self.analyzer.parse_content(path, &content)?;
+                self.endpoint_filter.canonicalize_all(&mut endpoints);
+                let script = self
+                    .analyzer
+                    .script_text(&path.to_string_lossy(), None, &content);
+                Ok((endpoints, script))
+            })
+            .collect();
+
+        let sources: Vec<String> = paths
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        let scripts: Vec<(&str, &str)> = parsed
+            .iter()
+            .zip(&sources)
+            .filter_map(|(result, source)| match result {
+                Ok((_, Some(text))) => Some((source.as_str(), text.as_str())),
+                _ => None,
+            })
+            .collect();
+        let wrappers = self.analyzer.find_wrappers(&scripts);
+
+        parsed
+            .into_iter()
+            .zip(&sources)
+            .map(|(result, source)| {
+                let (mut endpoints, script) = result?;
+                if let Some(text) = script.filter(|_| !wrappers.is_empty()) {
+                    let mut resolved = wrappers.resolve(&text, Some(source));
+                    self.endpoint_filter.canonicalize_all(&mut resolved);
+                    endpoints.extend(resolved);
+                }
+
+                let mut processed_endpoints = self.apply_plugins(endpoints)?;
+                self.classify(&mut processed_endpoints);
+                self.retain_in_scope(None, &mut processed_endpoints);
+                self.endpoint_filter.deduplicate(&mut processed_endpoints);
+                Ok(processed_endpoints)
+            })
+            .collect()
+    }
+
+    /// Probe endpoints on the target's host, recording how each responded
+    ///
+    /// Relative URLs are resolved against the target; endpoints on other
+    /// hosts, non-HTTP URLs, and URLs with template placeholders are left
+    /// alone. Probes share the crawler's rate limit and concurrency cap, and
+    /// stop being sent once `deadline` passes. With `probe_options`, any
+    /// endpoint that didn't answer 404 or 410 is then sent `OPTIONS`.
+    async fn probe_endpoints(
+        &self,
+        target: &str,
+        endpoints: &mut [Endpoint],
+        deadline: Option<Instant>,
+    ) {
+        let Ok(base) = Url::parse(target) else {
+            return;
+        };
+        let probes: Vec<(usize, String)> = endpoints
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| probe_url(&base, &e.url).map(|url| (i, url)))
+            .collect();
+        info!("Probing {} endpoints", probes.len());
+
+        let results: Vec<_> = stream::iter(probes)
+            .take_while(|_| future::ready(!self.out_of_time(deadline)))
+            .map(|(i, url)| async move {
+                let mut result = self.crawler.probe(&url).await;
+                if let Ok(probe) = &mut result {
+                    if self.config.probe_options && !matches!(probe.status, 404 | 410) {
+                        match self.crawler.allowed_methods(&url).await {
+                            Ok(methods) => probe.allowed_methods = methods,
+                            Err(e) => debug!("OPTIONS for {} failed: {}", url, e),
+                        }
+                    }
+                }
+                (i, url, result)
+            })
+            .buffer_unordered(self.config.max_concurrent.max(1))
+            .collect()
+            .await;
+        for (i, url, result) in results {
+            match result {
+                Ok(probe) => endpoints[i].probe = Some(probe),
+                Err(e) => debug!("Probe of {} failed: {}", url, e),
+            }
+        }
+    }
+
+    /// When a scan starting now has to stop, if it has a time limit
+    fn deadline(&self) -> Option<Instant> {
+        self.config.max_duration.map(|limit| Instant::now() + limit)
+    }
+
+    /// Check whether a deadline has passed, recording the exhausted budget
+    fn out_of_time(&self, deadline: Option<Instant>) -> bool {
+        let expired = deadline.is_some_and(|d| Instant::now() >= d);
+        if expired {
+            self.exhaust("max_duration");
+        }
+        expired
+    }
+
+    /// Trim a batch to the room `max_endpoints` leaves after `kept` endpoints
+    fn cap_endpoints(&self, endpoints: &mut Vec<Endpoint>, kept: usize) {
+        if let Some(max) = self.config.max_endpoints {
+            let room = max.saturating_sub(kept);
+            if endpoints.len() > room {
+                endpoints.truncate(room);
+                self.exhaust("max_endpoints");
+            }
+        }
+    }
+
+    /// Record the first budget that cut a scan short
+    fn exhaust(&self, budget: &str) {
+        let mut exhausted = lock(&self.budget_exhausted);
+        if exhausted.is_none() {
+            warn!("Scan budget {} exhausted, stopping early", budget);
+            *exhausted = Some(budget.to_string());
+        }
+    }
+
+    /// Call every registered observer
+    fn notify(&self, event: impl Fn(&dyn ScanObserver)) {
+        for observer in &self.observers {
+            event(observer.as_ref());
+        }
+    }
+
+    /// Technologies detected so far from headers, page content and bundles
+    ///
+    /// Accumulates across scans and parses like [`Scanner::findings`].
+    pub fn technologies(&self) -> Vec<Technology> {
+        self.analyzer.technologies()
+    }
+
+    /// Internal infrastructure found so far: env vars, internal hosts, private IPs
+    ///
+    /// Accumulates across `scan_url`, `parse_file`, and `parse_files` calls,
+    /// one entry per distinct kind and value.
+    pub fn findings(&self) -> Vec<Finding> {
+        self.analyzer.findings()
+    }
+
+    /// Detected technologies plus those revealed by endpoint paths
+    fn technologies_for(&self, endpoints: &[Endpoint]) -> Vec<Technology> {
+        let mut technologies = self.technologies();
+        for endpoint in endpoints {
+            for technology in self.analyzer.fingerprinter.from_url(&endpoint.url) {
+                merge_technology(&mut technologies, Some(technology));
+            }
+        }
+        technologies
+    }
+
+    /// Pair endpoints with the assets they were found in
+    fn plugin_batch(&self, endpoints: Vec<Endpoint>) -> Batch {
+        let assets = lock(&self.analyzer.assets);
+        endpoints
+            .into_iter()
+            .map(|ep| {
+                let asset = ep
+                    .source
+                    .as_deref()
+                    .and_then(|source| assets.get(source).cloned());
+                (ep, asset)
+            })
+            .collect()
+    }
+
+    /// Add the in-scope assets the plugins' `discover_urls` hooks know of
+    /// for a target to the crawled ones, on a blocking thread
+    async fn discover_assets(&self, target: &str, assets: &mut Vec<String>) -> Result<()> {
+        if self.plugin_manager.is_empty() {
+            return Ok(());
+        }
+        let manager = self.plugin_manager.clone();
+        let owned = target.to_string();
+        let discovered = blocking(move || manager.discover_urls(&owned)).await;
+        self.plugin_manager.check()?;
+        for url in discovered {
+            if !self.scope.allows(&url) {
+                debug!("Skipping out-of-scope discovered asset {}", url);
+            } else if !assets.contains(&url) {
+                debug!("Plugins discovered asset {}", url);
+                assets.push(url);
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse crawled pages for markup and inline scripts on a blocking thread
+    async fn parse_pages(&self, pages: Vec<(String, String)>) -> Vec<Endpoint> {
+        let analyzer = self.analyzer.clone();
+        blocking(move || {
+            pages
+                .iter()
+                .flat_map(|(url, html)| analyzer.parse_main_page(url, html))
+                .collect()
+        })
+        .await
+    }
+
+    /// Parse a fetched asset on a blocking thread, handing back its text as
+    /// well when it's a script that may define HTTP wrappers
+    async fn parse_fetched(
+        &self,
+        asset_url: String,
+        response: FetchResponse,
+    ) -> (Result<Vec<Endpoint>>, Option<String>) {
+        let analyzer = self.analyzer.clone();
+        blocking(move || {
+            let script = match response.spooled {
+                Some(_) => None,
+                None => analyzer.script_text(
+                    &asset_url,
+                    response.content_type.as_deref(),
+                    &response.body,
+                ),
+            };
+            (analyzer.parse_response(&asset_url, &response), script)
+        })
+        .await
+    }
+
+    /// Resolve wrapper calls across scripts on a blocking thread
+    async fn resolve_wrappers(&self, scripts: Vec<(String, String)>) -> Vec<Endpoint> {
+        let analyzer = self.analyzer.clone();
+        blocking(move || analyzer.resolve_wrappers(&scripts)).await
+    }
+
+    /// Run endpoints through the loaded plugins' filters and transforms,
+    /// with the assets they were found in
+    ///
+    /// Fails if a plugin hook, here or while parsing, overran its time limit
+    /// under the abort policy.
+    fn apply_plugins(&self, endpoints: Vec<Endpoint>) -> Result<Vec<Endpoint>> {
+        if self.plugin_manager.is_empty() {
+            return Ok(endpoints);
+        }
+        let processed = self.plugin_manager.process(self.plugin_batch(endpoints));
+        self.plugin_manager.check()?;
+        Ok(processed)
+    }
+
+    /// [`apply_plugins`](Self::apply_plugins) on a blocking thread, so a
+    /// slow plugin doesn't hold up the runtime's workers
+    async fn apply_plugins_async(&self, endpoints: Vec<Endpoint>) -> Result<Vec<Endpoint>> {
+        if self.plugin_manager.is_empty() {
+            return Ok(endpoints);
+        }
+        let batch = self.plugin_batch(endpoints);
+        let manager = self.plugin_manager.clone();
+        let processed = blocking(move || manager.process(batch)).await;
+        self.plugin_manager.check()?;
+        Ok(processed)
+    }
+
+    /// Tag endpoints by the classifier's rules and drop those outside the
+    /// configured severity and tag selection
+    fn classify(&self, endpoints: &mut Vec<Endpoint>) {
+        self.classifier.classify_all(endpoints);
+        if let Some(min) = self.config.min_severity {
+            endpoints.retain(|e| e.severity.is_some_and(|s| s >= min));
+        }
+        if !self.config.only_tags.is_empty() {
+            endpoints.retain(|e| self.config.only_tags.iter().any(|tag| e.has_tag(tag)));
+        }
+    }
+
+    /// Drop endpoints outside the configured scope; relative URLs are
+    /// resolved against `target`, the page they were found for
+    fn retain_in_scope(&self, target: Option<&str>, endpoints: &mut Vec<Endpoint>) {
+        if self.scope.is_empty() {
+            return;
+        }
+        let base = target.and_then(|target| Url::parse(target).ok());
+        endpoints.retain(|e| self.scope.allows_endpoint(&e.url, base.as_ref()));
+    }
+
+    /// Check that a loaded plugin will write `--format plugin` output
+    pub fn check_plugin_output(&self) -> Result<()> {
+        self.plugin_manager
+            .check_output(self.config.output_plugin.as_deref())
+    }
+
+    /// Hand a result to the loaded plugins' `write_output` hooks, for
+    /// `--format plugin`: only the configured output plugin's if one is
+    /// set. Runs on a blocking thread; returns how many plugins wrote it.
+    pub async fn write_plugin_output(&self, result: &ScanResult) -> Result<usize> {
+        let manager = self.plugin_manager.clone();
+        let result = result.clone();
+        let name = self.config.output_plugin.clone();
+        blocking(move || manager.write_output(&result, name.as_deref())).await
+    }
+
+    /// Deduplicate endpoints gathered from several scans or files
+    pub fn deduplicate(&self, endpoints: &mut Vec<Endpoint>) {
+        self.endpoint_filter.deduplicate(endpoints);
+    }
+}
+
+impl Analyzer {
+    /// Parse the target page itself for markup and inline scripts
+    fn parse_main_page(&self, url: &str, html: &str) -> Vec<Endpoint> {
+        if html.is_empty() {
+            return Vec::new();
+        }
+        self.files_processed.fetch_add(1, Ordering::Relaxed);
+        self.record_asset(url, || AssetInfo::from_content(url, html.as_bytes()));
+        let mut endpoints = match self.parser.analyze(html, AssetKind::Html, Some(url)) {
+            Ok(parsed) => self.take_findings(parsed),
+            Err(_) => Vec::new(),
+        };
+        endpoints.extend(self.plugin_generated_endpoints(html.as_bytes(), url));
+        endpoints
+    }
+
+    /// Parse a fetched asset as whatever kind its response says it is
+    fn parse_response(&self, asset_url: &str, response: &FetchResponse) -> Result<Vec<Endpoint>> {
+        let kind = detect_asset_kind(
+            Some(asset_url),
+            response.content_type.as_deref(),
+            &response.body,
+        );
+        debug!("Parsing {} as {}", asset_url, kind.as_str());
+        self.files_processed.fetch_add(1, Ordering::Relaxed);
+        self.record_asset(asset_url, || AssetInfo::from_response(asset_url, response));
+
+        let mut technologies = self
+            .fingerprinter
+            .from_headers(&response.headers, Some(asset_url));
+        technologies.extend(self.fingerprinter.from_url(asset_url));
+        if let Some(file) = &response.spooled {
+            self.record_technologies(technologies);
+            return self.parse_windowed(file, kind, asset_url);
+        }
+        let text = String::from_utf8_lossy(&response.body);
+        technologies.extend(self.fingerprinter.from_content(&text, Some(asset_url)));
+        self.record_technologies(technologies);
+
+        let parsed = self
+            .parser
+            .analyze_bytes(&response.body, kind, Some(asset_url))?;
+        let mut endpoints = self.take_findings(parsed);
+        endpoints.extend(self.plugin_generated_endpoints(&response.body, asset_url));
+        Ok(endpoints)
+    }
+
+    /// Decoded text of an asset that can define or call HTTP wrappers
+    ///
+    /// Only script-bearing assets within `max_parse_bytes` qualify.
+    fn script_text(
+        &self,
+        location: &str,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Option<String> {
+        if body.len() > self.max_parse_bytes {
+            return None;
+        }
+        detect_asset_kind(Some(location), content_type, body)
+            .is_script()
+            .then(|| String::from_utf8_lossy(body).into_owned())
+    }
+
+    /// Collect HTTP wrapper definitions from a batch of scripts
+    fn find_wrappers<S, T>(&self, scripts: &[(S, T)]) -> WrapperSet
+    where
+        S: AsRef<str> + Sync,
+        T: AsRef<str> + Sync,
+    {
+        let defs: Vec<_> = scripts
+            .par_iter()
+            .flat_map_iter(|(source, text)| {
+                self.wrapper_resolver
+                    .find_wrappers(text.as_ref(), Some(source.as_ref()))
+            })
+            .collect();
+        if !defs.is_empty() {
+            debug!("Found {} HTTP wrapper definitions", defs.len());
+        }
+        WrapperSet::new(defs)
+    }
+
+    /// Endpoints from calls to HTTP wrappers any of the scripts define
+    fn resolve_wrappers(&self, scripts: &[(String, String)]) -> Vec<Endpoint> {
+        let wrappers = self.find_wrappers(scripts);
+        if wrappers.is_empty() {
+            return Vec::new();
+        }
+        scripts
+            .par_iter()
+            .flat_map_iter(|(source, text)| wrappers.resolve(text, Some(source)))
+            .collect()
+    }
+
+    /// Parse a file's content according to its type
+    fn parse_content(&self, path: &Path, content: &[u8]) -> Result<Vec<Endpoint>> {
+        let source = path.to_string_lossy().to_string();
+        let kind = detect_asset_kind(Some(&source), None, content);
+        self.files_processed.fetch_add(1, Ordering::Relaxed);
+        self.record_asset(&source, || AssetInfo::from_content(&source, content));
+        self.record_technologies(
+            self.fingerprinter
+                .from_content(&String::from_utf8_lossy(content), Some(&source)),
+        );
+
+        let parsed = self.parser.analyze_bytes(content, kind, Some(&source))?;
+        let mut endpoints = self.take_findings(parsed);
+        endpoints.extend(self.plugin_generated_endpoints(content, &source));
+        Ok(endpoints)
+    }
+
+    /// Parse a file above `max_parse_bytes` in windows without loading it whole
+    fn parse_large_file(&self, path: &Path) -> Result<Vec<Endpoint>> {
+        info!(
+            "Parsing {} in {} byte windows",
+            path.display(),
+            self.parse_window_bytes
+        );
+
         let source = path.to_string_lossy().to_string();
+        let mut file = std::fs::File::open(path)?;
+
+        // Sniff the head of the file, then rewind for the windowed pass
+        let mut head = Vec::new();
+        (&mut file)
+            .take(SNIFF_BYTES as u64)
+            .read_to_end(&mut head)?;
+        file.seek(SeekFrom::Start(0))?;
+        let kind = detect_asset_kind(Some(&source), None, &head);
+        self.files_processed.fetch_add(1, Ordering::Relaxed);
+        let size = file.metadata()?.len();
+        self.record_asset(&source, || AssetInfo {
+            url: source.clone(),
+            size,
+            ..AssetInfo::default()
+        });
+
+        self.parse_windowed(&file, kind, &source)
+    }
 
-        let endpoints = self.parser.parse_js(&content, Some(&source))?;
+    /// Parse a file in windows, then fingerprint each window and hand it to
+    /// the plugins the way a smaller asset is handed over whole
+    fn parse_windowed(
+        &self,
+        mut file: &std::fs::File,
+        kind: AssetKind,
+        source: &str,
+    ) -> Result<Vec<Endpoint>> {
+        file.seek(SeekFrom::Start(0))?;
+        let parsed = self.parser.analyze_reader(
+            BufReader::new(file),
+            kind,
+            Some(source),
+            self.parse_window_bytes,
+        )?;
+        let mut endpoints = self.take_findings(parsed);
 
-        // Apply plugins
-        let mut processed_endpoints = Vec::new();
-        for ep in endpoints {
-            if self.plugin_manager.filter_endpoint(&ep) {
-                let transformed = self.plugin_manager.transform_endpoint(ep);
-                processed_endpoints.push(transformed);
+        file.seek(SeekFrom::Start(0))?;
+        let mut windows = WindowReader::new(BufReader::new(file), self.parse_window_bytes);
+        while let Some(window) = windows.next_window()? {
+            self.record_technologies(self.fingerprinter.from_content(&window.text, Some(source)));
+            for mut endpoint in self.plugin_generated_endpoints(window.text.as_bytes(), source) {
+                if let Some(line) = endpoint.line.as_mut() {
+                    *line += window.line_offset;
+                }
+                endpoints.push(endpoint);
             }
         }
+        Ok(endpoints)
+    }
 
-        Ok(processed_endpoints)
+    /// Record an asset's findings and hand back its endpoints
+    fn take_findings(&self, parsed: ParsedAsset) -> Vec<Endpoint> {
+        self.record_findings(parsed.findings);
+        parsed.endpoints
+    }
+
+    /// Remember findings, one entry per kind and value
+    fn record_findings(&self, found: Vec<Finding>) {
+        let mut findings = lock(&self.findings);
+        for finding in found {
+            if !findings
+                .iter()
+                .any(|f| f.kind == finding.kind && f.value == finding.value)
+            {
+                findings.push(finding);
+            }
+        }
+    }
+
+    /// Fingerprint the target page from its headers and HTML
+    fn fingerprint_page(&self, url: &str, page: &CrawledPage) {
+        let mut technologies = self.fingerprinter.from_headers(&page.headers, Some(url));
+        technologies.extend(self.fingerprinter.from_content(&page.html, Some(url)));
+        self.record_technologies(technologies);
+    }
+
+    /// Remember a crawled page and its headers for the plugins, before
+    /// [`parse_main_page`](Self::parse_main_page) would record it without
+    fn record_page(&self, url: &str, page: &CrawledPage) {
+        self.record_asset(url, || AssetInfo {
+            headers: page.headers.clone(),
+            ..AssetInfo::from_content(url, page.html.as_bytes())
+        });
+    }
+
+    /// Remember an asset for the plugins, unless it's known already or no
+    /// plugin would see it
+    fn record_asset(&self, source: &str, asset: impl FnOnce() -> AssetInfo) {
+        if self.plugin_manager.is_empty() {
+            return;
+        }
+        lock(&self.assets)
+            .entry(source.to_string())
+            .or_insert_with(|| Arc::new(asset()));
+    }
+
+    /// Remember detected technologies, one entry per name
+    fn record_technologies(&self, found: Vec<Technology>) {
+        if found.is_empty() {
+            return;
+        }
+        let mut technologies = lock(&self.technologies);
+        for technology in found {
+            merge_technology(&mut technologies, Some(technology));
+        }
+    }
+
+    /// Technologies detected so far
+    fn technologies(&self) -> Vec<Technology> {
+        lock(&self.technologies).clone()
+    }
+
+    /// Findings recorded so far
+    fn findings(&self) -> Vec<Finding> {
+        lock(&self.findings).clone()
+    }
+
+    /// Endpoints plugins find in an asset, attributed to it unless they say
+    /// otherwise
+    fn plugin_generated_endpoints(&self, content: &[u8], source: &str) -> Vec<Endpoint> {
+        let mut endpoints = self.plugin_manager.generate_endpoints(content, source);
+        for endpoint in &mut endpoints {
+            if endpoint.source.is_none() {
+                endpoint.source = Some(source.to_string());
+            }
+        }
+        endpoints
     }
 }
+
+/// Run parsing or plugin work on a blocking thread, so it doesn't hold up
+/// the runtime's workers; a panic there is resumed here
+async fn blocking<T, F>(f: F) -> T
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(value) => value,
+        Err(e) => std::panic::resume_unwind(e.into_panic()),
+    }
+}
+
+/// Stats derived from the endpoints alone: counts by type, version and severity
+///
+/// File and request counts, duration, and budget are left for the caller.
+pub(crate) fn endpoint_stats(endpoints: &[Endpoint]) -> ScanStats {
+    let mut endpoints_by_type = HashMap::new();
+    let mut endpoints_by_severity = HashMap::new();
+    for endpoint in endpoints {
+        *endpoints_by_type
+            .entry(endpoint.endpoint_type.clone())
+            .or_insert(0) += 1;
+        if let Some(severity) = endpoint.severity {
+            *endpoints_by_severity.entry(severity).or_insert(0) += 1;
+        }
+    }
+    let endpoints_by_version = group_by_version(endpoints)
+        .into_iter()
+        .map(|(version, group)| (version, group.len()))
+        .collect();
+
+    ScanStats {
+        files_processed: 0,
+        requests_made: 0,
+        duration_seconds: 0.0,
+        endpoints_by_type,
+        endpoints_by_version,
+        endpoints_by_severity,
+        budget_exhausted: None,
+        plugin_errors: HashMap::new(),
+    }
+}
+
+/// Absolute URL to probe for an endpoint, if it's on the target's host
+///
+/// Subdomains of the target count as its host.
+fn probe_url(base: &Url, endpoint_url: &str) -> Option<String> {
+    if endpoint_url.contains(['{', '}', '$', '*', '<', '>']) {
+        return None;
+    }
+    let url = base.join(endpoint_url).ok()?;
+    if !matches!(url.scheme(), "http" | "https") || url.path_segments()?.any(|s| s.starts_with(':'))
+    {
+        return None;
+    }
+
+    let host = url.host_str()?;
+    let target = base.host_str()?;
+    let in_scope = host == target
+        || host
+            .strip_suffix(target)
+            .is_some_and(|prefix| prefix.ends_with('.'));
+    in_scope.then(|| url.to_string())
+}
+
+/// Record which target an endpoint was found on
+fn label_target(mut endpoint: Endpoint, target: &str) -> Endpoint {
+    endpoint
+        .metadata
+        .get_or_insert_with(Default::default)
+        .entry("target".to_string())
+        .or_insert_with(|| target.to_string());
+    endpoint
+}
@@ -0,0 +1,180 @@
+//! Which hosts and URLs a scan may fetch and report
+
+use crate::config::ScanConfig;
+use crate::error::{Error, Result};
+use regex::Regex;
+use url::Url;
+
+/// Host and URL rules deciding what is in scope
+///
+/// A host rule such as `example.com` matches that host and its subdomains;
+/// `*.example.com` matches only the subdomains. Regexes are matched against
+/// the whole URL. Exclusions win over inclusions, and with no inclusions of
+/// a kind everything passes that kind.
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+    include_hosts: Vec<String>,
+    exclude_hosts: Vec<String>,
+    include_regex: Vec<Regex>,
+    exclude_regex: Vec<Regex>,
+}
+
+impl Scope {
+    /// Build a scope, failing on an invalid regex
+    pub fn new(
+        include_hosts: &[String],
+        exclude_hosts: &[String],
+        include_regex: &[String],
+        exclude_regex: &[String],
+    ) -> Result<Self> {
+        Ok(Self {
+            include_hosts: normalize_hosts(include_hosts),
+            exclude_hosts: normalize_hosts(exclude_hosts),
+            include_regex: compile(include_regex)?,
+            exclude_regex: compile(exclude_regex)?,
+        })
+    }
+
+    /// The scope a scan configuration describes
+    pub fn from_config(config: &ScanConfig) -> Result<Self> {
+        Self::new(
+            &config.include_hosts,
+            &config.exclude_hosts,
+            &config.include_regex,
+            &config.exclude_regex,
+        )
+    }
+
+    /// Whether there are no rules, so everything is in scope
+    pub fn is_empty(&self) -> bool {
+        self.include_hosts.is_empty()
+            && self.exclude_hosts.is_empty()
+            && self.include_regex.is_empty()
+            && self.exclude_regex.is_empty()
+    }
+
+    /// Whether an absolute URL may be fetched or reported
+    pub fn allows(&self, url: &str) -> bool {
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_ascii_lowercase));
+        self.check(url, host.as_deref())
+    }
+
+    /// Whether an endpoint may be reported
+    ///
+    /// Relative URLs are resolved against `base`, the page they were found
+    /// for. Without a base they are taken to be on the scanned host, so only
+    /// the regexes apply to them.
+    pub fn allows_endpoint(&self, url: &str, base: Option<&Url>) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        match Url::parse(url).or_else(|e| base.ok_or(e).and_then(|base| base.join(url))) {
+            Ok(resolved) => {
+                let host = resolved.host_str().map(str::to_ascii_lowercase);
+                self.check(resolved.as_str(), host.as_deref())
+            }
+            Err(_) => self.check(url, None),
+        }
+    }
+
+    fn check(&self, url: &str, host: Option<&str>) -> bool {
+        if let Some(host) = host {
+            if self
+                .exclude_hosts
+                .iter()
+                .any(|rule| host_matches(rule, host))
+            {
+                return false;
+            }
+            if !self.include_hosts.is_empty()
+                && !self
+                    .include_hosts
+                    .iter()
+                    .any(|rule| host_matches(rule, host))
+            {
+                return false;
+            }
+        }
+        if self.exclude_regex.iter().any(|re| re.is_match(url)) {
+            return false;
+        }
+        self.include_regex.is_empty() || self.include_regex.iter().any(|re| re.is_match(url))
+    }
+}
+
+/// Lowercase host rules, dropping blanks and trailing dots
+fn normalize_hosts(hosts: &[String]) -> Vec<String> {
+    hosts
+        .iter()
+        .map(|host| host.trim().trim_end_matches('.').to_ascii_lowercase())
+        .filter(|host| !host.is_empty())
+        .collect()
+}
+
+fn compile(patterns: &[String]) -> Result<Vec<Regex>> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            Regex::new(pattern).map_err(|e| {
+                Error::ValidationError(format!("invalid scope regex '{}': {}", pattern, e))
+            })
+        })
+        .collect()
+}
+
+/// Whether `host` matches a rule: the domain or a subdomain of it, or only
+/// subdomains for `*.` rules
+pub(crate) fn host_matches(rule: &str, host: &str) -> bool {
+    let is_subdomain = |domain: &str| {
+        host.strip_suffix(domain)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+    };
+    match rule.strip_prefix("*.") {
+        Some(domain) => is_subdomain(domain),
+        None => host == rule || is_subdomain(rule),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_scope_rules() {
+        let scope = Scope::new(
+            &strings(&["Example.com", "*.cdn.net"]),
+            &strings(&["legacy.example.com"]),
+            &[],
+            &strings(&[r"\.map$", "/logout"]),
+        )
+        .unwrap();
+
+        assert!(scope.allows("https://example.com/app.js"));
+        assert!(scope.allows("https://static.example.com/app.js"));
+        assert!(scope.allows("https://a.cdn.net/app.js"));
+        assert!(!scope.allows("https://cdn.net/app.js"));
+        assert!(!scope.allows("https://legacy.example.com/app.js"));
+        assert!(!scope.allows("https://notexample.com/app.js"));
+        assert!(!scope.allows("https://www.google-analytics.com/analytics.js"));
+        assert!(!scope.allows("https://example.com/app.js.map"));
+
+        let base = Url::parse("https://example.com/").unwrap();
+        assert!(scope.allows_endpoint("/api/v1/users", Some(&base)));
+        assert!(!scope.allows_endpoint("/logout", Some(&base)));
+        assert!(!scope.allows_endpoint("https://api.stripe.com/v1/charges", Some(&base)));
+        assert!(scope.allows_endpoint("/api/v1/users", None));
+
+        let regex_only = Scope::new(&[], &[], &strings(&["/api/"]), &[]).unwrap();
+        assert!(regex_only.allows_endpoint("/api/v1/users", None));
+        assert!(!regex_only.allows_endpoint("/static/logo.png", None));
+
+        assert!(Scope::default().is_empty());
+        assert!(Scope::new(&[], &[], &strings(&["("]), &[]).is_err());
+    }
+}
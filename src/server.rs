@@ -0,0 +1,586 @@
+//! REST API for running scans as a service
+//!
+//! `endpointo serve` accepts scans over HTTP and runs them in the
+//! background, at most a configured number at a time:
+//!
+//! - `POST /scans` with `{"url": ..., "profile": ..., "probe": ...}` queues a
+//!   scan and answers `202 Accepted` with its status
+//! - `GET /scans` lists every scan's status
+//! - `GET /scans/{id}` reports one scan's status, with stats once done
+//! - `GET /scans/{id}/endpoints` lists the endpoints found so far
+//! - `GET /scans/{id}/events` streams progress as server-sent events
+//!
+//! Scans live in memory for the server's lifetime. With a token set, every
+//! request needs `Authorization: Bearer <token>`. Plugins whose files
+//! change are reloaded before the next scan starts. A requested profile can
+//! make a scan gentler than the server's settings but never harsher: its
+//! rate limit and concurrency are capped at the server's, and robots.txt
+//! stays respected if the server respects it. The configured headers and
+//! cookies are only sent when the requested target is on an included host.
+
+use crate::config::{ScanConfig, ScanProfile};
+use crate::error::{Error, Result};
+use crate::observer::ScanObserver;
+use crate::plugins::PluginReloader;
+use crate::scanner::Scanner;
+use crate::scope::Scope;
+use crate::types::{Endpoint, ScanStats};
+use crate::util::{lock, secrets_match};
+use bytes::Bytes;
+use chrono::Utc;
+use futures::stream;
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full, Limited, StreamBody};
+use hyper::body::{Frame, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{header, Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, Semaphore};
+use tracing::{debug, info, warn};
+
+/// Scans run at once unless told otherwise
+pub const DEFAULT_MAX_SCANS: usize = 4;
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Progress events buffered per scan for slow event-stream readers
+const EVENT_BUFFER: usize = 1024;
+
+/// Body of a `POST /scans` request
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScanRequest {
+    url: String,
+    #[serde(default)]
+    profile: Option<String>,
+    #[serde(default)]
+    probe: bool,
+    /// Seconds after which the scan stops fetching, within the server's
+    /// own limit
+    #[serde(default)]
+    max_duration: Option<u64>,
+}
+
+/// Where a scan is in its life
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanState {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// A scan as reported by the API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanStatus {
+    pub id: u64,
+    pub target: String,
+    pub state: ScanState,
+    pub created: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished: Option<String>,
+    /// Endpoints found so far
+    pub total_endpoints: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<ScanStats>,
+}
+
+/// A progress event on a scan's event stream
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum ScanEvent {
+    Status(Box<ScanStatus>),
+    AssetFetched { url: String, bytes: usize },
+    EndpointFound(Box<Endpoint>),
+    Error { url: String, error: String },
+    Completed { total_endpoints: usize },
+    Failed { error: String },
+}
+
+impl ScanEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            ScanEvent::Status(_) => "status",
+            ScanEvent::AssetFetched { .. } => "asset_fetched",
+            ScanEvent::EndpointFound(_) => "endpoint_found",
+            ScanEvent::Error { .. } => "error",
+            ScanEvent::Completed { .. } => "completed",
+            ScanEvent::Failed { .. } => "failed",
+        }
+    }
+
+    /// Whether nothing follows this event
+    fn is_final(&self) -> bool {
+        matches!(self, ScanEvent::Completed { .. } | ScanEvent::Failed { .. })
+    }
+
+    /// The event in server-sent events framing
+    fn to_sse(&self) -> Bytes {
+        let data = serde_json::to_string(self).unwrap_or_default();
+        Bytes::from(format!("event: {}\ndata: {}\n\n", self.name(), data))
+    }
+}
+
+/// A scan accepted by the server
+///
+/// When both are held, `status` is locked before `endpoints`.
+struct ScanJob {
+    status: Mutex<ScanStatus>,
+    endpoints: Mutex<Vec<Endpoint>>,
+    events: broadcast::Sender<ScanEvent>,
+}
+
+impl ScanJob {
+    fn status(&self) -> ScanStatus {
+        lock(&self.status).clone()
+    }
+
+    fn emit(&self, event: ScanEvent) {
+        // No receivers just means nobody is watching
+        let _ = self.events.send(event);
+    }
+}
+
+/// Feeds a scan's progress into its job
+struct JobObserver(Arc<ScanJob>);
+
+impl ScanObserver for JobObserver {
+    fn on_asset_fetched(&self, url: &str, bytes: usize) {
+        self.0.emit(ScanEvent::AssetFetched {
+            url: url.to_string(),
+            bytes,
+        });
+    }
+
+    fn on_endpoint_found(&self, endpoint: &Endpoint) {
+        let total = {
+            let mut endpoints = lock(&self.0.endpoints);
+            endpoints.push(endpoint.clone());
+            endpoints.len()
+        };
+        lock(&self.0.status).total_endpoints = total;
+        self.0
+            .emit(ScanEvent::EndpointFound(Box::new(endpoint.clone())));
+    }
+
+    fn on_error(&self, url: &str, error: &Error) {
+        self.0.emit(ScanEvent::Error {
+            url: url.to_string(),
+            error: error.to_string(),
+        });
+    }
+}
+
+type Body = BoxBody<Bytes, Infallible>;
+
+/// Serves the scan API
+pub struct ApiServer {
+    config: ScanConfig,
+    plugins: Arc<Mutex<PluginReloader>>,
+    token: Option<String>,
+    scans: Mutex<BTreeMap<u64, Arc<ScanJob>>>,
+    next_id: AtomicU64,
+    running: Arc<Semaphore>,
+}
+
+impl ApiServer {
+    /// Serve scans using `config` as every scan's starting settings
+    pub fn new(config: ScanConfig) -> Self {
+        Self {
+            plugins: Arc::new(Mutex::new(PluginReloader::new(&config))),
+            config,
+            token: None,
+            scans: Mutex::new(BTreeMap::new()),
+            next_id: AtomicU64::new(1),
+            running: Arc::new(Semaphore::new(DEFAULT_MAX_SCANS)),
+        }
+    }
+
+    /// Require this bearer token on every request
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token.filter(|t| !t.is_empty());
+        self
+    }
+
+    /// Run at most this many scans at once; later ones wait queued
+    pub fn with_max_scans(mut self, max: Option<usize>) -> Self {
+        if let Some(max) = max {
+            self.running = Arc::new(Semaphore::new(max.max(1)));
+        }
+        self
+    }
+
+    /// Accept connections on `listener` until the task is dropped
+    pub async fn serve(self, listener: TcpListener) -> Result<()> {
+        let server = Arc::new(self);
+        info!("API listening on {}", listener.local_addr()?);
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            let server = server.clone();
+            tokio::spawn(async move {
+                let service = service_fn(move |request| {
+                    let server = server.clone();
+                    async move { Ok::<_, Infallible>(server.handle(request).await) }
+                });
+                if let Err(e) = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    debug!("Connection from {} failed: {}", peer, e);
+                }
+            });
+        }
+    }
+
+    async fn handle(self: &Arc<Self>, request: Request<Incoming>) -> Response<Body> {
+        if !self.authorized(&request) {
+            return error(StatusCode::UNAUTHORIZED, "missing or invalid bearer token");
+        }
+        let path: Vec<String> = request
+            .uri()
+            .path()
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+        let path: Vec<&str> = path.iter().map(String::as_str).collect();
+        match (request.method(), path.as_slice()) {
+            (&Method::POST, ["scans"]) => self.create_scan(request).await,
+            (&Method::GET, ["scans"]) => {
+                let statuses: Vec<ScanStatus> =
+                    lock(&self.scans).values().map(|job| job.status()).collect();
+                json(StatusCode::OK, &statuses)
+            }
+            (&Method::GET, ["scans", id, rest @ ..]) => {
+                let Some(job) = id
+                    .parse()
+                    .ok()
+                    .and_then(|id: u64| lock(&self.scans).get(&id).cloned())
+                else {
+                    return error(StatusCode::NOT_FOUND, "no such scan");
+                };
+                match rest {
+                    [] => json(StatusCode::OK, &job.status()),
+                    ["endpoints"] => json(StatusCode::OK, &*lock(&job.endpoints)),
+                    ["events"] => events(&job),
+                    _ => error(StatusCode::NOT_FOUND, "not found"),
+                }
+            }
+            (_, ["scans", ..]) => error(StatusCode::METHOD_NOT_ALLOWED, "method not allowed"),
+            _ => error(StatusCode::NOT_FOUND, "not found"),
+        }
+    }
+
+    fn authorized(&self, request: &Request<Incoming>) -> bool {
+        let Some(token) = &self.token else {
+            return true;
+        };
+        let given = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .unwrap_or_default();
+        secrets_match(given, token)
+    }
+
+    async fn create_scan(self: &Arc<Self>, request: Request<Incoming>) -> Response<Body> {
+        let body = match Limited::new(request.into_body(), MAX_BODY_BYTES)
+            .collect()
+            .await
+        {
+            Ok(body) => body.to_bytes(),
+            Err(e) => return error(StatusCode::BAD_REQUEST, &e.to_string()),
+        };
+        let scan: ScanRequest = match serde_json::from_slice(&body) {
+            Ok(scan) => scan,
+            Err(e) => return error(StatusCode::BAD_REQUEST, &e.to_string()),
+        };
+        let config = match self.scan_config(&scan) {
+            Ok(config) => config,
+            Err(e) => return error(StatusCode::BAD_REQUEST, &e.to_string()),
+        };
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let status = ScanStatus {
+            id,
+            target: scan.url,
+            state: ScanState::Queued,
+            created: Utc::now().to_rfc3339(),
+            finished: None,
+            total_endpoints: 0,
+            error: None,
+            stats: None,
+        };
+        let job = Arc::new(ScanJob {
+            status: Mutex::new(status.clone()),
+            endpoints: Mutex::new(Vec::new()),
+            events: broadcast::channel(EVENT_BUFFER).0,
+        });
+        lock(&self.scans).insert(id, job.clone());
+        info!("Queued scan {} of {}", id, status.target);
+
+        let running = self.running.clone();
+        let plugins = self.plugins.clone();
+        tokio::spawn(async move {
+            let _permit = running.acquire_owned().await;
+            run_scan(job, config, &plugins).await;
+        });
+        json(StatusCode::ACCEPTED, &status)
+    }
+
+    /// Settings for a requested scan
+    fn scan_config(&self, scan: &ScanRequest) -> Result<ScanConfig> {
+        let url = url::Url::parse(&scan.url)?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(Error::ValidationError(format!(
+                "scan URL must be http(s): {}",
+                scan.url
+            )));
+        }
+        let mut config = self.config.clone();
+        config.target_url = scan.url.clone();
+        // Callers pick the target, so the operator's headers and cookies
+        // only go along to hosts the operator included
+        let included = !self.config.include_hosts.is_empty()
+            && Scope::new(&self.config.include_hosts, &[], &[], &[])?.allows(&scan.url);
+        if !included {
+            config.headers.clear();
+            config.cookies = None;
+        }
+        if let Some(profile) = &scan.profile {
+            let profile: ScanProfile = profile.parse().map_err(Error::ValidationError)?;
+            config = config.with_profile(profile);
+            // The operator's limits still bound what the profile asks for
+            config.rate_limit = config.rate_limit.min(self.config.rate_limit);
+            config.max_concurrent = config.max_concurrent.min(self.config.max_concurrent);
+            config.respect_robots_txt |= self.config.respect_robots_txt;
+        }
+        if scan.probe {
+            config = config.with_probe(true);
+        }
+        // A caller may shorten the operator's time limit, not lift it
+        let requested = scan.max_duration.map(Duration::from_secs);
+        config.max_duration = match (requested, self.config.max_duration) {
+            (Some(requested), Some(limit)) => Some(requested.min(limit)),
+            (requested, limit) => requested.or(limit),
+        };
+        Ok(config)
+    }
+}
+
+/// A scanner for a scan, with the server's plugins reloaded if their files
+/// changed
+///
+/// Until the plugins first load, failing to load them fails the scan;
+/// after that, the scan runs with the plugins loaded before.
+fn scanner(config: ScanConfig, plugins: &Mutex<PluginReloader>) -> Result<Scanner> {
+    let mut plugins = lock(plugins);
+    let loaded = plugins.is_loaded();
+    match plugins.reload() {
+        Ok(changed) if loaded && !changed.is_empty() => {
+            info!("Reloaded plugins: {:?}", changed);
+        }
+        Ok(_) => {}
+        Err(e) if loaded => warn!("Plugin reload failed, keeping the previous plugins: {}", e),
+        Err(e) => return Err(e),
+    }
+    Scanner::builder(config)
+        .with_plugin_reloader(&plugins)
+        .build()
+}
+
+/// Run a queued scan to the end, recording the outcome on its job
+async fn run_scan(job: Arc<ScanJob>, config: ScanConfig, plugins: &Mutex<PluginReloader>) {
+    {
+        let mut status = lock(&job.status);
+        status.state = ScanState::Running;
+        job.emit(ScanEvent::Status(Box::new(status.clone())));
+    }
+    let outcome = match scanner(config, plugins) {
+        Ok(scanner) => scanner.with_observer(JobObserver(job.clone())).scan().await,
+        Err(e) => Err(e),
+    };
+
+    let mut status = lock(&job.status);
+    status.finished = Some(Utc::now().to_rfc3339());
+    match outcome {
+        Ok(result) => {
+            info!(
+                "Scan {} finished with {} endpoints",
+                status.id, result.total_endpoints
+            );
+            status.state = ScanState::Completed;
+            status.total_endpoints = result.total_endpoints;
+            status.stats = result.stats;
+            *lock(&job.endpoints) = result.endpoints;
+            job.emit(ScanEvent::Completed {
+                total_endpoints: status.total_endpoints,
+            });
+        }
+        Err(e) => {
+            info!("Scan {} failed: {}", status.id, e);
+            status.state = ScanState::Failed;
+            status.error = Some(e.to_string());
+            job.emit(ScanEvent::Failed {
+                error: e.to_string(),
+            });
+        }
+    }
+}
+
+/// A scan's progress as a server-sent events stream
+///
+/// Opens with the scan's current status and ends after it completes or
+/// fails.
+fn events(job: &ScanJob) -> Response<Body> {
+    // Subscribe before reading the status, so no event falls in between
+    let receiver = job.events.subscribe();
+    let status = job.status();
+    let finished = matches!(status.state, ScanState::Completed | ScanState::Failed);
+    let first = ScanEvent::Status(Box::new(status));
+
+    let rest = stream::unfold(
+        (receiver, finished),
+        |(mut receiver, finished)| async move {
+            if finished {
+                return None;
+            }
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        let done = event.is_final();
+                        return Some((event.to_sse(), (receiver, done)));
+                    }
+                    // A slow reader missed some events; carry on from here
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        },
+    );
+    let frames = futures::StreamExt::map(
+        futures::StreamExt::chain(stream::iter([first.to_sse()]), rest),
+        |bytes| Ok::<_, Infallible>(Frame::data(bytes)),
+    );
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/event-stream")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .body(StreamBody::new(frames).boxed())
+        .unwrap_or_else(|_| Response::new(empty()))
+}
+
+fn json<T: Serialize + ?Sized>(status: StatusCode, value: &T) -> Response<Body> {
+    let body = serde_json::to_vec(value).unwrap_or_default();
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from(body)).boxed())
+        .unwrap_or_else(|_| Response::new(empty()))
+}
+
+fn error(status: StatusCode, message: &str) -> Response<Body> {
+    json(status, &serde_json::json!({ "error": message }))
+}
+
+fn empty() -> Body {
+    Full::new(Bytes::new()).boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requested_profile_stays_within_server_limits() {
+        let server = ApiServer::new(
+            ScanConfig::default()
+                .with_profile(ScanProfile::Passive)
+                .with_rate_limit(Some(5)),
+        );
+        let request = |profile: &str| ScanRequest {
+            url: "https://example.com".to_string(),
+            profile: Some(profile.to_string()),
+            probe: false,
+            max_duration: None,
+        };
+
+        let aggressive = server.scan_config(&request("aggressive")).unwrap();
+        assert_eq!(aggressive.rate_limit, 5);
+        assert_eq!(aggressive.max_concurrent, 2);
+        assert!(aggressive.respect_robots_txt);
+
+        let passive = ApiServer::new(ScanConfig::default())
+            .scan_config(&request("passive"))
+            .unwrap();
+        assert_eq!(passive.rate_limit, 2);
+        assert!(server.scan_config(&request("reckless")).is_err());
+    }
+
+    #[test]
+    fn test_credentials_only_go_to_included_hosts() {
+        let request = |url: &str| ScanRequest {
+            url: url.to_string(),
+            profile: None,
+            probe: false,
+            max_duration: None,
+        };
+        let credentials = ScanConfig::default()
+            .with_headers(vec!["Authorization: Bearer s3cret".to_string()])
+            .with_cookies(Some("session=abc".to_string()));
+
+        let open = ApiServer::new(credentials.clone());
+        let config = open.scan_config(&request("https://evil.test")).unwrap();
+        assert!(config.headers.is_empty());
+        assert!(config.cookies.is_none());
+
+        let scoped =
+            ApiServer::new(credentials.with_include_hosts(vec!["*.example.com".to_string()]));
+        let config = scoped
+            .scan_config(&request("https://api.example.com/app"))
+            .unwrap();
+        assert_eq!(config.headers, ["Authorization: Bearer s3cret"]);
+        assert_eq!(config.cookies.as_deref(), Some("session=abc"));
+        let config = scoped.scan_config(&request("https://evil.test")).unwrap();
+        assert!(config.headers.is_empty());
+        assert!(config.cookies.is_none());
+    }
+
+    #[test]
+    fn test_requested_duration_stays_within_server_limit() {
+        let request = |max_duration: Option<u64>| ScanRequest {
+            url: "https://example.com".to_string(),
+            profile: None,
+            probe: false,
+            max_duration,
+        };
+        let server =
+            ApiServer::new(ScanConfig::default().with_max_duration(Some(Duration::from_secs(60))));
+        let limit = |max_duration| {
+            server
+                .scan_config(&request(max_duration))
+                .unwrap()
+                .max_duration
+        };
+        assert_eq!(limit(Some(3600)), Some(Duration::from_secs(60)));
+        assert_eq!(limit(Some(10)), Some(Duration::from_secs(10)));
+        assert_eq!(limit(None), Some(Duration::from_secs(60)));
+
+        let open = ApiServer::new(ScanConfig::default());
+        let config = open.scan_config(&request(Some(3600))).unwrap();
+        assert_eq!(config.max_duration, Some(Duration::from_secs(3600)));
+    }
+}
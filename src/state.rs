@@ -0,0 +1,147 @@
+//! Checkpoints of scans in progress, so an interrupted scan can resume
+//!
+//! A state file records each finished target with its endpoints, plus the
+//! findings and technologies seen so far. Resuming skips the finished
+//! targets and keeps their results. Targets that were still running when
+//! the scan stopped start over.
+
+use crate::error::{Error, Result};
+use crate::types::{Endpoint, Finding, Technology, SCHEMA_VERSION, TOOL_VERSION};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A target the scan finished, with its endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetState {
+    pub target: String,
+    pub endpoints: Vec<Endpoint>,
+}
+
+/// Progress of a scan, written to a state file as it goes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanState {
+    pub schema_version: u32,
+    pub tool_version: String,
+
+    /// When the state was last saved
+    pub saved_at: String,
+
+    /// Finished targets, in the order they finished
+    pub completed: Vec<TargetState>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub findings: Vec<Finding>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub technologies: Vec<Technology>,
+}
+
+impl Default for ScanState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScanState {
+    /// An empty state for a fresh scan
+    pub fn new() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            tool_version: TOOL_VERSION.to_string(),
+            saved_at: String::new(),
+            completed: Vec::new(),
+            findings: Vec::new(),
+            technologies: Vec::new(),
+        }
+    }
+
+    /// Read a state file written by [`ScanState::save`]
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| Error::ValidationError(format!("{}: {}", path.display(), e)))?;
+        let state: ScanState = serde_json::from_str(&content)
+            .map_err(|e| Error::ValidationError(format!("{}: {}", path.display(), e)))?;
+        if state.schema_version > SCHEMA_VERSION {
+            return Err(Error::ValidationError(format!(
+                "{}: state version {} is newer than this endpointo reads",
+                path.display(),
+                state.schema_version
+            )));
+        }
+        Ok(state)
+    }
+
+    /// Write the state, replacing the file only once it's fully written
+    pub fn save(&mut self, path: &Path) -> Result<()> {
+        self.saved_at = chrono::Utc::now().to_rfc3339();
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, serde_json::to_vec(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Whether a target already finished
+    pub fn is_done(&self, target: &str) -> bool {
+        self.completed.iter().any(|t| t.target == target)
+    }
+
+    /// Record a finished target along with the scan's findings and
+    /// technologies so far
+    pub fn record(
+        &mut self,
+        target: &str,
+        endpoints: &[Endpoint],
+        findings: Vec<Finding>,
+        technologies: Vec<Technology>,
+    ) {
+        self.completed.retain(|t| t.target != target);
+        self.completed.push(TargetState {
+            target: target.to_string(),
+            endpoints: endpoints.to_vec(),
+        });
+        self.findings = findings;
+        self.technologies = technologies;
+    }
+
+    /// Endpoints of a finished target
+    pub fn endpoints(&self, target: &str) -> Option<&[Endpoint]> {
+        self.completed
+            .iter()
+            .find(|t| t.target == target)
+            .map(|t| t.endpoints.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EndpointType;
+
+    #[test]
+    fn test_state_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scan.state");
+
+        let mut endpoint = Endpoint::new("/api/users".to_string(), EndpointType::Rest);
+        endpoint.source = Some("https://a.example/app.js".to_string());
+        let mut state = ScanState::new();
+        state.record("https://a.example/", &[endpoint], Vec::new(), Vec::new());
+        state.save(&path).unwrap();
+
+        let loaded = ScanState::load(&path).unwrap();
+        assert!(loaded.is_done("https://a.example/"));
+        assert!(!loaded.is_done("https://b.example/"));
+        assert_eq!(loaded.endpoints("https://a.example/").unwrap().len(), 1);
+        assert_eq!(
+            loaded.endpoints("https://a.example/").unwrap()[0]
+                .source
+                .as_deref(),
+            Some("https://a.example/app.js")
+        );
+        assert!(!loaded.saved_at.is_empty());
+
+        std::fs::write(&path, "{").unwrap();
+        assert!(ScanState::load(&path).is_err());
+    }
+}
@@ -0,0 +1,366 @@
+//! Persistent project store of endpoints across scans
+//!
+//! A SQLite database keyed by target. Every recorded scan adds its
+//! endpoints, matched to earlier sightings as in [`diff_endpoints`], so each
+//! endpoint is stored once per target with the first and last time a scan
+//! found it.
+//!
+//! [`diff_endpoints`]: crate::diff::diff_endpoints
+
+use crate::diff::endpoint_key;
+use crate::error::{Error, Result};
+use crate::parser::filters::EndpointFilter;
+use crate::types::{Endpoint, ScanResult};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS scans (
+    id INTEGER PRIMARY KEY,
+    target TEXT NOT NULL,
+    timestamp TEXT NOT NULL,
+    total_endpoints INTEGER NOT NULL,
+    new_endpoints INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS endpoints (
+    id INTEGER PRIMARY KEY,
+    target TEXT NOT NULL,
+    key TEXT NOT NULL,
+    data TEXT NOT NULL,
+    first_seen TEXT NOT NULL,
+    last_seen TEXT NOT NULL,
+    UNIQUE (target, key)
+);
+CREATE TABLE IF NOT EXISTS sightings (
+    scan_id INTEGER NOT NULL REFERENCES scans (id),
+    endpoint_id INTEGER NOT NULL REFERENCES endpoints (id),
+    data TEXT NOT NULL,
+    PRIMARY KEY (scan_id, endpoint_id)
+);
+";
+
+/// One recorded scan of a target
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScanRecord {
+    /// Row ID, increasing with every recorded scan
+    pub id: i64,
+
+    /// Target that was scanned
+    pub target: String,
+
+    /// When the scan started
+    pub timestamp: String,
+
+    /// Endpoints the scan found
+    pub total_endpoints: usize,
+
+    /// Endpoints no earlier scan of the target had found
+    pub new_endpoints: usize,
+}
+
+/// An endpoint with its sighting history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredEndpoint {
+    /// Target the endpoint was found on
+    pub target: String,
+
+    /// Start of the first scan that found it
+    pub first_seen: String,
+
+    /// Start of the latest scan that found it
+    pub last_seen: String,
+
+    /// Number of scans that found it
+    pub times_seen: usize,
+
+    /// The endpoint as the latest scan saw it
+    pub endpoint: Endpoint,
+}
+
+/// Scans and endpoints stored for a project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectHistory {
+    pub scans: Vec<ScanRecord>,
+    pub endpoints: Vec<StoredEndpoint>,
+}
+
+/// SQLite-backed store of scans and the endpoints they found
+pub struct ProjectStore {
+    conn: Connection,
+    filter: EndpointFilter,
+}
+
+impl ProjectStore {
+    /// Open a project database, creating it if needed
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Open a throwaway in-memory store
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn,
+            filter: EndpointFilter::new(),
+        })
+    }
+
+    /// Record a scan result and return the endpoints new to their target
+    ///
+    /// Endpoints are filed under their `target` metadata, falling back to
+    /// the result's target, so a multi-target result is recorded as one
+    /// scan per target. Known endpoints are replaced by the latest sighting
+    /// and have `last_seen` moved to the scan's timestamp.
+    pub fn record(&mut self, result: &ScanResult) -> Result<Vec<Endpoint>> {
+        let mut by_target: BTreeMap<&str, Vec<&Endpoint>> = BTreeMap::new();
+        for endpoint in &result.endpoints {
+            let target = endpoint
+                .metadata
+                .as_ref()
+                .and_then(|m| m.get("target"))
+                .map_or(result.target.as_str(), String::as_str);
+            by_target.entry(target).or_default().push(endpoint);
+        }
+        if by_target.is_empty() {
+            by_target.insert(&result.target, Vec::new());
+        }
+
+        let tx = self.conn.transaction()?;
+        let mut added = Vec::new();
+        for (target, endpoints) in by_target {
+            tx.execute(
+                "INSERT INTO scans (target, timestamp, total_endpoints, new_endpoints)
+                 VALUES (?1, ?2, ?3, 0)",
+                params![target, result.timestamp, endpoints.len()],
+            )?;
+            let scan_id = tx.last_insert_rowid();
+
+            let mut new_endpoints = 0;
+            for endpoint in endpoints {
+                let key = endpoint_key(&self.filter, endpoint);
+                let data = serde_json::to_string(endpoint)?;
+                let known: Option<i64> = tx
+                    .query_row(
+                        "SELECT id FROM endpoints WHERE target = ?1 AND key = ?2",
+                        params![target, key],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                let endpoint_id = match known {
+                    Some(id) => {
+                        tx.execute(
+                            "UPDATE endpoints SET data = ?1, last_seen = MAX(last_seen, ?2)
+                             WHERE id = ?3",
+                            params![data, result.timestamp, id],
+                        )?;
+                        id
+                    }
+                    None => {
+                        tx.execute(
+                            "INSERT INTO endpoints (target, key, data, first_seen, last_seen)
+                             VALUES (?1, ?2, ?3, ?4, ?4)",
+                            params![target, key, data, result.timestamp],
+                        )?;
+                        new_endpoints += 1;
+                        added.push(endpoint.clone());
+                        tx.last_insert_rowid()
+                    }
+                };
+                tx.execute(
+                    "INSERT OR IGNORE INTO sightings (scan_id, endpoint_id, data)
+                     VALUES (?1, ?2, ?3)",
+                    params![scan_id, endpoint_id, data],
+                )?;
+            }
+            tx.execute(
+                "UPDATE scans SET new_endpoints = ?1 WHERE id = ?2",
+                params![new_endpoints, scan_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(added)
+    }
+
+    /// Targets with recorded scans, sorted
+    pub fn targets(&self) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT target FROM scans ORDER BY target")?;
+        let targets = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(targets)
+    }
+
+    /// Recorded scans, oldest first, of one target or all of them
+    pub fn scans(&self, target: Option<&str>) -> Result<Vec<ScanRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, target, timestamp, total_endpoints, new_endpoints FROM scans
+             WHERE ?1 IS NULL OR target = ?1 ORDER BY id",
+        )?;
+        let scans = stmt
+            .query_map(params![target], |row| {
+                Ok(ScanRecord {
+                    id: row.get(0)?,
+                    target: row.get(1)?,
+                    timestamp: row.get(2)?,
+                    total_endpoints: row.get(3)?,
+                    new_endpoints: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(scans)
+    }
+
+    /// Stored endpoints of one target or all of them, in first-seen order
+    pub fn endpoints(&self, target: Option<&str>) -> Result<Vec<StoredEndpoint>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT e.target, e.first_seen, e.last_seen, e.data,
+                    (SELECT COUNT(*) FROM sightings s WHERE s.endpoint_id = e.id)
+             FROM endpoints e WHERE ?1 IS NULL OR e.target = ?1 ORDER BY e.id",
+        )?;
+        let rows: Vec<(String, String, String, String, usize)> = stmt
+            .query_map(params![target], |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        rows.into_iter()
+            .map(|(target, first_seen, last_seen, data, times_seen)| {
+                Ok(StoredEndpoint {
+                    target,
+                    first_seen,
+                    last_seen,
+                    times_seen,
+                    endpoint: serde_json::from_str(&data)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Endpoints found by one recorded scan, as that scan saw them
+    pub fn scan_endpoints(&self, scan_id: i64) -> Result<Vec<Endpoint>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT data FROM sightings WHERE scan_id = ?1 ORDER BY endpoint_id")?;
+        let rows: Vec<String> = stmt
+            .query_map(params![scan_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        rows.iter()
+            .map(|data| serde_json::from_str(data).map_err(Error::from))
+            .collect()
+    }
+
+    /// Scans and endpoints of one target or all of them
+    pub fn history(&self, target: Option<&str>) -> Result<ProjectHistory> {
+        Ok(ProjectHistory {
+            scans: self.scans(target)?,
+            endpoints: self.endpoints(target)?,
+        })
+    }
+
+    /// The two latest scans of a target, oldest first
+    ///
+    /// Without a target the store must hold exactly one.
+    pub fn latest_scans(&self, target: Option<&str>) -> Result<(ScanRecord, ScanRecord)> {
+        let target = match target {
+            Some(target) => target.to_string(),
+            None => match self.targets()?.as_slice() {
+                [target] => target.clone(),
+                [] => return Err(Error::ValidationError("no scans recorded".to_string())),
+                targets => {
+                    return Err(Error::ValidationError(format!(
+                        "{} targets recorded, choose one of: {}",
+                        targets.len(),
+                        targets.join(", ")
+                    )))
+                }
+            },
+        };
+        let mut scans = self.scans(Some(&target))?;
+        if scans.len() < 2 {
+            return Err(Error::ValidationError(format!(
+                "{}: at least two recorded scans are needed",
+                target
+            )));
+        }
+        let latest = scans.pop().unwrap();
+        let previous = scans.pop().unwrap();
+        Ok((previous, latest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EndpointType, SCHEMA_VERSION, TOOL_VERSION};
+
+    fn result(timestamp: &str, urls: &[&str]) -> ScanResult {
+        let endpoints: Vec<Endpoint> = urls
+            .iter()
+            .map(|url| Endpoint::new(url.to_string(), EndpointType::Rest))
+            .collect();
+        ScanResult {
+            schema_version: SCHEMA_VERSION,
+            tool_version: TOOL_VERSION.to_string(),
+            target: "https://example.com".to_string(),
+            timestamp: timestamp.to_string(),
+            total_endpoints: endpoints.len(),
+            endpoints,
+            findings: Vec::new(),
+            wordlist: Vec::new(),
+            technologies: Vec::new(),
+            stats: None,
+        }
+    }
+
+    #[test]
+    fn test_store_tracks_sightings() {
+        let mut store = ProjectStore::open_in_memory().unwrap();
+
+        let added = store
+            .record(&result(
+                "2026-01-01T00:00:00+00:00",
+                &["/api/users/1", "/api/a"],
+            ))
+            .unwrap();
+        assert_eq!(added.len(), 2);
+        // A different ID is the same endpoint
+        let added = store
+            .record(&result(
+                "2026-01-02T00:00:00+00:00",
+                &["/api/users/2", "/api/b"],
+            ))
+            .unwrap();
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].url, "/api/b");
+
+        let endpoints = store.endpoints(None).unwrap();
+        assert_eq!(endpoints.len(), 3);
+        assert_eq!(endpoints[0].endpoint.url, "/api/users/2");
+        assert_eq!(endpoints[0].first_seen, "2026-01-01T00:00:00+00:00");
+        assert_eq!(endpoints[0].last_seen, "2026-01-02T00:00:00+00:00");
+        assert_eq!(endpoints[0].times_seen, 2);
+
+        let (previous, latest) = store.latest_scans(None).unwrap();
+        assert_eq!(latest.new_endpoints, 1);
+        let urls: Vec<_> = store
+            .scan_endpoints(previous.id)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.url)
+            .collect();
+        assert_eq!(urls, vec!["/api/users/1", "/api/a"]);
+    }
+}
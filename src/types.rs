@@ -8,9 +8,91 @@ pub enum EndpointType {
     Rest,
     GraphQL,
     WebSocket,
+    /// Server-Sent Events stream
+    Sse,
     Unknown,
 }
 
+impl EndpointType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EndpointType::Rest => "rest",
+            EndpointType::GraphQL => "graphql",
+            EndpointType::WebSocket => "websocket",
+            EndpointType::Sse => "sse",
+            EndpointType::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::str::FromStr for EndpointType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "rest" => Ok(EndpointType::Rest),
+            "graphql" => Ok(EndpointType::GraphQL),
+            "websocket" => Ok(EndpointType::WebSocket),
+            "sse" => Ok(EndpointType::Sse),
+            "unknown" => Ok(EndpointType::Unknown),
+            _ => Err(format!(
+                "unknown endpoint type '{}' (expected rest, graphql, websocket, sse or unknown)",
+                s
+            )),
+        }
+    }
+}
+
+/// How much trust to place in an extracted endpoint
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+/// How urgently an endpoint deserves a closer look
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+impl std::str::FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "info" => Ok(Severity::Info),
+            "low" => Ok(Severity::Low),
+            "medium" => Ok(Severity::Medium),
+            "high" => Ok(Severity::High),
+            "critical" => Ok(Severity::Critical),
+            _ => Err(format!(
+                "unknown severity '{}' (expected info, low, medium, high or critical)",
+                s
+            )),
+        }
+    }
+}
+
 /// Represents a discovered endpoint
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Endpoint {
@@ -39,6 +121,70 @@ pub struct Endpoint {
     /// Additional metadata
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, String>>,
+
+    /// Confidence that this is a real endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<Confidence>,
+
+    /// Free-form tags (e.g. `comment`, `admin`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+
+    /// Highest severity of the tag rules the endpoint matched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+
+    /// Response to an active probe, when probing is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub probe: Option<ProbeResult>,
+}
+
+/// How a discovered endpoint responded when probed
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProbeResult {
+    /// HTTP status code
+    pub status: u16,
+
+    /// `Content-Length` header, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_length: Option<u64>,
+
+    /// `Content-Type` header, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+
+    /// Absolute `Location` of a redirect response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect: Option<String>,
+
+    /// Methods advertised by `Allow` or `Access-Control-Allow-Methods` in
+    /// response to `OPTIONS`, when that enrichment is enabled
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_methods: Vec<String>,
+
+    /// Responses after `redirect`, when redirects were followed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redirect_chain: Vec<RedirectHop>,
+
+    /// Whether the response looked like the host's answer for a path that
+    /// doesn't exist, despite its status
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub soft_404: bool,
+}
+
+impl ProbeResult {
+    /// Whether the endpoint seems to exist: it answered with something
+    /// other than 404, 410 or a soft 404
+    pub fn is_live(&self) -> bool {
+        !self.soft_404 && !matches!(self.status, 404 | 410)
+    }
+}
+
+/// One response in a followed redirect chain
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RedirectHop {
+    pub url: String,
+    pub status: u16,
 }
 
 impl Endpoint {
@@ -52,6 +198,10 @@ impl Endpoint {
             line: None,
             params: None,
             metadata: None,
+            confidence: None,
+            tags: None,
+            severity: None,
+            probe: None,
         }
     }
 
@@ -84,11 +234,211 @@ impl Endpoint {
         self.metadata = Some(metadata);
         self
     }
+
+    /// Set the severity
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = Some(severity);
+        self
+    }
+
+    /// Set the confidence level
+    pub fn with_confidence(mut self, confidence: Confidence) -> Self {
+        self.confidence = Some(confidence);
+        self
+    }
+
+    /// Add a tag, ignoring duplicates
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        let tags = self.tags.get_or_insert_with(Vec::new);
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+        self
+    }
+
+    /// Fold a duplicate sighting of this endpoint into it
+    ///
+    /// Keeps this endpoint's fields, takes the higher confidence and
+    /// severity, a more specific type than REST or unknown, and adds tags,
+    /// params, metadata keys and a probe result it doesn't already have.
+    pub fn merge(&mut self, other: Endpoint) {
+        let generic = |t: &EndpointType| matches!(t, EndpointType::Rest | EndpointType::Unknown);
+        if generic(&self.endpoint_type) && !generic(&other.endpoint_type) {
+            self.endpoint_type = other.endpoint_type;
+        }
+        self.confidence = self.confidence.max(other.confidence);
+        self.severity = self.severity.max(other.severity);
+        if self.probe.is_none() {
+            self.probe = other.probe;
+        }
+
+        for tag in other.tags.into_iter().flatten() {
+            let tags = self.tags.get_or_insert_with(Vec::new);
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        for param in other.params.into_iter().flatten() {
+            let params = self.params.get_or_insert_with(Vec::new);
+            if !params.contains(&param) {
+                params.push(param);
+            }
+        }
+
+        for (key, value) in other.metadata.into_iter().flatten() {
+            self.metadata
+                .get_or_insert_with(HashMap::new)
+                .entry(key)
+                .or_insert(value);
+        }
+    }
+
+    /// Check whether the endpoint carries a tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags
+            .as_ref()
+            .is_some_and(|tags| tags.iter().any(|t| t == tag))
+    }
+}
+
+/// Category of a non-endpoint finding
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    /// `process.env.X` / `import.meta.env.X` reference
+    EnvVar,
+    /// Hostname on an internal or non-public domain
+    InternalHost,
+    /// Private, loopback or link-local IP address
+    PrivateIp,
+    /// Credential in a well-known token format, such as a cloud access key
+    Secret,
+}
+
+impl FindingKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FindingKind::EnvVar => "env_var",
+            FindingKind::InternalHost => "internal_host",
+            FindingKind::PrivateIp => "private_ip",
+            FindingKind::Secret => "secret",
+        }
+    }
+}
+
+/// Leaked internal infrastructure found alongside endpoints
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Finding {
+    /// What was found
+    pub kind: FindingKind,
+
+    /// The variable name, hostname or address
+    pub value: String,
+
+    /// Source file where the finding was made
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+
+    /// Line number in source file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+
+    /// Free-form labels describing the finding
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl Finding {
+    /// Create a new finding
+    pub fn new(kind: FindingKind, value: impl Into<String>) -> Self {
+        Self {
+            kind,
+            value: value.into(),
+            source: None,
+            line: None,
+            tags: Vec::new(),
+        }
+    }
+
+    /// Set the source file
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Set the line number
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    /// Add a tag, ignoring duplicates
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+        self
+    }
+}
+
+/// What part of the stack a detected technology belongs to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TechCategory {
+    /// Front-end framework or library (React, Angular, Next.js, ...)
+    Framework,
+    /// Web server or application server (nginx, Express, ...)
+    Server,
+    /// CDN or edge platform (Cloudflare, CloudFront, Vercel, ...)
+    Cdn,
+    /// GraphQL server or client
+    GraphQL,
+    /// Content management system
+    Cms,
 }
 
+/// A framework or piece of infrastructure the target appears to use
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Technology {
+    /// Product name, e.g. `Next.js`
+    pub name: String,
+
+    /// Part of the stack it belongs to
+    pub category: TechCategory,
+
+    /// Version, when a signature includes one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// What gave it away, e.g. `server header` or `__NEXT_DATA__`
+    pub evidence: String,
+
+    /// URL or file the evidence was found in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// Version of the results file format, bumped on incompatible changes
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Version of endpointo writing results
+pub const TOOL_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// Scan result containing all discovered endpoints
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanResult {
+    /// Version of the results format (0 for files written before it was
+    /// recorded)
+    #[serde(default)]
+    pub schema_version: u32,
+
+    /// Version of endpointo that produced the result
+    #[serde(default)]
+    pub tool_version: String,
+
     /// Target URL that was scanned
     pub target: String,
 
@@ -101,6 +451,18 @@ pub struct ScanResult {
     /// List of discovered endpoints
     pub endpoints: Vec<Endpoint>,
 
+    /// Internal infrastructure findings
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub findings: Vec<Finding>,
+
+    /// Parameter names and path segments for fuzzing, sorted and deduplicated
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wordlist: Vec<String>,
+
+    /// Frameworks and infrastructure detected on the target
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub technologies: Vec<Technology>,
+
     /// Statistics
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stats: Option<ScanStats>,
@@ -120,4 +482,21 @@ pub struct ScanStats {
 
     /// Endpoints by type
     pub endpoints_by_type: HashMap<EndpointType, usize>,
+
+    /// Endpoints by detected API version (`unversioned` for the rest)
+    #[serde(default)]
+    pub endpoints_by_version: HashMap<String, usize>,
+
+    /// Endpoints by severity, for endpoints matched by a tag rule
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub endpoints_by_severity: HashMap<Severity, usize>,
+
+    /// Budget that cut the scan short (`max_duration`, `max_endpoints` or
+    /// `max_assets`), if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_exhausted: Option<String>,
+
+    /// Failed plugin hook calls by plugin, for plugins with any
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub plugin_errors: HashMap<String, usize>,
 }
@@ -0,0 +1,18 @@
+//! Small helpers shared across modules
+
+use std::sync::{Mutex, MutexGuard};
+
+/// Lock a mutex, carrying on with the data if a holder panicked
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Compare a secret without short-circuiting, so timing doesn't leak it
+pub(crate) fn secrets_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
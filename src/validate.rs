@@ -0,0 +1,308 @@
+//! Checks of results files before they're imported into other systems
+//!
+//! `endpointo validate` reads a results file entry by entry rather than
+//! all at once, so every malformed endpoint is reported with where it is
+//! instead of the first error stopping the load. A sample of endpoints can
+//! then be probed to find dead URLs.
+
+use crate::error::Result;
+use crate::output::file::{is_manifest, read_to_string, Manifest};
+use crate::probe::{probe_target, Prober};
+use crate::types::{Endpoint, ScanResult, SCHEMA_VERSION};
+use serde_json::Value;
+use std::path::Path;
+use url::Url;
+
+/// Something wrong with a results file, and where
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    /// Where in the file, e.g. `endpoints[3]` or `line 12`
+    pub location: String,
+    pub message: String,
+}
+
+/// What validating a results file found
+#[derive(Debug, Clone, Default)]
+pub struct Validation {
+    /// Endpoints that passed
+    pub endpoints: Vec<Endpoint>,
+
+    /// Malformed entries and envelope problems
+    pub problems: Vec<Problem>,
+
+    /// The results' target, empty if the file doesn't name one
+    pub target: String,
+}
+
+impl Validation {
+    fn problem(&mut self, location: impl Into<String>, message: impl Into<String>) {
+        self.problems.push(Problem {
+            location: location.into(),
+            message: message.into(),
+        });
+    }
+
+    /// Check one endpoint entry, keeping it if it's well formed
+    fn check_endpoint(&mut self, location: String, value: Value) {
+        let endpoint: Endpoint = match serde_json::from_value(value) {
+            Ok(endpoint) => endpoint,
+            Err(e) => return self.problem(location, e.to_string()),
+        };
+        if let Some(message) = endpoint_problem(&endpoint) {
+            return self.problem(location, message);
+        }
+        self.endpoints.push(endpoint);
+    }
+}
+
+/// Validate a results file written by `scan` or `parse`
+///
+/// Takes the same files as [`crate::diff::load_result`]. Only an
+/// unreadable file, or one that isn't JSON, JSON Lines or YAML at all, is
+/// an error; everything else is reported as a problem.
+pub fn validate_file(path: &Path) -> Result<Validation> {
+    let mut validation = Validation::default();
+    validate_into(path, "", &mut validation)?;
+    Ok(validation)
+}
+
+fn validate_into(path: &Path, prefix: &str, validation: &mut Validation) -> Result<()> {
+    let content = read_to_string(path)?;
+    if is_manifest(path) {
+        let manifest: Manifest = serde_json::from_str(&content)?;
+        check_schema_version(manifest.schema_version, validation);
+        validation.target = manifest.target.clone();
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let before = validation.endpoints.len() + validation.problems.len();
+        for shard in &manifest.shards {
+            let shard_path = dir.join(&shard.file);
+            if !shard_path.is_file() {
+                validation.problem(&shard.file, "shard file is missing");
+                continue;
+            }
+            validate_into(&shard_path, &format!("{}: ", shard.file), validation)?;
+        }
+        let loaded = validation.endpoints.len() + validation.problems.len() - before;
+        if loaded != manifest.total_endpoints {
+            validation.problem(
+                "total_endpoints",
+                format!(
+                    "manifest lists {} endpoints but its shards hold {}",
+                    manifest.total_endpoints, loaded
+                ),
+            );
+        }
+        return Ok(());
+    }
+
+    let name = path.to_string_lossy().to_lowercase();
+    let name = name.strip_suffix(".gz").unwrap_or(&name);
+    if name.ends_with(".jsonl") {
+        for (i, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let location = format!("{}line {}", prefix, i + 1);
+            match serde_json::from_str(line) {
+                Ok(value) => validation.check_endpoint(location, value),
+                Err(e) => validation.problem(location, format!("invalid JSON: {}", e)),
+            }
+        }
+        return Ok(());
+    }
+
+    let value: Value = if name.ends_with(".yaml") || name.ends_with(".yml") {
+        serde_yaml::from_str(&content)?
+    } else {
+        serde_json::from_str(&content)?
+    };
+    match value {
+        Value::Array(entries) => {
+            for (i, entry) in entries.into_iter().enumerate() {
+                validation.check_endpoint(format!("{}[{}]", prefix, i), entry);
+            }
+        }
+        Value::Object(mut envelope) => {
+            let entries = match envelope.insert("endpoints".to_string(), Value::Array(Vec::new())) {
+                Some(Value::Array(entries)) => entries,
+                _ => {
+                    validation.problem("endpoints", "missing endpoint list");
+                    Vec::new()
+                }
+            };
+            match serde_json::from_value::<ScanResult>(Value::Object(envelope)) {
+                Ok(result) => {
+                    check_schema_version(result.schema_version, validation);
+                    validation.target = result.target;
+                    if result.total_endpoints != entries.len() {
+                        validation.problem(
+                            "total_endpoints",
+                            format!(
+                                "says {} but the file holds {}",
+                                result.total_endpoints,
+                                entries.len()
+                            ),
+                        );
+                    }
+                    if !result.timestamp.is_empty()
+                        && chrono::DateTime::parse_from_rfc3339(&result.timestamp).is_err()
+                    {
+                        validation.problem(
+                            "timestamp",
+                            format!("'{}' is not an RFC 3339 time", result.timestamp),
+                        );
+                    }
+                }
+                Err(e) => validation.problem("result", e.to_string()),
+            }
+            for (i, entry) in entries.into_iter().enumerate() {
+                validation.check_endpoint(format!("{}endpoints[{}]", prefix, i), entry);
+            }
+        }
+        _ => validation.problem("file", "expected a scan result or an endpoint list"),
+    }
+    Ok(())
+}
+
+fn check_schema_version(version: u32, validation: &mut Validation) {
+    if version > SCHEMA_VERSION {
+        validation.problem(
+            "schema_version",
+            format!(
+                "version {} is newer than the {} this endpointo reads",
+                version, SCHEMA_VERSION
+            ),
+        );
+    }
+}
+
+/// What's wrong with an endpoint that deserialized, if anything
+fn endpoint_problem(endpoint: &Endpoint) -> Option<String> {
+    if endpoint.url.trim().is_empty() {
+        return Some("empty url".to_string());
+    }
+    if endpoint.url.contains("://") {
+        if let Err(e) = Url::parse(&endpoint.url) {
+            return Some(format!("invalid url '{}': {}", endpoint.url, e));
+        }
+    }
+    if endpoint.line == Some(0) {
+        return Some("line numbers start at 1".to_string());
+    }
+    if let Some(method) = &endpoint.method {
+        if method.is_empty() || !method.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Some(format!("invalid method '{}'", method));
+        }
+    }
+    if let Some(probe) = &endpoint.probe {
+        if !(100..600).contains(&probe.status) {
+            return Some(format!("invalid probe status {}", probe.status));
+        }
+    }
+    None
+}
+
+/// An endpoint that didn't answer, or answered that it doesn't exist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadEndpoint {
+    pub url: String,
+    pub reason: String,
+}
+
+/// Probe up to `sample` endpoints, spread evenly through the list
+///
+/// Only endpoints that can be probed are sampled; see [`Prober::probe_all`]
+/// for how relative URLs are resolved. Returns how many were checked and
+/// the ones that are dead.
+pub async fn check_live(
+    prober: &Prober,
+    endpoints: &[Endpoint],
+    sample: usize,
+    base: Option<&str>,
+) -> (usize, Vec<DeadEndpoint>) {
+    let candidates: Vec<&Endpoint> = endpoints
+        .iter()
+        .filter(|e| probe_target(e, base).is_some())
+        .collect();
+    if sample == 0 || candidates.is_empty() {
+        return (0, Vec::new());
+    }
+    let step = (candidates.len() / sample).max(1);
+    let mut sampled: Vec<Endpoint> = candidates
+        .into_iter()
+        .step_by(step)
+        .take(sample)
+        .map(|e| Endpoint {
+            probe: None,
+            ..e.clone()
+        })
+        .collect();
+
+    prober.probe_all(&mut sampled, base).await;
+    let dead = sampled
+        .iter()
+        .filter_map(|e| {
+            let reason = match &e.probe {
+                None => "no response".to_string(),
+                Some(probe) if probe.soft_404 => format!("soft 404 (status {})", probe.status),
+                Some(probe) if !probe.is_live() => format!("status {}", probe.status),
+                Some(_) => return None,
+            };
+            Some(DeadEndpoint {
+                url: e.url.clone(),
+                reason,
+            })
+        })
+        .collect();
+    (sampled.len(), dead)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_reports_each_bad_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "schema_version": 1,
+                "tool_version": "0.1.0",
+                "target": "https://example.com",
+                "timestamp": "yesterday",
+                "total_endpoints": 4,
+                "endpoints": [
+                    {"url": "/api/users", "endpoint_type": "rest"},
+                    {"url": "/api/orders", "endpoint_type": "soap"},
+                    {"url": "https://exa mple.com/x", "endpoint_type": "rest"},
+                    {"url": "/api/items", "endpoint_type": "rest", "line": 0}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let validation = validate_file(&path).unwrap();
+        assert_eq!(validation.endpoints.len(), 1);
+        let locations: Vec<&str> = validation
+            .problems
+            .iter()
+            .map(|p| p.location.as_str())
+            .collect();
+        assert_eq!(
+            locations,
+            vec!["timestamp", "endpoints[1]", "endpoints[2]", "endpoints[3]"]
+        );
+
+        let lines = dir.path().join("results.jsonl");
+        std::fs::write(
+            &lines,
+            "{\"url\": \"/a\", \"endpoint_type\": \"rest\"}\n{not json\n",
+        )
+        .unwrap();
+        let validation = validate_file(&lines).unwrap();
+        assert_eq!(validation.endpoints.len(), 1);
+        assert_eq!(validation.problems[0].location, "line 2");
+    }
+}
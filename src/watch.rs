@@ -0,0 +1,120 @@
+//! Repeated scans that report only endpoints not seen before
+
+use crate::diff::diff_endpoints;
+use crate::error::{Error, Result};
+use crate::types::Endpoint;
+use chrono::Utc;
+use std::time::Duration;
+
+/// Parse a watch interval such as `90s`, `15m`, `6h`, `1d`, or plain seconds
+pub fn parse_interval(interval: &str) -> Result<Duration> {
+    let interval = interval.trim();
+    let split = interval
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(interval.len());
+    let (number, unit) = interval.split_at(split);
+
+    let invalid = || {
+        Error::ValidationError(format!(
+            "invalid interval '{}', expected e.g. 90s, 15m, 6h or 1d",
+            interval
+        ))
+    };
+    let value: u64 = number.parse().map_err(|_| invalid())?;
+    let scale = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+    let seconds = value.checked_mul(scale).ok_or_else(invalid)?;
+    if seconds == 0 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(seconds))
+}
+
+/// Remembers every endpoint seen across runs of a watched scan
+///
+/// The first run is the baseline. After that, each run reports the
+/// endpoints that no earlier run found, matched as in
+/// [`diff_endpoints`], so an endpoint missing from one run and back in
+/// the next isn't reported twice.
+#[derive(Debug, Default)]
+pub struct Watcher {
+    known: Option<Vec<Endpoint>>,
+}
+
+impl Watcher {
+    /// Create a watcher with no baseline yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a run's endpoints and return the ones not seen before
+    ///
+    /// Returns `None` for the baseline run. New endpoints get a
+    /// `first_seen` timestamp in their metadata.
+    pub fn update(&mut self, endpoints: Vec<Endpoint>) -> Option<Vec<Endpoint>> {
+        let Some(known) = &mut self.known else {
+            self.known = Some(endpoints);
+            return None;
+        };
+
+        let first_seen = Utc::now().to_rfc3339();
+        let mut added = diff_endpoints(known, &endpoints).added;
+        for endpoint in &mut added {
+            endpoint
+                .metadata
+                .get_or_insert_with(Default::default)
+                .insert("first_seen".to_string(), first_seen.clone());
+        }
+        known.extend(added.iter().cloned());
+        Some(added)
+    }
+
+    /// Number of distinct endpoints seen so far
+    pub fn known(&self) -> usize {
+        self.known.as_ref().map_or(0, Vec::len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EndpointType;
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_interval("15m").unwrap(), Duration::from_secs(900));
+        assert_eq!(parse_interval("1d").unwrap(), Duration::from_secs(86400));
+        assert!(parse_interval("0s").is_err());
+        assert!(parse_interval("5 minutes").is_err());
+        assert!(parse_interval("999999999999999999d").is_err());
+        assert!(parse_interval("99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn test_watcher_reports_new_endpoints_once() {
+        let endpoint = |url: &str| Endpoint::new(url.to_string(), EndpointType::Rest);
+        let mut watcher = Watcher::new();
+
+        assert!(watcher.update(vec![endpoint("/api/a")]).is_none());
+        let new = watcher
+            .update(vec![endpoint("/api/a"), endpoint("/api/b")])
+            .unwrap();
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].url, "/api/b");
+        assert!(new[0].metadata.as_ref().unwrap().contains_key("first_seen"));
+
+        // Dropping out of a run and coming back isn't new
+        assert!(watcher.update(vec![endpoint("/api/b")]).unwrap().is_empty());
+        assert!(watcher
+            .update(vec![endpoint("/api/a"), endpoint("/api/b")])
+            .unwrap()
+            .is_empty());
+        assert_eq!(watcher.known(), 2);
+    }
+}